@@ -0,0 +1,21 @@
+#import bevy_pbr::forward_io::VertexOutput
+
+struct GridMaterial {
+    color: vec4<f32>,
+    spacing: f32,
+    line_width: f32,
+}
+
+@group(2) @binding(0) var<uniform> material: GridMaterial;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let coord = in.world_position.xz / material.spacing;
+
+    // Divide by screen-space derivatives to keep lines the same width at any distance.
+    let distance = abs(fract(coord - 0.5) - 0.5) / fwidth(coord);
+    let line = min(distance.x, distance.y);
+    let alpha = 1.0 - min(line / material.line_width, 1.0);
+
+    return vec4(material.color.rgb, material.color.a * alpha);
+}
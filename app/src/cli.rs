@@ -1,4 +1,8 @@
-use std::net::{IpAddr, Ipv4Addr};
+use std::{
+    env,
+    net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
+};
 
 use anyhow::{Context, Result};
 use bevy::prelude::*;
@@ -10,14 +14,30 @@ use bevy_replicon_renet::{
 use clap::{Args, Parser, Subcommand};
 
 use project_harmonia_base::{
+    core::{
+        transition::{Transition, TransitionRequest},
+        GameState,
+    },
     game_world::{
         actor::SelectedActor,
-        city::{ActiveCity, City},
+        city::{
+            generation::{CityGenerate, GenerationParams},
+            ActiveCity, City, CityBundle,
+        },
         family::FamilyMembers,
+        rng::WorldSeed,
         GameLoad, WorldName, WorldState,
     },
     message::error_message,
-    network::{self, DEFAULT_PORT},
+    mod_package,
+    network::{
+        self,
+        moderation::PlayerIdentity,
+        server_console::{Rcon, StdinConsole},
+        ServerInfo, DEFAULT_MAX_CLIENTS, DEFAULT_PORT,
+    },
+    save_encryption::SavePassphrase,
+    settings::Settings,
 };
 
 /// Logic for command line interface.
@@ -39,6 +59,22 @@ impl Plugin for CliPlugin {
                         .and_then(any_with_component::<FamilyMembers>)
                         .and_then(run_once()),
                 ),
+            )
+            .add_systems(
+                Update,
+                Self::generate_city.run_if(
+                    in_state(WorldState::World)
+                        .and_then(any_with_component::<City>)
+                        .and_then(run_once()),
+                ),
+            )
+            .add_systems(
+                Update,
+                Self::override_seed.run_if(
+                    in_state(WorldState::World)
+                        .and_then(any_with_component::<WorldSeed>)
+                        .and_then(run_once()),
+                ),
             );
     }
 }
@@ -47,42 +83,78 @@ impl CliPlugin {
     fn apply_subcommand(
         mut commands: Commands,
         mut load_events: EventWriter<GameLoad>,
+        mut transition_events: EventWriter<TransitionRequest>,
         cli: Res<Cli>,
         network_channels: Res<RepliconChannels>,
+        settings: Res<Settings>,
+        identity: Res<PlayerIdentity>,
     ) -> Result<()> {
         if let Some(subcommand) = &cli.subcommand {
             match subcommand {
                 GameCommand::Play(world_load) => {
                     load_events.send_default();
                     commands.insert_resource(WorldName(world_load.world_name.clone()));
+                    insert_passphrase(&mut commands);
                 }
-                GameCommand::Host { world_load, port } => {
-                    let server = RenetServer::new(ConnectionConfig {
-                        server_channels_config: network_channels.get_server_configs(),
-                        client_channels_config: network_channels.get_client_configs(),
-                        ..Default::default()
-                    });
-                    let transport =
-                        network::create_server(*port).context("unable to create server")?;
+                GameCommand::Host {
+                    world_load,
+                    port,
+                    password,
+                    max_players,
+                    rcon_ip,
+                    rcon_port,
+                    rcon_password,
+                } => {
+                    let server = RenetServer::new(network::server_connection_config(
+                        &network_channels,
+                        &settings.server,
+                    )?);
+                    let transport = network::create_server(*port, *max_players, password)
+                        .context("unable to create server")?;
 
                     commands.insert_resource(server);
                     commands.insert_resource(transport);
+                    commands.insert_resource(ServerInfo {
+                        name: world_load.world_name.clone(),
+                    });
                     commands.insert_resource(WorldName(world_load.world_name.clone()));
+                    insert_passphrase(&mut commands);
+                    commands.insert_resource(StdinConsole::spawn());
+                    if let Some(rcon_port) = *rcon_port {
+                        let rcon = Rcon::bind(*rcon_ip, rcon_port, rcon_password.clone())
+                            .context("unable to start remote console")?;
+                        commands.insert_resource(rcon);
+                    }
 
                     load_events.send_default();
                 }
-                GameCommand::Join { ip, port } => {
+                GameCommand::Join { ip, port, password } => {
                     let client = RenetClient::new(ConnectionConfig {
                         server_channels_config: network_channels.get_server_configs(),
                         client_channels_config: network_channels.get_client_configs(),
                         ..Default::default()
                     });
-                    let transport =
-                        network::create_client(*ip, *port).context("unable to create client")?;
+                    let transport = network::create_client(*ip, *port, password, *identity)
+                        .context("unable to create client")?;
 
                     commands.insert_resource(client);
                     commands.insert_resource(transport);
                 }
+                GameCommand::Generate(generate) => {
+                    commands.insert_resource(WorldName(generate.world_name.clone()));
+                    commands.spawn(CityBundle::new(generate.city_name.clone()));
+                    transition_events
+                        .send(TransitionRequest::new(Transition::Game(GameState::InGame)));
+                }
+                GameCommand::ValidateAssets => {
+                    // Handled by `InfoValidationPlugin` added on startup.
+                }
+                GameCommand::PackageMod { .. } => {
+                    // Previews are rendered by `PreviewExportPlugin` and packaging happens after exit.
+                }
+                GameCommand::InstallMod { .. } => {
+                    // Handled before the app creation.
+                }
             }
         }
 
@@ -91,7 +163,7 @@ impl CliPlugin {
 
     fn quick_load(
         mut commands: Commands,
-        mut world_state: ResMut<NextState<WorldState>>,
+        mut transition_events: EventWriter<TransitionRequest>,
         cli: Res<Cli>,
         cities: Query<(Entity, &Name), With<City>>,
         families: Query<(&Name, &FamilyMembers)>,
@@ -105,7 +177,8 @@ impl CliPlugin {
                         .with_context(|| format!("unable to find city named {name}"))?;
 
                     commands.entity(entity).insert(ActiveCity);
-                    world_state.set(WorldState::City);
+                    transition_events
+                        .send(TransitionRequest::new(Transition::World(WorldState::City)));
                 }
                 QuickLoad::Family { name } => {
                     let (_, members) = families
@@ -117,32 +190,107 @@ impl CliPlugin {
                         .first()
                         .expect("family should contain at least one actor");
                     commands.entity(entity).insert(SelectedActor);
-                    world_state.set(WorldState::Family);
+                    transition_events.send(TransitionRequest::new(Transition::World(
+                        WorldState::Family,
+                    )));
                 }
             }
         }
 
         Ok(())
     }
+
+    fn generate_city(
+        mut commands: Commands,
+        mut generate_events: EventWriter<CityGenerate>,
+        mut transition_events: EventWriter<TransitionRequest>,
+        cli: Res<Cli>,
+        cities: Query<(Entity, &Name), With<City>>,
+    ) {
+        if let Some(GameCommand::Generate(generate)) = &cli.subcommand {
+            let (entity, _) = cities
+                .iter()
+                .find(|(_, name)| name.as_str() == generate.city_name)
+                .expect("city should be spawned from the subcommand");
+
+            let params = generate.params();
+            info!("generating city with seed {}", params.seed);
+            generate_events.send(CityGenerate {
+                city_entity: entity,
+                params,
+            });
+            commands.entity(entity).insert(ActiveCity);
+            transition_events.send(TransitionRequest::new(Transition::World(WorldState::City)));
+        }
+    }
+
+    fn override_seed(cli: Res<Cli>, mut seeds: Query<&mut WorldSeed>) {
+        if let Some(seed) = cli.world_load().and_then(|world_load| world_load.seed) {
+            info!("replacing world seed with {seed}");
+            seeds.single_mut().0 = seed;
+        }
+    }
 }
 
 #[derive(Parser, Clone, Resource)]
 #[command(author, version, about)]
 pub(crate) struct Cli {
+    /// Export a diagnostic bundle for bug reports and exit.
+    #[arg(long)]
+    pub(crate) export_diagnostics: bool,
+
     /// Game command to run.
     #[command(subcommand)]
     subcommand: Option<GameCommand>,
 }
 
 impl Cli {
+    /// Returns arguments for world loading if was specified from any subcommand.
+    fn world_load(&self) -> Option<&WorldLoad> {
+        match &self.subcommand {
+            Some(GameCommand::Play(world_load)) => Some(world_load),
+            Some(GameCommand::Host { world_load, .. }) => Some(world_load),
+            _ => None,
+        }
+    }
+
     /// Returns arguments for quick load if was specified from any subcommand.
     fn quick_load(&self) -> Option<&QuickLoad> {
+        self.world_load()
+            .and_then(|world_load| world_load.quick_load.as_ref())
+    }
+
+    /// Runs mod commands that don't need the app.
+    ///
+    /// Returns [`None`] if no such command was requested.
+    pub(crate) fn run_mod_command(&self) -> Option<Result<()>> {
         match &self.subcommand {
-            Some(GameCommand::Play(world_load)) => world_load.quick_load.as_ref(),
-            Some(GameCommand::Host { world_load, .. }) => world_load.quick_load.as_ref(),
+            Some(GameCommand::InstallMod { archive }) => {
+                Some(mod_package::install(archive).map(|name| println!("mod '{name}' installed")))
+            }
             _ => None,
         }
     }
+
+    /// Returns mod directory and output directory if mod packaging was requested.
+    ///
+    /// Packaging needs the app to render object previews first.
+    pub(crate) fn package_mod(&self) -> Option<(PathBuf, PathBuf)> {
+        match &self.subcommand {
+            Some(GameCommand::PackageMod { dir, output }) => Some((dir.clone(), output.clone())),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if metadata validation was requested.
+    pub(crate) fn validate_assets(&self) -> bool {
+        matches!(self.subcommand, Some(GameCommand::ValidateAssets))
+    }
+
+    /// Returns `true` if the game was started as a server.
+    pub(crate) fn host(&self) -> bool {
+        matches!(self.subcommand, Some(GameCommand::Host { .. }))
+    }
 }
 
 impl Default for Cli {
@@ -161,6 +309,28 @@ enum GameCommand {
         /// Port to use.
         #[clap(short, long, default_value_t = DEFAULT_PORT)]
         port: u16,
+
+        /// Password required to join, empty for no password.
+        #[clap(long, default_value_t)]
+        password: String,
+
+        /// Maximum number of connected players.
+        #[clap(long, default_value_t = DEFAULT_MAX_CLIENTS)]
+        max_players: usize,
+
+        /// Address on which the remote console listens.
+        #[clap(long, default_value_t = Ipv4Addr::LOCALHOST.into())]
+        rcon_ip: IpAddr,
+
+        /// TCP port for the remote console, disabled if not specified.
+        ///
+        /// Commands are also accepted from the standard input.
+        #[clap(long, requires = "rcon_password")]
+        rcon_port: Option<u16>,
+
+        /// Password required to use the remote console.
+        #[clap(long, default_value_t)]
+        rcon_password: String,
     },
     Join {
         /// Server IP address.
@@ -170,21 +340,121 @@ enum GameCommand {
         /// Server port.
         #[clap(short, long, default_value_t = DEFAULT_PORT)]
         port: u16,
+
+        /// Server password.
+        #[clap(long, default_value_t)]
+        password: String,
+    },
+    /// Create a new world with a procedurally generated city.
+    Generate(GenerateArgs),
+    /// Check all metadata files and their references, print a report and exit.
+    ///
+    /// Useful for mod authors. Exits with an error code if any file is invalid.
+    ValidateAssets,
+    /// Validate a mod directory and pack it into an archive for distribution.
+    ///
+    /// Object previews are rendered into the mod directory and included into the archive.
+    PackageMod {
+        /// Mod directory inside the game assets, its name is used as the mod name.
+        dir: PathBuf,
+
+        /// Directory for the created archive.
+        #[arg(short, long, default_value = ".")]
+        output: PathBuf,
+    },
+    /// Verify a packaged mod and install it into the game assets.
+    InstallMod {
+        /// Path to the mod archive.
+        archive: PathBuf,
     },
 }
 
 /// Arguments for quick load.
+///
+/// Passphrase for encrypted worlds is read from [`SAVE_PASSPHRASE_VAR`].
 #[derive(Args, Clone)]
 struct WorldLoad {
     /// World name to load.
     #[arg(short, long)]
     world_name: String,
 
+    /// Replace the world seed to reproduce simulation.
+    ///
+    /// The current seed is printed to the log on load.
+    #[arg(long)]
+    seed: Option<u64>,
+
     /// City name to load.
     #[command(subcommand)]
     quick_load: Option<QuickLoad>,
 }
 
+/// Environment variable with a passphrase to decrypt the loaded world and encrypt it on save.
+///
+/// Encrypts an unencrypted world on the next save.
+/// Not accepted as an argument to keep it out of the process list.
+const SAVE_PASSPHRASE_VAR: &str = "HARMONIA_SAVE_PASSPHRASE";
+
+fn insert_passphrase(commands: &mut Commands) {
+    if let Ok(passphrase) = env::var(SAVE_PASSPHRASE_VAR) {
+        commands.insert_resource(SavePassphrase(passphrase));
+    }
+}
+
+/// Arguments for city generation.
+#[derive(Args, Clone)]
+struct GenerateArgs {
+    /// World name to create.
+    #[arg(short, long)]
+    world_name: String,
+
+    /// Name of the generated city.
+    #[arg(short, long, default_value = "Generated city")]
+    city_name: String,
+
+    /// Generation seed, random if not specified.
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Number of blocks along each axis.
+    #[arg(long, default_value_t = GenerationParams::default().blocks)]
+    blocks: u32,
+
+    /// Distance between parallel roads.
+    #[arg(long, default_value_t = GenerationParams::default().block_size)]
+    block_size: f32,
+
+    /// Chance for each lot place to contain a lot.
+    #[arg(long, default_value_t = GenerationParams::default().lot_density)]
+    lot_density: f32,
+
+    /// Chance for each road segment to have a street object.
+    #[arg(long, default_value_t = GenerationParams::default().street_density)]
+    street_density: f32,
+
+    /// Average number of foliage objects per block.
+    #[arg(long, default_value_t = GenerationParams::default().foliage_density)]
+    foliage_density: f32,
+
+    /// Number of blocks turned into parks with a pond.
+    #[arg(long, default_value_t = GenerationParams::default().parks)]
+    parks: u32,
+}
+
+impl GenerateArgs {
+    fn params(&self) -> GenerationParams {
+        GenerationParams {
+            seed: self.seed.unwrap_or_else(rand::random),
+            blocks: self.blocks,
+            block_size: self.block_size,
+            lot_density: self.lot_density,
+            street_density: self.street_density,
+            foliage_density: self.foliage_density,
+            parks: self.parks,
+        }
+    }
+}
+
 #[derive(Subcommand, Clone)]
 enum QuickLoad {
     City { name: String },
@@ -0,0 +1,92 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use bevy::{prelude::*, window::WindowCloseRequested};
+use bevy_replicon::prelude::*;
+
+use project_harmonia_base::{
+    core::GameState,
+    game_world::{ExitRequest, UnsavedChanges},
+    network::shutdown::{ServerShutdown, ShuttingDown},
+};
+
+/// Intercepts window closing to ask about saving.
+///
+/// Also handles termination signals (SIGINT and SIGTERM) to shut down the hosted server gracefully
+/// if [`TerminationSignal`] is inserted.
+///
+/// Expects [`WindowPlugin::close_when_requested`] to be disabled.
+pub(super) struct ExitPlugin;
+
+impl Plugin for ExitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                Self::handle_close_requests.run_if(on_event::<WindowCloseRequested>()),
+                Self::handle_signal.run_if(resource_exists::<TerminationSignal>),
+            ),
+        );
+    }
+}
+
+impl ExitPlugin {
+    fn handle_close_requests(
+        mut close_events: EventReader<WindowCloseRequested>,
+        mut exit_events: EventWriter<AppExit>,
+        mut request_events: EventWriter<ExitRequest>,
+        game_state: Res<State<GameState>>,
+        unsaved: Res<UnsavedChanges>,
+    ) {
+        close_events.clear();
+        if *game_state.get() == GameState::InGame && unsaved.0 {
+            info!("requesting exit confirmation");
+            request_events.send_default();
+        } else {
+            info!("exiting game");
+            exit_events.send_default();
+        }
+    }
+
+    /// Shuts down the server on the first signal and exits immediately on the next one.
+    fn handle_signal(
+        mut exit_events: EventWriter<AppExit>,
+        mut shutdown_events: EventWriter<ServerShutdown>,
+        signal: Res<TerminationSignal>,
+        server: Res<RepliconServer>,
+        shutting_down: Option<Res<ShuttingDown>>,
+    ) {
+        if !signal.0.swap(false, Ordering::Relaxed) {
+            return;
+        }
+
+        if server.is_running() && shutting_down.is_none() {
+            info!("received termination signal, shutting down server");
+            shutdown_events.send(ServerShutdown { exit: true });
+        } else {
+            info!("received termination signal, exiting game");
+            exit_events.send_default();
+        }
+    }
+}
+
+/// Set from the signal handler thread.
+#[derive(Resource)]
+pub(super) struct TerminationSignal(Arc<AtomicBool>);
+
+impl TerminationSignal {
+    /// Installs a process-wide handler for termination signals.
+    ///
+    /// Should be called only once.
+    pub(super) fn install() -> Self {
+        let signal = Arc::new(AtomicBool::default());
+        let handler_signal = signal.clone();
+        if let Err(e) = ctrlc::set_handler(move || handler_signal.store(true, Ordering::Relaxed)) {
+            error!("unable to set termination signal handler: {e}");
+        }
+
+        Self(signal)
+    }
+}
@@ -1,9 +1,11 @@
 mod cli;
+mod exit;
+mod window_state;
 
 use avian3d::{prelude::*, sync::SyncConfig};
 use bevy::{
-    core_pipeline::experimental::taa::TemporalAntiAliasPlugin, pbr::wireframe::WireframePlugin,
-    prelude::*, render::RenderPlugin,
+    core_pipeline::experimental::taa::TemporalAntiAliasPlugin, log::LogPlugin,
+    pbr::wireframe::WireframePlugin, prelude::*, render::RenderPlugin, window::WindowResolution,
 };
 use bevy_atmosphere::prelude::*;
 #[cfg(feature = "inspector")]
@@ -13,16 +15,59 @@ use bevy_replicon::prelude::*;
 use bevy_replicon_renet::RepliconRenetPlugins;
 use bevy_simple_text_input::TextInputPlugin;
 use leafwing_input_manager::prelude::*;
-use project_harmonia_base::{game_world::navigation::Obstacle, settings::Action, CorePlugins};
-use project_harmonia_ui::UiPlugins;
+use project_harmonia_base::{
+    asset::info::validation::InfoValidationPlugin, bug_report, game_paths::GamePaths,
+    game_world::navigation::Obstacle, mod_package, settings::Action, CorePlugins,
+};
+use project_harmonia_ui::{PreviewExportPlugin, UiPlugins, EXPORT_SIZE};
 use project_harmonia_widgets::WidgetsPlugin;
 use vleue_navigator::prelude::*;
 
 use cli::{Cli, CliPlugin};
+use exit::{ExitPlugin, TerminationSignal};
+use window_state::WindowStatePlugin;
 
 fn main() {
+    let cli = Cli::default();
+    if cli.export_diagnostics {
+        match bug_report::export(&GamePaths::default(), None) {
+            Ok(path) => println!("diagnostics saved to {path:?}"),
+            Err(e) => eprintln!("unable to export diagnostics: {e:#}"),
+        }
+        return;
+    }
+
+    if let Some(result) = cli.run_mod_command() {
+        if let Err(e) = result {
+            eprintln!("{e:#}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let package_mod = cli.package_mod();
+    let preview_export = match &package_mod {
+        Some((dir, _)) => match mod_package::prepare_previews(dir) {
+            Ok((pack, output_dir)) => Some(PreviewExportPlugin { pack, output_dir }),
+            Err(e) => {
+                eprintln!("{e:#}");
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    let validate_assets = cli.validate_assets();
+    // Only servers need a graceful shutdown, clients exit on signals as usual.
+    let termination_signal = cli.host().then(TerminationSignal::install);
+    let resolution = if preview_export.is_some() {
+        // Previews are captured from the window.
+        WindowResolution::new(EXPORT_SIZE, EXPORT_SIZE).with_scale_factor_override(1.0)
+    } else {
+        Default::default()
+    };
     let mut app = App::new();
-    app.init_resource::<Cli>()
+    app.insert_resource(cli)
         .insert_resource(SyncConfig {
             position_to_transform: false,
             ..Default::default()
@@ -33,15 +78,26 @@ fn main() {
                     synchronous_pipeline_compilation: true,
                     ..Default::default()
                 })
+                .set(LogPlugin {
+                    custom_layer: bug_report::log_layer,
+                    ..Default::default()
+                })
                 .set(WindowPlugin {
                     primary_window: Some(Window {
                         title: "Project Harmonia".to_string(),
+                        visible: !validate_assets && preview_export.is_none(),
+                        resolution,
                         ..Default::default()
                     }),
+                    close_when_requested: false,
                     ..Default::default()
                 }),
             TemporalAntiAliasPlugin,
-            RepliconPlugins,
+            RepliconPlugins.build().set(ServerPlugin {
+                // Ticks are incremented according to the server settings.
+                tick_policy: TickPolicy::Manual,
+                ..Default::default()
+            }),
             RepliconRenetPlugins,
             WireframePlugin,
             AtmospherePlugin,
@@ -56,10 +112,40 @@ fn main() {
             TextInputPlugin,
             OutlinePlugin,
         ))
-        .add_plugins((CliPlugin, CorePlugins, WidgetsPlugin, UiPlugins));
+        .add_plugins((CliPlugin, ExitPlugin, CorePlugins, WidgetsPlugin, UiPlugins));
+
+    if let Some(signal) = termination_signal {
+        app.insert_resource(signal);
+    }
+
+    if validate_assets {
+        app.add_plugins(InfoValidationPlugin);
+    }
+
+    if let Some(preview_export) = preview_export {
+        app.add_plugins(preview_export);
+    } else {
+        // Don't override the stored state with the temporary export window.
+        app.add_plugins(WindowStatePlugin);
+    }
 
     #[cfg(feature = "inspector")]
     app.add_plugins(WorldInspectorPlugin::default());
 
-    app.run();
+    let exit = app.run();
+
+    if let Some((dir, output)) = package_mod {
+        if exit.is_error() {
+            eprintln!("unable to render object previews");
+            std::process::exit(1);
+        }
+
+        match mod_package::package(&dir, &output) {
+            Ok(path) => println!("mod packaged to {path:?}"),
+            Err(e) => {
+                eprintln!("{e:#}");
+                std::process::exit(1);
+            }
+        }
+    }
 }
@@ -0,0 +1,95 @@
+use bevy::{
+    prelude::*,
+    window::{MonitorSelection, PrimaryWindow, WindowMode, WindowPosition},
+    winit::WinitWindows,
+};
+
+use project_harmonia_base::{
+    game_paths::GamePaths,
+    settings::{Settings, SettingsApply},
+};
+
+/// Applies video settings to the primary window and persists its state between sessions.
+pub(super) struct WindowStatePlugin;
+
+impl Plugin for WindowStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, (Self::restore, Self::apply_mode).chain())
+            .add_systems(
+                PostUpdate,
+                Self::apply_mode.run_if(on_event::<SettingsApply>()),
+            )
+            .add_systems(Last, Self::store.run_if(on_event::<AppExit>()));
+    }
+}
+
+impl WindowStatePlugin {
+    fn restore(settings: Res<Settings>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+        // Window is missing in headless mode.
+        let Ok(mut window) = windows.get_single_mut() else {
+            return;
+        };
+
+        let state = &settings.video.window;
+        debug!("restoring window state");
+        if let Some(size) = state.size {
+            window.resolution.set(size.x, size.y);
+        }
+        if let Some(position) = state.position {
+            window.position = WindowPosition::At(position);
+        } else if let Some(monitor) = state.monitor {
+            window.position = WindowPosition::Centered(MonitorSelection::Index(monitor));
+        }
+        if state.maximized {
+            window.set_maximized(true);
+        }
+    }
+
+    fn apply_mode(settings: Res<Settings>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+        let Ok(mut window) = windows.get_single_mut() else {
+            return;
+        };
+
+        if settings.video.fullscreen {
+            window.position =
+                WindowPosition::Centered(MonitorSelection::Index(settings.video.monitor));
+            window.mode = WindowMode::Fullscreen;
+        } else {
+            window.mode = WindowMode::Windowed;
+        }
+    }
+
+    fn store(
+        mut settings: ResMut<Settings>,
+        game_paths: Res<GamePaths>,
+        winit_windows: NonSend<WinitWindows>,
+        windows: Query<(Entity, &Window), With<PrimaryWindow>>,
+    ) {
+        let Ok((entity, window)) = windows.get_single() else {
+            return;
+        };
+
+        let state = &mut settings.video.window;
+        if let Some(winit_window) = winit_windows.get_window(entity) {
+            state.maximized = winit_window.is_maximized();
+            state.monitor = winit_window.current_monitor().and_then(|current| {
+                winit_window
+                    .available_monitors()
+                    .position(|monitor| monitor == current)
+            });
+        }
+
+        // Keep the windowed state to restore it after leaving fullscreen or maximized mode.
+        if window.mode == WindowMode::Windowed && !state.maximized {
+            state.size = Some(window.resolution.size());
+            if let WindowPosition::At(position) = window.position {
+                state.position = Some(position);
+            }
+        }
+
+        info!("storing window state");
+        if let Err(error) = settings.write(&game_paths.settings) {
+            error!("unable to store window state: {error:#}");
+        }
+    }
+}
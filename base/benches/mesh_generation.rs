@@ -0,0 +1,29 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use project_harmonia_base::game_world::bench::{RoadsFixture, WallsFixture};
+
+fn walls(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wall_mesh");
+    for (connections, apertures) in [(2, 0), (4, 0), (8, 0), (4, 4), (4, 16)] {
+        let mut fixture = WallsFixture::new(connections, apertures);
+        group.bench_function(
+            BenchmarkId::from_parameter(format!("{connections}_walls_{apertures}_apertures")),
+            |b| b.iter(|| fixture.generate()),
+        );
+    }
+    group.finish();
+}
+
+fn roads(c: &mut Criterion) {
+    let mut group = c.benchmark_group("road_mesh");
+    for connections in [2, 4, 8, 16] {
+        let mut fixture = RoadsFixture::new(connections);
+        group.bench_function(
+            BenchmarkId::from_parameter(format!("{connections}_roads")),
+            |b| b.iter(|| fixture.generate()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, walls, roads);
+criterion_main!(benches);
@@ -0,0 +1,93 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use criterion::{criterion_group, criterion_main, Criterion};
+use project_harmonia_base::{game_world::navigation::Obstacle, test_world::TestWorld};
+use vleue_navigator::prelude::*;
+
+/// Lots along each axis, each with a square room.
+const LOTS_PER_SIDE: usize = 10;
+const LOT_SIZE: f32 = 20.0;
+const ROOM_HALF_SIZE: f32 = 4.0;
+
+/// Maximum number of ticks to wait for a navmesh rebuild.
+const MAX_TICKS: usize = 1000;
+
+fn large_city() -> (TestWorld, Entity) {
+    let mut test_world = TestWorld::builder().build();
+    let city_entity = test_world.spawn_city("City");
+
+    let half_extent = LOTS_PER_SIDE as f32 * LOT_SIZE / 2.0;
+    for x in 0..LOTS_PER_SIDE {
+        for y in 0..LOTS_PER_SIDE {
+            let min = Vec2::new(x as f32, y as f32) * LOT_SIZE - half_extent;
+            let max = min + LOT_SIZE;
+            let lot_entity = test_world.spawn_lot(
+                city_entity,
+                [min, Vec2::new(min.x, max.y), max, Vec2::new(max.x, min.y)],
+            );
+
+            let center = (min + max) / 2.0;
+            let corners = [
+                center + Vec2::new(-ROOM_HALF_SIZE, -ROOM_HALF_SIZE),
+                center + Vec2::new(-ROOM_HALF_SIZE, ROOM_HALF_SIZE),
+                center + Vec2::new(ROOM_HALF_SIZE, ROOM_HALF_SIZE),
+                center + Vec2::new(ROOM_HALF_SIZE, -ROOM_HALF_SIZE),
+            ];
+            for (index, &start) in corners.iter().enumerate() {
+                let end = corners[(index + 1) % corners.len()];
+                test_world.spawn_wall(lot_entity, start, end);
+            }
+        }
+    }
+
+    wait_for_navmesh(&mut test_world);
+
+    (test_world, city_entity)
+}
+
+fn wait_for_navmesh(test_world: &mut TestWorld) {
+    let start_tick = test_world.world().change_tick();
+    let built = test_world.tick_until(MAX_TICKS, |world| {
+        let this_run = world.change_tick();
+        world
+            .query::<Ref<NavMeshStatus>>()
+            .iter(world)
+            .any(|status| {
+                matches!(*status, NavMeshStatus::Built)
+                    && status.last_changed().is_newer_than(start_tick, this_run)
+            })
+    });
+    assert!(built, "navmesh should be rebuilt in {MAX_TICKS} ticks");
+}
+
+fn rebuild(c: &mut Criterion) {
+    let (mut test_world, city_entity) = large_city();
+
+    c.bench_function("navmesh_rebuild", |b| {
+        b.iter(|| {
+            let obstacle_entity = test_world
+                .world_mut()
+                .spawn((
+                    Obstacle,
+                    Collider::cuboid(1.0, 1.0, 1.0),
+                    TransformBundle::default(),
+                ))
+                .set_parent(city_entity)
+                .id();
+            wait_for_navmesh(&mut test_world);
+
+            test_world
+                .world_mut()
+                .entity_mut(obstacle_entity)
+                .despawn_recursive();
+            wait_for_navmesh(&mut test_world);
+        })
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = rebuild
+}
+criterion_main!(benches);
@@ -0,0 +1,181 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use bevy::{prelude::*, scene::ron, utils::HashSet};
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+
+use super::{
+    game_paths::GamePaths,
+    game_world::{
+        city::lot::{LotFamily, LotVertices},
+        family::{building::wall::Wall, Budget, FamilyMembers, FamilyMode, SelectedFamily},
+        spline::SplineSegment,
+    },
+    message::error_message,
+};
+
+/// Tracks player achievements across all worlds.
+///
+/// Unlike world state, unlocked achievements are stored next to the settings.
+pub(super) struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        let game_paths = app.world().resource::<GamePaths>();
+
+        app.insert_resource(Achievements::read(&game_paths.achievements).unwrap_or_default())
+            .add_event::<AchievementUnlocked>()
+            .add_systems(
+                Update,
+                (Self::track_walls, Self::track_budget, Self::track_members),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::write
+                    .pipe(error_message)
+                    .run_if(on_event::<AchievementUnlocked>()),
+            );
+    }
+}
+
+/// Budget required for [`Achievement::Wealthy`].
+const WEALTHY_BUDGET: u32 = 10_000;
+
+/// Number of members required for [`Achievement::BigFamily`].
+const BIG_FAMILY_MEMBERS: usize = 8;
+
+impl AchievementsPlugin {
+    /// Unlocks [`Achievement::FirstHouse`] for walls built on the selected family lot.
+    ///
+    /// Runs in all states to skip walls spawned on load.
+    fn track_walls(
+        mut unlock_events: EventWriter<AchievementUnlocked>,
+        mut achievements: ResMut<Achievements>,
+        family_mode: Option<Res<State<FamilyMode>>>,
+        walls: Query<&SplineSegment, Added<Wall>>,
+        lots: Query<(&LotVertices, &LotFamily)>,
+        families: Query<(), With<SelectedFamily>>,
+    ) {
+        if family_mode.map_or(true, |mode| *mode.get() != FamilyMode::Building) {
+            return;
+        }
+
+        let family_lot = |segment: &SplineSegment| {
+            lots.iter().any(|(vertices, lot_family)| {
+                families.contains(**lot_family) && vertices.contains_point(segment.start)
+            })
+        };
+        if walls.iter().any(family_lot) {
+            achievements.unlock(Achievement::FirstHouse, &mut unlock_events);
+        }
+    }
+
+    fn track_budget(
+        mut unlock_events: EventWriter<AchievementUnlocked>,
+        mut achievements: ResMut<Achievements>,
+        families: Query<&Budget, (Changed<Budget>, With<SelectedFamily>)>,
+    ) {
+        if families.iter().any(|budget| **budget >= WEALTHY_BUDGET) {
+            achievements.unlock(Achievement::Wealthy, &mut unlock_events);
+        }
+    }
+
+    fn track_members(
+        mut unlock_events: EventWriter<AchievementUnlocked>,
+        mut achievements: ResMut<Achievements>,
+        families: Query<&FamilyMembers, (Changed<FamilyMembers>, With<SelectedFamily>)>,
+    ) {
+        if families
+            .iter()
+            .any(|members| members.len() >= BIG_FAMILY_MEMBERS)
+        {
+            achievements.unlock(Achievement::BigFamily, &mut unlock_events);
+        }
+    }
+
+    fn write(achievements: Res<Achievements>, game_paths: Res<GamePaths>) -> Result<()> {
+        achievements.write(&game_paths.achievements)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, EnumIter, Eq, Hash, PartialEq, Serialize)]
+pub enum Achievement {
+    FirstHouse,
+    Wealthy,
+    BigFamily,
+}
+
+impl Achievement {
+    pub fn name(self) -> &'static str {
+        match self {
+            Achievement::FirstHouse => "Home sweet home",
+            Achievement::Wealthy => "Wealthy",
+            Achievement::BigFamily => "Big family",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Achievement::FirstHouse => "Build your first wall",
+            Achievement::Wealthy => "Have a family budget of 10000",
+            Achievement::BigFamily => "Have a family of 8 members",
+        }
+    }
+}
+
+/// Unlocked achievements.
+#[derive(Default, Deserialize, Resource, Serialize)]
+#[serde(default)]
+pub struct Achievements {
+    unlocked: HashSet<Achievement>,
+}
+
+impl Achievements {
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked.contains(&achievement)
+    }
+
+    fn unlock(
+        &mut self,
+        achievement: Achievement,
+        unlock_events: &mut EventWriter<AchievementUnlocked>,
+    ) {
+        if self.unlocked.insert(achievement) {
+            info!("unlocking achievement `{achievement:?}`");
+            unlock_events.send(AchievementUnlocked(achievement));
+        }
+    }
+
+    /// Will be initialed with defaults if the file does not exist.
+    fn read(file_name: &Path) -> Result<Self> {
+        info!("reading achievements from {file_name:?}");
+
+        match fs::read_to_string(file_name) {
+            Ok(content) => ron::from_str(&content)
+                .with_context(|| format!("unable to read achievements from {file_name:?}")),
+            Err(_) => Ok(Default::default()),
+        }
+    }
+
+    fn write(&self, file_name: &Path) -> Result<()> {
+        info!("writing achievements to {file_name:?}");
+
+        let content = ron::ser::to_string_pretty(&self, Default::default())
+            .context("unable to serialize achievements")?;
+
+        let parent_folder = file_name
+            .parent()
+            .expect("achievements filename should have a parent dir");
+
+        fs::create_dir_all(parent_folder)
+            .with_context(|| format!("unable to create {parent_folder:?}"))?;
+
+        fs::write(file_name, content)
+            .with_context(|| format!("unable to write achievements to {file_name:?}"))
+    }
+}
+
+/// Emitted when a new achievement is unlocked.
+#[derive(Event)]
+pub struct AchievementUnlocked(pub Achievement);
@@ -1,5 +1,6 @@
 pub(super) mod collection;
 pub mod info;
+pub mod lifetime;
 pub(super) mod material;
 
 use std::path::Path;
@@ -7,13 +8,14 @@ use std::path::Path;
 use bevy::{asset::AssetPath, prelude::*};
 
 use info::InfoPlugins;
+use lifetime::LifetimePlugin;
 use material::MaterialPlugin;
 
 pub(super) struct AssetPlugin;
 
 impl Plugin for AssetPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((MaterialPlugin, InfoPlugins));
+        app.add_plugins((MaterialPlugin, InfoPlugins, LifetimePlugin));
     }
 }
 
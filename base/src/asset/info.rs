@@ -1,7 +1,18 @@
+pub mod animation_info;
+pub mod names_info;
 pub mod object_info;
+pub mod preload;
+pub mod preset_info;
 pub mod road_info;
-
-use std::{env, marker::PhantomData, path::Path};
+pub mod scenario_info;
+pub mod service_info;
+pub mod validation;
+
+use std::{
+    env,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Result;
 use bevy::{
@@ -10,20 +21,33 @@ use bevy::{
     prelude::*,
     reflect::{TypeRegistry, TypeRegistryArc},
     scene::ron::{self, error::SpannedResult},
+    tasks::{block_on, futures_lite::future, IoTaskPool, Task},
 };
 use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
 
+use animation_info::AnimationInfo;
+use names_info::NamesInfo;
 use object_info::ObjectInfo;
+use preload::{PreloadPlugin, PreloadProgress};
+use preset_info::PresetInfo;
 use road_info::RoadInfo;
+use scenario_info::ScenarioInfo;
+use service_info::ServiceInfo;
 
 pub(super) struct InfoPlugins;
 
 impl PluginGroup for InfoPlugins {
     fn build(self) -> PluginGroupBuilder {
         PluginGroupBuilder::start::<Self>()
+            .add(PreloadPlugin)
+            .add(InfoPlugin::<NamesInfo>::default())
             .add(InfoPlugin::<ObjectInfo>::default())
             .add(InfoPlugin::<RoadInfo>::default())
+            .add(InfoPlugin::<AnimationInfo>::default())
+            .add(InfoPlugin::<ServiceInfo>::default())
+            .add(InfoPlugin::<ScenarioInfo>::default())
+            .add(InfoPlugin::<PresetInfo>::default())
     }
 }
 
@@ -37,12 +61,48 @@ impl<A> Default for InfoPlugin<A> {
 
 impl<T: Asset + Info> Plugin for InfoPlugin<T> {
     fn build(&self, app: &mut App) {
-        app.init_asset::<T>().init_asset_loader::<InfoLoader<T>>();
+        app.init_asset::<T>()
+            .init_asset_loader::<InfoLoader<T>>()
+            .init_resource::<InfoHandles<T>>()
+            .add_systems(
+                PreUpdate,
+                Self::load_handles.run_if(resource_exists::<ScanTask<T>>),
+            );
     }
 
     fn finish(&self, app: &mut App) {
-        // Registered in the end to load all handles after all reflection registrations.
-        app.init_resource::<InfoHandles<T>>();
+        // Started in the end to load all handles after all reflection registrations.
+        app.world_mut()
+            .resource_mut::<PreloadProgress>()
+            .start_scan();
+        app.insert_resource(ScanTask::<T>::spawn());
+    }
+}
+
+impl<A: Asset + Info> InfoPlugin<A> {
+    /// Loads files found by [`ScanTask`] when it's finished.
+    fn load_handles(
+        mut commands: Commands,
+        mut scan_task: ResMut<ScanTask<A>>,
+        mut handles: ResMut<InfoHandles<A>>,
+        mut progress: ResMut<PreloadProgress>,
+        asset_server: Res<AssetServer>,
+    ) {
+        let Some(paths) = block_on(future::poll_once(&mut scan_task.task)) else {
+            return;
+        };
+
+        debug!("found {} `{}` files", paths.len(), A::EXTENSION);
+        let mut loaded = Vec::with_capacity(paths.len());
+        for path in &paths {
+            debug!("loading info for {path:?}");
+            let handle: Handle<A> = asset_server.load(path.clone());
+            loaded.push((path.as_path(), handle.clone().untyped()));
+            handles.0.push(handle);
+        }
+        progress.finish_scan(loaded);
+
+        commands.remove_resource::<ScanTask<A>>();
     }
 }
 
@@ -89,40 +149,61 @@ impl<A: Asset + Info> AssetLoader for InfoLoader<A> {
     }
 }
 
-/// Preloads and stores info handles.
+/// Stores info handles loaded on startup.
 #[derive(Resource)]
-#[allow(dead_code)]
 struct InfoHandles<A: Asset>(Vec<Handle<A>>);
 
-impl<A: Asset + Info> FromWorld for InfoHandles<A> {
-    fn from_world(world: &mut World) -> Self {
-        let assets_dir =
-            Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap_or_default()).join("assets");
+impl<A: Asset> Default for InfoHandles<A> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
 
-        let mut handles = Vec::new();
-        let asset_server = world.resource::<AssetServer>();
-        for entry in WalkDir::new(&assets_dir)
-            .into_iter()
-            .filter_map(|entry| entry.ok())
-        {
-            // Use `ends_with` because extension consists of 2 dots.
-            if entry
-                .path()
-                .to_str()
-                .is_some_and(|path| path.ends_with(A::EXTENSION))
-            {
-                let path = entry
-                    .path()
-                    .strip_prefix(&assets_dir)
-                    .unwrap_or_else(|e| panic!("entries should start with {assets_dir:?}: {e}"));
+/// Searches for info files in background.
+///
+/// Walking directories with many mods is slow, so it shouldn't block the first frame.
+#[derive(Resource)]
+struct ScanTask<A> {
+    task: Task<Vec<PathBuf>>,
+    marker: PhantomData<A>,
+}
 
-                debug!("loading info for {path:?}");
-                handles.push(asset_server.load(path.to_path_buf()));
-            }
+impl<A: Info + Send + Sync + 'static> ScanTask<A> {
+    fn spawn() -> Self {
+        let task = IoTaskPool::get().spawn(async { scan_paths(A::EXTENSION) });
+        Self {
+            task,
+            marker: PhantomData,
         }
+    }
+}
 
-        Self(handles)
+/// Finds all files with the extension in the assets directory.
+///
+/// Returns paths relative to the assets directory.
+fn scan_paths(extension: &str) -> Vec<PathBuf> {
+    let assets_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap_or_default()).join("assets");
+
+    let mut paths = Vec::new();
+    for entry in WalkDir::new(&assets_dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+    {
+        // Use `ends_with` because extension consists of 2 dots.
+        if entry
+            .path()
+            .to_str()
+            .is_some_and(|path| path.ends_with(extension))
+        {
+            let path = entry
+                .path()
+                .strip_prefix(&assets_dir)
+                .unwrap_or_else(|e| panic!("entries should start with {assets_dir:?}: {e}"));
+            paths.push(path.to_path_buf());
+        }
     }
+
+    paths
 }
 
 trait Info: Sized {
@@ -167,10 +248,29 @@ mod tests {
     use super::*;
     use crate::{
         combined_scene_collider::SceneColliderConstructor,
-        game_world::object::{
-            door::Door,
-            placing_object::{side_snap::SideSnap, wall_snap::WallSnap},
-            wall_mount::WallMount,
+        game_world::{
+            aging::Overgrowth,
+            burglary::{Alarm, Valuable},
+            collectable::Spawner,
+            family::{building::decoration::EdgeDecoration, utilities::Utilities},
+            object::{
+                counter::Counter,
+                door::Door,
+                fire::{Flammable, Stove},
+                garden_plot::GardenPlot,
+                lamp::LightSource,
+                occupancy::UseSlots,
+                pillar::Pillar,
+                placing_object::{
+                    ground_fit::GroundFit,
+                    placement_rules::PlacementRules,
+                    side_snap::SideSnap,
+                    surface_snap::{SurfaceItem, SurfaceSlots},
+                    wall_snap::{WallHeight, WallSnap},
+                },
+                wall_mount::WallMount,
+                wear::Durability,
+            },
         },
     };
 
@@ -179,12 +279,36 @@ mod tests {
         let mut registry = TypeRegistry::new();
         registry.register::<WallMount>();
         registry.register::<WallSnap>();
+        registry.register::<WallHeight>();
+        registry.register::<PlacementRules>();
+        registry.register::<GroundFit>();
         registry.register::<SideSnap>();
+        registry.register::<SurfaceSlots>();
+        registry.register::<SurfaceItem>();
+        registry.register::<UseSlots>();
+        registry.register::<Counter>();
         registry.register::<Door>();
+        registry.register::<Flammable>();
+        registry.register::<Stove>();
+        registry.register::<GardenPlot>();
+        registry.register::<Pillar>();
+        registry.register::<Durability>();
+        registry.register::<Spawner>();
+        registry.register::<Utilities>();
+        registry.register::<Overgrowth>();
+        registry.register::<Valuable>();
+        registry.register::<Alarm>();
+        registry.register::<EdgeDecoration>();
+        registry.register::<LightSource>();
         registry.register::<SceneColliderConstructor>();
 
+        deserialize::<NamesInfo>(&registry)?;
         deserialize::<ObjectInfo>(&registry)?;
         deserialize::<RoadInfo>(&registry)?;
+        deserialize::<AnimationInfo>(&registry)?;
+        deserialize::<ServiceInfo>(&registry)?;
+        deserialize::<ScenarioInfo>(&registry)?;
+        deserialize::<PresetInfo>(&registry)?;
 
         Ok(())
     }
@@ -0,0 +1,106 @@
+use std::path::Path;
+
+use bevy::{
+    animation::RepeatAnimation,
+    asset::AssetPath,
+    prelude::*,
+    reflect::TypeRegistry,
+    scene::ron::{self, error::SpannedResult},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{asset, game_world::actor::age::LifeStage};
+
+use super::Info;
+
+/// Actor animation clip addressable by its ID.
+#[derive(TypePath, Serialize, Deserialize, Asset)]
+pub struct AnimationInfo {
+    /// Unique string ID used by tasks to play the animation.
+    ///
+    /// Example: `thoughtful_nod`.
+    pub id: String,
+
+    /// Path to the clip with a label, like `idle.gltf#Animation0`.
+    pub clip: AssetPath<'static>,
+
+    /// Whether the clip moves the actor root instead of playing in place.
+    #[serde(default)]
+    pub root_motion: bool,
+
+    #[serde(default)]
+    pub loop_mode: LoopMode,
+
+    /// Names of bones affected by the clip with their descendants, empty for the whole body.
+    ///
+    /// Clips with a mask are played on top of the current locomotion,
+    /// for example, to talk while walking.
+    #[serde(default)]
+    pub mask: Vec<String>,
+
+    /// Makes the animation a variant randomly chosen while the actor stands.
+    #[serde(default)]
+    pub idle: Option<IdleInfo>,
+}
+
+impl Info for AnimationInfo {
+    const EXTENSION: &'static str = "animation.ron";
+
+    fn from_str(
+        data: &str,
+        options: ron::Options,
+        _registry: &TypeRegistry,
+        dir: Option<&Path>,
+    ) -> SpannedResult<Self> {
+        let mut info: Self = options.from_str(data)?;
+        if let Some(dir) = dir {
+            asset::change_parent_dir(&mut info.clip, dir);
+        }
+
+        Ok(info)
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct IdleInfo {
+    /// Relative chance to be chosen among other suitable variants.
+    pub weight: f32,
+
+    /// Mood in which the variant can be chosen, any if [`None`].
+    #[serde(default)]
+    pub mood: Option<Mood>,
+
+    /// Life stages for which the variant can be chosen, any if empty.
+    #[serde(default)]
+    pub stages: Vec<LifeStage>,
+
+    /// Whether the variant is played once from time to time instead of looping.
+    #[serde(default)]
+    pub fidget: bool,
+}
+
+/// Actor mood based on satisfaction of needs.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Mood {
+    Good,
+    Neutral,
+    Bad,
+}
+
+#[derive(Clone, Copy, Default, Deserialize, Serialize)]
+pub enum LoopMode {
+    #[default]
+    Once,
+    Count(u32),
+    Forever,
+}
+
+impl From<LoopMode> for RepeatAnimation {
+    fn from(value: LoopMode) -> Self {
+        match value {
+            LoopMode::Once => RepeatAnimation::Count(1),
+            LoopMode::Count(count) => RepeatAnimation::Count(count),
+            LoopMode::Forever => RepeatAnimation::Forever,
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use std::path::Path;
+
+use bevy::{
+    prelude::*,
+    reflect::TypeRegistry,
+    scene::ron::{self, error::SpannedResult},
+};
+use serde::{Deserialize, Serialize};
+
+use super::Info;
+
+/// Name tables for a single locale.
+///
+/// Used by [`NameGenerator`](crate::game_world::actor::name_generator::NameGenerator).
+#[derive(TypePath, Serialize, Deserialize, Asset)]
+pub struct NamesInfo {
+    /// Language code, like `en`.
+    pub locale: String,
+    pub male: Vec<String>,
+    pub female: Vec<String>,
+    pub last: Vec<String>,
+}
+
+impl Info for NamesInfo {
+    const EXTENSION: &'static str = "names.ron";
+
+    fn from_str(
+        data: &str,
+        options: ron::Options,
+        _registry: &TypeRegistry,
+        _dir: Option<&Path>,
+    ) -> SpannedResult<Self> {
+        options.from_str(data)
+    }
+}
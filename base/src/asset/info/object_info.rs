@@ -17,14 +17,18 @@ use serde::{
 use strum::{Display, IntoStaticStr, VariantNames};
 
 use super::{GeneralInfo, Info, ReflectMapPaths};
-use crate::asset;
+use crate::{asset, game_world::clock::Season};
 
 #[derive(TypePath, Asset)]
 pub struct ObjectInfo {
     pub general: GeneralInfo,
     pub scene: AssetPath<'static>,
     pub category: ObjectCategory,
+    pub tags: Vec<ObjectTag>,
+    /// Cost charged from the lot family when the object is bought and refunded on selling.
+    pub price: u32,
     pub preview_translation: Vec3,
+    pub preview: PreviewInfo,
     pub components: Vec<Box<dyn Reflect>>,
     pub place_components: Vec<Box<dyn Reflect>>,
     pub spawn_components: Vec<Box<dyn Reflect>>,
@@ -48,6 +52,16 @@ impl Info for ObjectInfo {
     }
 }
 
+impl ObjectInfo {
+    /// Returns the season from tags if the object is seasonal.
+    pub fn season(&self) -> Option<Season> {
+        self.tags.iter().find_map(|tag| match *tag {
+            ObjectTag::Season(season) => Some(season),
+            _ => None,
+        })
+    }
+}
+
 /// Fields of [`ObjectInfo`] for manual deserialization.
 #[derive(Deserialize, VariantNames, IntoStaticStr)]
 #[strum(serialize_all = "snake_case")]
@@ -56,7 +70,10 @@ enum ObjectInfoField {
     General,
     Scene,
     Category,
+    Tags,
+    Price,
     PreviewTranslation,
+    Preview,
     Components,
     PlaceComponents,
     SpawnComponents,
@@ -73,6 +90,9 @@ pub enum ObjectCategory {
     Furniture,
     Windows,
     Doors,
+    Structure,
+    /// Spawned by world aging, not sold in catalogs.
+    Overgrowth,
 }
 
 impl ObjectCategory {
@@ -92,6 +112,7 @@ impl ObjectCategory {
         ObjectCategory::Furniture,
         ObjectCategory::Windows,
         ObjectCategory::Doors,
+        ObjectCategory::Structure,
     ];
 
     pub fn glyph(self) -> &'static str {
@@ -105,6 +126,83 @@ impl ObjectCategory {
             ObjectCategory::Furniture => "💺",
             ObjectCategory::Windows => "🔲",
             ObjectCategory::Doors => "🚪",
+            ObjectCategory::Structure => "🏛",
+            ObjectCategory::Overgrowth => "🌿",
+        }
+    }
+
+    /// Returns `true` if objects of this category can be bought.
+    pub fn is_sold(self) -> bool {
+        self != ObjectCategory::Overgrowth
+    }
+}
+
+/// Camera framing for the object preview.
+#[derive(Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct PreviewInfo {
+    /// Distance from the camera to the object.
+    ///
+    /// Overrides the Z component of [`ObjectInfo::preview_translation`].
+    pub distance: Option<f32>,
+
+    /// Camera pitch in degrees, positive values look from above.
+    pub angle: f32,
+
+    pub background: Color,
+}
+
+impl Default for PreviewInfo {
+    fn default() -> Self {
+        Self {
+            distance: None,
+            angle: 0.0,
+            background: Color::NONE,
+        }
+    }
+}
+
+/// Free-form object tag from metadata.
+///
+/// Known tags are capabilities used by catalog filters and task lists via
+/// [`ObjectTags`](crate::game_world::object::ObjectTags).
+/// Unknown tags are preserved as is for forward compatibility.
+#[derive(Clone, Debug, Deserialize, Display, PartialEq)]
+#[serde(from = "String")]
+pub enum ObjectTag {
+    Seating,
+    Sleeping,
+    Surface,
+    #[strum(serialize = "Light source")]
+    LightSource,
+    /// Available in the catalog only during the season.
+    #[strum(to_string = "{0}")]
+    Season(Season),
+    #[strum(to_string = "{0}")]
+    Custom(String),
+}
+
+impl ObjectTag {
+    pub const CAPABILITIES: &'static [ObjectTag] = &[
+        ObjectTag::Seating,
+        ObjectTag::Sleeping,
+        ObjectTag::Surface,
+        ObjectTag::LightSource,
+    ];
+}
+
+impl From<String> for ObjectTag {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "seating" => ObjectTag::Seating,
+            "sleeping" => ObjectTag::Sleeping,
+            "surface" => ObjectTag::Surface,
+            "light_source" => ObjectTag::LightSource,
+            "spring" => ObjectTag::Season(Season::Spring),
+            "summer" => ObjectTag::Season(Season::Summer),
+            "autumn" => ObjectTag::Season(Season::Autumn),
+            "winter" => ObjectTag::Season(Season::Winter),
+            _ => ObjectTag::Custom(value),
         }
     }
 }
@@ -137,7 +235,10 @@ impl<'de> Visitor<'de> for ObjectInfoDeserializer<'_> {
         let mut general = None;
         let mut scene = None;
         let mut category = None;
+        let mut tags = None;
+        let mut price = None;
         let mut preview_translation = None;
+        let mut preview = None;
         let mut components = None;
         let mut place_components = None;
         let mut spawn_components = None;
@@ -161,6 +262,18 @@ impl<'de> Visitor<'de> for ObjectInfoDeserializer<'_> {
                     }
                     category = Some(map.next_value()?);
                 }
+                ObjectInfoField::Tags => {
+                    if tags.is_some() {
+                        return Err(de::Error::duplicate_field(ObjectInfoField::Tags.into()));
+                    }
+                    tags = Some(map.next_value()?);
+                }
+                ObjectInfoField::Price => {
+                    if price.is_some() {
+                        return Err(de::Error::duplicate_field(ObjectInfoField::Price.into()));
+                    }
+                    price = Some(map.next_value()?);
+                }
                 ObjectInfoField::PreviewTranslation => {
                     if preview_translation.is_some() {
                         return Err(de::Error::duplicate_field(
@@ -169,6 +282,12 @@ impl<'de> Visitor<'de> for ObjectInfoDeserializer<'_> {
                     }
                     preview_translation = Some(map.next_value()?);
                 }
+                ObjectInfoField::Preview => {
+                    if preview.is_some() {
+                        return Err(de::Error::duplicate_field(ObjectInfoField::Preview.into()));
+                    }
+                    preview = Some(map.next_value()?);
+                }
                 ObjectInfoField::Components => {
                     if components.is_some() {
                         return Err(de::Error::duplicate_field(
@@ -207,8 +326,11 @@ impl<'de> Visitor<'de> for ObjectInfoDeserializer<'_> {
         let scene = scene.ok_or_else(|| de::Error::missing_field(ObjectInfoField::Scene.into()))?;
         let category =
             category.ok_or_else(|| de::Error::missing_field(ObjectInfoField::Category.into()))?;
+        let tags = tags.unwrap_or_default();
+        let price = price.unwrap_or_default();
         let preview_translation = preview_translation
             .ok_or_else(|| de::Error::missing_field(ObjectInfoField::PreviewTranslation.into()))?;
+        let preview = preview.unwrap_or_default();
         let components = components.unwrap_or_default();
         let place_components = place_components.unwrap_or_default();
         let spawn_components = spawn_components.unwrap_or_default();
@@ -217,7 +339,10 @@ impl<'de> Visitor<'de> for ObjectInfoDeserializer<'_> {
             general,
             scene,
             category,
+            tags,
+            price,
             preview_translation,
+            preview,
             components,
             place_components,
             spawn_components,
@@ -0,0 +1,139 @@
+use std::{collections::BTreeMap, path::Path};
+
+use bevy::{asset::LoadState, prelude::*};
+
+/// Tracks loading of metadata files found on startup.
+///
+/// Each info plugin scans the assets directory in background
+/// and registers found files in [`PreloadProgress`] once the scan is finished.
+/// Files are counted as loaded when their loading finishes or fails.
+pub(super) struct PreloadPlugin;
+
+impl Plugin for PreloadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PreloadProgress>().add_systems(
+            PreUpdate,
+            Self::update_progress
+                .run_if(|progress: Res<PreloadProgress>| !progress.pending.is_empty()),
+        );
+    }
+}
+
+impl PreloadPlugin {
+    fn update_progress(asset_server: Res<AssetServer>, mut progress: ResMut<PreloadProgress>) {
+        let progress = &mut *progress;
+        progress.pending.retain(|(pack, handle)| {
+            if !matches!(
+                asset_server.load_state(handle.id()),
+                LoadState::Loaded | LoadState::Failed(_)
+            ) {
+                return true;
+            }
+
+            let pack_progress = progress
+                .packs
+                .get_mut(pack)
+                .expect("pending files should belong to a registered pack");
+            pack_progress.loaded += 1;
+            if pack_progress.is_finished() {
+                info!(
+                    "loaded {} metadata files from '{pack}'",
+                    pack_progress.total
+                );
+            }
+
+            false
+        });
+    }
+}
+
+/// Loading progress of metadata files grouped by asset packs.
+#[derive(Default, Resource)]
+pub struct PreloadProgress {
+    scans_left: usize,
+    packs: BTreeMap<String, PackProgress>,
+    pending: Vec<(String, UntypedHandle)>,
+}
+
+impl PreloadProgress {
+    /// Returns `true` if all metadata files are found and loaded.
+    pub fn is_finished(&self) -> bool {
+        self.scans_left == 0 && self.pending.is_empty()
+    }
+
+    /// Returns progress of all found asset packs sorted by name.
+    pub fn packs(&self) -> impl Iterator<Item = (&str, PackProgress)> {
+        self.packs
+            .iter()
+            .map(|(name, &progress)| (name.as_str(), progress))
+    }
+
+    pub(super) fn start_scan(&mut self) {
+        self.scans_left += 1;
+    }
+
+    /// Registers files found by a finished scan.
+    ///
+    /// Paths should be relative to the assets directory.
+    pub(super) fn finish_scan(
+        &mut self,
+        handles: impl IntoIterator<Item = (&Path, UntypedHandle)>,
+    ) {
+        for (path, handle) in handles {
+            let pack = pack_name(path);
+            self.packs.entry(pack.clone()).or_default().total += 1;
+            self.pending.push((pack, handle));
+        }
+        self.scans_left -= 1;
+    }
+}
+
+/// Returns asset pack name for a path relative to the assets directory.
+///
+/// Packs are top-level directories, like `base`.
+fn pack_name(path: &Path) -> String {
+    path.components()
+        .next()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Run condition that returns `true` after all metadata files are loaded.
+pub fn preload_finished(progress: Res<PreloadProgress>) -> bool {
+    progress.is_finished()
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct PackProgress {
+    pub loaded: usize,
+    pub total: usize,
+}
+
+impl PackProgress {
+    pub fn is_finished(&self) -> bool {
+        self.loaded == self.total
+    }
+
+    /// Returns loaded part from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.loaded as f32 / self.total as f32
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs() {
+        assert_eq!(
+            pack_name(Path::new("base/objects/chair.object.ron")),
+            "base"
+        );
+        assert_eq!(pack_name(Path::new("my_mod/road.road.ron")), "my_mod");
+    }
+}
@@ -0,0 +1,106 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use bevy::{
+    asset::AssetPath,
+    prelude::*,
+    reflect::TypeRegistry,
+    scene::ron::{self, error::SpannedResult},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{object_info::ObjectInfo, GeneralInfo, Info};
+
+/// Styled room layout that can be placed as a whole.
+#[derive(TypePath, Serialize, Deserialize, Asset)]
+pub struct PresetInfo {
+    pub general: GeneralInfo,
+    /// Size of the room for which the layout was designed.
+    ///
+    /// Object positions are scaled to the size of the target area.
+    pub size: Vec2,
+    pub objects: Vec<PresetObject>,
+}
+
+impl Info for PresetInfo {
+    const EXTENSION: &'static str = "preset.ron";
+
+    fn from_str(
+        data: &str,
+        options: ron::Options,
+        _registry: &TypeRegistry,
+        _dir: Option<&Path>,
+    ) -> SpannedResult<Self> {
+        options.from_str(data)
+    }
+}
+
+impl PresetInfo {
+    /// Returns the sum of prices of all objects.
+    ///
+    /// Returns an error if any object has no loaded info.
+    pub fn cost(
+        &self,
+        asset_server: &AssetServer,
+        objects_info: &Assets<ObjectInfo>,
+    ) -> Result<u32> {
+        self.objects.iter().try_fold(0u32, |cost, object| {
+            let info = asset_server
+                .get_handle(&object.info_path)
+                .and_then(|handle| objects_info.get(&handle))
+                .with_context(|| format!("object '{}' is missing", object.info_path))?;
+            Ok(cost.saturating_add(info.price))
+        })
+    }
+
+    /// Returns object positions and rotations scaled to fit the area.
+    pub fn fit(&self, area: Rect) -> impl Iterator<Item = (&AssetPath<'static>, Vec2, f32)> {
+        let scale = area.size() / self.size;
+        let center = area.center();
+        self.objects.iter().map(move |object| {
+            (
+                &object.info_path,
+                center + object.translation * scale,
+                object.rotation.to_radians(),
+            )
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PresetObject {
+    /// Path to the object info relative to the assets directory.
+    pub info_path: AssetPath<'static>,
+    /// Position relative to the layout center.
+    pub translation: Vec2,
+    /// Rotation around the Y axis in degrees.
+    #[serde(default)]
+    pub rotation: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit() {
+        let preset = PresetInfo {
+            general: GeneralInfo {
+                name: String::new(),
+                author: String::new(),
+                license: String::new(),
+            },
+            size: Vec2::new(4.0, 2.0),
+            objects: vec![PresetObject {
+                info_path: "object.ron".into(),
+                translation: Vec2::new(1.0, -1.0),
+                rotation: 90.0,
+            }],
+        };
+
+        let area = Rect::new(10.0, 10.0, 18.0, 11.0);
+        let (_, position, rotation) = preset.fit(area).next().unwrap();
+        assert_eq!(position, Vec2::new(16.0, 10.0));
+        assert_eq!(rotation, 90.0_f32.to_radians());
+    }
+}
@@ -18,6 +18,22 @@ pub struct RoadInfo {
     pub material: AssetPath<'static>,
     pub preview: AssetPath<'static>,
     pub half_width: f32,
+    #[serde(default)]
+    pub props: Vec<RoadProp>,
+    /// Looping sound played near the road, like traffic.
+    #[serde(default)]
+    pub ambience: Option<AssetPath<'static>>,
+}
+
+/// Object periodically placed along both sides of a decorated road.
+#[derive(Serialize, Deserialize)]
+pub struct RoadProp {
+    /// Path to the object info relative to the assets root.
+    pub object: AssetPath<'static>,
+    /// Distance between props along the road.
+    pub spacing: f32,
+    /// Distance from the road edge.
+    pub offset: f32,
 }
 
 impl Info for RoadInfo {
@@ -33,6 +49,9 @@ impl Info for RoadInfo {
         if let Some(dir) = dir {
             asset::change_parent_dir(&mut info.material, dir);
             asset::change_parent_dir(&mut info.preview, dir);
+            if let Some(ambience) = &mut info.ambience {
+                asset::change_parent_dir(ambience, dir);
+            }
         }
 
         Ok(info)
@@ -0,0 +1,97 @@
+use std::path::Path;
+
+use bevy::{
+    prelude::*,
+    reflect::TypeRegistry,
+    scene::ron::{self, error::SpannedResult},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{GeneralInfo, Info};
+use crate::game_world::city::{generation::GenerationParams, CityMode};
+
+/// Challenge that starts from a generated city and ends when its conditions are met.
+#[derive(TypePath, Serialize, Deserialize, Asset)]
+pub struct ScenarioInfo {
+    pub general: GeneralInfo,
+    pub description: String,
+    pub city_name: String,
+    #[serde(default)]
+    pub generation: GenerationParams,
+    /// Budget of each family created during the scenario.
+    pub budget: u32,
+    /// Features unavailable during the scenario.
+    #[serde(default)]
+    pub locked: Vec<ScenarioFeature>,
+    /// Conditions that all need to be met to win.
+    pub goals: Vec<ScenarioCondition>,
+    /// Conditions that end the scenario with a loss if any is met.
+    #[serde(default)]
+    pub failures: Vec<ScenarioCondition>,
+}
+
+impl Info for ScenarioInfo {
+    const EXTENSION: &'static str = "scenario.ron";
+
+    fn from_str(
+        data: &str,
+        options: ron::Options,
+        _registry: &TypeRegistry,
+        _dir: Option<&Path>,
+    ) -> SpannedResult<Self> {
+        options.from_str(data)
+    }
+}
+
+/// Condition evaluated against all cities of the world.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum ScenarioCondition {
+    /// Number of actors reaches the value.
+    Population(u32),
+    /// Total budget of all families reaches the value.
+    Wealth(u32),
+    /// Number of lots owned by families reaches the value.
+    ResidentialLots(u32),
+    /// Number of lots with a service reaches the value.
+    CommunityLots(u32),
+    /// Day with the specified number starting from 0 is reached.
+    Day(u32),
+    /// All families have an empty budget.
+    Bankrupt,
+}
+
+impl ScenarioCondition {
+    pub fn description(self) -> String {
+        match self {
+            Self::Population(value) => format!("Reach a population of {value}"),
+            Self::Wealth(value) => format!("Have a total budget of {value}"),
+            Self::ResidentialLots(value) => format!("Have {value} residential lots"),
+            Self::CommunityLots(value) => format!("Have {value} community lots"),
+            Self::Day(value) => format!("Reach day {}", value + 1),
+            Self::Bankrupt => "Run out of money".to_string(),
+        }
+    }
+}
+
+/// Feature that can be locked by a scenario.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Reflect, Serialize)]
+pub enum ScenarioFeature {
+    Lots,
+    Roads,
+    Water,
+    Generation,
+    Building,
+}
+
+impl ScenarioFeature {
+    /// Returns the city mode that provides this feature.
+    pub fn city_mode(self) -> Option<CityMode> {
+        match self {
+            Self::Lots => Some(CityMode::Lots),
+            Self::Roads => Some(CityMode::Roads),
+            Self::Water => Some(CityMode::Water),
+            Self::Generation => Some(CityMode::Generation),
+            Self::Building => None,
+        }
+    }
+}
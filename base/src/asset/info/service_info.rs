@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use bevy::{
+    asset::AssetPath,
+    prelude::*,
+    reflect::TypeRegistry,
+    scene::ron::{self, error::SpannedResult},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{GeneralInfo, Info};
+use crate::{asset, game_world::actor::needs::NeedKind};
+
+/// Community lot type, like a park or a gym.
+///
+/// Assigned to lots via [`LotService`](crate::game_world::city::lot::community_lot::LotService).
+#[derive(TypePath, Serialize, Deserialize, Asset)]
+pub struct ServiceInfo {
+    pub general: GeneralInfo,
+    /// Distance from the lot center within which the service is considered accessible.
+    pub coverage: f32,
+    pub effects: Vec<ServiceEffect>,
+    /// Looping sound played near the lot, like birds in a park.
+    #[serde(default)]
+    pub ambience: Option<AssetPath<'static>>,
+}
+
+impl Info for ServiceInfo {
+    const EXTENSION: &'static str = "service.ron";
+
+    fn from_str(
+        data: &str,
+        options: ron::Options,
+        _registry: &TypeRegistry,
+        dir: Option<&Path>,
+    ) -> SpannedResult<Self> {
+        let mut info: Self = options.from_str(data)?;
+        if let (Some(ambience), Some(dir)) = (&mut info.ambience, dir) {
+            asset::change_parent_dir(ambience, dir);
+        }
+
+        Ok(info)
+    }
+}
+
+/// Effect applied to visitors of a community lot.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum ServiceEffect {
+    /// Changes the need by the value every second.
+    Need { kind: NeedKind, rate: f32 },
+    /// Improves handiness by one level every specified number of seconds.
+    Handiness { interval: u32 },
+}
@@ -0,0 +1,334 @@
+use std::any::TypeId;
+
+use bevy::{
+    asset::{LoadState, RecursiveDependencyLoadState, UntypedAssetId},
+    gltf::{Gltf, GltfMesh},
+    prelude::*,
+    scene::ron,
+    utils::HashMap,
+};
+use serde::Serialize;
+
+use super::{
+    animation_info::AnimationInfo, names_info::NamesInfo, object_info::ObjectInfo,
+    preload::preload_finished, road_info::RoadInfo, scenario_info::ScenarioInfo,
+    service_info::ServiceInfo, InfoHandles,
+};
+use crate::combined_scene_collider::{self, SceneColliderConstructor};
+
+/// Checks all metadata files with their references, prints a report and exits.
+///
+/// Not a part of [`CorePlugins`](crate::CorePlugins), intended to be added only for validation.
+pub struct InfoValidationPlugin;
+
+impl Plugin for InfoValidationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReferencedAssets>().add_systems(
+            Update,
+            (Self::load_references, Self::validate)
+                .chain()
+                .run_if(preload_finished),
+        );
+    }
+}
+
+impl InfoValidationPlugin {
+    fn load_references(
+        asset_server: Res<AssetServer>,
+        mut references: ResMut<ReferencedAssets>,
+        objects_info: Res<Assets<ObjectInfo>>,
+        roads_info: Res<Assets<RoadInfo>>,
+        animations_info: Res<Assets<AnimationInfo>>,
+    ) {
+        for (id, info) in objects_info.iter() {
+            references.scenes.entry(id).or_insert_with(|| {
+                debug!("loading scene {:?} for validation", info.scene);
+                // Load the whole file to check its labels.
+                asset_server.load(info.scene.path().to_path_buf())
+            });
+        }
+        for (id, info) in roads_info.iter() {
+            references.materials.entry(id).or_insert_with(|| {
+                debug!("loading material {:?} for validation", info.material);
+                (
+                    asset_server.load(info.material.clone()),
+                    asset_server.load(info.preview.clone()),
+                )
+            });
+        }
+        for (id, info) in animations_info.iter() {
+            references.animations.entry(id).or_insert_with(|| {
+                debug!("loading clip {:?} for validation", info.clip);
+                asset_server.load(info.clip.path().to_path_buf())
+            });
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn validate(
+        mut exit_events: EventWriter<AppExit>,
+        asset_server: Res<AssetServer>,
+        references: Res<ReferencedAssets>,
+        names_handles: Res<InfoHandles<NamesInfo>>,
+        object_handles: Res<InfoHandles<ObjectInfo>>,
+        road_handles: Res<InfoHandles<RoadInfo>>,
+        animation_handles: Res<InfoHandles<AnimationInfo>>,
+        service_handles: Res<InfoHandles<ServiceInfo>>,
+        scenario_handles: Res<InfoHandles<ScenarioInfo>>,
+        objects_info: Res<Assets<ObjectInfo>>,
+        roads_info: Res<Assets<RoadInfo>>,
+        animations_info: Res<Assets<AnimationInfo>>,
+        gltfs: Res<Assets<Gltf>>,
+        gltf_meshes: Res<Assets<GltfMesh>>,
+        meshes: Res<Assets<Mesh>>,
+    ) {
+        let info_ids: Vec<UntypedAssetId> = names_handles
+            .0
+            .iter()
+            .map(|handle| handle.id().untyped())
+            .chain(object_handles.0.iter().map(|handle| handle.id().untyped()))
+            .chain(road_handles.0.iter().map(|handle| handle.id().untyped()))
+            .chain(
+                animation_handles
+                    .0
+                    .iter()
+                    .map(|handle| handle.id().untyped()),
+            )
+            .chain(service_handles.0.iter().map(|handle| handle.id().untyped()))
+            .chain(
+                scenario_handles
+                    .0
+                    .iter()
+                    .map(|handle| handle.id().untyped()),
+            )
+            .collect();
+        let reference_ids = references
+            .scenes
+            .values()
+            .map(|handle| handle.id().untyped())
+            .chain(
+                references
+                    .materials
+                    .values()
+                    .flat_map(|(material, preview)| {
+                        [material.id().untyped(), preview.id().untyped()]
+                    }),
+            )
+            .chain(
+                references
+                    .animations
+                    .values()
+                    .map(|handle| handle.id().untyped()),
+            );
+        if !info_ids
+            .iter()
+            .copied()
+            .chain(reference_ids)
+            .all(|id| is_finished(&asset_server, id))
+        {
+            return;
+        }
+        // References requested only for loaded infos.
+        if references.scenes.len() != objects_info.len()
+            || references.materials.len() != roads_info.len()
+            || references.animations.len() != animations_info.len()
+        {
+            return;
+        }
+
+        info!("validating {} metadata files", info_ids.len());
+        let mut report = ValidationReport::default();
+        for &id in &info_ids {
+            let mut errors = Vec::new();
+            if let Some(LoadState::Failed(error)) = asset_server.get_load_state(id) {
+                errors.push(error.to_string());
+            }
+
+            if let Some((info_id, info)) = id
+                .try_typed::<ObjectInfo>()
+                .ok()
+                .and_then(|info_id| objects_info.get(info_id).map(|info| (info_id, info)))
+            {
+                let gltf_handle = &references.scenes[&info_id];
+                errors.extend(check_object(
+                    info,
+                    gltf_handle,
+                    &asset_server,
+                    &gltfs,
+                    &gltf_meshes,
+                    &meshes,
+                ));
+            } else if let Some(info_id) = id
+                .try_typed::<RoadInfo>()
+                .ok()
+                .filter(|&info_id| roads_info.contains(info_id))
+            {
+                let (material, preview) = &references.materials[&info_id];
+                errors.extend(load_error(&asset_server, material.id().untyped()));
+                errors.extend(load_error(&asset_server, preview.id().untyped()));
+            } else if let Some((info_id, info)) = id
+                .try_typed::<AnimationInfo>()
+                .ok()
+                .and_then(|info_id| animations_info.get(info_id).map(|info| (info_id, info)))
+            {
+                let gltf_handle = &references.animations[&info_id];
+                errors.extend(check_animation(info, gltf_handle, &asset_server, &gltfs));
+            }
+
+            let path = asset_server
+                .get_path(id)
+                .map(|path| path.to_string())
+                .unwrap_or_default();
+            report.files.push(FileReport { path, errors });
+        }
+
+        let invalid = report.files.iter().any(|file| !file.errors.is_empty());
+        match ron::ser::to_string_pretty(&report, Default::default()) {
+            Ok(content) => println!("{content}"),
+            Err(e) => error!("unable to serialize validation report: {e}"),
+        }
+
+        if invalid {
+            error!("found invalid metadata files");
+            exit_events.send(AppExit::error());
+        } else {
+            info!("all metadata files are valid");
+            exit_events.send(AppExit::Success);
+        }
+    }
+}
+
+/// Returns `true` if the asset and all its dependencies finished loading, even if with an error.
+fn is_finished(asset_server: &AssetServer, id: UntypedAssetId) -> bool {
+    matches!(asset_server.get_load_state(id), Some(LoadState::Failed(_)))
+        || matches!(
+            asset_server.get_recursive_dependency_load_state(id),
+            Some(RecursiveDependencyLoadState::Loaded | RecursiveDependencyLoadState::Failed)
+        )
+}
+
+fn load_error(asset_server: &AssetServer, id: UntypedAssetId) -> Option<String> {
+    match asset_server.get_load_state(id) {
+        Some(LoadState::Failed(error)) => Some(error.to_string()),
+        _ => None,
+    }
+}
+
+/// Checks scene references and collider generation.
+fn check_object(
+    info: &ObjectInfo,
+    gltf_handle: &Handle<Gltf>,
+    asset_server: &AssetServer,
+    gltfs: &Assets<Gltf>,
+    gltf_meshes: &Assets<GltfMesh>,
+    meshes: &Assets<Mesh>,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    let Some(gltf) = gltfs.get(gltf_handle) else {
+        errors.extend(load_error(asset_server, gltf_handle.id().untyped()));
+        errors.push(format!("unable to load scene {:?}", info.scene.path()));
+        return errors;
+    };
+
+    match info.scene.label() {
+        Some(label) => {
+            if !contains_label(gltf, label) {
+                errors.push(format!("scene file doesn't contain `{label}`"));
+            }
+        }
+        None => errors.push("scene path should contain a label like `#Scene0`".to_string()),
+    }
+
+    if gltf.nodes.is_empty() {
+        errors.push("scene doesn't contain any nodes".to_string());
+    }
+
+    let constructor = info.components.iter().find_map(|component| {
+        let type_info = component.get_represented_type_info()?;
+        if type_info.type_id() != TypeId::of::<SceneColliderConstructor>() {
+            return None;
+        }
+        SceneColliderConstructor::from_reflect(&**component)
+    });
+    if let Some(constructor) = constructor {
+        let mut combined_mesh = combined_scene_collider::empty_mesh();
+        for primitive in gltf
+            .meshes
+            .iter()
+            .filter_map(|handle| gltf_meshes.get(handle))
+            .flat_map(|gltf_mesh| &gltf_mesh.primitives)
+        {
+            if let Some(mesh) = meshes.get(&primitive.mesh) {
+                combined_mesh.merge(mesh);
+            }
+        }
+
+        if constructor.construct(&combined_mesh).is_none() {
+            errors.push("unable to generate collider from scene meshes".to_string());
+        }
+    }
+
+    errors
+}
+
+/// Checks that the clip is present in its file.
+fn check_animation(
+    info: &AnimationInfo,
+    gltf_handle: &Handle<Gltf>,
+    asset_server: &AssetServer,
+    gltfs: &Assets<Gltf>,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    let Some(gltf) = gltfs.get(gltf_handle) else {
+        errors.extend(load_error(asset_server, gltf_handle.id().untyped()));
+        errors.push(format!("unable to load clip {:?}", info.clip.path()));
+        return errors;
+    };
+
+    match info.clip.label() {
+        Some(label) => {
+            if !label.starts_with("Animation") || !contains_label(gltf, label) {
+                errors.push(format!("clip file doesn't contain `{label}`"));
+            }
+        }
+        None => errors.push("clip path should contain a label like `#Animation0`".to_string()),
+    }
+
+    errors
+}
+
+/// Checks for `SceneN` and `AnimationN` labels.
+fn contains_label(gltf: &Gltf, label: &str) -> bool {
+    let index = |prefix| {
+        label
+            .strip_prefix(prefix)
+            .and_then(|index: &str| index.parse::<usize>().ok())
+    };
+
+    if let Some(index) = index("Scene") {
+        index < gltf.scenes.len()
+    } else if let Some(index) = index("Animation") {
+        index < gltf.animations.len()
+    } else {
+        false
+    }
+}
+
+/// Handles of assets referenced from metadata.
+#[derive(Default, Resource)]
+struct ReferencedAssets {
+    scenes: HashMap<AssetId<ObjectInfo>, Handle<Gltf>>,
+    materials: HashMap<AssetId<RoadInfo>, (Handle<StandardMaterial>, Handle<Image>)>,
+    animations: HashMap<AssetId<AnimationInfo>, Handle<Gltf>>,
+}
+
+#[derive(Default, Serialize)]
+struct ValidationReport {
+    files: Vec<FileReport>,
+}
+
+#[derive(Serialize)]
+struct FileReport {
+    path: String,
+    errors: Vec<String>,
+}
@@ -0,0 +1,243 @@
+use std::time::Duration;
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    prelude::*,
+    time::common_conditions::on_real_timer,
+    utils::HashMap,
+};
+
+use crate::game_world::city::ActiveCity;
+
+/// Keeps recently used scenes and images loaded for a while and unloads them afterwards.
+///
+/// Unused images are also unloaded earlier if loaded images exceed [`IMAGES_BUDGET`].
+/// Without it assets are either unloaded immediately after the last entity that uses them
+/// disappears, which causes reloading when the same object is placed again,
+/// or stay forever if something holds a handle.
+pub(super) struct LifetimePlugin;
+
+impl Plugin for LifetimePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RetainedAssets>()
+            .register_diagnostic(Diagnostic::new(LOADED_SCENES))
+            .register_diagnostic(Diagnostic::new(LOADED_IMAGES))
+            .register_diagnostic(Diagnostic::new(IMAGES_MEMORY).with_suffix(" MiB"))
+            .register_diagnostic(Diagnostic::new(LOADED_MESHES))
+            .register_diagnostic(Diagnostic::new(RETAINED_ASSETS))
+            .observe(Self::request_purge)
+            .add_systems(
+                Last,
+                (Self::track, Self::unload_unused, Self::measure)
+                    .chain()
+                    .run_if(on_real_timer(CHECK_INTERVAL)),
+            );
+    }
+}
+
+pub const LOADED_SCENES: DiagnosticPath = DiagnosticPath::const_new("assets/scenes");
+pub const LOADED_IMAGES: DiagnosticPath = DiagnosticPath::const_new("assets/images");
+pub const IMAGES_MEMORY: DiagnosticPath = DiagnosticPath::const_new("assets/images_memory");
+pub const LOADED_MESHES: DiagnosticPath = DiagnosticPath::const_new("assets/meshes");
+pub const RETAINED_ASSETS: DiagnosticPath = DiagnosticPath::const_new("assets/retained");
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long an asset stays loaded after the last use.
+const UNUSED_THRESHOLD: Duration = Duration::from_secs(120);
+
+/// Maximum memory of loaded images in bytes before unused images are unloaded
+/// without waiting for [`UNUSED_THRESHOLD`].
+const IMAGES_BUDGET: usize = 512 * 1024 * 1024;
+
+impl LifetimePlugin {
+    fn track(
+        time: Res<Time<Real>>,
+        mut retained: ResMut<RetainedAssets>,
+        scenes: Query<&Handle<Scene>>,
+        images: Query<&Handle<Image>>,
+    ) {
+        let now = time.elapsed();
+        for handle in &scenes {
+            retained.scenes.touch(handle, now);
+        }
+        for handle in &images {
+            retained.images.touch(handle, now);
+        }
+    }
+
+    fn unload_unused(
+        time: Res<Time<Real>>,
+        mut retained: ResMut<RetainedAssets>,
+        images: Res<Assets<Image>>,
+    ) {
+        let threshold = if retained.purge {
+            // Unload everything that wasn't used during the last check.
+            CHECK_INTERVAL
+        } else {
+            UNUSED_THRESHOLD
+        };
+        retained.purge = false;
+
+        let now = time.elapsed();
+        let scenes = retained.scenes.evict(now, threshold);
+        let mut images_count = retained.images.evict(now, threshold);
+        if scenes != 0 || images_count != 0 {
+            debug!("unloading {scenes} unused scenes and {images_count} unused images");
+        }
+
+        let excess = images_memory(&images).saturating_sub(IMAGES_BUDGET);
+        if excess != 0 {
+            images_count = retained.images.evict_least_recent(now, excess, |id| {
+                images.get(id).map_or(0, |image| image.data.len())
+            });
+            debug!("unloading {images_count} unused images to fit the memory budget");
+        }
+    }
+
+    fn measure(
+        mut diagnostics: Diagnostics,
+        retained: Res<RetainedAssets>,
+        scenes: Res<Assets<Scene>>,
+        images: Res<Assets<Image>>,
+        meshes: Res<Assets<Mesh>>,
+    ) {
+        diagnostics.add_measurement(&LOADED_SCENES, || scenes.len() as f64);
+        diagnostics.add_measurement(&LOADED_IMAGES, || images.len() as f64);
+        diagnostics.add_measurement(&IMAGES_MEMORY, || {
+            images_memory(&images) as f64 / (1024.0 * 1024.0)
+        });
+        diagnostics.add_measurement(&LOADED_MESHES, || meshes.len() as f64);
+        diagnostics.add_measurement(&RETAINED_ASSETS, || {
+            (retained.scenes.len() + retained.images.len()) as f64
+        });
+    }
+
+    /// Objects from the previous city are unlikely to be needed soon.
+    fn request_purge(
+        _trigger: Trigger<OnRemove, ActiveCity>,
+        mut retained: ResMut<RetainedAssets>,
+    ) {
+        debug!("requesting unloading of unused assets");
+        retained.purge = true;
+    }
+}
+
+/// Returns the size of all loaded images data in bytes.
+fn images_memory(images: &Assets<Image>) -> usize {
+    images.iter().map(|(_, image)| image.data.len()).sum()
+}
+
+#[derive(Resource, Default)]
+struct RetainedAssets {
+    scenes: Retained<Scene>,
+    images: Retained<Image>,
+
+    /// Unload unused assets on the next check ignoring [`UNUSED_THRESHOLD`].
+    purge: bool,
+}
+
+/// Strong handles with the time of their last use.
+struct Retained<A: Asset>(HashMap<AssetId<A>, (Handle<A>, Duration)>);
+
+impl<A: Asset> Retained<A> {
+    fn touch(&mut self, handle: &Handle<A>, now: Duration) {
+        self.0
+            .entry(handle.id())
+            .and_modify(|(_, last_use)| *last_use = now)
+            .or_insert_with(|| (handle.clone(), now));
+    }
+
+    /// Drops handles unused longer than `threshold` and returns their number.
+    fn evict(&mut self, now: Duration, threshold: Duration) -> usize {
+        let len = self.0.len();
+        self.0
+            .retain(|_, &mut (_, last_use)| now.saturating_sub(last_use) < threshold);
+        len - self.0.len()
+    }
+
+    /// Drops least recently used handles that weren't used at `now` until their total `size`
+    /// reaches `amount` and returns their number.
+    fn evict_least_recent(
+        &mut self,
+        now: Duration,
+        amount: usize,
+        size: impl Fn(AssetId<A>) -> usize,
+    ) -> usize {
+        let mut unused: Vec<_> = self
+            .0
+            .iter()
+            .filter(|(_, &(_, last_use))| last_use < now)
+            .map(|(&id, &(_, last_use))| (id, last_use))
+            .collect();
+        unused.sort_unstable_by_key(|&(_, last_use)| last_use);
+
+        let mut freed = 0;
+        let mut count = 0;
+        for (id, _) in unused {
+            if freed >= amount {
+                break;
+            }
+            freed += size(id);
+            count += 1;
+            self.0.remove(&id);
+        }
+
+        count
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<A: Asset> Default for Retained<A> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eviction() {
+        let mut retained = Retained::<Image>::default();
+        let first = Handle::<Image>::weak_from_u128(1);
+        let second = Handle::<Image>::weak_from_u128(2);
+
+        retained.touch(&first, Duration::ZERO);
+        retained.touch(&second, Duration::ZERO);
+        retained.touch(&first, Duration::from_secs(10));
+
+        assert_eq!(
+            retained.evict(Duration::from_secs(15), Duration::from_secs(10)),
+            1
+        );
+        assert_eq!(retained.len(), 1);
+        assert!(retained.0.contains_key(&first.id()));
+    }
+
+    #[test]
+    fn budget_eviction() {
+        let mut retained = Retained::<Image>::default();
+        let oldest = Handle::<Image>::weak_from_u128(1);
+        let older = Handle::<Image>::weak_from_u128(2);
+        let used = Handle::<Image>::weak_from_u128(3);
+
+        retained.touch(&oldest, Duration::ZERO);
+        retained.touch(&older, Duration::from_secs(1));
+        retained.touch(&used, Duration::from_secs(2));
+
+        let now = Duration::from_secs(2);
+        assert_eq!(retained.evict_least_recent(now, 0, |_| 10), 0);
+        assert_eq!(retained.evict_least_recent(now, 5, |_| 10), 1);
+        assert!(!retained.0.contains_key(&oldest.id()));
+
+        // Used handles are kept even above the budget.
+        assert_eq!(retained.evict_least_recent(now, 100, |_| 10), 1);
+        assert_eq!(retained.len(), 1);
+        assert!(retained.0.contains_key(&used.id()));
+    }
+}
@@ -0,0 +1,175 @@
+use std::{
+    env,
+    fs::{self, File},
+    io::BufWriter,
+    panic,
+    path::PathBuf,
+    sync::{Mutex, Once},
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use bevy::{
+    log::{tracing_subscriber, BoxedLayer},
+    prelude::*,
+};
+
+use super::{
+    game_paths::GamePaths,
+    game_world::WorldName,
+    message::{error_message, Message},
+    zip::ZipWriter,
+};
+
+/// Collects files useful for bug reports into a single archive.
+///
+/// Exports automatically on panic or on [`DiagnosticsExport`].
+pub(super) struct BugReportPlugin;
+
+impl Plugin for BugReportPlugin {
+    fn build(&self, app: &mut App) {
+        let game_paths = app.world().resource::<GamePaths>().clone();
+        *HOOK_STATE.lock().unwrap() = Some(HookState {
+            game_paths,
+            world_path: None,
+        });
+
+        // The hook reads the shared state, so it's installed only once even with multiple apps.
+        static HOOK: Once = Once::new();
+        HOOK.call_once(|| {
+            let default_hook = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                default_hook(info);
+                let Some(state) = HOOK_STATE.lock().ok().and_then(|state| state.clone()) else {
+                    return;
+                };
+                match export(&state.game_paths, state.world_path) {
+                    Ok(path) => eprintln!("diagnostics saved to {path:?}"),
+                    Err(e) => eprintln!("unable to export diagnostics: {e:#}"),
+                }
+            }));
+        });
+
+        app.add_event::<DiagnosticsExport>()
+            .add_systems(
+                Update,
+                Self::update_world_path.run_if(resource_exists_and_changed::<WorldName>),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::export
+                    .pipe(error_message)
+                    .run_if(on_event::<DiagnosticsExport>()),
+            );
+    }
+}
+
+impl BugReportPlugin {
+    fn update_world_path(game_paths: Res<GamePaths>, world_name: Res<WorldName>) {
+        if let Some(state) = HOOK_STATE.lock().unwrap().as_mut() {
+            state.world_path = Some(game_paths.world_path(&world_name.0));
+        }
+    }
+
+    fn export(
+        mut message_events: EventWriter<Message>,
+        game_paths: Res<GamePaths>,
+        world_name: Option<Res<WorldName>>,
+    ) -> Result<()> {
+        let world_path = world_name.map(|world_name| game_paths.world_path(&world_name.0));
+        let path = export(&game_paths, world_path)?;
+        message_events.send(Message(format!("Diagnostics saved to {path:?}")));
+
+        Ok(())
+    }
+}
+
+/// Creates an archive with logs, settings, the specified world and system info.
+///
+/// Returns path to the created archive.
+pub fn export(game_paths: &GamePaths, world_path: Option<PathBuf>) -> Result<PathBuf> {
+    fs::create_dir_all(&game_paths.diagnostics)
+        .with_context(|| format!("unable to create {:?}", game_paths.diagnostics))?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let path = game_paths
+        .diagnostics
+        .join(format!("diagnostics-{timestamp}.zip"));
+    info!("exporting diagnostics to {path:?}");
+
+    let file = File::create(&path).with_context(|| format!("unable to create {path:?}"))?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    zip.add("system.txt", system_info().as_bytes())?;
+
+    let mut sources = vec![
+        ("game.log", game_paths.log.clone()),
+        ("game.previous.log", game_paths.previous_log.clone()),
+        ("settings.ron", game_paths.settings.clone()),
+    ];
+    if let Some(world_path) = world_path {
+        sources.push(("world.scn", world_path));
+    }
+    for (name, source) in sources {
+        // Some files may not exist yet, skip them.
+        if let Ok(content) = fs::read(&source) {
+            zip.add(name, &content)
+                .with_context(|| format!("unable to add {source:?}"))?;
+        }
+    }
+
+    zip.finish()
+        .with_context(|| format!("unable to write {path:?}"))?;
+
+    Ok(path)
+}
+
+fn system_info() -> String {
+    let threads = thread::available_parallelism()
+        .map(|threads| threads.get())
+        .unwrap_or_default();
+    format!(
+        "version: {}\nos: {}\narch: {}\nthreads: {threads}\n",
+        env!("CARGO_PKG_VERSION"),
+        env::consts::OS,
+        env::consts::ARCH,
+    )
+}
+
+/// Writes logs into [`GamePaths::log`] to include them into diagnostics.
+///
+/// Should be passed to [`LogPlugin::custom_layer`](bevy::log::LogPlugin::custom_layer).
+///
+/// The log from the previous launch is kept in [`GamePaths::previous_log`] to report crashes after restart.
+pub fn log_layer(_app: &mut App) -> Option<BoxedLayer> {
+    let game_paths = GamePaths::default();
+    if game_paths.log.exists() {
+        if let Err(e) = fs::rename(&game_paths.log, &game_paths.previous_log) {
+            eprintln!("unable to keep the previous log: {e}");
+        }
+    }
+    let file = File::create(&game_paths.log).ok()?;
+    let layer = tracing_subscriber::fmt::layer()
+        .with_ansi(false)
+        .with_writer(Mutex::new(file));
+
+    Some(Box::new(layer))
+}
+
+/// Paths for the panic hook from the last built app.
+static HOOK_STATE: Mutex<Option<HookState>> = Mutex::new(None);
+
+#[derive(Clone)]
+struct HookState {
+    game_paths: GamePaths,
+
+    /// Path to the loaded world.
+    world_path: Option<PathBuf>,
+}
+
+/// Exports diagnostics and shows the path to the user.
+#[derive(Default, Event)]
+pub struct DiagnosticsExport;
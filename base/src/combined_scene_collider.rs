@@ -3,6 +3,7 @@ use bevy::{
     prelude::*,
     render::{mesh::Indices, render_resource::PrimitiveTopology},
     scene::{self, SceneInstanceReady},
+    utils::HashMap,
 };
 
 use crate::core::GameState;
@@ -11,12 +12,18 @@ pub(super) struct SceneColliderConstructorPlugin;
 
 impl Plugin for SceneColliderConstructorPlugin {
     fn build(&self, app: &mut App) {
-        app.register_type::<SceneColliderConstructor>().add_systems(
-            SpawnScene,
-            Self::init
-                .run_if(in_state(GameState::InGame))
-                .after(scene::scene_spawner_system),
-        );
+        app.register_type::<SceneColliderConstructor>()
+            .init_resource::<SceneColliders>()
+            .add_systems(
+                SpawnScene,
+                Self::init
+                    .run_if(in_state(GameState::InGame))
+                    .after(scene::scene_spawner_system),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::invalidate_cache.run_if(on_event::<AssetEvent<Scene>>()),
+            );
     }
 }
 
@@ -24,16 +31,22 @@ impl SceneColliderConstructorPlugin {
     fn init(
         mut commands: Commands,
         mut ready_events: EventReader<SceneInstanceReady>,
+        mut scene_colliders: ResMut<SceneColliders>,
         meshes: Res<Assets<Mesh>>,
-        scenes: Query<(Entity, &Children, &SceneColliderConstructor)>,
+        scenes: Query<(Entity, &Handle<Scene>, &Children, &SceneColliderConstructor)>,
         scene_meshes: Query<(&Transform, Option<&Handle<Mesh>>, Option<&Children>)>,
     ) {
-        for (scene_entity, children, constructor) in
+        for (scene_entity, scene_handle, children, &constructor) in
             scenes.iter_many(ready_events.read().map(|event| event.parent))
         {
-            let mut combined_mesh = Mesh::new(PrimitiveTopology::TriangleList, Default::default())
-                .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, Vec::<Vec3>::new())
-                .with_inserted_indices(Indices::U32(Vec::new()));
+            let key = (scene_handle.id(), constructor);
+            if let Some(collider) = scene_colliders.get(&key) {
+                debug!("inserting cached collider for `{scene_entity}`");
+                commands.entity(scene_entity).insert(collider.clone());
+                continue;
+            }
+
+            let mut combined_mesh = empty_mesh();
 
             for &child_entity in children {
                 recursive_merge(
@@ -45,29 +58,44 @@ impl SceneColliderConstructorPlugin {
                 );
             }
 
-            let collider = match constructor {
-                SceneColliderConstructor::Aabb => {
-                    let aabb = combined_mesh
-                        .compute_aabb()
-                        .expect("object mesh should be in compatible format");
-                    let center: Vec3 = aabb.center.into();
-                    let cuboid = Collider::cuboid(
-                        aabb.half_extents.x * 2.0,
-                        aabb.half_extents.y * 2.0,
-                        aabb.half_extents.z * 2.0,
-                    );
-                    Collider::compound(vec![(center, Rotation::default(), cuboid)])
-                }
-                SceneColliderConstructor::ConvexHull => {
-                    Collider::convex_hull_from_mesh(&combined_mesh)
-                        .expect("object mesh should be in compatible format")
-                }
-            };
+            let collider = constructor
+                .construct(&combined_mesh)
+                .expect("object mesh should be in compatible format");
 
             debug!("inserting collider for `{scene_entity}`");
+            scene_colliders.insert(key, collider.clone());
             commands.entity(scene_entity).insert(collider);
         }
     }
+
+    fn invalidate_cache(
+        mut asset_events: EventReader<AssetEvent<Scene>>,
+        mut scene_colliders: ResMut<SceneColliders>,
+    ) {
+        for event in asset_events.read() {
+            match *event {
+                AssetEvent::Modified { id }
+                | AssetEvent::Removed { id }
+                | AssetEvent::Unused { id } => {
+                    scene_colliders.retain(|&(scene_id, _), _| scene_id != id);
+                }
+                AssetEvent::Added { .. } | AssetEvent::LoadedWithDependencies { .. } => (),
+            }
+        }
+    }
+}
+
+/// Colliders computed for scenes.
+///
+/// Meshes of the same scene are merged only once, all instances share the collider.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct SceneColliders(HashMap<(AssetId<Scene>, SceneColliderConstructor), Collider>);
+
+/// Creates a mesh suitable for merging scene meshes into it.
+pub(super) fn empty_mesh() -> Mesh {
+    Mesh::new(PrimitiveTopology::TriangleList, Default::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, Vec::<Vec3>::new())
+        .with_inserted_indices(Indices::U32(Vec::new()))
 }
 
 fn recursive_merge(
@@ -104,9 +132,34 @@ fn recursive_merge(
     }
 }
 
-#[derive(Component, Reflect)]
+#[derive(Clone, Component, Copy, Eq, Hash, PartialEq, Reflect)]
 #[reflect(Component)]
 pub(super) enum SceneColliderConstructor {
     Aabb,
     ConvexHull,
 }
+
+impl SceneColliderConstructor {
+    /// Creates a collider from all meshes of a scene combined together.
+    ///
+    /// Returns [`None`] if the mesh is in an incompatible format.
+    pub(super) fn construct(&self, combined_mesh: &Mesh) -> Option<Collider> {
+        match self {
+            SceneColliderConstructor::Aabb => {
+                let aabb = combined_mesh.compute_aabb()?;
+                let center: Vec3 = aabb.center.into();
+                let cuboid = Collider::cuboid(
+                    aabb.half_extents.x * 2.0,
+                    aabb.half_extents.y * 2.0,
+                    aabb.half_extents.z * 2.0,
+                );
+                Some(Collider::compound(vec![(
+                    center,
+                    Rotation::default(),
+                    cuboid,
+                )]))
+            }
+            SceneColliderConstructor::ConvexHull => Collider::convex_hull_from_mesh(combined_mesh),
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use bevy::prelude::*;
+
+/// Text commands shared by all consoles.
+///
+/// Consoles send [`ConsoleInput`] with a line typed by the user
+/// and display [`ConsoleOutput`] with the same [`ConsoleSource`].
+/// Commands are registered via [`AppConsoleExt::add_console_command`].
+pub(super) struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConsoleCommands>()
+            .add_event::<ConsoleInput>()
+            .add_event::<ConsoleOutput>()
+            .add_systems(PostUpdate, Self::execute.run_if(on_event::<ConsoleInput>()));
+    }
+}
+
+impl ConsolePlugin {
+    fn execute(world: &mut World) {
+        let inputs: Vec<_> = world
+            .resource_mut::<Events<ConsoleInput>>()
+            .drain()
+            .collect();
+
+        world.resource_scope(|world, commands: Mut<ConsoleCommands>| {
+            for input in inputs {
+                info!("executing '{}' from `{:?}`", input.line, input.source);
+                let text = match commands.execute(world, &input.line) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        error!("unable to execute '{}': {e:#}", input.line);
+                        format!("error: {e:#}")
+                    }
+                };
+                world.send_event(ConsoleOutput {
+                    source: input.source,
+                    text,
+                });
+            }
+        });
+    }
+}
+
+/// Executes a command and returns its output.
+///
+/// Receives arguments split by whitespace without the command name.
+pub type CommandHandler = fn(&mut World, &[&str]) -> Result<String>;
+
+/// Registered console commands.
+#[derive(Default, Resource)]
+pub struct ConsoleCommands(BTreeMap<&'static str, ConsoleCommand>);
+
+impl ConsoleCommands {
+    /// Parses the line and runs the matching command.
+    ///
+    /// `help` is always available and lists all registered commands.
+    pub fn execute(&self, world: &mut World, line: &str) -> Result<String> {
+        let mut words = line.split_whitespace();
+        let Some(name) = words.next() else {
+            return Ok(String::new());
+        };
+        let args: Vec<_> = words.collect();
+
+        if name == "help" {
+            return Ok(self.help());
+        }
+
+        let Some(command) = self.0.get(name) else {
+            bail!("unknown command '{name}', type 'help' for the list of commands");
+        };
+
+        (command.handler)(world, &args)
+    }
+
+    fn help(&self) -> String {
+        let mut text = "help - list available commands".to_string();
+        for (name, command) in &self.0 {
+            text.push('\n');
+            text.push_str(name);
+            if !command.usage.is_empty() {
+                text.push(' ');
+                text.push_str(command.usage);
+            }
+            text.push_str(" - ");
+            text.push_str(command.description);
+        }
+        text
+    }
+}
+
+struct ConsoleCommand {
+    usage: &'static str,
+    description: &'static str,
+    handler: CommandHandler,
+}
+
+pub trait AppConsoleExt {
+    /// Registers a command available in all consoles.
+    ///
+    /// `usage` describes arguments, like `<client id>`, and can be empty.
+    fn add_console_command(
+        &mut self,
+        name: &'static str,
+        usage: &'static str,
+        description: &'static str,
+        handler: CommandHandler,
+    ) -> &mut Self;
+}
+
+impl AppConsoleExt for App {
+    fn add_console_command(
+        &mut self,
+        name: &'static str,
+        usage: &'static str,
+        description: &'static str,
+        handler: CommandHandler,
+    ) -> &mut Self {
+        let command = ConsoleCommand {
+            usage,
+            description,
+            handler,
+        };
+        let previous = self
+            .world_mut()
+            .get_resource_or_insert_with(ConsoleCommands::default)
+            .0
+            .insert(name, command);
+        assert!(
+            previous.is_none(),
+            "console command '{name}' should be registered only once"
+        );
+        self
+    }
+}
+
+/// A line typed in a console.
+#[derive(Event)]
+pub struct ConsoleInput {
+    pub source: ConsoleSource,
+    pub line: String,
+}
+
+/// Result of a [`ConsoleInput`] that should be displayed in the console it came from.
+#[derive(Event)]
+pub struct ConsoleOutput {
+    pub source: ConsoleSource,
+    pub text: String,
+}
+
+/// Console that sent a command.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConsoleSource {
+    /// Standard input of the dedicated server.
+    Stdin,
+    /// Remote connection with its index.
+    Rcon(u64),
+    /// In-game console panel.
+    Game,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execution() {
+        let mut commands = ConsoleCommands::default();
+        commands.0.insert(
+            "echo",
+            ConsoleCommand {
+                usage: "<text>",
+                description: "print text",
+                handler: |_, args| Ok(args.join(" ")),
+            },
+        );
+
+        let mut world = World::new();
+        assert_eq!(
+            commands.execute(&mut world, "  echo a   b ").unwrap(),
+            "a b"
+        );
+        assert!(commands.execute(&mut world, "").unwrap().is_empty());
+        assert!(commands
+            .execute(&mut world, "help")
+            .unwrap()
+            .contains("echo <text> - print text"));
+        assert!(commands.execute(&mut world, "unknown").is_err());
+    }
+}
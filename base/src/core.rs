@@ -1,15 +1,20 @@
+pub mod transition;
+
 use bevy::prelude::*;
 
+use transition::TransitionPlugin;
+
 pub(super) struct CorePlugin;
 
 impl Plugin for CorePlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<GameState>()
+        app.add_plugins(TransitionPlugin)
+            .init_state::<GameState>()
             .enable_state_scoped_entities::<GameState>();
     }
 }
 
-#[derive(States, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[derive(States, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
 pub enum GameState {
     #[default]
     Menu,
@@ -0,0 +1,264 @@
+use bevy::prelude::*;
+use strum::Display;
+
+use super::GameState;
+use crate::game_world::{commands_history::HistoryBuffer, UnsavedChanges, WorldState};
+
+/// Applies [`TransitionRequest`]s.
+///
+/// Transitions that leave the game are guarded and emit [`TransitionBlocked`] instead.
+/// Accepted transitions insert [`ActiveTransition`] and change the state
+/// only after all its steps are completed.
+pub(super) struct TransitionPlugin;
+
+impl Plugin for TransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TransitionRequest>()
+            .add_event::<TransitionBlocked>()
+            .add_systems(
+                PostUpdate,
+                (
+                    Self::start.run_if(on_event::<TransitionRequest>()),
+                    Self::finish.run_if(resource_exists::<ActiveTransition>),
+                )
+                    .chain()
+                    .in_set(TransitionSet),
+            );
+    }
+}
+
+impl TransitionPlugin {
+    fn start(
+        mut commands: Commands,
+        mut request_events: EventReader<TransitionRequest>,
+        mut blocked_events: EventWriter<TransitionBlocked>,
+        game_state: Res<State<GameState>>,
+        unsaved: Option<Res<UnsavedChanges>>,
+        history: Option<Res<HistoryBuffer>>,
+        active_transition: Option<Res<ActiveTransition>>,
+    ) {
+        for &request in request_events.read() {
+            if let Some(active_transition) = &active_transition {
+                warn!(
+                    "ignoring `{:?}` because `{:?}` is in progress",
+                    request.transition, active_transition.transition
+                );
+                continue;
+            }
+
+            if !request.force
+                && *game_state == GameState::InGame
+                && request.transition == Transition::Game(GameState::Menu)
+            {
+                let reason = if history
+                    .as_ref()
+                    .is_some_and(|history| history.has_unconfirmed())
+                {
+                    Some(BlockReason::PendingCommands)
+                } else if unsaved.as_ref().is_some_and(|unsaved| unsaved.0) {
+                    Some(BlockReason::UnsavedChanges)
+                } else {
+                    None
+                };
+
+                if let Some(reason) = reason {
+                    info!("blocking `{:?}`: {reason}", request.transition);
+                    blocked_events.send(TransitionBlocked {
+                        transition: request.transition,
+                        reason,
+                    });
+                    continue;
+                }
+            }
+
+            info!("starting `{:?}`", request.transition);
+            commands.insert_resource(ActiveTransition::new(request.transition));
+            return;
+        }
+    }
+
+    /// Applies the transition after all steps are completed.
+    ///
+    /// Waits at least one frame to let plugins register their steps.
+    fn finish(
+        mut commands: Commands,
+        mut game_state: ResMut<NextState<GameState>>,
+        mut world_state: ResMut<NextState<WorldState>>,
+        active_transition: Res<ActiveTransition>,
+    ) {
+        if active_transition.is_added() || !active_transition.steps.is_empty() {
+            return;
+        }
+
+        info!("finishing `{:?}`", active_transition.transition);
+        match active_transition.transition {
+            Transition::Game(state) => game_state.set(state),
+            Transition::World(state) => world_state.set(state),
+        }
+        commands.remove_resource::<ActiveTransition>();
+    }
+}
+
+/// Starts and finishes transitions in [`PostUpdate`].
+///
+/// Systems that add steps to a just started [`ActiveTransition`]
+/// in the same frame should run after this set.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub struct TransitionSet;
+
+/// Target state of a transition.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Transition {
+    Game(GameState),
+    World(WorldState),
+}
+
+/// Requests a state change.
+///
+/// Should be used instead of setting [`NextState`] directly.
+#[derive(Clone, Copy, Event)]
+pub struct TransitionRequest {
+    pub transition: Transition,
+    /// Skip guards, used after the player confirmed the transition.
+    pub force: bool,
+}
+
+impl TransitionRequest {
+    pub fn new(transition: Transition) -> Self {
+        Self {
+            transition,
+            force: false,
+        }
+    }
+
+    pub fn forced(transition: Transition) -> Self {
+        Self {
+            transition,
+            force: true,
+        }
+    }
+}
+
+/// Sent when a guard rejected [`TransitionRequest`].
+///
+/// UI should ask the player and send a forced request to proceed.
+#[derive(Event)]
+pub struct TransitionBlocked {
+    pub transition: Transition,
+    pub reason: BlockReason,
+}
+
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+pub enum BlockReason {
+    #[strum(serialize = "the world has unsaved changes")]
+    UnsavedChanges,
+    #[strum(serialize = "some changes are not yet confirmed by the server")]
+    PendingCommands,
+}
+
+/// Transition in progress.
+///
+/// Plugins that need asynchronous cleanup or setup add steps when this resource is added
+/// and complete them when the work is done.
+#[derive(Resource)]
+pub struct ActiveTransition {
+    transition: Transition,
+    steps: Vec<&'static str>,
+}
+
+impl ActiveTransition {
+    fn new(transition: Transition) -> Self {
+        Self {
+            transition,
+            steps: Default::default(),
+        }
+    }
+
+    pub fn transition(&self) -> Transition {
+        self.transition
+    }
+
+    /// Delays the transition until [`Self::complete_step`] is called with the same name.
+    pub fn add_step(&mut self, name: &'static str) {
+        debug!("adding step '{name}' to `{:?}`", self.transition);
+        self.steps.push(name);
+    }
+
+    pub fn complete_step(&mut self, name: &'static str) {
+        if let Some(index) = self.steps.iter().position(|&step| step == name) {
+            debug!("completing step '{name}' of `{:?}`", self.transition);
+            self.steps.swap_remove(index);
+        }
+    }
+
+    /// Returns the names of steps that are not yet completed.
+    pub fn pending_steps(&self) -> &[&'static str] {
+        &self.steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+
+    #[test]
+    fn steps() {
+        let mut app = App::new();
+        app.add_plugins((StatesPlugin, TransitionPlugin))
+            .init_state::<GameState>()
+            .add_sub_state::<WorldState>();
+
+        app.world_mut()
+            .send_event(TransitionRequest::new(Transition::Game(GameState::InGame)));
+        app.update();
+
+        app.world_mut()
+            .resource_mut::<ActiveTransition>()
+            .add_step("test");
+        app.update();
+        app.update();
+        assert_eq!(
+            **app.world().resource::<State<GameState>>(),
+            GameState::Menu
+        );
+
+        app.world_mut()
+            .resource_mut::<ActiveTransition>()
+            .complete_step("test");
+        app.update();
+        app.update();
+        assert_eq!(
+            **app.world().resource::<State<GameState>>(),
+            GameState::InGame
+        );
+        assert!(!app.world().contains_resource::<ActiveTransition>());
+    }
+
+    #[test]
+    fn unsaved_guard() {
+        let mut app = App::new();
+        app.add_plugins((StatesPlugin, TransitionPlugin))
+            .insert_state(GameState::InGame)
+            .add_sub_state::<WorldState>()
+            .insert_resource(UnsavedChanges(true));
+        app.update();
+
+        app.world_mut()
+            .send_event(TransitionRequest::new(Transition::Game(GameState::Menu)));
+        app.update();
+        assert!(!app.world().contains_resource::<ActiveTransition>());
+        let blocked = app.world().resource::<Events<TransitionBlocked>>();
+        assert_eq!(blocked.len(), 1);
+
+        app.world_mut()
+            .send_event(TransitionRequest::forced(Transition::Game(GameState::Menu)));
+        app.update();
+        app.update();
+        assert_eq!(
+            **app.world().resource::<State<GameState>>(),
+            GameState::Menu
+        );
+    }
+}
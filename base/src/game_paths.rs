@@ -17,12 +17,40 @@ impl Plugin for GamePathsPlugin {
 }
 
 const SCENE_EXTENSION: &str = "scn";
+const THUMBNAIL_EXTENSION: &str = "png";
 
 /// Paths with game files, such as settings and savegames.
-#[derive(Resource)]
+#[derive(Clone, Resource)]
 pub struct GamePaths {
     pub settings: PathBuf,
+    pub achievements: PathBuf,
     pub worlds: PathBuf,
+
+    /// Log of the current session.
+    pub log: PathBuf,
+
+    /// Log of the previous session.
+    pub previous_log: PathBuf,
+
+    /// Directory for exported diagnostic bundles.
+    pub diagnostics: PathBuf,
+
+    /// Directory for exported world archives.
+    pub archives: PathBuf,
+
+    /// Clients banned from servers hosted by the player.
+    pub bans: PathBuf,
+
+    /// Random ID of the player sent to servers.
+    pub identity: PathBuf,
+
+    /// Directory with pictures of family memories for each world.
+    pub snapshots: PathBuf,
+
+    /// Name of the recovery save created on the last crash.
+    ///
+    /// Removed after the player is notified.
+    pub crash_marker: PathBuf,
 }
 
 impl GamePaths {
@@ -32,6 +60,17 @@ impl GamePaths {
         path
     }
 
+    pub fn world_thumbnail_path(&self, name: &str) -> PathBuf {
+        let mut path = self.worlds.join(name);
+        path.set_extension(THUMBNAIL_EXTENSION);
+        path
+    }
+
+    /// Returns directory with memory snapshots of the world.
+    pub fn snapshots_dir(&self, world_name: &str) -> PathBuf {
+        self.snapshots.join(world_name)
+    }
+
     pub fn get_world_names(&self) -> Result<Vec<String>> {
         let entries = self
             .worlds
@@ -64,12 +103,34 @@ impl Default for GamePaths {
         settings.push(app_info.name);
         settings.set_extension("ron");
 
+        let achievements = config_dir.join("achievements.ron");
+        let log = config_dir.join("game.log");
+        let previous_log = config_dir.join("game.previous.log");
+        let diagnostics = config_dir.join("diagnostics");
+        let archives = config_dir.join("archives");
+        let bans = config_dir.join("bans.ron");
+        let identity = config_dir.join("identity");
+        let snapshots = config_dir.join("snapshots");
+        let crash_marker = config_dir.join("crash_marker");
+
         let mut worlds = config_dir;
         worlds.push("worlds");
         fs::create_dir_all(&worlds)
             .unwrap_or_else(|e| panic!("{worlds:?} should be writable: {e}"));
 
-        Self { settings, worlds }
+        Self {
+            settings,
+            achievements,
+            worlds,
+            log,
+            previous_log,
+            diagnostics,
+            archives,
+            bans,
+            identity,
+            snapshots,
+            crash_marker,
+        }
     }
 }
 
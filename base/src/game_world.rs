@@ -1,33 +1,76 @@
+//! Game world simulation and persistence.
+//!
+//! Extension points for external integrations are considered stable
+//! and changed only with a changelog entry:
+//! - [`GameSave`] and [`GameLoad`] to request saving and loading.
+//! - [`WorldSaved`] and [`save_hooks::SaveHook`] to react to completed saves.
+//! - [`WorldName`] with the name of the currently loaded world.
+
 pub mod actor;
+pub mod aging;
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod burglary;
 pub mod city;
+pub mod clock;
+pub mod collectable;
 pub mod commands_history;
+pub mod emergency_save;
 pub mod family;
 pub mod hover;
+pub mod inspection;
+pub mod mutation_log;
 pub mod navigation;
 pub mod object;
-mod player_camera;
-mod spline;
+pub mod player_camera;
+pub mod recovery;
+pub mod rng;
+pub mod save_hooks;
+pub mod scenario;
+pub mod showcase;
+pub mod simulation;
+pub(crate) mod spline;
 
-use std::fs;
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
 use anyhow::{Context, Result};
 use avian3d::prelude::*;
-use bevy::{
-    prelude::*,
-    scene::{ron, serde::SceneDeserializer},
-};
+use bevy::{prelude::*, scene::InstanceId};
 use bevy_replicon::prelude::*;
-use serde::de::DeserializeSeed;
 
-use super::{core::GameState, game_paths::GamePaths, message::error_message};
+use super::{
+    core::{
+        transition::{ActiveTransition, Transition, TransitionRequest, TransitionSet},
+        GameState,
+    },
+    game_paths::GamePaths,
+    message::error_message,
+    save_encryption::{self, SavePassphrase},
+};
 use actor::{Actor, ActorPlugin};
-use city::CityPlugin;
+use aging::AgingPlugin;
+use burglary::BurglaryPlugin;
+use city::{lot::permissions::LotPermissions, CityPlugin};
+use clock::{ClockPlugin, GameClock};
+use collectable::CollectablePlugin;
 use commands_history::CommandHistoryPlugin;
+use emergency_save::EmergencySavePlugin;
 use family::FamilyPlugin;
 use hover::HoverPlugin;
+use mutation_log::MutationLogPlugin;
 use navigation::NavigationPlugin;
 use object::ObjectPlugin;
 use player_camera::PlayerCameraPlugin;
+use recovery::{LoadRecovery, RecoveryPlugin};
+use rng::RngPlugin;
+use save_hooks::SaveHooksPlugin;
+use scenario::ScenarioPlugin;
+use showcase::ShowcasePlugin;
+use simulation::SimulationPlugin;
 use spline::SplinePlugin;
 
 pub(super) struct GameWorldPlugin;
@@ -37,18 +80,35 @@ impl Plugin for GameWorldPlugin {
         app.add_plugins((
             ActorPlugin,
             CityPlugin,
+            ClockPlugin,
+            CollectablePlugin,
             SplinePlugin,
             HoverPlugin,
             FamilyPlugin,
             NavigationPlugin,
             ObjectPlugin,
             PlayerCameraPlugin,
+            RecoveryPlugin,
+            RngPlugin,
             CommandHistoryPlugin,
+            ShowcasePlugin,
+            SimulationPlugin,
+        ))
+        .add_plugins((
+            AgingPlugin,
+            BurglaryPlugin,
+            EmergencySavePlugin,
+            MutationLogPlugin,
+            SaveHooksPlugin,
+            ScenarioPlugin,
         ))
         .add_sub_state::<WorldState>()
         .enable_state_scoped_entities::<WorldState>()
+        .init_resource::<UnsavedChanges>()
         .add_event::<GameSave>()
+        .add_event::<WorldSaved>()
         .add_event::<GameLoad>()
+        .add_event::<ExitRequest>()
         .add_systems(
             PreUpdate,
             Self::start_game
@@ -64,10 +124,22 @@ impl Plugin for GameWorldPlugin {
         )
         .add_systems(
             PostUpdate,
-            Self::save
-                .pipe(error_message)
-                .run_if(on_event::<GameSave>()),
+            (
+                (
+                    Self::delay_for_save,
+                    Self::save.pipe(error_message),
+                    Self::complete_save_step,
+                )
+                    .chain()
+                    .run_if(on_event::<GameSave>()),
+                Self::wait_for_scene.run_if(resource_exists::<LoadingScene>),
+                Self::wait_for_replication
+                    .run_if(client_connected)
+                    .run_if(resource_exists::<ActiveTransition>),
+            )
+                .after(TransitionSet),
         )
+        .add_systems(OnEnter(GameState::InGame), Self::reset_unsaved)
         .add_systems(OnExit(GameState::InGame), Self::cleanup);
     }
 }
@@ -75,78 +147,164 @@ impl Plugin for GameWorldPlugin {
 impl GameWorldPlugin {
     /// Saves world to disk with the name from [`WorldName`] resource.
     fn save(
+        mut commands: Commands,
         world: &World,
         world_name: Res<WorldName>,
         game_paths: Res<GamePaths>,
         registry: Res<AppTypeRegistry>,
+        passphrase: Option<Res<SavePassphrase>>,
         actors: Query<Entity, With<Actor>>,
     ) -> Result<()> {
         let world_path = game_paths.world_path(&world_name.0);
         info!("saving world to {world_path:?}");
+        let start = Instant::now();
 
         fs::create_dir_all(&game_paths.worlds)
             .with_context(|| format!("unable to create {world_path:?}"))?;
 
-        // Extract components that we don't replicate, but serialize.
-        let mut scene = DynamicSceneBuilder::from_world(world)
-            .deny_all()
-            .allow::<Transform>()
-            .extract_entities(actors.iter())
-            .build();
-
-        // Extract all replicated components that are reflected.
-        let registry = registry.read();
-        bevy_replicon::scene::replicate_into(&mut scene, world);
-        let bytes = scene
-            .serialize(&registry)
-            .expect("game world should be serialized");
-
-        fs::write(&world_path, bytes)
-            .with_context(|| format!("unable to save game to {world_path:?}"))
+        let bytes = serialize_world(world, actors.iter(), &registry, passphrase.as_deref())?;
+        fs::write(&world_path, &bytes)
+            .with_context(|| format!("unable to save game to {world_path:?}"))?;
+
+        commands.insert_resource(UnsavedChanges::default());
+
+        // Sent via command since the system has read access to the whole world.
+        let event = WorldSaved {
+            path: world_path,
+            duration: start.elapsed(),
+            size: bytes.len() as u64,
+        };
+        commands.add(move |world: &mut World| {
+            world.send_event(event);
+        });
+
+        Ok(())
     }
 
     /// Loads world from disk with the name from [`WorldName`] resource.
+    ///
+    /// If some entities can't be loaded, inserts [`LoadRecovery`] instead of entering the game.
     fn load(
+        mut commands: Commands,
         mut scene_spawner: ResMut<SceneSpawner>,
         mut scenes: ResMut<Assets<DynamicScene>>,
-        mut game_state: ResMut<NextState<GameState>>,
+        mut transition_events: EventWriter<TransitionRequest>,
+        asset_server: Res<AssetServer>,
         world_name: Res<WorldName>,
         game_paths: Res<GamePaths>,
         registry: Res<AppTypeRegistry>,
+        passphrase: Option<Res<SavePassphrase>>,
     ) -> Result<()> {
         let world_path = game_paths.world_path(&world_name.0);
         info!("loading world from {world_path:?}");
 
-        let bytes =
+        let mut bytes =
             fs::read(&world_path).with_context(|| format!("unable to load {world_path:?}"))?;
-        let mut deserializer = ron::Deserializer::from_bytes(&bytes)
-            .with_context(|| format!("unable to parse {world_path:?}"))?;
-        let scene_deserializer = SceneDeserializer {
-            type_registry: &registry.read(),
-        };
-        let mut scene = scene_deserializer
-            .deserialize(&mut deserializer)
-            .with_context(|| format!("unable to deserialize {world_path:?}"))?;
+        if save_encryption::is_encrypted(&bytes) {
+            let passphrase = passphrase
+                .with_context(|| format!("{world_path:?} is encrypted, passphrase is required"))?;
+            bytes = save_encryption::decrypt(&bytes, &passphrase.0)
+                .with_context(|| format!("unable to decrypt {world_path:?}"))?;
+        }
+        let (mut scene, issues) = recovery::deserialize_lenient(&bytes, &registry.read(), |path| {
+            asset_server.get_handle_untyped(path).is_some()
+        })
+        .with_context(|| format!("unable to parse {world_path:?}"))?;
 
         // All saved entities should have `Replicated` component.
         for entity in &mut scene.entities {
             entity.components.push(Replicated.clone_value());
         }
 
-        scene_spawner.spawn_dynamic(scenes.add(scene));
-        game_state.set(GameState::InGame);
+        if issues.is_empty() {
+            let instance_id = scene_spawner.spawn_dynamic(scenes.add(scene));
+            commands.insert_resource(LoadingScene(instance_id));
+            transition_events.send(TransitionRequest::new(Transition::Game(GameState::InGame)));
+        } else {
+            warn!(
+                "{} entities from {world_path:?} can't be loaded, entering recovery",
+                issues.len()
+            );
+            for issue in &issues {
+                warn!("`{}`: {}", issue.entity, issue.description);
+            }
+            commands.insert_resource(LoadRecovery::new(scene, issues));
+        }
 
         Ok(())
     }
 
-    fn start_game(mut commands: Commands, mut game_state: ResMut<NextState<GameState>>) {
+    fn start_game(mut commands: Commands, mut transition_events: EventWriter<TransitionRequest>) {
         info!("joining replicated world");
         commands.insert_resource(WorldName::default());
-        game_state.set(GameState::InGame);
+        transition_events.send(TransitionRequest::new(Transition::Game(GameState::InGame)));
+    }
+
+    /// Delays the active transition until the scene from [`Self::load`] is spawned.
+    fn wait_for_scene(
+        mut commands: Commands,
+        scene_spawner: Res<SceneSpawner>,
+        loading_scene: Res<LoadingScene>,
+        active_transition: Option<ResMut<ActiveTransition>>,
+    ) {
+        let Some(mut active_transition) = active_transition else {
+            return;
+        };
+
+        if active_transition.is_added()
+            && active_transition.transition() == Transition::Game(GameState::InGame)
+        {
+            active_transition.add_step(LOAD_STEP);
+        }
+
+        if scene_spawner.instance_is_ready(loading_scene.0) {
+            active_transition.complete_step(LOAD_STEP);
+            commands.remove_resource::<LoadingScene>();
+        }
+    }
+
+    /// Delays entering the game on clients until the initial world is received.
+    ///
+    /// [`GameClock`] is always spawned by the server, so its presence indicates that
+    /// the replicated world arrived.
+    fn wait_for_replication(
+        mut active_transition: ResMut<ActiveTransition>,
+        clocks: Query<(), With<GameClock>>,
+    ) {
+        if active_transition.is_added()
+            && active_transition.transition() == Transition::Game(GameState::InGame)
+        {
+            active_transition.add_step(CONNECT_STEP);
+        }
+
+        if !clocks.is_empty() {
+            active_transition.complete_step(CONNECT_STEP);
+        }
+    }
+
+    /// Delays the active transition until the world is saved.
+    ///
+    /// Used to save before exiting to the menu.
+    fn delay_for_save(active_transition: Option<ResMut<ActiveTransition>>) {
+        if let Some(mut active_transition) = active_transition {
+            active_transition.add_step(SAVE_STEP);
+        }
+    }
+
+    fn complete_save_step(active_transition: Option<ResMut<ActiveTransition>>) {
+        if let Some(mut active_transition) = active_transition {
+            active_transition.complete_step(SAVE_STEP);
+        }
+    }
+
+    fn reset_unsaved(mut unsaved: ResMut<UnsavedChanges>) {
+        unsaved.0 = false;
     }
 
     fn cleanup(mut commands: Commands) {
+        commands.remove_resource::<LoadingScene>();
         commands.remove_resource::<WorldName>();
+        commands.remove_resource::<SavePassphrase>();
     }
 }
 
@@ -154,16 +312,114 @@ impl GameWorldPlugin {
 #[derive(Default, Event)]
 pub struct GameSave;
 
+/// Event that indicates that the world was written to disk after [`GameSave`].
+///
+/// Not emitted if saving failed.
+/// Integrations can also register a [`save_hooks::SaveHook`] instead of reading events.
+#[derive(Clone, Debug, Event)]
+pub struct WorldSaved {
+    /// Path to the written save file.
+    pub path: PathBuf,
+
+    /// Time spent on serialization and writing.
+    pub duration: Duration,
+
+    /// Size of the written file in bytes.
+    pub size: u64,
+}
+
+/// Event that requests exiting the game.
+///
+/// Emitted instead of exiting immediately when there are [`UnsavedChanges`].
+/// UI should ask the player to save the world before exiting.
+#[derive(Default, Event)]
+pub struct ExitRequest;
+
 /// Event that indicates that game is about to be loaded from the file name based on [`WorldName`] resource.
 ///
 /// Sets game state to [`GameState::World`].
 #[derive(Default, Event)]
 pub struct GameLoad;
 
+/// Serializes the world into scene bytes, encrypting them if the passphrase is set.
+fn serialize_world(
+    world: &World,
+    actors: impl Iterator<Item = Entity>,
+    registry: &AppTypeRegistry,
+    passphrase: Option<&SavePassphrase>,
+) -> Result<Vec<u8>> {
+    let scene = extract_world(world, actors);
+    encode_world(
+        &scene,
+        registry,
+        passphrase.map(|passphrase| passphrase.0.as_str()),
+    )
+}
+
+/// Extracts all saved entities and components into a scene.
+fn extract_world(world: &World, actors: impl Iterator<Item = Entity>) -> DynamicScene {
+    // Extract components that we don't replicate, but serialize.
+    let lots = world
+        .iter_entities()
+        .filter(|entity| entity.contains::<LotPermissions>())
+        .map(|entity| entity.id());
+    let mut scene = DynamicSceneBuilder::from_world(world)
+        .deny_all()
+        .allow::<Transform>()
+        .extract_entities(actors)
+        .deny_all()
+        .allow::<LotPermissions>()
+        .extract_entities(lots)
+        .build();
+
+    // Extract all replicated components that are reflected.
+    bevy_replicon::scene::replicate_into(&mut scene, world);
+
+    scene
+}
+
+/// Serializes an extracted scene, encrypting it if the passphrase is set.
+///
+/// Doesn't need the world, so it can run outside of the main thread.
+fn encode_world(
+    scene: &DynamicScene,
+    registry: &AppTypeRegistry,
+    passphrase: Option<&str>,
+) -> Result<Vec<u8>> {
+    let registry = registry.read();
+    let mut bytes = scene
+        .serialize(&registry)
+        .expect("game world should be serialized")
+        .into_bytes();
+    if let Some(passphrase) = passphrase {
+        debug!("encrypting world");
+        bytes = save_encryption::encrypt(&bytes, passphrase)?;
+    }
+
+    Ok(bytes)
+}
+
+const LOAD_STEP: &str = "load";
+const CONNECT_STEP: &str = "connect";
+const SAVE_STEP: &str = "save";
+
+/// Scene instance spawned by [`GameWorldPlugin::load`].
+#[derive(Resource)]
+struct LoadingScene(InstanceId);
+
 /// Contains metadata of the currently loaded world.
 #[derive(Default, Resource)]
 pub struct WorldName(pub String);
 
+/// Indicates that the world was changed since the last save or load.
+///
+/// Set on mutation requests from clients recorded in [`mutation_log::MutationLog`]
+/// and when the clock advances in [`WorldState::Family`], since life simulation changes saved state.
+/// Replicated entities are constantly spawned and despawned by the simulation (tasks, for example),
+/// so tracking them would keep the world changed forever and also mark it right after loading.
+#[derive(Default, Resource)]
+pub struct UnsavedChanges(pub bool);
+
 #[derive(SubStates, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
 #[source(GameState = GameState::InGame)]
 pub enum WorldState {
@@ -172,6 +428,8 @@ pub enum WorldState {
     FamilyEditor,
     City,
     Family,
+    /// Observing the active city without controlling a family.
+    Spectator,
 }
 
 #[derive(PhysicsLayer)]
@@ -183,4 +441,5 @@ pub(super) enum Layer {
     PlacingWall,
     Road,
     PlacingRoad,
+    Water,
 }
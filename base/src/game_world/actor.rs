@@ -1,17 +1,24 @@
+pub mod actor_debug;
+pub mod age;
 mod animation_state;
-pub(super) mod human;
+pub mod genetics;
+pub(crate) mod human;
+pub mod job;
+pub mod name_generator;
 pub mod needs;
+pub mod outfit;
+pub mod privacy;
+pub mod relationships;
+pub mod skills;
 pub mod task;
 
 use avian3d::prelude::*;
 use bevy::{
-    asset::AssetPath,
     ecs::{entity::MapEntities, reflect::ReflectMapEntities},
     prelude::*,
 };
 use bevy_mod_outline::OutlineBundle;
 use bevy_replicon::prelude::*;
-use num_enum::IntoPrimitive;
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter};
 
@@ -19,43 +26,60 @@ use super::{
     hover::{highlighting::OutlineHighlightingExt, Hoverable},
     WorldState,
 };
-use crate::{
-    asset::collection::{AssetCollection, Collection},
-    core::GameState,
-};
+use crate::core::GameState;
+use actor_debug::ActorDebugPlugin;
+use age::AgePlugin;
 use animation_state::{AnimationState, AnimationStatePlugin};
+use genetics::GeneticsPlugin;
 use human::HumanPlugin;
+use job::JobPlugin;
 use needs::NeedsPlugin;
+use outfit::OutfitPlugin;
+use privacy::PrivacyPlugin;
+use relationships::RelationshipsPlugin;
+use skills::SkillsPlugin;
 use task::TaskPlugin;
 
 pub(super) struct ActorPlugin;
 
 impl Plugin for ActorPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<Collection<ActorAnimation>>()
-            .add_plugins((AnimationStatePlugin, NeedsPlugin, HumanPlugin, TaskPlugin))
-            .register_type::<Transform>()
-            .register_type::<Actor>()
-            .register_type::<FirstName>()
-            .register_type::<Sex>()
-            .register_type::<LastName>()
-            .register_type::<Movement>()
-            .replicate_mapped::<Actor>()
-            .replicate::<FirstName>()
-            .replicate::<Sex>()
-            .replicate::<LastName>()
-            .observe(Self::ensure_single_selection)
-            .add_systems(OnExit(WorldState::Family), Self::remove_selection)
-            .add_systems(
-                PreUpdate,
-                Self::init
-                    .after(ClientSet::Receive)
-                    .run_if(in_state(GameState::InGame)),
-            )
-            .add_systems(
-                PostUpdate,
-                Self::update_names.run_if(in_state(GameState::InGame)),
-            );
+        app.add_plugins((
+            ActorDebugPlugin,
+            AgePlugin,
+            AnimationStatePlugin,
+            GeneticsPlugin,
+            JobPlugin,
+            NeedsPlugin,
+            HumanPlugin,
+            OutfitPlugin,
+            PrivacyPlugin,
+            RelationshipsPlugin,
+            SkillsPlugin,
+            TaskPlugin,
+        ))
+        .register_type::<Transform>()
+        .register_type::<Actor>()
+        .register_type::<FirstName>()
+        .register_type::<Sex>()
+        .register_type::<LastName>()
+        .register_type::<Movement>()
+        .replicate_mapped::<Actor>()
+        .replicate::<FirstName>()
+        .replicate::<Sex>()
+        .replicate::<LastName>()
+        .observe(Self::ensure_single_selection)
+        .add_systems(OnExit(WorldState::Family), Self::remove_selection)
+        .add_systems(
+            PreUpdate,
+            Self::init
+                .after(ClientSet::Receive)
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            PostUpdate,
+            Self::update_names.run_if(in_state(GameState::InGame)),
+        );
     }
 }
 
@@ -167,47 +191,6 @@ pub trait ActorBundle: Reflect {
     fn glyph(&self) -> &'static str;
 }
 
-#[derive(Clone, Copy, Debug, EnumIter, IntoPrimitive)]
-#[repr(usize)]
-pub(super) enum ActorAnimation {
-    Idle,
-    MaleWalk,
-    FemaleWalk,
-    MaleRun,
-    FemaleRun,
-    TellSecret,
-    ThoughtfulNod,
-}
-
-impl AssetCollection for ActorAnimation {
-    type AssetType = AnimationClip;
-
-    fn asset_path(&self) -> AssetPath<'static> {
-        match self {
-            ActorAnimation::Idle => {
-                GltfAssetLabel::Animation(0).from_asset("base/actors/animations/idle.gltf")
-            }
-            ActorAnimation::MaleWalk => {
-                GltfAssetLabel::Animation(0).from_asset("base/actors/animations/male_walk.gltf")
-            }
-            ActorAnimation::FemaleWalk => {
-                GltfAssetLabel::Animation(0).from_asset("base/actors/animations/female_walk.gltf")
-            }
-            ActorAnimation::MaleRun => {
-                GltfAssetLabel::Animation(0).from_asset("base/actors/animations/male_run.gltf")
-            }
-            ActorAnimation::FemaleRun => {
-                GltfAssetLabel::Animation(0).from_asset("base/actors/animations/female_run.gltf")
-            }
-            ActorAnimation::TellSecret => {
-                GltfAssetLabel::Animation(0).from_asset("base/actors/animations/tell_secret.gltf")
-            }
-            ActorAnimation::ThoughtfulNod => GltfAssetLabel::Animation(0)
-                .from_asset("base/actors/animations/thoughtful_nod.gltf"),
-        }
-    }
-}
-
 /// Type of actor movement.
 #[derive(Clone, Copy, Default, Deserialize, Reflect, Serialize)]
 pub(super) enum Movement {
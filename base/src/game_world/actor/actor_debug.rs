@@ -0,0 +1,82 @@
+use bevy::{
+    color::palettes::css::{ORANGE, YELLOW},
+    prelude::*,
+};
+use leafwing_input_manager::common_conditions::action_just_pressed;
+
+use super::SelectedActor;
+use crate::{
+    common_conditions::in_any_state,
+    game_world::{
+        navigation::{NavDestination, NavPath},
+        WorldState,
+    },
+    settings::Action,
+};
+
+/// Draws navigation of actors marked with [`ActorDebug`].
+///
+/// Labels with needs and the current task are displayed by UI.
+pub(super) struct ActorDebugPlugin;
+
+impl Plugin for ActorDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                Self::toggle.run_if(action_just_pressed(Action::ToggleActorDebug)),
+                Self::draw,
+            )
+                .run_if(in_any_state([WorldState::City, WorldState::Family])),
+        );
+    }
+}
+
+/// Radius of the sphere at the navigation target.
+const TARGET_RADIUS: f32 = 0.2;
+
+impl ActorDebugPlugin {
+    fn toggle(
+        mut commands: Commands,
+        actors: Query<(Entity, Has<ActorDebug>), With<SelectedActor>>,
+    ) {
+        let Ok((entity, enabled)) = actors.get_single() else {
+            return;
+        };
+
+        if enabled {
+            info!("disabling debug overlay for `{entity}`");
+            commands.entity(entity).remove::<ActorDebug>();
+        } else {
+            info!("enabling debug overlay for `{entity}`");
+            commands.entity(entity).insert(ActorDebug);
+        }
+    }
+
+    fn draw(
+        mut gizmos: Gizmos,
+        actors: Query<(&Parent, &GlobalTransform, &NavPath, &NavDestination), With<ActorDebug>>,
+        cities: Query<&GlobalTransform>,
+    ) {
+        for (parent, actor_transform, path, dest) in &actors {
+            let city_transform = cities.get(**parent).unwrap();
+            gizmos.linestrip(
+                path.iter()
+                    .map(|&point| city_transform.transform_point(point)),
+                ORANGE,
+            );
+
+            if let Some(dest) = **dest {
+                let target = city_transform.transform_point(dest);
+                gizmos.line(actor_transform.translation(), target, YELLOW);
+                gizmos.sphere(target, Quat::IDENTITY, TARGET_RADIUS, YELLOW);
+            }
+        }
+    }
+}
+
+/// Enables debug overlay for an actor.
+///
+/// Local-only, toggled with [`Action::ToggleActorDebug`] for [`SelectedActor`].
+#[derive(Component)]
+pub struct ActorDebug;
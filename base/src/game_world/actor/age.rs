@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use super::Actor;
+use crate::{core::GameState, game_world::clock::GameClock};
+
+/// Ages actors with the in-game days.
+pub(super) struct AgePlugin;
+
+impl Plugin for AgePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Age>().replicate::<Age>().add_systems(
+            Update,
+            (Self::init, Self::advance)
+                .run_if(server_or_singleplayer)
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+impl AgePlugin {
+    /// Makes actors created without age adults.
+    fn init(mut commands: Commands, actors: Query<Entity, (With<Actor>, Without<Age>)>) {
+        for entity in &actors {
+            debug!("initializing age for `{entity}`");
+            commands.entity(entity).insert(Age::ADULT);
+        }
+    }
+
+    fn advance(
+        mut last_day: Local<Option<u32>>,
+        clocks: Query<&GameClock, Changed<GameClock>>,
+        mut actors: Query<(Entity, &mut Age)>,
+    ) {
+        let Ok(clock) = clocks.get_single() else {
+            return;
+        };
+
+        let day = clock.day();
+        let Some(previous_day) = last_day.replace(day) else {
+            // Don't age on world load.
+            return;
+        };
+
+        let passed = day.saturating_sub(previous_day);
+        if passed == 0 {
+            return;
+        }
+
+        for (entity, mut age) in &mut actors {
+            let previous_stage = age.stage();
+            age.days += passed;
+            let stage = age.stage();
+            if stage != previous_stage {
+                info!("`{entity}` grows up to {stage}");
+            }
+        }
+    }
+}
+
+/// Number of in-game days an actor has lived.
+#[derive(Clone, Component, Copy, Debug, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct Age {
+    pub days: u32,
+}
+
+impl Age {
+    pub const ADULT: Self = Self {
+        days: LifeStage::Adult.start(),
+    };
+
+    pub fn stage(self) -> LifeStage {
+        [
+            LifeStage::Elder,
+            LifeStage::Adult,
+            LifeStage::Teen,
+            LifeStage::Child,
+        ]
+        .into_iter()
+        .find(|stage| self.days >= stage.start())
+        .unwrap_or(LifeStage::Baby)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Display, PartialEq, PartialOrd, Serialize)]
+pub enum LifeStage {
+    Baby,
+    Child,
+    Teen,
+    Adult,
+    Elder,
+}
+
+impl LifeStage {
+    /// Returns the age in days at which the stage begins.
+    const fn start(self) -> u32 {
+        match self {
+            LifeStage::Baby => 0,
+            LifeStage::Child => 3,
+            LifeStage::Teen => 14,
+            LifeStage::Adult => 21,
+            LifeStage::Elder => 70,
+        }
+    }
+
+    /// Returns the body scale multiplier for the stage.
+    pub fn scale(self) -> f32 {
+        match self {
+            LifeStage::Baby => 0.3,
+            LifeStage::Child => 0.6,
+            LifeStage::Teen => 0.85,
+            LifeStage::Adult => 1.0,
+            LifeStage::Elder => 0.95,
+        }
+    }
+
+    /// Returns `true` if the actor can have children.
+    pub fn is_fertile(self) -> bool {
+        self == LifeStage::Adult
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages() {
+        assert_eq!(Age::default().stage(), LifeStage::Baby);
+        assert_eq!(Age { days: 3 }.stage(), LifeStage::Child);
+        assert_eq!(Age { days: 20 }.stage(), LifeStage::Teen);
+        assert_eq!(Age::ADULT.stage(), LifeStage::Adult);
+        assert_eq!(Age { days: 100 }.stage(), LifeStage::Elder);
+    }
+}
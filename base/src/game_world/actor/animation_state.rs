@@ -1,91 +1,224 @@
+use std::iter;
+
 use bevy::{
-    animation::RepeatAnimation,
+    animation::{Animation, AnimationTarget, AnimationTargetId, Keyframes, RepeatAnimation},
+    math::Vec3Swizzles,
     prelude::*,
     scene::{self, SceneInstanceReady},
-    utils::Duration,
+    transform::TransformSystem,
+    utils::{Duration, HashMap},
 };
+use rand::{seq::SliceRandom, Rng};
 use strum::EnumCount;
 
-use super::{ActorAnimation, Movement, Sex};
+use super::{
+    age::{Age, LifeStage},
+    needs::Need,
+    Movement, Sex,
+};
 use crate::{
-    asset::collection::Collection,
+    asset::info::animation_info::{AnimationInfo, IdleInfo, LoopMode, Mood},
     core::GameState,
-    game_world::navigation::{NavPath, NavSettings},
+    game_world::navigation::{NavPath, NavSettings, RootMotionDelta},
 };
 
 pub(super) struct AnimationStatePlugin;
 
 impl Plugin for AnimationStatePlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<MontageFinished>()
+        app.init_resource::<AnimationLibrary>()
+            .add_event::<MontageFinished>()
+            .add_systems(PreUpdate, Self::update_library)
             .add_systems(
                 SpawnScene,
                 Self::init_scene
                     .run_if(in_state(GameState::InGame))
                     .after(scene::scene_spawner_system),
             )
-            .add_systems(PostUpdate, Self::update.run_if(in_state(GameState::InGame)));
+            .add_systems(
+                PostUpdate,
+                (
+                    Self::update,
+                    Self::extract_root_motion
+                        .after(Animation)
+                        .before(TransformSystem::TransformPropagate),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
     }
 }
 
 impl AnimationStatePlugin {
+    fn update_library(
+        mut change_events: EventReader<AssetEvent<AnimationInfo>>,
+        asset_server: Res<AssetServer>,
+        animations_info: Res<Assets<AnimationInfo>>,
+        mut library: ResMut<AnimationLibrary>,
+    ) {
+        for &event in change_events.read() {
+            match event {
+                AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                    let info = animations_info
+                        .get(id)
+                        .expect("info should always come from file");
+
+                    // ID could be changed on modification.
+                    library.0.retain(|_, clip| clip.info_id != id);
+
+                    debug!("adding animation `{}` to library", info.id);
+                    let clip = LibraryClip {
+                        info_id: id,
+                        handle: asset_server.load(info.clip.clone()),
+                        loop_mode: info.loop_mode,
+                        root_motion: info.root_motion,
+                        mask: info.mask.clone(),
+                        idle: info.idle.clone(),
+                    };
+                    if library.0.insert(info.id.clone(), clip).is_some() {
+                        warn!("animation `{}` is defined more than once", info.id);
+                    }
+                }
+                AssetEvent::Removed { id } => {
+                    library.0.retain(|_, clip| clip.info_id != id);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Creates animation graphs for spawned actor scenes.
+    ///
+    /// Scenes are kept pending until the locomotion clips are loaded
+    /// because root motion is detected from their curves.
+    #[allow(clippy::too_many_arguments)]
     fn init_scene(
+        mut pending: Local<Vec<Entity>>,
         mut commands: Commands,
         mut ready_events: EventReader<SceneInstanceReady>,
         mut animation_graphs: ResMut<Assets<AnimationGraph>>,
-        actor_animations: Res<Collection<ActorAnimation>>,
-        mut actors: Query<(Entity, &mut AnimationState, &Sex)>,
+        library: Res<AnimationLibrary>,
+        clips: Res<Assets<AnimationClip>>,
+        mut actors: Query<(Entity, &mut AnimationState, &Sex, Option<&Age>)>,
         children: Query<&Children>,
         mut players: Query<(Entity, &mut AnimationPlayer)>,
+        targets: Query<(Entity, &AnimationTarget)>,
     ) {
-        for parent_entity in ready_events.read().map(|event| event.parent) {
-            let Ok((state_entity, mut state, sex)) = actors.get_mut(parent_entity) else {
-                continue;
+        pending.extend(ready_events.read().map(|event| event.parent));
+        pending.retain(|&parent_entity| {
+            let Ok((state_entity, mut state, sex, age)) = actors.get_mut(parent_entity) else {
+                return false;
             };
-
-            if let Some((player_entity, mut player)) = players
+            let Some((player_entity, mut player)) = players
                 .iter_many_mut(children.iter_descendants(state_entity))
                 .fetch_next()
+            else {
+                return false;
+            };
+
+            let (walk_id, run_id) = match sex {
+                Sex::Male => ("male_walk", "male_run"),
+                Sex::Female => ("female_walk", "female_run"),
+            };
+            let (Some(walk), Some(run), Some(idle_handle)) = (
+                library.clip(walk_id),
+                library.clip(run_id),
+                library.random_idle(Mood::Neutral, life_stage(age), false),
+            ) else {
+                trace!("waiting for locomotion animations for `{state_entity}`");
+                return true;
+            };
+            if [walk, run]
+                .iter()
+                .any(|clip| clip.root_motion && !clips.contains(&clip.handle))
             {
-                debug!("initializing player `{player_entity}` for state `{state_entity}`");
+                trace!("waiting for root motion clips for `{state_entity}`");
+                return true;
+            }
 
-                let mut graph = AnimationGraph::new();
-                let idle_handle = actor_animations.handle(ActorAnimation::Idle);
-                let walk_handle = match sex {
-                    Sex::Male => actor_animations.handle(ActorAnimation::MaleWalk),
-                    Sex::Female => actor_animations.handle(ActorAnimation::FemaleWalk),
-                };
-                let run_handle = match sex {
-                    Sex::Male => actor_animations.handle(ActorAnimation::MaleRun),
-                    Sex::Female => actor_animations.handle(ActorAnimation::FemaleRun),
-                };
+            debug!("initializing player `{player_entity}` for state `{state_entity}`");
+            let mut graph = AnimationGraph::new();
+            state.nodes[AnimationNode::Idle as usize] =
+                graph.add_clip(idle_handle, 1.0, graph.root);
+            state.nodes[AnimationNode::Fidget as usize] = graph.add_blend(1.0, graph.root);
+            state.nodes[AnimationNode::Walk as usize] =
+                graph.add_clip(walk.handle.clone(), 1.0, graph.root);
+            state.nodes[AnimationNode::Run as usize] =
+                graph.add_clip(run.handle.clone(), 1.0, graph.root);
+            state.nodes[AnimationNode::Montage as usize] = graph.add_blend(1.0, graph.root);
+            state.player_entity = Some(player_entity);
+            state.reset_fidget_timer();
+
+            let mut transitions = AnimationTransitions::new();
+            transitions.play(
+                &mut player,
+                state.nodes[AnimationNode::Idle as usize],
+                Duration::ZERO,
+            );
 
-                state.nodes[AnimationNode::Idle as usize] =
-                    graph.add_clip(idle_handle, 1.0, graph.root);
-                state.nodes[AnimationNode::Walk as usize] =
-                    graph.add_clip(walk_handle, 1.0, graph.root);
-                state.nodes[AnimationNode::Run as usize] =
-                    graph.add_clip(run_handle, 1.0, graph.root);
-                state.nodes[AnimationNode::Montage as usize] = graph.add_blend(1.0, graph.root);
-                state.player_entity = Some(player_entity);
-
-                let mut transitions = AnimationTransitions::new();
-                transitions.play(
-                    &mut player,
-                    state.nodes[AnimationNode::Idle as usize],
-                    Duration::ZERO,
-                );
-
-                commands
-                    .entity(player_entity)
-                    .insert((transitions, animation_graphs.add(graph)));
+            commands
+                .entity(player_entity)
+                .insert((transitions, animation_graphs.add(graph)));
+
+            let root_motion_clips: Vec<_> = [walk, run]
+                .into_iter()
+                .filter(|clip| clip.root_motion)
+                .filter_map(|clip| clips.get(&clip.handle))
+                .collect();
+            if root_motion_clips.is_empty() {
+                return false;
             }
-        }
+
+            // Descendants are iterated in breadth-first order, so the first match is the topmost bone.
+            let root_bone = targets
+                .iter_many(children.iter_descendants(player_entity))
+                .find(|(_, target)| {
+                    root_motion_clips
+                        .iter()
+                        .any(|clip| has_translation(clip, target.id))
+                });
+            let Some((bone_entity, _)) = root_bone else {
+                warn!("unable to find root bone for `{state_entity}`");
+                return false;
+            };
+
+            debug!("extracting root motion from `{bone_entity}` for `{state_entity}`");
+            let mut nodes = [false; AnimationNode::COUNT];
+            nodes[AnimationNode::Walk as usize] = walk.root_motion;
+            nodes[AnimationNode::Run as usize] = run.root_motion;
+            commands.entity(state_entity).insert((
+                RootMotion {
+                    nodes,
+                    bone_entity,
+                    anchor: Vec3::ZERO,
+                    previous: None,
+                    last_step: 0.0,
+                },
+                RootMotionDelta::default(),
+            ));
+
+            false
+        });
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn update(
+        mut masked_clips: Local<MaskedClips>,
         mut finish_events: EventWriter<MontageFinished>,
-        mut actors: Query<(Entity, &mut AnimationState, &NavSettings, Ref<NavPath>)>,
+        time: Res<Time>,
+        library: Res<AnimationLibrary>,
+        mut clips: ResMut<Assets<AnimationClip>>,
+        mut actors: Query<(
+            Entity,
+            &mut AnimationState,
+            &NavSettings,
+            Ref<NavPath>,
+            &Children,
+            Option<&Age>,
+        )>,
+        needs: Query<&Need>,
+        hierarchy: Query<&Children>,
+        bones: Query<(Entity, &Name), With<AnimationTarget>>,
+        targets: Query<&AnimationTarget>,
         mut players: Query<(
             &mut AnimationPlayer,
             &mut AnimationTransitions,
@@ -93,7 +226,7 @@ impl AnimationStatePlugin {
         )>,
         mut graphs: ResMut<Assets<AnimationGraph>>,
     ) {
-        for (actor_entity, mut state, navigation, path) in &mut actors {
+        for (actor_entity, mut state, navigation, path, children, age) in &mut actors {
             let Some(player_entity) = state.player_entity else {
                 continue;
             };
@@ -103,62 +236,283 @@ impl AnimationStatePlugin {
 
             match &state.montage_state {
                 MontageState::Stopped => trace!("no montage to play"),
+                MontageState::Skipped => {
+                    debug!("skipping missing montage");
+                    finish_events.send(MontageFinished(actor_entity));
+                    state.montage_state = MontageState::Stopped;
+                }
                 MontageState::Pending(montage) => {
+                    let layered = !montage.mask.is_empty();
+                    let clip_handle = if layered {
+                        let target_ids = bones
+                            .iter_many(hierarchy.iter_descendants(player_entity))
+                            .filter(|(_, name)| {
+                                montage.mask.iter().any(|bone| bone == name.as_str())
+                            })
+                            .flat_map(|(bone_entity, _)| {
+                                targets.iter_many(
+                                    iter::once(bone_entity)
+                                        .chain(hierarchy.iter_descendants(bone_entity)),
+                                )
+                            })
+                            .map(|target| target.id)
+                            .collect();
+                        let Some(handle) =
+                            masked_clips.get_or_create(&mut clips, &montage.handle, target_ids)
+                        else {
+                            trace!("waiting for montage clip to load");
+                            continue;
+                        };
+                        handle
+                    } else {
+                        montage.handle.clone()
+                    };
+                    let repeat = montage.repeat;
+                    let transition_time = montage.transition_time;
+                    let montage_time = match repeat {
+                        RepeatAnimation::Count(count) => clips
+                            .get(&montage.handle)
+                            .map(|clip| (0.0, clip.duration() * count as f32)),
+                        RepeatAnimation::Never => clips
+                            .get(&montage.handle)
+                            .map(|clip| (0.0, clip.duration())),
+                        RepeatAnimation::Forever => None,
+                    };
+
                     debug!("applying pending montage");
                     let graph = graphs
                         .get_mut(handle)
                         .expect("animation graph handle should be valid");
                     let index = state.nodes[AnimationNode::Montage as usize];
                     let node = graph.get_mut(index).expect("montage index should be valid");
-                    node.clip = Some(montage.handle.clone());
+                    node.clip = Some(clip_handle);
 
-                    transitions
-                        .play(&mut player, index, montage.transition_time)
-                        .set_repeat(montage.repeat);
-                    state.current_node = AnimationNode::Montage;
+                    if layered {
+                        // Played on top of the current state, which keeps driving bones outside the mask.
+                        node.weight = LAYERED_MONTAGE_WEIGHT;
+                        player.start(index).set_repeat(repeat);
+                    } else {
+                        node.weight = 1.0;
+                        transitions
+                            .play(&mut player, index, transition_time)
+                            .set_repeat(repeat);
+                        state.current_node = AnimationNode::Montage;
+                    }
+                    state.montage_time = montage_time;
+                    state.montage_layered = layered;
                     state.montage_state = MontageState::Playing;
-                    continue;
+                    if !layered {
+                        continue;
+                    }
                 }
                 MontageState::Playing => {
                     let index = state.nodes[AnimationNode::Montage as usize];
-                    if player.is_playing_animation(index) {
+                    if let Some(animation) = player.animation(index).filter(|animation| {
+                        player.is_playing_animation(index) && !animation.is_finished()
+                    }) {
                         trace!("playing montage");
-                        // Continue playing, nothing to update.
-                        continue;
+                        let elapsed = animation.elapsed();
+                        if let Some((montage_elapsed, _)) = &mut state.montage_time {
+                            *montage_elapsed = elapsed;
+                        }
+                        if !state.montage_layered {
+                            continue;
+                        }
+                    } else {
+                        debug!("montage finished");
+                        if state.montage_layered {
+                            player.stop(index);
+                        }
+                        finish_events.send(MontageFinished(actor_entity));
+                        state.montage_state = MontageState::Stopped;
+                        state.montage_time = None;
                     }
-
-                    debug!("montage finished");
-                    finish_events.send(MontageFinished(actor_entity));
-                    state.montage_state = MontageState::Stopped;
                 }
             }
 
-            let node = if path.is_empty() {
-                AnimationNode::Idle
-            } else if navigation.speed() <= Movement::Walk.speed() {
-                AnimationNode::Walk
+            let mood = current_mood(needs.iter_many(children));
+            let stage = life_stage(age);
+            let node = if !path.is_empty() {
+                if navigation.speed() <= Movement::Walk.speed() {
+                    AnimationNode::Walk
+                } else {
+                    AnimationNode::Run
+                }
             } else {
-                AnimationNode::Run
+                match state.current_node {
+                    AnimationNode::Idle => {
+                        state.fidget_timer.tick(time.delta());
+                        if state.fidget_timer.finished() {
+                            state.reset_fidget_timer();
+                            if let Some(fidget_handle) = library.random_idle(mood, stage, true) {
+                                let graph = graphs
+                                    .get_mut(handle)
+                                    .expect("animation graph handle should be valid");
+                                let index = state.nodes[AnimationNode::Fidget as usize];
+                                let node =
+                                    graph.get_mut(index).expect("fidget index should be valid");
+                                node.clip = Some(fidget_handle);
+                                AnimationNode::Fidget
+                            } else {
+                                AnimationNode::Idle
+                            }
+                        } else {
+                            AnimationNode::Idle
+                        }
+                    }
+                    AnimationNode::Fidget
+                        if player
+                            .is_playing_animation(state.nodes[AnimationNode::Fidget as usize]) =>
+                    {
+                        AnimationNode::Fidget
+                    }
+                    _ => AnimationNode::Idle,
+                }
             };
 
             if state.current_node != node {
                 debug!("switching current node to `{node:?}`");
                 let index = state.nodes[node as usize];
+                let repeat = if node == AnimationNode::Fidget {
+                    RepeatAnimation::Count(1)
+                } else {
+                    RepeatAnimation::Forever
+                };
+
+                if node == AnimationNode::Idle {
+                    // Safe to replace since the node isn't playing.
+                    if let Some(idle_handle) = library.random_idle(mood, stage, false) {
+                        trace!("choosing idle for `{mood:?}` mood");
+                        let graph = graphs
+                            .get_mut(handle)
+                            .expect("animation graph handle should be valid");
+                        let node = graph.get_mut(index).expect("idle index should be valid");
+                        node.clip = Some(idle_handle);
+                    }
+                    state.reset_fidget_timer();
+                }
+
                 transitions
                     .play(&mut player, index, DEFAULT_TRANSITION_TIME)
-                    .set_repeat(RepeatAnimation::Forever);
+                    .set_repeat(repeat);
 
                 state.current_node = node;
             }
         }
     }
+
+    /// Keeps the root bone in place and passes its movement to navigation.
+    ///
+    /// Runs after animations are applied, but before transform propagation.
+    fn extract_root_motion(
+        mut actors: Query<(
+            &GlobalTransform,
+            &AnimationState,
+            &mut RootMotion,
+            &mut RootMotionDelta,
+        )>,
+        parents: Query<&Parent>,
+        global_transforms: Query<&GlobalTransform>,
+        mut bones: Query<&mut Transform>,
+    ) {
+        for (actor_transform, state, mut root_motion, mut delta) in &mut actors {
+            if !root_motion.nodes[state.current_node as usize] {
+                if root_motion.previous.take().is_some() {
+                    trace!("stopping root motion extraction");
+                    delta.0 = None;
+                }
+                continue;
+            }
+
+            let Ok(mut bone_transform) = bones.get_mut(root_motion.bone_entity) else {
+                continue;
+            };
+            let parent = parents
+                .get(root_motion.bone_entity)
+                .expect("bones should have parents");
+            let parent_transform = global_transforms
+                .get(**parent)
+                .expect("bone parents should have transforms");
+
+            let to_actor = actor_transform.affine().inverse() * parent_transform.affine();
+            let position = to_actor.transform_point3(bone_transform.translation);
+            match root_motion.previous.replace(position) {
+                Some(previous) => {
+                    let mut step = (position - previous).xz().length();
+                    if step < MAX_ROOT_STEP {
+                        root_motion.last_step = step;
+                    } else {
+                        // The bone jumps back when the clip loops.
+                        step = root_motion.last_step;
+                    }
+                    *delta.0.get_or_insert(0.0) += step;
+                }
+                None => {
+                    root_motion.anchor = position;
+                    root_motion.last_step = 0.0;
+                    delta.0 = Some(0.0);
+                }
+            }
+
+            let pinned = Vec3::new(root_motion.anchor.x, position.y, root_motion.anchor.z);
+            bone_transform.translation = to_actor.inverse().transform_point3(pinned);
+        }
+    }
+}
+
+/// Returns the life stage for idle selection.
+///
+/// Actors get [`Age`] on the server, so clients could see them without it for a moment.
+fn life_stage(age: Option<&Age>) -> LifeStage {
+    age.map_or(LifeStage::Adult, |age| age.stage())
+}
+
+/// Calculates mood from the average satisfaction of needs.
+fn current_mood<'a>(needs: impl Iterator<Item = &'a Need>) -> Mood {
+    let (sum, count) = needs.fold((0.0, 0), |(sum, count), need| (sum + need.0, count + 1));
+    if count == 0 {
+        return Mood::Neutral;
+    }
+
+    match sum / count as f32 {
+        average if average >= GOOD_MOOD_NEEDS => Mood::Good,
+        average if average < BAD_MOOD_NEEDS => Mood::Bad,
+        _ => Mood::Neutral,
+    }
+}
+
+fn has_translation(clip: &AnimationClip, target_id: AnimationTargetId) -> bool {
+    clip.curves_for_target(target_id).is_some_and(|curves| {
+        curves
+            .iter()
+            .any(|curve| matches!(curve.keyframes, Keyframes::Translation(_)))
+    })
 }
 
 const DEFAULT_TRANSITION_TIME: Duration = Duration::from_millis(200);
 
+/// Weight of montages with a mask relative to the state animation.
+///
+/// Masked bones are blended with the state animation,
+/// so the weight is high to make the montage dominate.
+const LAYERED_MONTAGE_WEIGHT: f32 = 100.0;
+
+/// Maximum root bone movement per frame in meters.
+const MAX_ROOT_STEP: f32 = 0.5;
+
+/// Minimum average of needs for [`Mood::Good`].
+const GOOD_MOOD_NEEDS: f32 = 70.0;
+
+/// Average of needs below which the mood is [`Mood::Bad`].
+const BAD_MOOD_NEEDS: f32 = 30.0;
+
+/// Range in seconds between playing fidget idles.
+const FIDGET_INTERVAL: (f32, f32) = (8.0, 16.0);
+
 /// Manages actor animations based on the current state.
 ///
 /// State animations are driven by the actor's navigation speed.
+/// While standing, idle variants are picked based on mood with occasional fidgets.
 /// State animations can be temporarily overridden by a montage.
 #[derive(Component, Default)]
 pub(super) struct AnimationState {
@@ -166,6 +520,17 @@ pub(super) struct AnimationState {
     nodes: [AnimationNodeIndex; AnimationNode::COUNT],
     montage_state: MontageState,
     player_entity: Option<Entity>,
+
+    /// Whether the playing montage affects only masked bones on top of the current state.
+    montage_layered: bool,
+
+    /// Elapsed and total time of the playing montage in seconds.
+    ///
+    /// Available only for montages with a limited number of repeats.
+    montage_time: Option<(f32, f32)>,
+
+    /// Time until the next fidget while idling.
+    fidget_timer: Timer,
 }
 
 impl AnimationState {
@@ -173,8 +538,14 @@ impl AnimationState {
     ///
     /// Emits [`MontageFinished`] when the montage completes,
     /// then resumes the animation based on the current state.
-    pub(super) fn play_montage(&mut self, montage: Montage) {
-        self.montage_state = MontageState::Pending(montage);
+    /// Montages with a mask override only the masked bones.
+    ///
+    /// If the montage is missing, emits [`MontageFinished`] right away to let tasks continue.
+    pub(super) fn play_montage(&mut self, montage: Option<Montage>) {
+        self.montage_state = match montage {
+            Some(montage) => MontageState::Pending(montage),
+            None => MontageState::Skipped,
+        };
     }
 
     /// Stops the current montage, if any.
@@ -182,15 +553,150 @@ impl AnimationState {
     /// Resumes the animation based on the current state.
     pub(super) fn stop_montage(&mut self) {
         self.montage_state = MontageState::Stopped;
+        self.montage_time = None;
+    }
+
+    /// Returns elapsed and total time of the playing montage in seconds.
+    ///
+    /// Returns [`None`] if no montage is playing or it repeats forever.
+    pub(super) fn montage_time(&self) -> Option<(f32, f32)> {
+        self.montage_time
+    }
+
+    fn reset_fidget_timer(&mut self) {
+        let (min, max) = FIDGET_INTERVAL;
+        let secs = rand::thread_rng().gen_range(min..max);
+        self.fidget_timer = Timer::from_seconds(secs, TimerMode::Once);
+    }
+}
+
+/// Animations from [`AnimationInfo`] by their IDs.
+#[derive(Default, Resource)]
+pub(super) struct AnimationLibrary(HashMap<String, LibraryClip>);
+
+impl AnimationLibrary {
+    /// Creates a montage for the animation with the specified ID.
+    ///
+    /// Repeats according to the animation loop mode.
+    /// Returns [`None`] if there is no such animation.
+    pub(super) fn montage(&self, id: &str) -> Option<Montage> {
+        let Some(clip) = self.clip(id) else {
+            error!("animation `{id}` is not registered");
+            return None;
+        };
+
+        Some(Montage {
+            handle: clip.handle.clone(),
+            repeat: clip.loop_mode.into(),
+            transition_time: DEFAULT_TRANSITION_TIME,
+            mask: clip.mask.clone(),
+        })
+    }
+
+    /// Picks a random idle variant suitable for the mood and life stage according to weights.
+    fn random_idle(
+        &self,
+        mood: Mood,
+        stage: LifeStage,
+        fidget: bool,
+    ) -> Option<Handle<AnimationClip>> {
+        let variants: Vec<_> = self
+            .0
+            .values()
+            .filter_map(|clip| clip.idle.as_ref().map(|idle| (clip, idle)))
+            .filter(|(_, idle)| {
+                idle.fidget == fidget
+                    && idle.mood.map_or(true, |idle_mood| idle_mood == mood)
+                    && (idle.stages.is_empty() || idle.stages.contains(&stage))
+            })
+            .collect();
+
+        variants
+            .choose_weighted(&mut rand::thread_rng(), |(_, idle)| idle.weight)
+            .ok()
+            .map(|(clip, _)| clip.handle.clone())
+    }
+
+    fn clip(&self, id: &str) -> Option<&LibraryClip> {
+        self.0.get(id)
     }
 }
 
+struct LibraryClip {
+    info_id: AssetId<AnimationInfo>,
+    handle: Handle<AnimationClip>,
+    loop_mode: LoopMode,
+    root_motion: bool,
+    mask: Vec<String>,
+    idle: Option<IdleInfo>,
+}
+
+/// Copies of montage clips with curves only for masked bones.
+#[derive(Default)]
+struct MaskedClips(
+    HashMap<(AssetId<AnimationClip>, Vec<AnimationTargetId>), Handle<AnimationClip>>,
+);
+
+impl MaskedClips {
+    /// Returns the clip that animates only the specified targets.
+    ///
+    /// Returns [`None`] if the original clip is not loaded yet.
+    fn get_or_create(
+        &mut self,
+        clips: &mut Assets<AnimationClip>,
+        handle: &Handle<AnimationClip>,
+        target_ids: Vec<AnimationTargetId>,
+    ) -> Option<Handle<AnimationClip>> {
+        let key = (handle.id(), target_ids);
+        if let Some(masked_handle) = self.0.get(&key) {
+            return Some(masked_handle.clone());
+        }
+
+        let clip = clips.get(handle)?;
+        let mut masked = AnimationClip::default();
+        for &target_id in &key.1 {
+            for curve in clip.curves_for_target(target_id).into_iter().flatten() {
+                masked.add_curve_to_target(target_id, curve.clone());
+            }
+        }
+
+        let masked_handle = clips.add(masked);
+        self.0.insert(key, masked_handle.clone());
+        Some(masked_handle)
+    }
+}
+
+/// Tracks the root bone of clips with root motion.
+///
+/// Inserted together with [`RootMotionDelta`] only if any locomotion clip has root motion.
+#[derive(Component)]
+struct RootMotion {
+    /// Nodes which clips have root motion.
+    nodes: [bool; AnimationNode::COUNT],
+    bone_entity: Entity,
+
+    /// Position in actor space where the bone is kept.
+    ///
+    /// Taken from the first frame of the clip.
+    anchor: Vec3,
+
+    /// Unmodified bone position in actor space from the previous frame.
+    previous: Option<Vec3>,
+
+    /// Horizontal bone movement from the previous frame.
+    ///
+    /// Used instead of the jump when the clip loops.
+    last_step: f32,
+}
+
 #[derive(Default)]
 enum MontageState {
     #[default]
     Stopped,
     Pending(Montage),
     Playing,
+    /// Montage is missing, will be reported as finished.
+    Skipped,
 }
 
 #[derive(Event)]
@@ -198,17 +704,10 @@ pub(super) struct Montage {
     handle: Handle<AnimationClip>,
     repeat: RepeatAnimation,
     transition_time: Duration,
+    mask: Vec<String>,
 }
 
 impl Montage {
-    pub(super) fn new(handle: Handle<AnimationClip>) -> Self {
-        Self {
-            handle,
-            repeat: RepeatAnimation::Count(1),
-            transition_time: DEFAULT_TRANSITION_TIME,
-        }
-    }
-
     pub(super) fn with_repeat(mut self, repeat: RepeatAnimation) -> Self {
         self.repeat = repeat;
         self
@@ -222,6 +721,7 @@ pub(super) struct MontageFinished(pub(super) Entity);
 enum AnimationNode {
     #[default]
     Idle,
+    Fidget,
     Walk,
     Run,
     Montage,
@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{age::Age, Actor};
+use crate::{
+    core::GameState,
+    game_world::rng::{RngStream, WorldRng},
+};
+
+/// Initializes and applies inherited appearance parameters.
+pub(super) struct GeneticsPlugin;
+
+impl Plugin for GeneticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Genes>()
+            .replicate::<Genes>()
+            .add_systems(
+                Update,
+                (Self::init.run_if(server_or_singleplayer), Self::apply)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+impl GeneticsPlugin {
+    /// Assigns random genes to actors created without parents.
+    fn init(
+        mut commands: Commands,
+        mut world_rng: ResMut<WorldRng>,
+        actors: Query<Entity, (With<Actor>, Without<Genes>)>,
+    ) {
+        let rng = world_rng.stream(RngStream::Ai);
+        for entity in &actors {
+            debug!("initializing genes for `{entity}`");
+            commands.entity(entity).insert(Genes::random(rng));
+        }
+    }
+
+    /// Scales actors by their genes and life stage.
+    fn apply(
+        mut actors: Query<
+            (Entity, &Genes, Option<&Age>, &mut Transform),
+            Or<(Changed<Genes>, Changed<Age>)>,
+        >,
+    ) {
+        for (entity, genes, age, mut transform) in &mut actors {
+            debug!("applying genes for `{entity}`");
+            let age_scale = age.map(|age| age.stage().scale()).unwrap_or(1.0);
+            transform.scale = Vec3::new(genes.build, genes.height, genes.build) * age_scale;
+        }
+    }
+}
+
+/// Appearance parameters passed from parents to children.
+///
+/// Each value is a scale multiplier in [`Genes::MIN`]..=[`Genes::MAX`].
+#[derive(Clone, Component, Copy, Debug, Deserialize, PartialEq, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct Genes {
+    pub height: f32,
+    pub build: f32,
+}
+
+impl Genes {
+    pub const MIN: f32 = 0.9;
+    pub const MAX: f32 = 1.1;
+
+    /// Maximum random deviation from the inherited value.
+    const MUTATION: f32 = 0.02;
+
+    pub(crate) fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self {
+            height: rng.gen_range(Self::MIN..=Self::MAX),
+            build: rng.gen_range(Self::MIN..=Self::MAX),
+        }
+    }
+
+    /// Mixes genes of both parents with a small mutation.
+    pub(crate) fn inherit<R: Rng + ?Sized>(first: Self, second: Self, rng: &mut R) -> Self {
+        let mut mix = |a: f32, b: f32| {
+            let value =
+                a + (b - a) * rng.gen::<f32>() + rng.gen_range(-Self::MUTATION..=Self::MUTATION);
+            value.clamp(Self::MIN, Self::MAX)
+        };
+
+        Self {
+            height: mix(first.height, second.height),
+            build: mix(first.build, second.build),
+        }
+    }
+}
+
+impl Default for Genes {
+    fn default() -> Self {
+        Self {
+            height: 1.0,
+            build: 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn inheritance_in_range() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let first = Genes {
+            height: Genes::MIN,
+            build: Genes::MAX,
+        };
+        let second = Genes {
+            height: Genes::MIN,
+            build: Genes::MIN,
+        };
+
+        for _ in 0..100 {
+            let child = Genes::inherit(first, second, &mut rng);
+            assert!(child.height <= Genes::MIN + Genes::MUTATION);
+            assert!((Genes::MIN..=Genes::MAX).contains(&child.build));
+        }
+    }
+}
@@ -8,6 +8,7 @@ use strum::EnumIter;
 
 use super::{
     needs::{Bladder, Energy, Fun, Hunger, Hygiene, Need, NeedBundle, Social},
+    outfit::{CurrentOutfit, Outfits},
     Actor, ActorBundle, FirstName, LastName, ReflectActorBundle, Sex,
 };
 use crate::{
@@ -85,9 +86,9 @@ impl HumanPlugin {
     /// Fills [`FamilyScene`] with editing human actors.
     fn fill_scene(
         mut family_scene: ResMut<FamilyScene>,
-        mut actors: Query<(&mut FirstName, &mut LastName, &Sex), With<EditableActor>>,
+        mut actors: Query<(&mut FirstName, &mut LastName, &Sex, &Outfits), With<EditableActor>>,
     ) {
-        for (mut first_name, mut last_name, &sex) in &mut actors {
+        for (mut first_name, mut last_name, &sex, &outfits) in &mut actors {
             debug!(
                 "adding human '{} {}' to family scene '{}'",
                 first_name.0, last_name.0, family_scene.name
@@ -96,6 +97,7 @@ impl HumanPlugin {
                 mem::take(&mut first_name),
                 mem::take(&mut last_name),
                 sex,
+                outfits,
             )));
         }
     }
@@ -107,19 +109,23 @@ pub(crate) struct Human;
 
 #[derive(Bundle, Default, Reflect)]
 #[reflect(Bundle, ActorBundle)]
-struct HumanBundle {
+pub(crate) struct HumanBundle {
     first_name: FirstName,
     last_name: LastName,
     sex: Sex,
+    outfits: Outfits,
+    current_outfit: CurrentOutfit,
     human: Human,
 }
 
 impl HumanBundle {
-    fn new(first_name: FirstName, last_name: LastName, sex: Sex) -> Self {
+    fn new(first_name: FirstName, last_name: LastName, sex: Sex, outfits: Outfits) -> Self {
         Self {
             first_name,
             last_name,
             sex,
+            outfits,
+            current_outfit: Default::default(),
             human: Human,
         }
     }
@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    age::{Age, LifeStage},
+    Actor,
+};
+use crate::{
+    core::GameState,
+    game_world::{clock::GameClock, family::Budget},
+};
+
+/// Pays employed actors and promotes them with the in-game days.
+pub(super) struct JobPlugin;
+
+impl Plugin for JobPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Job>()
+            .replicate::<Job>()
+            .add_event::<Promotion>()
+            .add_systems(
+                Update,
+                Self::work
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+impl JobPlugin {
+    fn work(
+        mut commands: Commands,
+        mut last_day: Local<Option<u32>>,
+        mut promotion_events: EventWriter<Promotion>,
+        clocks: Query<&GameClock, Changed<GameClock>>,
+        mut actors: Query<(Entity, &Actor, &Age, &mut Job)>,
+        mut families: Query<&mut Budget>,
+    ) {
+        let Ok(clock) = clocks.get_single() else {
+            return;
+        };
+
+        let day = clock.day();
+        let Some(previous_day) = last_day.replace(day) else {
+            // Don't pay on world load.
+            return;
+        };
+
+        let passed = day.saturating_sub(previous_day);
+        if passed == 0 {
+            return;
+        }
+
+        for (entity, actor, age, mut job) in &mut actors {
+            if age.stage() == LifeStage::Elder {
+                info!("`{entity}` retires");
+                commands.entity(entity).remove::<Job>();
+                continue;
+            }
+
+            if let Ok(mut budget) = families.get_mut(actor.family_entity) {
+                budget.earn(job.salary() * passed);
+            }
+
+            if job.work(passed) {
+                info!("`{entity}` gets promoted to level {}", job.level);
+                promotion_events.send(Promotion {
+                    actor_entity: entity,
+                    level: job.level,
+                });
+            }
+        }
+    }
+}
+
+/// Employment of an actor.
+///
+/// Employed actors earn [`Job::salary`] for their family every day.
+#[derive(Clone, Component, Copy, Debug, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct Job {
+    pub level: u8,
+
+    /// Days worked since the last promotion.
+    days: u32,
+}
+
+impl Job {
+    pub const MAX_LEVEL: u8 = 5;
+
+    /// Number of days to work on a level before the promotion.
+    const PROMOTION_DAYS: u32 = 5;
+
+    const BASE_SALARY: u32 = 50;
+
+    /// Returns the daily salary for the current level.
+    pub fn salary(self) -> u32 {
+        Self::BASE_SALARY * (self.level as u32 + 1)
+    }
+
+    /// Adds worked days and promotes if enough days have passed.
+    ///
+    /// Returns `true` if promoted.
+    fn work(&mut self, days: u32) -> bool {
+        if self.level == Self::MAX_LEVEL {
+            return false;
+        }
+
+        self.days += days;
+        if self.days < Self::PROMOTION_DAYS {
+            return false;
+        }
+
+        self.days = 0;
+        self.level += 1;
+        true
+    }
+}
+
+/// Sent on the server when an actor gets a higher [`Job::level`].
+#[derive(Clone, Copy, Event)]
+pub(crate) struct Promotion {
+    pub(crate) actor_entity: Entity,
+    pub(crate) level: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn promotion() {
+        let mut job = Job::default();
+        assert!(!job.work(Job::PROMOTION_DAYS - 1));
+        assert_eq!(job.level, 0);
+        assert!(job.work(1));
+        assert_eq!(job.level, 1);
+        assert_eq!(job.salary(), Job::BASE_SALARY * 2);
+
+        job.level = Job::MAX_LEVEL;
+        assert!(!job.work(Job::PROMOTION_DAYS));
+        assert_eq!(job.level, Job::MAX_LEVEL);
+    }
+}
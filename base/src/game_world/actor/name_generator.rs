@@ -0,0 +1,100 @@
+use std::env;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use rand::{
+    seq::{IteratorRandom, SliceRandom},
+    Rng,
+};
+
+use super::{FirstName, LastName, Sex};
+use crate::{asset::info::names_info::NamesInfo, settings::Settings};
+
+/// Generates actor names from [`NamesInfo`] tables.
+#[derive(SystemParam)]
+pub struct NameGenerator<'w> {
+    names: Res<'w, Assets<NamesInfo>>,
+    settings: Res<'w, Settings>,
+}
+
+impl NameGenerator<'_> {
+    /// Returns all loaded locales.
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|(_, info)| info.locale.as_str())
+    }
+
+    /// Returns a random name for the given sex.
+    ///
+    /// If `locale` is `None`, uses the locale from settings or the system language.
+    /// If no table exists for the locale, picks a random table.
+    /// Returns `None` if no tables are loaded.
+    pub fn generate<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        sex: Sex,
+        locale: Option<&str>,
+    ) -> Option<(FirstName, LastName)> {
+        let preferred_locale = locale
+            .map(ToString::to_string)
+            .or_else(|| self.settings.gameplay.names_locale.clone())
+            .or_else(system_locale);
+        let info = self.table(rng, preferred_locale.as_deref())?;
+        let first_names = match sex {
+            Sex::Male => &info.male,
+            Sex::Female => &info.female,
+        };
+
+        let first_name = first_names.choose(rng)?;
+        let last_name = info.last.choose(rng)?;
+
+        Some((FirstName(first_name.clone()), LastName(last_name.clone())))
+    }
+
+    fn table<R: Rng + ?Sized>(&self, rng: &mut R, locale: Option<&str>) -> Option<&NamesInfo> {
+        let matching = self
+            .names
+            .iter()
+            .map(|(_, info)| info)
+            .filter(|info| Some(info.locale.as_str()) == locale)
+            .choose(rng);
+
+        matching.or_else(|| {
+            if let Some(locale) = locale {
+                debug!("no name tables for locale '{locale}', using any");
+            }
+            self.names.iter().map(|(_, info)| info).choose(rng)
+        })
+    }
+}
+
+/// Returns language code from the environment, like `en` for `en_US.UTF-8`.
+fn system_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| parse_locale(&value))
+}
+
+fn parse_locale(value: &str) -> Option<String> {
+    let language = value.split(['_', '.', '@', '-']).next()?;
+    if language.is_empty() || language == "C" || language == "POSIX" {
+        return None;
+    }
+
+    Some(language.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locale_parsing() {
+        assert_eq!(parse_locale("de_DE.UTF-8").as_deref(), Some("de"));
+        assert_eq!(parse_locale("en").as_deref(), Some("en"));
+        assert_eq!(parse_locale("pt-BR").as_deref(), Some("pt"));
+        assert_eq!(parse_locale("C.UTF-8"), None);
+        assert_eq!(parse_locale("POSIX"), None);
+        assert_eq!(parse_locale(""), None);
+    }
+}
@@ -38,7 +38,7 @@ impl Plugin for NeedsPlugin {
                     .run_if(in_state(GameState::InGame)),
             )
             .add_systems(
-                Update,
+                FixedUpdate,
                 Self::update_values
                     .run_if(on_timer(Duration::from_secs(1)))
                     .run_if(server_or_singleplayer),
@@ -55,7 +55,7 @@ impl NeedsPlugin {
             trace!("initializing hunger need for `{entity}`");
             commands
                 .entity(entity)
-                .insert((NeedGlyph("🍴"), NeedRate(-0.4)));
+                .insert((NeedKind::Hunger, NeedGlyph("🍴"), NeedRate(-0.4)));
         }
     }
 
@@ -67,7 +67,7 @@ impl NeedsPlugin {
             trace!("initializing social need for `{entity}`");
             commands
                 .entity(entity)
-                .insert((NeedGlyph("💬"), NeedRate(-0.1)));
+                .insert((NeedKind::Social, NeedGlyph("💬"), NeedRate(-0.1)));
         }
     }
 
@@ -79,7 +79,7 @@ impl NeedsPlugin {
             trace!("initializing hygiene need for `{entity}`");
             commands
                 .entity(entity)
-                .insert((NeedGlyph("🚿"), NeedRate(-0.3)));
+                .insert((NeedKind::Hygiene, NeedGlyph("🚿"), NeedRate(-0.3)));
         }
     }
 
@@ -88,7 +88,7 @@ impl NeedsPlugin {
             trace!("initializing fun need for `{entity}`");
             commands
                 .entity(entity)
-                .insert((NeedGlyph("🎉"), NeedRate(-0.1)));
+                .insert((NeedKind::Fun, NeedGlyph("🎉"), NeedRate(-0.1)));
         }
     }
 
@@ -100,7 +100,7 @@ impl NeedsPlugin {
             trace!("initializing energy need for `{entity}`");
             commands
                 .entity(entity)
-                .insert((NeedGlyph("🔋"), NeedRate(-0.2)));
+                .insert((NeedKind::Energy, NeedGlyph("🔋"), NeedRate(-0.2)));
         }
     }
 
@@ -112,7 +112,7 @@ impl NeedsPlugin {
             trace!("initializing bladder need for `{entity}`");
             commands
                 .entity(entity)
-                .insert((NeedGlyph("🚽"), NeedRate(-0.5)));
+                .insert((NeedKind::Bladder, NeedGlyph("🚽"), NeedRate(-0.5)));
         }
     }
 
@@ -180,6 +180,26 @@ impl Default for Need {
     }
 }
 
+impl Need {
+    /// Changes the value keeping it in the valid range.
+    pub(crate) fn add(&mut self, value: f32) {
+        self.0 = (self.0 + value).clamp(0.0, 100.0);
+    }
+}
+
+/// Kind of the need entity, inserted together with [`NeedGlyph`].
+///
+/// Allows to refer to needs from metadata.
+#[derive(Clone, Component, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum NeedKind {
+    Hunger,
+    Social,
+    Hygiene,
+    Fun,
+    Energy,
+    Bladder,
+}
+
 #[derive(Component)]
 struct NeedRate(f32);
 
@@ -0,0 +1,231 @@
+use bevy::{
+    color::palettes::css::{BEIGE, BLACK, DARK_RED, NAVY, OLIVE, SILVER, TEAL, WHITE},
+    prelude::*,
+    scene::{self, SceneInstanceReady},
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
+
+use super::{
+    task::{TaskOutfit, TaskState},
+    Actor,
+};
+use crate::core::GameState;
+
+pub(super) struct OutfitPlugin;
+
+impl Plugin for OutfitPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Outfits>()
+            .register_type::<CurrentOutfit>()
+            .replicate::<Outfits>()
+            .replicate::<CurrentOutfit>()
+            .add_systems(
+                SpawnScene,
+                Self::init_scene
+                    .run_if(in_state(GameState::InGame))
+                    .after(scene::scene_spawner_system),
+            )
+            .add_systems(
+                Update,
+                Self::update_materials.run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::switch
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+impl OutfitPlugin {
+    fn init_scene(
+        mut materials: ResMut<Assets<StandardMaterial>>,
+        mut ready_events: EventReader<SceneInstanceReady>,
+        actors: Query<(&Outfits, &CurrentOutfit)>,
+        children: Query<&Children>,
+        mut material_handles: Query<(&Name, &mut Handle<StandardMaterial>)>,
+    ) {
+        for parent_entity in ready_events.read().map(|event| event.parent) {
+            if let Ok((outfits, current_outfit)) = actors.get(parent_entity) {
+                debug!(
+                    "applying outfit `{:?}` for `{parent_entity}`",
+                    current_outfit.0
+                );
+                let color = outfits.get(current_outfit.0).color();
+                apply_color(
+                    &mut materials,
+                    &children,
+                    &mut material_handles,
+                    parent_entity,
+                    color,
+                );
+            }
+        }
+    }
+
+    fn update_materials(
+        mut materials: ResMut<Assets<StandardMaterial>>,
+        actors: Query<
+            (Entity, &Outfits, &CurrentOutfit),
+            Or<(Changed<Outfits>, Changed<CurrentOutfit>)>,
+        >,
+        children: Query<&Children>,
+        mut material_handles: Query<(&Name, &mut Handle<StandardMaterial>)>,
+    ) {
+        for (entity, outfits, current_outfit) in &actors {
+            debug!("changing outfit to `{:?}` for `{entity}`", current_outfit.0);
+            let color = outfits.get(current_outfit.0).color();
+            apply_color(
+                &mut materials,
+                &children,
+                &mut material_handles,
+                entity,
+                color,
+            );
+        }
+    }
+
+    /// Switches outfit to the one requested by the active task or to [`OutfitCategory::Everyday`].
+    fn switch(
+        mut actors: Query<(&mut CurrentOutfit, &Children), With<Actor>>,
+        tasks: Query<(&TaskOutfit, &TaskState)>,
+    ) {
+        for (mut current_outfit, children) in &mut actors {
+            let category = tasks
+                .iter_many(children)
+                .filter(|(_, &task_state)| task_state == TaskState::Active)
+                .find_map(|(task_outfit, _)| task_outfit.0)
+                .unwrap_or_default();
+
+            current_outfit.set_if_neq(CurrentOutfit(category));
+        }
+    }
+}
+
+/// Tints body surface materials of the actor scene.
+///
+/// Used until we have separate clothing meshes.
+/// Other meshes, such as joints, keep their colors to distinguish outfit from the skin.
+fn apply_color(
+    materials: &mut Assets<StandardMaterial>,
+    children: &Query<&Children>,
+    material_handles: &mut Query<(&Name, &mut Handle<StandardMaterial>)>,
+    entity: Entity,
+    color: Color,
+) {
+    let mut iter = material_handles.iter_many_mut(children.iter_descendants(entity));
+    while let Some((name, mut material_handle)) = iter.fetch_next() {
+        if !is_outfit_mesh(name) {
+            continue;
+        }
+
+        let material = materials
+            .get(&*material_handle)
+            .expect("material handle should be valid");
+
+        if material.base_color == color {
+            continue;
+        }
+
+        let mut material = material.clone();
+        material.base_color = color;
+        *material_handle = materials.add(material);
+    }
+}
+
+/// Returns `true` if the mesh represents clothes.
+///
+/// Bot models have a single surface mesh for the body.
+fn is_outfit_mesh(name: &Name) -> bool {
+    name.ends_with("_Surface")
+}
+
+/// Outfit color for each situation.
+#[derive(Clone, Component, Copy, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct Outfits {
+    pub everyday: OutfitColor,
+    pub formal: OutfitColor,
+    pub sleep: OutfitColor,
+    pub swim: OutfitColor,
+}
+
+impl Outfits {
+    pub fn get(&self, category: OutfitCategory) -> OutfitColor {
+        match category {
+            OutfitCategory::Everyday => self.everyday,
+            OutfitCategory::Formal => self.formal,
+            OutfitCategory::Sleep => self.sleep,
+            OutfitCategory::Swim => self.swim,
+        }
+    }
+
+    pub fn set(&mut self, category: OutfitCategory, color: OutfitColor) {
+        match category {
+            OutfitCategory::Everyday => self.everyday = color,
+            OutfitCategory::Formal => self.formal = color,
+            OutfitCategory::Sleep => self.sleep = color,
+            OutfitCategory::Swim => self.swim = color,
+        }
+    }
+}
+
+impl Default for Outfits {
+    fn default() -> Self {
+        Self {
+            everyday: OutfitColor::Olive,
+            formal: OutfitColor::Black,
+            sleep: OutfitColor::Navy,
+            swim: OutfitColor::Teal,
+        }
+    }
+}
+
+/// Situation for which an outfit is worn.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Display, EnumIter, PartialEq, Reflect, Serialize,
+)]
+pub enum OutfitCategory {
+    #[default]
+    Everyday,
+    Formal,
+    Sleep,
+    Swim,
+}
+
+#[derive(Clone, Copy, Deserialize, Display, EnumIter, PartialEq, Reflect, Serialize)]
+pub enum OutfitColor {
+    White,
+    Silver,
+    Beige,
+    Olive,
+    Teal,
+    Navy,
+    Red,
+    Black,
+}
+
+impl OutfitColor {
+    pub fn color(self) -> Color {
+        match self {
+            OutfitColor::White => WHITE.into(),
+            OutfitColor::Silver => SILVER.into(),
+            OutfitColor::Beige => BEIGE.into(),
+            OutfitColor::Olive => OLIVE.into(),
+            OutfitColor::Teal => TEAL.into(),
+            OutfitColor::Navy => NAVY.into(),
+            OutfitColor::Red => DARK_RED.into(),
+            OutfitColor::Black => BLACK.into(),
+        }
+    }
+}
+
+/// Outfit category that the actor currently wears.
+///
+/// Switched automatically on the server based on active tasks.
+#[derive(Clone, Component, Copy, Default, Deserialize, PartialEq, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct CurrentOutfit(pub OutfitCategory);
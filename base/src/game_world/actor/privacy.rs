@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    needs::{Fun, Need},
+    task::{TaskPrivacy, TaskState},
+    Actor,
+};
+use crate::{core::GameState, game_world::family::building::room::Rooms};
+
+/// Makes actors avoid private tasks with others in the same room.
+pub(super) struct PrivacyPlugin;
+
+impl Plugin for PrivacyPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Embarrassed>()
+            .replicate::<Embarrassed>()
+            .add_systems(
+                Update,
+                Self::refuse
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (Self::embarrass, Self::calm_down)
+                    .run_if(on_timer(Duration::from_secs(1)))
+                    .run_if(server_or_singleplayer),
+            );
+    }
+}
+
+/// How long the embarrassment lasts in seconds.
+const EMBARRASSMENT_DURATION: u32 = 60;
+
+const FUN_PENALTY: f32 = 15.0;
+
+impl PrivacyPlugin {
+    /// Cancels private tasks if someone else is in the room on activation.
+    fn refuse(
+        rooms: Res<Rooms>,
+        actors: Query<(), With<Actor>>,
+        mut tasks: Query<(Entity, &Parent, &TaskPrivacy, &mut TaskState), Changed<TaskState>>,
+    ) {
+        for (entity, parent, &privacy, mut task_state) in &mut tasks {
+            if *privacy
+                && *task_state == TaskState::Active
+                && has_company(&rooms, &actors, **parent)
+            {
+                info!(
+                    "`{}` refuses `{entity}` because the room is not empty",
+                    **parent
+                );
+                *task_state = TaskState::Cancelled;
+            }
+        }
+    }
+
+    /// Embarrasses actors that were disturbed during a private task.
+    fn embarrass(
+        mut commands: Commands,
+        rooms: Res<Rooms>,
+        mut needs: Query<&mut Need, With<Fun>>,
+        actors: Query<(), With<Actor>>,
+        undisturbed_actors: Query<&Children, (With<Actor>, Without<Embarrassed>)>,
+        tasks: Query<(&Parent, &TaskPrivacy, &TaskState)>,
+    ) {
+        for (parent, &privacy, &task_state) in &tasks {
+            if !*privacy || task_state != TaskState::Active {
+                continue;
+            }
+
+            let Ok(children) = undisturbed_actors.get(**parent) else {
+                continue;
+            };
+
+            if has_company(&rooms, &actors, **parent) {
+                info!("`{}` gets embarrassed", **parent);
+                if let Some(mut fun) = needs.iter_many_mut(children).fetch_next() {
+                    fun.add(-FUN_PENALTY);
+                }
+                commands
+                    .entity(**parent)
+                    .insert(Embarrassed(EMBARRASSMENT_DURATION));
+            }
+        }
+    }
+
+    fn calm_down(mut commands: Commands, mut actors: Query<(Entity, &mut Embarrassed)>) {
+        for (entity, mut embarrassed) in &mut actors {
+            embarrassed.0 = embarrassed.0.saturating_sub(1);
+            if embarrassed.0 == 0 {
+                debug!("`{entity}` is no longer embarrassed");
+                commands.entity(entity).remove::<Embarrassed>();
+            }
+        }
+    }
+}
+
+/// Returns `true` if other actors are in the room of an actor.
+///
+/// Rooms are areas enclosed by walls, actors outside of rooms are never in company.
+fn has_company(rooms: &Rooms, actors: &Query<(), With<Actor>>, actor_entity: Entity) -> bool {
+    let Some(room) = rooms.room_of(actor_entity) else {
+        return false;
+    };
+
+    room.entities
+        .iter()
+        .any(|&entity| entity != actor_entity && actors.contains(entity))
+}
+
+/// Moodlet of an actor disturbed during a private task.
+///
+/// Stores the remaining duration in seconds.
+#[derive(Component, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct Embarrassed(pub u32);
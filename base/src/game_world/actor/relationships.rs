@@ -0,0 +1,93 @@
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::Actor;
+use crate::core::GameState;
+
+/// Tracks how actors feel about each other.
+pub(super) struct RelationshipsPlugin;
+
+impl Plugin for RelationshipsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Relationships>()
+            .replicate_mapped::<Relationships>()
+            .add_systems(
+                Update,
+                Self::init
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+impl RelationshipsPlugin {
+    fn init(mut commands: Commands, actors: Query<Entity, (With<Actor>, Without<Relationships>)>) {
+        for entity in &actors {
+            debug!("initializing relationships for `{entity}`");
+            commands.entity(entity).insert(Relationships::default());
+        }
+    }
+}
+
+/// Friendship values of an actor with other actors.
+///
+/// Actors without an entry are strangers.
+#[derive(Component, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+pub struct Relationships(Vec<(Entity, f32)>);
+
+impl Relationships {
+    pub const MAX: f32 = 100.0;
+
+    /// Returns friendship value with the given actor in range `[-MAX, MAX]`.
+    pub fn get(&self, actor_entity: Entity) -> f32 {
+        self.0
+            .iter()
+            .find(|&&(entity, _)| entity == actor_entity)
+            .map_or(0.0, |&(_, value)| value)
+    }
+
+    pub(crate) fn add(&mut self, actor_entity: Entity, amount: f32) {
+        match self
+            .0
+            .iter_mut()
+            .find(|(entity, _)| *entity == actor_entity)
+        {
+            Some((_, value)) => *value = (*value + amount).clamp(-Self::MAX, Self::MAX),
+            None => self
+                .0
+                .push((actor_entity, amount.clamp(-Self::MAX, Self::MAX))),
+        }
+    }
+}
+
+impl MapEntities for Relationships {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        for (entity, _) in &mut self.0 {
+            *entity = entity_mapper.map_entity(*entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamping() {
+        let entity = Entity::from_raw(1);
+        let mut relationships = Relationships::default();
+        assert_eq!(relationships.get(entity), 0.0);
+
+        relationships.add(entity, 60.0);
+        relationships.add(entity, 60.0);
+        assert_eq!(relationships.get(entity), Relationships::MAX);
+
+        relationships.add(entity, -300.0);
+        assert_eq!(relationships.get(entity), -Relationships::MAX);
+    }
+}
@@ -0,0 +1,68 @@
+use std::any;
+
+use bevy::{prelude::*, utils::get_short_name};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::Actor;
+use crate::core::GameState;
+
+pub(super) struct SkillsPlugin;
+
+impl Plugin for SkillsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Handiness>()
+            .register_type::<Cooking>()
+            .replicate::<Handiness>()
+            .replicate::<Cooking>()
+            .add_systems(
+                Update,
+                (Self::init::<Handiness>, Self::init::<Cooking>)
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+impl SkillsPlugin {
+    fn init<C: Component + Default>(
+        mut commands: Commands,
+        actors: Query<Entity, (With<Actor>, Without<C>)>,
+    ) {
+        for entity in &actors {
+            debug!(
+                "initializing `{}` for `{entity}`",
+                get_short_name(any::type_name::<C>())
+            );
+            commands.entity(entity).insert(C::default());
+        }
+    }
+}
+
+/// Ability to repair objects.
+#[derive(Clone, Component, Copy, Default, Deref, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct Handiness(u8);
+
+impl Handiness {
+    pub const MAX: u8 = 10;
+
+    /// Increases the level by one up to [`Self::MAX`].
+    pub(crate) fn improve(&mut self) {
+        self.0 = (self.0 + 1).min(Self::MAX);
+    }
+}
+
+/// Ability to cook without starting fires.
+#[derive(Clone, Component, Copy, Default, Deref, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct Cooking(u8);
+
+impl Cooking {
+    pub const MAX: u8 = 10;
+
+    /// Increases the level by one up to [`Self::MAX`].
+    pub(crate) fn improve(&mut self) {
+        self.0 = (self.0 + 1).min(Self::MAX);
+    }
+}
@@ -1,9 +1,29 @@
 mod buy_lot;
+mod call_police;
+mod cook;
+mod extinguish;
+mod find_job;
+mod fish;
 mod friendly;
+mod gardening;
+mod group_activity;
+mod harvest;
+mod have_meal;
 mod linked_task;
 mod move_here;
+mod panic;
+mod pay_bills;
+mod repair;
+mod sell_collectables;
+mod sit;
+mod sleep;
+mod toggle_claim;
+mod toggle_lamp;
+pub(crate) mod travel;
+mod visit_service;
+mod watch_tv;
 
-use std::{fmt::Debug, io::Cursor};
+use std::{cmp::Reverse, fmt::Debug, io::Cursor};
 
 use bevy::{
     ecs::{entity::MapEntities, reflect::ReflectCommandExt},
@@ -20,17 +40,41 @@ use leafwing_input_manager::common_conditions::action_just_pressed;
 use serde::{de::DeserializeSeed, Deserialize, Serialize};
 
 use crate::{
+    core::GameState,
     game_world::{
-        actor::{animation_state::AnimationState, Actor},
-        family::FamilyMode,
+        actor::{animation_state::AnimationState, outfit::OutfitCategory, Actor},
+        family::{FamilyControl, FamilyMode},
+        mutation_log::{Mutation, MutationKind, MutationRecorder},
         navigation::NavDestination,
+        object::occupancy::{SlotKind, SlotOccupancy, SlotReservation},
     },
+    network::validation::{AppValidationExt, ClientViolation, ValidationSet},
     settings::Action,
 };
 use buy_lot::BuyLotPlugin;
+use call_police::CallPolicePlugin;
+use cook::CookPlugin;
+use extinguish::ExtinguishPlugin;
+use find_job::FindJobPlugin;
+use fish::FishPlugin;
 use friendly::FriendlyPlugins;
+use gardening::GardeningPlugin;
+use group_activity::GroupActivityPlugin;
+use harvest::HarvestPlugin;
+use have_meal::HaveMealPlugin;
 use linked_task::LinkedTaskPlugin;
 use move_here::MoveHerePlugin;
+use panic::PanicPlugin;
+use pay_bills::PayBillsPlugin;
+use repair::RepairPlugin;
+use sell_collectables::SellCollectablesPlugin;
+use sit::SitPlugin;
+use sleep::SleepPlugin;
+use toggle_claim::ToggleClaimPlugin;
+use toggle_lamp::ToggleLampPlugin;
+use travel::TravelPlugin;
+use visit_service::VisitServicePlugin;
+use watch_tv::WatchTvPlugin;
 
 pub(super) struct TaskPlugin;
 
@@ -38,18 +82,44 @@ impl Plugin for TaskPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((
             BuyLotPlugin,
+            CallPolicePlugin,
+            CookPlugin,
+            ExtinguishPlugin,
+            FindJobPlugin,
+            FishPlugin,
             FriendlyPlugins,
+            GardeningPlugin,
+            GroupActivityPlugin,
+            HarvestPlugin,
+            HaveMealPlugin,
             LinkedTaskPlugin,
             MoveHerePlugin,
+            PanicPlugin,
+        ))
+        .add_plugins((
+            PayBillsPlugin,
+            RepairPlugin,
+            SellCollectablesPlugin,
+            SitPlugin,
+            SleepPlugin,
+            ToggleClaimPlugin,
+            ToggleLampPlugin,
+            TravelPlugin,
+            VisitServicePlugin,
+            WatchTvPlugin,
         ))
         .register_type::<TaskState>()
+        .register_type::<TaskProgress>()
         .replicate::<TaskState>()
+        .replicate::<TaskProgress>()
         .add_client_event::<TaskCancel>(ChannelKind::Unordered)
+        .add_mapped_client_event::<TaskMove>(ChannelKind::Ordered)
         .add_client_event_with(
             ChannelKind::Unordered,
             serialize_task_request,
             deserialize_task_request,
         )
+        .limit_client_event::<TaskRequest>(10)
         .add_event::<TaskList>()
         .configure_sets(
             Update,
@@ -59,13 +129,25 @@ impl Plugin for TaskPlugin {
         )
         .add_systems(
             PreUpdate,
-            (Self::request, Self::cancel)
+            (Self::request, Self::cancel, Self::reorder)
                 .after(ClientSet::Receive)
+                .after(ValidationSet)
                 .run_if(server_or_singleplayer),
         )
+        .add_systems(
+            Update,
+            Self::update_progress
+                .run_if(server_or_singleplayer)
+                .run_if(in_state(GameState::InGame)),
+        )
         .add_systems(
             PostUpdate,
-            (Self::despawn_cancelled, Self::activate_queued).run_if(server_or_singleplayer),
+            (
+                Self::despawn_cancelled,
+                Self::activate_queued,
+                Self::stop_paused.after(Self::activate_queued),
+            )
+                .run_if(server_or_singleplayer),
         );
     }
 }
@@ -74,11 +156,27 @@ impl TaskPlugin {
     fn request(
         mut commands: Commands,
         mut request_events: ResMut<Events<FromClient<TaskRequest>>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        control: FamilyControl,
         actors: Query<(), With<Actor>>,
     ) {
         for FromClient { client_id, event } in request_events.drain() {
             if actors.get(event.entity).is_ok() {
+                if !control.actor_allowed(client_id, event.entity) {
+                    error!(
+                        "`{client_id:?}` requests task for not owned actor `{}`",
+                        event.entity
+                    );
+                    violation_events.send(ClientViolation {
+                        client_id,
+                        reason: "requesting tasks for another family".into(),
+                    });
+                    continue;
+                }
+
                 info!("`{client_id:?}` requests task '{}'", event.task.name());
+                recorder.record_without_payload(client_id, &event);
                 commands.entity(event.entity).with_children(|parent| {
                     parent
                         .spawn(TaskBundle::new(&*event.task))
@@ -90,25 +188,89 @@ impl TaskPlugin {
         }
     }
 
+    /// Activates pending tasks by priority and pauses active tasks that block a more important one.
+    ///
+    /// Preempting task is activated on the next run to let paused tasks stop first.
     fn activate_queued(
-        mut tasks: Query<(Entity, &TaskGroups, &mut TaskState)>,
-        actors: Query<&Children, With<Actor>>,
+        mut commands: Commands,
+        occupancy: SlotOccupancy,
+        mut tasks: Query<(
+            Entity,
+            &TaskGroups,
+            Option<&TaskPriority>,
+            Option<&UsedSlot>,
+            &mut TaskState,
+        )>,
+        actors: Query<(Entity, &Children), With<Actor>>,
     ) {
-        for children in &actors {
-            let current_groups = tasks
-                .iter_many(children)
-                .filter(|(.., &task_state)| task_state != TaskState::Queued)
-                .map(|(_, &groups, _)| groups)
-                .reduce(|acc, groups| acc & groups)
-                .unwrap_or_default();
-
-            let mut iter = tasks.iter_many_mut(children);
-            while let Some((entity, groups, mut task_state)) = iter.fetch_next() {
-                if *task_state == TaskState::Queued && !groups.intersects(current_groups) {
-                    *task_state = TaskState::Active;
-                    debug!("setting `{task_state:?}` for `{entity}`");
-                    break;
+        // Reservations are inserted via commands, so track the ones from this run.
+        let mut reserved = Vec::new();
+        for (actor_entity, children) in &actors {
+            let mut active = Vec::new();
+            let mut pending = Vec::new();
+            for (entity, &groups, priority, used_slot, &task_state) in tasks.iter_many(children) {
+                let priority = priority.copied().unwrap_or_default();
+                match task_state {
+                    TaskState::Queued | TaskState::Paused => {
+                        let used_slot = used_slot.and_then(|used_slot| used_slot.0);
+                        pending.push((entity, groups, priority, used_slot));
+                    }
+                    TaskState::Active => active.push((entity, groups, priority)),
+                    TaskState::Cancelled => (),
+                }
+            }
+            // Stable sort to keep the queue order for tasks with the same priority.
+            pending.sort_by_key(|&(.., priority, _)| Reverse(priority));
+
+            let current_groups = active
+                .iter()
+                .fold(TaskGroups::empty(), |acc, &(_, groups, _)| acc | groups);
+
+            for (entity, groups, priority, used_slot) in pending {
+                if groups.intersects(current_groups) {
+                    let blocking: Vec<_> = active
+                        .iter()
+                        .filter(|(_, active_groups, _)| active_groups.intersects(groups))
+                        .collect();
+                    if blocking
+                        .iter()
+                        .all(|&&(.., active_priority)| active_priority < priority)
+                    {
+                        for &&(active_entity, ..) in &blocking {
+                            let (.., mut task_state) = tasks
+                                .get_mut(active_entity)
+                                .expect("active task should be a child of the actor");
+                            *task_state = TaskState::Paused;
+                            debug!("pausing `{active_entity}` in favor of `{entity}`");
+                        }
+                        break;
+                    }
+                    continue;
+                }
+
+                if let Some((object_entity, kind)) = used_slot {
+                    let Some(reservation) =
+                        occupancy.pick(actor_entity, object_entity, kind, &reserved)
+                    else {
+                        info!("removing task `{entity}` because `{object_entity}` has no free `{kind:?}` slots");
+                        commands.entity(entity).despawn();
+                        continue;
+                    };
+
+                    debug!(
+                        "reserving slot {} of `{}` for `{entity}`",
+                        reservation.index, reservation.object_entity
+                    );
+                    reserved.push((reservation.object_entity, reservation.index));
+                    commands.entity(entity).insert(reservation);
                 }
+
+                let (.., mut task_state) = tasks
+                    .get_mut(entity)
+                    .expect("pending task should be a child of the actor");
+                *task_state = TaskState::Active;
+                debug!("setting `{:?}` for `{entity}`", *task_state);
+                break;
             }
         }
     }
@@ -116,13 +278,29 @@ impl TaskPlugin {
     fn cancel(
         mut commands: Commands,
         mut cancel_events: EventReader<FromClient<TaskCancel>>,
-        mut tasks: Query<&mut TaskState>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        control: FamilyControl,
+        mut tasks: Query<(&Parent, &mut TaskState)>,
     ) {
         for FromClient { client_id, event } in cancel_events.read() {
-            if let Ok(mut task_state) = tasks.get_mut(event.0) {
+            if let Ok((parent, mut task_state)) = tasks.get_mut(event.0) {
+                if !control.actor_allowed(*client_id, **parent) {
+                    error!(
+                        "`{client_id:?}` cancels task of not owned actor `{}`",
+                        **parent
+                    );
+                    violation_events.send(ClientViolation {
+                        client_id: *client_id,
+                        reason: "cancelling tasks of another family".into(),
+                    });
+                    continue;
+                }
+
                 info!("`{client_id:?}` cancels task `{:?}`", event.0);
+                recorder.record(*client_id, event);
                 match *task_state {
-                    TaskState::Queued => commands.entity(event.0).despawn(),
+                    TaskState::Queued | TaskState::Paused => commands.entity(event.0).despawn(),
                     TaskState::Active => *task_state = TaskState::Cancelled,
                     TaskState::Cancelled => (),
                 }
@@ -132,6 +310,111 @@ impl TaskPlugin {
         }
     }
 
+    fn reorder(
+        mut commands: Commands,
+        mut move_events: EventReader<FromClient<TaskMove>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        control: FamilyControl,
+        tasks: Query<(&Parent, &TaskState)>,
+        actors: Query<&Children>,
+    ) {
+        for FromClient { client_id, event } in move_events.read() {
+            let Ok((parent, &task_state)) = tasks.get(event.task_entity) else {
+                error!("entity {:?} is not a task", event.task_entity);
+                continue;
+            };
+            let Ok((target_parent, &target_state)) = tasks.get(event.target_entity) else {
+                error!("entity {:?} is not a task", event.target_entity);
+                continue;
+            };
+            if parent != target_parent
+                || task_state != TaskState::Queued
+                || target_state != TaskState::Queued
+            {
+                error!(
+                    "unable to move task `{:?}` to `{:?}`, only queued tasks of the same actor can be reordered",
+                    event.task_entity, event.target_entity
+                );
+                continue;
+            }
+            if !control.actor_allowed(*client_id, **parent) {
+                error!(
+                    "`{client_id:?}` moves task of not owned actor `{}`",
+                    **parent
+                );
+                violation_events.send(ClientViolation {
+                    client_id: *client_id,
+                    reason: "reordering tasks of another family".into(),
+                });
+                continue;
+            }
+
+            info!(
+                "`{client_id:?}` moves task `{:?}` to `{:?}`",
+                event.task_entity, event.target_entity
+            );
+            recorder.record(*client_id, event);
+            let children = actors
+                .get(**parent)
+                .expect("task parent should have children");
+            let index = children
+                .iter()
+                .position(|&entity| entity == event.target_entity)
+                .expect("target task should be a child of the actor");
+            commands
+                .entity(**parent)
+                .insert_children(index, &[event.task_entity]);
+        }
+    }
+
+    /// Updates progress of active tasks with [`TaskProgress`] from the playing montage.
+    fn update_progress(
+        mut tasks: Query<(&Parent, &TaskState, &mut TaskProgress)>,
+        actors: Query<&AnimationState>,
+    ) {
+        for (parent, &task_state, mut progress) in &mut tasks {
+            if task_state != TaskState::Active {
+                continue;
+            }
+
+            let animation_state = actors
+                .get(**parent)
+                .expect("actor should have animation state");
+            if let Some((elapsed, duration)) = animation_state.montage_time() {
+                progress.set_if_neq(TaskProgress::new(
+                    elapsed / duration,
+                    Some(duration - elapsed),
+                ));
+            }
+        }
+    }
+
+    /// Stops navigation and animation of paused tasks and releases their slots.
+    fn stop_paused(
+        mut commands: Commands,
+        tasks: Query<(Entity, &Parent, &TaskGroups, &TaskState), Changed<TaskState>>,
+        mut actors: Query<(&mut NavDestination, &mut AnimationState)>,
+    ) {
+        for (entity, parent, groups, &task_state) in &tasks {
+            if task_state == TaskState::Paused {
+                debug!("stopping paused task `{entity}`");
+                let (mut dest, mut animation_state) = actors
+                    .get_mut(**parent)
+                    .expect("actor should have animaition state");
+
+                if groups.contains(TaskGroups::LEGS) {
+                    **dest = None;
+                }
+
+                animation_state.stop_montage();
+
+                // Will be reserved again on resume.
+                commands.entity(entity).remove::<SlotReservation>();
+            }
+        }
+    }
+
     fn despawn_cancelled(
         mut commands: Commands,
         tasks: Query<(Entity, &Parent, &TaskGroups, &TaskState), Changed<TaskState>>,
@@ -210,6 +493,10 @@ impl<T: Task> From<T> for TaskList {
 #[derive(Bundle)]
 struct TaskBundle {
     groups: TaskGroups,
+    priority: TaskPriority,
+    outfit: TaskOutfit,
+    used_slot: UsedSlot,
+    privacy: TaskPrivacy,
     state: TaskState,
     parent_sync: ParentSync,
     replication: Replicated,
@@ -219,6 +506,10 @@ impl TaskBundle {
     fn new(task: &dyn Task) -> Self {
         Self {
             groups: task.groups(),
+            priority: task.priority(),
+            outfit: TaskOutfit(task.outfit()),
+            used_slot: UsedSlot(task.used_slot()),
+            privacy: TaskPrivacy(task.private()),
             state: Default::default(),
             parent_sync: Default::default(),
             replication: Replicated,
@@ -232,9 +523,70 @@ pub enum TaskState {
     #[default]
     Queued,
     Active,
+    /// Interrupted by a task with higher priority, will be resumed after it.
+    Paused,
     Cancelled,
 }
 
+/// Normalized progress of a long-running task with the estimated remaining time.
+///
+/// Tasks insert it when they want to report progress.
+/// Filled automatically from the playing montage, but can be also updated by the task itself.
+/// Values are rounded to reduce replication traffic, so use [`Mut::set_if_neq`] for updates.
+#[derive(Clone, Component, Copy, Debug, Default, Deserialize, PartialEq, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct TaskProgress {
+    value: f32,
+    eta: Option<u32>,
+}
+
+impl TaskProgress {
+    /// Creates progress from a value between 0 and 1 and the remaining time in seconds.
+    pub(super) fn new(value: f32, remaining: Option<f32>) -> Self {
+        const STEP: f32 = 0.05;
+        Self {
+            value: (value.clamp(0.0, 1.0) / STEP).floor() * STEP,
+            eta: remaining.map(|secs| secs.max(0.0).ceil() as u32),
+        }
+    }
+
+    /// Returns progress from 0 to 1.
+    pub fn value(self) -> f32 {
+        self.value
+    }
+
+    /// Returns the estimated remaining time in seconds, if known.
+    pub fn eta(self) -> Option<u32> {
+        self.eta
+    }
+}
+
+/// Importance of a task, see [`Task::priority`].
+///
+/// Pending tasks with higher priority are activated first
+/// and pause active tasks with lower priority that use the same [`TaskGroups`].
+#[derive(Clone, Component, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub enum TaskPriority {
+    #[default]
+    Normal,
+    /// Interrupts any normal task.
+    Emergency,
+}
+
+/// Outfit requested by the task, see [`Task::outfit`].
+#[derive(Component, Clone, Copy, Default)]
+pub(super) struct TaskOutfit(pub(super) Option<OutfitCategory>);
+
+/// Object slot requested by the task, see [`Task::used_slot`].
+///
+/// Reserved on activation as [`SlotReservation`].
+#[derive(Component, Clone, Copy, Default)]
+struct UsedSlot(Option<(Entity, SlotKind)>);
+
+/// Whether the task needs privacy, see [`Task::private`].
+#[derive(Component, Clone, Copy, Default, Deref)]
+pub(crate) struct TaskPrivacy(bool);
+
 bitflags! {
     #[derive(Default, Component, Clone, Copy)]
     pub struct TaskGroups: u8 {
@@ -251,6 +603,31 @@ pub trait Task: Reflect {
     fn groups(&self) -> TaskGroups {
         TaskGroups::default()
     }
+
+    /// Outfit that actor should wear while the task is active.
+    fn outfit(&self) -> Option<OutfitCategory> {
+        None
+    }
+
+    /// Whether the task should interrupt other tasks of the actor.
+    fn priority(&self) -> TaskPriority {
+        TaskPriority::Normal
+    }
+
+    /// Object and kind of its slot that should be reserved while the task is active.
+    ///
+    /// For seats and beds a similar object could be reserved instead, see [`SlotOccupancy::pick`].
+    /// The task will be removed on activation if all slots are taken.
+    fn used_slot(&self) -> Option<(Entity, SlotKind)> {
+        None
+    }
+
+    /// Whether the actor refuses to start the task with other actors in the same room.
+    ///
+    /// Actors get embarrassed if someone enters the room while the task is active.
+    fn private(&self) -> bool {
+        false
+    }
 }
 
 /// An event of canceling the specified task.
@@ -265,6 +642,46 @@ impl MapEntities for TaskCancel {
     }
 }
 
+impl Mutation for TaskCancel {
+    const TARGET: &'static str = "task";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Delete
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        Some(self.0)
+    }
+}
+
+/// An event of moving a queued task to the position of another queued task of the same actor.
+///
+/// Emitted by players.
+#[derive(Clone, Copy, Deserialize, Event, Serialize)]
+pub struct TaskMove {
+    pub task_entity: Entity,
+    pub target_entity: Entity,
+}
+
+impl MapEntities for TaskMove {
+    fn map_entities<T: EntityMapper>(&mut self, mapper: &mut T) {
+        self.task_entity = mapper.map_entity(self.task_entity);
+        self.target_entity = mapper.map_entity(self.target_entity);
+    }
+}
+
+impl Mutation for TaskMove {
+    const TARGET: &'static str = "task";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Move
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        Some(self.task_entity)
+    }
+}
+
 #[derive(Event)]
 pub struct TaskRequest {
     pub entity: Entity,
@@ -276,3 +693,15 @@ impl MapEntities for TaskRequest {
         self.entity = mapper.map_entity(self.entity);
     }
 }
+
+impl Mutation for TaskRequest {
+    const TARGET: &'static str = "task";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Spawn
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        None
+    }
+}
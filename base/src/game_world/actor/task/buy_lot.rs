@@ -12,9 +12,10 @@ use crate::game_world::{
         Actor,
     },
     city::{
-        lot::{LotFamily, LotVertices},
+        lot::{community_lot::LotService, permissions::LotPermissions, LotFamily, LotVertices},
         Ground,
     },
+    family::memories::{MemoryKind, MemoryRecord},
     hover::Hovered,
 };
 
@@ -38,7 +39,7 @@ impl BuyLotPlugin {
     fn add_to_list(
         mut list_events: EventWriter<TaskList>,
         mut grounds: Query<&Hovered, With<Ground>>,
-        lots: Query<(Entity, &LotVertices), Without<LotFamily>>,
+        lots: Query<(Entity, &LotVertices), (Without<LotFamily>, Without<LotService>)>,
     ) {
         if let Ok(point) = grounds.get_single_mut().map(|point| point.xz()) {
             if let Some((lot_entity, _)) = lots
@@ -52,7 +53,8 @@ impl BuyLotPlugin {
 
     fn buy(
         mut commands: Commands,
-        lots: Query<(), Without<LotFamily>>,
+        mut record_events: EventWriter<MemoryRecord>,
+        lots: Query<(), (Without<LotFamily>, Without<LotService>)>,
         actors: Query<&Actor>,
         tasks: Query<(Entity, &Parent, &BuyLot, &TaskState), Changed<TaskState>>,
     ) {
@@ -64,7 +66,12 @@ impl BuyLotPlugin {
                 if lots.get(buy.0).is_ok() {
                     commands
                         .entity(buy.0)
-                        .insert(LotFamily(actor.family_entity));
+                        .insert((LotFamily(actor.family_entity), LotPermissions::default()));
+                    record_events.send(MemoryRecord {
+                        family_entity: actor.family_entity,
+                        kind: MemoryKind::Home,
+                        description: "Moved into a new home".into(),
+                    });
                 } else {
                     error!("`{buy:?}` from actor `{entity}` points to not a lot");
                 }
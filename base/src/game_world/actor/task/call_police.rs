@@ -0,0 +1,183 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game_world::{
+    actor::task::{Task, TaskList, TaskListSet, TaskProgress, TaskState},
+    burglary::Burglar,
+    hover::Hovered,
+};
+
+pub(super) struct CallPolicePlugin;
+
+impl Plugin for CallPolicePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<CallPolice>()
+            .replicate_mapped::<CallPolice>()
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    (Self::dial, Self::call).run_if(server_or_singleplayer),
+                ),
+            );
+    }
+}
+
+/// How long it takes to report a burglar.
+const CALL_DURATION: Duration = Duration::from_secs(10);
+
+impl CallPolicePlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        burglars: Query<(Entity, &Burglar), With<Hovered>>,
+    ) {
+        if let Ok((entity, burglar)) = burglars.get_single() {
+            if !burglar.police_called() {
+                list_events.send(CallPolice(entity).into());
+            }
+        }
+    }
+
+    fn dial(
+        mut commands: Commands,
+        tasks: Query<
+            (Entity, &Parent, &CallPolice, &TaskState),
+            (Changed<TaskState>, Without<PhoneCall>),
+        >,
+    ) {
+        for (entity, parent, call_police, &task_state) in &tasks {
+            if task_state == TaskState::Active {
+                debug!("`{}` dials police about `{}`", **parent, call_police.0);
+                commands.entity(entity).insert((
+                    PhoneCall(Timer::new(CALL_DURATION, TimerMode::Once)),
+                    TaskProgress::new(0.0, Some(CALL_DURATION.as_secs_f32())),
+                ));
+            }
+        }
+    }
+
+    fn call(
+        mut commands: Commands,
+        time: Res<Time>,
+        mut burglars: Query<&mut Burglar>,
+        mut tasks: Query<(
+            Entity,
+            &Parent,
+            &CallPolice,
+            &TaskState,
+            &mut PhoneCall,
+            &mut TaskProgress,
+        )>,
+    ) {
+        for (entity, parent, call_police, &task_state, mut call, mut progress) in &mut tasks {
+            if task_state != TaskState::Active {
+                continue;
+            }
+            let Ok(mut burglar) = burglars.get_mut(call_police.0) else {
+                debug!("`{}` is gone, hanging up", call_police.0);
+                commands.entity(entity).despawn();
+                continue;
+            };
+
+            call.tick(time.delta());
+            progress.set_if_neq(TaskProgress::new(
+                call.fraction(),
+                Some(call.remaining_secs()),
+            ));
+            if call.finished() {
+                info!("`{}` calls police on `{}`", **parent, call_police.0);
+                burglar.call_police();
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+/// Calls the police to catch a burglar.
+#[derive(Component, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+struct CallPolice(Entity);
+
+impl Task for CallPolice {
+    fn name(&self) -> &str {
+        "Call police"
+    }
+}
+
+/// Remaining time of the call.
+///
+/// Server-only.
+#[derive(Component, Deref, DerefMut)]
+struct PhoneCall(Timer);
+
+impl FromWorld for CallPolice {
+    fn from_world(_world: &mut World) -> Self {
+        Self(Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for CallPolice {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game_world::actor::{task::TaskRequest, Actor},
+        test_world::TestWorld,
+    };
+
+    #[test]
+    fn takes_time() {
+        // Virtual time doesn't advance more than 250 ms per update.
+        const TICK: Duration = Duration::from_millis(250);
+        let mut test_world = TestWorld::builder().tick_duration(TICK).build();
+        let city_entity = test_world.spawn_city("City");
+        test_world.spawn_family(city_entity, "Family", 1);
+        let mut actors = test_world
+            .world_mut()
+            .query_filtered::<Entity, With<Actor>>();
+        let actor_entity = actors.single(test_world.world());
+
+        // Without navigation components, so the burglar stays in place.
+        let burglar = Burglar::from_world(test_world.world_mut());
+        let burglar_entity = test_world
+            .world_mut()
+            .spawn((burglar, Transform::default()))
+            .id();
+        test_world.world_mut().send_event(FromClient {
+            client_id: ClientId::SERVER,
+            event: TaskRequest {
+                entity: actor_entity,
+                task: Box::new(CallPolice(burglar_entity)),
+            },
+        });
+
+        test_world.tick_n(3);
+        let burglar = test_world.world().get::<Burglar>(burglar_entity).unwrap();
+        assert!(!burglar.police_called(), "call should take time");
+
+        let call_ticks = CALL_DURATION.as_millis() / TICK.as_millis();
+        test_world.tick_until(call_ticks as usize + 1, |world| {
+            world
+                .get::<Burglar>(burglar_entity)
+                .unwrap()
+                .police_called()
+        });
+        let burglar = test_world.world().get::<Burglar>(burglar_entity).unwrap();
+        assert!(burglar.police_called());
+
+        test_world.tick();
+        let mut tasks = test_world.world_mut().query::<&CallPolice>();
+        assert_eq!(tasks.iter(test_world.world()).count(), 0);
+    }
+}
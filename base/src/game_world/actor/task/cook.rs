@@ -0,0 +1,185 @@
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{
+            animation_state::{AnimationLibrary, AnimationState, MontageFinished},
+            needs::{Hunger, Need},
+            skills::Cooking,
+            task::{Task, TaskGroups, TaskList, TaskListSet, TaskState},
+            Movement,
+        },
+        hover::Hovered,
+        navigation::{NavDestination, NavSettings},
+        object::{
+            fire::{Burning, FireDamaged, Stove},
+            wear::{Broken, ObjectUsed},
+        },
+        rng::{RngStream, WorldRng},
+    },
+};
+
+pub(super) struct CookPlugin;
+
+impl Plugin for CookPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Cook>()
+            .replicate_mapped::<Cook>()
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    Self::start_cooking,
+                    Self::finish.run_if(server_or_singleplayer),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            // Should run in `PostUpdate` to let tiles initialize.
+            .add_systems(
+                PostUpdate,
+                Self::start_navigation.run_if(server_or_singleplayer),
+            );
+    }
+}
+
+/// Hunger restored by a cooked meal.
+const MEAL_VALUE: f32 = 60.0;
+
+impl CookPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        stoves: Query<
+            Entity,
+            (
+                With<Hovered>,
+                With<Stove>,
+                Without<Burning>,
+                Without<FireDamaged>,
+                Without<Broken>,
+            ),
+        >,
+    ) {
+        if let Ok(stove_entity) = stoves.get_single() {
+            list_events.send(Cook(stove_entity).into());
+        }
+    }
+
+    fn start_navigation(
+        mut actors: Query<(&mut NavSettings, &mut NavDestination)>,
+        stoves: Query<&Transform>,
+        tasks: Query<(&Parent, &Cook, &TaskState), Changed<TaskState>>,
+    ) {
+        for (parent, cook, &task_state) in &tasks {
+            if task_state == TaskState::Active {
+                let Ok(stove_transform) = stoves.get(cook.0) else {
+                    error!("`{cook:?}` points to an entity without transform");
+                    continue;
+                };
+
+                let (mut nav_settings, mut dest) = actors
+                    .get_mut(**parent)
+                    .expect("actors should have navigation component");
+                *nav_settings = NavSettings::new(Movement::Walk.speed()).with_offset(0.6);
+                **dest = Some(stove_transform.translation);
+            }
+        }
+    }
+
+    fn start_cooking(
+        animations: Res<AnimationLibrary>,
+        mut actors: Query<
+            (&Children, &NavDestination, &mut AnimationState),
+            Changed<NavDestination>,
+        >,
+        tasks: Query<&TaskState, With<Cook>>,
+    ) {
+        for (children, dest, mut animation_state) in &mut actors {
+            if dest.is_none()
+                && tasks
+                    .iter_many(children)
+                    .any(|&task_state| task_state == TaskState::Active)
+            {
+                let montage = animations.montage("cook");
+                animation_state.play_montage(montage);
+            }
+        }
+    }
+
+    /// Feeds the cook and rolls for a fire depending on the skill.
+    fn finish(
+        mut commands: Commands,
+        mut finish_events: EventReader<MontageFinished>,
+        mut used_events: EventWriter<ObjectUsed>,
+        mut world_rng: ResMut<WorldRng>,
+        mut actors: Query<(&Children, &mut Cooking)>,
+        mut needs: Query<&mut Need, With<Hunger>>,
+        stoves: Query<&Stove, (Without<Burning>, Without<FireDamaged>)>,
+        tasks: Query<(Entity, &Cook, &TaskState)>,
+    ) {
+        for event in finish_events.read() {
+            let Ok((children, mut cooking)) = actors.get_mut(event.0) else {
+                continue;
+            };
+
+            let Some((entity, cook, _)) = tasks
+                .iter_many(children)
+                .find(|(.., &task_state)| task_state == TaskState::Active)
+            else {
+                continue;
+            };
+            commands.entity(entity).despawn();
+
+            let Ok(stove) = stoves.get(cook.0) else {
+                debug!("`{}` can no longer be used for cooking", cook.0);
+                continue;
+            };
+
+            used_events.send(ObjectUsed(cook.0));
+            let rng = world_rng.stream(RngStream::Events);
+            if rng.gen::<f32>() < stove.fire_chance(*cooking) {
+                info!("`{}` set `{}` on fire while cooking", event.0, cook.0);
+                commands.entity(cook.0).insert(Burning::default());
+                continue;
+            }
+
+            info!("`{}` cooks a meal on `{}`", event.0, cook.0);
+            if let Some(mut hunger) = needs.iter_many_mut(children).fetch_next() {
+                hunger.add(MEAL_VALUE);
+            }
+            cooking.improve();
+        }
+    }
+}
+
+#[derive(Component, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+struct Cook(Entity);
+
+impl Task for Cook {
+    fn name(&self) -> &str {
+        "Cook"
+    }
+
+    fn groups(&self) -> TaskGroups {
+        TaskGroups::LEGS | TaskGroups::BOTH_HANDS
+    }
+}
+
+impl FromWorld for Cook {
+    fn from_world(_world: &mut World) -> Self {
+        Self(Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for Cook {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
@@ -0,0 +1,155 @@
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{
+            animation_state::{AnimationLibrary, AnimationState, MontageFinished},
+            task::{Task, TaskGroups, TaskList, TaskListSet, TaskState},
+            Movement,
+        },
+        hover::Hovered,
+        navigation::{NavDestination, NavSettings},
+        object::fire::{Burning, FireDamaged, DAMAGE_PROGRESS},
+    },
+};
+
+pub(super) struct ExtinguishPlugin;
+
+impl Plugin for ExtinguishPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Extinguish>()
+            .replicate_mapped::<Extinguish>()
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    Self::start_extinguishing,
+                    Self::finish.run_if(server_or_singleplayer),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            // Should run in `PostUpdate` to let tiles initialize.
+            .add_systems(
+                PostUpdate,
+                Self::start_navigation.run_if(server_or_singleplayer),
+            );
+    }
+}
+
+impl ExtinguishPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        objects: Query<Entity, (With<Burning>, With<Hovered>)>,
+    ) {
+        if let Ok(entity) = objects.get_single() {
+            list_events.send(Extinguish(entity).into());
+        }
+    }
+
+    fn start_navigation(
+        mut actors: Query<(&mut NavSettings, &mut NavDestination)>,
+        objects: Query<&Transform>,
+        tasks: Query<(&Parent, &Extinguish, &TaskState), Changed<TaskState>>,
+    ) {
+        for (parent, extinguish, &task_state) in &tasks {
+            if task_state == TaskState::Active {
+                let Ok(object_transform) = objects.get(extinguish.0) else {
+                    error!("`{extinguish:?}` points to an entity without transform");
+                    continue;
+                };
+
+                let (mut nav_settings, mut dest) = actors
+                    .get_mut(**parent)
+                    .expect("actors should have navigation component");
+                *nav_settings = NavSettings::new(Movement::Walk.speed()).with_offset(1.0);
+                **dest = Some(object_transform.translation);
+            }
+        }
+    }
+
+    fn start_extinguishing(
+        animations: Res<AnimationLibrary>,
+        mut actors: Query<
+            (&Children, &NavDestination, &mut AnimationState),
+            Changed<NavDestination>,
+        >,
+        tasks: Query<&TaskState, With<Extinguish>>,
+    ) {
+        for (children, dest, mut animation_state) in &mut actors {
+            if dest.is_none()
+                && tasks
+                    .iter_many(children)
+                    .any(|&task_state| task_state == TaskState::Active)
+            {
+                // TODO: Replace with a proper extinguishing animation.
+                let montage = animations.montage("thoughtful_nod");
+                animation_state.play_montage(montage);
+            }
+        }
+    }
+
+    fn finish(
+        mut commands: Commands,
+        mut finish_events: EventReader<MontageFinished>,
+        actors: Query<&Children>,
+        objects: Query<&Burning>,
+        tasks: Query<(Entity, &Extinguish, &TaskState)>,
+    ) {
+        for event in finish_events.read() {
+            let Ok(children) = actors.get(event.0) else {
+                continue;
+            };
+
+            let Some((entity, extinguish, _)) = tasks
+                .iter_many(children)
+                .find(|(.., &task_state)| task_state == TaskState::Active)
+            else {
+                continue;
+            };
+
+            if let Ok(burning) = objects.get(extinguish.0) {
+                info!("`{}` extinguishes `{}`", event.0, extinguish.0);
+                let mut object = commands.entity(extinguish.0);
+                object.remove::<Burning>();
+                if burning.progress >= DAMAGE_PROGRESS {
+                    object.insert(FireDamaged);
+                }
+            }
+
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Puts out a fire on an object.
+#[derive(Component, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+struct Extinguish(Entity);
+
+impl Task for Extinguish {
+    fn name(&self) -> &str {
+        "Extinguish"
+    }
+
+    fn groups(&self) -> TaskGroups {
+        TaskGroups::LEGS | TaskGroups::BOTH_HANDS
+    }
+}
+
+impl FromWorld for Extinguish {
+    fn from_world(_world: &mut World) -> Self {
+        Self(Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for Extinguish {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game_world::{
+    actor::{
+        age::{Age, LifeStage},
+        job::Job,
+        task::{Task, TaskList, TaskListSet, TaskState},
+        SelectedActor,
+    },
+    hover::Hovered,
+};
+
+pub(super) struct FindJobPlugin;
+
+impl Plugin for FindJobPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<FindJob>()
+            .register_type::<QuitJob>()
+            .replicate::<FindJob>()
+            .replicate::<QuitJob>()
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    (Self::find, Self::quit).run_if(server_or_singleplayer),
+                ),
+            );
+    }
+}
+
+impl FindJobPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        actors: Query<(&Age, Has<Job>), (With<SelectedActor>, With<Hovered>)>,
+    ) {
+        let Ok((age, has_job)) = actors.get_single() else {
+            return;
+        };
+
+        if has_job {
+            list_events.send(QuitJob.into());
+        } else if age.stage() == LifeStage::Adult {
+            list_events.send(FindJob.into());
+        }
+    }
+
+    fn find(
+        mut commands: Commands,
+        actors: Query<(&Age, Has<Job>)>,
+        tasks: Query<(Entity, &Parent, &TaskState), (With<FindJob>, Changed<TaskState>)>,
+    ) {
+        for (entity, parent, &task_state) in &tasks {
+            if task_state == TaskState::Active {
+                let (age, has_job) = actors
+                    .get(**parent)
+                    .expect("task should have assigned actors");
+
+                if !has_job && age.stage() == LifeStage::Adult {
+                    info!("`{}` finds a job", **parent);
+                    commands.entity(**parent).insert(Job::default());
+                } else {
+                    info!("`{}` can't get a job", **parent);
+                }
+
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+
+    fn quit(
+        mut commands: Commands,
+        tasks: Query<(Entity, &Parent, &TaskState), (With<QuitJob>, Changed<TaskState>)>,
+    ) {
+        for (entity, parent, &task_state) in &tasks {
+            if task_state == TaskState::Active {
+                info!("`{}` quits job", **parent);
+                commands.entity(**parent).remove::<Job>();
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+#[derive(Clone, Component, Copy, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+struct FindJob;
+
+impl Task for FindJob {
+    fn name(&self) -> &str {
+        "Find job"
+    }
+}
+
+#[derive(Clone, Component, Copy, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+struct QuitJob;
+
+impl Task for QuitJob {
+    fn name(&self) -> &str {
+        "Quit job"
+    }
+}
@@ -0,0 +1,195 @@
+use bevy::{
+    animation::RepeatAnimation,
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    math::Vec3Swizzles,
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{
+            animation_state::{AnimationLibrary, AnimationState, MontageFinished},
+            task::{Task, TaskGroups, TaskList, TaskListSet, TaskProgress, TaskState},
+            Movement, ACTOR_RADIUS,
+        },
+        city::water::WaterVertices,
+        collectable::{Inventory, SpawnerStock},
+        hover::Hovered,
+        navigation::{NavDestination, NavSettings},
+    },
+};
+
+pub(super) struct FishPlugin;
+
+impl Plugin for FishPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Fish>()
+            .replicate_mapped::<Fish>()
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    Self::start_fishing,
+                    Self::finish.run_if(server_or_singleplayer),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            // Should run in `PostUpdate` to let tiles initialize.
+            .add_systems(
+                PostUpdate,
+                Self::start_navigation.run_if(server_or_singleplayer),
+            );
+    }
+}
+
+impl FishPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        water_bodies: Query<(Entity, &Hovered, &WaterVertices)>,
+    ) {
+        if let Ok((water_entity, hovered, vertices)) = water_bodies.get_single() {
+            // Keep a small gap to let the navigation reach the point.
+            const SHORE_OFFSET: f32 = ACTOR_RADIUS + 0.2;
+            if let Some(shore_point) = vertices.shore_point(hovered.xz(), SHORE_OFFSET) {
+                list_events.send(
+                    Fish {
+                        water_entity,
+                        shore_point: shore_point.extend(0.0).xzy(),
+                        water_point: hovered.0,
+                    }
+                    .into(),
+                );
+            }
+        }
+    }
+
+    fn start_navigation(
+        mut commands: Commands,
+        mut actors: Query<(&mut NavSettings, &mut NavDestination)>,
+        tasks: Query<(Entity, &Parent, &Fish, &TaskState), Changed<TaskState>>,
+    ) {
+        for (entity, parent, fish, &task_state) in &tasks {
+            if task_state == TaskState::Active {
+                let (mut nav_settings, mut dest) = actors
+                    .get_mut(**parent)
+                    .expect("actors should have navigation component");
+                *nav_settings = NavSettings::new(Movement::Walk.speed());
+                **dest = Some(fish.shore_point);
+                commands.entity(entity).insert(TaskProgress::default());
+            }
+        }
+    }
+
+    fn start_fishing(
+        animations: Res<AnimationLibrary>,
+        mut actors: Query<
+            (
+                &Children,
+                &NavDestination,
+                &mut Transform,
+                &mut AnimationState,
+            ),
+            Changed<NavDestination>,
+        >,
+        tasks: Query<(&Fish, &TaskState)>,
+    ) {
+        for (children, dest, mut transform, mut animation_state) in &mut actors {
+            if !dest.is_none() {
+                continue;
+            }
+
+            let Some((fish, _)) = tasks
+                .iter_many(children)
+                .find(|(_, &task_state)| task_state == TaskState::Active)
+            else {
+                continue;
+            };
+
+            let target = fish.water_point.with_y(transform.translation.y);
+            transform.look_at(target, Vec3::Y);
+
+            const CASTS: u32 = 5;
+            let montage = animations
+                .montage("fish")
+                .map(|montage| montage.with_repeat(RepeatAnimation::Count(CASTS)));
+            animation_state.play_montage(montage);
+        }
+    }
+
+    fn finish(
+        mut commands: Commands,
+        mut finish_events: EventReader<MontageFinished>,
+        mut actors: Query<(&Children, &mut Inventory)>,
+        mut water_bodies: Query<&mut SpawnerStock>,
+        tasks: Query<(Entity, &Fish, &TaskState)>,
+    ) {
+        for event in finish_events.read() {
+            let Ok((children, mut inventory)) = actors.get_mut(event.0) else {
+                continue;
+            };
+
+            let Some((entity, fish, _)) = tasks
+                .iter_many(children)
+                .find(|(.., &task_state)| task_state == TaskState::Active)
+            else {
+                continue;
+            };
+
+            match water_bodies
+                .get_mut(fish.water_entity)
+                .ok()
+                .and_then(|mut stock| stock.pop())
+            {
+                Some(collectable) => {
+                    info!("`{}` caught '{}'", event.0, collectable.name);
+                    inventory.push(collectable);
+                }
+                None => debug!("`{}` caught nothing", event.0),
+            }
+
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Fishing from the shore of a water body.
+#[derive(Clone, Component, Copy, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+struct Fish {
+    water_entity: Entity,
+
+    /// Point near the water from which the actor fishes.
+    shore_point: Vec3,
+
+    /// Hovered point on the water surface to face.
+    water_point: Vec3,
+}
+
+impl Task for Fish {
+    fn name(&self) -> &str {
+        "Fish"
+    }
+
+    fn groups(&self) -> TaskGroups {
+        TaskGroups::LEGS | TaskGroups::BOTH_HANDS
+    }
+}
+
+impl FromWorld for Fish {
+    fn from_world(_world: &mut World) -> Self {
+        Self {
+            water_entity: Entity::PLACEHOLDER,
+            shore_point: Default::default(),
+            water_point: Default::default(),
+        }
+    }
+}
+
+impl MapEntities for Fish {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.water_entity = entity_mapper.map_entity(self.water_entity);
+    }
+}
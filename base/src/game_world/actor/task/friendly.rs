@@ -1,13 +1,17 @@
 mod tell_secret;
+mod try_for_baby;
 
 use bevy::{app::PluginGroupBuilder, prelude::*};
 
 use tell_secret::TellSecretPlugin;
+use try_for_baby::TryForBabyPlugin;
 
 pub(super) struct FriendlyPlugins;
 
 impl PluginGroup for FriendlyPlugins {
     fn build(self) -> PluginGroupBuilder {
-        PluginGroupBuilder::start::<Self>().add(TellSecretPlugin)
+        PluginGroupBuilder::start::<Self>()
+            .add(TellSecretPlugin)
+            .add(TryForBabyPlugin)
     }
 }
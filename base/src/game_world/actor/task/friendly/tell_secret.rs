@@ -7,13 +7,12 @@ use bevy_replicon::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    asset::collection::Collection,
     core::GameState,
     game_world::{
         actor::{
-            animation_state::{AnimationState, Montage, MontageFinished},
+            animation_state::{AnimationLibrary, AnimationState, MontageFinished},
             task::{linked_task::LinkedTask, Task, TaskGroups, TaskList, TaskListSet, TaskState},
-            Actor, ActorAnimation, Movement,
+            Actor, Movement,
         },
         hover::Hovered,
         navigation::{following::Following, NavDestination, NavSettings},
@@ -71,7 +70,7 @@ impl TellSecretPlugin {
 
     fn start_telling(
         mut commands: Commands,
-        actor_animations: Res<Collection<ActorAnimation>>,
+        animations: Res<AnimationLibrary>,
         mut actors: Query<
             (Entity, &Children, &NavDestination, &mut AnimationState),
             Changed<NavDestination>,
@@ -90,7 +89,7 @@ impl TellSecretPlugin {
                 continue;
             };
 
-            let montage = Montage::new(actor_animations.handle(ActorAnimation::TellSecret));
+            let montage = animations.montage("tell_secret");
             animator.play_montage(montage);
 
             // TODO: Handle cancellation of currently active tasks.
@@ -101,7 +100,7 @@ impl TellSecretPlugin {
     }
 
     fn start_listening(
-        actor_animations: Res<Collection<ActorAnimation>>,
+        animations: Res<AnimationLibrary>,
         tasks: Query<(&ListenSecret, &Parent, &TaskState), Changed<TaskState>>,
         mut actors: Query<(&mut Transform, &mut AnimationState)>,
     ) {
@@ -115,8 +114,9 @@ impl TellSecretPlugin {
                     .expect("listener should have transform and animation");
 
                 listen_transform.look_at(tell_transform.translation, Vec3::Y);
-                let montage = Montage::new(actor_animations.handle(ActorAnimation::ThoughtfulNod))
-                    .with_repeat(RepeatAnimation::Forever);
+                let montage = animations
+                    .montage("thoughtful_nod")
+                    .map(|montage| montage.with_repeat(RepeatAnimation::Forever));
                 animation_state.play_montage(montage);
             }
         }
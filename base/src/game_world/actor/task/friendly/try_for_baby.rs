@@ -0,0 +1,283 @@
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use rand::seq::IteratorRandom;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{
+            age::Age,
+            animation_state::{AnimationLibrary, AnimationState, MontageFinished},
+            genetics::Genes,
+            human::Human,
+            name_generator::NameGenerator,
+            outfit::{CurrentOutfit, Outfits},
+            task::{Task, TaskGroups, TaskList, TaskListSet, TaskState},
+            Actor, LastName, Movement, SelectedActor, Sex,
+        },
+        clock::GameClock,
+        family::memories::{MemoryKind, MemoryRecord},
+        hover::Hovered,
+        navigation::{NavDestination, NavSettings, NavigationBundle},
+        rng::{RngStream, WorldRng},
+    },
+};
+
+pub(super) struct TryForBabyPlugin;
+
+impl Plugin for TryForBabyPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TryForBaby>()
+            .register_type::<Pregnancy>()
+            .replicate_mapped::<TryForBaby>()
+            .replicate::<Pregnancy>()
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    Self::start_trying,
+                    (Self::finish, Self::give_birth).run_if(server_or_singleplayer),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            // Should run in `PostUpdate` to let tiles initialize.
+            .add_systems(
+                PostUpdate,
+                Self::start_navigation.run_if(server_or_singleplayer),
+            );
+    }
+}
+
+/// In-game days between conception and birth.
+const PREGNANCY_DAYS: u32 = 3;
+
+impl TryForBabyPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        hovered_actors: Query<(Entity, &Actor, &Age, Has<Pregnancy>), With<Hovered>>,
+        selected_actors: Query<(Entity, &Actor, &Age, Has<Pregnancy>), With<SelectedActor>>,
+    ) {
+        let Ok((partner_entity, partner, partner_age, partner_pregnant)) =
+            hovered_actors.get_single()
+        else {
+            return;
+        };
+        let Ok((actor_entity, actor, actor_age, actor_pregnant)) = selected_actors.get_single()
+        else {
+            return;
+        };
+
+        if actor_entity != partner_entity
+            && actor.family_entity == partner.family_entity
+            && actor_age.stage().is_fertile()
+            && partner_age.stage().is_fertile()
+            && !actor_pregnant
+            && !partner_pregnant
+        {
+            list_events.send(TryForBaby(partner_entity).into());
+        }
+    }
+
+    fn start_navigation(
+        mut actors: Query<(&mut NavSettings, &mut NavDestination)>,
+        partners: Query<&Transform>,
+        tasks: Query<(&Parent, &TryForBaby, &TaskState), Changed<TaskState>>,
+    ) {
+        for (parent, try_for_baby, &task_state) in &tasks {
+            if task_state == TaskState::Active {
+                let Ok(partner_transform) = partners.get(try_for_baby.0) else {
+                    error!("`{try_for_baby:?}` points to an entity without transform");
+                    continue;
+                };
+
+                let (mut nav_settings, mut dest) = actors
+                    .get_mut(**parent)
+                    .expect("actors should have navigation component");
+                *nav_settings = NavSettings::new(Movement::Walk.speed()).with_offset(0.5);
+                **dest = Some(partner_transform.translation);
+            }
+        }
+    }
+
+    fn start_trying(
+        animations: Res<AnimationLibrary>,
+        mut actors: Query<
+            (&Children, &NavDestination, &mut AnimationState),
+            Changed<NavDestination>,
+        >,
+        tasks: Query<&TaskState, With<TryForBaby>>,
+    ) {
+        for (children, dest, mut animation_state) in &mut actors {
+            if dest.is_none()
+                && tasks
+                    .iter_many(children)
+                    .any(|&task_state| task_state == TaskState::Active)
+            {
+                // TODO: Replace with a proper animation.
+                let montage = animations.montage("thoughtful_nod");
+                animation_state.play_montage(montage);
+            }
+        }
+    }
+
+    /// Starts a pregnancy for one of the partners.
+    ///
+    /// The female partner carries the baby, for same-sex couples it's the task owner.
+    fn finish(
+        mut commands: Commands,
+        mut finish_events: EventReader<MontageFinished>,
+        clocks: Query<&GameClock>,
+        actors: Query<(&Children, &Sex, &Genes, Has<Pregnancy>)>,
+        tasks: Query<(Entity, &TryForBaby, &TaskState)>,
+    ) {
+        for event in finish_events.read() {
+            let Ok((children, &sex, &genes, pregnant)) = actors.get(event.0) else {
+                continue;
+            };
+
+            let Some((entity, try_for_baby, _)) = tasks
+                .iter_many(children)
+                .find(|(.., &task_state)| task_state == TaskState::Active)
+            else {
+                continue;
+            };
+
+            commands.entity(entity).despawn();
+
+            let Ok((_, &partner_sex, &partner_genes, partner_pregnant)) =
+                actors.get(try_for_baby.0)
+            else {
+                error!("`{try_for_baby:?}` points to an entity that is not an actor");
+                continue;
+            };
+
+            if pregnant || partner_pregnant {
+                debug!("`{}` or `{}` is already pregnant", event.0, try_for_baby.0);
+                continue;
+            }
+
+            let Ok(clock) = clocks.get_single() else {
+                error!("unable to start pregnancy without a clock");
+                continue;
+            };
+
+            let (mother_entity, father_genes) = if sex != Sex::Female && partner_sex == Sex::Female
+            {
+                (try_for_baby.0, genes)
+            } else {
+                (event.0, partner_genes)
+            };
+
+            info!("`{mother_entity}` is expecting a baby");
+            commands.entity(mother_entity).insert(Pregnancy {
+                partner_genes: father_genes,
+                due_day: clock.day() + PREGNANCY_DAYS,
+            });
+        }
+    }
+
+    /// Spawns a newborn with genes of both parents when the pregnancy is due.
+    fn give_birth(
+        mut commands: Commands,
+        mut record_events: EventWriter<MemoryRecord>,
+        mut world_rng: ResMut<WorldRng>,
+        name_generator: NameGenerator,
+        clocks: Query<&GameClock, Changed<GameClock>>,
+        actors: Query<(
+            Entity,
+            &Parent,
+            &Actor,
+            &Name,
+            &Transform,
+            &LastName,
+            &Genes,
+            &Pregnancy,
+        )>,
+    ) {
+        let Ok(clock) = clocks.get_single() else {
+            return;
+        };
+
+        for (entity, parent, actor, name, transform, last_name, &genes, pregnancy) in &actors {
+            if pregnancy.due_day > clock.day() {
+                continue;
+            }
+
+            commands.entity(entity).remove::<Pregnancy>();
+
+            let rng = world_rng.stream(RngStream::Ai);
+            let sex = Sex::iter()
+                .choose(rng)
+                .expect("sex should have at least one variant");
+            let Some((first_name, _)) = name_generator.generate(rng, sex, None) else {
+                error!("unable to generate a name for the newborn");
+                continue;
+            };
+
+            info!("`{entity}` gives birth to a baby named '{}'", first_name.0);
+            record_events.send(MemoryRecord {
+                family_entity: actor.family_entity,
+                kind: MemoryKind::Birth,
+                description: format!("{name} gave birth to {}", first_name.0),
+            });
+            commands.entity(**parent).with_children(|parent| {
+                parent.spawn((
+                    ParentSync::default(),
+                    *transform,
+                    NavigationBundle::default(),
+                    Actor {
+                        family_entity: actor.family_entity,
+                    },
+                    Human,
+                    first_name,
+                    last_name.clone(),
+                    sex,
+                    Age::default(),
+                    Outfits::default(),
+                    CurrentOutfit::default(),
+                    Genes::inherit(genes, pregnancy.partner_genes, rng),
+                    Replicated,
+                ));
+            });
+        }
+    }
+}
+
+/// Expected baby with genes of the other parent.
+#[derive(Component, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+struct Pregnancy {
+    partner_genes: Genes,
+    due_day: u32,
+}
+
+#[derive(Component, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+struct TryForBaby(Entity);
+
+impl Task for TryForBaby {
+    fn name(&self) -> &str {
+        "Try for baby"
+    }
+
+    fn groups(&self) -> TaskGroups {
+        TaskGroups::LEGS | TaskGroups::BOTH_HANDS
+    }
+}
+
+impl FromWorld for TryForBaby {
+    fn from_world(_world: &mut World) -> Self {
+        Self(Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for TryForBaby {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
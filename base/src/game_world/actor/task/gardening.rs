@@ -0,0 +1,259 @@
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{
+            animation_state::{AnimationLibrary, AnimationState, MontageFinished},
+            task::{Task, TaskGroups, TaskList, TaskListSet, TaskState},
+            Movement,
+        },
+        collectable::Inventory,
+        hover::Hovered,
+        navigation::{NavDestination, NavSettings},
+        object::{
+            garden_plot::{GardenPlot, Plant, PlantBundle, PlantCare},
+            wear::{Broken, ObjectUsed},
+        },
+    },
+};
+
+pub(super) struct GardeningPlugin;
+
+impl Plugin for GardeningPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Gardening>()
+            .replicate_mapped::<Gardening>()
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    Self::start_working,
+                    Self::finish.run_if(server_or_singleplayer),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            // Should run in `PostUpdate` to let tiles initialize.
+            .add_systems(
+                PostUpdate,
+                Self::start_navigation.run_if(server_or_singleplayer),
+            );
+    }
+}
+
+/// Minimum care values to show the corresponding tasks.
+const WATER_THRESHOLD: f32 = 0.6;
+const WEEDS_THRESHOLD: f32 = 0.2;
+
+impl GardeningPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        plots: Query<(Entity, &GardenPlot, Option<&Children>), (With<Hovered>, Without<Broken>)>,
+        plants: Query<(&Plant, &PlantCare)>,
+    ) {
+        let Ok((plot_entity, plot, children)) = plots.get_single() else {
+            return;
+        };
+
+        let plant = children.and_then(|children| plants.iter_many(children).next());
+        let Some((plant, care)) = plant else {
+            for (index, crop) in plot.crops.iter().enumerate() {
+                list_events.send(
+                    Gardening {
+                        plot_entity,
+                        action: GardeningAction::Plant {
+                            crop: index,
+                            name: format!("Plant {}", crop.name),
+                        },
+                    }
+                    .into(),
+                );
+            }
+            return;
+        };
+
+        let mut send = |action| {
+            list_events.send(
+                Gardening {
+                    plot_entity,
+                    action,
+                }
+                .into(),
+            );
+        };
+        if plot.is_grown(plant) {
+            send(GardeningAction::Harvest);
+        } else if care.hydration < WATER_THRESHOLD {
+            send(GardeningAction::Water);
+        }
+        if care.weeds > WEEDS_THRESHOLD {
+            send(GardeningAction::Weed);
+        }
+    }
+
+    fn start_navigation(
+        mut actors: Query<(&mut NavSettings, &mut NavDestination)>,
+        plots: Query<&Transform>,
+        tasks: Query<(&Parent, &Gardening, &TaskState), Changed<TaskState>>,
+    ) {
+        for (parent, gardening, &task_state) in &tasks {
+            if task_state == TaskState::Active {
+                let Ok(plot_transform) = plots.get(gardening.plot_entity) else {
+                    error!("`{gardening:?}` points to an entity without transform");
+                    continue;
+                };
+
+                let (mut nav_settings, mut dest) = actors
+                    .get_mut(**parent)
+                    .expect("actors should have navigation component");
+                *nav_settings = NavSettings::new(Movement::Walk.speed()).with_offset(1.0);
+                **dest = Some(plot_transform.translation);
+            }
+        }
+    }
+
+    fn start_working(
+        animations: Res<AnimationLibrary>,
+        mut actors: Query<
+            (&Children, &NavDestination, &mut AnimationState),
+            Changed<NavDestination>,
+        >,
+        tasks: Query<&TaskState, With<Gardening>>,
+    ) {
+        for (children, dest, mut animation_state) in &mut actors {
+            if dest.is_none()
+                && tasks
+                    .iter_many(children)
+                    .any(|&task_state| task_state == TaskState::Active)
+            {
+                // TODO: Replace with proper gardening animations.
+                let montage = animations.montage("thoughtful_nod");
+                animation_state.play_montage(montage);
+            }
+        }
+    }
+
+    fn finish(
+        mut commands: Commands,
+        mut finish_events: EventReader<MontageFinished>,
+        mut used_events: EventWriter<ObjectUsed>,
+        mut actors: Query<(&Children, &mut Inventory)>,
+        plots: Query<(&GardenPlot, Option<&Children>)>,
+        mut plants: Query<(Entity, &Plant, &mut PlantCare)>,
+        tasks: Query<(Entity, &Gardening, &TaskState)>,
+    ) {
+        for event in finish_events.read() {
+            let Ok((children, mut inventory)) = actors.get_mut(event.0) else {
+                continue;
+            };
+
+            let Some((task_entity, gardening, _)) = tasks
+                .iter_many(children)
+                .find(|(.., &task_state)| task_state == TaskState::Active)
+            else {
+                continue;
+            };
+            commands.entity(task_entity).despawn();
+
+            let Ok((plot, plot_children)) = plots.get(gardening.plot_entity) else {
+                error!("`{gardening:?}` points to not a garden plot");
+                continue;
+            };
+
+            used_events.send(ObjectUsed(gardening.plot_entity));
+
+            let plant_entity = plot_children
+                .and_then(|children| children.iter().find(|&&entity| plants.contains(entity)))
+                .copied();
+
+            match (&gardening.action, plant_entity) {
+                (GardeningAction::Plant { crop, .. }, None) => {
+                    info!("`{}` plants crop {crop}", event.0);
+                    commands
+                        .entity(gardening.plot_entity)
+                        .with_children(|parent| {
+                            parent.spawn(PlantBundle::new(*crop));
+                        });
+                }
+                (GardeningAction::Water, Some(plant_entity)) => {
+                    info!("`{}` waters `{plant_entity}`", event.0);
+                    let (.., mut care) = plants.get_mut(plant_entity).unwrap();
+                    care.hydration = 1.0;
+                }
+                (GardeningAction::Weed, Some(plant_entity)) => {
+                    info!("`{}` removes weeds from `{plant_entity}`", event.0);
+                    let (.., mut care) = plants.get_mut(plant_entity).unwrap();
+                    care.weeds = 0.0;
+                }
+                (GardeningAction::Harvest, Some(plant_entity)) => {
+                    let (_, &plant, _) = plants.get(plant_entity).unwrap();
+                    let Some(crop) = plot.crops.get(plant.crop()) else {
+                        error!("`{plant_entity}` has invalid crop");
+                        continue;
+                    };
+                    if plot.is_grown(&plant) {
+                        info!("`{}` harvests '{}'", event.0, crop.name);
+                        inventory.push(crop.harvest.clone());
+                        commands.entity(plant_entity).despawn_recursive();
+                    }
+                }
+                (action, _) => debug!("ignoring outdated gardening action `{action:?}`"),
+            }
+        }
+    }
+}
+
+#[derive(Component, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+struct Gardening {
+    plot_entity: Entity,
+    action: GardeningAction,
+}
+
+impl Task for Gardening {
+    fn name(&self) -> &str {
+        match &self.action {
+            GardeningAction::Plant { name, .. } => name,
+            GardeningAction::Water => "Water plant",
+            GardeningAction::Weed => "Remove weeds",
+            GardeningAction::Harvest => "Harvest",
+        }
+    }
+
+    fn groups(&self) -> TaskGroups {
+        TaskGroups::LEGS | TaskGroups::BOTH_HANDS
+    }
+}
+
+impl FromWorld for Gardening {
+    fn from_world(_world: &mut World) -> Self {
+        Self {
+            plot_entity: Entity::PLACEHOLDER,
+            action: GardeningAction::Water,
+        }
+    }
+}
+
+impl MapEntities for Gardening {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.plot_entity = entity_mapper.map_entity(self.plot_entity);
+    }
+}
+
+#[derive(Debug, Deserialize, Reflect, Serialize)]
+enum GardeningAction {
+    Plant {
+        /// Index of the crop inside [`GardenPlot::crops`].
+        crop: usize,
+        /// Displayed task name.
+        name: String,
+    },
+    Water,
+    Weed,
+    Harvest,
+}
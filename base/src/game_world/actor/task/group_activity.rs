@@ -0,0 +1,193 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+
+use super::TaskState;
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{relationships::Relationships, Actor},
+        family::memories::{MemoryKind, MemoryRecord},
+    },
+};
+
+/// Coordinates tasks of multiple actors that share an activity on the same object.
+pub(super) struct GroupActivityPlugin;
+
+impl Plugin for GroupActivityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GroupFinished>().add_systems(
+            Update,
+            (Self::start, Self::tick)
+                .chain()
+                .run_if(server_or_singleplayer)
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+/// How long ready members wait for others before starting without them.
+const MAX_WAIT: Duration = Duration::from_secs(20);
+
+/// Friendship gain for each pair of participants.
+const RELATIONSHIP_GAIN: f32 = 5.0;
+
+/// Minimum number of participants for an activity to be remembered as a party.
+const PARTY_SIZE: usize = 4;
+
+impl GroupActivityPlugin {
+    /// Starts activities on objects where all active members are ready
+    /// or someone waits for too long.
+    fn start(
+        mut commands: Commands,
+        time: Res<Time>,
+        mut members: Query<(&mut GroupMember, &TaskState)>,
+        objects: Query<Has<GroupActivity>>,
+    ) {
+        let mut groups: Vec<(Entity, Duration, bool)> = Vec::new();
+        for (mut member, _) in members
+            .iter_mut()
+            .filter(|(_, &task_state)| task_state == TaskState::Active)
+        {
+            if member.ready {
+                member.waited += time.delta();
+            }
+            match groups
+                .iter_mut()
+                .find(|(object_entity, ..)| *object_entity == member.object_entity)
+            {
+                Some((.., ready)) => *ready &= member.ready,
+                None => groups.push((member.object_entity, member.duration, member.ready)),
+            }
+        }
+
+        for (object_entity, duration, ready) in groups {
+            let timed_out = || {
+                members.iter().any(|(member, &task_state)| {
+                    task_state == TaskState::Active
+                        && member.object_entity == object_entity
+                        && member.waited >= MAX_WAIT
+                })
+            };
+            if (ready || timed_out()) && objects.get(object_entity).is_ok_and(|running| !running) {
+                debug!("starting group activity on `{object_entity}`");
+                commands
+                    .entity(object_entity)
+                    .insert(GroupActivity(Timer::new(duration, TimerMode::Once)));
+            }
+        }
+    }
+
+    fn tick(
+        mut commands: Commands,
+        mut finish_events: EventWriter<GroupFinished>,
+        mut record_events: EventWriter<MemoryRecord>,
+        time: Res<Time>,
+        mut activities: Query<(Entity, &mut GroupActivity)>,
+        mut actors: Query<&mut Relationships>,
+        families: Query<&Actor>,
+        members: Query<(Entity, &Parent, &GroupMember, &TaskState)>,
+    ) {
+        for (object_entity, mut activity) in &mut activities {
+            let participants: Vec<_> = members
+                .iter()
+                .filter(|(_, _, member, &task_state)| {
+                    member.object_entity == object_entity
+                        && member.ready
+                        && task_state == TaskState::Active
+                })
+                .map(|(entity, parent, ..)| (entity, **parent))
+                .collect();
+
+            if participants.is_empty() {
+                debug!("stopping group activity on `{object_entity}` without participants");
+                commands.entity(object_entity).remove::<GroupActivity>();
+                continue;
+            }
+
+            activity.0.tick(time.delta());
+            if activity.0.finished() {
+                debug!(
+                    "finishing group activity on `{object_entity}` with {} participants",
+                    participants.len()
+                );
+                for &(task_entity, actor_entity) in &participants {
+                    finish_events.send(GroupFinished {
+                        task_entity,
+                        participants: participants.len(),
+                    });
+
+                    let Ok(mut relationships) = actors.get_mut(actor_entity) else {
+                        continue;
+                    };
+                    for &(_, other_entity) in &participants {
+                        if other_entity != actor_entity {
+                            relationships.add(other_entity, RELATIONSHIP_GAIN);
+                        }
+                    }
+                }
+
+                if participants.len() >= PARTY_SIZE {
+                    let mut family_entities: Vec<_> = families
+                        .iter_many(participants.iter().map(|&(_, actor_entity)| actor_entity))
+                        .map(|actor| actor.family_entity)
+                        .collect();
+                    family_entities.sort_unstable();
+                    family_entities.dedup();
+                    for family_entity in family_entities {
+                        record_events.send(MemoryRecord {
+                            family_entity,
+                            kind: MemoryKind::Party,
+                            description: format!("Had a party with {} people", participants.len()),
+                        });
+                    }
+                }
+                commands.entity(object_entity).remove::<GroupActivity>();
+            }
+        }
+    }
+}
+
+/// Participation of a task in an activity shared by all actors with such tasks on the same object.
+///
+/// The activity starts when all active members are ready or after [`MAX_WAIT`]
+/// for a ready member, so a member that can't reach the object doesn't block others.
+/// Members that get ready later join the running activity.
+/// Finished activity improves relationships between participants.
+/// Server-only.
+#[derive(Component)]
+pub(super) struct GroupMember {
+    object_entity: Entity,
+    duration: Duration,
+
+    /// Whether the actor reached the object and waits for others.
+    pub(super) ready: bool,
+
+    /// How long the member waits since getting ready.
+    waited: Duration,
+}
+
+impl GroupMember {
+    pub(super) fn new(object_entity: Entity, duration: Duration) -> Self {
+        Self {
+            object_entity,
+            duration,
+            ready: false,
+            waited: Duration::ZERO,
+        }
+    }
+}
+
+/// Shared activity running on an object.
+#[derive(Component)]
+struct GroupActivity(Timer);
+
+/// Emitted for each participant when a group activity finishes.
+#[derive(Event)]
+pub(super) struct GroupFinished {
+    pub(super) task_entity: Entity,
+
+    /// Number of actors that took part in the activity.
+    pub(super) participants: usize,
+}
@@ -0,0 +1,156 @@
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{
+            animation_state::{AnimationLibrary, AnimationState, MontageFinished},
+            task::{Task, TaskGroups, TaskList, TaskListSet, TaskState},
+            Movement,
+        },
+        collectable::{Inventory, SpawnerStock},
+        hover::Hovered,
+        navigation::{NavDestination, NavSettings},
+    },
+};
+
+pub(super) struct HarvestPlugin;
+
+impl Plugin for HarvestPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Harvest>()
+            .replicate_mapped::<Harvest>()
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    Self::start_harvesting,
+                    Self::finish.run_if(server_or_singleplayer),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            // Should run in `PostUpdate` to let tiles initialize.
+            .add_systems(
+                PostUpdate,
+                Self::start_navigation.run_if(server_or_singleplayer),
+            );
+    }
+}
+
+impl HarvestPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        spawners: Query<(Entity, &SpawnerStock), With<Hovered>>,
+    ) {
+        if let Ok((entity, stock)) = spawners.get_single() {
+            if !stock.is_empty() {
+                list_events.send(Harvest(entity).into());
+            }
+        }
+    }
+
+    fn start_navigation(
+        mut actors: Query<(&mut NavSettings, &mut NavDestination)>,
+        spawners: Query<&Transform>,
+        tasks: Query<(&Parent, &Harvest, &TaskState), Changed<TaskState>>,
+    ) {
+        for (parent, harvest, &task_state) in &tasks {
+            if task_state == TaskState::Active {
+                let Ok(spawner_transform) = spawners.get(harvest.0) else {
+                    error!("`{harvest:?}` points to an entity without transform");
+                    continue;
+                };
+
+                let (mut nav_settings, mut dest) = actors
+                    .get_mut(**parent)
+                    .expect("actors should have navigation component");
+                *nav_settings = NavSettings::new(Movement::Walk.speed()).with_offset(1.0);
+                **dest = Some(spawner_transform.translation);
+            }
+        }
+    }
+
+    fn start_harvesting(
+        animations: Res<AnimationLibrary>,
+        mut actors: Query<
+            (&Children, &NavDestination, &mut AnimationState),
+            Changed<NavDestination>,
+        >,
+        tasks: Query<&TaskState, With<Harvest>>,
+    ) {
+        for (children, dest, mut animation_state) in &mut actors {
+            if dest.is_none()
+                && tasks
+                    .iter_many(children)
+                    .any(|&task_state| task_state == TaskState::Active)
+            {
+                let montage = animations.montage("harvest");
+                animation_state.play_montage(montage);
+            }
+        }
+    }
+
+    fn finish(
+        mut commands: Commands,
+        mut finish_events: EventReader<MontageFinished>,
+        mut actors: Query<(&Children, &mut Inventory)>,
+        mut spawners: Query<&mut SpawnerStock>,
+        tasks: Query<(Entity, &Harvest, &TaskState)>,
+    ) {
+        for event in finish_events.read() {
+            let Ok((children, mut inventory)) = actors.get_mut(event.0) else {
+                continue;
+            };
+
+            let Some((entity, harvest, _)) = tasks
+                .iter_many(children)
+                .find(|(.., &task_state)| task_state == TaskState::Active)
+            else {
+                continue;
+            };
+
+            if let Some(collectable) = spawners
+                .get_mut(harvest.0)
+                .ok()
+                .and_then(|mut stock| stock.pop())
+            {
+                info!("`{}` collected '{}'", event.0, collectable.name);
+                inventory.push(collectable);
+            }
+
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Picks up a collectable from a spawner.
+#[derive(Component, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+struct Harvest(Entity);
+
+impl Task for Harvest {
+    fn name(&self) -> &str {
+        "Collect"
+    }
+
+    fn groups(&self) -> TaskGroups {
+        TaskGroups::LEGS | TaskGroups::BOTH_HANDS
+    }
+}
+
+impl FromWorld for Harvest {
+    fn from_world(_world: &mut World) -> Self {
+        Self(Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for Harvest {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
@@ -0,0 +1,290 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{
+            animation_state::{AnimationLibrary, AnimationState},
+            needs::{Hunger, Need, Social},
+            task::{
+                group_activity::{GroupFinished, GroupMember},
+                Task, TaskBundle, TaskGroups, TaskList, TaskListSet, TaskState,
+            },
+            Actor, Movement,
+        },
+        hover::Hovered,
+        navigation::{NavDestination, NavSettings},
+        object::occupancy::{SlotKind, SlotOccupancy, SlotReservation, UseSlots},
+    },
+};
+
+pub(super) struct HaveMealPlugin;
+
+impl Plugin for HaveMealPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<HaveMeal>()
+            .replicate_mapped::<HaveMeal>()
+            .observe(Self::stop_eating)
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    Self::start_eating,
+                    (Self::get_ready, Self::invite, Self::finish).run_if(server_or_singleplayer),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            // Should run in `PostUpdate` to let tiles initialize.
+            .add_systems(
+                PostUpdate,
+                Self::start_navigation.run_if(server_or_singleplayer),
+            );
+    }
+}
+
+/// How long actors eat together.
+const MEAL_DURATION: Duration = Duration::from_secs(20);
+
+const HUNGER_GAIN: f32 = 40.0;
+
+/// Social gain for each other participant.
+const SOCIAL_GAIN: f32 = 10.0;
+
+impl HaveMealPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        occupancy: SlotOccupancy,
+        tables: Query<Entity, (With<Hovered>, With<UseSlots>)>,
+    ) {
+        if let Ok(table_entity) = tables.get_single() {
+            if occupancy
+                .free_slots(table_entity, SlotKind::Dining)
+                .next()
+                .is_some()
+            {
+                list_events.send(HaveMeal { table_entity }.into());
+            }
+        }
+    }
+
+    /// Navigates to the reserved place and joins the group.
+    ///
+    /// Reservation is inserted on task activation.
+    fn start_navigation(
+        mut commands: Commands,
+        mut actors: Query<(&Parent, &mut NavSettings, &mut NavDestination)>,
+        tables: Query<(&GlobalTransform, &UseSlots)>,
+        cities: Query<&GlobalTransform>,
+        tasks: Query<(Entity, &Parent, &HaveMeal, &SlotReservation), Added<SlotReservation>>,
+    ) {
+        for (entity, parent, have_meal, reservation) in &tasks {
+            let Ok((table_transform, slots)) = tables.get(have_meal.table_entity) else {
+                error!("`{have_meal:?}` points to an entity without slots");
+                continue;
+            };
+            let Some(slot) = slots.get(reservation.index) else {
+                error!("`{reservation:?}` points to a missing slot");
+                continue;
+            };
+
+            let (city_parent, mut nav_settings, mut dest) = actors
+                .get_mut(**parent)
+                .expect("actors should have navigation component");
+            let city_transform = cities
+                .get(**city_parent)
+                .expect("actors should be children of a city");
+            let slot_transform = slot.transform(table_transform, city_transform);
+
+            *nav_settings = NavSettings::new(Movement::Walk.speed());
+            **dest = Some(slot_transform.translation);
+
+            commands
+                .entity(entity)
+                .insert(GroupMember::new(have_meal.table_entity, MEAL_DURATION));
+        }
+    }
+
+    fn start_eating(
+        animations: Res<AnimationLibrary>,
+        mut actors: Query<
+            (&Children, &NavDestination, &mut AnimationState),
+            Changed<NavDestination>,
+        >,
+        tasks: Query<&TaskState, With<HaveMeal>>,
+    ) {
+        for (children, dest, mut animation_state) in &mut actors {
+            if dest.is_none()
+                && tasks
+                    .iter_many(children)
+                    .any(|&task_state| task_state == TaskState::Active)
+            {
+                let montage = animations.montage("eat");
+                animation_state.play_montage(montage);
+            }
+        }
+    }
+
+    /// Turns actors that reached their places to the table and marks them as ready.
+    fn get_ready(
+        mut actors: Query<
+            (&Parent, &Children, &NavDestination, &mut Transform),
+            Changed<NavDestination>,
+        >,
+        tables: Query<(&GlobalTransform, &UseSlots)>,
+        cities: Query<&GlobalTransform>,
+        mut tasks: Query<(&HaveMeal, &SlotReservation, &TaskState, &mut GroupMember)>,
+    ) {
+        for (city_parent, children, dest, mut transform) in &mut actors {
+            if !dest.is_none() {
+                continue;
+            }
+
+            let mut iter = tasks.iter_many_mut(children);
+            while let Some((have_meal, reservation, &task_state, mut member)) = iter.fetch_next() {
+                if task_state != TaskState::Active {
+                    continue;
+                }
+
+                if let Ok((table_transform, slots)) = tables.get(have_meal.table_entity) {
+                    if let Some(slot) = slots.get(reservation.index) {
+                        let city_transform = cities
+                            .get(**city_parent)
+                            .expect("actors should be children of a city");
+                        let slot_transform = slot.transform(table_transform, city_transform);
+                        transform.rotation = slot_transform.rotation;
+                    }
+                }
+
+                debug!("`{}` is ready for a meal", have_meal.table_entity);
+                member.ready = true;
+                break;
+            }
+        }
+    }
+
+    /// Invites idle family members to join.
+    fn invite(
+        mut commands: Commands,
+        occupancy: SlotOccupancy,
+        members: Query<(&Parent, &HaveMeal), Added<GroupMember>>,
+        actors: Query<(Entity, &Actor, Option<&Children>)>,
+        tasks: Query<(), With<TaskState>>,
+    ) {
+        for (parent, &have_meal) in &members {
+            let Ok((_, inviter, _)) = actors.get(**parent) else {
+                continue;
+            };
+
+            let free_slots = occupancy
+                .free_slots(have_meal.table_entity, SlotKind::Dining)
+                .count();
+            let is_idle = |children: Option<&Children>| {
+                children.map_or(true, |children| tasks.iter_many(children).next().is_none())
+            };
+            let idle_members = actors.iter().filter(|&(entity, actor, children)| {
+                entity != **parent
+                    && actor.family_entity == inviter.family_entity
+                    && is_idle(children)
+            });
+            for (entity, ..) in idle_members.take(free_slots) {
+                info!(
+                    "inviting `{entity}` to a meal at `{}`",
+                    have_meal.table_entity
+                );
+                commands.entity(entity).with_children(|parent| {
+                    parent.spawn((TaskBundle::new(&have_meal), have_meal));
+                });
+            }
+        }
+    }
+
+    fn finish(
+        mut commands: Commands,
+        mut finish_events: EventReader<GroupFinished>,
+        mut animation_states: Query<&mut AnimationState>,
+        mut needs: Query<(&mut Need, Has<Hunger>, Has<Social>)>,
+        actors: Query<&Children>,
+        tasks: Query<&Parent, With<HaveMeal>>,
+    ) {
+        for event in finish_events.read() {
+            let Ok(parent) = tasks.get(event.task_entity) else {
+                continue;
+            };
+
+            let children = actors.get(**parent).expect("actors should have needs");
+            let mut iter = needs.iter_many_mut(children);
+            while let Some((mut need, hunger, social)) = iter.fetch_next() {
+                if hunger {
+                    need.add(HUNGER_GAIN);
+                } else if social {
+                    need.add(SOCIAL_GAIN * (event.participants - 1) as f32);
+                }
+            }
+
+            let mut animation_state = animation_states
+                .get_mut(**parent)
+                .expect("actors should have animation state");
+            animation_state.stop_montage();
+
+            debug!("`{}` finished the meal", **parent);
+            commands.entity(event.task_entity).despawn();
+        }
+    }
+
+    /// Stops the looped montage on clients and after cancellation.
+    fn stop_eating(
+        trigger: Trigger<OnRemove, HaveMeal>,
+        tasks: Query<(&Parent, &TaskState)>,
+        mut actors: Query<&mut AnimationState>,
+    ) {
+        if let Ok((parent, &task_state)) = tasks.get(trigger.entity()) {
+            if task_state == TaskState::Active {
+                if let Ok(mut animation_state) = actors.get_mut(**parent) {
+                    animation_state.stop_montage();
+                }
+            }
+        }
+    }
+}
+
+/// Eating together with other actors at a table.
+#[derive(Clone, Component, Copy, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+struct HaveMeal {
+    table_entity: Entity,
+}
+
+impl Task for HaveMeal {
+    fn name(&self) -> &str {
+        "Have a meal"
+    }
+
+    fn groups(&self) -> TaskGroups {
+        TaskGroups::LEGS | TaskGroups::BOTH_HANDS
+    }
+
+    fn used_slot(&self) -> Option<(Entity, SlotKind)> {
+        Some((self.table_entity, SlotKind::Dining))
+    }
+}
+
+impl FromWorld for HaveMeal {
+    fn from_world(_world: &mut World) -> Self {
+        Self {
+            table_entity: Entity::PLACEHOLDER,
+        }
+    }
+}
+
+impl MapEntities for HaveMeal {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.table_entity = entity_mapper.map_entity(self.table_entity);
+    }
+}
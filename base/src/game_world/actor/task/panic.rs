@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{
+            task::{Task, TaskBundle, TaskGroups, TaskPriority, TaskState},
+            Actor, Movement,
+        },
+        navigation::{NavDestination, NavSettings},
+        object::fire::Burning,
+    },
+};
+
+/// Makes actors run away from a starting fire.
+pub(super) struct PanicPlugin;
+
+impl Plugin for PanicPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Panic>()
+            .replicate::<Panic>()
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_actors.run_if(server_or_singleplayer),
+                    Self::finish,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            // Should run in `PostUpdate` to let tiles initialize.
+            .add_systems(
+                PostUpdate,
+                Self::start_navigation.run_if(server_or_singleplayer),
+            );
+    }
+}
+
+/// Maximum distance to a fire at which actors panic.
+const PANIC_RADIUS: f32 = 6.0;
+
+/// How far actors run from a fire.
+const PANIC_DISTANCE: f32 = 4.0;
+
+impl PanicPlugin {
+    fn add_to_actors(
+        mut commands: Commands,
+        fires: Query<(&Parent, &Transform), Added<Burning>>,
+        actors: Query<(Entity, &Parent, &Transform, Option<&Children>), With<Actor>>,
+        tasks: Query<(), With<Panic>>,
+    ) {
+        for (fire_parent, fire_transform) in &fires {
+            for (actor_entity, actor_parent, actor_transform, children) in &actors {
+                let offset = actor_transform.translation - fire_transform.translation;
+                if actor_parent != fire_parent || offset.length() > PANIC_RADIUS {
+                    continue;
+                }
+
+                if children.is_some_and(|children| tasks.iter_many(children).next().is_some()) {
+                    continue;
+                }
+
+                let direction = Vec3::new(offset.x, 0.0, offset.z)
+                    .try_normalize()
+                    .unwrap_or(Vec3::X);
+                let panic = Panic {
+                    endpoint: actor_transform.translation + direction * PANIC_DISTANCE,
+                };
+                info!("`{actor_entity}` panics because of fire");
+                commands.entity(actor_entity).with_children(|parent| {
+                    parent.spawn((TaskBundle::new(&panic), panic));
+                });
+            }
+        }
+    }
+
+    fn start_navigation(
+        mut actors: Query<(&mut NavSettings, &mut NavDestination)>,
+        tasks: Query<(&Parent, &Panic, &TaskState), Changed<TaskState>>,
+    ) {
+        for (parent, panic, &task_state) in &tasks {
+            if task_state == TaskState::Active {
+                let (mut nav_settings, mut dest) = actors
+                    .get_mut(**parent)
+                    .expect("actors should have navigation component");
+                *nav_settings = NavSettings::new(Movement::Run.speed());
+                **dest = Some(panic.endpoint);
+            }
+        }
+    }
+
+    fn finish(
+        mut commands: Commands,
+        actors: Query<(&Children, &NavDestination), Changed<NavDestination>>,
+        tasks: Query<(Entity, &TaskState), With<Panic>>,
+    ) {
+        for (children, dest) in &actors {
+            if dest.is_none() {
+                if let Some((entity, _)) = tasks
+                    .iter_many(children)
+                    .find(|(_, &task_state)| task_state == TaskState::Active)
+                {
+                    commands.entity(entity).despawn();
+                }
+            }
+        }
+    }
+}
+
+/// Running away from a fire.
+///
+/// Assigned automatically.
+#[derive(Clone, Component, Copy, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+struct Panic {
+    endpoint: Vec3,
+}
+
+impl Task for Panic {
+    fn name(&self) -> &str {
+        "Panic"
+    }
+
+    fn groups(&self) -> TaskGroups {
+        TaskGroups::LEGS
+    }
+
+    fn priority(&self) -> TaskPriority {
+        TaskPriority::Emergency
+    }
+}
@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game_world::{
+    actor::{
+        task::{Task, TaskList, TaskListSet, TaskState},
+        Actor, SelectedActor,
+    },
+    family::{utilities::UtilityBills, Budget},
+    hover::Hovered,
+};
+
+pub(super) struct PayBillsPlugin;
+
+impl Plugin for PayBillsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PayBills>()
+            .replicate::<PayBills>()
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    Self::pay.run_if(server_or_singleplayer),
+                ),
+            );
+    }
+}
+
+impl PayBillsPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        actors: Query<&Actor, (With<SelectedActor>, With<Hovered>)>,
+        families: Query<&UtilityBills>,
+    ) {
+        let Ok(actor) = actors.get_single() else {
+            return;
+        };
+
+        if families
+            .get(actor.family_entity)
+            .is_ok_and(|bills| bills.due > 0)
+        {
+            list_events.send(PayBills.into());
+        }
+    }
+
+    fn pay(
+        mut commands: Commands,
+        actors: Query<&Actor>,
+        mut families: Query<(&mut Budget, &mut UtilityBills)>,
+        tasks: Query<(Entity, &Parent, &TaskState), (With<PayBills>, Changed<TaskState>)>,
+    ) {
+        for (entity, parent, &task_state) in &tasks {
+            if task_state == TaskState::Active {
+                let actor = actors
+                    .get(**parent)
+                    .expect("task should have assigned actors");
+                let (mut budget, mut bills) = families
+                    .get_mut(actor.family_entity)
+                    .expect("actor should belong to a family");
+
+                if budget.spend(bills.due) {
+                    info!("`{}` pays bills for {}", **parent, bills.due);
+                    *bills = Default::default();
+                } else {
+                    info!(
+                        "`{}` can't pay bills for {} with budget {}",
+                        **parent, bills.due, **budget
+                    );
+                }
+
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+#[derive(Clone, Component, Copy, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+struct PayBills;
+
+impl Task for PayBills {
+    fn name(&self) -> &str {
+        "Pay bills"
+    }
+}
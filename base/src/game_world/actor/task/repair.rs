@@ -0,0 +1,205 @@
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{
+            animation_state::{AnimationLibrary, AnimationState, MontageFinished},
+            skills::Handiness,
+            task::{Task, TaskGroups, TaskList, TaskListSet, TaskState},
+            Actor, Movement, SelectedActor,
+        },
+        family::Budget,
+        hover::Hovered,
+        navigation::{NavDestination, NavSettings},
+        object::{
+            fire::FireDamaged,
+            wear::{Broken, Condition, Durability},
+        },
+    },
+};
+
+pub(super) struct RepairPlugin;
+
+impl Plugin for RepairPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Repair>()
+            .replicate_mapped::<Repair>()
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    Self::start_repairing,
+                    Self::finish.run_if(server_or_singleplayer),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            // Should run in `PostUpdate` to let tiles initialize.
+            .add_systems(
+                PostUpdate,
+                Self::start_navigation.run_if(server_or_singleplayer),
+            );
+    }
+}
+
+/// Cost of repairing fire damage.
+const REPAIR_COST: u32 = 50;
+
+impl RepairPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        actors: Query<&Handiness, With<SelectedActor>>,
+        objects: Query<(Entity, Has<FireDamaged>, Option<&Durability>, Has<Broken>), With<Hovered>>,
+    ) {
+        let Ok((entity, fire_damaged, durability, broken)) = objects.get_single() else {
+            return;
+        };
+
+        // Broken objects require a skilled actor.
+        let skilled = actors.get_single().is_ok_and(|&handiness| {
+            durability.is_some_and(|durability| *handiness >= durability.repair_level)
+        });
+        if fire_damaged || (broken && skilled) {
+            list_events.send(Repair(entity).into());
+        }
+    }
+
+    fn start_navigation(
+        mut actors: Query<(&mut NavSettings, &mut NavDestination)>,
+        objects: Query<&Transform>,
+        tasks: Query<(&Parent, &Repair, &TaskState), Changed<TaskState>>,
+    ) {
+        for (parent, repair, &task_state) in &tasks {
+            if task_state == TaskState::Active {
+                let Ok(object_transform) = objects.get(repair.0) else {
+                    error!("`{repair:?}` points to an entity without transform");
+                    continue;
+                };
+
+                let (mut nav_settings, mut dest) = actors
+                    .get_mut(**parent)
+                    .expect("actors should have navigation component");
+                *nav_settings = NavSettings::new(Movement::Walk.speed()).with_offset(1.0);
+                **dest = Some(object_transform.translation);
+            }
+        }
+    }
+
+    fn start_repairing(
+        animations: Res<AnimationLibrary>,
+        mut actors: Query<
+            (&Children, &NavDestination, &mut AnimationState),
+            Changed<NavDestination>,
+        >,
+        tasks: Query<&TaskState, With<Repair>>,
+    ) {
+        for (children, dest, mut animation_state) in &mut actors {
+            if dest.is_none()
+                && tasks
+                    .iter_many(children)
+                    .any(|&task_state| task_state == TaskState::Active)
+            {
+                // TODO: Replace with a proper repairing animation.
+                let montage = animations.montage("thoughtful_nod");
+                animation_state.play_montage(montage);
+            }
+        }
+    }
+
+    fn finish(
+        mut commands: Commands,
+        mut finish_events: EventReader<MontageFinished>,
+        mut actors: Query<(&Actor, &Children, &mut Handiness)>,
+        mut families: Query<&mut Budget>,
+        mut objects: Query<(
+            Has<FireDamaged>,
+            Option<&Durability>,
+            Option<&mut Condition>,
+            Has<Broken>,
+        )>,
+        tasks: Query<(Entity, &Repair, &TaskState)>,
+    ) {
+        for event in finish_events.read() {
+            let Ok((actor, children, mut handiness)) = actors.get_mut(event.0) else {
+                continue;
+            };
+
+            let Some((entity, repair, _)) = tasks
+                .iter_many(children)
+                .find(|(.., &task_state)| task_state == TaskState::Active)
+            else {
+                continue;
+            };
+
+            let Ok((fire_damaged, durability, condition, broken)) = objects.get_mut(repair.0)
+            else {
+                commands.entity(entity).despawn();
+                continue;
+            };
+
+            if fire_damaged {
+                let mut budget = families
+                    .get_mut(actor.family_entity)
+                    .expect("actor should belong to a family");
+                if budget.spend(REPAIR_COST) {
+                    info!("`{}` repairs fire damage of `{}`", event.0, repair.0);
+                    commands.entity(repair.0).remove::<FireDamaged>();
+                } else {
+                    info!(
+                        "`{}` can't repair `{}` with budget {}",
+                        event.0, repair.0, **budget
+                    );
+                }
+            }
+
+            let repair_level = durability.map_or(0, |durability| durability.repair_level);
+            if broken && *handiness < repair_level {
+                info!(
+                    "`{}` is not handy enough to fix `{}` of level {repair_level}",
+                    event.0, repair.0
+                );
+            } else if broken {
+                info!("`{}` fixes `{}`", event.0, repair.0);
+                commands.entity(repair.0).remove::<Broken>();
+                if let Some(mut condition) = condition {
+                    *condition = Default::default();
+                }
+                handiness.improve();
+            }
+
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Fixes a damaged object.
+#[derive(Component, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+struct Repair(Entity);
+
+impl Task for Repair {
+    fn name(&self) -> &str {
+        "Repair"
+    }
+
+    fn groups(&self) -> TaskGroups {
+        TaskGroups::LEGS | TaskGroups::BOTH_HANDS
+    }
+}
+
+impl FromWorld for Repair {
+    fn from_world(_world: &mut World) -> Self {
+        Self(Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for Repair {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
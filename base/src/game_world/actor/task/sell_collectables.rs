@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game_world::{
+    actor::{
+        task::{Task, TaskList, TaskListSet, TaskState},
+        Actor, SelectedActor,
+    },
+    collectable::Inventory,
+    family::Budget,
+    hover::Hovered,
+};
+
+pub(super) struct SellCollectablesPlugin;
+
+impl Plugin for SellCollectablesPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SellCollectables>()
+            .replicate::<SellCollectables>()
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    Self::sell.run_if(server_or_singleplayer),
+                ),
+            );
+    }
+}
+
+impl SellCollectablesPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        actors: Query<&Inventory, (With<SelectedActor>, With<Hovered>)>,
+    ) {
+        if let Ok(inventory) = actors.get_single() {
+            if !inventory.is_empty() {
+                list_events.send(SellCollectables.into());
+            }
+        }
+    }
+
+    fn sell(
+        mut commands: Commands,
+        mut actors: Query<(&Actor, &mut Inventory)>,
+        mut families: Query<&mut Budget>,
+        tasks: Query<(Entity, &Parent, &TaskState), (With<SellCollectables>, Changed<TaskState>)>,
+    ) {
+        for (entity, parent, &task_state) in &tasks {
+            if task_state == TaskState::Active {
+                let (actor, mut inventory) = actors
+                    .get_mut(**parent)
+                    .expect("task should have assigned actors");
+                let mut budget = families
+                    .get_mut(actor.family_entity)
+                    .expect("actor should belong to a family");
+
+                let price = inventory.price();
+                info!(
+                    "`{}` sells {} collectables for {price}",
+                    **parent,
+                    inventory.len()
+                );
+                budget.earn(price);
+                inventory.clear();
+
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+#[derive(Clone, Component, Copy, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+struct SellCollectables;
+
+impl Task for SellCollectables {
+    fn name(&self) -> &str {
+        "Sell collectables"
+    }
+}
@@ -0,0 +1,217 @@
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asset::info::object_info::ObjectTag,
+    core::GameState,
+    game_world::{
+        actor::{
+            task::{Task, TaskGroups, TaskList, TaskListSet, TaskState},
+            Movement, ACTOR_RADIUS,
+        },
+        hover::Hovered,
+        navigation::{NavDestination, NavSettings},
+        object::{
+            occupancy::{SlotKind, SlotOccupancy, SlotReservation, UseSlots},
+            ObjectTags,
+        },
+    },
+};
+
+pub(super) struct SitPlugin;
+
+impl Plugin for SitPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Sit>()
+            .replicate_mapped::<Sit>()
+            .observe(Self::stand_up)
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    Self::sit_down.run_if(server_or_singleplayer),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            // Should run in `PostUpdate` to let tiles initialize.
+            .add_systems(
+                PostUpdate,
+                Self::start_navigation.run_if(server_or_singleplayer),
+            );
+    }
+}
+
+impl SitPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        occupancy: SlotOccupancy,
+        objects: Query<(Entity, &ObjectTags), (With<Hovered>, With<UseSlots>)>,
+    ) {
+        if let Ok((object_entity, tags)) = objects.get_single() {
+            if tags.contains(&ObjectTag::Seating)
+                && occupancy
+                    .free_slots(object_entity, SlotKind::Seat)
+                    .next()
+                    .is_some()
+            {
+                list_events.send(Sit { object_entity }.into());
+            }
+        }
+    }
+
+    /// Navigates to the reserved slot.
+    ///
+    /// Reservation is inserted on task activation.
+    fn start_navigation(
+        mut actors: Query<(&Parent, &mut NavSettings, &mut NavDestination)>,
+        objects: Query<(&GlobalTransform, &UseSlots)>,
+        cities: Query<&GlobalTransform>,
+        tasks: Query<(&Parent, &SlotReservation), (With<Sit>, Added<SlotReservation>)>,
+    ) {
+        for (parent, reservation) in &tasks {
+            let Ok((object_transform, slots)) = objects.get(reservation.object_entity) else {
+                error!("`{reservation:?}` points to an entity without slots");
+                continue;
+            };
+
+            let (city_parent, mut nav_settings, mut dest) = actors
+                .get_mut(**parent)
+                .expect("actors should have navigation component");
+            let city_transform = cities
+                .get(**city_parent)
+                .expect("actors should be children of a city");
+            let Some(slot) = slots.get(reservation.index) else {
+                error!("`{reservation:?}` points to a missing slot");
+                continue;
+            };
+            let slot_transform = slot.transform(object_transform, city_transform);
+
+            *nav_settings = NavSettings::new(Movement::Walk.speed());
+            **dest = Some(approach_point(slot_transform));
+        }
+    }
+
+    fn sit_down(
+        mut commands: Commands,
+        mut actors: Query<
+            (&Parent, &Children, &NavDestination, &mut Transform),
+            Changed<NavDestination>,
+        >,
+        objects: Query<(&GlobalTransform, &UseSlots)>,
+        cities: Query<&GlobalTransform>,
+        tasks: Query<(Entity, &SlotReservation, &TaskState), With<Sit>>,
+    ) {
+        for (city_parent, children, dest, mut transform) in &mut actors {
+            if !dest.is_none() {
+                continue;
+            }
+
+            let Some((task_entity, reservation, _)) = tasks
+                .iter_many(children)
+                .find(|(.., &task_state)| task_state == TaskState::Active)
+            else {
+                continue;
+            };
+
+            let Ok((object_transform, slots)) = objects.get(reservation.object_entity) else {
+                continue;
+            };
+            let Some(slot) = slots.get(reservation.index) else {
+                continue;
+            };
+            let city_transform = cities
+                .get(**city_parent)
+                .expect("actors should be children of a city");
+
+            // TODO: Play a sitting animation.
+            let slot_transform = slot.transform(object_transform, city_transform);
+            debug!("sitting on `{}`", reservation.object_entity);
+            transform.translation = slot_transform.translation;
+            transform.rotation = slot_transform.rotation;
+            commands.entity(task_entity).insert(Seated);
+        }
+    }
+
+    /// Moves the actor back in front of the seat.
+    ///
+    /// Seats are inside the object collider, so the actor
+    /// would be stuck after finishing or cancelling the task.
+    fn stand_up(
+        trigger: Trigger<OnRemove, Sit>,
+        mut actors: Query<(&Parent, &mut Transform)>,
+        objects: Query<(&GlobalTransform, &UseSlots)>,
+        cities: Query<&GlobalTransform>,
+        tasks: Query<(&Parent, &SlotReservation), With<Seated>>,
+    ) {
+        let Ok((parent, reservation)) = tasks.get(trigger.entity()) else {
+            return;
+        };
+        let Ok((city_parent, mut transform)) = actors.get_mut(**parent) else {
+            return;
+        };
+        let Ok((object_transform, slots)) = objects.get(reservation.object_entity) else {
+            return;
+        };
+        let Some(slot) = slots.get(reservation.index) else {
+            return;
+        };
+        let Ok(city_transform) = cities.get(**city_parent) else {
+            return;
+        };
+
+        let slot_transform = slot.transform(object_transform, city_transform);
+        debug!("standing up from `{}`", reservation.object_entity);
+        transform.translation = approach_point(slot_transform);
+    }
+}
+
+/// Returns a point in front of the slot.
+///
+/// Used instead of the slot translation for navigation since the slot is inside the object collider.
+fn approach_point(slot_transform: Transform) -> Vec3 {
+    const APPROACH_DISTANCE: f32 = ACTOR_RADIUS + 0.3;
+    slot_transform.translation + slot_transform.forward() * APPROACH_DISTANCE
+}
+
+/// Marks a sit task whose actor reached the seat.
+#[derive(Component)]
+struct Seated;
+
+/// Occupying a seat of an object until cancelled.
+#[derive(Clone, Component, Copy, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+struct Sit {
+    object_entity: Entity,
+}
+
+impl Task for Sit {
+    fn name(&self) -> &str {
+        "Sit"
+    }
+
+    fn groups(&self) -> TaskGroups {
+        TaskGroups::LEGS
+    }
+
+    fn used_slot(&self) -> Option<(Entity, SlotKind)> {
+        Some((self.object_entity, SlotKind::Seat))
+    }
+}
+
+impl FromWorld for Sit {
+    fn from_world(_world: &mut World) -> Self {
+        Self {
+            object_entity: Entity::PLACEHOLDER,
+        }
+    }
+}
+
+impl MapEntities for Sit {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.object_entity = entity_mapper.map_entity(self.object_entity);
+    }
+}
@@ -0,0 +1,295 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+    time::common_conditions::on_timer,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asset::info::object_info::ObjectTag,
+    core::GameState,
+    game_world::{
+        actor::{
+            needs::{Energy, Need},
+            outfit::OutfitCategory,
+            task::{Task, TaskGroups, TaskList, TaskListSet, TaskState},
+            Movement,
+        },
+        hover::Hovered,
+        navigation::{NavDestination, NavSettings},
+        object::{
+            occupancy::{SlotKind, SlotOccupancy, SlotReservation, UseSlots},
+            ObjectTags,
+        },
+    },
+};
+
+pub(super) struct SleepPlugin;
+
+impl Plugin for SleepPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Sleep>()
+            .replicate_mapped::<Sleep>()
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    Self::lie_down.run_if(server_or_singleplayer),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            // Should run in `PostUpdate` to let tiles initialize.
+            .add_systems(
+                PostUpdate,
+                Self::start_navigation.run_if(server_or_singleplayer),
+            )
+            .add_systems(
+                FixedUpdate,
+                Self::restore_energy
+                    .run_if(on_timer(Duration::from_secs(1)))
+                    .run_if(server_or_singleplayer),
+            );
+    }
+}
+
+/// Energy restored per second of sleep.
+const ENERGY_GAIN: f32 = 1.0;
+
+impl SleepPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        occupancy: SlotOccupancy,
+        objects: Query<(Entity, &ObjectTags), (With<Hovered>, With<UseSlots>)>,
+    ) {
+        if let Ok((bed_entity, tags)) = objects.get_single() {
+            if tags.contains(&ObjectTag::Sleeping)
+                && occupancy
+                    .free_slots(bed_entity, SlotKind::Bed)
+                    .next()
+                    .is_some()
+            {
+                list_events.send(Sleep { bed_entity }.into());
+            }
+        }
+    }
+
+    /// Navigates to the reserved slot.
+    ///
+    /// Reservation is inserted on task activation.
+    fn start_navigation(
+        mut actors: Query<(&Parent, &mut NavSettings, &mut NavDestination)>,
+        objects: Query<(&GlobalTransform, &UseSlots)>,
+        cities: Query<&GlobalTransform>,
+        tasks: Query<(&Parent, &SlotReservation), (With<Sleep>, Added<SlotReservation>)>,
+    ) {
+        for (parent, reservation) in &tasks {
+            let Ok((object_transform, slots)) = objects.get(reservation.object_entity) else {
+                error!("`{reservation:?}` points to an entity without slots");
+                continue;
+            };
+            let Some(slot) = slots.get(reservation.index) else {
+                error!("`{reservation:?}` points to a missing slot");
+                continue;
+            };
+
+            let (city_parent, mut nav_settings, mut dest) = actors
+                .get_mut(**parent)
+                .expect("actors should have navigation component");
+            let city_transform = cities
+                .get(**city_parent)
+                .expect("actors should be children of a city");
+            let slot_transform = slot.transform(object_transform, city_transform);
+
+            *nav_settings = NavSettings::new(Movement::Walk.speed()).with_offset(0.5);
+            **dest = Some(slot_transform.translation);
+        }
+    }
+
+    fn lie_down(
+        mut commands: Commands,
+        mut actors: Query<
+            (&Parent, &Children, &NavDestination, &mut Transform),
+            Changed<NavDestination>,
+        >,
+        objects: Query<(&GlobalTransform, &UseSlots)>,
+        cities: Query<&GlobalTransform>,
+        tasks: Query<(Entity, &SlotReservation, &TaskState), With<Sleep>>,
+    ) {
+        for (city_parent, children, dest, mut transform) in &mut actors {
+            if !dest.is_none() {
+                continue;
+            }
+
+            let Some((task_entity, reservation, _)) = tasks
+                .iter_many(children)
+                .find(|(.., &task_state)| task_state == TaskState::Active)
+            else {
+                continue;
+            };
+
+            let Ok((object_transform, slots)) = objects.get(reservation.object_entity) else {
+                continue;
+            };
+            let Some(slot) = slots.get(reservation.index) else {
+                error!("`{reservation:?}` points to a missing slot");
+                continue;
+            };
+            let city_transform = cities
+                .get(**city_parent)
+                .expect("actors should be children of a city");
+
+            // TODO: Play a sleeping animation.
+            let slot_transform = slot.transform(object_transform, city_transform);
+            debug!("lying down on `{}`", reservation.object_entity);
+            transform.translation = slot_transform.translation;
+            transform.rotation = slot_transform.rotation;
+            commands.entity(task_entity).insert(Asleep);
+        }
+    }
+
+    /// Restores energy of sleeping actors and wakes them up when rested.
+    fn restore_energy(
+        mut commands: Commands,
+        mut needs: Query<&mut Need, With<Energy>>,
+        actors: Query<&Children>,
+        tasks: Query<(Entity, &Parent, &TaskState), (With<Sleep>, With<Asleep>)>,
+    ) {
+        for (entity, parent, &task_state) in &tasks {
+            if task_state != TaskState::Active {
+                continue;
+            }
+
+            let Ok(children) = actors.get(**parent) else {
+                continue;
+            };
+            let Some(mut energy) = needs.iter_many_mut(children).fetch_next() else {
+                continue;
+            };
+
+            energy.add(ENERGY_GAIN);
+            if energy.0 >= 100.0 {
+                info!("`{}` wakes up", **parent);
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+/// Marks a sleep task whose actor reached the bed.
+#[derive(Component)]
+struct Asleep;
+
+/// Sleeping in a bed until the energy is restored.
+#[derive(Clone, Component, Copy, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+struct Sleep {
+    bed_entity: Entity,
+}
+
+impl Task for Sleep {
+    fn name(&self) -> &str {
+        "Sleep"
+    }
+
+    fn groups(&self) -> TaskGroups {
+        TaskGroups::LEGS | TaskGroups::BOTH_HANDS
+    }
+
+    fn outfit(&self) -> Option<OutfitCategory> {
+        Some(OutfitCategory::Sleep)
+    }
+
+    fn used_slot(&self) -> Option<(Entity, SlotKind)> {
+        Some((self.bed_entity, SlotKind::Bed))
+    }
+
+    fn private(&self) -> bool {
+        true
+    }
+}
+
+impl FromWorld for Sleep {
+    fn from_world(_world: &mut World) -> Self {
+        Self {
+            bed_entity: Entity::PLACEHOLDER,
+        }
+    }
+}
+
+impl MapEntities for Sleep {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.bed_entity = entity_mapper.map_entity(self.bed_entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game_world::actor::{task::TaskRequest, Actor},
+        test_world::TestWorld,
+    };
+
+    #[test]
+    fn alone() {
+        assert!(sleep_in_room(1), "actor should sleep alone");
+    }
+
+    #[test]
+    fn company() {
+        assert!(
+            !sleep_in_room(2),
+            "actor should refuse to sleep with others in the room"
+        );
+    }
+
+    /// Requests sleep for an actor in a room with the given number of actors.
+    ///
+    /// Returns `true` if the task wasn't refused.
+    fn sleep_in_room(actors_count: usize) -> bool {
+        let mut test_world = TestWorld::builder().build();
+        let city_entity = test_world.spawn_city("City");
+        let corners = [
+            Vec2::new(-3.0, -3.0),
+            Vec2::new(3.0, -3.0),
+            Vec2::new(3.0, 3.0),
+            Vec2::new(-3.0, 3.0),
+        ];
+        for (&start, &end) in corners.iter().zip(corners.iter().cycle().skip(1)) {
+            test_world.spawn_wall(city_entity, start, end);
+        }
+        let bed_entity = test_world.spawn_object(
+            city_entity,
+            "base/objects/outdoor_furniture/comfortable_bench/comfortable_bench.object.ron",
+            Transform::from_xyz(0.0, 0.0, 1.0),
+        );
+        let loaded =
+            test_world.tick_until(100, |world| world.get::<UseSlots>(bed_entity).is_some());
+        assert!(loaded, "object components should be loaded from metadata");
+
+        test_world.spawn_family(city_entity, "Family", actors_count);
+        test_world.tick();
+        let mut actors = test_world
+            .world_mut()
+            .query_filtered::<Entity, With<Actor>>();
+        let actor_entity = actors
+            .iter(test_world.world())
+            .next()
+            .expect("family should have actors");
+
+        test_world.world_mut().send_event(FromClient {
+            client_id: ClientId::SERVER,
+            event: TaskRequest {
+                entity: actor_entity,
+                task: Box::new(Sleep { bed_entity }),
+            },
+        });
+        test_world.tick_n(3);
+
+        let mut tasks = test_world.world_mut().query::<&Sleep>();
+        tasks.iter(test_world.world()).count() != 0
+    }
+}
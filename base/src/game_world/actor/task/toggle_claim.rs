@@ -0,0 +1,201 @@
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game_world::{
+    actor::{
+        task::{Task, TaskList, TaskListSet, TaskState},
+        Actor, SelectedActor,
+    },
+    city::lot::{LotFamily, LotObjects},
+    hover::Hovered,
+    object::claim::{Claimable, ClaimedBy},
+};
+
+pub(super) struct ToggleClaimPlugin;
+
+impl Plugin for ToggleClaimPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ToggleClaim>()
+            .replicate_mapped::<ToggleClaim>()
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    Self::apply.run_if(server_or_singleplayer),
+                ),
+            );
+    }
+}
+
+impl ToggleClaimPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        actors: Query<Entity, With<SelectedActor>>,
+        objects: Query<(Entity, Option<&ClaimedBy>), (With<Hovered>, With<Claimable>)>,
+    ) {
+        let Ok((object_entity, claimed_by)) = objects.get_single() else {
+            return;
+        };
+        let Ok(actor_entity) = actors.get_single() else {
+            return;
+        };
+
+        let claim = claimed_by.map_or(true, |claimed_by| claimed_by.0 != actor_entity);
+        list_events.send(
+            ToggleClaim {
+                object_entity,
+                claim,
+            }
+            .into(),
+        );
+    }
+
+    /// Applies the claim if the object is located on the lot of the actor's family.
+    fn apply(
+        mut commands: Commands,
+        objects: Query<(), With<Claimable>>,
+        actors: Query<&Actor>,
+        lots: Query<(&LotObjects, &LotFamily)>,
+        tasks: Query<(Entity, &Parent, &ToggleClaim, &TaskState), Changed<TaskState>>,
+    ) {
+        for (entity, parent, claim, &task_state) in &tasks {
+            if task_state != TaskState::Active {
+                continue;
+            }
+
+            let actor = actors
+                .get(**parent)
+                .expect("tasks should be children of actors");
+            let owned = lots.iter().any(|(lot_objects, lot_family)| {
+                lot_objects.contains(&claim.object_entity) && **lot_family == actor.family_entity
+            });
+            if !owned {
+                error!(
+                    "`{}` can't toggle claim on `{}` outside of the family lot",
+                    **parent, claim.object_entity
+                );
+            } else if objects.get(claim.object_entity).is_ok() {
+                if claim.claim {
+                    info!("`{}` claims `{}`", **parent, claim.object_entity);
+                    commands
+                        .entity(claim.object_entity)
+                        .insert(ClaimedBy(**parent));
+                } else {
+                    info!("`{}` unclaims `{}`", **parent, claim.object_entity);
+                    commands.entity(claim.object_entity).remove::<ClaimedBy>();
+                }
+            } else {
+                error!("`{claim:?}` points to an entity that can't be claimed");
+            }
+
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Claims an object as personal or releases the claim.
+#[derive(Clone, Component, Copy, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+struct ToggleClaim {
+    object_entity: Entity,
+    claim: bool,
+}
+
+impl Task for ToggleClaim {
+    fn name(&self) -> &str {
+        if self.claim {
+            "Claim"
+        } else {
+            "Unclaim"
+        }
+    }
+}
+
+impl FromWorld for ToggleClaim {
+    fn from_world(_world: &mut World) -> Self {
+        Self {
+            object_entity: Entity::PLACEHOLDER,
+            claim: true,
+        }
+    }
+}
+
+impl MapEntities for ToggleClaim {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.object_entity = entity_mapper.map_entity(self.object_entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game_world::{actor::task::TaskRequest, UnsavedChanges},
+        test_world::TestWorld,
+    };
+
+    #[test]
+    fn outside_family_lot() {
+        let mut test_world = TestWorld::builder().build();
+        let city_entity = test_world.spawn_city("City");
+        test_world.spawn_family(city_entity, "Family", 1);
+        let mut actors = test_world
+            .world_mut()
+            .query_filtered::<Entity, With<Actor>>();
+        let actor_entity = actors.single(test_world.world());
+
+        let object_entity = test_world
+            .world_mut()
+            .spawn((Claimable, Transform::default()))
+            .id();
+        test_world.world_mut().send_event(FromClient {
+            client_id: ClientId::SERVER,
+            event: TaskRequest {
+                entity: actor_entity,
+                task: Box::new(ToggleClaim {
+                    object_entity,
+                    claim: true,
+                }),
+            },
+        });
+
+        test_world.tick_n(3);
+        assert!(test_world.world().get::<ClaimedBy>(object_entity).is_none());
+        let mut tasks = test_world.world_mut().query::<&ToggleClaim>();
+        assert_eq!(tasks.iter(test_world.world()).count(), 0);
+    }
+
+    #[test]
+    fn unsaved_request() {
+        let mut test_world = TestWorld::builder().build();
+        let city_entity = test_world.spawn_city("City");
+        test_world.spawn_family(city_entity, "Family", 1);
+        let mut actors = test_world
+            .world_mut()
+            .query_filtered::<Entity, With<Actor>>();
+        let actor_entity = actors.single(test_world.world());
+        test_world.world_mut().resource_mut::<UnsavedChanges>().0 = false;
+
+        let object_entity = test_world
+            .world_mut()
+            .spawn((Claimable, Transform::default()))
+            .id();
+        test_world.world_mut().send_event(FromClient {
+            client_id: ClientId::SERVER,
+            event: TaskRequest {
+                entity: actor_entity,
+                task: Box::new(ToggleClaim {
+                    object_entity,
+                    claim: true,
+                }),
+            },
+        });
+        test_world.tick();
+
+        assert!(test_world.world().resource::<UnsavedChanges>().0);
+    }
+}
@@ -0,0 +1,176 @@
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{
+            animation_state::{AnimationLibrary, AnimationState, MontageFinished},
+            task::{Task, TaskGroups, TaskList, TaskListSet, TaskState},
+            Movement,
+        },
+        hover::Hovered,
+        navigation::{NavDestination, NavSettings},
+        object::{
+            lamp::LampState,
+            wear::{Broken, ObjectUsed},
+        },
+    },
+};
+
+pub(super) struct ToggleLampPlugin;
+
+impl Plugin for ToggleLampPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ToggleLamp>()
+            .replicate_mapped::<ToggleLamp>()
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    Self::start_toggling,
+                    Self::finish.run_if(server_or_singleplayer),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            // Should run in `PostUpdate` to let tiles initialize.
+            .add_systems(
+                PostUpdate,
+                Self::start_navigation.run_if(server_or_singleplayer),
+            );
+    }
+}
+
+impl ToggleLampPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        lamps: Query<(Entity, &LampState), (With<Hovered>, Without<Broken>)>,
+    ) {
+        if let Ok((lamp_entity, state)) = lamps.get_single() {
+            list_events.send(
+                ToggleLamp {
+                    lamp_entity,
+                    enable: !state.enabled,
+                }
+                .into(),
+            );
+        }
+    }
+
+    fn start_navigation(
+        mut actors: Query<(&mut NavSettings, &mut NavDestination)>,
+        lamps: Query<&Transform>,
+        tasks: Query<(&Parent, &ToggleLamp, &TaskState), Changed<TaskState>>,
+    ) {
+        for (parent, toggle, &task_state) in &tasks {
+            if task_state == TaskState::Active {
+                let Ok(lamp_transform) = lamps.get(toggle.lamp_entity) else {
+                    error!("`{toggle:?}` points to an entity without transform");
+                    continue;
+                };
+
+                let (mut nav_settings, mut dest) = actors
+                    .get_mut(**parent)
+                    .expect("actors should have navigation component");
+                *nav_settings = NavSettings::new(Movement::Walk.speed()).with_offset(0.5);
+                **dest = Some(lamp_transform.translation);
+            }
+        }
+    }
+
+    fn start_toggling(
+        animations: Res<AnimationLibrary>,
+        mut actors: Query<
+            (&Children, &NavDestination, &mut AnimationState),
+            Changed<NavDestination>,
+        >,
+        tasks: Query<&TaskState, With<ToggleLamp>>,
+    ) {
+        for (children, dest, mut animation_state) in &mut actors {
+            if dest.is_none()
+                && tasks
+                    .iter_many(children)
+                    .any(|&task_state| task_state == TaskState::Active)
+            {
+                // TODO: Replace with a proper switch animation.
+                let montage = animations.montage("thoughtful_nod");
+                animation_state.play_montage(montage);
+            }
+        }
+    }
+
+    fn finish(
+        mut commands: Commands,
+        mut finish_events: EventReader<MontageFinished>,
+        mut used_events: EventWriter<ObjectUsed>,
+        actors: Query<&Children>,
+        mut lamps: Query<&mut LampState>,
+        tasks: Query<(Entity, &ToggleLamp, &TaskState)>,
+    ) {
+        for event in finish_events.read() {
+            let Ok(children) = actors.get(event.0) else {
+                continue;
+            };
+
+            let Some((entity, toggle, _)) = tasks
+                .iter_many(children)
+                .find(|(.., &task_state)| task_state == TaskState::Active)
+            else {
+                continue;
+            };
+
+            if let Ok(mut state) = lamps.get_mut(toggle.lamp_entity) {
+                info!(
+                    "`{}` switches `{}` to {}",
+                    event.0, toggle.lamp_entity, toggle.enable
+                );
+                // Manual switch overrides the time of day.
+                state.enabled = toggle.enable;
+                state.automatic = false;
+                used_events.send(ObjectUsed(toggle.lamp_entity));
+            }
+
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[derive(Component, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+struct ToggleLamp {
+    lamp_entity: Entity,
+    enable: bool,
+}
+
+impl Task for ToggleLamp {
+    fn name(&self) -> &str {
+        if self.enable {
+            "Turn on"
+        } else {
+            "Turn off"
+        }
+    }
+
+    fn groups(&self) -> TaskGroups {
+        TaskGroups::LEGS | TaskGroups::LEFT_HAND
+    }
+}
+
+impl FromWorld for ToggleLamp {
+    fn from_world(_world: &mut World) -> Self {
+        Self {
+            lamp_entity: Entity::PLACEHOLDER,
+            enable: true,
+        }
+    }
+}
+
+impl MapEntities for ToggleLamp {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.lamp_entity = entity_mapper.map_entity(self.lamp_entity);
+    }
+}
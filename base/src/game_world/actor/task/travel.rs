@@ -0,0 +1,319 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    math::Vec3Swizzles,
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{
+            task::{Task, TaskGroups, TaskList, TaskListSet, TaskProgress, TaskState},
+            Movement, SelectedActor,
+        },
+        city::{lot::LotVertices, Ground},
+        hover::Hovered,
+        navigation::{NavDestination, NavSettings},
+    },
+};
+
+pub(super) struct TravelPlugin;
+
+impl Plugin for TravelPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Travel>()
+            .register_type::<InTransit>()
+            .replicate_mapped::<Travel>()
+            .replicate::<InTransit>()
+            .observe(Self::hide)
+            .observe(Self::show)
+            .observe(Self::cancel_transit)
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    (Self::pause, Self::depart, Self::arrive)
+                        .chain()
+                        .run_if(server_or_singleplayer),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            // Should run in `PostUpdate` to let tiles initialize.
+            .add_systems(
+                PostUpdate,
+                Self::start_navigation.run_if(server_or_singleplayer),
+            );
+    }
+}
+
+/// Simulated speed of a taxi between lots in meters per second.
+const TRAVEL_SPEED: f32 = 25.0;
+
+const MIN_TRAVEL_TIME: Duration = Duration::from_secs(3);
+
+impl TravelPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        grounds: Query<(&Parent, &Hovered), With<Ground>>,
+        actors: Query<(&Parent, &Transform), With<SelectedActor>>,
+        cities: Query<&GlobalTransform>,
+        lots: Query<(Entity, &Parent, &LotVertices)>,
+    ) {
+        let Ok((ground_parent, hovered)) = grounds.get_single() else {
+            return;
+        };
+        let Ok((actor_parent, actor_transform)) = actors.get_single() else {
+            return;
+        };
+
+        let city_transform = cities
+            .get(**ground_parent)
+            .expect("ground should be a child of a city");
+        let point = city_transform
+            .affine()
+            .inverse()
+            .transform_point3(hovered.0)
+            .xz();
+        let Some((lot_entity, ..)) = lots.iter().find(|(_, parent, vertices)| {
+            **parent == **ground_parent && vertices.contains_point(point)
+        }) else {
+            return;
+        };
+
+        let already_there = **actor_parent == **ground_parent
+            && lots.get(lot_entity).is_ok_and(|(.., vertices)| {
+                vertices.contains_point(actor_transform.translation.xz())
+            });
+        if !already_there {
+            list_events.send(Travel { lot_entity }.into());
+        }
+    }
+
+    /// Walks to the edge of the current lot or departs immediately if the actor is outside of any lot.
+    fn start_navigation(
+        mut commands: Commands,
+        mut actors: Query<(&Parent, &Transform, &mut NavSettings, &mut NavDestination)>,
+        lots: Query<(&Parent, &LotVertices)>,
+        tasks: Query<(Entity, &Parent, &TaskState), (With<Travel>, Changed<TaskState>)>,
+        transits: Query<(), With<InTransit>>,
+    ) {
+        for (entity, parent, &task_state) in &tasks {
+            if task_state != TaskState::Active || transits.get(**parent).is_ok() {
+                continue;
+            }
+
+            let (city_parent, transform, mut nav_settings, mut dest) = actors
+                .get_mut(**parent)
+                .expect("actors should have navigation component");
+            let position = transform.translation.xz();
+            let current_lot = lots.iter().find(|(lot_parent, vertices)| {
+                **lot_parent == **city_parent && vertices.contains_point(position)
+            });
+
+            if let Some((_, vertices)) = current_lot {
+                let exit_point = vertices
+                    .closest_boundary_point(position)
+                    .unwrap_or(position);
+                debug!("`{}` walks to the lot edge at {exit_point}", **parent);
+                *nav_settings = NavSettings::new(Movement::Walk.speed());
+                **dest = Some(Vec3::new(exit_point.x, 0.0, exit_point.y));
+            } else {
+                commands.entity(entity).insert(TaskProgress::default());
+                commands.entity(**parent).insert(InTransit);
+            }
+        }
+    }
+
+    /// Starts the transit after reaching the lot edge.
+    fn depart(
+        mut commands: Commands,
+        actors: Query<
+            (Entity, &Children, &NavDestination),
+            (Changed<NavDestination>, Without<InTransit>),
+        >,
+        tasks: Query<(Entity, &TaskState), With<Travel>>,
+    ) {
+        for (actor_entity, children, dest) in &actors {
+            if !dest.is_none() {
+                continue;
+            }
+
+            if let Some((entity, _)) = tasks
+                .iter_many(children)
+                .find(|(_, &task_state)| task_state == TaskState::Active)
+            {
+                commands.entity(entity).insert(TaskProgress::default());
+                commands.entity(actor_entity).insert(InTransit);
+            }
+        }
+    }
+
+    /// Simulates the trip and places the actor on the destination lot.
+    fn arrive(
+        mut commands: Commands,
+        time: Res<Time>,
+        mut actors: Query<(&Parent, &mut Transform), With<InTransit>>,
+        lots: Query<(&Parent, &LotVertices)>,
+        cities: Query<&GlobalTransform>,
+        mut tasks: Query<(
+            Entity,
+            &Parent,
+            &Travel,
+            &TaskState,
+            &mut TaskProgress,
+            Option<&mut TravelTimer>,
+        )>,
+    ) {
+        for (entity, parent, travel, &task_state, mut progress, timer) in &mut tasks {
+            if task_state != TaskState::Active {
+                continue;
+            }
+            let Ok((city_parent, mut transform)) = actors.get_mut(**parent) else {
+                continue;
+            };
+            let Ok((lot_parent, vertices)) = lots.get(travel.lot_entity) else {
+                error!("`{travel:?}` points to not a lot");
+                commands.entity(entity).despawn();
+                continue;
+            };
+
+            let Some(mut timer) = timer else {
+                let city_transform = cities
+                    .get(**city_parent)
+                    .expect("actors should be children of a city");
+                let lot_city_transform = cities
+                    .get(**lot_parent)
+                    .expect("lots should be children of a city");
+                let departure = city_transform.transform_point(transform.translation);
+                let center = vertices.center();
+                let arrival =
+                    lot_city_transform.transform_point(Vec3::new(center.x, 0.0, center.y));
+                let duration = Duration::from_secs_f32(departure.distance(arrival) / TRAVEL_SPEED)
+                    .max(MIN_TRAVEL_TIME);
+
+                info!(
+                    "`{}` departs to `{}` for {duration:?}",
+                    **parent, travel.lot_entity
+                );
+                commands
+                    .entity(entity)
+                    .insert(TravelTimer(Timer::new(duration, TimerMode::Once)));
+                continue;
+            };
+
+            timer.tick(time.delta());
+            progress.set_if_neq(TaskProgress::new(
+                timer.fraction(),
+                Some(timer.remaining_secs()),
+            ));
+            if !timer.finished() {
+                continue;
+            }
+
+            // Cities share the same local layout, so the closest point is meaningful for any city.
+            let position = transform.translation.xz();
+            let arrival_point = vertices
+                .closest_boundary_point(position)
+                .unwrap_or(position);
+            info!("`{}` arrives to `{}`", **parent, travel.lot_entity);
+            transform.translation = Vec3::new(arrival_point.x, 0.0, arrival_point.y);
+            if **city_parent != **lot_parent {
+                commands.entity(**parent).set_parent(**lot_parent);
+            }
+            commands.entity(**parent).remove::<InTransit>();
+            commands.entity(entity).despawn();
+        }
+    }
+
+    /// Returns the actor to the departure point until the task resumes.
+    fn pause(
+        mut commands: Commands,
+        tasks: Query<(&Parent, &TaskState), (With<Travel>, Changed<TaskState>)>,
+        actors: Query<(), With<InTransit>>,
+    ) {
+        for (parent, &task_state) in &tasks {
+            if task_state == TaskState::Paused && actors.get(**parent).is_ok() {
+                debug!("pausing transit of `{}`", **parent);
+                commands.entity(**parent).remove::<InTransit>();
+            }
+        }
+    }
+
+    fn hide(trigger: Trigger<OnAdd, InTransit>, mut actors: Query<&mut Visibility>) {
+        if let Ok(mut visibility) = actors.get_mut(trigger.entity()) {
+            debug!("hiding `{}` in transit", trigger.entity());
+            *visibility = Visibility::Hidden;
+        }
+    }
+
+    fn show(trigger: Trigger<OnRemove, InTransit>, mut actors: Query<&mut Visibility>) {
+        if let Ok(mut visibility) = actors.get_mut(trigger.entity()) {
+            debug!("showing `{}` after transit", trigger.entity());
+            *visibility = Visibility::Inherited;
+        }
+    }
+
+    /// Returns the actor to the departure point if the task is removed during the trip.
+    fn cancel_transit(
+        trigger: Trigger<OnRemove, Travel>,
+        mut commands: Commands,
+        tasks: Query<&Parent>,
+        actors: Query<(), With<InTransit>>,
+    ) {
+        let Ok(parent) = tasks.get(trigger.entity()) else {
+            return;
+        };
+        if actors.get(**parent).is_ok() {
+            debug!("interrupting transit of `{}`", **parent);
+            commands.entity(**parent).remove::<InTransit>();
+        }
+    }
+}
+
+/// Travelling to another lot, possibly in another city.
+#[derive(Clone, Component, Copy, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+struct Travel {
+    lot_entity: Entity,
+}
+
+impl Task for Travel {
+    fn name(&self) -> &str {
+        "Travel here"
+    }
+
+    fn groups(&self) -> TaskGroups {
+        TaskGroups::LEGS
+    }
+}
+
+impl FromWorld for Travel {
+    fn from_world(_world: &mut World) -> Self {
+        Self {
+            lot_entity: Entity::PLACEHOLDER,
+        }
+    }
+}
+
+impl MapEntities for Travel {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.lot_entity = entity_mapper.map_entity(self.lot_entity);
+    }
+}
+
+/// Marks an actor that left the lot and not yet arrived.
+///
+/// Actors in transit are hidden.
+#[derive(Component, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub(crate) struct InTransit;
+
+/// Remaining trip time.
+///
+/// Server-only.
+#[derive(Component, Deref, DerefMut)]
+struct TravelTimer(Timer);
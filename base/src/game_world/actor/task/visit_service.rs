@@ -0,0 +1,197 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    math::Vec3Swizzles,
+    prelude::*,
+    time::common_conditions::on_timer,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game_world::{
+    actor::task::{
+        travel::InTransit, Task, TaskGroups, TaskList, TaskListSet, TaskProgress, TaskState,
+    },
+    city::{
+        lot::{
+            community_lot::{LotService, ServiceEffects},
+            LotVertices,
+        },
+        Ground,
+    },
+    hover::Hovered,
+};
+
+pub(super) struct VisitServicePlugin;
+
+impl Plugin for VisitServicePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<VisitService>()
+            .replicate_mapped::<VisitService>()
+            .observe(Self::cancel_visit)
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    Self::pause.run_if(server_or_singleplayer),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                Self::visit
+                    .run_if(on_timer(Duration::from_secs(1)))
+                    .run_if(server_or_singleplayer),
+            );
+    }
+}
+
+/// Duration of an off-screen visit in seconds, including the trip.
+const VISIT_DURATION: u32 = 60;
+
+impl VisitServicePlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        grounds: Query<(&Parent, &Hovered), With<Ground>>,
+        cities: Query<&GlobalTransform>,
+        lots: Query<(Entity, &Parent, &LotVertices), With<LotService>>,
+    ) {
+        let Ok((ground_parent, hovered)) = grounds.get_single() else {
+            return;
+        };
+
+        let city_transform = cities
+            .get(**ground_parent)
+            .expect("ground should be a child of a city");
+        let point = city_transform
+            .affine()
+            .inverse()
+            .transform_point3(hovered.0)
+            .xz();
+        if let Some((lot_entity, ..)) = lots.iter().find(|(_, parent, vertices)| {
+            **parent == **ground_parent && vertices.contains_point(point)
+        }) {
+            list_events.send(VisitService { lot_entity }.into());
+        }
+    }
+
+    /// Simulates the visit while the actor is hidden and returns it to the departure point.
+    fn visit(
+        mut commands: Commands,
+        mut effects: ServiceEffects,
+        lots: Query<&LotService>,
+        actors: Query<(&Children, Has<InTransit>)>,
+        mut tasks: Query<(
+            Entity,
+            &Parent,
+            &VisitService,
+            &TaskState,
+            Option<&mut VisitTime>,
+        )>,
+    ) {
+        for (entity, parent, visit, &task_state, time) in &mut tasks {
+            if task_state != TaskState::Active {
+                continue;
+            }
+            let Ok((children, in_transit)) = actors.get(**parent) else {
+                continue;
+            };
+            let Ok(service) = lots.get(visit.lot_entity) else {
+                info!("removing `{visit:?}` because the lot is no longer a community lot");
+                commands.entity(entity).despawn();
+                continue;
+            };
+
+            if !in_transit {
+                commands.entity(**parent).insert(InTransit);
+            }
+
+            let Some(mut time) = time else {
+                info!("`{}` departs to visit `{}`", **parent, visit.lot_entity);
+                commands.entity(entity).insert((
+                    VisitTime(0),
+                    TaskProgress::new(0.0, Some(VISIT_DURATION as f32)),
+                ));
+                continue;
+            };
+
+            time.0 += 1;
+            effects.apply(service, **parent, children, time.0);
+            commands.entity(entity).insert(TaskProgress::new(
+                time.0 as f32 / VISIT_DURATION as f32,
+                Some((VISIT_DURATION - time.0) as f32),
+            ));
+
+            if time.0 >= VISIT_DURATION {
+                info!("`{}` returns from `{}`", **parent, visit.lot_entity);
+                commands.entity(**parent).remove::<InTransit>();
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+
+    fn pause(
+        mut commands: Commands,
+        tasks: Query<(&Parent, &TaskState), (With<VisitService>, Changed<TaskState>)>,
+        actors: Query<(), With<InTransit>>,
+    ) {
+        for (parent, &task_state) in &tasks {
+            if task_state == TaskState::Paused && actors.get(**parent).is_ok() {
+                debug!("pausing visit of `{}`", **parent);
+                commands.entity(**parent).remove::<InTransit>();
+            }
+        }
+    }
+
+    fn cancel_visit(
+        trigger: Trigger<OnRemove, VisitService>,
+        mut commands: Commands,
+        tasks: Query<&Parent>,
+        actors: Query<(), With<InTransit>>,
+    ) {
+        let Ok(parent) = tasks.get(trigger.entity()) else {
+            return;
+        };
+        if actors.get(**parent).is_ok() {
+            debug!("interrupting visit of `{}`", **parent);
+            commands.entity(**parent).remove::<InTransit>();
+        }
+    }
+}
+
+/// Off-screen visit to a community lot.
+#[derive(Clone, Component, Copy, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+struct VisitService {
+    lot_entity: Entity,
+}
+
+impl Task for VisitService {
+    fn name(&self) -> &str {
+        "Visit"
+    }
+
+    fn groups(&self) -> TaskGroups {
+        TaskGroups::LEGS
+    }
+}
+
+impl FromWorld for VisitService {
+    fn from_world(_world: &mut World) -> Self {
+        Self {
+            lot_entity: Entity::PLACEHOLDER,
+        }
+    }
+}
+
+impl MapEntities for VisitService {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.lot_entity = entity_mapper.map_entity(self.lot_entity);
+    }
+}
+
+/// Seconds passed since the departure.
+///
+/// Server-only.
+#[derive(Component)]
+struct VisitTime(u32);
@@ -0,0 +1,302 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{
+            animation_state::{AnimationLibrary, AnimationState},
+            needs::{Fun, Need, Social},
+            task::{
+                group_activity::{GroupFinished, GroupMember},
+                Task, TaskBundle, TaskGroups, TaskList, TaskListSet, TaskState,
+            },
+            Actor, Movement,
+        },
+        family::utilities::UtilitiesShutOff,
+        hover::Hovered,
+        navigation::{NavDestination, NavSettings},
+        object::{
+            occupancy::{SlotKind, SlotOccupancy, SlotReservation, UseSlots},
+            wear::Broken,
+        },
+    },
+};
+
+pub(super) struct WatchTvPlugin;
+
+impl Plugin for WatchTvPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<WatchTv>()
+            .replicate_mapped::<WatchTv>()
+            .observe(Self::stop_watching)
+            .add_systems(
+                Update,
+                (
+                    Self::add_to_list.in_set(TaskListSet),
+                    Self::start_watching,
+                    (Self::get_ready, Self::invite, Self::finish).run_if(server_or_singleplayer),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            // Should run in `PostUpdate` to let tiles initialize.
+            .add_systems(
+                PostUpdate,
+                Self::start_navigation.run_if(server_or_singleplayer),
+            );
+    }
+}
+
+/// How long actors watch together.
+const WATCH_DURATION: Duration = Duration::from_secs(30);
+
+const FUN_GAIN: f32 = 20.0;
+
+/// Social gain for each other participant.
+const SOCIAL_GAIN: f32 = 10.0;
+
+impl WatchTvPlugin {
+    fn add_to_list(
+        mut list_events: EventWriter<TaskList>,
+        occupancy: SlotOccupancy,
+        tvs: Query<
+            Entity,
+            (
+                With<Hovered>,
+                With<UseSlots>,
+                Without<UtilitiesShutOff>,
+                Without<Broken>,
+            ),
+        >,
+    ) {
+        if let Ok(tv_entity) = tvs.get_single() {
+            if occupancy
+                .free_slots(tv_entity, SlotKind::Spot)
+                .next()
+                .is_some()
+            {
+                list_events.send(WatchTv { tv_entity }.into());
+            }
+        }
+    }
+
+    /// Navigates to the reserved spot and joins the group.
+    ///
+    /// Reservation is inserted on task activation.
+    fn start_navigation(
+        mut commands: Commands,
+        mut actors: Query<(&Parent, &mut NavSettings, &mut NavDestination)>,
+        tvs: Query<(&GlobalTransform, &UseSlots)>,
+        cities: Query<&GlobalTransform>,
+        tasks: Query<(Entity, &Parent, &WatchTv, &SlotReservation), Added<SlotReservation>>,
+    ) {
+        for (entity, parent, watch_tv, reservation) in &tasks {
+            let Ok((tv_transform, slots)) = tvs.get(watch_tv.tv_entity) else {
+                error!("`{watch_tv:?}` points to an entity without slots");
+                continue;
+            };
+
+            let (city_parent, mut nav_settings, mut dest) = actors
+                .get_mut(**parent)
+                .expect("actors should have navigation component");
+            let city_transform = cities
+                .get(**city_parent)
+                .expect("actors should be children of a city");
+            let Some(slot) = slots.get(reservation.index) else {
+                error!("`{reservation:?}` points to a missing slot");
+                continue;
+            };
+            let slot_transform = slot.transform(tv_transform, city_transform);
+
+            *nav_settings = NavSettings::new(Movement::Walk.speed());
+            **dest = Some(slot_transform.translation);
+
+            commands
+                .entity(entity)
+                .insert(GroupMember::new(watch_tv.tv_entity, WATCH_DURATION));
+        }
+    }
+
+    fn start_watching(
+        animations: Res<AnimationLibrary>,
+        mut actors: Query<
+            (&Children, &NavDestination, &mut AnimationState),
+            Changed<NavDestination>,
+        >,
+        tasks: Query<&TaskState, With<WatchTv>>,
+    ) {
+        for (children, dest, mut animation_state) in &mut actors {
+            if dest.is_none()
+                && tasks
+                    .iter_many(children)
+                    .any(|&task_state| task_state == TaskState::Active)
+            {
+                let montage = animations.montage("watch_tv");
+                animation_state.play_montage(montage);
+            }
+        }
+    }
+
+    /// Turns actors that reached their spots to the TV and marks them as ready.
+    fn get_ready(
+        mut actors: Query<
+            (&Parent, &Children, &NavDestination, &mut Transform),
+            Changed<NavDestination>,
+        >,
+        tvs: Query<(&GlobalTransform, &UseSlots)>,
+        cities: Query<&GlobalTransform>,
+        mut tasks: Query<(&WatchTv, &SlotReservation, &TaskState, &mut GroupMember)>,
+    ) {
+        for (city_parent, children, dest, mut transform) in &mut actors {
+            if !dest.is_none() {
+                continue;
+            }
+
+            let mut iter = tasks.iter_many_mut(children);
+            while let Some((watch_tv, reservation, &task_state, mut member)) = iter.fetch_next() {
+                if task_state != TaskState::Active {
+                    continue;
+                }
+
+                if let Ok((tv_transform, slots)) = tvs.get(watch_tv.tv_entity) {
+                    if let Some(slot) = slots.get(reservation.index) {
+                        let city_transform = cities
+                            .get(**city_parent)
+                            .expect("actors should be children of a city");
+                        let slot_transform = slot.transform(tv_transform, city_transform);
+                        transform.rotation = slot_transform.rotation;
+                    }
+                }
+
+                debug!("`{}` is ready to watch", watch_tv.tv_entity);
+                member.ready = true;
+                break;
+            }
+        }
+    }
+
+    /// Invites idle family members to join.
+    fn invite(
+        mut commands: Commands,
+        occupancy: SlotOccupancy,
+        members: Query<(&Parent, &WatchTv), Added<GroupMember>>,
+        actors: Query<(Entity, &Actor, Option<&Children>)>,
+        tasks: Query<(), With<TaskState>>,
+    ) {
+        for (parent, &watch_tv) in &members {
+            let Ok((_, inviter, _)) = actors.get(**parent) else {
+                continue;
+            };
+
+            let free_slots = occupancy
+                .free_slots(watch_tv.tv_entity, SlotKind::Spot)
+                .count();
+            let is_idle = |children: Option<&Children>| {
+                children.map_or(true, |children| tasks.iter_many(children).next().is_none())
+            };
+            let idle_members = actors.iter().filter(|&(entity, actor, children)| {
+                entity != **parent
+                    && actor.family_entity == inviter.family_entity
+                    && is_idle(children)
+            });
+            for (entity, ..) in idle_members.take(free_slots) {
+                info!("inviting `{entity}` to watch `{}`", watch_tv.tv_entity);
+                commands.entity(entity).with_children(|parent| {
+                    parent.spawn((TaskBundle::new(&watch_tv), watch_tv));
+                });
+            }
+        }
+    }
+
+    fn finish(
+        mut commands: Commands,
+        mut finish_events: EventReader<GroupFinished>,
+        mut animation_states: Query<&mut AnimationState>,
+        mut needs: Query<(&mut Need, Has<Fun>, Has<Social>)>,
+        actors: Query<&Children>,
+        tasks: Query<&Parent, With<WatchTv>>,
+    ) {
+        for event in finish_events.read() {
+            let Ok(parent) = tasks.get(event.task_entity) else {
+                continue;
+            };
+
+            let children = actors.get(**parent).expect("actors should have needs");
+            let mut iter = needs.iter_many_mut(children);
+            while let Some((mut need, fun, social)) = iter.fetch_next() {
+                let gain = if fun {
+                    FUN_GAIN
+                } else if social {
+                    SOCIAL_GAIN * (event.participants - 1) as f32
+                } else {
+                    continue;
+                };
+                need.0 = (need.0 + gain).min(100.0);
+            }
+
+            let mut animation_state = animation_states
+                .get_mut(**parent)
+                .expect("actors should have animation state");
+            animation_state.stop_montage();
+
+            debug!("`{}` finished watching", **parent);
+            commands.entity(event.task_entity).despawn();
+        }
+    }
+
+    /// Stops the looped montage on clients and after cancellation.
+    fn stop_watching(
+        trigger: Trigger<OnRemove, WatchTv>,
+        tasks: Query<(&Parent, &TaskState)>,
+        mut actors: Query<&mut AnimationState>,
+    ) {
+        if let Ok((parent, &task_state)) = tasks.get(trigger.entity()) {
+            if task_state == TaskState::Active {
+                if let Ok(mut animation_state) = actors.get_mut(**parent) {
+                    animation_state.stop_montage();
+                }
+            }
+        }
+    }
+}
+
+/// Watching TV together with other actors.
+#[derive(Clone, Component, Copy, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+struct WatchTv {
+    tv_entity: Entity,
+}
+
+impl Task for WatchTv {
+    fn name(&self) -> &str {
+        "Watch TV"
+    }
+
+    fn groups(&self) -> TaskGroups {
+        TaskGroups::LEGS
+    }
+
+    fn used_slot(&self) -> Option<(Entity, SlotKind)> {
+        Some((self.tv_entity, SlotKind::Spot))
+    }
+}
+
+impl FromWorld for WatchTv {
+    fn from_world(_world: &mut World) -> Self {
+        Self {
+            tv_entity: Entity::PLACEHOLDER,
+        }
+    }
+}
+
+impl MapEntities for WatchTv {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.tv_entity = entity_mapper.map_entity(self.tv_entity);
+    }
+}
@@ -0,0 +1,347 @@
+use std::time::Duration;
+
+use bevy::{ecs::entity::MapEntities, math::Vec3Swizzles, prelude::*};
+use bevy_replicon::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    actor::Actor,
+    city::{
+        lot::{community_lot::LotService, LotFamily, LotObjects, LotVertices},
+        GroundSampler,
+    },
+    clock,
+    family::{Budget, FamilyOwner},
+    mutation_log::{Mutation, MutationKind, MutationRecorder},
+    object::{
+        wear::{Broken, Condition},
+        ObjectBundle,
+    },
+    rng::{RngStream, WorldRng},
+};
+use crate::core::GameState;
+
+/// Optional world decay enabled at creation.
+///
+/// Unattended lots accumulate [`Overgrowth`] and objects wear out faster.
+/// Families can hire [`Maintenance`] to keep their lots clean.
+pub(super) struct AgingPlugin;
+
+impl Plugin for AgingPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<WorldAging>()
+            .register_type::<Overgrowth>()
+            .register_type::<Maintenance>()
+            .replicate::<WorldAging>()
+            .replicate::<Maintenance>()
+            .add_mapped_client_event::<MaintenanceHire>(ChannelKind::Ordered)
+            .add_systems(
+                OnEnter(GameState::InGame),
+                Self::enable.run_if(resource_exists::<EnableAging>),
+            )
+            .add_systems(
+                PreUpdate,
+                (Self::init, Self::hire.run_if(server_or_singleplayer))
+                    .after(ServerSet::Receive)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    Self::grow.run_if(clock::on_game_timer(GROWTH_INTERVAL)),
+                    Self::maintain.run_if(clock::on_game_timer(MAINTENANCE_INTERVAL)),
+                )
+                    .run_if(any_with_component::<WorldAging>)
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// In-game time between growth attempts.
+const GROWTH_INTERVAL: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// Chance for an unattended lot to get a new overgrowth object on each interval.
+const GROWTH_CHANCE: f64 = 0.5;
+
+/// Maximum number of overgrowth objects per lot.
+const MAX_OVERGROWTH: usize = 5;
+
+const OVERGROWTH_OBJECTS: [&str; 2] = [
+    "base/objects/foliage/weeds/weeds.object.ron",
+    "base/objects/rocks/debris/debris.object.ron",
+];
+
+/// In-game time between maintenance visits.
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(5 * 60 * 60);
+
+/// Charged from the family budget on each maintenance interval.
+pub const MAINTENANCE_COST: u32 = 20;
+
+/// Condition restored for unbroken objects on each maintenance visit.
+const MAINTENANCE_REPAIR: f32 = 0.1;
+
+impl AgingPlugin {
+    fn enable(mut commands: Commands) {
+        info!("enabling world aging");
+        commands.remove_resource::<EnableAging>();
+        commands.spawn((Name::new("World aging"), WorldAging, Replicated));
+    }
+
+    fn init(
+        mut commands: Commands,
+        aging: Query<Entity, (With<WorldAging>, Without<StateScoped<GameState>>)>,
+    ) {
+        for entity in &aging {
+            commands
+                .entity(entity)
+                .insert(StateScoped(GameState::InGame));
+        }
+    }
+
+    fn hire(
+        mut commands: Commands,
+        mut hire_events: EventReader<FromClient<MaintenanceHire>>,
+        mut recorder: MutationRecorder,
+        families: Query<(Option<&FamilyOwner>, Has<Maintenance>), With<Budget>>,
+    ) {
+        for FromClient { client_id, event } in hire_events.read().cloned() {
+            let (owner, hired) = match families.get(event.family_entity) {
+                Ok(family) => family,
+                Err(e) => {
+                    error!("`{client_id:?}` tries to hire maintenance for invalid family: {e}");
+                    continue;
+                }
+            };
+            if owner.is_some_and(|owner| **owner != client_id) {
+                error!(
+                    "`{client_id:?}` tries to change maintenance of not owned family `{}`",
+                    event.family_entity
+                );
+                continue;
+            }
+
+            if event.hire && !hired {
+                info!(
+                    "`{client_id:?}` hires maintenance for `{}`",
+                    event.family_entity
+                );
+                recorder.record(client_id, &event);
+                commands.entity(event.family_entity).insert(Maintenance);
+            } else if !event.hire && hired {
+                info!(
+                    "`{client_id:?}` cancels maintenance for `{}`",
+                    event.family_entity
+                );
+                recorder.record(client_id, &event);
+                commands.entity(event.family_entity).remove::<Maintenance>();
+            }
+        }
+    }
+
+    /// Spawns overgrowth on lots without actors and hired maintenance.
+    ///
+    /// Community lots are maintained by the city.
+    fn grow(
+        mut commands: Commands,
+        mut rng: ResMut<WorldRng>,
+        ground_sampler: GroundSampler,
+        lots: Query<
+            (
+                Entity,
+                &Parent,
+                &LotVertices,
+                &LotObjects,
+                Option<&LotFamily>,
+            ),
+            Without<LotService>,
+        >,
+        cities: Query<&GlobalTransform>,
+        families: Query<Has<Maintenance>>,
+        actors: Query<(&Parent, &Transform), With<Actor>>,
+        overgrowth: Query<(), With<Overgrowth>>,
+    ) {
+        let rng = rng.stream(RngStream::Aging);
+        for (lot_entity, lot_parent, vertices, lot_objects, lot_family) in &lots {
+            if lot_family.is_some_and(|family| families.get(family.0).unwrap_or_default()) {
+                continue;
+            }
+            let attended = actors.iter().any(|(actor_parent, transform)| {
+                actor_parent == lot_parent && vertices.contains_point(transform.translation.xz())
+            });
+            if attended {
+                continue;
+            }
+            if overgrowth.iter_many(lot_objects.iter()).count() >= MAX_OVERGROWTH {
+                continue;
+            }
+            if !rng.gen_bool(GROWTH_CHANCE) {
+                continue;
+            }
+
+            // Lots can be concave, so the point may miss on the first tries.
+            let bounds = vertices.bounds();
+            let Some(point) = (0..10)
+                .map(|_| {
+                    Vec2::new(
+                        rng.gen_range(bounds.min.x..=bounds.max.x),
+                        rng.gen_range(bounds.min.y..=bounds.max.y),
+                    )
+                })
+                .find(|&point| vertices.contains_point(point))
+            else {
+                continue;
+            };
+
+            let Ok(city_transform) = cities.get(**lot_parent) else {
+                continue;
+            };
+            let global_point = city_transform.transform_point(Vec3::new(point.x, 0.0, point.y));
+            let Some((height, _)) = ground_sampler.height_range([global_point.xz()]) else {
+                continue;
+            };
+            let height = height - city_transform.translation().y;
+
+            let info_path = OVERGROWTH_OBJECTS[rng.gen_range(0..OVERGROWTH_OBJECTS.len())];
+            let transform = Transform::from_xyz(point.x, height, point.y).with_rotation(
+                Quat::from_rotation_y(rng.gen_range(0.0..std::f32::consts::TAU)),
+            );
+            debug!("spawning overgrowth '{info_path}' on lot `{lot_entity}`");
+            commands.entity(**lot_parent).with_children(|parent| {
+                parent.spawn(ObjectBundle::new(info_path.into(), transform));
+            });
+        }
+    }
+
+    /// Charges families for maintenance and cleans up their lots.
+    fn maintain(
+        mut commands: Commands,
+        mut families: Query<(Entity, &mut Budget), With<Maintenance>>,
+        lots: Query<(&LotObjects, &LotFamily)>,
+        overgrowth: Query<(), With<Overgrowth>>,
+        mut objects: Query<&mut Condition, Without<Broken>>,
+    ) {
+        for (family_entity, mut budget) in &mut families {
+            if !budget.spend(MAINTENANCE_COST) {
+                info!("cancelling maintenance for `{family_entity}` due to insufficient budget");
+                commands.entity(family_entity).remove::<Maintenance>();
+                continue;
+            }
+
+            debug!("maintaining lots of `{family_entity}`");
+            for (lot_objects, _) in lots.iter().filter(|(_, family)| family.0 == family_entity) {
+                for &entity in lot_objects.iter() {
+                    if overgrowth.get(entity).is_ok() {
+                        commands.entity(entity).despawn_recursive();
+                    } else if let Ok(mut condition) = objects.get_mut(entity) {
+                        **condition = (**condition + MAINTENANCE_REPAIR).min(1.0);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Enables [`WorldAging`] after entering [`GameState::InGame`].
+///
+/// Should be inserted together with [`WorldName`](super::WorldName).
+#[derive(Resource)]
+pub struct EnableAging;
+
+/// Marks a world with enabled aging.
+///
+/// Stored on a separate entity to be included in saves.
+#[derive(Component, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct WorldAging;
+
+impl WorldAging {
+    /// Multiplier for object wear in aging worlds.
+    pub(crate) const WEAR_MULTIPLIER: f32 = 2.0;
+}
+
+/// Objects that appear on unattended lots and are removed by [`Maintenance`].
+///
+/// Usually defined in object metadata.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+pub(crate) struct Overgrowth;
+
+/// Hired maintenance service of a family.
+#[derive(Component, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct Maintenance;
+
+/// Hires or cancels [`Maintenance`] for a family.
+#[derive(Clone, Copy, Deserialize, Event, Serialize)]
+pub struct MaintenanceHire {
+    pub family_entity: Entity,
+    pub hire: bool,
+}
+
+impl MapEntities for MaintenanceHire {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.family_entity = entity_mapper.map_entity(self.family_entity);
+    }
+}
+
+impl Mutation for MaintenanceHire {
+    const TARGET: &'static str = "maintenance";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Edit
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        Some(self.family_entity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game_world::{clock::GameClock, object::Object},
+        test_world::TestWorld,
+    };
+
+    #[test]
+    fn growth() {
+        let mut test_world = TestWorld::builder().build();
+        test_world.world_mut().spawn(WorldAging);
+        let city_entity = test_world.spawn_city("City");
+        let vertices = [Vec2::ZERO, Vec2::X * 10.0, Vec2::ONE * 10.0, Vec2::Y * 10.0];
+        test_world.spawn_lot(city_entity, vertices);
+        let community_entity =
+            test_world.spawn_lot(city_entity, vertices.map(|vertex| vertex + Vec2::X * 20.0));
+        test_world
+            .world_mut()
+            .entity_mut(community_entity)
+            .insert(LotService("base/services/park/park.service.ron".into()));
+
+        let grown = test_world.tick_until(100, |world| {
+            // Skip the growth interval instead of waiting for it.
+            let mut clocks = world.query::<&mut GameClock>();
+            let mut clock = clocks.single_mut(world);
+            let hour = (clock.hour() + 2) % 24;
+            let minute = clock.minute();
+            clock.advance_to(hour, minute);
+
+            let mut objects = world.query_filtered::<(), With<Object>>();
+            objects.iter(world).next().is_some()
+        });
+        assert!(grown, "overgrowth should appear on unattended lots");
+
+        let mut objects = test_world
+            .world_mut()
+            .query_filtered::<&Transform, With<Object>>();
+        for transform in objects.iter(test_world.world()) {
+            let point = transform.translation.xz();
+            assert!(
+                (0.0..=10.0).contains(&point.x) && (0.0..=10.0).contains(&point.y),
+                "overgrowth should grow only on the regular lot"
+            );
+        }
+    }
+}
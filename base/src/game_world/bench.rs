@@ -0,0 +1,142 @@
+//! Fixtures to call internal mesh generators directly from benchmarks.
+//!
+//! Run with `cargo bench -p project_harmonia_base --features bench`.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use super::{
+    city::road::road_mesh,
+    family::building::wall::{wall_mesh, Aperture, Apertures},
+    spline::{
+        dynamic_mesh::DynamicMesh, PointConnections, PointKind, SplineConnection,
+        SplineConnections, SplineSegment,
+    },
+};
+use crate::math::{segment::Segment, triangulator::Triangulator};
+
+/// Segments that start from the same point and spread out evenly.
+fn star_segments(count: usize, length: f32) -> Vec<(SplineSegment, SplineConnections)> {
+    let segments: Vec<_> = (0..count)
+        .map(|index| {
+            let end = Vec2::from_angle(index as f32 / count as f32 * TAU) * length;
+            SplineSegment(Segment::new(Vec2::ZERO, end))
+        })
+        .collect();
+
+    segments
+        .iter()
+        .enumerate()
+        .map(|(index, &segment)| {
+            let connections = segments
+                .iter()
+                .enumerate()
+                .filter(|&(other_index, _)| other_index != index)
+                .map(|(other_index, &other_segment)| SplineConnection {
+                    entity: Entity::from_raw(other_index as u32),
+                    segment: *other_segment,
+                    kind: (PointKind::Start, PointKind::Start),
+                })
+                .collect();
+
+            (segment, SplineConnections(connections))
+        })
+        .collect()
+}
+
+/// Walls connected at a single point with doors and windows on each of them.
+pub struct WallsFixture {
+    walls: Vec<(SplineSegment, SplineConnections, Apertures)>,
+    point_connections: PointConnections,
+    mesh: DynamicMesh,
+    triangulator: Triangulator,
+}
+
+impl WallsFixture {
+    const APERTURE_SPACING: f32 = 2.0;
+
+    /// Creates walls with `apertures_count` apertures on each.
+    ///
+    /// Apertures alternate between doors and windows.
+    pub fn new(walls_count: usize, apertures_count: usize) -> Self {
+        let length = (apertures_count + 1) as f32 * Self::APERTURE_SPACING;
+        let walls = star_segments(walls_count, length)
+            .into_iter()
+            .map(|(segment, connections)| {
+                let mut apertures = Apertures::default();
+                let dir = segment.displacement().normalize();
+                for index in 0..apertures_count {
+                    let distance = (index + 1) as f32 * Self::APERTURE_SPACING;
+                    let position = segment.start + dir * distance;
+                    let hole = index % 2 == 1;
+                    let (min_y, max_y) = if hole { (0.9, 2.3) } else { (0.0, 2.0) };
+                    apertures.insert(Aperture {
+                        object_entity: Entity::from_raw(index as u32),
+                        translation: Vec3::new(position.x, 0.0, position.y),
+                        distance,
+                        cutout: vec![
+                            Vec2::new(-0.4, min_y),
+                            Vec2::new(-0.4, max_y),
+                            Vec2::new(0.4, max_y),
+                            Vec2::new(0.4, min_y),
+                        ],
+                        hole,
+                        placing_object: false,
+                    });
+                }
+
+                (segment, connections, apertures)
+            })
+            .collect();
+
+        Self {
+            walls,
+            point_connections: Default::default(),
+            mesh: Default::default(),
+            triangulator: Default::default(),
+        }
+    }
+
+    /// Regenerates meshes for all walls, reusing allocations like the game does.
+    pub fn generate(&mut self) {
+        for (segment, connections, apertures) in &self.walls {
+            wall_mesh::generate(
+                &mut self.mesh,
+                *segment,
+                connections,
+                &self.point_connections,
+                apertures,
+                wall_mesh::HEIGHT,
+                &mut self.triangulator,
+            )
+            .expect("fixture walls should be valid");
+        }
+    }
+}
+
+/// Roads connected at a single point.
+pub struct RoadsFixture {
+    roads: Vec<(SplineSegment, SplineConnections)>,
+    mesh: DynamicMesh,
+}
+
+impl RoadsFixture {
+    const LENGTH: f32 = 20.0;
+    const HALF_WIDTH: f32 = 4.0;
+
+    pub fn new(roads_count: usize) -> Self {
+        Self {
+            roads: star_segments(roads_count, Self::LENGTH),
+            mesh: Default::default(),
+        }
+    }
+
+    /// Regenerates meshes for all roads.
+    pub fn generate(&mut self) {
+        for (segment, connections) in &self.roads {
+            road_mesh::generate(&mut self.mesh, *segment, connections, Self::HALF_WIDTH)
+                .expect("fixture roads should be valid");
+        }
+    }
+}
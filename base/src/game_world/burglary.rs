@@ -0,0 +1,525 @@
+use std::time::Duration;
+
+use avian3d::prelude::*;
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    math::Vec3Swizzles,
+    prelude::*,
+    time::common_conditions::on_timer,
+};
+use bevy_mod_outline::OutlineBundle;
+use bevy_replicon::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    actor::{Movement, ACTOR_RADIUS},
+    city::{
+        lot::{LotFamily, LotObjects, LotVertices},
+        road::Road,
+    },
+    clock::GameClock,
+    family::{
+        memories::{MemoryKind, MemoryRecord},
+        utilities::UtilitiesShutOff,
+        Budget,
+    },
+    hover::{highlighting::OutlineHighlightingExt, Hoverable, Hovered},
+    navigation::{NavDestination, NavSettings, NavigationBundle},
+    object::{occupancy::SlotReservation, wear::Broken},
+    rng::{RngStream, WorldRng},
+    spline::SplineSegment,
+};
+use crate::core::GameState;
+
+/// Sends burglars at night to steal [`Valuable`] objects from family lots.
+///
+/// Burglars flee from lots with an [`Alarm`] and get caught if the police
+/// arrives before they leave. Stolen objects are reimbursed by insurance.
+pub(super) struct BurglaryPlugin;
+
+impl Plugin for BurglaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Burglar>()
+            .register_type::<Valuable>()
+            .register_type::<Alarm>()
+            .register_type::<Stolen>()
+            .replicate_mapped::<Burglar>()
+            .replicate::<Stolen>()
+            .add_systems(
+                PreUpdate,
+                Self::init
+                    .after(ClientSet::Receive)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    Self::spawn.run_if(on_timer(Duration::from_secs(60))),
+                    Self::rob,
+                )
+                    .chain()
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                PostUpdate,
+                (Self::hide_stolen, Self::show_returned).run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Chance for each eligible lot to be robbed per minute at night.
+const BURGLARY_CHANCE: f64 = 0.02;
+
+/// Maximum number of objects taken by a single burglar.
+const MAX_STOLEN: usize = 2;
+
+/// Seconds to pick up an object.
+const PICK_TIME: f32 = 5.0;
+
+/// Distance from which a burglar can pick up an object.
+const PICK_DISTANCE: f32 = 1.5;
+
+/// Seconds for the police to arrive after a call.
+const POLICE_DELAY: f32 = 30.0;
+
+/// Part of the stolen value reimbursed to the family.
+const INSURANCE_RATE: f32 = 0.8;
+
+impl BurglaryPlugin {
+    fn init(
+        mut commands: Commands,
+        mut meshes: ResMut<Assets<Mesh>>,
+        mut materials: ResMut<Assets<StandardMaterial>>,
+        burglars: Query<Entity, (With<Burglar>, Without<GlobalTransform>)>,
+    ) {
+        for entity in &burglars {
+            debug!("initializing burglar `{entity}`");
+            commands
+                .entity(entity)
+                .insert((
+                    GlobalTransform::default(),
+                    VisibilityBundle::default(),
+                    RigidBody::Kinematic,
+                    Collider::capsule_endpoints(
+                        ACTOR_RADIUS,
+                        Vec3::Y * ACTOR_RADIUS,
+                        Vec3::Y * (BURGLAR_HEIGHT - ACTOR_RADIUS),
+                    ),
+                    OutlineBundle::highlighting(),
+                    Hoverable,
+                ))
+                .with_children(|parent| {
+                    // TODO: Replace with a human model.
+                    parent.spawn(PbrBundle {
+                        mesh: meshes.add(Capsule3d::new(
+                            ACTOR_RADIUS,
+                            BURGLAR_HEIGHT - 2.0 * ACTOR_RADIUS,
+                        )),
+                        material: materials.add(Color::srgb(0.1, 0.1, 0.12)),
+                        transform: Transform::from_translation(Vec3::Y * BURGLAR_HEIGHT / 2.0),
+                        ..Default::default()
+                    });
+                });
+        }
+    }
+
+    /// Sends burglars from the nearest road to family lots with valuables.
+    fn spawn(
+        mut commands: Commands,
+        mut world_rng: ResMut<WorldRng>,
+        clocks: Query<&GameClock>,
+        lots: Query<(Entity, &Parent, &LotVertices, &LotObjects), With<LotFamily>>,
+        roads: Query<(&Parent, &SplineSegment), With<Road>>,
+        burglars: Query<&Burglar>,
+        valuables: Query<(), (With<Valuable>, Without<Stolen>)>,
+    ) {
+        if !clocks.get_single().is_ok_and(GameClock::is_night) {
+            return;
+        }
+
+        let rng = world_rng.stream(RngStream::Events);
+        for (lot_entity, lot_parent, vertices, lot_objects) in &lots {
+            if burglars
+                .iter()
+                .any(|burglar| burglar.lot_entity == lot_entity)
+            {
+                continue;
+            }
+            if valuables.iter_many(lot_objects.iter()).next().is_none() {
+                continue;
+            }
+            if !rng.gen_bool(BURGLARY_CHANCE) {
+                continue;
+            }
+
+            let center = vertices.center();
+            let Some(exit) = roads
+                .iter()
+                .filter(|(road_parent, _)| *road_parent == lot_parent)
+                .map(|(_, segment)| segment.closest_point(center))
+                .min_by(|a, b| {
+                    a.distance_squared(center)
+                        .total_cmp(&b.distance_squared(center))
+                })
+            else {
+                continue;
+            };
+
+            info!("sending burglar to lot `{lot_entity}`");
+            let exit = Vec3::new(exit.x, 0.0, exit.y);
+            commands.entity(**lot_parent).with_children(|parent| {
+                parent.spawn((
+                    Name::new("Burglar"),
+                    Burglar {
+                        lot_entity,
+                        exit,
+                        state: BurglarState::Searching,
+                        target: None,
+                        elapsed: 0.0,
+                        stolen: Vec::new(),
+                        police: None,
+                    },
+                    Transform::from_translation(exit),
+                    NavigationBundle::default(),
+                    ParentSync::default(),
+                    Replicated,
+                ));
+            });
+        }
+    }
+
+    fn rob(
+        mut commands: Commands,
+        mut record_events: EventWriter<MemoryRecord>,
+        time: Res<Time>,
+        mut burglars: Query<(
+            Entity,
+            &mut Burglar,
+            &Transform,
+            &mut NavSettings,
+            &mut NavDestination,
+        )>,
+        lots: Query<(&LotVertices, &LotObjects, Option<&LotFamily>)>,
+        mut families: Query<&mut Budget>,
+        alarms: Query<(), (With<Alarm>, Without<Broken>, Without<UtilitiesShutOff>)>,
+        valuables: Query<(Entity, &Transform, &Valuable), Without<Stolen>>,
+        reservations: Query<&SlotReservation>,
+        stolen: Query<&Valuable, With<Stolen>>,
+    ) {
+        let delta = time.delta_seconds();
+        for (burglar_entity, mut burglar, transform, mut nav_settings, mut dest) in &mut burglars {
+            if let Some(police) = &mut burglar.police {
+                *police -= delta;
+                if *police <= 0.0 {
+                    info!("police caught `{burglar_entity}`");
+                    for &entity in &burglar.stolen {
+                        commands.entity(entity).remove::<Stolen>();
+                    }
+                    commands.entity(burglar_entity).despawn_recursive();
+                    if let Some(lot_family) = lots
+                        .get(burglar.lot_entity)
+                        .ok()
+                        .and_then(|(.., lot_family)| lot_family)
+                    {
+                        record_events.send(MemoryRecord {
+                            family_entity: lot_family.0,
+                            kind: MemoryKind::Burglary,
+                            description: "Police caught a burglar".into(),
+                        });
+                    }
+                    continue;
+                }
+            }
+
+            let lot = lots.get(burglar.lot_entity).ok();
+            let position = transform.translation.xz();
+            if burglar.state == BurglarState::Searching {
+                let Some((vertices, lot_objects, _)) = lot else {
+                    debug!("lot for `{burglar_entity}` was removed");
+                    burglar.leave(&mut nav_settings, &mut dest);
+                    continue;
+                };
+                if vertices.contains_point(position)
+                    && alarms.iter_many(lot_objects.iter()).next().is_some()
+                {
+                    info!("alarm scares `{burglar_entity}` away");
+                    burglar.leave(&mut nav_settings, &mut dest);
+                    continue;
+                }
+            }
+
+            if dest.is_some() {
+                continue;
+            }
+
+            match burglar.state {
+                BurglarState::Searching => {
+                    let Some((_, lot_objects, _)) = lot else {
+                        continue;
+                    };
+                    if let Some(target) = burglar.target {
+                        let Ok((_, object_transform, _)) = valuables.get(target) else {
+                            debug!("`{target}` is no longer available for `{burglar_entity}`");
+                            burglar.target = None;
+                            continue;
+                        };
+                        if object_transform.translation.xz().distance(position) > PICK_DISTANCE {
+                            debug!("`{burglar_entity}` can't reach `{target}`");
+                            burglar.leave(&mut nav_settings, &mut dest);
+                            continue;
+                        }
+
+                        burglar.elapsed += delta;
+                        if burglar.elapsed >= PICK_TIME {
+                            info!("`{burglar_entity}` steals `{target}`");
+                            commands.entity(target).insert(Stolen);
+                            burglar.stolen.push(target);
+                            burglar.target = None;
+                        }
+                    } else if burglar.stolen.len() < MAX_STOLEN {
+                        // Objects stolen earlier are already filtered out.
+                        // Objects in use are skipped since their users are nearby.
+                        let next = valuables
+                            .iter_many(lot_objects.iter())
+                            .find(|&(entity, ..)| {
+                                !reservations
+                                    .iter()
+                                    .any(|reservation| reservation.object_entity == entity)
+                            });
+                        if let Some((entity, object_transform, _)) = next {
+                            debug!("`{burglar_entity}` goes to `{entity}`");
+                            burglar.target = Some(entity);
+                            burglar.elapsed = 0.0;
+                            *nav_settings =
+                                NavSettings::new(Movement::Walk.speed()).with_offset(1.0);
+                            **dest = Some(object_transform.translation);
+                        } else {
+                            burglar.leave(&mut nav_settings, &mut dest);
+                        }
+                    } else {
+                        burglar.leave(&mut nav_settings, &mut dest);
+                    }
+                }
+                BurglarState::Leaving => {
+                    let value: u32 = stolen
+                        .iter_many(&burglar.stolen)
+                        .map(|valuable| valuable.value)
+                        .sum();
+                    info!("`{burglar_entity}` escapes with items worth {value}");
+                    for &entity in &burglar.stolen {
+                        commands.entity(entity).despawn_recursive();
+                    }
+                    commands.entity(burglar_entity).despawn_recursive();
+
+                    if value == 0 {
+                        continue;
+                    }
+                    let Some(lot_family) = lot.and_then(|(.., lot_family)| lot_family) else {
+                        continue;
+                    };
+                    record_events.send(MemoryRecord {
+                        family_entity: lot_family.0,
+                        kind: MemoryKind::Burglary,
+                        description: format!("A burglar stole items worth {value}"),
+                    });
+                    let Ok(mut budget) = families.get_mut(lot_family.0) else {
+                        continue;
+                    };
+                    let reimbursement = (value as f32 * INSURANCE_RATE) as u32;
+                    info!("insurance reimburses {reimbursement}");
+                    budget.earn(reimbursement);
+                }
+            }
+        }
+    }
+
+    /// Hides stolen objects and disables interaction with them.
+    fn hide_stolen(
+        mut commands: Commands,
+        mut objects: Query<
+            (Entity, &mut Visibility),
+            (With<Stolen>, Or<(Added<Stolen>, Added<Visibility>)>),
+        >,
+    ) {
+        for (entity, mut visibility) in &mut objects {
+            *visibility = Visibility::Hidden;
+            commands.entity(entity).remove::<(Hoverable, Hovered)>();
+        }
+    }
+
+    fn show_returned(
+        mut commands: Commands,
+        mut removed_stolen: RemovedComponents<Stolen>,
+        mut objects: Query<(Entity, &mut Visibility)>,
+    ) {
+        let mut iter = objects.iter_many_mut(removed_stolen.read());
+        while let Some((entity, mut visibility)) = iter.fetch_next() {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).insert(Hoverable);
+        }
+    }
+}
+
+const BURGLAR_HEIGHT: f32 = 1.8;
+
+/// NPC that tries to steal [`Valuable`] objects from a lot.
+#[derive(Component, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+pub struct Burglar {
+    lot_entity: Entity,
+    /// Point where the burglar came from and leaves to.
+    exit: Vec3,
+    state: BurglarState,
+    /// Object the burglar currently walks to or picks up.
+    target: Option<Entity>,
+    /// Seconds spent picking up the target.
+    elapsed: f32,
+    /// Objects that will be taken away if the burglar leaves.
+    stolen: Vec<Entity>,
+    /// Remaining seconds until the police arrives.
+    police: Option<f32>,
+}
+
+impl Burglar {
+    /// Calls the police that catches the burglar after a delay.
+    pub(crate) fn call_police(&mut self) {
+        if self.police.is_none() {
+            self.police = Some(POLICE_DELAY);
+        }
+    }
+
+    pub fn police_called(&self) -> bool {
+        self.police.is_some()
+    }
+
+    fn leave(&mut self, nav_settings: &mut NavSettings, dest: &mut NavDestination) {
+        self.state = BurglarState::Leaving;
+        self.target = None;
+        *nav_settings = NavSettings::new(Movement::Run.speed());
+        **dest = Some(self.exit);
+    }
+}
+
+impl FromWorld for Burglar {
+    fn from_world(_world: &mut World) -> Self {
+        Self {
+            lot_entity: Entity::PLACEHOLDER,
+            exit: Vec3::ZERO,
+            state: BurglarState::Searching,
+            target: None,
+            elapsed: 0.0,
+            stolen: Vec::new(),
+            police: None,
+        }
+    }
+}
+
+impl MapEntities for Burglar {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.lot_entity = entity_mapper.map_entity(self.lot_entity);
+        if let Some(target) = &mut self.target {
+            *target = entity_mapper.map_entity(*target);
+        }
+        for entity in &mut self.stolen {
+            *entity = entity_mapper.map_entity(*entity);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Reflect, Serialize)]
+enum BurglarState {
+    /// Picks up objects on the lot.
+    Searching,
+    /// Runs back to the exit.
+    Leaving,
+}
+
+/// Object that burglars try to steal.
+///
+/// Usually defined in object metadata.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+pub(crate) struct Valuable {
+    value: u32,
+}
+
+/// Object that scares burglars away from its lot unless broken or without power.
+///
+/// Usually defined in object metadata.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+pub(crate) struct Alarm;
+
+/// Marks an object picked up by a burglar.
+///
+/// Hidden and can't be used until the burglar is caught
+/// or despawned when the burglar leaves.
+#[derive(Component, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub(crate) struct Stolen;
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::SystemState;
+
+    use super::*;
+    use crate::{
+        game_world::object::occupancy::{SlotKind, SlotOccupancy, UseSlots},
+        test_world::TestWorld,
+    };
+
+    #[test]
+    fn stolen_unusable() {
+        let mut test_world = TestWorld::builder().build();
+        let city_entity = test_world.spawn_city("City");
+        let tv_entity = test_world.spawn_object(
+            city_entity,
+            "base/objects/electronics/retro_tv/retro_tv.object.ron",
+            Transform::default(),
+        );
+        let loaded = test_world.tick_until(100, |world| world.get::<UseSlots>(tv_entity).is_some());
+        assert!(loaded, "object components should be loaded from metadata");
+
+        test_world.world_mut().entity_mut(tv_entity).insert(Stolen);
+        test_world.tick();
+
+        let world = test_world.world_mut();
+        assert_eq!(
+            world.get::<Visibility>(tv_entity),
+            Some(&Visibility::Hidden)
+        );
+        assert!(world.get::<Hoverable>(tv_entity).is_none());
+        let mut occupancy = SystemState::<SlotOccupancy>::new(world);
+        assert_eq!(
+            occupancy
+                .get(world)
+                .free_slots(tv_entity, SlotKind::Spot)
+                .count(),
+            0,
+            "stolen objects shouldn't be usable"
+        );
+
+        test_world
+            .world_mut()
+            .entity_mut(tv_entity)
+            .remove::<Stolen>();
+        test_world.tick();
+
+        let world = test_world.world_mut();
+        assert_eq!(
+            world.get::<Visibility>(tv_entity),
+            Some(&Visibility::Inherited)
+        );
+        assert!(world.get::<Hoverable>(tv_entity).is_some());
+        let mut occupancy = SystemState::<SlotOccupancy>::new(world);
+        assert_ne!(
+            occupancy
+                .get(world)
+                .free_slots(tv_entity, SlotKind::Spot)
+                .count(),
+            0
+        );
+    }
+}
@@ -1,10 +1,20 @@
+pub mod ambience;
+pub mod generation;
+pub mod grid;
 pub mod lot;
 pub mod road;
+pub mod statistics;
+pub mod water;
 
 use std::f32::consts::FRAC_PI_2;
 
 use avian3d::prelude::*;
-use bevy::{prelude::*, render::mesh::VertexAttributeValues};
+use bevy::{
+    ecs::system::SystemParam,
+    math::Vec3Swizzles,
+    prelude::*,
+    render::{mesh::VertexAttributeValues, view::RenderLayers},
+};
 use bevy_atmosphere::prelude::*;
 use bevy_replicon::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -13,42 +23,65 @@ use vleue_navigator::prelude::*;
 
 use super::{
     actor::SelectedActor,
+    clock::Sun,
+    family::building::ceiling::CEILING_LAYER,
     hover::Hoverable,
-    player_camera::{EnvironmentMap, PlayerCameraBundle},
+    navigation::NavMeshVersion,
+    player_camera::{EnvironmentMap, PlayerCamera, PlayerCameraBundle},
     WorldState,
 };
 use crate::{
     asset::collection::Collection,
     core::GameState,
     game_world::{actor::ACTOR_RADIUS, Layer},
+    math::segment::Segment,
 };
+use ambience::AmbiencePlugin;
+use generation::GenerationPlugin;
+use grid::GridPlugin;
 use lot::LotPlugin;
 use road::RoadPlugin;
+use statistics::CityStatisticsPlugin;
+use water::WaterPlugin;
 
 pub(super) struct CityPlugin;
 
 impl Plugin for CityPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((LotPlugin, RoadPlugin))
-            .add_sub_state::<CityMode>()
-            .enable_state_scoped_entities::<CityMode>()
-            .register_type::<City>()
-            .replicate_group::<(City, Name)>()
-            .init_resource::<PlacedCities>()
-            .add_systems(OnEnter(WorldState::City), Self::init_activated)
-            .add_systems(
-                OnEnter(WorldState::Family),
-                (Self::activate_by_actor, Self::init_activated).chain(),
-            )
-            .add_systems(OnExit(WorldState::City), Self::deactivate)
-            .add_systems(OnExit(WorldState::Family), Self::deactivate)
-            .add_systems(
-                PreUpdate,
-                Self::init
-                    .after(ClientSet::Receive)
-                    .run_if(in_state(GameState::InGame)),
-            )
-            .add_systems(OnExit(GameState::InGame), Self::cleanup);
+        app.add_plugins((
+            AmbiencePlugin,
+            GenerationPlugin,
+            GridPlugin,
+            LotPlugin,
+            RoadPlugin,
+            CityStatisticsPlugin,
+            WaterPlugin,
+        ))
+        .add_sub_state::<CityMode>()
+        .enable_state_scoped_entities::<CityMode>()
+        .register_type::<City>()
+        .replicate_group::<(City, Name)>()
+        .init_resource::<PlacedCities>()
+        .add_systems(OnEnter(WorldState::City), Self::init_activated)
+        .add_systems(OnEnter(WorldState::Spectator), Self::init_activated)
+        .add_systems(
+            OnEnter(WorldState::Family),
+            (Self::activate_by_actor, Self::init_activated).chain(),
+        )
+        .add_systems(OnExit(WorldState::City), Self::deactivate)
+        .add_systems(OnExit(WorldState::Family), Self::deactivate)
+        .add_systems(OnExit(WorldState::Spectator), Self::deactivate)
+        .add_systems(
+            Update,
+            Self::follow_selected_actor.run_if(in_state(WorldState::Family)),
+        )
+        .add_systems(
+            PreUpdate,
+            Self::init
+                .after(ClientSet::Receive)
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(OnExit(GameState::InGame), Self::cleanup);
     }
 }
 
@@ -56,6 +89,13 @@ impl Plugin for CityPlugin {
 const CITY_SIZE: f32 = 500.0;
 pub(super) const HALF_CITY_SIZE: f32 = CITY_SIZE / 2.0;
 
+/// Returns `true` if the point in city coordinates is finite and inside the city.
+///
+/// Used to validate positions received from clients.
+pub(crate) fn within_city(point: Vec2) -> bool {
+    point.is_finite() && point.abs().max_element() <= HALF_CITY_SIZE
+}
+
 impl CityPlugin {
     /// Inserts [`TransformBundle`] and places cities next to each other.
     fn init(
@@ -69,22 +109,25 @@ impl CityPlugin {
             debug!("initializing city `{entity}`");
 
             let navmesh_entity = commands
-                .spawn(NavMeshBundle {
-                    settings: NavMeshSettings {
-                        fixed: Triangulation::from_outer_edges(&[
-                            Vec2::new(-HALF_CITY_SIZE, -HALF_CITY_SIZE),
-                            Vec2::new(HALF_CITY_SIZE, -HALF_CITY_SIZE),
-                            Vec2::new(HALF_CITY_SIZE, HALF_CITY_SIZE),
-                            Vec2::new(-HALF_CITY_SIZE, HALF_CITY_SIZE),
-                        ]),
-                        agent_radius: ACTOR_RADIUS,
-                        default_delta: 0.2, // To avoid agents stuck on namesh edges.
-                        ..Default::default()
+                .spawn((
+                    NavMeshVersion::default(),
+                    NavMeshBundle {
+                        settings: NavMeshSettings {
+                            fixed: Triangulation::from_outer_edges(&[
+                                Vec2::new(-HALF_CITY_SIZE, -HALF_CITY_SIZE),
+                                Vec2::new(HALF_CITY_SIZE, -HALF_CITY_SIZE),
+                                Vec2::new(HALF_CITY_SIZE, HALF_CITY_SIZE),
+                                Vec2::new(-HALF_CITY_SIZE, HALF_CITY_SIZE),
+                            ]),
+                            agent_radius: ACTOR_RADIUS,
+                            default_delta: 0.2, // To avoid agents stuck on namesh edges.
+                            ..Default::default()
+                        },
+                        transform: Transform::from_rotation(Quat::from_rotation_x(FRAC_PI_2)),
+                        update_mode: NavMeshUpdateMode::Direct,
+                        ..NavMeshBundle::with_unique_id(placed_citites.0 as u128)
                     },
-                    transform: Transform::from_rotation(Quat::from_rotation_x(FRAC_PI_2)),
-                    update_mode: NavMeshUpdateMode::Direct,
-                    ..NavMeshBundle::with_unique_id(placed_citites.0 as u128)
-                })
+                ))
                 .id();
 
             let transform =
@@ -134,6 +177,7 @@ impl CityPlugin {
         commands.entity(entity).with_children(|parent| {
             parent.spawn((
                 Name::new("Sun"),
+                Sun,
                 StateScoped(**world_state),
                 DirectionalLightBundle {
                     directional_light: DirectionalLight {
@@ -143,6 +187,7 @@ impl CityPlugin {
                     transform: Transform::from_xyz(4.0, 7.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
                     ..Default::default()
                 },
+                RenderLayers::default().with(CEILING_LAYER),
             ));
             parent.spawn((
                 Name::new("Player camera"),
@@ -153,6 +198,46 @@ impl CityPlugin {
         });
     }
 
+    /// Moves activation to the city of the selected actor if it lives in another city.
+    ///
+    /// Happens when switching between families or when the actor travels to another city.
+    fn follow_selected_actor(
+        mut commands: Commands,
+        actors: Query<
+            &Parent,
+            (
+                With<SelectedActor>,
+                Or<(Added<SelectedActor>, Changed<Parent>)>,
+            ),
+        >,
+        mut active_cities: Query<(Entity, &mut Visibility), With<ActiveCity>>,
+        mut cities: Query<&mut Visibility, (With<City>, Without<ActiveCity>)>,
+        attached: Query<Entity, Or<(With<Sun>, With<PlayerCamera>)>>,
+    ) {
+        let Ok(city_entity) = actors.get_single().map(|parent| **parent) else {
+            return;
+        };
+        let Ok((active_entity, mut active_visibility)) = active_cities.get_single_mut() else {
+            return;
+        };
+        if active_entity == city_entity {
+            return;
+        }
+
+        info!("moving activation from city `{active_entity}` to `{city_entity}`");
+        *active_visibility = Visibility::Hidden;
+        commands.entity(active_entity).remove::<ActiveCity>();
+
+        let mut visibility = cities
+            .get_mut(city_entity)
+            .expect("actors should always be children of cities");
+        *visibility = Visibility::Visible;
+        commands.entity(city_entity).insert(ActiveCity);
+        for entity in &attached {
+            commands.entity(entity).set_parent(city_entity);
+        }
+    }
+
     fn deactivate(
         mut commands: Commands,
         mut active_cities: Query<(Entity, &mut Visibility), With<ActiveCity>>,
@@ -203,6 +288,9 @@ pub enum CityMode {
     Objects,
     Lots,
     Roads,
+    Water,
+    Generation,
+    Statistics,
 }
 
 impl CityMode {
@@ -211,6 +299,9 @@ impl CityMode {
             Self::Objects => "🌳",
             Self::Lots => "⬛",
             Self::Roads => "🚧",
+            Self::Water => "🌊",
+            Self::Generation => "🎲",
+            Self::Statistics => "📊",
         }
     }
 }
@@ -275,3 +366,80 @@ impl Default for GroundBundle {
 
 #[derive(Component)]
 pub(super) struct Ground;
+
+/// A helper to sample [`Ground`] heights.
+#[derive(SystemParam)]
+pub(crate) struct GroundSampler<'w, 's> {
+    spatial_query: SpatialQuery<'w, 's>,
+}
+
+impl GroundSampler<'_, '_> {
+    /// Returns minimum and maximum ground heights under the specified global points.
+    ///
+    /// Returns [`None`] if any of the points is outside of the ground.
+    pub(crate) fn height_range(
+        &self,
+        points: impl IntoIterator<Item = Vec2>,
+    ) -> Option<(f32, f32)> {
+        const RAY_HEIGHT: f32 = 100.0;
+        let filter = SpatialQueryFilter::from_mask(Layer::Ground);
+
+        let mut range: Option<(f32, f32)> = None;
+        for point in points {
+            let hit = self.spatial_query.cast_ray(
+                Vec3::new(point.x, RAY_HEIGHT, point.y),
+                Dir3::NEG_Y,
+                RAY_HEIGHT * 2.0,
+                true,
+                filter.clone(),
+            )?;
+            let height = RAY_HEIGHT - hit.time_of_impact;
+            range = Some(match range {
+                Some((min, max)) => (min.min(height), max.max(height)),
+                None => (height, height),
+            });
+        }
+
+        range
+    }
+
+    /// Returns the ground height difference along the segment in city coordinates.
+    ///
+    /// Samples both ends and the middle.
+    /// Returns [`None`] if the segment is outside of the ground.
+    pub(crate) fn segment_height_diff(
+        &self,
+        city_transform: &GlobalTransform,
+        segment: Segment,
+    ) -> Option<f32> {
+        let points = [
+            segment.start,
+            segment.start.lerp(segment.end, 0.5),
+            segment.end,
+        ]
+        .map(|point| {
+            city_transform
+                .transform_point(Vec3::new(point.x, 0.0, point.y))
+                .xz()
+        });
+
+        self.height_range(points).map(|(min, max)| max - min)
+    }
+
+    /// Returns the height of the walkable surface under the global point.
+    ///
+    /// Includes elevated roads. The ray starts slightly above the point to let agents
+    /// walk up slopes, but not climb onto bridges from below.
+    pub(crate) fn surface_height(&self, point: Vec3) -> Option<f32> {
+        const STEP_HEIGHT: f32 = 0.5;
+        const MAX_DISTANCE: f32 = 100.0;
+        let filter = SpatialQueryFilter::from_mask([Layer::Ground, Layer::Road]);
+
+        let origin = point + Vec3::Y * STEP_HEIGHT;
+        let hit = self
+            .spatial_query
+            .cast_ray(origin, Dir3::NEG_Y, MAX_DISTANCE, true, filter)?;
+
+        Some(origin.y - hit.time_of_impact)
+    }
+}
@@ -0,0 +1,258 @@
+use bevy::{
+    asset::AssetPath,
+    audio::{AudioPlugin, Volume},
+    prelude::*,
+    utils::HashMap,
+};
+
+use super::{
+    lot::{community_lot::LotService, LotVertices},
+    road::Road,
+    ActiveCity,
+};
+use crate::{
+    asset::info::{road_info::RoadInfo, service_info::ServiceInfo},
+    core::GameState,
+    game_world::{
+        clock::GameClock, family::building::room::Rooms, player_camera::PlayerCamera,
+        spline::SplineSegment,
+    },
+    settings::Settings,
+};
+
+/// Plays looping ambient sounds near lots and roads of the active city.
+///
+/// Sounds are defined by [`ServiceInfo::ambience`] and [`RoadInfo::ambience`].
+/// Each sound has a single emitter that fades in or out depending on the distance
+/// from the camera focus to the closest area with this sound.
+/// Sounds are muffled when the focus is inside a room.
+/// While [`GameClock::is_raining`], rain is played everywhere and louder under roofs.
+pub(super) struct AmbiencePlugin;
+
+impl Plugin for AmbiencePlugin {
+    fn build(&self, app: &mut App) {
+        // Audio is unavailable on headless servers and in tests.
+        if !app.is_plugin_added::<AudioPlugin>() {
+            return;
+        }
+
+        app.add_systems(
+            Update,
+            (Self::update_targets, Self::crossfade)
+                .chain()
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+/// Distance from an area at which its sound becomes silent.
+const AUDIBLE_DISTANCE: f32 = 40.0;
+
+/// Volume multiplier for outdoor sounds when the camera focus is inside a room.
+const INDOOR_VOLUME: f32 = 0.3;
+
+/// How much the volume of each emitter can change per second.
+const CROSSFADE_SPEED: f32 = 0.5;
+
+const RAIN_SOUND: &str = "base/ambience/rain.wav";
+
+/// Volume of rain when the camera focus is outside of rooms.
+const OUTDOOR_RAIN_VOLUME: f32 = 0.6;
+
+impl AmbiencePlugin {
+    /// Computes target volumes for all sounds and spawns missing emitters.
+    fn update_targets(
+        mut commands: Commands,
+        asset_server: Res<AssetServer>,
+        services_info: Res<Assets<ServiceInfo>>,
+        roads_info: Res<Assets<RoadInfo>>,
+        rooms: Res<Rooms>,
+        clocks: Query<&GameClock>,
+        cameras: Query<&GlobalTransform, With<PlayerCamera>>,
+        cities: Query<(Entity, &GlobalTransform), With<ActiveCity>>,
+        lots: Query<(&Parent, &LotVertices, &LotService)>,
+        roads: Query<(&Parent, &SplineSegment, &Road)>,
+        mut emitters: Query<&mut AmbienceEmitter>,
+    ) {
+        let mut targets = HashMap::<AssetPath<'static>, f32>::new();
+        if let (Ok(camera_transform), Ok((city_entity, city_transform))) =
+            (cameras.get_single(), cities.get_single())
+        {
+            let focus = city_transform
+                .affine()
+                .inverse()
+                .transform_point3(focus_point(camera_transform))
+                .xz();
+
+            for (parent, vertices, service) in &lots {
+                if **parent != city_entity {
+                    continue;
+                }
+                let Some(path) = asset_server
+                    .get_handle(&service.0)
+                    .and_then(|handle| services_info.get(&handle))
+                    .and_then(|info| info.ambience.as_ref())
+                else {
+                    continue;
+                };
+
+                let distance = if vertices.contains_point(focus) {
+                    0.0
+                } else {
+                    vertices
+                        .closest_boundary_point(focus)
+                        .map(|point| point.distance(focus))
+                        .unwrap_or(f32::MAX)
+                };
+                add_target(&mut targets, path, distance);
+            }
+
+            for (parent, segment, road) in &roads {
+                if **parent != city_entity {
+                    continue;
+                }
+                let Some(info) = asset_server
+                    .get_handle(&road.0)
+                    .and_then(|handle| roads_info.get(&handle))
+                else {
+                    continue;
+                };
+                let Some(path) = &info.ambience else {
+                    continue;
+                };
+
+                let distance = segment.closest_point(focus).distance(focus) - info.half_width;
+                add_target(&mut targets, path, distance.max(0.0));
+            }
+
+            let indoor = rooms.room_at(city_entity, focus).is_some();
+            if indoor {
+                for target in targets.values_mut() {
+                    *target *= INDOOR_VOLUME;
+                }
+            }
+
+            if clocks.get_single().is_ok_and(GameClock::is_raining) {
+                // Rain on the roof is louder.
+                let volume = if indoor { 1.0 } else { OUTDOOR_RAIN_VOLUME };
+                targets.insert(RAIN_SOUND.into(), volume);
+            }
+        }
+
+        for mut emitter in &mut emitters {
+            emitter.target = targets.remove(&emitter.path).unwrap_or_default();
+        }
+
+        for (path, target) in targets {
+            if target <= 0.0 {
+                continue;
+            }
+
+            debug!("spawning ambience emitter for '{path}'");
+            commands.spawn((
+                Name::new("Ambience emitter"),
+                StateScoped(GameState::InGame),
+                AudioBundle {
+                    source: asset_server.load(path.clone()),
+                    settings: PlaybackSettings::LOOP.with_volume(Volume::new(0.0)),
+                },
+                AmbienceEmitter {
+                    path,
+                    volume: 0.0,
+                    target,
+                },
+            ));
+        }
+    }
+
+    /// Moves emitter volumes towards their targets and despawns silent emitters.
+    fn crossfade(
+        mut commands: Commands,
+        time: Res<Time<Real>>,
+        settings: Res<Settings>,
+        global_volume: Res<GlobalVolume>,
+        mut emitters: Query<(Entity, &mut AmbienceEmitter, Option<&AudioSink>)>,
+    ) {
+        let max_delta = CROSSFADE_SPEED * time.delta_seconds();
+        // Setting the sink volume overrides the global volume applied on playback start.
+        let multiplier = global_volume.volume.get() * settings.audio.ambience_volume;
+        for (entity, mut emitter, sink) in &mut emitters {
+            let delta = (emitter.target - emitter.volume).clamp(-max_delta, max_delta);
+            emitter.volume += delta;
+
+            if emitter.volume <= 0.0 && emitter.target <= 0.0 {
+                debug!("despawning silent ambience emitter for '{}'", emitter.path);
+                commands.entity(entity).despawn();
+                continue;
+            }
+
+            // Sink is inserted after the sound starts playing.
+            if let Some(sink) = sink {
+                sink.set_volume(emitter.volume * multiplier);
+            }
+        }
+    }
+}
+
+/// Returns the point on the ground at which the camera looks.
+///
+/// Falls back to the camera position if it doesn't look down.
+fn focus_point(camera_transform: &GlobalTransform) -> Vec3 {
+    let origin = camera_transform.translation();
+    let direction = camera_transform.forward();
+    if direction.y >= 0.0 {
+        return origin;
+    }
+
+    origin + direction * (-origin.y / direction.y)
+}
+
+/// Updates the target volume of the sound with the area at the specified distance.
+///
+/// The closest area defines the volume.
+fn add_target(
+    targets: &mut HashMap<AssetPath<'static>, f32>,
+    path: &AssetPath<'static>,
+    distance: f32,
+) {
+    let volume = (1.0 - distance / AUDIBLE_DISTANCE).clamp(0.0, 1.0);
+    let target = targets.entry(path.clone()).or_default();
+    *target = target.max(volume);
+}
+
+/// Looping ambient sound with its current and desired volume without settings applied.
+#[derive(Component)]
+struct AmbienceEmitter {
+    path: AssetPath<'static>,
+    volume: f32,
+    target: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn focus() {
+        let camera_transform = GlobalTransform::from(
+            Transform::from_xyz(0.0, 10.0, 10.0).looking_at(Vec3::new(2.0, 0.0, 0.0), Vec3::Y),
+        );
+        assert!(focus_point(&camera_transform).abs_diff_eq(Vec3::new(2.0, 0.0, 0.0), 0.001));
+
+        let camera_transform = GlobalTransform::from(Transform::from_xyz(1.0, 5.0, 0.0));
+        assert_eq!(focus_point(&camera_transform), Vec3::new(1.0, 5.0, 0.0));
+    }
+
+    #[test]
+    fn targets() {
+        let birds = AssetPath::from("birds.ogg");
+        let traffic = AssetPath::from("traffic.ogg");
+        let mut targets = HashMap::new();
+        add_target(&mut targets, &birds, AUDIBLE_DISTANCE);
+        add_target(&mut targets, &birds, AUDIBLE_DISTANCE / 2.0);
+        add_target(&mut targets, &traffic, AUDIBLE_DISTANCE * 2.0);
+
+        assert_eq!(targets[&birds], 0.5);
+        assert_eq!(targets[&traffic], 0.0);
+    }
+}
@@ -0,0 +1,436 @@
+use std::f32::consts::TAU;
+
+use bevy::{ecs::entity::MapEntities, prelude::*};
+use bevy_replicon::prelude::*;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    lot::{LotBundle, LotVertices},
+    road::RoadBundle,
+    water::{WaterBundle, WaterVertices},
+    City, HALF_CITY_SIZE,
+};
+use crate::{
+    asset::info::scenario_info::ScenarioFeature,
+    game_world::{
+        mutation_log::{Mutation, MutationKind, MutationRecorder},
+        object::{Object, ObjectBundle},
+        scenario::ScenarioLocks,
+        spline::SplineSegment,
+    },
+    math::{polygon::Polygon, segment::Segment},
+    network::validation::ClientViolation,
+};
+
+/// Procedurally lays out a starting town on an empty city.
+pub(super) struct GenerationPlugin;
+
+impl Plugin for GenerationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_mapped_client_event::<CityGenerate>(ChannelKind::Unordered)
+            .add_systems(
+                PreUpdate,
+                Self::generate
+                    .after(ServerSet::Receive)
+                    .run_if(server_or_singleplayer),
+            );
+    }
+}
+
+/// Road used for the generated street grid.
+const ROAD_INFO: &str = "base/roads/road_007/road_007.road.ron";
+
+/// Distance from the road center line to the sidewalk.
+const ROAD_HALF_WIDTH: f32 = 4.25;
+
+/// Free space between roads and lots.
+const BLOCK_MARGIN: f32 = ROAD_HALF_WIDTH + 2.0;
+
+/// Free space between neighboring lots.
+const LOT_GAP: f32 = 1.0;
+
+/// Smallest block that still has room for lots.
+const MIN_BLOCK_SIZE: f32 = BLOCK_MARGIN * 2.0 + 4.0;
+
+/// Limits the number of spawned objects.
+const MAX_FOLIAGE_DENSITY: f32 = 50.0;
+
+const STREET_OBJECTS: &[&str] = &[
+    "base/objects/street/crossing_road_sign/crossing_road_sign.object.ron",
+    "base/objects/street/sewer_hatch/sewer_hatch.object.ron",
+    "base/objects/street/storm_drain/storm_drain.object.ron",
+    "base/objects/outdoor_furniture/simple_bench/simple_bench.object.ron",
+];
+
+const FOLIAGE_OBJECTS: &[&str] = &[
+    "base/objects/foliage/simple_bush/simple_bush.object.ron",
+    "base/objects/rocks/medium_stone/medium_stone.object.ron",
+    "base/objects/rocks/small_stone/small_stone.object.ron",
+];
+
+impl GenerationPlugin {
+    fn generate(
+        mut commands: Commands,
+        mut generate_events: EventReader<FromClient<CityGenerate>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        locks: ScenarioLocks,
+        cities: Query<Option<&Children>, With<City>>,
+        content: Query<
+            (),
+            Or<(
+                With<SplineSegment>,
+                With<LotVertices>,
+                With<WaterVertices>,
+                With<Object>,
+            )>,
+        >,
+    ) {
+        for FromClient { client_id, event } in generate_events.read().copied() {
+            if client_id != ClientId::SERVER {
+                error!("`{client_id:?}` tries to generate a city without being a host");
+                violation_events.send(ClientViolation {
+                    client_id,
+                    reason: "generating a city without being a host".into(),
+                });
+                continue;
+            }
+
+            if let Err(violation) = locks.check(ScenarioFeature::Generation, client_id) {
+                violation_events.send(violation);
+                continue;
+            }
+
+            let Ok(children) = cities.get(event.city_entity) else {
+                error!(
+                    "`{client_id:?}` requested generation for invalid city `{}`",
+                    event.city_entity
+                );
+                continue;
+            };
+
+            if content
+                .iter_many(children.into_iter().flatten())
+                .next()
+                .is_some()
+            {
+                error!(
+                    "ignoring generation for city `{}` because it's not empty",
+                    event.city_entity
+                );
+                continue;
+            }
+
+            info!(
+                "`{client_id:?}` generates city `{}` with seed {}",
+                event.city_entity, event.params.seed
+            );
+            recorder.record(client_id, &event);
+            commands
+                .entity(event.city_entity)
+                .with_children(|parent| spawn_layout(parent, event.params));
+        }
+    }
+}
+
+/// Generates a city layout and spawns it as children of a city.
+pub(crate) fn spawn_layout(parent: &mut ChildBuilder, params: GenerationParams) {
+    let layout = CityLayout::new(params);
+    debug!(
+        "generated {} roads, {} lots, {} water bodies and {} objects",
+        layout.roads.len(),
+        layout.lots.len(),
+        layout.water.len(),
+        layout.objects.len()
+    );
+
+    for segment in layout.roads {
+        parent.spawn(RoadBundle::new(
+            ROAD_INFO.into(),
+            segment,
+            Default::default(),
+        ));
+    }
+    for polygon in layout.lots {
+        parent.spawn(LotBundle::new(polygon));
+    }
+    for polygon in layout.water {
+        parent.spawn(WaterBundle::new(polygon));
+    }
+    for (info_path, transform) in layout.objects {
+        parent.spawn(ObjectBundle::new(info_path.into(), transform));
+    }
+}
+
+/// Tunable parameters for [`CityGenerate`].
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GenerationParams {
+    pub seed: u64,
+
+    /// Number of blocks along each axis.
+    pub blocks: u32,
+
+    /// Distance between parallel roads.
+    pub block_size: f32,
+
+    /// Chance for each lot place to contain a lot.
+    pub lot_density: f32,
+
+    /// Chance for each road segment to have a street object.
+    pub street_density: f32,
+
+    /// Average number of foliage objects per block.
+    pub foliage_density: f32,
+
+    /// Number of blocks turned into parks with a pond.
+    pub parks: u32,
+}
+
+impl GenerationParams {
+    /// Returns parameters with all values in valid ranges.
+    ///
+    /// Non-finite values are replaced with defaults.
+    fn clamped(self) -> Self {
+        let default = Self::default();
+        let finite_or = |value: f32, default| if value.is_finite() { value } else { default };
+
+        Self {
+            seed: self.seed,
+            blocks: self.blocks.max(1),
+            block_size: finite_or(self.block_size, default.block_size)
+                .clamp(MIN_BLOCK_SIZE, HALF_CITY_SIZE * 2.0),
+            lot_density: finite_or(self.lot_density, default.lot_density).clamp(0.0, 1.0),
+            street_density: finite_or(self.street_density, default.street_density).clamp(0.0, 1.0),
+            foliage_density: finite_or(self.foliage_density, default.foliage_density)
+                .clamp(0.0, MAX_FOLIAGE_DENSITY),
+            parks: self.parks,
+        }
+    }
+}
+
+impl Default for GenerationParams {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            blocks: 3,
+            block_size: 40.0,
+            lot_density: 0.7,
+            street_density: 0.5,
+            foliage_density: 6.0,
+            parks: 1,
+        }
+    }
+}
+
+/// Content produced by the generator in local city coordinates.
+#[derive(Default)]
+struct CityLayout {
+    roads: Vec<Segment>,
+    lots: Vec<Polygon>,
+    water: Vec<Polygon>,
+    objects: Vec<(&'static str, Transform)>,
+}
+
+impl CityLayout {
+    fn new(params: GenerationParams) -> Self {
+        let params = params.clamped();
+        let mut rng = StdRng::seed_from_u64(params.seed);
+        let mut layout = Self::default();
+
+        // Keep the whole grid inside the city.
+        let max_blocks = ((HALF_CITY_SIZE * 2.0 / params.block_size) as u32).saturating_sub(1);
+        let blocks = params.blocks.clamp(1, max_blocks.max(1));
+        let half_extent = blocks as f32 * params.block_size / 2.0;
+        let coord = |index: u32| index as f32 * params.block_size - half_extent;
+
+        // Split roads at intersections to let them connect.
+        for line in 0..=blocks {
+            for step in 0..blocks {
+                layout.roads.push(Segment::new(
+                    Vec2::new(coord(line), coord(step)),
+                    Vec2::new(coord(line), coord(step + 1)),
+                ));
+                layout.roads.push(Segment::new(
+                    Vec2::new(coord(step), coord(line)),
+                    Vec2::new(coord(step + 1), coord(line)),
+                ));
+            }
+        }
+
+        for segment in &layout.roads {
+            if !rng.gen_bool(params.street_density.into()) {
+                continue;
+            }
+
+            let disp = segment.displacement();
+            let side = if rng.gen() { 1.0 } else { -1.0 };
+            let normal = disp.perp().normalize() * side;
+            let point = segment.start.lerp(segment.end, rng.gen_range(0.2..0.8))
+                + normal * (ROAD_HALF_WIDTH + 0.5);
+            let info_path = *STREET_OBJECTS.choose(&mut rng).unwrap();
+            let rotation = Quat::from_rotation_y(normal.angle_between(Vec2::Y));
+            layout.objects.push((
+                info_path,
+                Transform::from_xyz(point.x, 0.0, point.y).with_rotation(rotation),
+            ));
+        }
+
+        let mut cells: Vec<_> = (0..blocks)
+            .flat_map(|x| (0..blocks).map(move |z| UVec2::new(x, z)))
+            .collect();
+        cells.shuffle(&mut rng);
+        for (index, cell) in cells.into_iter().enumerate() {
+            let min = Vec2::new(coord(cell.x), coord(cell.y)) + BLOCK_MARGIN;
+            let max = Vec2::new(coord(cell.x + 1), coord(cell.y + 1)) - BLOCK_MARGIN;
+            if max.cmple(min).any() {
+                continue;
+            }
+
+            if index < params.parks as usize {
+                let center = (min + max) / 2.0;
+                let radius = (max - min).min_element() / 4.0;
+                layout.water.push(circle(center, radius));
+            } else {
+                let size = (max - min) / 2.0;
+                for x in 0..2 {
+                    for z in 0..2 {
+                        if !rng.gen_bool(params.lot_density.into()) {
+                            continue;
+                        }
+
+                        let lot_min = min + size * Vec2::new(x as f32, z as f32) + LOT_GAP / 2.0;
+                        let lot_max = lot_min + size - LOT_GAP;
+                        layout.lots.push(rectangle(lot_min, lot_max));
+                    }
+                }
+            }
+
+            // Add a random fraction to support non-integer densities.
+            let count = (params.foliage_density + rng.gen::<f32>()) as u32;
+            for _ in 0..count {
+                let point = Vec2::new(rng.gen_range(min.x..max.x), rng.gen_range(min.y..max.y));
+                if layout
+                    .lots
+                    .iter()
+                    .chain(&layout.water)
+                    .any(|polygon| polygon.contains_point(point))
+                {
+                    continue;
+                }
+
+                let info_path = *FOLIAGE_OBJECTS.choose(&mut rng).unwrap();
+                let rotation = Quat::from_rotation_y(rng.gen_range(0.0..TAU));
+                layout.objects.push((
+                    info_path,
+                    Transform::from_xyz(point.x, 0.0, point.y).with_rotation(rotation),
+                ));
+            }
+        }
+
+        layout
+    }
+}
+
+/// Returns a closed rectangle polygon.
+fn rectangle(min: Vec2, max: Vec2) -> Polygon {
+    Polygon(vec![
+        min,
+        Vec2::new(min.x, max.y),
+        max,
+        Vec2::new(max.x, min.y),
+        min,
+    ])
+}
+
+/// Returns a closed polygon approximating a circle.
+fn circle(center: Vec2, radius: f32) -> Polygon {
+    const SEGMENTS: usize = 16;
+    let mut points: Vec<_> = (0..SEGMENTS)
+        .map(|index| center + Vec2::from_angle(index as f32 / SEGMENTS as f32 * TAU) * radius)
+        .collect();
+    points.push(points[0]);
+    Polygon(points)
+}
+
+/// Requests procedural generation for an empty city.
+#[derive(Clone, Copy, Deserialize, Event, Serialize)]
+pub struct CityGenerate {
+    pub city_entity: Entity,
+    pub params: GenerationParams,
+}
+
+impl MapEntities for CityGenerate {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.city_entity = entity_mapper.map_entity(self.city_entity);
+    }
+}
+
+impl Mutation for CityGenerate {
+    const TARGET: &'static str = "city";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Edit
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        Some(self.city_entity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reproducible() {
+        let params = GenerationParams {
+            seed: 42,
+            ..Default::default()
+        };
+        let first = CityLayout::new(params);
+        let second = CityLayout::new(params);
+
+        assert_eq!(first.roads.len(), 24);
+        assert_eq!(first.lots.len(), second.lots.len());
+        assert_eq!(first.objects.len(), second.objects.len());
+        for ((first_path, first_transform), (second_path, second_transform)) in
+            first.objects.iter().zip(&second.objects)
+        {
+            assert_eq!(first_path, second_path);
+            assert_eq!(first_transform.translation, second_transform.translation);
+        }
+    }
+
+    #[test]
+    fn invalid_params() {
+        for params in [
+            GenerationParams {
+                blocks: 0,
+                block_size: HALF_CITY_SIZE * 4.0,
+                ..Default::default()
+            },
+            GenerationParams {
+                blocks: u32::MAX,
+                block_size: f32::NAN,
+                lot_density: f32::INFINITY,
+                street_density: -1.0,
+                foliage_density: f32::MAX,
+                parks: u32::MAX,
+                ..Default::default()
+            },
+            GenerationParams {
+                block_size: 0.0,
+                ..Default::default()
+            },
+        ] {
+            let layout = CityLayout::new(params);
+            assert!(!layout.roads.is_empty());
+            for segment in &layout.roads {
+                assert!(segment.start.abs().max_element() <= HALF_CITY_SIZE);
+                assert!(segment.end.abs().max_element() <= HALF_CITY_SIZE);
+            }
+        }
+    }
+}
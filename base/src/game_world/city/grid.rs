@@ -0,0 +1,170 @@
+use bevy::{
+    pbr::{NotShadowCaster, NotShadowReceiver},
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderRef, ShaderType},
+};
+use leafwing_input_manager::common_conditions::action_just_pressed;
+
+use super::{ActiveCity, Ground};
+use crate::{
+    core::GameState,
+    game_world::{family::FamilyMode, player_camera::CameraCaster, WorldState},
+    settings::Action,
+};
+
+/// Overlay with lines every meter to help with precise building.
+pub(super) struct GridPlugin;
+
+impl Plugin for GridPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MaterialPlugin::<GridMaterial>::default())
+            .init_resource::<GridEnabled>()
+            .init_resource::<CursorCoords>()
+            .observe(Self::init)
+            .add_systems(
+                Update,
+                (
+                    Self::toggle.run_if(action_just_pressed(Action::ToggleGrid)),
+                    Self::update_visibility,
+                    Self::update_coords,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Distance between grid lines in meters.
+const GRID_SPACING: f32 = 1.0;
+
+/// Lifts the grid to avoid z-fighting with the ground.
+const GRID_OFFSET: f32 = 0.01;
+
+impl GridPlugin {
+    fn init(
+        trigger: Trigger<OnAdd, Ground>,
+        mut commands: Commands,
+        mut materials: ResMut<Assets<GridMaterial>>,
+        grounds: Query<&Handle<Mesh>>,
+    ) {
+        let mesh = grounds
+            .get(trigger.entity())
+            .expect("ground should have a mesh")
+            .clone();
+
+        debug!("initializing grid for `{}`", trigger.entity());
+        commands.entity(trigger.entity()).with_children(|parent| {
+            parent.spawn((
+                Name::new("Grid"),
+                Grid,
+                NotShadowCaster,
+                NotShadowReceiver,
+                MaterialMeshBundle {
+                    mesh,
+                    material: materials.add(GridMaterial::default()),
+                    transform: Transform::from_translation(Vec3::Y * GRID_OFFSET),
+                    visibility: Visibility::Hidden,
+                    ..Default::default()
+                },
+            ));
+        });
+    }
+
+    fn toggle(mut grid_enabled: ResMut<GridEnabled>) {
+        grid_enabled.0 = !grid_enabled.0;
+        info!("setting grid to `{}`", grid_enabled.0);
+    }
+
+    fn update_visibility(
+        grid_enabled: Res<GridEnabled>,
+        world_state: Res<State<WorldState>>,
+        family_mode: Option<Res<State<FamilyMode>>>,
+        mut grids: Query<&mut Visibility, With<Grid>>,
+    ) {
+        let building = match world_state.get() {
+            WorldState::City => true,
+            WorldState::Family => family_mode.is_some_and(|mode| *mode == FamilyMode::Building),
+            _ => false,
+        };
+        let visibility = if grid_enabled.0 && building {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        for mut grid_visibility in &mut grids {
+            grid_visibility.set_if_neq(visibility);
+        }
+    }
+
+    fn update_coords(
+        mut cursor_coords: ResMut<CursorCoords>,
+        camera_caster: CameraCaster,
+        grids: Query<(&Parent, &Visibility), With<Grid>>,
+        grounds: Query<&Parent, With<Ground>>,
+        cities: Query<(), With<ActiveCity>>,
+    ) {
+        let grid_visible = grids.iter().any(|(parent, &visibility)| {
+            visibility != Visibility::Hidden
+                && grounds
+                    .get(**parent)
+                    .is_ok_and(|city_entity| cities.get(**city_entity).is_ok())
+        });
+
+        let coords = grid_visible
+            .then(|| camera_caster.intersect_ground())
+            .flatten()
+            .map(|point| Vec2::new(point.x, point.z));
+        cursor_coords.set_if_neq(CursorCoords(coords));
+    }
+}
+
+/// Controls [`Grid`] visibility in city and building modes.
+#[derive(Resource, Default, Deref)]
+pub struct GridEnabled(bool);
+
+/// Cursor position on the ground in city coordinates while the grid is visible.
+#[derive(Resource, Default, Deref, PartialEq)]
+pub struct CursorCoords(Option<Vec2>);
+
+#[derive(Component)]
+struct Grid;
+
+#[derive(Asset, TypePath, AsBindGroup, Clone)]
+struct GridMaterial {
+    #[uniform(0)]
+    uniform: GridUniform,
+}
+
+impl Default for GridMaterial {
+    fn default() -> Self {
+        Self {
+            uniform: GridUniform {
+                color: LinearRgba::new(1.0, 1.0, 1.0, 0.3),
+                spacing: GRID_SPACING,
+                line_width: 1.0,
+            },
+        }
+    }
+}
+
+impl Material for GridMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "base/shaders/grid.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+}
+
+#[derive(ShaderType, Clone)]
+struct GridUniform {
+    color: LinearRgba,
+
+    /// Distance between lines in meters.
+    spacing: f32,
+
+    /// Line width in pixels.
+    line_width: f32,
+}
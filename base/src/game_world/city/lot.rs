@@ -1,18 +1,42 @@
+pub mod bulldozing_lot;
+pub mod community_lot;
 pub mod creating_lot;
+pub mod editing_lot;
 pub mod moving_lot;
+pub mod permissions;
 
-use bevy::{ecs::entity::MapEntities, prelude::*};
+use anyhow::{ensure, Result};
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    math::Vec3Swizzles,
+    prelude::*,
+};
 use bevy_replicon::prelude::*;
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter};
 
 use crate::{
+    asset::info::scenario_info::ScenarioFeature,
     common_conditions::in_any_state,
-    game_world::{city::CityMode, WorldState},
-    math::polygon::Polygon,
+    core::GameState,
+    game_world::{
+        city::{self, road::Road, CityMode, GroundSampler},
+        family::FamilyControl,
+        mutation_log::{Mutation, MutationKind, MutationRecorder},
+        object::Object,
+        scenario::ScenarioLocks,
+        spline::SplineSegment,
+        WorldState,
+    },
+    math::{polygon::Polygon, segment::Segment},
+    network::validation::{AppValidationExt, ClientViolation, ValidationSet},
 };
+use bulldozing_lot::BulldozingLotPlugin;
+use community_lot::CommunityLotPlugin;
 use creating_lot::CreatingLotPlugin;
+use editing_lot::{EditingLot, EditingLotPlugin};
 use moving_lot::MovingLotPlugin;
+use permissions::LotPermissionsPlugin;
 
 pub(super) struct LotPlugin;
 
@@ -20,19 +44,43 @@ impl Plugin for LotPlugin {
     fn build(&self, app: &mut App) {
         app.add_sub_state::<LotTool>()
             .enable_state_scoped_entities::<LotTool>()
-            .add_plugins((CreatingLotPlugin, MovingLotPlugin))
+            .add_plugins((
+                BulldozingLotPlugin,
+                CommunityLotPlugin,
+                CreatingLotPlugin,
+                EditingLotPlugin,
+                MovingLotPlugin,
+                LotPermissionsPlugin,
+            ))
             .register_type::<LotVertices>()
+            .register_type::<LotFamily>()
             .replicate::<LotVertices>()
+            .replicate_mapped::<LotFamily>()
             .add_mapped_client_event::<LotCreate>(ChannelKind::Unordered)
             .add_mapped_client_event::<LotMove>(ChannelKind::Ordered)
+            .add_mapped_client_event::<LotEdit>(ChannelKind::Ordered)
             .add_mapped_client_event::<LotDelete>(ChannelKind::Unordered)
+            .limit_client_event::<LotCreate>(5)
+            .limit_client_event::<LotMove>(30)
+            .limit_client_event::<LotEdit>(30)
             .add_server_event::<LotEventConfirmed>(ChannelKind::Unordered)
+            .add_mapped_server_event::<LotUpdated>(ChannelKind::Unordered)
+            .observe(Self::init_objects)
             .add_systems(
                 PreUpdate,
-                (Self::create, Self::apply_movement, Self::delete)
-                    .after(ServerSet::Receive)
+                (
+                    Self::create,
+                    Self::apply_movement,
+                    Self::apply_edit,
+                    Self::delete,
+                )
+                    .after(ValidationSet)
                     .run_if(server_or_singleplayer),
             )
+            .add_systems(
+                Update,
+                Self::update_objects.run_if(in_state(GameState::InGame)),
+            )
             .add_systems(
                 PostUpdate,
                 Self::draw_lines.run_if(in_any_state([WorldState::City, WorldState::Family])),
@@ -60,9 +108,32 @@ impl LotPlugin {
         mut commands: Commands,
         mut create_events: EventReader<FromClient<LotCreate>>,
         mut confirm_events: EventWriter<ToClients<LotEventConfirmed>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        locks: ScenarioLocks,
+        ground_sampler: GroundSampler,
+        cities: Query<&GlobalTransform>,
     ) {
         for FromClient { client_id, event } in create_events.read().cloned() {
+            if let Err(violation) = locks.check(ScenarioFeature::Lots, client_id) {
+                violation_events.send(violation);
+                continue;
+            }
+
+            let Ok(city_transform) = cities.get(event.city_entity) else {
+                error!(
+                    "`{client_id:?}` tries to create lot in non-existing city `{}`",
+                    event.city_entity
+                );
+                continue;
+            };
+            if let Err(e) = check_ground(&ground_sampler, city_transform, &event.polygon) {
+                error!("`{client_id:?}` tries to create invalid lot: {e}");
+                continue;
+            }
+
             info!("`{client_id:?}` creates lot");
+            recorder.record(client_id, &event);
             commands.entity(event.city_entity).with_children(|parent| {
                 parent.spawn(LotBundle::new(event.polygon));
             });
@@ -76,12 +147,46 @@ impl LotPlugin {
     fn apply_movement(
         mut move_events: EventReader<FromClient<LotMove>>,
         mut confirm_events: EventWriter<ToClients<LotEventConfirmed>>,
-        mut lots: Query<&mut LotVertices>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        locks: ScenarioLocks,
+        control: FamilyControl,
+        mut lots: Query<(&mut LotVertices, Option<&LotFamily>)>,
     ) {
         for FromClient { client_id, event } in move_events.read().copied() {
+            if let Err(violation) = locks.check(ScenarioFeature::Lots, client_id) {
+                violation_events.send(violation);
+                continue;
+            }
+
             match lots.get_mut(event.entity) {
-                Ok(mut vertices) => {
+                Ok((vertices, _))
+                    if !vertices
+                        .iter()
+                        .all(|&vertex| city::within_city(vertex + event.offset)) =>
+                {
+                    error!(
+                        "`{client_id:?}` tries to move lot `{:?}` outside of the city",
+                        event.entity
+                    );
+                    violation_events.send(ClientViolation {
+                        client_id,
+                        reason: "moving lots outside of the world".into(),
+                    });
+                }
+                Ok((_, Some(&lot_family))) if !control.family_allowed(client_id, *lot_family) => {
+                    error!(
+                        "`{client_id:?}` tries to move lot `{:?}` of another family",
+                        event.entity
+                    );
+                    violation_events.send(ClientViolation {
+                        client_id,
+                        reason: "moving a lot owned by another family".into(),
+                    });
+                }
+                Ok((mut vertices, _)) => {
                     info!("`{client_id:?}` moves lot `{:?}`", event.entity);
+                    recorder.record(client_id, &event);
                     for vertex in vertices.iter_mut() {
                         *vertex += event.offset;
                     }
@@ -95,13 +200,155 @@ impl LotPlugin {
         }
     }
 
+    fn apply_edit(
+        mut edit_events: EventReader<FromClient<LotEdit>>,
+        mut confirm_events: EventWriter<ToClients<LotEventConfirmed>>,
+        mut update_events: EventWriter<ToClients<LotUpdated>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        locks: ScenarioLocks,
+        control: FamilyControl,
+        mut lots: Query<
+            (Entity, &Parent, &mut LotVertices, Option<&LotFamily>),
+            Without<EditingLot>,
+        >,
+        roads: Query<(&Parent, &SplineSegment), With<Road>>,
+    ) {
+        for FromClient { client_id, event } in edit_events.read().cloned() {
+            if let Err(violation) = locks.check(ScenarioFeature::Lots, client_id) {
+                violation_events.send(violation);
+                continue;
+            }
+
+            let Ok((_, parent, _, lot_family)) = lots.get(event.entity) else {
+                error!(
+                    "`{client_id:?}` tries to edit non-existing lot `{:?}`",
+                    event.entity
+                );
+                continue;
+            };
+            if let Some(&lot_family) = lot_family {
+                if !control.family_allowed(client_id, *lot_family) {
+                    error!(
+                        "`{client_id:?}` tries to edit lot `{:?}` of another family",
+                        event.entity
+                    );
+                    violation_events.send(ClientViolation {
+                        client_id,
+                        reason: "editing a lot owned by another family".into(),
+                    });
+                    continue;
+                }
+            }
+
+            let other_lots = lots
+                .iter()
+                .filter(|&(entity, other_parent, ..)| {
+                    entity != event.entity && other_parent == parent
+                })
+                .map(|(_, _, vertices, _)| &vertices.0);
+            let roads = roads
+                .iter()
+                .filter(|&(road_parent, _)| road_parent == parent)
+                .map(|(_, segment)| **segment);
+            match validate_shape(&event.polygon, other_lots, roads) {
+                Ok(()) => {
+                    info!("`{client_id:?}` edits lot `{:?}`", event.entity);
+                    recorder.record(client_id, &event);
+                    let (_, _, mut vertices, _) = lots.get_mut(event.entity).unwrap();
+                    vertices.0 = event.polygon;
+                    update_events.send(ToClients {
+                        mode: SendMode::Broadcast,
+                        event: LotUpdated(event.entity),
+                    });
+                }
+                Err(e) => error!("unable to apply edit from `{client_id:?}`: {e}"),
+            }
+
+            confirm_events.send(ToClients {
+                mode: SendMode::Direct(client_id),
+                event: LotEventConfirmed,
+            });
+        }
+    }
+
+    fn init_objects(trigger: Trigger<OnAdd, LotVertices>, mut commands: Commands) {
+        commands
+            .entity(trigger.entity())
+            .insert(LotObjects::default());
+    }
+
+    /// Recomputes objects inside lots when lots or objects change.
+    fn update_objects(
+        mut removed_objects: RemovedComponents<Object>,
+        mut lots: Query<(Entity, &Parent, Ref<LotVertices>, &mut LotObjects)>,
+        objects: Query<(Entity, &Parent, Ref<Transform>), With<Object>>,
+    ) {
+        let objects_changed = removed_objects.read().count() != 0
+            || objects.iter().any(|(.., transform)| transform.is_changed());
+        for (lot_entity, lot_parent, vertices, mut lot_objects) in &mut lots {
+            if !objects_changed && !vertices.is_changed() {
+                continue;
+            }
+
+            let entities: Vec<_> = objects
+                .iter()
+                .filter(|(_, object_parent, transform)| {
+                    *object_parent == lot_parent
+                        && vertices.contains_point(transform.translation.xz())
+                })
+                .map(|(entity, ..)| entity)
+                .collect();
+            if lot_objects.0 != entities {
+                trace!("updating objects for lot `{lot_entity}`: {entities:?}");
+                lot_objects.0 = entities;
+            }
+        }
+    }
+
     fn delete(
         mut commands: Commands,
         mut delete_events: EventReader<FromClient<LotDelete>>,
         mut confirm_events: EventWriter<ToClients<LotEventConfirmed>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        locks: ScenarioLocks,
+        control: FamilyControl,
+        lots: Query<Option<&LotFamily>, With<LotVertices>>,
     ) {
         for FromClient { client_id, event } in delete_events.read().copied() {
+            if let Err(violation) = locks.check(ScenarioFeature::Lots, client_id) {
+                violation_events.send(violation);
+                continue;
+            }
+
+            let Ok(lot_family) = lots.get(event.0) else {
+                error!(
+                    "`{client_id:?}` tries to delete `{:?}` which is not a lot",
+                    event.0
+                );
+                violation_events.send(ClientViolation {
+                    client_id,
+                    reason: "deleting an entity that is not a lot".into(),
+                });
+                continue;
+            };
+            if let Some(&lot_family) = lot_family {
+                if !control.family_allowed(client_id, *lot_family) {
+                    error!(
+                        "`{client_id:?}` tries to delete lot `{:?}` of another family",
+                        event.0
+                    );
+                    violation_events.send(ClientViolation {
+                        client_id,
+                        reason: "deleting a lot owned by another family".into(),
+                    });
+                    continue;
+                }
+            }
+
             info!("`{client_id:?}` deletes lot `{:?}`", event.0);
+            recorder.record(client_id, &event);
             commands.entity(event.0).despawn_recursive();
             confirm_events.send(ToClients {
                 mode: SendMode::Direct(client_id),
@@ -119,6 +366,9 @@ pub enum LotTool {
     #[default]
     Create,
     Move,
+    Edit,
+    Bulldoze,
+    Service,
 }
 
 impl LotTool {
@@ -126,19 +376,71 @@ impl LotTool {
         match self {
             Self::Create => "✏",
             Self::Move => "↔",
+            Self::Edit => "📐",
+            Self::Bulldoze => "🚜",
+            Self::Service => "🏛",
         }
     }
 }
 
+/// Checks that a lot shape is a simple polygon that doesn't overlap other lots and roads.
+fn validate_shape<'a>(
+    polygon: &Polygon,
+    mut other_lots: impl Iterator<Item = &'a Polygon>,
+    mut roads: impl Iterator<Item = Segment>,
+) -> Result<()> {
+    ensure!(polygon.len() >= 4, "lot should have at least 3 vertices");
+    ensure!(
+        polygon.iter().all(|&vertex| city::within_city(vertex)),
+        "lot should be inside the city"
+    );
+    ensure!(polygon.is_simple(), "lot edges shouldn't cross each other");
+    ensure!(
+        !other_lots.any(|other| polygon.overlaps(other)),
+        "lot shouldn't overlap other lots"
+    );
+    ensure!(
+        !roads.any(|segment| polygon.overlaps_segment(segment)),
+        "lot shouldn't overlap roads"
+    );
+
+    Ok(())
+}
+
+/// Maximum ground height difference under a lot.
+const MAX_HEIGHT_DIFF: f32 = 0.5;
+
+/// Checks that the ground under the polygon in city coordinates is flat enough.
+fn check_ground(
+    ground_sampler: &GroundSampler,
+    city_transform: &GlobalTransform,
+    polygon: &Polygon,
+) -> Result<()> {
+    let points = polygon.iter().map(|vertex| {
+        city_transform
+            .transform_point(vertex.extend(0.0).xzy())
+            .xz()
+    });
+    if let Some((min, max)) = ground_sampler.height_range(points) {
+        ensure!(
+            max - min <= MAX_HEIGHT_DIFF,
+            "lot ground is too uneven, the height difference is {:.2} m, but the maximum is {MAX_HEIGHT_DIFF} m",
+            max - min
+        );
+    }
+
+    Ok(())
+}
+
 #[derive(Bundle)]
-struct LotBundle {
+pub(crate) struct LotBundle {
     vertices: LotVertices,
     parent_sync: ParentSync,
     replication: Replicated,
 }
 
 impl LotBundle {
-    fn new(polygon: Polygon) -> Self {
+    pub(crate) fn new(polygon: Polygon) -> Self {
         Self {
             vertices: LotVertices(polygon),
             parent_sync: Default::default(),
@@ -151,10 +453,37 @@ impl LotBundle {
 #[reflect(Component)]
 pub(crate) struct LotVertices(Polygon);
 
+impl LotVertices {
+    /// Returns the center of the bounding rectangle.
+    pub(crate) fn center(&self) -> Vec2 {
+        self.bounds().center()
+    }
+
+    pub(crate) fn bounds(&self) -> Rect {
+        let (min, max) = self.iter().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), &vertex| (min.min(vertex), max.max(vertex)),
+        );
+        Rect { min, max }
+    }
+}
+
 /// Contains a family entity that owns the lot.
-#[derive(Component)]
-#[allow(dead_code)]
-pub(crate) struct LotFamily(pub(crate) Entity);
+#[derive(Clone, Component, Copy, Deref, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+pub struct LotFamily(pub(crate) Entity);
+
+impl FromWorld for LotFamily {
+    fn from_world(_world: &mut World) -> Self {
+        Self(Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for LotFamily {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
 
 #[derive(Clone, Deserialize, Event, Serialize)]
 struct LotCreate {
@@ -168,6 +497,18 @@ impl MapEntities for LotCreate {
     }
 }
 
+impl Mutation for LotCreate {
+    const TARGET: &'static str = "lot";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Spawn
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        None
+    }
+}
+
 #[derive(Clone, Copy, Deserialize, Event, Serialize)]
 struct LotMove {
     entity: Entity,
@@ -180,6 +521,43 @@ impl MapEntities for LotMove {
     }
 }
 
+impl Mutation for LotMove {
+    const TARGET: &'static str = "lot";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Move
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        Some(self.entity)
+    }
+}
+
+/// Replaces lot vertices with a new shape.
+#[derive(Clone, Deserialize, Event, Serialize)]
+struct LotEdit {
+    entity: Entity,
+    polygon: Polygon,
+}
+
+impl MapEntities for LotEdit {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.entity = entity_mapper.map_entity(self.entity);
+    }
+}
+
+impl Mutation for LotEdit {
+    const TARGET: &'static str = "lot";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Edit
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        Some(self.entity)
+    }
+}
+
 #[derive(Clone, Copy, Event, Deserialize, Serialize)]
 struct LotDelete(Entity);
 
@@ -189,8 +567,107 @@ impl MapEntities for LotDelete {
     }
 }
 
+impl Mutation for LotDelete {
+    const TARGET: &'static str = "lot";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Delete
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        Some(self.0)
+    }
+}
+
 #[derive(Deserialize, Event, Serialize)]
 struct LotEventConfirmed;
 
+/// Sent to all clients after a lot was reshaped.
+#[derive(Clone, Copy, Deserialize, Event, Serialize)]
+pub struct LotUpdated(pub Entity);
+
+impl MapEntities for LotUpdated {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
+
+/// Objects placed directly inside the lot.
+///
+/// Local, recomputed when lots or objects change.
+#[derive(Component, Default, Deref)]
+pub(crate) struct LotObjects(Vec<Entity>);
+
 #[derive(Component)]
 struct UnconfirmedLot;
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use super::*;
+    use crate::{
+        game_world::{city::HALF_CITY_SIZE, scenario::Scenario},
+        test_world::TestWorld,
+    };
+
+    #[test]
+    fn shape_validation() {
+        let square = Polygon(vec![Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y, Vec2::ZERO]);
+        assert!(validate_shape(&square, iter::empty(), iter::empty()).is_ok());
+
+        let mut invalid = square.clone();
+        invalid.0[1] = Vec2::NAN;
+        assert!(validate_shape(&invalid, iter::empty(), iter::empty()).is_err());
+
+        let mut outside = square.clone();
+        outside.0[1] = Vec2::X * HALF_CITY_SIZE * 2.0;
+        assert!(validate_shape(&outside, iter::empty(), iter::empty()).is_err());
+
+        let overlapping = Polygon(square.iter().map(|&vertex| vertex + 0.5).collect());
+        assert!(validate_shape(&square, iter::once(&overlapping), iter::empty()).is_err());
+    }
+
+    #[test]
+    fn scenario_lock() {
+        let mut test_world = TestWorld::builder().build();
+        let city_entity = test_world.spawn_city("City");
+        let lot_entity = test_world.spawn_lot(
+            city_entity,
+            [Vec2::ZERO, Vec2::X * 10.0, Vec2::ONE * 10.0, Vec2::Y * 10.0],
+        );
+        test_world
+            .world_mut()
+            .spawn(Scenario::with_locked(vec![ScenarioFeature::Lots]));
+
+        test_world.world_mut().send_event(FromClient {
+            client_id: ClientId::SERVER,
+            event: LotDelete(lot_entity),
+        });
+        test_world.tick();
+
+        assert!(test_world.world().get_entity(lot_entity).is_some());
+        let violations = test_world.world().resource::<Events<ClientViolation>>();
+        assert!(violations
+            .iter_current_update_events()
+            .any(|violation| violation.client_id == ClientId::SERVER));
+    }
+
+    #[test]
+    fn delete_not_lot() {
+        let mut test_world = TestWorld::builder().build();
+        let city_entity = test_world.spawn_city("City");
+
+        test_world.world_mut().send_event(FromClient {
+            client_id: ClientId::SERVER,
+            event: LotDelete(city_entity),
+        });
+        test_world.tick();
+
+        assert!(test_world.world().get_entity(city_entity).is_some());
+        let violations = test_world.world().resource::<Events<ClientViolation>>();
+        assert!(violations
+            .iter_current_update_events()
+            .any(|violation| violation.client_id == ClientId::SERVER));
+    }
+}
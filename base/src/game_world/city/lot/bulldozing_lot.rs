@@ -0,0 +1,621 @@
+use anyhow::{bail, ensure, Context, Result};
+use bevy::{
+    asset::AssetPath,
+    color::palettes::css::RED,
+    ecs::{entity::MapEntities, system::SystemParam},
+    math::Vec3Swizzles,
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use leafwing_input_manager::common_conditions::action_just_pressed;
+use serde::{Deserialize, Serialize};
+
+use super::{permissions::BuildPermissions, LotFamily, LotObjects, LotTool, LotVertices};
+use crate::{
+    asset::info::{object_info::ObjectInfo, scenario_info::ScenarioFeature},
+    game_world::{
+        city::ActiveCity,
+        commands_history::{
+            CommandConfirmation, CommandId, CommandRequest, CommandsHistory, ConfirmableCommand,
+            EntityRecorder, PendingCommand,
+        },
+        family::{
+            building::wall::{self, Wall, WallBundle},
+            Budget,
+        },
+        mutation_log::{Mutation, MutationKind, MutationRecorder},
+        object::{placing_object::ground_fit::PendingGroundCheck, Object, ObjectBundle},
+        player_camera::CameraCaster,
+        scenario::ScenarioLocks,
+        spline::SplineSegment,
+    },
+    math::segment::Segment,
+    network::validation::ClientViolation,
+    settings::Action,
+};
+
+/// Removes all objects and walls inside a lot.
+///
+/// Picking a lot spawns [`BulldozingLot`] which waits for [`LotBulldoze`] from UI.
+pub(super) struct BulldozingLotPlugin;
+
+impl Plugin for BulldozingLotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<LotBulldoze>()
+            .add_mapped_client_event::<CommandRequest<BulldozeCommand>>(ChannelKind::Unordered)
+            .add_systems(
+                Update,
+                (
+                    Self::pick
+                        .run_if(action_just_pressed(Action::Confirm))
+                        .run_if(not(any_with_component::<BulldozingLot>)),
+                    Self::request_bulldoze.run_if(on_event::<LotBulldoze>()),
+                    Self::draw_lines,
+                )
+                    .run_if(in_state(LotTool::Bulldoze)),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::apply_command
+                    .before(ServerSet::StoreHierarchy)
+                    .run_if(server_or_singleplayer),
+            );
+    }
+}
+
+impl BulldozingLotPlugin {
+    fn pick(
+        mut commands: Commands,
+        camera_caster: CameraCaster,
+        contents: LotContents,
+        lots: Query<(Entity, &Parent, &LotVertices)>,
+        cities: Query<Entity, With<ActiveCity>>,
+    ) {
+        let Some(point) = camera_caster.intersect_ground().map(|point| point.xz()) else {
+            return;
+        };
+        let Ok(city_entity) = cities.get_single() else {
+            return;
+        };
+
+        let Some((lot_entity, ..)) = lots.iter().find(|(_, parent, vertices)| {
+            ***parent == city_entity && vertices.contains_point(point)
+        }) else {
+            return;
+        };
+
+        let (objects, walls) = contents.get(lot_entity);
+        info!("picking lot `{lot_entity}` for bulldozing");
+        commands.spawn((
+            StateScoped(LotTool::Bulldoze),
+            BulldozingLot {
+                lot_entity,
+                objects: objects.len(),
+                walls: walls.len(),
+            },
+        ));
+    }
+
+    fn request_bulldoze(
+        mut history: CommandsHistory,
+        mut bulldoze_events: EventReader<LotBulldoze>,
+        contents: LotContents,
+    ) {
+        for &LotBulldoze(lot_entity) in bulldoze_events.read() {
+            let (objects, walls) = contents.get(lot_entity);
+            info!(
+                "bulldozing lot `{lot_entity}` with {} objects and {} walls",
+                objects.len(),
+                walls.len()
+            );
+            history.push_pending(BulldozeCommand::Clear {
+                lot_entity,
+                objects,
+                walls,
+            });
+        }
+    }
+
+    fn draw_lines(
+        mut gizmos: Gizmos,
+        bulldozing_lots: Query<&BulldozingLot>,
+        lots: Query<(&Parent, &LotVertices)>,
+        cities: Query<&GlobalTransform>,
+    ) {
+        for bulldozing_lot in &bulldozing_lots {
+            let Ok((parent, vertices)) = lots.get(bulldozing_lot.lot_entity) else {
+                continue;
+            };
+            let transform = cities.get(**parent).unwrap();
+            let points_iter = vertices
+                .iter()
+                .map(|vertex| transform.transform_point(vertex.extend(0.0).xzy()));
+            gizmos.linestrip(points_iter, RED);
+        }
+    }
+
+    fn apply_command(
+        mut commands: Commands,
+        mut request_events: EventReader<FromClient<CommandRequest<BulldozeCommand>>>,
+        mut confirm_events: EventWriter<ToClients<CommandConfirmation>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        locks: ScenarioLocks,
+        permissions: BuildPermissions,
+        asset_server: Res<AssetServer>,
+        objects_info: Res<Assets<ObjectInfo>>,
+        lots: Query<(&Parent, &LotVertices)>,
+        lot_families: Query<&LotFamily>,
+        mut families: Query<&mut Budget>,
+        objects: Query<(&Parent, &Transform), With<Object>>,
+        object_paths: Query<&Object>,
+        walls: Query<(&Parent, &SplineSegment), With<Wall>>,
+    ) {
+        let object_price = |info_path: &AssetPath| {
+            asset_server
+                .get_handle(info_path)
+                .and_then(|handle| objects_info.get(&handle))
+                .map(|info| info.price)
+        };
+
+        for FromClient { client_id, event } in request_events.read().cloned() {
+            if let Err(violation) = locks.check(ScenarioFeature::Lots, client_id) {
+                violation_events.send(violation);
+                continue;
+            }
+
+            let validation =
+                event
+                    .command
+                    .validate(&lots, &objects, &walls, |city_entity, point| {
+                        permissions.allowed(client_id, city_entity, point)
+                    });
+            let city_entity = match validation {
+                Ok(city_entity) => city_entity,
+                Err(error) => {
+                    error!("unable to apply bulldoze command from `{client_id:?}`: {error:#}");
+                    continue;
+                }
+            };
+
+            // Like buying and selling, contents are paid only on family lots.
+            let mut budget = lot_families
+                .get(event.command.lot_entity())
+                .ok()
+                .and_then(|lot_family| families.get_mut(**lot_family).ok());
+            if let BulldozeCommand::Restore {
+                objects: restored_objects,
+                walls: segments,
+                ..
+            } = &event.command
+            {
+                let Some(objects_price) = restored_objects
+                    .iter()
+                    .map(|restored| object_price(&restored.info_path))
+                    .try_fold(0, |sum: u32, price| {
+                        price.map(|price| sum.saturating_add(price))
+                    })
+                else {
+                    error!("`{client_id:?}` tries to restore unknown objects");
+                    continue;
+                };
+                let price = segments
+                    .iter()
+                    .map(|&segment| wall::wall_price(segment))
+                    .fold(objects_price, u32::saturating_add);
+                if let Some(budget) = &mut budget {
+                    if !budget.spend(price) {
+                        error!("`{client_id:?}` can't afford restoring lot for {price}");
+                        continue;
+                    }
+                }
+            }
+
+            recorder.record(client_id, &event);
+            let mut confirmation = CommandConfirmation::new(event.id);
+            match event.command {
+                BulldozeCommand::Clear {
+                    lot_entity,
+                    objects: object_entities,
+                    walls: wall_entities,
+                } => {
+                    info!("`{client_id:?}` bulldozes lot `{lot_entity}`");
+                    if let Some(budget) = &mut budget {
+                        let refund = object_entities
+                            .iter()
+                            .filter_map(|&entity| object_paths.get(entity).ok())
+                            .filter_map(|object| object_price(&object.0))
+                            .fold(0, u32::saturating_add);
+                        budget.earn(refund);
+                    }
+                    for entity in object_entities {
+                        // Objects placed on other objects will be despawned with their parents.
+                        let (parent, _) = objects.get(entity).unwrap();
+                        if **parent == city_entity {
+                            commands.entity(entity).despawn_recursive();
+                        }
+                    }
+                    for entity in wall_entities {
+                        commands.entity(entity).despawn();
+                    }
+                }
+                BulldozeCommand::Restore {
+                    lot_entity,
+                    objects: restored_objects,
+                    walls: segments,
+                } => {
+                    info!("`{client_id:?}` restores lot `{lot_entity}`");
+                    for restored in restored_objects {
+                        let parent_entity = restored
+                            .parent
+                            .map_or(city_entity, |index| confirmation.entities[index]);
+                        commands.entity(parent_entity).with_children(|parent| {
+                            let transform = Transform::from_translation(restored.translation)
+                                .with_rotation(restored.rotation);
+                            let mut entity =
+                                parent.spawn(ObjectBundle::new(restored.info_path, transform));
+                            if restored.parent.is_none() {
+                                entity.insert(PendingGroundCheck::default());
+                            }
+                            let entity = entity.id();
+                            confirmation.entities.push(entity);
+                        });
+                    }
+                    commands.entity(city_entity).with_children(|parent| {
+                        for segment in segments {
+                            let entity = parent.spawn(WallBundle::new(segment)).id();
+                            confirmation.entities.push(entity);
+                        }
+                    });
+                }
+            }
+
+            confirm_events.send(ToClients {
+                mode: SendMode::Direct(client_id),
+                event: confirmation,
+            });
+        }
+    }
+}
+
+/// Collects objects and walls inside lots.
+#[derive(SystemParam)]
+struct LotContents<'w, 's> {
+    lots: Query<'w, 's, (&'static Parent, &'static LotVertices, &'static LotObjects)>,
+    children: Query<'w, 's, &'static Children>,
+    objects: Query<'w, 's, (), With<Object>>,
+    walls: Query<'w, 's, (Entity, &'static Parent, &'static SplineSegment), With<Wall>>,
+}
+
+impl LotContents<'_, '_> {
+    /// Returns objects and walls of the lot.
+    ///
+    /// Objects placed on other objects are included and always follow their parents.
+    fn get(&self, lot_entity: Entity) -> (Vec<Entity>, Vec<Entity>) {
+        let Ok((lot_parent, vertices, lot_objects)) = self.lots.get(lot_entity) else {
+            return Default::default();
+        };
+
+        let mut objects = lot_objects.0.clone();
+        let mut index = 0;
+        while let Some(&entity) = objects.get(index) {
+            if let Ok(children) = self.children.get(entity) {
+                objects.extend(
+                    children
+                        .iter()
+                        .filter(|&&child| self.objects.contains(child)),
+                );
+            }
+            index += 1;
+        }
+
+        let walls = self
+            .walls
+            .iter()
+            .filter(|(_, wall_parent, segment)| {
+                *wall_parent == lot_parent
+                    && segment
+                        .points()
+                        .into_iter()
+                        .all(|point| vertices.contains_point(point))
+            })
+            .map(|(entity, ..)| entity)
+            .collect();
+
+        (objects, walls)
+    }
+}
+
+/// Lot picked for bulldozing.
+///
+/// Despawned by UI after the player's decision.
+#[derive(Component)]
+pub struct BulldozingLot {
+    pub lot_entity: Entity,
+
+    /// Number of objects that will be removed.
+    pub objects: usize,
+
+    /// Number of walls that will be removed.
+    pub walls: usize,
+}
+
+/// Bulldozes the lot after the player's confirmation.
+#[derive(Clone, Copy, Event)]
+pub struct LotBulldoze(pub Entity);
+
+#[derive(Clone, Deserialize, Serialize)]
+enum BulldozeCommand {
+    /// Despawns listed objects and walls.
+    ///
+    /// Objects are ordered so that parents always come before objects placed on them.
+    Clear {
+        lot_entity: Entity,
+        objects: Vec<Entity>,
+        walls: Vec<Entity>,
+    },
+    /// Spawns previously bulldozed objects and walls.
+    ///
+    /// Spawned entities are confirmed in the same order: objects first, then walls.
+    Restore {
+        lot_entity: Entity,
+        objects: Vec<RestoredObject>,
+        walls: Vec<Segment>,
+    },
+}
+
+impl BulldozeCommand {
+    fn lot_entity(&self) -> Entity {
+        match *self {
+            Self::Clear { lot_entity, .. } | Self::Restore { lot_entity, .. } => lot_entity,
+        }
+    }
+
+    /// Checks that everything affected by the command lies inside the lot and can be built.
+    ///
+    /// Returns the city entity of the lot.
+    fn validate(
+        &self,
+        lots: &Query<(&Parent, &LotVertices)>,
+        objects: &Query<(&Parent, &Transform), With<Object>>,
+        walls: &Query<(&Parent, &SplineSegment), With<Wall>>,
+        allowed: impl Fn(Entity, Vec2) -> bool,
+    ) -> Result<Entity> {
+        let lot_entity = self.lot_entity();
+        let (city_entity, vertices) = lots
+            .get(lot_entity)
+            .map(|(parent, vertices)| (**parent, vertices))
+            .with_context(|| format!("lot `{lot_entity}` doesn't exist"))?;
+        let check_point = |point: Vec2| -> Result<()> {
+            ensure!(
+                vertices.contains_point(point),
+                "point {point} is outside of the lot"
+            );
+            ensure!(allowed(city_entity, point), "no build rights at {point}");
+            Ok(())
+        };
+
+        match self {
+            Self::Clear {
+                objects: object_entities,
+                walls: wall_entities,
+                ..
+            } => {
+                for (index, &entity) in object_entities.iter().enumerate() {
+                    let (parent, transform) = objects
+                        .get(entity)
+                        .with_context(|| format!("object `{entity}` doesn't exist"))?;
+                    if **parent == city_entity {
+                        check_point(transform.translation.xz())?;
+                    } else if !object_entities[..index].contains(&**parent) {
+                        bail!("parent of object `{entity}` isn't bulldozed");
+                    }
+                }
+                for &entity in wall_entities {
+                    let (parent, segment) = walls
+                        .get(entity)
+                        .with_context(|| format!("wall `{entity}` doesn't exist"))?;
+                    ensure!(
+                        **parent == city_entity,
+                        "wall `{entity}` belongs to another city"
+                    );
+                    for point in segment.points() {
+                        check_point(point)?;
+                    }
+                }
+            }
+            Self::Restore {
+                objects: restored_objects,
+                walls: segments,
+                ..
+            } => {
+                for (index, restored) in restored_objects.iter().enumerate() {
+                    match restored.parent {
+                        Some(parent_index) => ensure!(
+                            parent_index < index,
+                            "object should be restored after its parent"
+                        ),
+                        None => check_point(restored.translation.xz())?,
+                    }
+                }
+                for segment in segments {
+                    for point in segment.points() {
+                        check_point(point)?;
+                    }
+                }
+            }
+        }
+
+        Ok(city_entity)
+    }
+}
+
+impl Mutation for BulldozeCommand {
+    const TARGET: &'static str = "lot contents";
+
+    fn kind(&self) -> MutationKind {
+        match self {
+            Self::Clear { .. } => MutationKind::Delete,
+            Self::Restore { .. } => MutationKind::Spawn,
+        }
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        Some(self.lot_entity())
+    }
+}
+
+impl PendingCommand for BulldozeCommand {
+    fn apply(
+        self: Box<Self>,
+        id: CommandId,
+        mut recorder: EntityRecorder,
+        world: &mut World,
+    ) -> Box<dyn ConfirmableCommand> {
+        let reverse_command = match &*self {
+            Self::Clear {
+                lot_entity,
+                objects,
+                walls,
+            } => {
+                for &entity in objects.iter().chain(walls) {
+                    recorder.record(entity);
+                }
+
+                let restored_objects = objects
+                    .iter()
+                    .map(|&entity| {
+                        let entity = world.entity(entity);
+                        let parent = entity.get::<Parent>().unwrap();
+                        let transform = entity.get::<Transform>().unwrap();
+                        RestoredObject {
+                            info_path: entity.get::<Object>().unwrap().0.clone(),
+                            parent: objects.iter().position(|&object| object == **parent),
+                            translation: transform.translation,
+                            rotation: transform.rotation,
+                        }
+                    })
+                    .collect();
+                let segments = walls
+                    .iter()
+                    .map(|&entity| **world.get::<SplineSegment>(entity).unwrap())
+                    .collect();
+
+                Self::Restore {
+                    lot_entity: *lot_entity,
+                    objects: restored_objects,
+                    walls: segments,
+                }
+            }
+            Self::Restore {
+                lot_entity,
+                objects,
+                walls,
+            } => Self::Clear {
+                lot_entity: *lot_entity,
+                // Correct entities will be set after the server confirmation.
+                objects: vec![Entity::PLACEHOLDER; objects.len()],
+                walls: vec![Entity::PLACEHOLDER; walls.len()],
+            },
+        };
+
+        world.send_event(CommandRequest { id, command: *self });
+
+        Box::new(reverse_command)
+    }
+}
+
+impl ConfirmableCommand for BulldozeCommand {
+    fn confirm(
+        mut self: Box<Self>,
+        mut recorder: EntityRecorder,
+        confirmation: CommandConfirmation,
+    ) -> Box<dyn PendingCommand> {
+        if let Self::Clear { objects, walls, .. } = &mut *self {
+            assert_eq!(
+                objects.len() + walls.len(),
+                confirmation.entities.len(),
+                "confirmation for lot restoring should contain all spawned entities"
+            );
+            let (object_entities, wall_entities) = confirmation.entities.split_at(objects.len());
+            objects.copy_from_slice(object_entities);
+            walls.copy_from_slice(wall_entities);
+            for &entity in objects.iter().chain(walls.iter()) {
+                recorder.record(entity);
+            }
+        }
+
+        self
+    }
+}
+
+impl MapEntities for BulldozeCommand {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        match self {
+            Self::Clear {
+                lot_entity,
+                objects,
+                walls,
+            } => {
+                *lot_entity = entity_mapper.map_entity(*lot_entity);
+                for entity in objects.iter_mut().chain(walls) {
+                    *entity = entity_mapper.map_entity(*entity);
+                }
+            }
+            Self::Restore { lot_entity, .. } => *lot_entity = entity_mapper.map_entity(*lot_entity),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct RestoredObject {
+    info_path: AssetPath<'static>,
+
+    /// Index of the parent object inside the command.
+    ///
+    /// `None` if the object placed directly in the city.
+    parent: Option<usize>,
+
+    /// Translation relative to the parent.
+    translation: Vec3,
+    rotation: Quat,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_world::TestWorld;
+
+    #[test]
+    fn restore_without_funds() {
+        let mut test_world = TestWorld::builder().build();
+        let city_entity = test_world.spawn_city("City");
+        let family_entity = test_world.spawn_family(city_entity, "Family", 1);
+        let lot_entity = test_world.spawn_lot(
+            city_entity,
+            [Vec2::ZERO, Vec2::X * 10.0, Vec2::ONE * 10.0, Vec2::Y * 10.0],
+        );
+        let world = test_world.world_mut();
+        world
+            .entity_mut(lot_entity)
+            .insert(LotFamily(family_entity));
+        *world.get_mut::<Budget>(family_entity).unwrap() = Budget::default();
+
+        // Families without owners allow building for everyone, only the budget is checked.
+        world.send_event(FromClient {
+            client_id: ClientId::new(1),
+            event: CommandRequest {
+                id: CommandId::default(),
+                command: BulldozeCommand::Restore {
+                    lot_entity,
+                    objects: Vec::new(),
+                    walls: vec![Segment::new(Vec2::ONE, Vec2::new(5.0, 1.0))],
+                },
+            },
+        });
+        test_world.tick();
+
+        let mut walls = test_world.world_mut().query_filtered::<(), With<Wall>>();
+        assert_eq!(walls.iter(test_world.world()).count(), 0);
+    }
+}
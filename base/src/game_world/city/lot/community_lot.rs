@@ -0,0 +1,567 @@
+use std::time::Duration;
+
+use bevy::{
+    asset::AssetPath,
+    color::palettes::css::LIME,
+    ecs::{entity::MapEntities, system::SystemParam},
+    math::Vec3Swizzles,
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use leafwing_input_manager::{common_conditions::action_just_pressed, prelude::ActionState};
+use serde::{Deserialize, Serialize};
+
+use super::{LotFamily, LotTool, LotVertices};
+use crate::{
+    asset::info::{
+        scenario_info::ScenarioFeature,
+        service_info::{ServiceEffect, ServiceInfo},
+    },
+    game_world::{
+        actor::{
+            needs::{Need, NeedKind},
+            skills::Handiness,
+            task::travel::InTransit,
+            Actor,
+        },
+        city::ActiveCity,
+        clock,
+        family::FamilyControl,
+        mutation_log::{Mutation, MutationKind, MutationRecorder},
+        player_camera::CameraCaster,
+        scenario::ScenarioLocks,
+        WorldState,
+    },
+    network::validation::ClientViolation,
+    settings::Action,
+};
+
+/// Lots that provide services, like parks or gyms, defined by [`ServiceInfo`].
+///
+/// Actors standing on such lots receive the service effects every game minute.
+pub(super) struct CommunityLotPlugin;
+
+impl Plugin for CommunityLotPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<LotService>()
+            .replicate::<LotService>()
+            .add_mapped_client_event::<LotServiceAssign>(ChannelKind::Ordered)
+            .add_systems(
+                PreUpdate,
+                Self::assign
+                    .after(ServerSet::Receive)
+                    .run_if(server_or_singleplayer),
+            )
+            .add_systems(
+                Update,
+                Self::pick
+                    .run_if(
+                        action_just_pressed(Action::Confirm)
+                            .and_then(resource_exists::<SelectedService>)
+                            .or_else(action_just_pressed(Action::Delete)),
+                    )
+                    .run_if(in_state(LotTool::Service)),
+            )
+            .add_systems(OnExit(LotTool::Service), Self::clear_selection)
+            .add_systems(
+                FixedUpdate,
+                Self::visit_on_site
+                    .run_if(clock::on_game_timer(Duration::from_secs(60)))
+                    .run_if(server_or_singleplayer),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::draw_coverage.run_if(in_state(WorldState::City)),
+            );
+    }
+}
+
+impl CommunityLotPlugin {
+    /// Assigns [`SelectedService`] to the lot under cursor or clears the service on deletion.
+    fn pick(
+        mut assign_events: EventWriter<LotServiceAssign>,
+        camera_caster: CameraCaster,
+        asset_server: Res<AssetServer>,
+        action_state: Res<ActionState<Action>>,
+        selected_service: Option<Res<SelectedService>>,
+        lots: Query<(Entity, &Parent, &LotVertices)>,
+        cities: Query<Entity, With<ActiveCity>>,
+    ) {
+        let Some(point) = camera_caster.intersect_ground().map(|point| point.xz()) else {
+            return;
+        };
+        let Ok(city_entity) = cities.get_single() else {
+            return;
+        };
+        let Some((lot_entity, ..)) = lots.iter().find(|(_, parent, vertices)| {
+            ***parent == city_entity && vertices.contains_point(point)
+        }) else {
+            return;
+        };
+
+        let service = if action_state.just_pressed(&Action::Delete) {
+            None
+        } else {
+            let id = selected_service
+                .expect("picking should run only with a selected service")
+                .0;
+            let path = asset_server
+                .get_path(id)
+                .expect("info should always come from file");
+            Some(path.into_owned())
+        };
+
+        info!("assigning service `{service:?}` to lot `{lot_entity}`");
+        assign_events.send(LotServiceAssign {
+            entity: lot_entity,
+            service,
+        });
+    }
+
+    fn clear_selection(mut commands: Commands) {
+        debug!("clearing selected service");
+        commands.remove_resource::<SelectedService>();
+    }
+
+    fn assign(
+        mut commands: Commands,
+        mut assign_events: EventReader<FromClient<LotServiceAssign>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        asset_server: Res<AssetServer>,
+        locks: ScenarioLocks,
+        control: FamilyControl,
+        lots: Query<Option<&LotFamily>, With<LotVertices>>,
+    ) {
+        for FromClient { client_id, event } in assign_events.read().cloned() {
+            if let Err(violation) = locks.check(ScenarioFeature::Lots, client_id) {
+                violation_events.send(violation);
+                continue;
+            }
+
+            let Ok(lot_family) = lots.get(event.entity) else {
+                error!(
+                    "`{client_id:?}` tries to assign service to non-existing lot `{:?}`",
+                    event.entity
+                );
+                continue;
+            };
+            if let Some(&lot_family) = lot_family {
+                if !control.family_allowed(client_id, *lot_family) {
+                    error!(
+                        "`{client_id:?}` tries to change service of lot `{:?}` of another family",
+                        event.entity
+                    );
+                    violation_events.send(ClientViolation {
+                        client_id,
+                        reason: "changing service of a lot owned by another family".into(),
+                    });
+                    continue;
+                }
+            }
+
+            match &event.service {
+                Some(path) => {
+                    if lot_family.is_some() {
+                        error!(
+                            "`{client_id:?}` tries to assign service to residential lot `{:?}`",
+                            event.entity
+                        );
+                        continue;
+                    }
+                    if asset_server.get_handle::<ServiceInfo>(path).is_none() {
+                        error!("`{client_id:?}` tries to assign unknown service '{path}'");
+                        continue;
+                    }
+
+                    info!(
+                        "`{client_id:?}` assigns '{path}' to lot `{:?}`",
+                        event.entity
+                    );
+                    recorder.record(client_id, &event);
+                    commands
+                        .entity(event.entity)
+                        .insert(LotService(path.clone()));
+                }
+                None => {
+                    info!("`{client_id:?}` clears service of lot `{:?}`", event.entity);
+                    recorder.record(client_id, &event);
+                    commands.entity(event.entity).remove::<LotService>();
+                }
+            }
+        }
+    }
+
+    fn visit_on_site(
+        mut commands: Commands,
+        mut effects: ServiceEffects,
+        mut actors: Query<
+            (
+                Entity,
+                &Parent,
+                &Transform,
+                &Children,
+                Option<&mut ServiceVisit>,
+            ),
+            (With<Actor>, Without<InTransit>),
+        >,
+        lots: Query<(Entity, &Parent, &LotVertices, &LotService)>,
+    ) {
+        for (actor_entity, actor_parent, transform, children, visit) in &mut actors {
+            let position = transform.translation.xz();
+            let Some((lot_entity, .., service)) =
+                lots.iter().find(|(_, lot_parent, vertices, _)| {
+                    *lot_parent == actor_parent && vertices.contains_point(position)
+                })
+            else {
+                if visit.is_some() {
+                    debug!("`{actor_entity}` left community lot");
+                    commands.entity(actor_entity).remove::<ServiceVisit>();
+                }
+                continue;
+            };
+
+            let elapsed = match visit {
+                Some(mut visit) if visit.lot_entity == lot_entity => {
+                    visit.elapsed += 1;
+                    visit.elapsed
+                }
+                _ => {
+                    debug!("`{actor_entity}` visits community lot `{lot_entity}`");
+                    commands.entity(actor_entity).insert(ServiceVisit {
+                        lot_entity,
+                        elapsed: 0,
+                    });
+                    0
+                }
+            };
+
+            effects.apply(service, actor_entity, children, elapsed);
+        }
+    }
+
+    fn draw_coverage(
+        mut gizmos: Gizmos,
+        asset_server: Res<AssetServer>,
+        services_info: Res<Assets<ServiceInfo>>,
+        lots: Query<(&Parent, &LotVertices, &LotService)>,
+        cities: Query<&GlobalTransform, With<ActiveCity>>,
+    ) {
+        for (parent, vertices, service) in &lots {
+            let Ok(transform) = cities.get(**parent) else {
+                continue;
+            };
+            let Some(info) = asset_server
+                .get_handle(&service.0)
+                .and_then(|handle| services_info.get(&handle))
+            else {
+                continue;
+            };
+
+            let center = vertices.center();
+            let point = transform.transform_point(Vec3::new(center.x, 0.0, center.y));
+            gizmos.circle(point, Dir3::Y, info.coverage, LIME);
+        }
+    }
+}
+
+/// Counts residential lots within the coverage of community lots.
+#[derive(SystemParam)]
+pub struct ServiceCoverage<'w, 's> {
+    asset_server: Res<'w, AssetServer>,
+    services_info: Res<'w, Assets<ServiceInfo>>,
+    lots: Query<
+        'w,
+        's,
+        (
+            &'static Parent,
+            &'static LotVertices,
+            Option<&'static LotService>,
+        ),
+    >,
+}
+
+impl ServiceCoverage<'_, '_> {
+    /// Returns the number of covered residential lots for each service in the city
+    /// and the total number of residential lots.
+    ///
+    /// Services are sorted by name.
+    pub fn city_coverage(&self, city_entity: Entity) -> (Vec<(String, usize)>, usize) {
+        let city_lots = || {
+            self.lots
+                .iter()
+                .filter(move |(parent, ..)| ***parent == city_entity)
+        };
+        let residential: Vec<_> = city_lots()
+            .filter(|(.., service)| service.is_none())
+            .map(|(_, vertices, _)| vertices.center())
+            .collect();
+
+        // Group coverage areas by service.
+        let mut services: Vec<(String, Vec<(Vec2, f32)>)> = Vec::new();
+        for (_, vertices, service) in city_lots() {
+            let Some(info) = service.and_then(|service| {
+                self.asset_server
+                    .get_handle(&service.0)
+                    .and_then(|handle| self.services_info.get(&handle))
+            }) else {
+                continue;
+            };
+
+            let area = (vertices.center(), info.coverage);
+            match services
+                .iter_mut()
+                .find(|(name, _)| *name == info.general.name)
+            {
+                Some((_, areas)) => areas.push(area),
+                None => services.push((info.general.name.clone(), vec![area])),
+            }
+        }
+
+        let mut coverage: Vec<_> = services
+            .into_iter()
+            .map(|(name, areas)| (name, covered_lots(&areas, &residential)))
+            .collect();
+        coverage.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        (coverage, residential.len())
+    }
+}
+
+/// Returns the number of lot centers within any of the areas.
+///
+/// Areas are defined by their centers and radiuses.
+fn covered_lots(areas: &[(Vec2, f32)], lot_centers: &[Vec2]) -> usize {
+    lot_centers
+        .iter()
+        .filter(|lot_center| {
+            areas
+                .iter()
+                .any(|&(center, radius)| lot_center.distance(center) <= radius)
+        })
+        .count()
+}
+
+/// Applies [`ServiceEffect`]s to actors.
+#[derive(SystemParam)]
+pub(crate) struct ServiceEffects<'w, 's> {
+    asset_server: Res<'w, AssetServer>,
+    services_info: Res<'w, Assets<ServiceInfo>>,
+    needs: Query<'w, 's, (&'static mut Need, &'static NeedKind)>,
+    skills: Query<'w, 's, &'static mut Handiness>,
+}
+
+impl ServiceEffects<'_, '_> {
+    /// Applies effects of a single step of the visit.
+    ///
+    /// `elapsed` is the number of steps since the visit started.
+    pub(crate) fn apply(
+        &mut self,
+        service: &LotService,
+        actor_entity: Entity,
+        children: &Children,
+        elapsed: u32,
+    ) {
+        let Some(info) = self
+            .asset_server
+            .get_handle(&service.0)
+            .and_then(|handle| self.services_info.get(&handle))
+        else {
+            error!("service '{}' is not loaded", service.0);
+            return;
+        };
+
+        for &effect in &info.effects {
+            match effect {
+                ServiceEffect::Need { kind, rate } => {
+                    let mut needs = self.needs.iter_many_mut(children);
+                    while let Some((mut need, &need_kind)) = needs.fetch_next() {
+                        if need_kind == kind {
+                            need.add(rate);
+                        }
+                    }
+                }
+                ServiceEffect::Handiness { interval } => {
+                    if interval != 0 && elapsed != 0 && elapsed % interval == 0 {
+                        if let Ok(mut handiness) = self.skills.get_mut(actor_entity) {
+                            debug!("improving handiness of `{actor_entity}` by visiting");
+                            handiness.improve();
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Path to [`ServiceInfo`] that the lot provides.
+///
+/// Lots with a service can't be owned by families.
+#[derive(Component, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct LotService(pub(crate) AssetPath<'static>);
+
+/// Service to assign to lots with [`LotTool::Service`].
+///
+/// Picking won't happen until this resource is inserted.
+#[derive(Resource)]
+pub struct SelectedService(pub AssetId<ServiceInfo>);
+
+/// Assigns a service to a lot or clears it when the service is `None`.
+#[derive(Clone, Debug, Deserialize, Event, PartialEq, Serialize)]
+struct LotServiceAssign {
+    entity: Entity,
+    service: Option<AssetPath<'static>>,
+}
+
+impl MapEntities for LotServiceAssign {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.entity = entity_mapper.map_entity(self.entity);
+    }
+}
+
+impl Mutation for LotServiceAssign {
+    const TARGET: &'static str = "lot service";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Edit
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        Some(self.entity)
+    }
+}
+
+/// Community lot on which the actor currently stays.
+///
+/// Server-only.
+#[derive(Component)]
+struct ServiceVisit {
+    lot_entity: Entity,
+    elapsed: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game_world::{mutation_log::MutationLog, UnsavedChanges},
+        test_world::TestWorld,
+    };
+
+    #[test]
+    fn coverage() {
+        let areas = [(Vec2::ZERO, 10.0), (Vec2::X * 30.0, 5.0)];
+        let lot_centers = [
+            Vec2::X * 5.0,
+            // Covered by both areas.
+            Vec2::new(8.0, 0.0),
+            Vec2::X * 32.0,
+            Vec2::X * 20.0,
+        ];
+        assert_eq!(covered_lots(&areas, &lot_centers), 3);
+        assert_eq!(covered_lots(&[], &lot_centers), 0);
+    }
+
+    #[test]
+    fn residential_assignment() {
+        let mut test_world = TestWorld::builder().build();
+        let city_entity = test_world.spawn_city("City");
+        let family_entity = test_world.spawn_family(city_entity, "Family", 1);
+        let lot_entity = test_world.spawn_lot(
+            city_entity,
+            [
+                Vec2::ZERO,
+                Vec2::X * 10.0,
+                Vec2::new(10.0, 10.0),
+                Vec2::Y * 10.0,
+            ],
+        );
+        test_world
+            .world_mut()
+            .entity_mut(lot_entity)
+            .insert(LotFamily(family_entity));
+
+        test_world.world_mut().send_event(FromClient {
+            client_id: ClientId::SERVER,
+            event: LotServiceAssign {
+                entity: lot_entity,
+                service: Some("base/services/park/park.service.ron".into()),
+            },
+        });
+        test_world.tick();
+
+        assert!(!test_world
+            .world()
+            .entity(lot_entity)
+            .contains::<LotService>());
+    }
+
+    #[test]
+    fn foreign_family_lot() {
+        let mut test_world = TestWorld::builder().build();
+        let city_entity = test_world.spawn_city("City");
+        let family_entity = test_world.spawn_family(city_entity, "Family", 1);
+        let lot_entity = test_world.spawn_lot(
+            city_entity,
+            [
+                Vec2::ZERO,
+                Vec2::X * 10.0,
+                Vec2::new(10.0, 10.0),
+                Vec2::Y * 10.0,
+            ],
+        );
+        test_world.world_mut().entity_mut(lot_entity).insert((
+            LotFamily(family_entity),
+            LotService("base/services/park/park.service.ron".into()),
+        ));
+
+        // Families without owners can be controlled only by the server.
+        let client_id = ClientId::new(1);
+        test_world.world_mut().send_event(FromClient {
+            client_id,
+            event: LotServiceAssign {
+                entity: lot_entity,
+                service: None,
+            },
+        });
+        test_world.tick();
+
+        assert!(test_world
+            .world()
+            .entity(lot_entity)
+            .contains::<LotService>());
+        let violations = test_world.world().resource::<Events<ClientViolation>>();
+        assert!(violations
+            .iter_current_update_events()
+            .any(|violation| violation.client_id == client_id));
+
+        // Rejected requests shouldn't be logged.
+        let log = test_world.world().resource::<MutationLog>();
+        assert_eq!(log.entries().count(), 0);
+        assert!(!test_world.world().resource::<UnsavedChanges>().0);
+
+        let event = LotServiceAssign {
+            entity: lot_entity,
+            service: None,
+        };
+        test_world.world_mut().send_event(FromClient {
+            client_id: ClientId::SERVER,
+            event: event.clone(),
+        });
+        test_world.tick();
+
+        assert!(!test_world
+            .world()
+            .entity(lot_entity)
+            .contains::<LotService>());
+        let log = test_world.world().resource::<MutationLog>();
+        let entry = log
+            .entries()
+            .next()
+            .expect("accepted request should be logged");
+        assert_eq!(entry.client_id, ClientId::SERVER);
+        assert_eq!(entry.event::<LotServiceAssign>(), Some(event));
+        assert!(test_world.world().resource::<UnsavedChanges>().0);
+    }
+}
@@ -1,10 +1,15 @@
+use anyhow::Result;
 use bevy::{math::Vec3Swizzles, prelude::*};
 use bevy_replicon::prelude::*;
 use leafwing_input_manager::common_conditions::action_just_pressed;
 
 use super::{LotCreate, LotEventConfirmed, LotTool, LotVertices, UnconfirmedLot};
 use crate::{
-    game_world::{city::ActiveCity, player_camera::CameraCaster},
+    game_world::{
+        city::{ActiveCity, GroundSampler},
+        player_camera::CameraCaster,
+    },
+    message::error_message,
     settings::Action,
 };
 
@@ -26,7 +31,9 @@ impl Plugin for CreatingLotPlugin {
                     .run_if(action_just_pressed(Action::Confirm))
                     .run_if(not(any_with_component::<CreatingLot>)),
                 Self::set_vertex_position,
-                Self::confirm.run_if(action_just_pressed(Action::Confirm)),
+                Self::confirm
+                    .pipe(error_message)
+                    .run_if(action_just_pressed(Action::Confirm)),
                 Self::end_creation.run_if(action_just_pressed(Action::Cancel)),
             )
                 .run_if(in_state(LotTool::Create)),
@@ -79,25 +86,31 @@ impl CreatingLotPlugin {
 
     fn confirm(
         mut create_events: EventWriter<LotCreate>,
+        ground_sampler: GroundSampler,
         mut creating_lots: Query<&mut LotVertices, (With<CreatingLot>, Without<UnconfirmedLot>)>,
-        cities: Query<Entity, With<ActiveCity>>,
-    ) {
+        cities: Query<(Entity, &GlobalTransform), With<ActiveCity>>,
+    ) -> Result<()> {
         if let Ok(mut lot_vertices) = creating_lots.get_single_mut() {
             let first_vertex = *lot_vertices
                 .first()
                 .expect("vertices should have at least 2 vertices");
             let last_vertex = *lot_vertices.last().unwrap();
             if first_vertex == last_vertex {
+                let (city_entity, city_transform) = cities.single();
+                super::check_ground(&ground_sampler, city_transform, &lot_vertices)?;
+
                 info!("confirming lot creation");
                 create_events.send(LotCreate {
                     polygon: lot_vertices.0.clone(),
-                    city_entity: cities.single(),
+                    city_entity,
                 });
             } else {
                 info!("confirming lot point");
                 lot_vertices.push(last_vertex);
             }
         }
+
+        Ok(())
     }
 
     fn end_creation(mut commands: Commands, creating_lots: Query<Entity, With<CreatingLot>>) {
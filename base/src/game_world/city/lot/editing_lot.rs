@@ -0,0 +1,235 @@
+use anyhow::Result;
+use bevy::{color::palettes::css::ORANGE, math::Vec3Swizzles, prelude::*};
+use bevy_replicon::prelude::*;
+use leafwing_input_manager::common_conditions::action_just_pressed;
+
+use super::{LotEdit, LotEventConfirmed, LotTool, LotVertices};
+use crate::{
+    game_world::{
+        city::{road::Road, ActiveCity},
+        player_camera::CameraCaster,
+        spline::SplineSegment,
+    },
+    math::{polygon::Polygon, segment::Segment},
+    message::error_message,
+    settings::Action,
+};
+
+/// Reshapes existing lots by dragging their vertices and edges.
+pub(super) struct EditingLotPlugin;
+
+impl Plugin for EditingLotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            Self::end_editing
+                .after(ClientSet::Receive)
+                .run_if(in_state(LotTool::Edit))
+                .run_if(on_event::<LotEventConfirmed>()),
+        )
+        .add_systems(
+            Update,
+            (
+                Self::pick
+                    .run_if(action_just_pressed(Action::Confirm))
+                    .run_if(not(any_with_component::<EditingLot>)),
+                Self::drag,
+                Self::confirm
+                    .pipe(error_message)
+                    .run_if(action_just_pressed(Action::Confirm)),
+                Self::end_editing.run_if(action_just_pressed(Action::Cancel)),
+                Self::draw_handles,
+            )
+                .run_if(in_state(LotTool::Edit)),
+        )
+        .add_systems(
+            PostUpdate,
+            Self::cleanup_despawned.run_if(in_state(LotTool::Edit)),
+        );
+    }
+}
+
+/// Maximum distance from the cursor to a vertex or an edge to pick it.
+const HANDLE_RADIUS: f32 = 0.3;
+
+impl EditingLotPlugin {
+    fn pick(
+        mut commands: Commands,
+        camera_caster: CameraCaster,
+        lots: Query<(Entity, &Parent, &LotVertices)>,
+        cities: Query<Entity, With<ActiveCity>>,
+    ) {
+        let Some(point) = camera_caster.intersect_ground().map(|point| point.xz()) else {
+            return;
+        };
+        let Ok(city_entity) = cities.get_single() else {
+            return;
+        };
+
+        for (entity, parent, vertices) in &lots {
+            if **parent != city_entity {
+                continue;
+            }
+
+            let Some(handle) = LotHandle::find(vertices, point) else {
+                continue;
+            };
+
+            info!("picking `{handle:?}` of lot `{entity}`");
+            commands.entity(city_entity).with_children(|parent| {
+                parent.spawn((
+                    StateScoped(LotTool::Edit),
+                    vertices.clone(),
+                    EditingLot {
+                        entity,
+                        handle,
+                        origin: point,
+                        original: vertices.0.clone(),
+                    },
+                ));
+            });
+            return;
+        }
+    }
+
+    fn drag(camera_caster: CameraCaster, mut editing_lots: Query<(&mut LotVertices, &EditingLot)>) {
+        let Ok((mut vertices, editing_lot)) = editing_lots.get_single_mut() else {
+            return;
+        };
+        let Some(point) = camera_caster.intersect_ground().map(|point| point.xz()) else {
+            return;
+        };
+
+        let offset = point - editing_lot.origin;
+        let mut polygon = editing_lot.original.clone();
+        match editing_lot.handle {
+            LotHandle::Vertex(index) => move_vertex(&mut polygon, index, offset),
+            LotHandle::Edge(index) => {
+                move_vertex(&mut polygon, index, offset);
+                move_vertex(&mut polygon, index + 1, offset);
+            }
+        }
+
+        vertices.0 = polygon;
+    }
+
+    fn confirm(
+        mut edit_events: EventWriter<LotEdit>,
+        editing_lots: Query<(&Parent, &LotVertices, &EditingLot)>,
+        lots: Query<(Entity, &Parent, &LotVertices), Without<EditingLot>>,
+        roads: Query<(&Parent, &SplineSegment), With<Road>>,
+    ) -> Result<()> {
+        let Ok((parent, vertices, editing_lot)) = editing_lots.get_single() else {
+            return Ok(());
+        };
+
+        let other_lots = lots
+            .iter()
+            .filter(|&(entity, other_parent, _)| {
+                entity != editing_lot.entity && other_parent == parent
+            })
+            .map(|(.., vertices)| &vertices.0);
+        let roads = roads
+            .iter()
+            .filter(|&(road_parent, _)| road_parent == parent)
+            .map(|(_, segment)| **segment);
+        super::validate_shape(vertices, other_lots, roads)?;
+
+        info!("confirming lot edit");
+        edit_events.send(LotEdit {
+            entity: editing_lot.entity,
+            polygon: vertices.0.clone(),
+        });
+
+        Ok(())
+    }
+
+    fn draw_handles(
+        mut gizmos: Gizmos,
+        lots: Query<(&Parent, &LotVertices)>,
+        cities: Query<&GlobalTransform, With<ActiveCity>>,
+    ) {
+        for (parent, vertices) in &lots {
+            let Ok(transform) = cities.get(**parent) else {
+                continue;
+            };
+            for vertex in vertices.iter() {
+                let point = transform.transform_point(vertex.extend(0.0).xzy());
+                gizmos.circle(point, Dir3::Y, HANDLE_RADIUS, ORANGE);
+            }
+        }
+    }
+
+    fn end_editing(mut commands: Commands, editing_lots: Query<Entity, With<EditingLot>>) {
+        if let Ok(entity) = editing_lots.get_single() {
+            info!("ending lot editing");
+            commands.entity(entity).despawn();
+        }
+    }
+
+    fn cleanup_despawned(mut commands: Commands, editing_lots: Query<(Entity, &EditingLot)>) {
+        if let Ok((entity, editing_lot)) = editing_lots.get_single() {
+            if commands.get_entity(editing_lot.entity).is_none() {
+                info!(
+                    "cancelling editing for despawned lot `{:?}`",
+                    editing_lot.entity
+                );
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+/// Moves a vertex keeping the polygon closed.
+fn move_vertex(polygon: &mut Polygon, index: usize, offset: Vec2) {
+    let last_index = polygon.len() - 1;
+    polygon[index] += offset;
+    if index == 0 {
+        polygon[last_index] += offset;
+    } else if index == last_index {
+        polygon[0] += offset;
+    }
+}
+
+#[derive(Component)]
+pub struct EditingLot {
+    /// The entity of the lot for which the editing is performed.
+    entity: Entity,
+    handle: LotHandle,
+
+    /// Cursor position when the handle was picked.
+    origin: Vec2,
+
+    /// Vertices before editing.
+    original: Polygon,
+}
+
+/// Part of the lot boundary that is dragged.
+#[derive(Clone, Copy, Debug)]
+enum LotHandle {
+    /// Index of the vertex.
+    Vertex(usize),
+    /// Index of the edge start vertex.
+    Edge(usize),
+}
+
+impl LotHandle {
+    /// Returns the closest vertex or edge within [`HANDLE_RADIUS`], preferring vertices.
+    fn find(vertices: &LotVertices, point: Vec2) -> Option<Self> {
+        // Skip the last vertex since it's the same as the first one for closed polygons.
+        let vertex = vertices
+            .iter()
+            .take(vertices.len().saturating_sub(1))
+            .position(|vertex| vertex.distance(point) <= HANDLE_RADIUS);
+        if let Some(index) = vertex {
+            return Some(Self::Vertex(index));
+        }
+
+        vertices
+            .edges()
+            .position(|edge: Segment| {
+                edge.start != edge.end && edge.closest_point(point).distance(point) <= HANDLE_RADIUS
+            })
+            .map(Self::Edge)
+    }
+}
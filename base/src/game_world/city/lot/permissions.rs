@@ -0,0 +1,216 @@
+use bevy::{
+    ecs::{entity::MapEntities, system::SystemParam},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{LotFamily, LotVertices};
+use crate::{
+    game_world::{
+        family::FamilyOwner,
+        mutation_log::{Mutation, MutationKind, MutationRecorder},
+    },
+    network::{
+        moderation::{ClientIdentities, PlayerIdentity},
+        validation::ClientViolation,
+    },
+};
+
+/// Build rights on lots owned by families.
+///
+/// Walls and objects on such lots can be modified only by the owner of the family
+/// and clients listed in [`LotBuilders`].
+pub(super) struct LotPermissionsPlugin;
+
+impl Plugin for LotPermissionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<LotPermissions>()
+            .replicate::<LotBuilders>()
+            .add_mapped_client_event::<LotPermissionChange>(ChannelKind::Ordered)
+            .add_systems(
+                PreUpdate,
+                (Self::apply_changes, Self::update_builders)
+                    .chain()
+                    .after(ServerSet::Receive)
+                    .run_if(server_or_singleplayer),
+            );
+    }
+}
+
+impl LotPermissionsPlugin {
+    fn apply_changes(
+        mut change_events: EventReader<FromClient<LotPermissionChange>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        mut lots: Query<(&LotFamily, &mut LotPermissions)>,
+        identities: Res<ClientIdentities>,
+        families: Query<&FamilyOwner>,
+    ) {
+        for FromClient { client_id, event } in change_events.read().copied() {
+            let Ok((lot_family, mut permissions)) = lots.get_mut(event.lot_entity) else {
+                error!(
+                    "`{client_id:?}` tries to change permissions for lot `{}` without a family",
+                    event.lot_entity
+                );
+                continue;
+            };
+            if !families
+                .get(lot_family.0)
+                .is_ok_and(|&owner| *owner == client_id)
+            {
+                error!(
+                    "`{client_id:?}` tries to change permissions for not owned lot `{}`",
+                    event.lot_entity
+                );
+                violation_events.send(ClientViolation {
+                    client_id,
+                    reason: "changing permissions of a lot owned by another family".into(),
+                });
+                continue;
+            }
+            let Some(identity) = identities.get(event.client_id) else {
+                error!(
+                    "`{client_id:?}` tries to change build rights of disconnected `{:?}`",
+                    event.client_id
+                );
+                continue;
+            };
+
+            if event.allowed {
+                if !permissions.contains(&identity) {
+                    info!(
+                        "`{client_id:?}` grants build rights on lot `{}` to `{:?}`",
+                        event.lot_entity, event.client_id
+                    );
+                    recorder.record(client_id, &event);
+                    permissions.0.push(identity);
+                }
+            } else {
+                info!(
+                    "`{client_id:?}` revokes build rights on lot `{}` from `{:?}`",
+                    event.lot_entity, event.client_id
+                );
+                recorder.record(client_id, &event);
+                permissions.0.retain(|&allowed| allowed != identity);
+            }
+        }
+    }
+
+    /// Resolves permitted identities into connected clients.
+    fn update_builders(
+        mut commands: Commands,
+        identities: Res<ClientIdentities>,
+        mut lots: Query<(Entity, Ref<LotPermissions>, Option<&mut LotBuilders>)>,
+    ) {
+        for (lot_entity, permissions, builders) in &mut lots {
+            if !identities.is_changed() && !permissions.is_changed() {
+                continue;
+            }
+
+            let clients: Vec<_> = permissions
+                .iter()
+                .filter_map(|&identity| identities.client_id(identity))
+                .collect();
+            match builders {
+                Some(mut builders) => {
+                    if builders.0 != clients {
+                        debug!("updating builders for lot `{lot_entity}`: {clients:?}");
+                        builders.0 = clients;
+                    }
+                }
+                None => {
+                    commands.entity(lot_entity).insert(LotBuilders(clients));
+                }
+            }
+        }
+    }
+}
+
+/// Checks build rights on lots.
+#[derive(SystemParam)]
+pub(crate) struct BuildPermissions<'w, 's> {
+    client: Res<'w, RepliconClient>,
+    lots: Query<
+        'w,
+        's,
+        (
+            &'static Parent,
+            &'static LotVertices,
+            &'static LotFamily,
+            Option<&'static LotBuilders>,
+        ),
+    >,
+    families: Query<'w, 's, &'static FamilyOwner>,
+}
+
+impl BuildPermissions<'_, '_> {
+    /// Returns `true` if the client can build at the point in city coordinates.
+    ///
+    /// Points outside of lots owned by families and lots of unclaimed families are always allowed.
+    pub(crate) fn allowed(&self, client_id: ClientId, city_entity: Entity, point: Vec2) -> bool {
+        self.lots
+            .iter()
+            .filter(|(parent, vertices, ..)| {
+                ***parent == city_entity && vertices.contains_point(point)
+            })
+            .all(|(_, _, lot_family, builders)| {
+                builders.is_some_and(|builders| builders.contains(&client_id))
+                    || self
+                        .families
+                        .get(lot_family.0)
+                        .map_or(true, |&owner| *owner == client_id)
+            })
+    }
+
+    /// Like [`Self::allowed`], but for this game instance.
+    ///
+    /// Server and singleplayer act as [`ClientId::SERVER`].
+    pub(crate) fn locally_allowed(&self, city_entity: Entity, point: Vec2) -> bool {
+        let client_id = self.client.id().unwrap_or(ClientId::SERVER);
+        self.allowed(client_id, city_entity, point)
+    }
+}
+
+/// Players that are allowed to build on a lot in addition to the owner of its family.
+///
+/// Inserted together with [`LotFamily`].
+/// Stored by identity to persist with the world, exists only on the server
+/// to avoid exposing identities to other players.
+#[derive(Clone, Component, Default, Deref, Reflect)]
+#[reflect(Component)]
+pub(crate) struct LotPermissions(Vec<PlayerIdentity>);
+
+/// Connected clients from [`LotPermissions`].
+///
+/// Updated automatically. Not saved since client IDs are assigned per connection.
+#[derive(Clone, Component, Default, Deref, Deserialize, Serialize)]
+pub struct LotBuilders(Vec<ClientId>);
+
+/// Grants or revokes build rights on a lot.
+///
+/// Can be sent only by the owner of the lot family.
+#[derive(Clone, Copy, Deserialize, Event, Serialize)]
+pub struct LotPermissionChange {
+    pub lot_entity: Entity,
+    pub client_id: ClientId,
+    pub allowed: bool,
+}
+
+impl MapEntities for LotPermissionChange {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.lot_entity = entity_mapper.map_entity(self.lot_entity);
+    }
+}
+
+impl Mutation for LotPermissionChange {
+    const TARGET: &'static str = "lot permissions";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Edit
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        Some(self.lot_entity)
+    }
+}
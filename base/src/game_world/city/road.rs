@@ -1,6 +1,8 @@
 pub mod placing_road;
 pub(crate) mod road_mesh;
+pub(crate) mod street_props;
 
+use anyhow::{ensure, Context, Result};
 use avian3d::prelude::*;
 use bevy::{
     asset::AssetPath, ecs::entity::MapEntities, prelude::*, render::view::NoFrustumCulling,
@@ -10,34 +12,41 @@ use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter};
 
 use crate::{
-    asset::info::road_info::RoadInfo,
+    asset::info::{road_info::RoadInfo, scenario_info::ScenarioFeature},
     core::GameState,
     game_world::{
-        city::CityMode,
+        city::{self, CityMode},
         commands_history::{
             CommandConfirmation, CommandId, CommandRequest, ConfirmableCommand, EntityRecorder,
             PendingCommand,
         },
         hover::Hoverable,
+        mutation_log::{Mutation, MutationKind, MutationRecorder},
+        scenario::ScenarioLocks,
         spline::{
-            dynamic_mesh::DynamicMesh, PointKind, SplineConnections, SplinePlugin, SplineSegment,
+            dynamic_mesh::{DynamicMesh, MeshTask},
+            PointKind, SplineConnections, SplinePlugin, SplineSegment,
         },
         Layer,
     },
     math::segment::Segment,
+    network::validation::ClientViolation,
 };
 use placing_road::PlacingRoadPlugin;
+use street_props::{DecoratedRoad, StreetPropsPlugin};
 
 pub(crate) struct RoadPlugin;
 
 impl Plugin for RoadPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(PlacingRoadPlugin)
+        app.add_plugins((PlacingRoadPlugin, StreetPropsPlugin))
             .add_sub_state::<RoadTool>()
             .enable_state_scoped_entities::<RoadTool>()
             .register_type::<Road>()
             .register_type::<RoadData>()
+            .register_type::<RoadElevation>()
             .replicate::<Road>()
+            .replicate::<RoadElevation>()
             .add_mapped_client_event::<CommandRequest<RoadCommand>>(ChannelKind::Unordered)
             .add_systems(
                 PreUpdate,
@@ -64,9 +73,9 @@ impl RoadPlugin {
         asset_server: Res<AssetServer>,
         mut meshes: ResMut<Assets<Mesh>>,
         roads_info: Res<Assets<RoadInfo>>,
-        roads: Query<(Entity, &Road), Without<Handle<Mesh>>>,
+        roads: Query<(Entity, &Road, Has<RoadElevation>), Without<Handle<Mesh>>>,
     ) {
-        for (entity, road) in &roads {
+        for (entity, road, has_elevation) in &roads {
             let info_handle = asset_server
                 .get_handle(&road.0)
                 .expect("info should be preloaded");
@@ -86,35 +95,52 @@ impl RoadPlugin {
                     ..Default::default()
                 },
             ));
+
+            // Roads from older saves don't have elevation.
+            if !has_elevation {
+                commands.entity(entity).insert(RoadElevation::default());
+            }
         }
     }
 
     fn update_meshes(
-        mut meshes: ResMut<Assets<Mesh>>,
         mut changed_roads: Query<
             (
-                &Handle<Mesh>,
+                Entity,
                 Ref<SplineSegment>,
                 &SplineConnections,
                 &RoadData,
+                Ref<RoadElevation>,
                 &mut Collider,
+                &mut MeshTask,
             ),
-            Changed<SplineConnections>,
+            Or<(Changed<SplineConnections>, Changed<RoadElevation>)>,
         >,
     ) {
-        for (mesh_handle, segment, connections, road_data, mut collider) in &mut changed_roads {
-            let mesh = meshes
-                .get_mut(mesh_handle)
-                .expect("road handles should be valid");
-
-            trace!("regenerating road mesh");
-            let mut dyn_mesh = DynamicMesh::take(mesh);
-            road_mesh::generate(&mut dyn_mesh, *segment, connections, road_data.half_width);
-            dyn_mesh.apply(mesh);
+        for (entity, segment, connections, road_data, elevation, mut collider, mut mesh_task) in
+            &mut changed_roads
+        {
+            trace!("regenerating mesh for road `{entity}`");
+            let road_segment = *segment;
+            let road_elevation = *elevation;
+            let connections = connections.clone();
+            let half_width = road_data.half_width;
+            mesh_task.spawn(move |dyn_mesh| {
+                if let Err(error) = road_mesh::generate(
+                    dyn_mesh,
+                    road_segment,
+                    road_elevation,
+                    &connections,
+                    half_width,
+                ) {
+                    error!("unable to generate mesh for road `{entity}`: {error}");
+                }
+            });
 
-            if segment.is_changed() || collider.is_added() {
+            if segment.is_changed() || elevation.is_changed() || collider.is_added() {
                 trace!("regenerating road collision");
-                *collider = road_mesh::generate_collider(*segment, road_data.half_width);
+                *collider =
+                    road_mesh::generate_collider(*segment, *elevation, road_data.half_width);
             }
         }
     }
@@ -123,42 +149,122 @@ impl RoadPlugin {
         mut commands: Commands,
         mut request_events: EventReader<FromClient<CommandRequest<RoadCommand>>>,
         mut confirm_events: EventWriter<ToClients<CommandConfirmation>>,
-        mut roads: Query<&mut SplineSegment, With<Road>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        locks: ScenarioLocks,
+        mut roads: Query<(Entity, &Parent, &mut SplineSegment, &mut RoadElevation), With<Road>>,
     ) {
         for FromClient { client_id, event } in request_events.read().cloned() {
+            if let Err(violation) = locks.check(ScenarioFeature::Roads, client_id) {
+                violation_events.send(violation);
+                continue;
+            }
+
+            if !event.command.points().into_iter().all(city::within_city) {
+                error!("`{client_id:?}` sends road points outside of city size");
+                violation_events.send(ClientViolation {
+                    client_id,
+                    reason: "building roads outside of the world".into(),
+                });
+                continue;
+            }
+
             // TODO: validate if command can be applied.
             let mut confirmation = CommandConfirmation::new(event.id);
-            match event.command {
+            match event.command.clone() {
                 RoadCommand::Create {
                     city_entity,
                     info_path,
                     segment,
+                    elevation,
+                    decorated,
                 } => {
+                    let city_roads = roads
+                        .iter()
+                        .filter(|&(_, parent, ..)| **parent == city_entity)
+                        .map(|(entity, _, segment, &elevation)| (entity, **segment, elevation));
+                    let connected = elevation
+                        .validate(segment)
+                        .and_then(|_| connected_elevations(city_roads, segment, elevation));
+                    let connected = match connected {
+                        Ok(connected) => connected,
+                        Err(error) => {
+                            error!("unable to spawn road for `{client_id:?}`: {error:#}");
+                            continue;
+                        }
+                    };
+
                     info!("`{client_id:?}` spawns road");
+                    recorder.record(client_id, &event);
+                    update_connected(&mut roads, connected);
                     commands.entity(city_entity).with_children(|parent| {
-                        let entity = parent
-                            .spawn(RoadBundle::new(info_path.clone(), segment))
-                            .id();
-                        confirmation.entity = Some(entity);
+                        let mut entity =
+                            parent.spawn(RoadBundle::new(info_path.clone(), segment, elevation));
+                        if decorated {
+                            entity.insert(DecoratedRoad::default());
+                        }
+                        confirmation.entity = Some(entity.id());
                     });
                 }
                 RoadCommand::MovePoint {
                     entity,
                     kind,
                     point,
-                } => match roads.get_mut(entity) {
-                    Ok(mut segment) => {
-                        info!("`{client_id:?}` moves `{kind:?}` for road `{entity}`");
+                    height,
+                    bank,
+                } => match roads.get(entity) {
+                    Ok((_, parent, segment, &elevation)) => {
+                        let mut new_segment = **segment;
+                        let mut new_elevation = RoadElevation { bank, ..elevation };
                         match kind {
-                            PointKind::Start => segment.start = point,
-                            PointKind::End => segment.end = point,
+                            PointKind::Start => {
+                                new_segment.start = point;
+                                new_elevation.start = height;
+                            }
+                            PointKind::End => {
+                                new_segment.end = point;
+                                new_elevation.end = height;
+                            }
                         }
+
+                        let city_entity = **parent;
+                        let city_roads = roads
+                            .iter()
+                            .filter(|&(other_entity, parent, ..)| {
+                                other_entity != entity && **parent == city_entity
+                            })
+                            .map(|(entity, _, segment, &elevation)| (entity, **segment, elevation));
+                        let connected = new_elevation.validate(new_segment).and_then(|_| {
+                            connected_elevations(city_roads, new_segment, new_elevation)
+                        });
+                        let connected = match connected {
+                            Ok(connected) => connected,
+                            Err(error) => {
+                                error!(
+                                    "unable to move road `{entity}` for `{client_id:?}`: {error:#}"
+                                );
+                                continue;
+                            }
+                        };
+
+                        info!("`{client_id:?}` moves `{kind:?}` for road `{entity}`");
+                        recorder.record(client_id, &event);
+                        let (.., mut segment, mut elevation) = roads.get_mut(entity).unwrap();
+                        **segment = new_segment;
+                        elevation.set_if_neq(new_elevation);
+                        update_connected(&mut roads, connected);
                     }
                     Err(e) => error!("unable to move road `{entity}`: {e}"),
                 },
                 RoadCommand::Delete { entity } => {
+                    if !roads.contains(entity) {
+                        error!("`{client_id:?}` tries to remove non-road entity `{entity}`");
+                        continue;
+                    }
+
                     info!("`{client_id:?}` removes road `{entity}`");
-                    commands.entity(entity).despawn();
+                    recorder.record(client_id, &event);
+                    commands.entity(entity).despawn_recursive();
                 }
             }
 
@@ -170,6 +276,51 @@ impl RoadPlugin {
     }
 }
 
+/// Returns new elevations for roads connected to the segment to share heights at its points.
+///
+/// Returns an error if any connected road can't take the new height.
+fn connected_elevations(
+    city_roads: impl Iterator<Item = (Entity, Segment, RoadElevation)>,
+    segment: Segment,
+    elevation: RoadElevation,
+) -> Result<Vec<(Entity, RoadElevation)>> {
+    let mut connected = Vec::new();
+    for (entity, other_segment, other_elevation) in city_roads {
+        let mut new_elevation = other_elevation;
+        for (point, height) in [
+            (segment.start, elevation.start),
+            (segment.end, elevation.end),
+        ] {
+            if other_segment.start == point {
+                new_elevation.start = height;
+            }
+            if other_segment.end == point {
+                new_elevation.end = height;
+            }
+        }
+
+        if new_elevation != other_elevation {
+            new_elevation
+                .validate(other_segment)
+                .with_context(|| format!("connected road `{entity}` can't match the height"))?;
+            connected.push((entity, new_elevation));
+        }
+    }
+
+    Ok(connected)
+}
+
+fn update_connected(
+    roads: &mut Query<(Entity, &Parent, &mut SplineSegment, &mut RoadElevation), With<Road>>,
+    connected: Vec<(Entity, RoadElevation)>,
+) {
+    for (entity, new_elevation) in connected {
+        debug!("updating elevation for connected road `{entity}`");
+        let (.., mut elevation) = roads.get_mut(entity).unwrap();
+        *elevation = new_elevation;
+    }
+}
+
 #[derive(
     Clone, Component, Copy, Debug, Default, Display, EnumIter, Eq, Hash, PartialEq, SubStates,
 )]
@@ -190,18 +341,24 @@ impl RoadTool {
 }
 
 #[derive(Bundle)]
-struct RoadBundle {
+pub(super) struct RoadBundle {
     road: Road,
     spline_segment: SplineSegment,
+    elevation: RoadElevation,
     parent_sync: ParentSync,
     replication: Replicated,
 }
 
 impl RoadBundle {
-    fn new(info_path: AssetPath<'static>, segment: Segment) -> Self {
+    pub(super) fn new(
+        info_path: AssetPath<'static>,
+        segment: Segment,
+        elevation: RoadElevation,
+    ) -> Self {
         Self {
             road: Road(info_path),
             spline_segment: SplineSegment(segment),
+            elevation,
             parent_sync: Default::default(),
             replication: Replicated,
         }
@@ -211,7 +368,7 @@ impl RoadBundle {
 /// Stores path to the road info.
 #[derive(Component, Deserialize, Reflect, Serialize)]
 #[reflect(Component)]
-struct Road(AssetPath<'static>);
+pub(crate) struct Road(pub(crate) AssetPath<'static>);
 
 /// Stores road information needed at runtime from [`RoadInfo`].
 #[derive(Component, Reflect)]
@@ -228,23 +385,151 @@ impl RoadData {
     }
 }
 
+/// Maximum road elevation above the ground.
+pub(crate) const MAX_ELEVATION: f32 = 6.0;
+
+/// Maximum ratio of the elevation difference to the road length.
+const MAX_GRADE: f32 = 0.2;
+
+/// Maximum ratio of the height difference between road sides to the road width.
+pub(crate) const MAX_BANK: f32 = 0.1;
+
+/// Heights of road points above the ground.
+///
+/// Roads above the ground are displayed as bridges.
+#[derive(Clone, Component, Copy, Debug, Default, Deserialize, PartialEq, Reflect, Serialize)]
+#[reflect(Component)]
+pub(crate) struct RoadElevation {
+    pub(crate) start: f32,
+    pub(crate) end: f32,
+
+    /// Cross slope of the surface.
+    ///
+    /// Positive values raise the left side, negative values raise the right side.
+    /// The lower side stays at the point heights.
+    #[reflect(default)]
+    pub(crate) bank: f32,
+}
+
+impl RoadElevation {
+    /// Returns the height at the point projected on the segment.
+    pub(crate) fn height_at(self, segment: Segment, point: Vec2) -> f32 {
+        let disp = segment.displacement();
+        let length_squared = disp.length_squared();
+        if length_squared == 0.0 {
+            return self.start;
+        }
+
+        let fraction = ((point - segment.start).dot(disp) / length_squared).clamp(0.0, 1.0);
+        self.start + (self.end - self.start) * fraction
+    }
+
+    /// Returns the height of the road surface at the point, including banking.
+    pub(crate) fn surface_height(self, segment: Segment, half_width: f32, point: Vec2) -> f32 {
+        let disp = segment.displacement();
+        if disp == Vec2::ZERO {
+            return self.start;
+        }
+
+        let side_offset = (point - segment.start).dot(disp.perp().normalize());
+        let bank_height = self.bank.abs() * (half_width + side_offset * self.bank.signum());
+        self.height_at(segment, point) + bank_height.max(0.0)
+    }
+
+    /// Returns the height for the segment point.
+    pub(crate) fn point_height(self, kind: PointKind) -> f32 {
+        match kind {
+            PointKind::Start => self.start,
+            PointKind::End => self.end,
+        }
+    }
+
+    /// Returns the ratio of the elevation difference to the segment length.
+    fn grade(self, segment: Segment) -> f32 {
+        let length = segment.displacement().length();
+        if length == 0.0 {
+            return 0.0;
+        }
+
+        (self.end - self.start).abs() / length
+    }
+
+    /// Checks that heights are in range and the road is not too steep or banked.
+    pub(crate) fn validate(self, segment: Segment) -> Result<()> {
+        ensure!(
+            (0.0..=MAX_ELEVATION).contains(&self.start)
+                && (0.0..=MAX_ELEVATION).contains(&self.end),
+            "road elevation should be between 0 and {MAX_ELEVATION} m"
+        );
+        let grade = self.grade(segment);
+        ensure!(
+            grade <= MAX_GRADE,
+            "road is too steep, the grade is {:.0}%, but the maximum is {:.0}%",
+            grade * 100.0,
+            MAX_GRADE * 100.0
+        );
+        ensure!(
+            (-MAX_BANK..=MAX_BANK).contains(&self.bank),
+            "road bank should be between -{0:.0}% and {0:.0}%",
+            MAX_BANK * 100.0
+        );
+
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 enum RoadCommand {
     Create {
         city_entity: Entity,
         info_path: AssetPath<'static>,
         segment: Segment,
+        elevation: RoadElevation,
+        /// Spawn props from [`RoadInfo::props`] along the road.
+        decorated: bool,
     },
     MovePoint {
         entity: Entity,
         kind: PointKind,
         point: Vec2,
+        height: f32,
+        bank: f32,
     },
     Delete {
         entity: Entity,
     },
 }
 
+impl RoadCommand {
+    /// Returns positions in city coordinates sent with the command.
+    fn points(&self) -> Vec<Vec2> {
+        match *self {
+            Self::Create { segment, .. } => segment.points().into(),
+            Self::MovePoint { point, .. } => vec![point],
+            Self::Delete { .. } => Vec::new(),
+        }
+    }
+}
+
+impl Mutation for RoadCommand {
+    const TARGET: &'static str = "road";
+
+    fn kind(&self) -> MutationKind {
+        match self {
+            Self::Create { .. } => MutationKind::Spawn,
+            Self::MovePoint { .. } => MutationKind::Edit,
+            Self::Delete { .. } => MutationKind::Delete,
+        }
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        match *self {
+            Self::Create { .. } => None,
+            Self::MovePoint { entity, .. } | Self::Delete { entity } => Some(entity),
+        }
+    }
+}
+
 impl PendingCommand for RoadCommand {
     fn apply(
         self: Box<Self>,
@@ -263,10 +548,13 @@ impl PendingCommand for RoadCommand {
                     PointKind::Start => segment.start,
                     PointKind::End => segment.end,
                 };
+                let elevation = world.get::<RoadElevation>(entity).unwrap();
                 Self::MovePoint {
                     entity,
                     kind,
                     point,
+                    height: elevation.point_height(kind),
+                    bank: elevation.bank,
                 }
             }
             Self::Delete { entity } => {
@@ -274,11 +562,14 @@ impl PendingCommand for RoadCommand {
                 let entity = world.entity(entity);
                 let road = entity.get::<Road>().unwrap();
                 let segment = **entity.get::<SplineSegment>().unwrap();
+                let elevation = *entity.get::<RoadElevation>().unwrap();
                 let city_entity = **entity.get::<Parent>().unwrap();
                 Self::Create {
                     city_entity,
                     info_path: road.0.clone(),
                     segment,
+                    elevation,
+                    decorated: entity.contains::<DecoratedRoad>(),
                 }
             }
         };
@@ -315,3 +606,133 @@ impl MapEntities for RoadCommand {
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elevation_height() {
+        let segment = Segment::new(Vec2::ZERO, Vec2::X * 10.0);
+        let elevation = RoadElevation {
+            start: 0.0,
+            end: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(elevation.height_at(segment, Vec2::new(5.0, 2.0)), 0.5);
+        assert_eq!(elevation.height_at(segment, Vec2::NEG_X), 0.0);
+        assert_eq!(elevation.height_at(segment, Vec2::X * 20.0), 1.0);
+    }
+
+    #[test]
+    fn elevation_validation() {
+        let segment = Segment::new(Vec2::ZERO, Vec2::X * 10.0);
+        let elevation = RoadElevation {
+            start: 0.0,
+            end: 2.0,
+            ..Default::default()
+        };
+        assert!(elevation.validate(segment).is_ok());
+
+        let elevation = RoadElevation {
+            start: 0.0,
+            end: 3.0,
+            ..Default::default()
+        };
+        assert!(elevation.validate(segment).is_err());
+
+        let elevation = RoadElevation {
+            start: -1.0,
+            end: 0.0,
+            ..Default::default()
+        };
+        assert!(elevation.validate(segment).is_err());
+
+        let elevation = RoadElevation {
+            bank: MAX_BANK * 2.0,
+            ..Default::default()
+        };
+        assert!(elevation.validate(segment).is_err());
+
+        let elevation = RoadElevation {
+            start: f32::NAN,
+            ..Default::default()
+        };
+        assert!(elevation.validate(segment).is_err());
+    }
+
+    #[test]
+    fn banked_height() {
+        let segment = Segment::new(Vec2::ZERO, Vec2::X * 10.0);
+        let elevation = RoadElevation {
+            start: 1.0,
+            end: 1.0,
+            bank: 0.1,
+        };
+        assert_eq!(
+            elevation.surface_height(segment, 2.0, Vec2::new(5.0, 2.0)),
+            1.4
+        );
+        assert_eq!(
+            elevation.surface_height(segment, 2.0, Vec2::new(5.0, -2.0)),
+            1.0
+        );
+
+        let elevation = RoadElevation {
+            bank: -0.1,
+            ..elevation
+        };
+        assert_eq!(
+            elevation.surface_height(segment, 2.0, Vec2::new(5.0, -2.0)),
+            1.4
+        );
+        assert_eq!(
+            elevation.surface_height(segment, 2.0, Vec2::new(5.0, 2.0)),
+            1.0
+        );
+    }
+
+    #[test]
+    fn connected_heights() {
+        let segment = Segment::new(Vec2::ZERO, Vec2::X * 10.0);
+        let elevation = RoadElevation {
+            start: 0.0,
+            end: 1.0,
+            ..Default::default()
+        };
+        let connected = Entity::from_raw(0);
+        let unrelated = Entity::from_raw(1);
+        let city_roads = [
+            (
+                connected,
+                Segment::new(Vec2::X * 10.0, Vec2::new(10.0, 10.0)),
+                RoadElevation::default(),
+            ),
+            (
+                unrelated,
+                Segment::new(Vec2::Y * 5.0, Vec2::Y * 15.0),
+                RoadElevation::default(),
+            ),
+        ];
+
+        let updates = connected_elevations(city_roads.into_iter(), segment, elevation)
+            .expect("connected road should take the height");
+        assert_eq!(
+            updates,
+            [(
+                connected,
+                RoadElevation {
+                    start: 1.0,
+                    ..Default::default()
+                }
+            )]
+        );
+
+        let steep_road = [(
+            connected,
+            Segment::new(Vec2::X * 10.0, Vec2::X * 11.0),
+            RoadElevation::default(),
+        )];
+        assert!(connected_elevations(steep_road.into_iter(), segment, elevation).is_err());
+    }
+}
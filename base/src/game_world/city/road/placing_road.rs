@@ -1,25 +1,25 @@
+use anyhow::Result;
 use avian3d::prelude::*;
-use bevy::{
-    color::palettes::css::{RED, WHITE},
-    math::Vec3Swizzles,
-    prelude::*,
-    render::view::NoFrustumCulling,
+use bevy::{math::Vec3Swizzles, prelude::*, render::view::NoFrustumCulling};
+use leafwing_input_manager::{
+    common_conditions::{action_just_pressed, action_pressed},
+    prelude::ActionState,
 };
-use leafwing_input_manager::common_conditions::action_just_pressed;
 
-use super::{Road, RoadData, RoadTool};
+use super::{Road, RoadData, RoadElevation, RoadTool, MAX_BANK, MAX_ELEVATION, MAX_GRADE};
 use crate::{
     asset::info::road_info::RoadInfo,
     game_world::{
-        city::{road::RoadCommand, ActiveCity, CityMode},
+        city::{road::RoadCommand, ActiveCity, CityMode, GroundSampler},
         commands_history::{CommandsHistory, PendingDespawn},
         hover::{HoverPlugin, Hovered},
         player_camera::CameraCaster,
         spline::{dynamic_mesh::DynamicMesh, PointKind, SplineSegment},
         Layer,
     },
-    ghost::Ghost,
+    ghost::{Ghost, GhostMaterial},
     math::segment::Segment,
+    message::error_message,
     settings::Action,
 };
 
@@ -27,7 +27,8 @@ pub(super) struct PlacingRoadPlugin;
 
 impl Plugin for PlacingRoadPlugin {
     fn build(&self, app: &mut App) {
-        app.observe(HoverPlugin::enable_on_remove::<PlacingRoad>)
+        app.init_resource::<RoadDecoration>()
+            .observe(HoverPlugin::enable_on_remove::<PlacingRoad>)
             .observe(HoverPlugin::disable_on_add::<PlacingRoad>)
             .add_systems(
                 Update,
@@ -41,9 +42,13 @@ impl Plugin for PlacingRoadPlugin {
                         .run_if(action_just_pressed(Action::Confirm))
                         .run_if(not(any_with_component::<PlacingRoad>)),
                     (
+                        Self::offset_vertically.run_if(action_pressed(Action::VerticalOffset)),
+                        Self::change_bank.run_if(action_just_pressed(Action::RotateObject)),
                         Self::update_end,
-                        Self::update_material,
-                        Self::confirm.run_if(action_just_pressed(Action::Confirm)),
+                        Self::update_validity,
+                        Self::confirm
+                            .pipe(error_message)
+                            .run_if(action_just_pressed(Action::Confirm)),
                         Self::delete.run_if(action_just_pressed(Action::Delete)),
                         Self::cancel.run_if(action_just_pressed(Action::Cancel)),
                     )
@@ -53,6 +58,12 @@ impl Plugin for PlacingRoadPlugin {
     }
 }
 
+/// Elevation change per [`Action::ZoomCamera`] unit.
+const ELEVATION_STEP: f32 = 0.25;
+
+/// Bank change per [`Action::RotateObject`] press.
+const BANK_STEP: f32 = 0.05;
+
 impl PlacingRoadPlugin {
     fn pick(
         mut commands: Commands,
@@ -65,10 +76,13 @@ impl PlacingRoadPlugin {
             &Handle<StandardMaterial>,
             &Road,
             &SplineSegment,
+            &RoadElevation,
             &Hovered,
         )>,
     ) {
-        let Ok((entity, parent, material, road, &segment, hovered)) = roads.get_single() else {
+        let Ok((entity, parent, material, road, &segment, &elevation, hovered)) =
+            roads.get_single()
+        else {
             return;
         };
 
@@ -94,6 +108,7 @@ impl PlacingRoadPlugin {
                     PlacingRoad::MovingPoint { entity, kind },
                     info.half_width,
                     *segment,
+                    elevation,
                     material.clone(),
                     meshes.add(DynamicMesh::create_empty()),
                 ),
@@ -108,7 +123,7 @@ impl PlacingRoadPlugin {
         asset_server: Res<AssetServer>,
         roads_info: Res<Assets<RoadInfo>>,
         placing_id: Res<SpawnRoadId>,
-        roads: Query<(&Parent, &SplineSegment), With<Road>>,
+        roads: Query<(&Parent, &SplineSegment, &RoadElevation), With<Road>>,
         cities: Query<Entity, With<ActiveCity>>,
     ) {
         let Some(point) = camera_caster.intersect_ground().map(|point| point.xz()) else {
@@ -121,12 +136,13 @@ impl PlacingRoadPlugin {
             .expect("info should be preloaded");
 
         // Use an existing point if it is within the half width distance.
-        let point = roads
+        let (point, height) = roads
             .iter()
-            .filter(|(parent, _)| ***parent == city_entity)
-            .flat_map(|(_, segment)| segment.points())
-            .find(|vertex| vertex.distance(point) < info.half_width)
-            .unwrap_or(point);
+            .filter(|(parent, ..)| ***parent == city_entity)
+            .find_map(|(_, segment, elevation)| {
+                snap_point(segment, elevation, point, info.half_width)
+            })
+            .unwrap_or((point, 0.0));
 
         info!("spawning new road");
         commands.entity(city_entity).with_children(|parent| {
@@ -134,47 +150,114 @@ impl PlacingRoadPlugin {
                 PlacingRoad::Spawning(placing_id.0),
                 info.half_width,
                 Segment::splat(point),
+                RoadElevation {
+                    start: height,
+                    end: height,
+                    ..Default::default()
+                },
                 asset_server.load(info.material.clone()),
                 meshes.add(DynamicMesh::create_empty()),
             ));
         });
     }
 
-    fn update_material(
-        mut materials: ResMut<Assets<StandardMaterial>>,
+    /// Checks collisions, elevation and ground slope.
+    fn update_validity(
+        ground_sampler: GroundSampler,
         mut placing_roads: Query<
-            (&mut Handle<StandardMaterial>, &CollidingEntities),
-            (Changed<CollidingEntities>, With<PlacingRoad>),
+            (
+                &mut GhostMaterial,
+                &CollidingEntities,
+                &Parent,
+                &SplineSegment,
+                &RoadElevation,
+            ),
+            (
+                Or<(
+                    Changed<CollidingEntities>,
+                    Changed<SplineSegment>,
+                    Changed<RoadElevation>,
+                )>,
+                With<PlacingRoad>,
+            ),
         >,
+        cities: Query<&GlobalTransform>,
     ) {
-        let Ok((mut material_handle, colliding_entities)) = placing_roads.get_single_mut() else {
+        let Ok((mut ghost_material, colliding_entities, parent, &segment, &elevation)) =
+            placing_roads.get_single_mut()
+        else {
             return;
         };
 
-        let mut material = materials
-            .get(&*material_handle)
-            .cloned()
-            .expect("material handle should be valid");
+        let city_transform = cities
+            .get(**parent)
+            .expect("roads should have a city parent");
+        let length = segment.displacement().length();
+        let gentle_slope = ground_sampler
+            .segment_height_diff(city_transform, *segment)
+            .is_some_and(|diff| length == 0.0 || diff / length <= MAX_GRADE);
+
+        ghost_material.set_if_neq(GhostMaterial {
+            allowed: colliding_entities.is_empty()
+                && elevation.validate(*segment).is_ok()
+                && gentle_slope,
+        });
+    }
 
-        let color = if colliding_entities.is_empty() {
-            WHITE.into()
-        } else {
-            RED.into()
+    fn offset_vertically(
+        action_state: Res<ActionState<Action>>,
+        mut placing_roads: Query<(&mut RoadElevation, &PlacingRoad)>,
+    ) {
+        let Ok((mut elevation, placing_road)) = placing_roads.get_single_mut() else {
+            return;
         };
-        debug!("changing base color to `{color:?}`");
 
-        material.alpha_mode = AlphaMode::Add;
-        material.base_color = color;
+        let mut step = action_state.value(&Action::ZoomCamera) * ELEVATION_STEP;
+        if action_state.pressed(&Action::FineAdjustment) {
+            step /= 4.0;
+        }
+        if step == 0.0 {
+            return;
+        }
 
-        *material_handle = materials.add(material);
+        let height = match placing_road.point_kind() {
+            PointKind::Start => &mut elevation.start,
+            PointKind::End => &mut elevation.end,
+        };
+        let new_height = (*height + step).clamp(0.0, MAX_ELEVATION);
+        if *height != new_height {
+            debug!("changing road elevation to {new_height}");
+            *height = new_height;
+        }
+    }
+
+    /// Cycles the bank from the minimum to the maximum.
+    fn change_bank(mut placing_roads: Query<&mut RoadElevation, With<PlacingRoad>>) {
+        let Ok(mut elevation) = placing_roads.get_single_mut() else {
+            return;
+        };
+
+        let max_steps = (MAX_BANK / BANK_STEP).round() as i32;
+        let mut steps = (elevation.bank / BANK_STEP).round() as i32 + 1;
+        if steps > max_steps {
+            steps = -max_steps;
+        }
+        elevation.bank = steps as f32 * BANK_STEP;
+        debug!("changing road bank to {}", elevation.bank);
     }
 
     fn update_end(
         camera_caster: CameraCaster,
-        mut placing_roads: Query<(&mut SplineSegment, &Parent, &PlacingRoad, &RoadData)>,
-        roads: Query<(&Parent, &SplineSegment), (With<Road>, Without<PlacingRoad>)>,
+        mut placing_roads: Query<(
+            &mut SplineSegment,
+            &mut RoadElevation,
+            &Parent,
+            &PlacingRoad,
+            &RoadData,
+        )>,
+        roads: Query<(&Parent, &SplineSegment, &RoadElevation), (With<Road>, Without<PlacingRoad>)>,
     ) {
-        let Ok((mut segment, placing_parent, placing_road, road_data)) =
+        let Ok((mut segment, mut elevation, placing_parent, placing_road, road_data)) =
             placing_roads.get_single_mut()
         else {
             return;
@@ -185,12 +268,13 @@ impl PlacingRoadPlugin {
         };
 
         // Use an already existing vertex if it is within the half width distance if one exists.
-        let vertex = roads
+        let snapped = roads
             .iter()
-            .filter(|(parent, _)| *parent == placing_parent)
-            .flat_map(|(_, segment)| segment.points())
-            .find(|vertex| vertex.distance(point) < road_data.half_width)
-            .unwrap_or(point);
+            .filter(|(parent, ..)| *parent == placing_parent)
+            .find_map(|(_, segment, elevation)| {
+                snap_point(segment, elevation, point, road_data.half_width)
+            });
+        let vertex = snapped.map_or(point, |(vertex, _)| vertex);
 
         let point_kind = placing_road.point_kind();
 
@@ -199,18 +283,39 @@ impl PlacingRoadPlugin {
             PointKind::Start => segment.start = vertex,
             PointKind::End => segment.end = vertex,
         }
+
+        // Connected roads should share the height.
+        if let Some((_, height)) = snapped {
+            let mut new_elevation = *elevation;
+            match point_kind {
+                PointKind::Start => new_elevation.start = height,
+                PointKind::End => new_elevation.end = height,
+            }
+            elevation.set_if_neq(new_elevation);
+        }
     }
 
     fn confirm(
         mut commands: Commands,
         mut history: CommandsHistory,
         asset_server: Res<AssetServer>,
-        mut placing_roads: Query<(Entity, &Parent, &SplineSegment, &PlacingRoad)>,
-    ) {
-        let Ok((entity, parent, &segment, &placing_road)) = placing_roads.get_single_mut() else {
-            return;
+        decoration: Res<RoadDecoration>,
+        mut placing_roads: Query<(
+            Entity,
+            &Parent,
+            &SplineSegment,
+            &RoadElevation,
+            &PlacingRoad,
+        )>,
+    ) -> Result<()> {
+        let Ok((entity, parent, &segment, &elevation, &placing_road)) =
+            placing_roads.get_single_mut()
+        else {
+            return Ok(());
         };
 
+        elevation.validate(*segment)?;
+
         info!("configrming {placing_road:?}");
         let command_id = match placing_road {
             PlacingRoad::Spawning(id) => {
@@ -221,6 +326,8 @@ impl PlacingRoadPlugin {
                     city_entity: **parent,
                     info_path: info_path.into_owned(),
                     segment: *segment,
+                    elevation,
+                    decorated: decoration.0,
                 })
             }
             PlacingRoad::MovingPoint { entity, kind } => {
@@ -232,6 +339,8 @@ impl PlacingRoadPlugin {
                     entity,
                     kind,
                     point,
+                    height: elevation.point_height(kind),
+                    bank: elevation.bank,
                 })
             }
         };
@@ -240,15 +349,18 @@ impl PlacingRoadPlugin {
             .entity(entity)
             .insert(PendingDespawn { command_id })
             .remove::<PlacingRoad>();
+
+        Ok(())
     }
 
     fn delete(
         mut commands: Commands,
         mut history: CommandsHistory,
-        mut placing_roads: Query<(Entity, &PlacingRoad, &mut SplineSegment)>,
-        roads: Query<&SplineSegment, Without<PlacingRoad>>,
+        mut placing_roads: Query<(Entity, &PlacingRoad, &mut SplineSegment, &mut RoadElevation)>,
+        roads: Query<(&SplineSegment, &RoadElevation), Without<PlacingRoad>>,
     ) {
-        let Ok((placing_entity, &placing_road, mut segment)) = placing_roads.get_single_mut()
+        let Ok((placing_entity, &placing_road, mut segment, mut elevation)) =
+            placing_roads.get_single_mut()
         else {
             return;
         };
@@ -256,7 +368,10 @@ impl PlacingRoadPlugin {
         info!("deleting road");
         if let PlacingRoad::MovingPoint { entity, .. } = placing_road {
             // Set original segment until the deletion is confirmed.
-            *segment = *roads.get(entity).expect("moving road should exist");
+            let (&original_segment, &original_elevation) =
+                roads.get(entity).expect("moving road should exist");
+            *segment = original_segment;
+            *elevation = original_elevation;
 
             let command_id = history.push_pending(RoadCommand::Delete { entity });
             commands
@@ -276,21 +391,43 @@ impl PlacingRoadPlugin {
     }
 }
 
+/// Returns a segment point with its height if it's within the distance.
+fn snap_point(
+    segment: &SplineSegment,
+    elevation: &RoadElevation,
+    point: Vec2,
+    distance: f32,
+) -> Option<(Vec2, f32)> {
+    if segment.start.distance(point) < distance {
+        Some((segment.start, elevation.start))
+    } else if segment.end.distance(point) < distance {
+        Some((segment.end, elevation.end))
+    } else {
+        None
+    }
+}
+
 /// ID to spawn new roads with.
 ///
 /// Spawning won't start until this resource is inserted.
 #[derive(Resource)]
 pub struct SpawnRoadId(pub AssetId<RoadInfo>);
 
+/// Whether new roads should be decorated with props along their sides.
+#[derive(Default, Resource)]
+pub struct RoadDecoration(pub bool);
+
 #[derive(Bundle)]
 struct PlacingRoadBundle {
     name: Name,
     placing_road: PlacingRoad,
     road_data: RoadData,
     segment: SplineSegment,
+    elevation: RoadElevation,
     state_scoped: StateScoped<RoadTool>,
     collider: Collider,
     collision_layers: CollisionLayers,
+    ghost_material: GhostMaterial,
     no_culling: NoFrustumCulling,
     pbr_bundle: PbrBundle,
 }
@@ -300,6 +437,7 @@ impl PlacingRoadBundle {
         placing_road: PlacingRoad,
         half_width: f32,
         segment: Segment,
+        elevation: RoadElevation,
         material: Handle<StandardMaterial>,
         mesh: Handle<Mesh>,
     ) -> Self {
@@ -312,12 +450,14 @@ impl PlacingRoadBundle {
             road_data: RoadData { half_width },
             placing_road,
             segment: SplineSegment(segment),
+            elevation,
             state_scoped: StateScoped(tool),
             collider: Default::default(),
             collision_layers: CollisionLayers::new(
                 Layer::PlacingRoad,
                 [Layer::Wall, Layer::PlacingWall],
             ),
+            ghost_material: Default::default(),
             no_culling: NoFrustumCulling,
             pbr_bundle: PbrBundle {
                 material,
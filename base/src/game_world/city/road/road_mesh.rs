@@ -1,27 +1,32 @@
 use std::f32::consts::FRAC_PI_2;
 
 use avian3d::prelude::Collider;
-use bevy::prelude::*;
+use bevy::{math::Vec3Swizzles, prelude::*};
 use itertools::MinMaxResult;
 
+use super::RoadElevation;
 use crate::{
     game_world::spline::{dynamic_mesh::DynamicMesh, PointKind, SplineConnections, SplineSegment},
-    math::segment::Segment,
+    math::segment::{OffsetError, Segment},
 };
 
 /// Small offset to avoid Z-fighting with the ground.
 const HEIGHT: f32 = 0.001;
 
-pub(super) fn generate(
+/// Thickness of the bridge deck under the road surface.
+const DECK_THICKNESS: f32 = 0.5;
+
+pub(crate) fn generate(
     mesh: &mut DynamicMesh,
     segment: SplineSegment,
+    elevation: RoadElevation,
     connections: &SplineConnections,
     half_width: f32,
-) {
+) -> Result<(), OffsetError> {
     mesh.clear();
 
     if segment.start == segment.end {
-        return;
+        return Ok(());
     }
 
     let disp = segment.displacement();
@@ -31,16 +36,24 @@ pub(super) fn generate(
 
     let start_connections = connections.minmax_angles(disp, PointKind::Start);
     let (start_left, start_right) =
-        segment.offset_points(width_disp, half_width, start_connections);
+        segment.offset_points(width_disp, half_width, start_connections)?;
 
     let end_connections = connections.minmax_angles(-disp, PointKind::End);
     let (end_right, end_left) =
         segment
             .inverse()
-            .offset_points(-width_disp, half_width, end_connections);
+            .offset_points(-width_disp, half_width, end_connections)?;
+    let (start_left, end_left) = segment.uncross(start_left, end_left);
+    let (start_right, end_right) = segment.uncross(start_right, end_right);
 
     let width = half_width * 2.0;
 
+    let start_left = surface_point(*segment, elevation, half_width, start_left);
+    let start_right = surface_point(*segment, elevation, half_width, start_right);
+    let end_left = surface_point(*segment, elevation, half_width, end_left);
+    let end_right = surface_point(*segment, elevation, half_width, end_right);
+    let normal = surface_normal(*segment, elevation);
+
     generate_surface(
         mesh,
         *segment,
@@ -48,34 +61,74 @@ pub(super) fn generate(
         start_right,
         end_left,
         end_right,
+        normal,
         rotation_mat,
         width,
     );
 
     if let MinMaxResult::MinMax(_, _) = start_connections {
-        generate_start_connection(mesh, *segment);
+        generate_start_connection(mesh, *segment, elevation, half_width, normal);
     }
 
     if let MinMaxResult::MinMax(_, _) = end_connections {
-        generate_end_connection(mesh, *segment, rotation_mat, width);
+        generate_end_connection(
+            mesh,
+            *segment,
+            elevation,
+            half_width,
+            normal,
+            rotation_mat,
+            width,
+        );
     }
+
+    // Banked roads on the ground also need sides for the raised edge.
+    if elevation.start > 0.0 || elevation.end > 0.0 || elevation.bank != 0.0 {
+        let side_normal = width_disp.normalize().extend(0.0).xzy();
+        generate_side(mesh, start_left, end_left, side_normal, width, false);
+        generate_side(mesh, start_right, end_right, -side_normal, width, true);
+        generate_bottom(mesh, start_left, start_right, end_left, end_right);
+    }
+
+    Ok(())
+}
+
+/// Returns the road surface point for the point on the ground.
+fn surface_point(segment: Segment, elevation: RoadElevation, half_width: f32, point: Vec2) -> Vec3 {
+    // To avoid interfering with the ground.
+    let height = elevation.surface_height(segment, half_width, point) + HEIGHT;
+    Vec3::new(point.x, height, point.y)
+}
+
+/// Returns the normal of the sloped and banked road surface.
+fn surface_normal(segment: Segment, elevation: RoadElevation) -> Vec3 {
+    let disp = segment.displacement();
+    let slope = disp * (elevation.end - elevation.start) / disp.length_squared();
+    let bank = disp.perp().normalize() * elevation.bank;
+    let gradient = slope + bank;
+    Vec3::new(-gradient.x, 1.0, -gradient.y).normalize()
 }
 
 fn generate_surface(
     mesh: &mut DynamicMesh,
     segment: Segment,
-    start_left: Vec2,
-    start_right: Vec2,
-    end_left: Vec2,
-    end_right: Vec2,
+    start_left: Vec3,
+    start_right: Vec3,
+    end_left: Vec3,
+    end_right: Vec3,
+    normal: Vec3,
     rotation_mat: Mat2,
     width: f32,
 ) {
-    // To avoid interfering with the ground.
-    mesh.positions.push([start_left.x, HEIGHT, start_left.y]);
-    mesh.positions.push([start_right.x, HEIGHT, start_right.y]);
-    mesh.positions.push([end_right.x, HEIGHT, end_right.y]);
-    mesh.positions.push([end_left.x, HEIGHT, end_left.y]);
+    mesh.positions.push(start_left.into());
+    mesh.positions.push(start_right.into());
+    mesh.positions.push(end_right.into());
+    mesh.positions.push(end_left.into());
+
+    let start_left = start_left.xz();
+    let start_right = start_right.xz();
+    let end_left = end_left.xz();
+    let end_right = end_right.xz();
 
     // Road UV on X axis should go from 0.0 to 1.0.
     // But on Y we use segment length divided by width to scale it properly.
@@ -90,7 +143,7 @@ fn generate_surface(
     mesh.uvs
         .push([0.0, (rotation_mat * (end_left - segment.start)).y / width]);
 
-    mesh.normals.extend_from_slice(&[[0.0, 1.0, 0.0]; 4]);
+    mesh.normals.extend_from_slice(&[normal.into(); 4]);
 
     mesh.indices.push(0);
     mesh.indices.push(3);
@@ -101,13 +154,19 @@ fn generate_surface(
 }
 
 /// Inside triangle to fill the gap between 3+ walls.
-fn generate_start_connection(mesh: &mut DynamicMesh, segment: Segment) {
+fn generate_start_connection(
+    mesh: &mut DynamicMesh,
+    segment: Segment,
+    elevation: RoadElevation,
+    half_width: f32,
+    normal: Vec3,
+) {
     let vertices_start = mesh.vertices_count();
 
     mesh.positions
-        .push([segment.start.x, HEIGHT, segment.start.y]);
+        .push(surface_point(segment, elevation, half_width, segment.start).into());
     mesh.uvs.push([0.5, 0.0]);
-    mesh.normals.push([0.0, 1.0, 0.0]);
+    mesh.normals.push(normal.into());
 
     mesh.indices.push(1);
     mesh.indices.push(vertices_start);
@@ -118,24 +177,102 @@ fn generate_start_connection(mesh: &mut DynamicMesh, segment: Segment) {
 fn generate_end_connection(
     mesh: &mut DynamicMesh,
     segment: Segment,
+    elevation: RoadElevation,
+    half_width: f32,
+    normal: Vec3,
     rotation_mat: Mat2,
     width: f32,
 ) {
     let vertices_start = mesh.vertices_count();
 
-    mesh.positions.push([segment.end.x, HEIGHT, segment.end.y]);
+    mesh.positions
+        .push(surface_point(segment, elevation, half_width, segment.end).into());
     mesh.uvs.push([
         0.5,
         (rotation_mat * (segment.end - segment.start)).y / width,
     ]);
-    mesh.normals.push([0.0, 1.0, 0.0]);
+    mesh.normals.push(normal.into());
 
     mesh.indices.push(3);
     mesh.indices.push(vertices_start);
     mesh.indices.push(2);
 }
 
-pub(super) fn generate_collider(segment: SplineSegment, half_width: f32) -> Collider {
+/// Vertical bridge side from the road edge down to the deck bottom.
+///
+/// Right side is mirrored to keep the faces pointing outside.
+fn generate_side(
+    mesh: &mut DynamicMesh,
+    start: Vec3,
+    end: Vec3,
+    normal: Vec3,
+    width: f32,
+    mirrored: bool,
+) {
+    let vertices_start = mesh.vertices_count();
+
+    mesh.positions.push(start.into());
+    mesh.positions.push(deck_bottom(start).into());
+    mesh.positions.push(deck_bottom(end).into());
+    mesh.positions.push(end.into());
+
+    let length = start.xz().distance(end.xz()) / width;
+    let thickness = DECK_THICKNESS / width;
+    mesh.uvs.push([0.0, thickness]);
+    mesh.uvs.push([0.0, 0.0]);
+    mesh.uvs.push([length, 0.0]);
+    mesh.uvs.push([length, thickness]);
+
+    mesh.normals.extend_from_slice(&[normal.into(); 4]);
+
+    let indices = if mirrored {
+        [0, 3, 1, 3, 2, 1]
+    } else {
+        [0, 1, 3, 3, 1, 2]
+    };
+    mesh.indices
+        .extend(indices.map(|index| vertices_start + index));
+}
+
+/// Bottom of the bridge deck, visible from below.
+fn generate_bottom(
+    mesh: &mut DynamicMesh,
+    start_left: Vec3,
+    start_right: Vec3,
+    end_left: Vec3,
+    end_right: Vec3,
+) {
+    let vertices_start = mesh.vertices_count();
+
+    for point in [start_left, start_right, end_right, end_left] {
+        mesh.positions.push(deck_bottom(point).into());
+    }
+
+    let start_width = start_left.distance(start_right);
+    let end_width = end_left.distance(end_right);
+    let length = start_left.xz().distance(end_left.xz());
+    mesh.uvs.push([0.0, 0.0]);
+    mesh.uvs.push([start_width, 0.0]);
+    mesh.uvs.push([end_width, length]);
+    mesh.uvs.push([0.0, length]);
+
+    mesh.normals.extend_from_slice(&[Vec3::NEG_Y.into(); 4]);
+
+    // Reversed surface order to face down.
+    mesh.indices
+        .extend([0, 1, 3, 1, 2, 3].map(|index| vertices_start + index));
+}
+
+/// Returns the deck bottom point under the road surface point.
+fn deck_bottom(point: Vec3) -> Vec3 {
+    point - Vec3::Y * DECK_THICKNESS
+}
+
+pub(super) fn generate_collider(
+    segment: SplineSegment,
+    elevation: RoadElevation,
+    half_width: f32,
+) -> Collider {
     if segment.start == segment.end {
         return Default::default();
     }
@@ -150,13 +287,81 @@ pub(super) fn generate_collider(segment: SplineSegment, half_width: f32) -> Coll
     let left_end = segment.end + width_disp;
     let right_end = segment.end - width_disp;
 
-    vertices.push(Vec3::new(left_start.x, 0.0, left_start.y));
-    vertices.push(Vec3::new(right_start.x, 0.0, right_start.y));
-    vertices.push(Vec3::new(right_end.x, 0.0, right_end.y));
-    vertices.push(Vec3::new(left_end.x, 0.0, left_end.y));
+    for point in [left_start, right_start, right_end, left_end] {
+        let height = elevation.surface_height(*segment, half_width, point);
+        vertices.push(Vec3::new(point.x, height, point.y));
+    }
 
     indices.push([1, 0, 2]);
     indices.push([0, 3, 2]);
 
     Collider::trimesh(vertices, indices)
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::game_world::{city::road::MAX_BANK, spline::tests::random_graph};
+
+    #[test]
+    fn random_graphs() {
+        const HALF_WIDTH: f32 = 0.5;
+        const EPSILON: f32 = 1e-4;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut mesh = DynamicMesh::default();
+        for _ in 0..1000 {
+            for (segment, connections) in random_graph(&mut rng) {
+                let elevation = if rng.gen_bool(0.5) {
+                    RoadElevation::default()
+                } else {
+                    RoadElevation {
+                        start: rng.gen_range(0.0..5.0),
+                        end: rng.gen_range(0.0..5.0),
+                        bank: rng.gen_range(-MAX_BANK..=MAX_BANK),
+                    }
+                };
+                generate(&mut mesh, segment, elevation, &connections, HALF_WIDTH)
+                    .expect("segment should be valid");
+
+                assert_eq!(mesh.uvs.len(), mesh.positions.len());
+                assert_eq!(mesh.normals.len(), mesh.positions.len());
+                assert_eq!(mesh.indices.len() % 3, 0);
+                assert!(mesh
+                    .indices
+                    .iter()
+                    .all(|&index| index < mesh.vertices_count()));
+                for (position, normal) in mesh.positions.iter().zip(&mesh.normals) {
+                    assert!(
+                        position.iter().chain(normal).all(|value| value.is_finite()),
+                        "vertex {position:?} with normal {normal:?} should be finite"
+                    );
+                }
+
+                if segment.start == segment.end {
+                    assert!(mesh.positions.is_empty());
+                    continue;
+                }
+
+                // Surface vertices should stay on their sides of the segment.
+                let disp = segment.displacement();
+                let side = |index: usize| {
+                    let [x, _, z] = mesh.positions[index];
+                    disp.perp_dot(Vec2::new(x, z) - segment.start)
+                };
+                for (left, right) in [(0, 1), (3, 2)] {
+                    assert!(
+                        side(left) > -EPSILON,
+                        "left vertex should be on the left side"
+                    );
+                    assert!(
+                        side(right) < EPSILON,
+                        "right vertex should be on the right side"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::f32::consts::PI;
+
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{Road, RoadData, RoadElevation};
+use crate::{
+    asset::info::road_info::{RoadInfo, RoadProp},
+    core::GameState,
+    game_world::{city::lot::LotVertices, object::ObjectBundle, spline::SplineSegment},
+    math::segment::Segment,
+};
+
+/// Decorates roads with props from [`RoadInfo::props`].
+pub(super) struct StreetPropsPlugin;
+
+impl Plugin for StreetPropsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<DecoratedRoad>()
+            .replicate::<DecoratedRoad>()
+            .add_systems(
+                Update,
+                Self::spawn_props
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+impl StreetPropsPlugin {
+    /// Respawns props for decorated roads whose shape changed.
+    ///
+    /// Props are spawned as children of the road, so they are removed together with it.
+    /// Roads loaded from a save already have their props, so they are skipped.
+    fn spawn_props(
+        mut commands: Commands,
+        asset_server: Res<AssetServer>,
+        roads_info: Res<Assets<RoadInfo>>,
+        mut changed_roads: Query<
+            (
+                Entity,
+                &Parent,
+                &Road,
+                &SplineSegment,
+                &RoadElevation,
+                &mut DecoratedRoad,
+            ),
+            (
+                With<DecoratedRoad>,
+                Or<(
+                    Changed<SplineSegment>,
+                    Changed<RoadElevation>,
+                    Added<DecoratedRoad>,
+                )>,
+            ),
+        >,
+        roads: Query<(Entity, &Parent, &SplineSegment, &RoadData), With<Road>>,
+        lots: Query<(&Parent, &LotVertices)>,
+    ) {
+        for (road_entity, parent, road, &segment, &elevation, mut decorated) in &mut changed_roads {
+            if decorated.is_added() && decorated.props_spawned {
+                debug!("skipping props for loaded road `{road_entity}`");
+                continue;
+            }
+
+            let info_handle = asset_server
+                .get_handle(&road.0)
+                .expect("info should be preloaded");
+            let info = roads_info.get(&info_handle).unwrap();
+
+            let other_roads: Vec<_> = roads
+                .iter()
+                .filter(|&(entity, road_parent, ..)| entity != road_entity && road_parent == parent)
+                .map(|(_, _, segment, road_data)| (**segment, road_data.half_width))
+                .collect();
+            let city_lots: Vec<_> = lots
+                .iter()
+                .filter(|&(lot_parent, _)| lot_parent == parent)
+                .map(|(_, vertices)| vertices)
+                .collect();
+
+            debug!("spawning props for road `{road_entity}`");
+            commands
+                .entity(road_entity)
+                .despawn_descendants()
+                .with_children(|parent| {
+                    for prop in &info.props {
+                        for transform in prop_transforms(*segment, info.half_width, prop) {
+                            let point = transform.translation.xz();
+                            // Bridges have nothing to put props on.
+                            if elevation.height_at(*segment, point) > 0.0 {
+                                continue;
+                            }
+                            if other_roads.iter().any(|&(other_segment, half_width)| {
+                                other_segment.closest_point(point).distance(point) <= half_width
+                            }) {
+                                continue;
+                            }
+                            if city_lots
+                                .iter()
+                                .any(|vertices| vertices.contains_point(point))
+                            {
+                                continue;
+                            }
+
+                            parent.spawn(ObjectBundle::new(prop.object.clone(), transform));
+                        }
+                    }
+                });
+            if !decorated.props_spawned {
+                decorated.props_spawned = true;
+            }
+        }
+    }
+}
+
+/// Returns transforms for the prop on both sides of a road segment.
+///
+/// Props are spaced evenly and centered along the segment,
+/// so the same segment always produces the same layout.
+fn prop_transforms(
+    segment: Segment,
+    half_width: f32,
+    prop: &RoadProp,
+) -> impl Iterator<Item = Transform> {
+    let disp = segment.displacement();
+    let length = disp.length();
+    let count = if prop.spacing > 0.0 {
+        (length / prop.spacing) as usize
+    } else {
+        0
+    };
+    let dir = disp.normalize_or_zero();
+    let side = dir.perp() * (half_width + prop.offset);
+    let margin = (length - count as f32 * prop.spacing) / 2.0;
+    let angle = -dir.to_angle();
+
+    (0..count).flat_map(move |index| {
+        let point = segment.start + dir * (margin + prop.spacing * (index as f32 + 0.5));
+        [(point + side, angle), (point - side, angle + PI)].map(|(point, angle)| {
+            Transform::from_xyz(point.x, 0.0, point.y).with_rotation(Quat::from_rotation_y(angle))
+        })
+    })
+}
+
+/// Marks a road that should be decorated with props along its sides.
+#[derive(Component, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub(crate) struct DecoratedRoad {
+    /// Set after the first spawn to keep props from a save on load.
+    #[reflect(default)]
+    props_spawned: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::AssetPath;
+
+    use super::*;
+
+    #[test]
+    fn transforms() {
+        let segment = Segment::new(Vec2::ZERO, Vec2::X * 10.0);
+        let prop = RoadProp {
+            object: AssetPath::from("prop.object.ron"),
+            spacing: 4.0,
+            offset: 1.0,
+        };
+
+        let points: Vec<_> = prop_transforms(segment, 2.0, &prop)
+            .map(|transform| transform.translation)
+            .collect();
+        assert_eq!(
+            points,
+            [
+                Vec3::new(3.0, 0.0, 3.0),
+                Vec3::new(3.0, 0.0, -3.0),
+                Vec3::new(7.0, 0.0, 3.0),
+                Vec3::new(7.0, 0.0, -3.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn short_segment() {
+        let segment = Segment::new(Vec2::ZERO, Vec2::X);
+        let prop = RoadProp {
+            object: AssetPath::from("prop.object.ron"),
+            spacing: 4.0,
+            offset: 1.0,
+        };
+
+        assert_eq!(prop_transforms(segment, 2.0, &prop).count(), 0);
+    }
+}
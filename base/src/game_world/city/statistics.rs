@@ -0,0 +1,210 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    lot::{community_lot::LotService, LotFamily, LotVertices},
+    City,
+};
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{
+            age::{Age, LifeStage},
+            job::Job,
+            needs::Need,
+            Actor,
+        },
+        clock::GameClock,
+        family::{
+            statistics::{average, Samples},
+            Budget, FamilyMembers,
+        },
+    },
+};
+
+/// Periodically aggregates city state for the statistics screen.
+pub(super) struct CityStatisticsPlugin;
+
+impl Plugin for CityStatisticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<CityStatistics>()
+            .replicate::<CityStatistics>()
+            .add_systems(
+                Update,
+                (
+                    Self::init,
+                    Self::update.run_if(on_timer(Duration::from_secs(2))),
+                )
+                    .chain()
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+impl CityStatisticsPlugin {
+    fn init(mut commands: Commands, cities: Query<Entity, (With<City>, Without<CityStatistics>)>) {
+        for entity in &cities {
+            debug!("initializing statistics for `{entity}`");
+            commands.entity(entity).insert(CityStatistics::default());
+        }
+    }
+
+    /// Recomputes current values and records trend samples once per game hour.
+    fn update(
+        clocks: Query<&GameClock>,
+        mut cities: Query<(Entity, &mut CityStatistics)>,
+        actors: Query<(&Parent, &Children, Option<&Age>, Has<Job>), With<Actor>>,
+        needs: Query<&Need>,
+        families: Query<(&Budget, &FamilyMembers)>,
+        lots: Query<(&Parent, Has<LotFamily>, Has<LotService>), With<LotVertices>>,
+    ) {
+        let hour = clocks
+            .get_single()
+            .ok()
+            .map(|clock| clock.day() * 24 + clock.hour());
+
+        for (city_entity, mut statistics) in &mut cities {
+            let mut new_statistics = statistics.clone();
+
+            let mut needs_sum = 0.0;
+            let mut needs_count = 0;
+            new_statistics.population = 0;
+            new_statistics.working_age = 0;
+            new_statistics.employed = 0;
+            for (_, children, age, has_job) in actors
+                .iter()
+                .filter(|(parent, ..)| ***parent == city_entity)
+            {
+                new_statistics.population += 1;
+                if age.is_some_and(|age| age.stage() == LifeStage::Adult) {
+                    new_statistics.working_age += 1;
+                }
+                if has_job {
+                    new_statistics.employed += 1;
+                }
+                for need in needs.iter_many(children) {
+                    needs_sum += need.0;
+                    needs_count += 1;
+                }
+            }
+            new_statistics.needs = average(needs_sum, needs_count);
+
+            new_statistics.wealth = families
+                .iter()
+                .filter(|(_, members)| {
+                    members.iter().any(|&entity| {
+                        actors
+                            .get(entity)
+                            .is_ok_and(|(parent, ..)| **parent == city_entity)
+                    })
+                })
+                .map(|(budget, _)| **budget)
+                .sum();
+
+            new_statistics.residential_lots = 0;
+            new_statistics.community_lots = 0;
+            new_statistics.vacant_lots = 0;
+            for (_, has_family, has_service) in
+                lots.iter().filter(|(parent, ..)| ***parent == city_entity)
+            {
+                if has_family {
+                    new_statistics.residential_lots += 1;
+                } else if has_service {
+                    new_statistics.community_lots += 1;
+                } else {
+                    new_statistics.vacant_lots += 1;
+                }
+            }
+
+            if hour.is_some() && new_statistics.last_hour != hour {
+                trace!("sampling statistics for `{city_entity}`");
+                new_statistics.last_hour = hour;
+                let population = new_statistics.population as f32;
+                let wealth = new_statistics.wealth as f32;
+                let needs = new_statistics.needs;
+                new_statistics.population_trend.push(population);
+                new_statistics.wealth_trend.push(wealth);
+                new_statistics.needs_trend.push(needs);
+            }
+
+            statistics.set_if_neq(new_statistics);
+        }
+    }
+}
+
+/// Aggregated state of the city.
+///
+/// Trends are sampled every game hour.
+#[derive(Clone, Component, Default, Deserialize, PartialEq, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct CityStatistics {
+    /// Number of actors in the city.
+    pub population: u32,
+
+    /// Number of adults in the city.
+    pub working_age: u32,
+
+    /// Number of actors with a job.
+    pub employed: u32,
+
+    /// Total budget of families that live in the city.
+    pub wealth: u32,
+
+    /// Lots owned by families.
+    pub residential_lots: u32,
+
+    /// Lots with a service.
+    pub community_lots: u32,
+
+    pub vacant_lots: u32,
+
+    /// Average of all resident needs.
+    pub needs: f32,
+
+    pub population_trend: Samples,
+    pub wealth_trend: Samples,
+    pub needs_trend: Samples,
+
+    /// Game hour of the last sample.
+    last_hour: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_world::TestWorld;
+
+    #[test]
+    fn employment() {
+        let mut test_world = TestWorld::builder().build();
+        let city_entity = test_world.spawn_city("City");
+        let family_entity = test_world.spawn_family(city_entity, "Family", 2);
+        let members = test_world
+            .world()
+            .get::<FamilyMembers>(family_entity)
+            .expect("family should have members");
+        let actor_entity = members[0];
+        test_world
+            .world_mut()
+            .entity_mut(actor_entity)
+            .insert(Job::default());
+
+        let updated = test_world.tick_until(300, |world| {
+            world
+                .get::<CityStatistics>(city_entity)
+                .is_some_and(|statistics| statistics.population == 2)
+        });
+        assert!(updated, "statistics should be updated on timer");
+
+        let statistics = test_world
+            .world()
+            .get::<CityStatistics>(city_entity)
+            .unwrap();
+        assert_eq!(statistics.working_age, 2);
+        assert_eq!(statistics.employed, 1);
+    }
+}
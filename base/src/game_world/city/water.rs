@@ -0,0 +1,529 @@
+pub mod creating_water;
+
+use std::f32::consts::TAU;
+
+use anyhow::{ensure, Result};
+use avian3d::prelude::*;
+use bevy::{
+    ecs::entity::MapEntities,
+    math::Affine2,
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::{ImageAddressMode, ImageSampler, ImageSamplerDescriptor},
+    },
+    tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task},
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asset::info::scenario_info::ScenarioFeature,
+    common_conditions::in_any_state,
+    core::GameState,
+    game_world::{
+        city,
+        collectable::{Collectable, CollectableEntry, Rarity, Spawner},
+        hover::Hoverable,
+        mutation_log::{Mutation, MutationKind, MutationRecorder},
+        navigation::Obstacle,
+        scenario::ScenarioLocks,
+        Layer, WorldState,
+    },
+    math::{polygon::Polygon, segment::Segment, triangulator::Triangulator},
+    network::validation::ClientViolation,
+};
+use creating_water::{CreatingWater, CreatingWaterPlugin};
+
+pub(super) struct WaterPlugin;
+
+impl Plugin for WaterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(CreatingWaterPlugin)
+            .init_resource::<WaterMaterial>()
+            .register_type::<WaterVertices>()
+            .replicate::<WaterVertices>()
+            .add_mapped_client_event::<WaterCreate>(ChannelKind::Unordered)
+            .add_mapped_client_event::<WaterDelete>(ChannelKind::Unordered)
+            .add_server_event::<WaterEventConfirmed>(ChannelKind::Unordered)
+            .add_systems(
+                PreUpdate,
+                (
+                    (Self::create, Self::delete)
+                        .after(ServerSet::Receive)
+                        .run_if(server_or_singleplayer),
+                    Self::init
+                        .after(ClientSet::Receive)
+                        .run_if(in_state(GameState::InGame)),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::apply_meshes.run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                Update,
+                Self::animate.run_if(in_any_state([
+                    WorldState::City,
+                    WorldState::Family,
+                    WorldState::Spectator,
+                ])),
+            );
+    }
+}
+
+/// Height of the water surface above the ground.
+const WATER_HEIGHT: f32 = 0.02;
+
+/// Depth of the water collider below the ground.
+///
+/// The collider should cross the ground plane to affect the navmesh.
+const WATER_DEPTH: f32 = 0.1;
+
+impl WaterPlugin {
+    /// Inserts components and starts mesh generation in [`AsyncComputeTaskPool`].
+    fn init(
+        mut commands: Commands,
+        water_material: Res<WaterMaterial>,
+        water_bodies: Query<
+            (Entity, &WaterVertices),
+            (Without<Handle<Mesh>>, Without<CreatingWater>),
+        >,
+    ) {
+        for (entity, vertices) in &water_bodies {
+            debug!("initializing water `{entity}`");
+
+            let vertices = vertices.clone();
+            let task = AsyncComputeTaskPool::get().spawn(async move {
+                let triangles = triangulate(&mut Triangulator::default(), &vertices);
+                let collider = generate_collider(&triangles)?;
+                Some((generate_mesh(&triangles), collider))
+            });
+
+            commands.entity(entity).insert((
+                Name::new("Water"),
+                CollisionLayers::new(Layer::Water, [Layer::PlacingObject, Layer::PlacingWall]),
+                Hoverable,
+                Obstacle,
+                fish_spawner(),
+                WaterMeshTask(task),
+                PbrBundle {
+                    material: water_material.material.clone(),
+                    ..Default::default()
+                },
+            ));
+        }
+    }
+
+    /// Applies meshes and colliders from finished [`WaterMeshTask`]s.
+    fn apply_meshes(
+        mut commands: Commands,
+        mut meshes: ResMut<Assets<Mesh>>,
+        mut water_bodies: Query<(Entity, &mut Handle<Mesh>, &mut WaterMeshTask)>,
+    ) {
+        for (entity, mut mesh_handle, mut mesh_task) in &mut water_bodies {
+            let Some(generated) = block_on(future::poll_once(&mut mesh_task.0)) else {
+                continue;
+            };
+
+            let mut entity_commands = commands.entity(entity);
+            entity_commands.remove::<WaterMeshTask>();
+            // Invalid shapes from older saves are kept, but without mesh and collision.
+            match generated {
+                Some((mesh, collider)) => {
+                    trace!("applying generated mesh for water `{entity}`");
+                    *mesh_handle = meshes.add(mesh);
+                    entity_commands.insert(collider);
+                }
+                None => error!("water `{entity}` has no area"),
+            }
+        }
+    }
+
+    /// Scrolls the normal map to simulate ripples.
+    fn animate(
+        time: Res<Time>,
+        water_material: Res<WaterMaterial>,
+        mut materials: ResMut<Assets<StandardMaterial>>,
+    ) {
+        const SPEED: Vec2 = Vec2::new(0.02, 0.015);
+        let material = materials
+            .get_mut(&water_material.material)
+            .expect("water material should always exist");
+        let offset = (SPEED * time.elapsed_seconds_wrapped()).fract();
+        material.uv_transform = Affine2::from_scale_angle_translation(
+            Vec2::splat(WaterMaterial::UV_SCALE),
+            0.0,
+            offset,
+        );
+    }
+
+    fn create(
+        mut commands: Commands,
+        mut create_events: EventReader<FromClient<WaterCreate>>,
+        mut confirm_events: EventWriter<ToClients<WaterEventConfirmed>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        locks: ScenarioLocks,
+    ) {
+        for FromClient { client_id, event } in create_events.read().cloned() {
+            if let Err(violation) = locks.check(ScenarioFeature::Water, client_id) {
+                violation_events.send(violation);
+                continue;
+            }
+
+            if let Err(e) = event.validate() {
+                error!("`{client_id:?}` tries to create invalid water: {e}");
+                continue;
+            }
+
+            info!("`{client_id:?}` creates water");
+            recorder.record(client_id, &event);
+            commands.entity(event.city_entity).with_children(|parent| {
+                parent.spawn(WaterBundle::new(event.polygon));
+            });
+            confirm_events.send(ToClients {
+                mode: SendMode::Direct(client_id),
+                event: WaterEventConfirmed,
+            });
+        }
+    }
+
+    fn delete(
+        mut commands: Commands,
+        mut delete_events: EventReader<FromClient<WaterDelete>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        locks: ScenarioLocks,
+        water_bodies: Query<(), With<WaterVertices>>,
+    ) {
+        for FromClient { client_id, event } in delete_events.read().copied() {
+            if let Err(violation) = locks.check(ScenarioFeature::Water, client_id) {
+                violation_events.send(violation);
+                continue;
+            }
+
+            if water_bodies.get(event.0).is_ok() {
+                info!("`{client_id:?}` deletes water `{:?}`", event.0);
+                recorder.record(client_id, &event);
+                commands.entity(event.0).despawn_recursive();
+            } else {
+                error!(
+                    "`{client_id:?}` tried to delete non-water entity `{:?}`",
+                    event.0
+                );
+            }
+        }
+    }
+}
+
+/// Water mesh and collider generation that runs in [`AsyncComputeTaskPool`].
+///
+/// Contains [`None`] for shapes without area.
+#[derive(Component)]
+struct WaterMeshTask(Task<Option<(Mesh, Collider)>>);
+
+/// Creates a spawner for fishing.
+fn fish_spawner() -> Spawner {
+    let entry = |rarity, name: &str, price| CollectableEntry {
+        rarity,
+        collectable: Collectable {
+            name: name.to_string(),
+            price,
+        },
+    };
+
+    Spawner {
+        interval: 120.0,
+        capacity: 3,
+        table: vec![
+            entry(Rarity::Common, "Perch", 5),
+            entry(Rarity::Common, "Roach", 4),
+            entry(Rarity::Uncommon, "Pike", 20),
+            entry(Rarity::Rare, "Golden carp", 100),
+        ],
+    }
+}
+
+/// Returns triangles for the water surface in local city coordinates.
+///
+/// Triangles are ordered counter-clockwise when viewed from above.
+fn triangulate(triangulator: &mut Triangulator, vertices: &WaterVertices) -> Vec<[Vec2; 3]> {
+    let mut points = &vertices[..];
+    if points.len() > 1 && points.first() == points.last() {
+        points = &points[..points.len() - 1];
+    }
+
+    let positions: Vec<_> = points.iter().map(|point| [point.x, point.y, 0.0]).collect();
+    triangulator
+        .triangulate(&positions)
+        .chunks_exact(3)
+        .map(|triangle| {
+            let [a, b, c] = [0, 1, 2].map(|index| points[triangle[index] as usize]);
+            // Flip to make the triangle face up after mapping Y to Z.
+            if (b - a).perp_dot(c - a) > 0.0 {
+                [a, c, b]
+            } else {
+                [a, b, c]
+            }
+        })
+        .collect()
+}
+
+fn generate_mesh(triangles: &[[Vec2; 3]]) -> Mesh {
+    let mut positions = Vec::with_capacity(triangles.len() * 3);
+    let mut uvs = Vec::with_capacity(triangles.len() * 3);
+    for &point in triangles.iter().flatten() {
+        positions.push([point.x, WATER_HEIGHT, point.y]);
+        uvs.push(point.to_array());
+    }
+    let normals = vec![[0.0, 1.0, 0.0]; positions.len()];
+    let indices = (0..positions.len() as u32).collect();
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+    .with_inserted_indices(Indices::U32(indices))
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+
+    mesh.generate_tangents()
+        .expect("water mesh should have all attributes for tangents generation");
+
+    mesh
+}
+
+/// Generates a thin prism for each triangle.
+///
+/// Returns [`None`] for degenerate polygons without triangles.
+fn generate_collider(triangles: &[[Vec2; 3]]) -> Option<Collider> {
+    let shapes: Vec<_> = triangles
+        .iter()
+        .filter_map(|triangle| {
+            let points = triangle
+                .iter()
+                .flat_map(|point| {
+                    [
+                        Vec3::new(point.x, WATER_HEIGHT, point.y),
+                        Vec3::new(point.x, -WATER_DEPTH, point.y),
+                    ]
+                })
+                .collect();
+            Collider::convex_hull(points)
+        })
+        .map(|collider| (Vec3::ZERO, Quat::IDENTITY, collider))
+        .collect();
+
+    if shapes.is_empty() {
+        return None;
+    }
+
+    Some(Collider::compound(shapes))
+}
+
+/// Shared material for all water bodies.
+#[derive(Resource)]
+struct WaterMaterial {
+    material: Handle<StandardMaterial>,
+}
+
+impl WaterMaterial {
+    /// Normal map tiles per meter.
+    const UV_SCALE: f32 = 0.25;
+
+    /// Generates a tileable normal map from a sum of sine waves.
+    fn generate_normal_map() -> Image {
+        const SIZE: u32 = 64;
+        const WAVES: [(f32, f32, f32); 3] = [(1.0, 2.0, 0.3), (3.0, 1.0, 0.2), (2.0, 5.0, 0.1)];
+
+        let mut data = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let uv = Vec2::new(x as f32, y as f32) / SIZE as f32 * TAU;
+                let mut slope = Vec2::ZERO;
+                for (freq_x, freq_y, amplitude) in WAVES {
+                    let derivative = amplitude * (uv.x * freq_x + uv.y * freq_y).cos();
+                    slope += Vec2::new(freq_x, freq_y) * derivative;
+                }
+                let normal = Vec3::new(-slope.x, -slope.y, SIZE as f32 / TAU).normalize();
+                let color = (normal * 0.5 + 0.5) * u8::MAX as f32;
+                data.extend_from_slice(&[color.x as u8, color.y as u8, color.z as u8, u8::MAX]);
+            }
+        }
+
+        let mut image = Image::new(
+            Extent3d {
+                width: SIZE,
+                height: SIZE,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8Unorm,
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+            address_mode_u: ImageAddressMode::Repeat,
+            address_mode_v: ImageAddressMode::Repeat,
+            ..ImageSamplerDescriptor::linear()
+        });
+
+        image
+    }
+}
+
+impl FromWorld for WaterMaterial {
+    fn from_world(world: &mut World) -> Self {
+        let mut images = world.resource_mut::<Assets<Image>>();
+        let normal_map = images.add(Self::generate_normal_map());
+
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let material = materials.add(StandardMaterial {
+            base_color: Srgba::new(0.1, 0.35, 0.5, 0.8).into(),
+            perceptual_roughness: 0.05,
+            reflectance: 0.6,
+            normal_map_texture: Some(normal_map),
+            alpha_mode: AlphaMode::Blend,
+            uv_transform: Affine2::from_scale(Vec2::splat(Self::UV_SCALE)),
+            ..Default::default()
+        });
+
+        Self { material }
+    }
+}
+
+#[derive(Bundle)]
+pub(super) struct WaterBundle {
+    vertices: WaterVertices,
+    parent_sync: ParentSync,
+    replication: Replicated,
+}
+
+impl WaterBundle {
+    pub(super) fn new(polygon: Polygon) -> Self {
+        Self {
+            vertices: WaterVertices(polygon),
+            parent_sync: Default::default(),
+            replication: Replicated,
+        }
+    }
+}
+
+/// Closed polygon of a water body in local city coordinates.
+///
+/// Used for both ponds and rivers.
+#[derive(Clone, Component, Default, Deref, DerefMut, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub(crate) struct WaterVertices(Polygon);
+
+impl WaterVertices {
+    /// Returns a point on the shore closest to the specified point inside the water.
+    ///
+    /// The returned point is moved away from the water by `offset`.
+    pub(crate) fn shore_point(&self, point: Vec2, offset: f32) -> Option<Vec2> {
+        let closest = self
+            .windows(2)
+            .map(|points| Segment::new(points[0], points[1]).closest_point(point))
+            .min_by(|a, b| {
+                a.distance_squared(point)
+                    .total_cmp(&b.distance_squared(point))
+            })?;
+
+        let direction = (closest - point).try_normalize().unwrap_or(Vec2::X);
+        Some(closest + direction * offset)
+    }
+}
+
+#[derive(Clone, Deserialize, Event, Serialize)]
+struct WaterCreate {
+    polygon: Polygon,
+    city_entity: Entity,
+}
+
+/// Maximum number of water vertices to limit triangulation cost.
+const MAX_VERTICES: usize = 256;
+
+/// Minimum water area in square meters.
+const MIN_AREA: f32 = 1.0;
+
+impl WaterCreate {
+    /// Checks that the polygon is a closed simple shape inside the city.
+    fn validate(&self) -> Result<()> {
+        let len = self.polygon.len();
+        ensure!(
+            (4..=MAX_VERTICES).contains(&len),
+            "water should have between 3 and {} vertices, but has {}",
+            MAX_VERTICES - 1,
+            len.saturating_sub(1)
+        );
+        ensure!(
+            self.polygon.first() == self.polygon.last(),
+            "water polygon should be closed"
+        );
+        ensure!(
+            self.polygon.iter().all(|&vertex| city::within_city(vertex)),
+            "water vertices should be inside the city"
+        );
+        ensure!(
+            self.polygon.is_simple(),
+            "water edges shouldn't cross each other"
+        );
+        let area = self.polygon.area();
+        ensure!(
+            area >= MIN_AREA,
+            "water area should be at least {MIN_AREA} m², but it's {area:.2} m²"
+        );
+
+        Ok(())
+    }
+}
+
+impl MapEntities for WaterCreate {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.city_entity = entity_mapper.map_entity(self.city_entity);
+    }
+}
+
+impl Mutation for WaterCreate {
+    const TARGET: &'static str = "water";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Spawn
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        None
+    }
+}
+
+#[derive(Clone, Copy, Deserialize, Event, Serialize)]
+struct WaterDelete(Entity);
+
+impl MapEntities for WaterDelete {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
+
+impl Mutation for WaterDelete {
+    const TARGET: &'static str = "water";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Delete
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        Some(self.0)
+    }
+}
+
+#[derive(Deserialize, Event, Serialize)]
+struct WaterEventConfirmed;
+
+/// Used to filter out water being created by the current player.
+#[derive(Component)]
+struct UnconfirmedWater;
@@ -0,0 +1,161 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_replicon::prelude::*;
+use leafwing_input_manager::common_conditions::action_just_pressed;
+
+use super::{UnconfirmedWater, WaterCreate, WaterDelete, WaterEventConfirmed, WaterVertices};
+use crate::{
+    game_world::{
+        city::{ActiveCity, CityMode},
+        hover::Hovered,
+        player_camera::CameraCaster,
+    },
+    settings::Action,
+};
+
+pub(super) struct CreatingWaterPlugin;
+
+impl Plugin for CreatingWaterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            Self::end_creation
+                .after(ClientSet::Receive)
+                .run_if(in_state(CityMode::Water))
+                .run_if(on_event::<WaterEventConfirmed>()),
+        )
+        .add_systems(
+            Update,
+            (
+                Self::start_creation
+                    .run_if(action_just_pressed(Action::Confirm))
+                    .run_if(not(any_with_component::<CreatingWater>)),
+                Self::set_vertex_position,
+                Self::confirm.run_if(action_just_pressed(Action::Confirm)),
+                Self::end_creation.run_if(action_just_pressed(Action::Cancel)),
+                Self::delete
+                    .run_if(action_just_pressed(Action::Delete))
+                    .run_if(not(any_with_component::<CreatingWater>)),
+            )
+                .run_if(in_state(CityMode::Water)),
+        )
+        .add_systems(
+            PostUpdate,
+            Self::draw_lines.run_if(in_state(CityMode::Water)),
+        );
+    }
+}
+
+impl CreatingWaterPlugin {
+    fn start_creation(
+        camera_caster: CameraCaster,
+        mut commands: Commands,
+        cities: Query<Entity, With<ActiveCity>>,
+    ) {
+        if let Some(point) = camera_caster.intersect_ground() {
+            info!("starting placing water");
+            // Spawn with two the same vertices because we edit the last one on cursor movement.
+            commands.entity(cities.single()).with_children(|parent| {
+                parent.spawn((
+                    StateScoped(CityMode::Water),
+                    WaterVertices(vec![point.xz(); 2].into()),
+                    CreatingWater,
+                ));
+            });
+        }
+    }
+
+    fn set_vertex_position(
+        camera_caster: CameraCaster,
+        mut creating_water: Query<
+            &mut WaterVertices,
+            (With<CreatingWater>, Without<UnconfirmedWater>),
+        >,
+    ) {
+        if let Ok(mut water_vertices) = creating_water.get_single_mut() {
+            if let Some(point) = camera_caster.intersect_ground().map(|hover| hover.xz()) {
+                let first_vertex = *water_vertices
+                    .first()
+                    .expect("vertices should have at least 2 vertices");
+                let last_vertex = water_vertices.last_mut().unwrap();
+
+                const SNAP_DELTA: f32 = 0.1;
+                let delta = first_vertex - point;
+                if delta.x.abs() <= SNAP_DELTA && delta.y.abs() <= SNAP_DELTA {
+                    trace!("snapping vertex position to first vertex `{first_vertex:?}`");
+                    *last_vertex = first_vertex;
+                } else {
+                    trace!("updating vertex position to `{point:?}`");
+                    *last_vertex = point;
+                }
+            }
+        }
+    }
+
+    fn confirm(
+        mut commands: Commands,
+        mut create_events: EventWriter<WaterCreate>,
+        mut creating_water: Query<
+            (Entity, &mut WaterVertices),
+            (With<CreatingWater>, Without<UnconfirmedWater>),
+        >,
+        cities: Query<Entity, With<ActiveCity>>,
+    ) {
+        if let Ok((entity, mut water_vertices)) = creating_water.get_single_mut() {
+            let first_vertex = *water_vertices
+                .first()
+                .expect("vertices should have at least 2 vertices");
+            let last_vertex = *water_vertices.last().unwrap();
+            if first_vertex == last_vertex {
+                if water_vertices.len() < 4 {
+                    debug!("ignoring water confirmation with less than 3 points");
+                    return;
+                }
+
+                info!("confirming water creation");
+                commands.entity(entity).insert(UnconfirmedWater);
+                create_events.send(WaterCreate {
+                    polygon: water_vertices.0.clone(),
+                    city_entity: cities.single(),
+                });
+            } else {
+                info!("confirming water point");
+                water_vertices.push(last_vertex);
+            }
+        }
+    }
+
+    fn end_creation(mut commands: Commands, creating_water: Query<Entity, With<CreatingWater>>) {
+        if let Ok(entity) = creating_water.get_single() {
+            info!("ending water creation");
+            commands.entity(entity).despawn();
+        }
+    }
+
+    fn delete(
+        mut delete_events: EventWriter<WaterDelete>,
+        water_bodies: Query<Entity, (With<WaterVertices>, With<Hovered>)>,
+    ) {
+        if let Ok(entity) = water_bodies.get_single() {
+            info!("deleting water `{entity}`");
+            delete_events.send(WaterDelete(entity));
+        }
+    }
+
+    fn draw_lines(
+        mut gizmos: Gizmos,
+        creating_water: Query<(&Parent, &WaterVertices), With<CreatingWater>>,
+        cities: Query<&GlobalTransform>,
+    ) {
+        for (parent, vertices) in &creating_water {
+            let transform = cities.get(**parent).unwrap();
+            let points_iter = vertices
+                .iter()
+                .map(|vertex| Vec3::new(vertex.x, 0.0, vertex.y))
+                .map(|point| transform.transform_point(point));
+            gizmos.linestrip(points_iter, Color::WHITE);
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct CreatingWater;
@@ -0,0 +1,314 @@
+use std::{f32::consts::TAU, time::Duration};
+
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
+
+use super::{UnsavedChanges, WorldState};
+use crate::core::GameState;
+
+/// Advances in-game time and moves the sun.
+pub(super) struct ClockPlugin;
+
+impl Plugin for ClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GameClock>()
+            .replicate::<GameClock>()
+            .add_systems(
+                FixedUpdate,
+                (Self::init, Self::advance)
+                    .chain()
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(Update, Self::update_sun.run_if(in_state(GameState::InGame)));
+    }
+}
+
+/// Game seconds that pass per one real second.
+const TIME_SCALE: f32 = 60.0;
+
+/// Game seconds by which the clock advances at once.
+///
+/// Avoids changing (and replicating) the clock every tick.
+const CLOCK_STEP: f64 = 60.0;
+
+const SECONDS_PER_DAY: f32 = 24.0 * 60.0 * 60.0;
+
+const DAYS_PER_SEASON: u32 = 7;
+
+impl ClockPlugin {
+    /// Spawns a clock if the world doesn't have one.
+    ///
+    /// Clock is a replicated entity to be stored in saves.
+    fn init(mut commands: Commands, clocks: Query<(), With<GameClock>>) {
+        if clocks.is_empty() {
+            debug!("spawning game clock");
+            commands.spawn((
+                Name::new("Game clock"),
+                StateScoped(GameState::InGame),
+                GameClock::default(),
+                Replicated,
+            ));
+        }
+    }
+
+    fn advance(
+        time: Res<Time>,
+        mut pending: Local<f64>,
+        mut unsaved: ResMut<UnsavedChanges>,
+        world_state: Res<State<WorldState>>,
+        mut clocks: Query<&mut GameClock>,
+    ) {
+        let Ok(mut clock) = clocks.get_single_mut() else {
+            return;
+        };
+
+        *pending += (time.delta_seconds() * TIME_SCALE) as f64;
+        if *pending >= CLOCK_STEP {
+            let advanced = (*pending / CLOCK_STEP).floor() * CLOCK_STEP;
+            clock.seconds += advanced;
+            *pending -= advanced;
+
+            // Life simulation changes needs, budgets and other saved state without client requests.
+            if **world_state == WorldState::Family {
+                unsaved.0 = true;
+            }
+        }
+    }
+
+    fn update_sun(
+        clocks: Query<&GameClock, Changed<GameClock>>,
+        mut suns: Query<(&mut Transform, &mut DirectionalLight), With<Sun>>,
+    ) {
+        let Ok(clock) = clocks.get_single() else {
+            return;
+        };
+
+        for (mut transform, mut light) in &mut suns {
+            // Rises at 6:00 in the east and sets at 18:00 in the west.
+            let angle = (clock.day_fraction() - 0.25) * TAU;
+            let direction = Vec3::new(angle.cos(), angle.sin(), 0.3);
+            transform.translation = direction * 10.0;
+            transform.look_at(Vec3::ZERO, Vec3::Y);
+            // Keep a bit of moonlight at night.
+            light.illuminance = light_consts::lux::AMBIENT_DAYLIGHT * direction.y.max(0.01);
+        }
+    }
+}
+
+/// Current in-game time.
+#[derive(Clone, Component, Copy, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct GameClock {
+    /// Game seconds since the world creation.
+    seconds: f64,
+}
+
+impl Default for GameClock {
+    /// Starts in the morning of the first day.
+    fn default() -> Self {
+        Self {
+            seconds: 8.0 * 60.0 * 60.0,
+        }
+    }
+}
+
+impl GameClock {
+    /// Returns the number of the current day starting from 0.
+    pub fn day(&self) -> u32 {
+        (self.seconds / SECONDS_PER_DAY as f64) as u32
+    }
+
+    /// Returns the passed part of the current day from 0 to 1.
+    pub fn day_fraction(&self) -> f32 {
+        (self.seconds % SECONDS_PER_DAY as f64) as f32 / SECONDS_PER_DAY
+    }
+
+    /// Returns the current hour from 0 to 23.
+    pub fn hour(&self) -> u32 {
+        (self.day_fraction() * 24.0) as u32
+    }
+
+    pub fn minute(&self) -> u32 {
+        (self.day_fraction() * 24.0 * 60.0) as u32 % 60
+    }
+
+    /// Moves the clock forward to the nearest moment with the given time of day.
+    ///
+    /// Skips to the next day if the time has already passed today.
+    pub fn advance_to(&mut self, hour: u32, minute: u32) {
+        let day_start = self.day() as f64 * SECONDS_PER_DAY as f64;
+        let mut seconds = day_start + (hour * 60 * 60 + minute * 60) as f64;
+        if seconds < self.seconds {
+            seconds += SECONDS_PER_DAY as f64;
+        }
+        self.seconds = seconds;
+    }
+
+    pub fn is_night(&self) -> bool {
+        !(7..20).contains(&self.hour())
+    }
+
+    /// Returns `true` if it's raining.
+    ///
+    /// Weather is derived from the time, so it's the same for all players without replication.
+    /// Rain lasts for [`RAIN_HOURS`] and is more likely in spring and autumn.
+    pub fn is_raining(&self) -> bool {
+        let chance = match self.season() {
+            Season::Spring => 0.3,
+            Season::Summer => 0.15,
+            Season::Autumn => 0.4,
+            Season::Winter => 0.1,
+        };
+        let period = self.day() * (24 / RAIN_HOURS) + self.hour() / RAIN_HOURS;
+
+        hash_fraction(period) < chance
+    }
+
+    /// Returns the current season, the world starts in spring.
+    pub fn season(&self) -> Season {
+        match self.day() / DAYS_PER_SEASON % 4 {
+            0 => Season::Spring,
+            1 => Season::Summer,
+            2 => Season::Autumn,
+            _ => Season::Winter,
+        }
+    }
+}
+
+/// Duration of a single weather period in hours.
+const RAIN_HOURS: u32 = 4;
+
+/// Maps a number into a pseudo-random value from 0 to 1.
+fn hash_fraction(value: u32) -> f32 {
+    let mut hash = value.wrapping_mul(0x9E37_79B9);
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85EB_CA6B);
+    hash ^= hash >> 13;
+
+    hash as f32 / u32::MAX as f32
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Display, EnumIter, Eq, Hash, PartialEq, Serialize)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+/// Run condition that passes once per `interval` of in-game time.
+///
+/// Unlike [`on_timer`](bevy::time::common_conditions::on_timer),
+/// follows [`GameClock`] and doesn't advance while the clock is missing.
+pub(crate) fn on_game_timer(interval: Duration) -> impl FnMut(Query<&GameClock>) -> bool {
+    let interval = interval.as_secs_f64();
+    let mut last_seconds = None;
+    move |clocks| {
+        let Ok(clock) = clocks.get_single() else {
+            return false;
+        };
+
+        match last_seconds {
+            // Also reset when another world with an earlier clock is loaded.
+            Some(last) if clock.seconds >= last => {
+                if clock.seconds - last < interval {
+                    return false;
+                }
+                last_seconds = Some(clock.seconds);
+                true
+            }
+            _ => {
+                last_seconds = Some(clock.seconds);
+                false
+            }
+        }
+    }
+}
+
+/// Marks the main directional light that follows [`GameClock`].
+#[derive(Component)]
+pub(super) struct Sun;
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::System;
+
+    use super::*;
+
+    #[test]
+    fn season() {
+        let clock = GameClock::default();
+        assert_eq!(clock.season(), Season::Spring);
+
+        let seconds_per_season = (DAYS_PER_SEASON as f32 * SECONDS_PER_DAY) as f64;
+        let clock = GameClock {
+            seconds: 3.0 * seconds_per_season,
+        };
+        assert_eq!(clock.season(), Season::Winter);
+
+        let clock = GameClock {
+            seconds: 4.0 * seconds_per_season,
+        };
+        assert_eq!(clock.season(), Season::Spring, "seasons should repeat");
+    }
+
+    #[test]
+    fn rain() {
+        let seconds_per_period = (RAIN_HOURS * 60 * 60) as f64;
+        let periods = 24 / RAIN_HOURS * DAYS_PER_SEASON;
+        let rainy = (0..periods)
+            .filter(|&period| {
+                let clock = GameClock {
+                    seconds: period as f64 * seconds_per_period,
+                };
+                clock.is_raining()
+            })
+            .count();
+        assert!(rainy > 0, "it should rain sometimes in spring");
+        assert!(rainy < periods as usize, "it shouldn't rain all the time");
+    }
+
+    #[test]
+    fn advance_to() {
+        let mut clock = GameClock::default();
+        clock.advance_to(21, 30);
+        assert_eq!(clock.day(), 0);
+        assert_eq!(clock.hour(), 21);
+        assert_eq!(clock.minute(), 30);
+
+        clock.advance_to(6, 0);
+        assert_eq!(clock.day(), 1, "past time should move to the next day");
+        assert_eq!(clock.hour(), 6);
+        assert_eq!(clock.minute(), 0);
+    }
+
+    #[test]
+    fn game_timer() {
+        let mut world = World::new();
+        let mut condition = IntoSystem::into_system(on_game_timer(Duration::from_secs(3600)));
+        condition.initialize(&mut world);
+        assert!(!condition.run((), &mut world), "should wait for a clock");
+
+        let clock_entity = world.spawn(GameClock::default()).id();
+        assert!(!condition.run((), &mut world), "should start counting");
+
+        world.get_mut::<GameClock>(clock_entity).unwrap().seconds += 1800.0;
+        assert!(!condition.run((), &mut world));
+
+        world.get_mut::<GameClock>(clock_entity).unwrap().seconds += 1800.0;
+        assert!(condition.run((), &mut world));
+        assert!(!condition.run((), &mut world), "should pass only once");
+
+        world.get_mut::<GameClock>(clock_entity).unwrap().seconds = 0.0;
+        assert!(
+            !condition.run((), &mut world),
+            "should restart on earlier clock"
+        );
+
+        world.get_mut::<GameClock>(clock_entity).unwrap().seconds = 3600.0;
+        assert!(condition.run((), &mut world));
+    }
+}
@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use super::{
+    actor::Actor,
+    rng::{RngStream, WorldRng},
+};
+use crate::core::GameState;
+
+/// Periodically produces collectable items on spawners.
+///
+/// Collected items are stored in actor's [`Inventory`].
+pub(super) struct CollectablePlugin;
+
+impl Plugin for CollectablePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Spawner>()
+            .register_type::<CollectableEntry>()
+            .register_type::<Rarity>()
+            .register_type::<Collectable>()
+            .register_type::<SpawnerStock>()
+            .register_type::<Inventory>()
+            .replicate::<SpawnerStock>()
+            .replicate::<Inventory>()
+            .add_systems(
+                Update,
+                (Self::init_spawners, Self::init_inventories)
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                FixedUpdate,
+                Self::produce
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+impl CollectablePlugin {
+    fn init_spawners(
+        mut commands: Commands,
+        spawners: Query<(Entity, &Spawner, Has<SpawnerStock>), Without<SpawnerTimer>>,
+    ) {
+        for (entity, spawner, has_stock) in &spawners {
+            debug!("initializing spawner `{entity}`");
+            let timer = Timer::from_seconds(spawner.interval, TimerMode::Repeating);
+            let mut entity = commands.entity(entity);
+            entity.insert(SpawnerTimer(timer));
+            // Stock could be already loaded from a save.
+            if !has_stock {
+                entity.insert(SpawnerStock::default());
+            }
+        }
+    }
+
+    fn init_inventories(
+        mut commands: Commands,
+        actors: Query<Entity, (With<Actor>, Without<Inventory>)>,
+    ) {
+        for entity in &actors {
+            debug!("initializing inventory for `{entity}`");
+            commands.entity(entity).insert(Inventory::default());
+        }
+    }
+
+    fn produce(
+        time: Res<Time>,
+        mut world_rng: ResMut<WorldRng>,
+        mut spawners: Query<(Entity, &Spawner, &mut SpawnerTimer, &mut SpawnerStock)>,
+    ) {
+        let rng = world_rng.stream(RngStream::Collectables);
+        for (entity, spawner, mut timer, mut stock) in &mut spawners {
+            timer.tick(time.delta());
+            for _ in 0..timer.times_finished_this_tick() {
+                if stock.len() >= spawner.capacity {
+                    break;
+                }
+
+                if let Ok(entry) = spawner
+                    .table
+                    .choose_weighted(rng, |entry| entry.rarity.weight())
+                {
+                    debug!(
+                        "spawning {} '{}' on `{entity}`",
+                        entry.rarity, entry.collectable.name
+                    );
+                    stock.push(entry.collectable.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Produces collectables from a rarity table.
+///
+/// Usually defined in object metadata.
+#[derive(Clone, Component, Default, Reflect)]
+#[reflect(Component, Default)]
+pub(crate) struct Spawner {
+    /// Seconds between spawns.
+    pub(crate) interval: f32,
+
+    /// Maximum number of collectables waiting for pickup.
+    pub(crate) capacity: usize,
+
+    pub(crate) table: Vec<CollectableEntry>,
+}
+
+#[derive(Clone, Reflect)]
+pub(crate) struct CollectableEntry {
+    pub(crate) rarity: Rarity,
+    pub(crate) collectable: Collectable,
+}
+
+#[derive(Clone, Copy, Display, Reflect)]
+pub(crate) enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+}
+
+impl Rarity {
+    fn weight(self) -> u32 {
+        match self {
+            Rarity::Common => 70,
+            Rarity::Uncommon => 25,
+            Rarity::Rare => 5,
+        }
+    }
+}
+
+#[derive(Clone, Default, Deserialize, Reflect, Serialize)]
+pub struct Collectable {
+    pub name: String,
+    pub price: u32,
+}
+
+/// Collectables available for pickup on a [`Spawner`].
+#[derive(Clone, Component, Default, Deref, DerefMut, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub(crate) struct SpawnerStock(Vec<Collectable>);
+
+#[derive(Component, Deref, DerefMut)]
+struct SpawnerTimer(Timer);
+
+/// Collectables carried by an actor.
+#[derive(Clone, Component, Default, Deref, DerefMut, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct Inventory(pub(crate) Vec<Collectable>);
+
+impl Inventory {
+    /// Returns the total price of all collectables.
+    pub fn price(&self) -> u32 {
+        self.iter().map(|collectable| collectable.price).sum()
+    }
+}
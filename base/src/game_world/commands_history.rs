@@ -11,6 +11,7 @@ use bevy::{
 use bevy_replicon::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use super::mutation_log::{Mutation, MutationKind};
 use crate::core::GameState;
 
 pub(super) struct CommandHistoryPlugin;
@@ -37,9 +38,7 @@ impl CommandHistoryPlugin {
         mut buffer: ResMut<HistoryBuffer>,
         despawn_entities: Query<(Entity, &PendingDespawn)>,
     ) {
-        for &confirmation in confirmation_events.read() {
-            buffer.confirm(confirmation);
-
+        for confirmation in confirmation_events.read().cloned() {
             if let Some((entity, _)) = despawn_entities
                 .iter()
                 .find(|(_, despawn)| despawn.command_id == confirmation.id)
@@ -47,6 +46,8 @@ impl CommandHistoryPlugin {
                 debug!("despawning entity `{entity}` for `{confirmation:?}`");
                 commands.entity(entity).despawn_recursive();
             }
+
+            buffer.confirm(confirmation);
         }
     }
 
@@ -139,7 +140,7 @@ impl CommandIds {
 }
 
 #[derive(Resource, Default)]
-struct HistoryBuffer {
+pub(crate) struct HistoryBuffer {
     undo: LinkedList<CommandRecord>,
     redo: LinkedList<CommandRecord>,
     mapper: CommandEntityMapper,
@@ -147,6 +148,11 @@ struct HistoryBuffer {
 }
 
 impl HistoryBuffer {
+    /// Returns `true` if some commands are waiting for the server confirmation.
+    pub(crate) fn has_unconfirmed(&self) -> bool {
+        !self.unconfirmed.is_empty()
+    }
+
     /// Applies the command for `stack` from the reverse one.
     fn apply_reverse(&mut self, stack: Stack, world: &mut World) {
         let record = match stack {
@@ -378,10 +384,18 @@ impl<'a> EntityRecorder<'a> {
 
         self.index += 1;
     }
+
+    /// Skips entities that won't be recorded during this execution.
+    ///
+    /// Needed for commands that record different entities on apply and confirmation,
+    /// but should keep their positions stable.
+    pub(super) fn skip(&mut self, count: usize) {
+        self.index += count;
+    }
 }
 
 /// Server event to notify client about command confirmation.
-#[derive(Event, Serialize, Deserialize, Clone, Copy, Debug)]
+#[derive(Event, Serialize, Deserialize, Clone, Debug)]
 pub(super) struct CommandConfirmation {
     /// Confirmed command ID.
     pub(super) id: CommandId,
@@ -390,17 +404,24 @@ pub(super) struct CommandConfirmation {
     ///
     /// Needed for some commands to properly generate the undo/redo.
     pub(super) entity: Option<Entity>,
+
+    /// Entities spawned by batched commands in the order they were requested.
+    pub(super) entities: Vec<Entity>,
 }
 
 impl CommandConfirmation {
     /// Creates a new confirmation without an associated entity.
     pub(super) fn new(id: CommandId) -> Self {
-        Self { id, entity: None }
+        Self {
+            id,
+            entity: None,
+            entities: Vec::new(),
+        }
     }
 }
 
 /// ID for an unconfirmed command.
-#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, Serialize, Deserialize)]
 pub(super) struct CommandId(u8);
 
 #[derive(Deref, DerefMut, Default)]
@@ -439,3 +460,15 @@ impl<C: MapEntities> MapEntities for CommandRequest<C> {
         self.command.map_entities(entity_mapper);
     }
 }
+
+impl<C: Mutation> Mutation for CommandRequest<C> {
+    const TARGET: &'static str = C::TARGET;
+
+    fn kind(&self) -> MutationKind {
+        self.command.kind()
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        self.command.entity()
+    }
+}
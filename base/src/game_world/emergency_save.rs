@@ -0,0 +1,259 @@
+use std::{
+    fs, panic,
+    sync::{Arc, Mutex, Once},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use bevy::{
+    prelude::*,
+    tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task},
+};
+use bevy_replicon::prelude::*;
+
+use super::{actor::Actor, encode_world, extract_world, UnsavedChanges, WorldName, WorldSaved};
+use crate::{
+    core::GameState, game_paths::GamePaths, message::Message, save_encryption::SavePassphrase,
+};
+
+/// Writes a recovery save if the game crashes.
+///
+/// A panic hook can't access the world, so unsaved worlds are serialized in memory
+/// and the hook only writes the latest snapshot to disk. The first snapshot is taken
+/// right after the world gets unsaved changes and then refreshed every [`SNAPSHOT_INTERVAL`].
+/// Serialization and encryption run in [`AsyncComputeTaskPool`] to avoid hitches.
+/// The recovery save is a regular world, so it's listed with other worlds.
+/// On the next launch the player is notified about it.
+pub(super) struct EmergencySavePlugin;
+
+impl Plugin for EmergencySavePlugin {
+    fn build(&self, app: &mut App) {
+        let game_paths = app.world().resource::<GamePaths>().clone();
+        let snapshot = EmergencySnapshot::default();
+        *HOOK_STATE.lock().unwrap() = Some(HookState {
+            game_paths,
+            snapshot: snapshot.clone(),
+        });
+
+        // The hook reads the shared state, so it's installed only once even with multiple apps.
+        static HOOK: Once = Once::new();
+        HOOK.call_once(|| {
+            let default_hook = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                default_hook(info);
+                let Some(state) = HOOK_STATE.lock().ok().and_then(|state| state.clone()) else {
+                    return;
+                };
+                let Some(snapshot) = state
+                    .snapshot
+                    .0
+                    .lock()
+                    .ok()
+                    .and_then(|mut data| data.take())
+                else {
+                    return;
+                };
+                match snapshot.write(&state.game_paths) {
+                    Ok(name) => eprintln!("recovery save created as '{name}'"),
+                    Err(e) => eprintln!("unable to create recovery save: {e:#}"),
+                }
+            }));
+        });
+
+        app.insert_resource(snapshot)
+            .add_systems(Startup, Self::read_marker)
+            .add_systems(
+                Update,
+                Self::notify
+                    .run_if(resource_exists::<CrashRecovery>)
+                    .run_if(in_state(GameState::Menu)),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    Self::take_snapshot
+                        .run_if(not(resource_exists::<SnapshotTask>))
+                        .run_if(snapshot_outdated),
+                    Self::finish_snapshot.run_if(resource_exists::<SnapshotTask>),
+                )
+                    .run_if(in_state(GameState::InGame))
+                    .run_if(server_or_singleplayer),
+            )
+            .add_systems(Last, Self::clear_snapshot.run_if(on_event::<WorldSaved>()))
+            .add_systems(OnExit(GameState::InGame), Self::clear_snapshot);
+    }
+}
+
+/// How often unsaved worlds are serialized again for recovery.
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Shared with the panic hook since it's installed only once.
+static HOOK_STATE: Mutex<Option<HookState>> = Mutex::new(None);
+
+impl EmergencySavePlugin {
+    fn read_marker(mut commands: Commands, game_paths: Res<GamePaths>) {
+        if let Some(world_name) = take_crash_marker(&game_paths) {
+            info!("found recovery save '{world_name}' from the last crash");
+            commands.insert_resource(CrashRecovery(world_name));
+        }
+    }
+
+    fn notify(
+        mut commands: Commands,
+        mut message_events: EventWriter<Message>,
+        recovery: Res<CrashRecovery>,
+    ) {
+        let name = &recovery.0;
+        message_events.send(Message(format!(
+            "The game crashed, a recovery save '{name}' was created.\n\
+            You can load it from the world list."
+        )));
+        commands.remove_resource::<CrashRecovery>();
+    }
+
+    /// Extracts the world and starts its serialization in [`AsyncComputeTaskPool`].
+    fn take_snapshot(
+        mut commands: Commands,
+        world: &World,
+        world_name: Res<WorldName>,
+        registry: Res<AppTypeRegistry>,
+        passphrase: Option<Res<SavePassphrase>>,
+        actors: Query<Entity, With<Actor>>,
+    ) {
+        debug!("taking emergency snapshot of '{}'", world_name.0);
+        let scene = extract_world(world, actors.iter());
+        let registry = registry.clone();
+        let passphrase = passphrase.map(|passphrase| passphrase.0.clone());
+        let world_name = world_name.0.clone();
+        let task = AsyncComputeTaskPool::get().spawn(async move {
+            let bytes = encode_world(&scene, &registry, passphrase.as_deref())?;
+            Ok(SnapshotData {
+                world_name,
+                bytes,
+                taken_at: Instant::now(),
+            })
+        });
+
+        commands.insert_resource(SnapshotTask(task));
+    }
+
+    fn finish_snapshot(
+        mut commands: Commands,
+        mut task: ResMut<SnapshotTask>,
+        snapshot: Res<EmergencySnapshot>,
+    ) {
+        let Some(result) = block_on(future::poll_once(&mut task.0)) else {
+            return;
+        };
+
+        commands.remove_resource::<SnapshotTask>();
+        match result {
+            Ok(data) => *snapshot.0.lock().unwrap() = Some(data),
+            Err(e) => error!("unable to take emergency snapshot: {e:#}"),
+        }
+    }
+
+    /// Drops the snapshot since the world on disk is now newer.
+    ///
+    /// Also cancels the snapshot in progress since it could finish after the save.
+    fn clear_snapshot(mut commands: Commands, snapshot: Res<EmergencySnapshot>) {
+        commands.remove_resource::<SnapshotTask>();
+        if snapshot.0.lock().unwrap().take().is_some() {
+            debug!("clearing emergency snapshot");
+        }
+    }
+}
+
+/// Returns `true` if the world has unsaved changes that aren't in the snapshot yet.
+fn snapshot_outdated(unsaved: Res<UnsavedChanges>, snapshot: Res<EmergencySnapshot>) -> bool {
+    if !unsaved.0 {
+        return false;
+    }
+
+    snapshot
+        .0
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map_or(true, |data| data.taken_at.elapsed() >= SNAPSHOT_INTERVAL)
+}
+
+/// Returns the world name from the crash marker and removes it.
+fn take_crash_marker(game_paths: &GamePaths) -> Option<String> {
+    let world_name = fs::read_to_string(&game_paths.crash_marker).ok()?;
+    if let Err(e) = fs::remove_file(&game_paths.crash_marker) {
+        error!("unable to remove {:?}: {e}", game_paths.crash_marker);
+    }
+
+    Some(world_name)
+}
+
+#[derive(Clone)]
+struct HookState {
+    game_paths: GamePaths,
+    snapshot: EmergencySnapshot,
+}
+
+/// Latest serialized world shared with the panic hook.
+#[derive(Clone, Default, Resource)]
+struct EmergencySnapshot(Arc<Mutex<Option<SnapshotData>>>);
+
+/// Serialization started by [`EmergencySavePlugin::take_snapshot`].
+#[derive(Resource)]
+struct SnapshotTask(Task<Result<SnapshotData>>);
+
+struct SnapshotData {
+    world_name: String,
+    bytes: Vec<u8>,
+    taken_at: Instant,
+}
+
+impl SnapshotData {
+    /// Writes the world as a separate save with a crash marker.
+    ///
+    /// Returns name of the written world.
+    fn write(&self, game_paths: &GamePaths) -> Result<String> {
+        let name = format!("{} (recovered)", self.world_name);
+        let world_path = game_paths.world_path(&name);
+        fs::write(&world_path, &self.bytes)
+            .with_context(|| format!("unable to write {world_path:?}"))?;
+        fs::write(&game_paths.crash_marker, &name)
+            .with_context(|| format!("unable to write {:?}", game_paths.crash_marker))?;
+
+        Ok(name)
+    }
+}
+
+/// Name of the recovery save created on the last crash.
+///
+/// Removed after showing the notification.
+#[derive(Resource)]
+struct CrashRecovery(String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_world;
+
+    #[test]
+    fn recovery_save() {
+        let game_paths = test_world::temporary_game_paths();
+
+        let snapshot = SnapshotData {
+            world_name: "Town".to_string(),
+            bytes: b"world".to_vec(),
+            taken_at: Instant::now(),
+        };
+        let name = snapshot.write(&game_paths).unwrap();
+        assert_eq!(name, "Town (recovered)");
+        assert!(game_paths.get_world_names().unwrap().contains(&name));
+        assert_eq!(
+            take_crash_marker(&game_paths).as_deref(),
+            Some(name.as_str())
+        );
+        assert!(
+            take_crash_marker(&game_paths).is_none(),
+            "marker should be removed after reading"
+        );
+    }
+}
@@ -1,10 +1,16 @@
 pub mod building;
 pub mod editor;
+pub mod memories;
+pub mod statistics;
+pub mod utilities;
 
 use std::io::Cursor;
 
 use bevy::{
-    ecs::entity::{EntityMapper, MapEntities},
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        system::SystemParam,
+    },
     prelude::*,
     reflect::serde::{ReflectDeserializer, ReflectSerializer},
     utils::HashMap,
@@ -19,43 +25,66 @@ use strum::{Display, EnumIter};
 
 use super::{
     actor::{Actor, ActorBundle, ReflectActorBundle, SelectedActor},
+    mutation_log::{Mutation, MutationKind, MutationRecorder},
     navigation::NavigationBundle,
+    scenario::Scenario,
     WorldState,
 };
-use crate::{component_commands::ComponentCommandsExt, core::GameState};
+use crate::{
+    component_commands::ComponentCommandsExt, core::GameState, network::validation::ClientViolation,
+};
 use building::BuildingPlugin;
 use editor::EditorPlugin;
+use memories::MemoriesPlugin;
+use statistics::StatisticsPlugin;
+use utilities::UtilitiesPlugin;
 
 pub struct FamilyPlugin;
 
 impl Plugin for FamilyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((EditorPlugin, BuildingPlugin))
-            .add_sub_state::<FamilyMode>()
-            .enable_state_scoped_entities::<FamilyMode>()
-            .register_type::<Family>()
-            .register_type::<Budget>()
-            .replicate::<Budget>()
-            .replicate_group::<(Family, Name)>()
-            .add_client_event_with(
-                ChannelKind::Unordered,
-                serialize_family_spawn,
-                deserialize_family_spawn,
+        app.add_plugins((
+            EditorPlugin,
+            BuildingPlugin,
+            MemoriesPlugin,
+            StatisticsPlugin,
+            UtilitiesPlugin,
+        ))
+        .add_sub_state::<FamilyMode>()
+        .enable_state_scoped_entities::<FamilyMode>()
+        .register_type::<Family>()
+        .register_type::<Budget>()
+        .replicate::<Budget>()
+        .replicate::<FamilyOwner>()
+        .replicate_group::<(Family, Name)>()
+        .add_client_event_with(
+            ChannelKind::Unordered,
+            serialize_family_spawn,
+            deserialize_family_spawn,
+        )
+        .add_mapped_client_event::<FamilyDelete>(ChannelKind::Unordered)
+        .add_mapped_client_event::<FamilyClaim>(ChannelKind::Unordered)
+        .add_event::<FamilySwitch>()
+        .add_mapped_server_event::<SelectedFamilyCreated>(ChannelKind::Unordered)
+        .add_systems(OnEnter(WorldState::Family), Self::select)
+        .add_systems(OnExit(WorldState::Family), Self::deselect)
+        .add_systems(
+            PreUpdate,
+            (
+                Self::update_members,
+                Self::init,
+                (Self::create, Self::delete, Self::claim).run_if(server_or_singleplayer),
+                Self::release.run_if(server_running),
             )
-            .add_mapped_client_event::<FamilyDelete>(ChannelKind::Unordered)
-            .add_mapped_server_event::<SelectedFamilyCreated>(ChannelKind::Unordered)
-            .add_systems(OnEnter(WorldState::Family), Self::select)
-            .add_systems(OnExit(WorldState::Family), Self::deselect)
-            .add_systems(
-                PreUpdate,
-                (
-                    Self::update_members,
-                    Self::init,
-                    (Self::create, Self::delete).run_if(server_or_singleplayer),
-                )
-                    .after(ClientSet::Receive)
-                    .run_if(in_state(GameState::InGame)),
-            );
+                .after(ClientSet::Receive)
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            Update,
+            Self::switch
+                .run_if(on_event::<FamilySwitch>())
+                .run_if(in_state(WorldState::Family)),
+        );
     }
 }
 
@@ -111,11 +140,21 @@ impl FamilyPlugin {
         mut commands: Commands,
         mut created_events: EventWriter<ToClients<SelectedFamilyCreated>>,
         mut create_events: ResMut<Events<FromClient<FamilyCreate>>>,
+        mut recorder: MutationRecorder,
+        scenarios: Query<&Scenario>,
     ) {
         for FromClient { client_id, event } in create_events.drain() {
             info!("creating new family");
+            recorder.record_without_payload(client_id, &event);
+            let budget = scenarios
+                .get_single()
+                .map(|scenario| Budget(scenario.budget()))
+                .unwrap_or(event.scene.budget);
             let family_entity = commands
-                .spawn(FamilyBundle::new(event.scene.name, event.scene.budget))
+                .spawn((
+                    FamilyBundle::new(event.scene.name, budget),
+                    FamilyOwner(client_id),
+                ))
                 .id();
             for actor in event.scene.actors {
                 commands.entity(event.city_entity).with_children(|parent| {
@@ -142,12 +181,26 @@ impl FamilyPlugin {
     fn delete(
         mut commands: Commands,
         mut delete_events: EventReader<FromClient<FamilyDelete>>,
-        families: Query<&mut FamilyMembers>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        control: FamilyControl,
+        families: Query<&FamilyMembers>,
     ) {
-        for family_entity in delete_events.read().map(|event| event.event.0) {
+        for &FromClient { client_id, event } in delete_events.read() {
+            let family_entity = event.0;
+            if !control.family_allowed(client_id, family_entity) {
+                error!("`{client_id:?}` tries to delete not owned family `{family_entity}`");
+                violation_events.send(ClientViolation {
+                    client_id,
+                    reason: "deleting a family owned by another player".into(),
+                });
+                continue;
+            }
+
             match families.get(family_entity) {
                 Ok(members) => {
                     info!("deleting family `{family_entity}`");
+                    recorder.record(client_id, &event);
                     commands.entity(family_entity).despawn();
                     for &entity in &members.0 {
                         commands.entity(entity).despawn_recursive();
@@ -158,18 +211,84 @@ impl FamilyPlugin {
         }
     }
 
+    fn claim(
+        mut commands: Commands,
+        mut claim_events: EventReader<FromClient<FamilyClaim>>,
+        mut recorder: MutationRecorder,
+        families: Query<Option<&FamilyOwner>, With<Family>>,
+    ) {
+        for FromClient { client_id, event } in claim_events.read() {
+            match families.get(event.0) {
+                Ok(Some(owner)) if owner.0 != *client_id => {
+                    error!(
+                        "`{client_id:?}` tried to claim family `{}` owned by `{:?}`",
+                        event.0, owner.0
+                    );
+                }
+                Ok(_) => {
+                    info!("assigning family `{}` to `{client_id:?}`", event.0);
+                    recorder.record(*client_id, event);
+                    commands.entity(event.0).insert(FamilyOwner(*client_id));
+                }
+                Err(e) => error!("received an invalid family to claim: {e}"),
+            }
+        }
+    }
+
+    /// Frees families of disconnected clients.
+    fn release(
+        mut commands: Commands,
+        mut server_events: EventReader<ServerEvent>,
+        families: Query<(Entity, &FamilyOwner)>,
+    ) {
+        for event in server_events.read() {
+            if let ServerEvent::ClientDisconnected { client_id, .. } = *event {
+                for (family_entity, _) in families.iter().filter(|(_, owner)| owner.0 == client_id)
+                {
+                    info!("releasing family `{family_entity}` from `{client_id:?}`");
+                    commands.entity(family_entity).remove::<FamilyOwner>();
+                }
+            }
+        }
+    }
+
+    /// Changes the controlled family without leaving [`WorldState::Family`].
+    fn switch(
+        mut commands: Commands,
+        mut switch_events: EventReader<FamilySwitch>,
+        families: Query<&FamilyMembers>,
+        selected_families: Query<Entity, With<SelectedFamily>>,
+    ) {
+        let Some(&FamilySwitch(family_entity)) = switch_events.read().last() else {
+            return;
+        };
+        let Ok(members) = families.get(family_entity) else {
+            error!("unable to switch to invalid family `{family_entity}`");
+            return;
+        };
+
+        for entity in &selected_families {
+            commands.entity(entity).remove::<SelectedFamily>();
+        }
+
+        info!("switching to family `{family_entity}`");
+        let actor_entity = *members
+            .first()
+            .expect("family always have at least one member");
+        commands.entity(family_entity).insert(SelectedFamily);
+        commands.entity(actor_entity).insert(SelectedActor);
+    }
+
     pub fn select(mut commands: Commands, actors: Query<&Actor, With<SelectedActor>>) {
         let actor = actors.single();
         info!("selecting `{}`", actor.family_entity);
         commands.entity(actor.family_entity).insert(SelectedFamily);
     }
 
-    fn deselect(mut commands: Commands, families: Query<&Actor, With<SelectedActor>>) {
-        if let Ok(actor) = families.get_single() {
-            info!("deselecting `{}`", actor.family_entity);
-            commands
-                .entity(actor.family_entity)
-                .remove::<SelectedFamily>();
+    fn deselect(mut commands: Commands, families: Query<Entity, With<SelectedFamily>>) {
+        if let Ok(family_entity) = families.get_single() {
+            info!("deselecting `{family_entity}`");
+            commands.entity(family_entity).remove::<SelectedFamily>();
         }
     }
 }
@@ -280,10 +399,72 @@ pub struct Family;
 #[derive(Component)]
 pub struct SelectedFamily;
 
+/// Client that controls the family.
+///
+/// A client can own multiple families.
+/// Not saved since client IDs are assigned per connection.
+#[derive(Clone, Component, Copy, Deref, Deserialize, Serialize)]
+pub struct FamilyOwner(ClientId);
+
+impl FamilyOwner {
+    /// Returns `true` if the family is controlled by this game instance.
+    ///
+    /// Server and singleplayer act as [`ClientId::SERVER`].
+    pub fn is_local(&self, client: &RepliconClient) -> bool {
+        self.0 == client.id().unwrap_or(ClientId::SERVER)
+    }
+}
+
+/// Checks if clients can control families and their actors.
+#[derive(SystemParam)]
+pub(crate) struct FamilyControl<'w, 's> {
+    actors: Query<'w, 's, &'static Actor>,
+    families: Query<'w, 's, Option<&'static FamilyOwner>, With<Family>>,
+}
+
+impl FamilyControl<'_, '_> {
+    /// Returns `true` if the client owns the family.
+    ///
+    /// Families without an owner can be controlled only by the host.
+    pub(crate) fn family_allowed(&self, client_id: ClientId, family_entity: Entity) -> bool {
+        match self.families.get(family_entity) {
+            Ok(Some(owner)) => owner.0 == client_id,
+            Ok(None) => client_id == ClientId::SERVER,
+            Err(_) => false,
+        }
+    }
+
+    /// Like [`Self::family_allowed`], but for the family of the actor.
+    pub(crate) fn actor_allowed(&self, client_id: ClientId, actor_entity: Entity) -> bool {
+        self.actors
+            .get(actor_entity)
+            .is_ok_and(|actor| self.family_allowed(client_id, actor.family_entity))
+    }
+}
+
 #[derive(Clone, Component, Copy, Default, Debug, Deserialize, Reflect, Serialize, Deref)]
 #[reflect(Component)]
 pub struct Budget(u32);
 
+impl Budget {
+    pub(crate) fn earn(&mut self, amount: u32) {
+        self.0 = self.0.saturating_add(amount);
+    }
+
+    /// Subtracts the amount if the budget is sufficient.
+    ///
+    /// Returns `false` if there is not enough money.
+    pub(crate) fn spend(&mut self, amount: u32) -> bool {
+        match self.0.checked_sub(amount) {
+            Some(remaining) => {
+                self.0 = remaining;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
 /// Contains the entities of all the actors that belong to the family.
 ///
 /// Automatically created and updated based on [`ActorFamily`].
@@ -303,6 +484,18 @@ impl MapEntities for FamilyCreate {
     }
 }
 
+impl Mutation for FamilyCreate {
+    const TARGET: &'static str = "family";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Spawn
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        None
+    }
+}
+
 #[derive(Default, Resource)]
 pub struct FamilyScene {
     pub name: String,
@@ -329,6 +522,44 @@ impl MapEntities for FamilyDelete {
     }
 }
 
+impl Mutation for FamilyDelete {
+    const TARGET: &'static str = "family";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Delete
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        Some(self.0)
+    }
+}
+
+/// Requests ownership of a family that has no [`FamilyOwner`].
+#[derive(Clone, Copy, Deserialize, Event, Serialize)]
+pub struct FamilyClaim(pub Entity);
+
+impl MapEntities for FamilyClaim {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
+
+impl Mutation for FamilyClaim {
+    const TARGET: &'static str = "family";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Edit
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        Some(self.0)
+    }
+}
+
+/// Selects another family while in [`WorldState::Family`].
+#[derive(Clone, Copy, Event)]
+pub struct FamilySwitch(pub Entity);
+
 /// An event from server which indicates spawn confirmation for the selected family.
 #[derive(Deserialize, Event, Serialize)]
 pub(super) struct SelectedFamilyCreated(pub(super) Entity);
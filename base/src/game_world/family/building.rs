@@ -1,7 +1,16 @@
+pub mod ceiling;
+pub mod decoration;
+pub mod preset;
+pub mod room;
 pub mod wall;
 
 use bevy::prelude::*;
 use strum::{Display, EnumIter};
+
+use ceiling::CeilingPlugin;
+use decoration::DecorationPlugin;
+use preset::PresetPlugin;
+use room::RoomPlugin;
 use wall::WallPlugin;
 
 use super::FamilyMode;
@@ -12,7 +21,13 @@ impl Plugin for BuildingPlugin {
     fn build(&self, app: &mut App) {
         app.add_sub_state::<BuildingMode>()
             .enable_state_scoped_entities::<BuildingMode>()
-            .add_plugins(WallPlugin);
+            .add_plugins((
+                CeilingPlugin,
+                DecorationPlugin,
+                PresetPlugin,
+                RoomPlugin,
+                WallPlugin,
+            ));
     }
 }
 
@@ -24,6 +39,8 @@ pub enum BuildingMode {
     #[default]
     Objects,
     Walls,
+    Decorations,
+    Presets,
 }
 
 impl BuildingMode {
@@ -31,6 +48,8 @@ impl BuildingMode {
         match self {
             Self::Objects => "💺",
             Self::Walls => "🔰",
+            Self::Decorations => "🎄",
+            Self::Presets => "🛋",
         }
     }
 }
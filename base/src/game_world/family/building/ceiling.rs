@@ -0,0 +1,176 @@
+use bevy::{prelude::*, render::view::RenderLayers};
+
+use super::{
+    room::{RoomSet, Rooms},
+    wall::wall_mesh::HEIGHT,
+};
+use crate::{
+    core::GameState,
+    game_world::{player_camera::PlayerCamera, spline::dynamic_mesh::DynamicMesh, WorldState},
+    math::{polygon::Polygon, triangulator::Triangulator},
+    settings::{Settings, WallView},
+};
+
+/// Covers rooms enclosed by walls with ceilings.
+///
+/// Ceilings are generated locally from rooms and hidden from the player camera while it's above them.
+/// Hidden ceilings still cast shadows, so the sun doesn't light up interiors.
+pub(super) struct CeilingPlugin;
+
+impl Plugin for CeilingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CeilingMaterial>().add_systems(
+            PostUpdate,
+            (
+                Self::regenerate.run_if(resource_changed::<Rooms>),
+                Self::update_visibility,
+            )
+                .chain()
+                .after(RoomSet)
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+impl CeilingPlugin {
+    fn regenerate(
+        mut commands: Commands,
+        mut meshes: ResMut<Assets<Mesh>>,
+        ceiling_material: Res<CeilingMaterial>,
+        rooms: Res<Rooms>,
+        ceilings: Query<(Entity, &Parent, &Ceiling)>,
+    ) {
+        // Rooms are also rebuilt on aperture changes, which don't affect ceilings.
+        let unchanged = ceilings.iter().count() == rooms.iter().count()
+            && rooms.iter().all(|room| {
+                ceilings.iter().any(|(_, parent, ceiling)| {
+                    **parent == room.city_entity && ceiling.0 .0 == room.polygon.0
+                })
+            });
+        if unchanged {
+            return;
+        }
+
+        for (entity, ..) in &ceilings {
+            commands.entity(entity).despawn();
+        }
+
+        debug!("generating {} ceilings", rooms.iter().count());
+        let mut triangulator = Triangulator::default();
+        for room in rooms.iter() {
+            let mut dyn_mesh = DynamicMesh::default();
+            generate(&mut dyn_mesh, &room.polygon, &mut triangulator);
+            let mut mesh = DynamicMesh::create_empty();
+            dyn_mesh.apply(&mut mesh);
+
+            commands.entity(room.city_entity).with_children(|parent| {
+                parent.spawn((
+                    Name::new("Ceiling"),
+                    Ceiling(room.polygon.clone()),
+                    RenderLayers::layer(CEILING_LAYER),
+                    PbrBundle {
+                        mesh: meshes.add(mesh),
+                        material: ceiling_material.0.clone(),
+                        ..Default::default()
+                    },
+                ));
+            });
+        }
+    }
+
+    fn update_visibility(
+        settings: Res<Settings>,
+        world_state: Res<State<WorldState>>,
+        cameras: Query<&GlobalTransform, With<PlayerCamera>>,
+        mut ceilings: Query<&mut RenderLayers, With<Ceiling>>,
+    ) {
+        let Ok(camera_transform) = cameras.get_single() else {
+            return;
+        };
+
+        // Let the player look into rooms from above or through lowered walls.
+        let walls_lowered =
+            **world_state == WorldState::Family && settings.gameplay.wall_view != WallView::Up;
+        let layers = if walls_lowered || camera_transform.translation().y > HEIGHT {
+            RenderLayers::layer(CEILING_LAYER)
+        } else {
+            RenderLayers::default().with(CEILING_LAYER)
+        };
+        for mut ceiling_layers in &mut ceilings {
+            ceiling_layers.set_if_neq(layers.clone());
+        }
+    }
+}
+
+/// Render layer for ceilings.
+///
+/// The player camera renders only the default layer, while lights that should be blocked
+/// by ceilings need to include this layer.
+pub(crate) const CEILING_LAYER: usize = 1;
+
+/// Generates a downward-facing ceiling at the top of walls.
+fn generate(mesh: &mut DynamicMesh, polygon: &Polygon, triangulator: &mut Triangulator) {
+    let flat: Vec<_> = polygon
+        .iter()
+        .map(|point| [point.x, point.y, 0.0])
+        .collect();
+    for triangle in triangulator.triangulate(&flat).chunks_exact(3) {
+        let [a, b, c] =
+            [triangle[0], triangle[1], triangle[2]].map(|index| polygon[index as usize]);
+
+        // Counterclockwise in city coordinates faces downward.
+        let vertices = if (b - a).perp_dot(c - a) > 0.0 {
+            [a, b, c]
+        } else {
+            [a, c, b]
+        };
+        for vertex in vertices {
+            let index = mesh.vertices_count();
+            mesh.indices.push(index);
+            mesh.positions.push([vertex.x, HEIGHT, vertex.y]);
+            mesh.uvs.push(vertex.into());
+            mesh.normals.push([0.0, -1.0, 0.0]);
+        }
+    }
+}
+
+#[derive(Resource)]
+struct CeilingMaterial(Handle<StandardMaterial>);
+
+impl FromWorld for CeilingMaterial {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        Self(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.9, 0.9, 0.88),
+            perceptual_roughness: 0.9,
+            ..Default::default()
+        }))
+    }
+}
+
+/// Room area covered by the ceiling in city coordinates.
+#[derive(Component, Deref)]
+pub struct Ceiling(Polygon);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mesh_faces_down() {
+        let polygon = Polygon(vec![
+            Vec2::ZERO,
+            Vec2::new(4.0, 0.0),
+            Vec2::new(4.0, 4.0),
+            Vec2::new(0.0, 4.0),
+        ]);
+        let mut mesh = DynamicMesh::default();
+        generate(&mut mesh, &polygon, &mut Triangulator::default());
+        assert_eq!(mesh.indices.len(), 6);
+        for triangle in mesh.positions.chunks_exact(3) {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(Vec3::from);
+            let normal = (b - a).cross(c - a);
+            assert!(normal.y < 0.0, "triangle should face down");
+        }
+    }
+}
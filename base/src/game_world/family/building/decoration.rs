@@ -0,0 +1,433 @@
+use bevy::{asset::AssetPath, ecs::entity::MapEntities, prelude::*};
+use bevy_replicon::prelude::*;
+use leafwing_input_manager::common_conditions::action_just_pressed;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    wall::{wall_mesh::HEIGHT, Wall},
+    BuildingMode,
+};
+use crate::{
+    asset::info::{
+        object_info::{ObjectInfo, ObjectTag},
+        scenario_info::ScenarioFeature,
+    },
+    core::GameState,
+    game_world::{
+        city::lot::{permissions::BuildPermissions, LotFamily, LotObjects, LotVertices},
+        clock::{GameClock, Season},
+        family::{Budget, FamilyControl, SelectedFamily},
+        hover::Hovered,
+        mutation_log::{Mutation, MutationKind, MutationRecorder},
+        object::{ObjectBundle, ObjectTags},
+        scenario::ScenarioLocks,
+        spline::SplineSegment,
+    },
+    math::segment::Segment,
+    network::validation::ClientViolation,
+    settings::Action,
+};
+
+/// Places decorations along wall edges in batches and removes seasonal objects after their season.
+pub(super) struct DecorationPlugin;
+
+impl Plugin for DecorationPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<EdgeDecoration>()
+            .add_mapped_client_event::<DecorationPlace>(ChannelKind::Ordered)
+            .add_mapped_client_event::<DecorationCleanup>(ChannelKind::Ordered)
+            .add_event::<CleanupOffer>()
+            .add_systems(
+                PreUpdate,
+                (Self::place, Self::cleanup)
+                    .after(ServerSet::Receive)
+                    .run_if(server_or_singleplayer),
+            )
+            .add_systems(
+                Update,
+                (
+                    Self::pick
+                        .run_if(action_just_pressed(Action::Confirm))
+                        .run_if(resource_exists::<SelectedDecoration>)
+                        .run_if(in_state(BuildingMode::Decorations)),
+                    Self::offer_cleanup,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(OnExit(BuildingMode::Decorations), Self::deselect);
+    }
+}
+
+impl DecorationPlugin {
+    fn pick(
+        mut place_events: EventWriter<DecorationPlace>,
+        asset_server: Res<AssetServer>,
+        selected: Res<SelectedDecoration>,
+        walls: Query<Entity, (With<Wall>, With<Hovered>)>,
+    ) {
+        let Ok(wall_entity) = walls.get_single() else {
+            return;
+        };
+
+        let info_path = asset_server
+            .get_path(selected.0)
+            .expect("info should always come from file")
+            .into_owned();
+        info!("decorating wall `{wall_entity}` with '{info_path}'");
+        place_events.send(DecorationPlace {
+            wall_entity,
+            info_path,
+        });
+    }
+
+    fn deselect(mut commands: Commands) {
+        commands.remove_resource::<SelectedDecoration>();
+    }
+
+    fn place(
+        mut commands: Commands,
+        mut place_events: EventReader<FromClient<DecorationPlace>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        asset_server: Res<AssetServer>,
+        objects_info: Res<Assets<ObjectInfo>>,
+        locks: ScenarioLocks,
+        permissions: BuildPermissions,
+        clocks: Query<&GameClock>,
+        walls: Query<(&Parent, &SplineSegment), With<Wall>>,
+        lots: Query<(&Parent, &LotVertices, &LotFamily)>,
+        mut families: Query<&mut Budget>,
+    ) {
+        for FromClient { client_id, event } in place_events.read().cloned() {
+            if let Err(violation) = locks.check(ScenarioFeature::Building, client_id) {
+                violation_events.send(violation);
+                continue;
+            }
+
+            let Ok((parent, segment)) = walls.get(event.wall_entity) else {
+                error!(
+                    "`{client_id:?}` tries to decorate non-existing wall `{:?}`",
+                    event.wall_entity
+                );
+                continue;
+            };
+            let Some((info, decoration)) = asset_server
+                .get_handle(&event.info_path)
+                .and_then(|handle| objects_info.get(&handle))
+                .and_then(|info| {
+                    EdgeDecoration::from_info(info).map(|decoration| (info, decoration))
+                })
+            else {
+                error!(
+                    "`{client_id:?}` tries to place '{}' that is not a decoration",
+                    event.info_path
+                );
+                continue;
+            };
+            if let Some(season) = info.season() {
+                if clocks.get_single().ok().map(GameClock::season) != Some(season) {
+                    error!(
+                        "`{client_id:?}` tries to place '{}' outside of `{season}`",
+                        event.info_path
+                    );
+                    violation_events.send(ClientViolation {
+                        client_id,
+                        reason: "placing decorations out of season".into(),
+                    });
+                    continue;
+                }
+            }
+            if !segment
+                .points()
+                .into_iter()
+                .all(|point| permissions.allowed(client_id, **parent, point))
+            {
+                error!("`{client_id:?}` has no build rights to decorate wall");
+                violation_events.send(ClientViolation {
+                    client_id,
+                    reason: "placing decorations without build rights".into(),
+                });
+                continue;
+            }
+
+            let points = decoration.points(**segment);
+
+            // Like regular objects, decorations are charged only on family lots.
+            let price = info.price.saturating_mul(points.len() as u32);
+            let family = lots
+                .iter()
+                .find(|(lot_parent, vertices, _)| {
+                    ***lot_parent == **parent && vertices.contains_point(segment.start)
+                })
+                .and_then(|(.., lot_family)| families.get_mut(lot_family.0).ok());
+            if let Some(mut budget) = family {
+                if !budget.spend(price) {
+                    error!("`{client_id:?}` can't afford decorations for {price}");
+                    continue;
+                }
+            }
+
+            info!(
+                "`{client_id:?}` places {} '{}' along wall `{:?}`",
+                points.len(),
+                event.info_path,
+                event.wall_entity
+            );
+            recorder.record(client_id, &event);
+            let disp = segment.displacement();
+            let rotation = Quat::from_rotation_y(-disp.y.atan2(disp.x));
+            commands.entity(**parent).with_children(|parent| {
+                for point in points {
+                    let transform =
+                        Transform::from_xyz(point.x, HEIGHT, point.y).with_rotation(rotation);
+                    parent.spawn(ObjectBundle::new(event.info_path.clone(), transform));
+                }
+            });
+        }
+    }
+
+    /// Offers to remove seasonal objects from lots of the selected family when their season ends.
+    fn offer_cleanup(
+        mut offer_events: EventWriter<CleanupOffer>,
+        mut previous_season: Local<Option<Season>>,
+        clocks: Query<&GameClock>,
+        families: Query<Entity, With<SelectedFamily>>,
+        lots: Query<(&LotObjects, &LotFamily)>,
+        objects: Query<&ObjectTags>,
+    ) {
+        let Ok(clock) = clocks.get_single() else {
+            return;
+        };
+        let season = clock.season();
+        let Some(previous) = previous_season.replace(season) else {
+            return;
+        };
+        if previous == season {
+            return;
+        }
+        let Ok(family_entity) = families.get_single() else {
+            return;
+        };
+
+        let has_leftovers = lots
+            .iter()
+            .filter(|(_, lot_family)| lot_family.0 == family_entity)
+            .flat_map(|(lot_objects, _)| objects.iter_many(lot_objects.iter()))
+            .any(|tags| tags.contains(&ObjectTag::Season(previous)));
+        if has_leftovers {
+            info!("offering cleanup of `{previous}` objects for `{family_entity}`");
+            offer_events.send(CleanupOffer {
+                family_entity,
+                season: previous,
+            });
+        }
+    }
+
+    fn cleanup(
+        mut commands: Commands,
+        mut cleanup_events: EventReader<FromClient<DecorationCleanup>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        control: FamilyControl,
+        lots: Query<(&LotObjects, &LotFamily)>,
+        objects: Query<(Entity, &ObjectTags)>,
+    ) {
+        for FromClient { client_id, event } in cleanup_events.read().copied() {
+            if !control.family_allowed(client_id, event.family_entity) {
+                error!(
+                    "`{client_id:?}` tries to clean up lots of not owned family `{}`",
+                    event.family_entity
+                );
+                violation_events.send(ClientViolation {
+                    client_id,
+                    reason: "cleaning up lots of another family".into(),
+                });
+                continue;
+            }
+
+            info!(
+                "`{client_id:?}` removes `{}` objects of `{:?}`",
+                event.season, event.family_entity
+            );
+            recorder.record(client_id, &event);
+            let tag = ObjectTag::Season(event.season);
+            for (lot_objects, _) in lots
+                .iter()
+                .filter(|(_, lot_family)| lot_family.0 == event.family_entity)
+            {
+                for (entity, tags) in objects.iter_many(lot_objects.iter()) {
+                    if tags.contains(&tag) {
+                        commands.entity(entity).despawn_recursive();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Object that can be placed along wall edges in a batch.
+///
+/// Usually defined in object metadata.
+#[derive(Clone, Component, Copy, Reflect)]
+#[reflect(Component)]
+pub struct EdgeDecoration {
+    /// Distance between placed objects.
+    spacing: f32,
+}
+
+impl EdgeDecoration {
+    /// Minimum distance between placed objects.
+    ///
+    /// Limits the number of objects spawned for a single wall.
+    const MIN_SPACING: f32 = 0.1;
+
+    /// Returns the decoration component from object metadata if present and valid.
+    pub fn from_info(info: &ObjectInfo) -> Option<Self> {
+        info.components
+            .iter()
+            .find(|component| component.represents::<Self>())
+            .and_then(|component| Self::from_reflect(&**component))
+            .filter(|decoration| decoration.is_valid())
+    }
+
+    /// Returns `false` for spacing that is not finite or too small.
+    fn is_valid(self) -> bool {
+        self.spacing.is_finite() && self.spacing >= Self::MIN_SPACING
+    }
+
+    /// Returns evenly distributed points along the segment, excluding its ends.
+    fn points(self, segment: Segment) -> Vec<Vec2> {
+        let length = segment.displacement().length();
+        let count = (length / self.spacing) as usize;
+        (1..=count)
+            .map(|index| index as f32 * self.spacing)
+            .filter(|&distance| distance < length)
+            .map(|distance| segment.start + segment.displacement() * distance / length)
+            .collect()
+    }
+}
+
+impl Default for EdgeDecoration {
+    fn default() -> Self {
+        Self { spacing: 0.5 }
+    }
+}
+
+/// Decoration to place with [`BuildingMode::Decorations`].
+///
+/// Picking won't happen until this resource is inserted.
+#[derive(Resource)]
+pub struct SelectedDecoration(pub AssetId<ObjectInfo>);
+
+/// Places decorations along the wall top edge.
+#[derive(Clone, Deserialize, Event, Serialize)]
+struct DecorationPlace {
+    wall_entity: Entity,
+    info_path: AssetPath<'static>,
+}
+
+impl MapEntities for DecorationPlace {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.wall_entity = entity_mapper.map_entity(self.wall_entity);
+    }
+}
+
+impl Mutation for DecorationPlace {
+    const TARGET: &'static str = "decoration";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Spawn
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        Some(self.wall_entity)
+    }
+}
+
+/// Removes objects of a season from family lots.
+#[derive(Clone, Copy, Deserialize, Event, Serialize)]
+pub struct DecorationCleanup {
+    pub family_entity: Entity,
+    pub season: Season,
+}
+
+impl MapEntities for DecorationCleanup {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.family_entity = entity_mapper.map_entity(self.family_entity);
+    }
+}
+
+impl Mutation for DecorationCleanup {
+    const TARGET: &'static str = "decoration";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Delete
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        Some(self.family_entity)
+    }
+}
+
+/// Emitted locally when the season of objects on lots of the selected family ends.
+#[derive(Clone, Copy, Event)]
+pub struct CleanupOffer {
+    pub family_entity: Entity,
+    pub season: Season,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_world::TestWorld;
+
+    #[test]
+    fn points() {
+        let decoration = EdgeDecoration { spacing: 1.0 };
+        let segment = Segment::new(Vec2::ZERO, Vec2::X * 3.5);
+        assert_eq!(
+            decoration.points(segment),
+            [Vec2::X, Vec2::X * 2.0, Vec2::X * 3.0]
+        );
+
+        let segment = Segment::new(Vec2::ZERO, Vec2::Y * 2.0);
+        assert_eq!(
+            decoration.points(segment),
+            [Vec2::Y],
+            "points shouldn't be placed on the segment ends"
+        );
+    }
+
+    #[test]
+    fn spacing_validation() {
+        assert!(EdgeDecoration::default().is_valid());
+        for spacing in [0.0, -1.0, 0.01, f32::NAN, f32::INFINITY] {
+            assert!(
+                !EdgeDecoration { spacing }.is_valid(),
+                "spacing {spacing} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn foreign_cleanup() {
+        let mut test_world = TestWorld::builder().build();
+        let city_entity = test_world.spawn_city("City");
+        let family_entity = test_world.spawn_family(city_entity, "Family", 1);
+
+        let client_id = ClientId::new(1);
+        test_world.world_mut().send_event(FromClient {
+            client_id,
+            event: DecorationCleanup {
+                family_entity,
+                season: Season::Winter,
+            },
+        });
+        test_world.tick();
+
+        let violations = test_world.world().resource::<Events<ClientViolation>>();
+        assert!(violations
+            .iter_current_update_events()
+            .any(|violation| violation.client_id == client_id));
+    }
+}
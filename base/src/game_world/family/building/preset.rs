@@ -0,0 +1,398 @@
+use avian3d::prelude::*;
+use bevy::{
+    asset::AssetPath,
+    color::palettes::css::{RED, WHITE},
+    ecs::entity::MapEntities,
+    math::Vec3Swizzles,
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use leafwing_input_manager::common_conditions::action_just_pressed;
+use serde::{Deserialize, Serialize};
+
+use super::BuildingMode;
+use crate::{
+    asset::info::{
+        object_info::ObjectInfo, preset_info::PresetInfo, scenario_info::ScenarioFeature,
+    },
+    game_world::{
+        city::{
+            self,
+            lot::{permissions::BuildPermissions, LotFamily, LotVertices},
+            ActiveCity, GroundSampler,
+        },
+        family::Budget,
+        mutation_log::{Mutation, MutationKind, MutationRecorder},
+        object::{placing_object::ground_fit::PendingGroundCheck, ObjectBundle},
+        player_camera::CameraCaster,
+        scenario::ScenarioLocks,
+        Layer,
+    },
+    network::validation::ClientViolation,
+    settings::Action,
+};
+
+/// Places furniture sets from [`PresetInfo`] into a rectangular area.
+pub(super) struct PresetPlugin;
+
+impl Plugin for PresetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_mapped_client_event::<PresetStamp>(ChannelKind::Ordered)
+            .add_systems(
+                PreUpdate,
+                Self::stamp
+                    .after(ServerSet::Receive)
+                    .run_if(server_or_singleplayer),
+            )
+            .add_systems(
+                Update,
+                (
+                    Self::start
+                        .run_if(action_just_pressed(Action::Confirm))
+                        .run_if(not(any_with_component::<PresetArea>)),
+                    Self::update_end,
+                    Self::confirm.run_if(action_just_pressed(Action::Confirm)),
+                    Self::cancel.run_if(action_just_pressed(Action::Cancel)),
+                )
+                    .run_if(resource_exists::<SelectedPreset>)
+                    .run_if(in_state(BuildingMode::Presets)),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::draw.run_if(in_state(BuildingMode::Presets)),
+            )
+            .add_systems(OnExit(BuildingMode::Presets), Self::deselect);
+    }
+}
+
+/// Minimum side of the area in meters.
+const MIN_SIZE: f32 = 1.0;
+
+impl PresetPlugin {
+    fn start(
+        camera_caster: CameraCaster,
+        mut commands: Commands,
+        cities: Query<Entity, With<ActiveCity>>,
+    ) {
+        if let Some(point) = camera_caster.intersect_ground().map(|point| point.xz()) {
+            info!("starting preset area");
+            commands.entity(cities.single()).with_children(|parent| {
+                parent.spawn((
+                    StateScoped(BuildingMode::Presets),
+                    PresetArea {
+                        start: point,
+                        end: point,
+                    },
+                ));
+            });
+        }
+    }
+
+    fn update_end(camera_caster: CameraCaster, mut areas: Query<&mut PresetArea>) {
+        if let Ok(mut area) = areas.get_single_mut() {
+            if let Some(point) = camera_caster.intersect_ground().map(|point| point.xz()) {
+                trace!("updating preset area end to `{point:?}`");
+                area.end = point;
+            }
+        }
+    }
+
+    fn confirm(
+        mut commands: Commands,
+        mut stamp_events: EventWriter<PresetStamp>,
+        asset_server: Res<AssetServer>,
+        selected: Res<SelectedPreset>,
+        spatial_query: SpatialQuery,
+        permissions: BuildPermissions,
+        areas: Query<(Entity, &Parent, &PresetArea)>,
+        cities: Query<&GlobalTransform>,
+    ) {
+        let Ok((entity, parent, area)) = areas.get_single() else {
+            return;
+        };
+        if !area.is_valid() {
+            debug!("ignoring confirmation of too small preset area");
+            return;
+        }
+        let city_transform = cities.get(**parent).unwrap();
+        if area.overlaps(&spatial_query, city_transform) {
+            debug!("ignoring confirmation of occupied preset area");
+            return;
+        }
+        if !area
+            .corners()
+            .into_iter()
+            .all(|point| permissions.locally_allowed(**parent, point))
+        {
+            debug!("ignoring preset confirmation without build rights");
+            return;
+        }
+
+        let info_path = asset_server
+            .get_path(selected.0)
+            .expect("info should always come from file")
+            .into_owned();
+        info!("stamping preset '{info_path}'");
+        stamp_events.send(PresetStamp {
+            city_entity: **parent,
+            info_path,
+            start: area.start,
+            end: area.end,
+        });
+        commands.entity(entity).despawn();
+    }
+
+    fn cancel(mut commands: Commands, areas: Query<Entity, With<PresetArea>>) {
+        if let Ok(entity) = areas.get_single() {
+            info!("cancelling preset area");
+            commands.entity(entity).despawn();
+        }
+    }
+
+    fn deselect(mut commands: Commands) {
+        commands.remove_resource::<SelectedPreset>();
+    }
+
+    fn draw(
+        mut gizmos: Gizmos,
+        selected: Option<Res<SelectedPreset>>,
+        presets_info: Res<Assets<PresetInfo>>,
+        spatial_query: SpatialQuery,
+        permissions: BuildPermissions,
+        areas: Query<(&Parent, &PresetArea)>,
+        cities: Query<&GlobalTransform>,
+    ) {
+        let Ok((parent, area)) = areas.get_single() else {
+            return;
+        };
+        let transform = cities.get(**parent).unwrap();
+
+        let allowed = area
+            .corners()
+            .into_iter()
+            .all(|point| permissions.locally_allowed(**parent, point));
+        let color = if area.is_valid() && allowed && !area.overlaps(&spatial_query, transform) {
+            WHITE
+        } else {
+            RED
+        };
+
+        let corners = area
+            .corners()
+            .map(|point| transform.transform_point(Vec3::new(point.x, 0.0, point.y)));
+        gizmos.linestrip(corners.into_iter().chain([corners[0]]), color);
+
+        // Show where objects will be placed.
+        let Some(info) = selected.and_then(|selected| presets_info.get(selected.0)) else {
+            return;
+        };
+        for (_, position, _) in info.fit(area.rect()) {
+            let point = transform.transform_point(Vec3::new(position.x, 0.0, position.y));
+            gizmos.circle(point, Dir3::Y, 0.2, color);
+        }
+    }
+
+    fn stamp(
+        mut commands: Commands,
+        mut stamp_events: EventReader<FromClient<PresetStamp>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        asset_server: Res<AssetServer>,
+        presets_info: Res<Assets<PresetInfo>>,
+        objects_info: Res<Assets<ObjectInfo>>,
+        locks: ScenarioLocks,
+        spatial_query: SpatialQuery,
+        ground_sampler: GroundSampler,
+        permissions: BuildPermissions,
+        lots: Query<(&Parent, &LotVertices, &LotFamily)>,
+        cities: Query<&GlobalTransform>,
+        mut families: Query<&mut Budget>,
+    ) {
+        for FromClient { client_id, event } in stamp_events.read().cloned() {
+            if let Err(violation) = locks.check(ScenarioFeature::Building, client_id) {
+                violation_events.send(violation);
+                continue;
+            }
+
+            let Some(info) = asset_server
+                .get_handle(&event.info_path)
+                .and_then(|handle| presets_info.get(&handle))
+            else {
+                error!(
+                    "`{client_id:?}` tries to stamp non-existing preset '{}'",
+                    event.info_path
+                );
+                continue;
+            };
+            let area = PresetArea {
+                start: event.start,
+                end: event.end,
+            };
+            if !area.is_valid() {
+                error!("`{client_id:?}` tries to stamp preset into too small area");
+                continue;
+            }
+            if !area.corners().into_iter().all(city::within_city) {
+                error!("`{client_id:?}` tries to stamp preset outside of city size");
+                violation_events.send(ClientViolation {
+                    client_id,
+                    reason: "stamping presets outside of the world".into(),
+                });
+                continue;
+            }
+            if !area
+                .corners()
+                .into_iter()
+                .all(|point| permissions.allowed(client_id, event.city_entity, point))
+            {
+                error!("`{client_id:?}` has no build rights to stamp preset");
+                violation_events.send(ClientViolation {
+                    client_id,
+                    reason: "stamping presets without build rights".into(),
+                });
+                continue;
+            }
+            let Ok(city_transform) = cities.get(event.city_entity) else {
+                error!(
+                    "`{client_id:?}` tries to stamp preset into invalid city `{}`",
+                    event.city_entity
+                );
+                continue;
+            };
+            if area.overlaps(&spatial_query, city_transform) {
+                error!("`{client_id:?}` tries to stamp preset into occupied area");
+                continue;
+            }
+            let cost = match info.cost(&asset_server, &objects_info) {
+                Ok(cost) => cost,
+                Err(e) => {
+                    error!("unable to stamp preset '{}': {e:#}", event.info_path);
+                    continue;
+                }
+            };
+
+            // Placing individual objects is free, so only charge on family lots.
+            let center = area.rect().center();
+            let family = lots
+                .iter()
+                .find(|(parent, vertices, _)| {
+                    ***parent == event.city_entity && vertices.contains_point(center)
+                })
+                .and_then(|(.., lot_family)| families.get_mut(lot_family.0).ok());
+            if let Some(mut budget) = family {
+                if !budget.spend(cost) {
+                    error!(
+                        "`{client_id:?}` can't afford preset '{}' for {cost}",
+                        event.info_path
+                    );
+                    continue;
+                }
+            }
+
+            info!(
+                "`{client_id:?}` stamps preset '{}' for {cost}",
+                event.info_path
+            );
+            recorder.record(client_id, &event);
+            commands.entity(event.city_entity).with_children(|parent| {
+                for (info_path, position, rotation) in info.fit(area.rect()) {
+                    let global_point =
+                        city_transform.transform_point(Vec3::new(position.x, 0.0, position.y));
+                    let height = ground_sampler
+                        .height_range([global_point.xz()])
+                        .map(|(_, max)| max - city_transform.translation().y)
+                        .unwrap_or_default();
+                    let transform = Transform::from_xyz(position.x, height, position.y)
+                        .with_rotation(Quat::from_rotation_y(rotation));
+                    // Like bought objects, removed if placed on uneven ground.
+                    parent.spawn((
+                        ObjectBundle::new(info_path.clone(), transform),
+                        PendingGroundCheck::default(),
+                    ));
+                }
+            });
+        }
+    }
+}
+
+/// Preset to place with [`BuildingMode::Presets`].
+///
+/// Area selection won't start until this resource is inserted.
+#[derive(Resource)]
+pub struct SelectedPreset(pub AssetId<PresetInfo>);
+
+/// Area selected for a preset in city coordinates.
+#[derive(Component)]
+pub struct PresetArea {
+    start: Vec2,
+    end: Vec2,
+}
+
+impl PresetArea {
+    pub fn rect(&self) -> Rect {
+        Rect::from_corners(self.start, self.end)
+    }
+
+    fn corners(&self) -> [Vec2; 4] {
+        let rect = self.rect();
+        [
+            rect.min,
+            Vec2::new(rect.max.x, rect.min.y),
+            rect.max,
+            Vec2::new(rect.min.x, rect.max.y),
+        ]
+    }
+
+    fn is_valid(&self) -> bool {
+        let size = self.rect().size();
+        size.x >= MIN_SIZE && size.y >= MIN_SIZE
+    }
+
+    /// Returns `true` if the area intersects objects or walls.
+    fn overlaps(&self, spatial_query: &SpatialQuery, city_transform: &GlobalTransform) -> bool {
+        /// Vertical extent of the checked volume.
+        const HEIGHT: f32 = 20.0;
+        /// Shrinks the area to allow placing right next to walls.
+        const MARGIN: f32 = 0.05;
+
+        let rect = self.rect().inflate(-MARGIN);
+        let center = rect.center();
+        let size = rect.size();
+        let (_, rotation, _) = city_transform.to_scale_rotation_translation();
+        !spatial_query
+            .shape_intersections(
+                &Collider::cuboid(size.x, HEIGHT, size.y),
+                city_transform.transform_point(Vec3::new(center.x, 0.0, center.y)),
+                rotation,
+                SpatialQueryFilter::from_mask([Layer::Object, Layer::Wall]),
+            )
+            .is_empty()
+    }
+}
+
+/// Places all objects from a preset into an area.
+#[derive(Clone, Deserialize, Event, Serialize)]
+struct PresetStamp {
+    city_entity: Entity,
+    info_path: AssetPath<'static>,
+    start: Vec2,
+    end: Vec2,
+}
+
+impl MapEntities for PresetStamp {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.city_entity = entity_mapper.map_entity(self.city_entity);
+    }
+}
+
+impl Mutation for PresetStamp {
+    const TARGET: &'static str = "preset";
+
+    fn kind(&self) -> MutationKind {
+        MutationKind::Spawn
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        Some(self.city_entity)
+    }
+}
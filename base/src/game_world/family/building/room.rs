@@ -0,0 +1,385 @@
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use itertools::Itertools;
+
+use super::wall::{placing_wall::PlacingWall, Apertures, Wall};
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::Actor,
+        city::{lot::LotVertices, City},
+        object::Object,
+        spline::SplineSegment,
+    },
+    math::{polygon::Polygon, segment::Segment},
+};
+
+/// Detects rooms from wall topology.
+///
+/// Rooms are computed locally from replicated walls,
+/// so features like ceilings or privacy don't need to recompute geometry.
+pub(super) struct RoomPlugin;
+
+impl Plugin for RoomPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rooms>().add_systems(
+            PostUpdate,
+            (
+                Self::rebuild.run_if(walls_changed.or_else(apertures_changed)),
+                Self::update_entities,
+            )
+                .chain()
+                .in_set(RoomSet)
+                .after(TransformSystem::TransformPropagate)
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+impl RoomPlugin {
+    fn rebuild(
+        mut rooms: ResMut<Rooms>,
+        walls: Query<(&Parent, &SplineSegment, &Apertures), (With<Wall>, Without<PlacingWall>)>,
+        lots: Query<(Entity, &Parent, &LotVertices)>,
+    ) {
+        rooms.0.clear();
+
+        let mut city_walls = HashMap::<_, Vec<_>>::new();
+        for (parent, segment, apertures) in &walls {
+            city_walls
+                .entry(**parent)
+                .or_default()
+                .push((**segment, apertures));
+        }
+
+        for (city_entity, walls) in city_walls {
+            let segments: Vec<_> = walls.iter().map(|&(segment, _)| segment).collect();
+            let first_id = rooms.0.len();
+            for polygon in enclosed_loops(&segments) {
+                let center = polygon.iter().sum::<Vec2>() / polygon.len() as f32;
+                let lot_entity = lots
+                    .iter()
+                    .find(|(_, parent, vertices)| {
+                        ***parent == city_entity && vertices.contains_point(center)
+                    })
+                    .map(|(entity, ..)| entity);
+
+                let id = RoomId(rooms.0.len());
+                rooms.0.push(Room {
+                    id,
+                    city_entity,
+                    lot_entity,
+                    polygon,
+                    entities: Vec::new(),
+                    adjacent: Vec::new(),
+                });
+            }
+
+            // Rooms are connected by walls with doors or arches between them.
+            for (segment, apertures) in walls {
+                if !apertures
+                    .iter()
+                    .any(|aperture| !aperture.hole && !aperture.placing_object)
+                {
+                    continue;
+                }
+
+                let sides: Vec<_> = rooms.0[first_id..]
+                    .iter()
+                    .filter(|room| room.has_edge(segment))
+                    .map(|room| room.id)
+                    .collect();
+                if let [a, b] = sides[..] {
+                    rooms.0[a.0].adjacent.push(b);
+                    rooms.0[b.0].adjacent.push(a);
+                }
+            }
+        }
+
+        debug!("detected {} rooms", rooms.0.len());
+    }
+
+    fn update_entities(
+        mut rooms: ResMut<Rooms>,
+        cities: Query<&GlobalTransform, With<City>>,
+        entities: Query<(Entity, &GlobalTransform), Or<(With<Actor>, With<Object>)>>,
+        changed_entities: Query<(), (Or<(With<Actor>, With<Object>)>, Changed<GlobalTransform>)>,
+    ) {
+        if !rooms.is_changed() && changed_entities.is_empty() {
+            return;
+        }
+
+        let rooms = rooms.bypass_change_detection();
+        for room in &mut rooms.0 {
+            room.entities.clear();
+            let Ok(city_transform) = cities.get(room.city_entity) else {
+                continue;
+            };
+            let inverse = city_transform.affine().inverse();
+            for (entity, transform) in &entities {
+                let point = inverse.transform_point3(transform.translation());
+                if room.polygon.contains_point(point.xz()) {
+                    room.entities.push(entity);
+                }
+            }
+        }
+    }
+}
+
+/// Systems that update [`Rooms`].
+///
+/// Run in [`PostUpdate`].
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub(crate) struct RoomSet;
+
+/// Returns `true` if placed walls were changed.
+///
+/// Ghost walls are ignored until placement is confirmed.
+pub(crate) fn walls_changed(
+    mut removed_walls: RemovedComponents<Wall>,
+    mut removed_placing: RemovedComponents<PlacingWall>,
+    changed_walls: Query<(), (With<Wall>, Without<PlacingWall>, Changed<SplineSegment>)>,
+    added_placing: Query<(), (With<Wall>, Added<PlacingWall>)>,
+) -> bool {
+    let removed = removed_walls.read().count() + removed_placing.read().count();
+    removed != 0 || !changed_walls.is_empty() || !added_placing.is_empty()
+}
+
+fn apertures_changed(changed_walls: Query<(), (With<Wall>, Changed<Apertures>)>) -> bool {
+    !changed_walls.is_empty()
+}
+
+/// Rooms enclosed by walls in all cities.
+///
+/// Rebuilt when walls change, so [`RoomId`] is valid only until the next rebuild.
+/// Contained entities are updated when actors or objects move.
+#[derive(Resource, Default)]
+pub(crate) struct Rooms(Vec<Room>);
+
+impl Rooms {
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Room> {
+        self.0.iter()
+    }
+
+    pub(crate) fn get(&self, id: RoomId) -> Option<&Room> {
+        self.0.get(id.0)
+    }
+
+    /// Returns the room that contains the entity.
+    pub(crate) fn room_of(&self, entity: Entity) -> Option<&Room> {
+        self.iter().find(|room| room.entities.contains(&entity))
+    }
+
+    /// Returns the room of the city that contains the point in city coordinates.
+    pub(crate) fn room_at(&self, city_entity: Entity, point: Vec2) -> Option<&Room> {
+        self.iter()
+            .find(|room| room.city_entity == city_entity && room.polygon.contains_point(point))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) struct RoomId(usize);
+
+pub(crate) struct Room {
+    pub(crate) id: RoomId,
+    pub(crate) city_entity: Entity,
+
+    /// Lot on which the room is located.
+    pub(crate) lot_entity: Option<Entity>,
+
+    /// Counterclockwise room outline in city coordinates.
+    pub(crate) polygon: Polygon,
+
+    /// Actors and objects inside the room.
+    pub(crate) entities: Vec<Entity>,
+
+    /// Rooms reachable through doors or arches.
+    pub(crate) adjacent: Vec<RoomId>,
+}
+
+impl Room {
+    /// Returns `true` if the wall is a part of the room outline.
+    fn has_edge(&self, segment: Segment) -> bool {
+        const EPSILON: f32 = 0.001;
+        self.polygon
+            .iter()
+            .circular_tuple_windows()
+            .any(|(&start, &end)| {
+                (start.distance(segment.start) < EPSILON && end.distance(segment.end) < EPSILON)
+                    || (start.distance(segment.end) < EPSILON
+                        && end.distance(segment.start) < EPSILON)
+            })
+    }
+}
+
+/// Returns counterclockwise loops of walls that enclose rooms.
+///
+/// Walls should be connected by their endpoints.
+/// Walls that don't belong to any loop are ignored.
+fn enclosed_loops(segments: &[Segment]) -> Vec<Polygon> {
+    // Vertices are matched with a small tolerance to avoid precision issues.
+    const PRECISION: f32 = 1000.0;
+    let mut vertices = Vec::new();
+    let mut keys = HashMap::new();
+    let mut vertex_index = |point: Vec2| {
+        let key = (point * PRECISION).round().as_ivec2();
+        *keys.entry(key).or_insert_with(|| {
+            vertices.push(point);
+            vertices.len() - 1
+        })
+    };
+
+    let mut neighbors = HashMap::<usize, Vec<usize>>::new();
+    for segment in segments {
+        let start = vertex_index(segment.start);
+        let end = vertex_index(segment.end);
+        if start != end {
+            neighbors.entry(start).or_default().push(end);
+            neighbors.entry(end).or_default().push(start);
+        }
+    }
+    for adjacent in neighbors.values_mut() {
+        adjacent.sort_unstable();
+        adjacent.dedup();
+    }
+
+    // Remove dangling walls since they can't enclose anything.
+    loop {
+        let Some(vertex) = neighbors
+            .iter()
+            .find(|(_, adjacent)| adjacent.len() < 2)
+            .map(|(&vertex, _)| vertex)
+        else {
+            break;
+        };
+        for other in neighbors.remove(&vertex).unwrap() {
+            if let Some(adjacent) = neighbors.get_mut(&other) {
+                adjacent.retain(|&index| index != vertex);
+            }
+        }
+    }
+
+    // Sort outgoing edges counterclockwise to walk faces.
+    for (&vertex, adjacent) in &mut neighbors {
+        let origin = vertices[vertex];
+        adjacent.sort_by(|&a, &b| {
+            let angle_a = (vertices[a] - origin).to_angle();
+            let angle_b = (vertices[b] - origin).to_angle();
+            angle_a.total_cmp(&angle_b)
+        });
+    }
+
+    let mut visited = HashSet::new();
+    let mut loops = Vec::new();
+    for (&from, adjacent) in &neighbors {
+        for &to in adjacent {
+            if visited.contains(&(from, to)) {
+                continue;
+            }
+
+            let mut points = Vec::new();
+            let (mut current_from, mut current_to) = (from, to);
+            while visited.insert((current_from, current_to)) {
+                points.push(vertices[current_from]);
+
+                // Take the edge right before the reverse one to keep the face on the left.
+                let outgoing = &neighbors[&current_to];
+                let reverse_index = outgoing
+                    .iter()
+                    .position(|&index| index == current_from)
+                    .unwrap();
+                let next_index = (reverse_index + outgoing.len() - 1) % outgoing.len();
+                (current_from, current_to) = (current_to, outgoing[next_index]);
+            }
+
+            // The outer boundary of connected walls goes clockwise.
+            let area: f32 = points
+                .iter()
+                .circular_tuple_windows()
+                .map(|(a, b)| a.perp_dot(*b))
+                .sum();
+            if area > f32::EPSILON && points.len() >= 3 {
+                loops.push(Polygon(points));
+            }
+        }
+    }
+
+    loops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_room() {
+        let loops = enclosed_loops(&square(Vec2::ZERO));
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].len(), 4);
+    }
+
+    #[test]
+    fn adjacent_rooms() {
+        let mut segments = square(Vec2::ZERO);
+        // Share the left wall of the second room with the first one.
+        segments.extend(square(Vec2::X * 4.0).into_iter().take(3));
+        assert_eq!(enclosed_loops(&segments).len(), 2);
+    }
+
+    #[test]
+    fn open_walls() {
+        let mut segments = square(Vec2::ZERO);
+        segments.pop();
+        assert!(enclosed_loops(&segments).is_empty());
+    }
+
+    #[test]
+    fn dangling_wall() {
+        let mut segments = square(Vec2::ZERO);
+        segments.push(Segment::new(Vec2::new(2.0, 0.0), Vec2::new(2.0, 2.0)));
+        segments.push(Segment::new(Vec2::ZERO, Vec2::new(-2.0, -2.0)));
+        let loops = enclosed_loops(&segments);
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].len(), 4);
+    }
+
+    #[test]
+    fn shared_edge() {
+        let mut segments = square(Vec2::ZERO);
+        segments.extend(square(Vec2::X * 4.0).into_iter().take(3));
+        let rooms: Vec<_> = enclosed_loops(&segments)
+            .into_iter()
+            .enumerate()
+            .map(|(index, polygon)| Room {
+                id: RoomId(index),
+                city_entity: Entity::PLACEHOLDER,
+                lot_entity: None,
+                polygon,
+                entities: Vec::new(),
+                adjacent: Vec::new(),
+            })
+            .collect();
+
+        let shared = Segment::new(Vec2::new(4.0, 4.0), Vec2::new(4.0, 0.0));
+        assert!(rooms.iter().all(|room| room.has_edge(shared)));
+        let outer = Segment::new(Vec2::ZERO, Vec2::new(4.0, 0.0));
+        assert_eq!(rooms.iter().filter(|room| room.has_edge(outer)).count(), 1);
+    }
+
+    /// Returns walls of a 4x4 room going counterclockwise from the bottom wall.
+    fn square(origin: Vec2) -> Vec<Segment> {
+        [
+            Vec2::ZERO,
+            Vec2::new(4.0, 0.0),
+            Vec2::new(4.0, 4.0),
+            Vec2::new(0.0, 4.0),
+        ]
+        .into_iter()
+        .map(|point| point + origin)
+        .circular_tuple_windows()
+        .map(|(start, end)| Segment::new(start, end))
+        .collect()
+    }
+}
@@ -1,28 +1,45 @@
+mod apertures_debug;
+pub mod cutaway;
+pub mod placing_room;
 pub mod placing_wall;
 pub(crate) mod wall_mesh;
 
 use avian3d::prelude::*;
 use bevy::{ecs::entity::MapEntities, prelude::*, render::view::NoFrustumCulling};
 use bevy_replicon::prelude::*;
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter};
 
 use crate::{
+    asset::info::scenario_info::ScenarioFeature,
     core::GameState,
     game_world::{
+        city::{
+            self,
+            lot::{permissions::BuildPermissions, LotFamily, LotVertices},
+        },
         commands_history::{
             CommandConfirmation, CommandId, CommandRequest, ConfirmableCommand, EntityRecorder,
             PendingCommand,
         },
+        family::Budget,
         hover::Hoverable,
+        mutation_log::{Mutation, MutationKind, MutationRecorder},
         navigation::Obstacle,
+        scenario::ScenarioLocks,
         spline::{
-            dynamic_mesh::DynamicMesh, PointKind, SplineConnections, SplinePlugin, SplineSegment,
+            dynamic_mesh::{DynamicMesh, MeshTask},
+            PointConnections, PointKind, SplineConnections, SplinePlugin, SplineSegment,
         },
         Layer,
     },
     math::{segment::Segment, triangulator::Triangulator},
+    network::validation::{AppValidationExt, ClientViolation},
 };
+use apertures_debug::AperturesDebugPlugin;
+use cutaway::{CutawayPlugin, Lowered};
+use placing_room::PlacingRoomPlugin;
 use placing_wall::PlacingWallPlugin;
 
 use super::BuildingMode;
@@ -31,29 +48,37 @@ pub(crate) struct WallPlugin;
 
 impl Plugin for WallPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(PlacingWallPlugin)
-            .add_sub_state::<WallTool>()
-            .enable_state_scoped_entities::<WallTool>()
-            .init_resource::<WallMaterial>()
-            .register_type::<Wall>()
-            .replicate::<Wall>()
-            .add_mapped_client_event::<CommandRequest<WallCommand>>(ChannelKind::Unordered)
-            .add_systems(
-                PreUpdate,
-                Self::init
-                    .after(ClientSet::Receive)
-                    .run_if(in_state(GameState::InGame)),
+        app.add_plugins((
+            AperturesDebugPlugin,
+            CutawayPlugin,
+            PlacingRoomPlugin,
+            PlacingWallPlugin,
+        ))
+        .add_sub_state::<WallTool>()
+        .enable_state_scoped_entities::<WallTool>()
+        .init_resource::<WallMaterial>()
+        .register_type::<Wall>()
+        .replicate::<Wall>()
+        .add_mapped_client_event::<CommandRequest<WallCommand>>(ChannelKind::Unordered)
+        .limit_client_event::<CommandRequest<WallCommand>>(30)
+        .add_systems(
+            PreUpdate,
+            Self::init
+                .after(ClientSet::Receive)
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            PostUpdate,
+            (
+                Self::apply_command
+                    .run_if(server_or_singleplayer)
+                    .before(ServerSet::StoreHierarchy),
+                Self::update_meshes
+                    .after(SplinePlugin::update_connections)
+                    .after(SplinePlugin::update_point_connections),
             )
-            .add_systems(
-                PostUpdate,
-                (
-                    Self::apply_command
-                        .run_if(server_or_singleplayer)
-                        .before(ServerSet::StoreHierarchy),
-                    Self::update_meshes.after(SplinePlugin::update_connections),
-                )
-                    .run_if(in_state(GameState::InGame)),
-            );
+                .run_if(in_state(GameState::InGame)),
+        );
     }
 }
 
@@ -71,6 +96,7 @@ impl WallPlugin {
                 Name::new("Wall"),
                 Apertures::default(),
                 Collider::default(),
+                Lowered::default(),
                 CollisionLayers::new(
                     Layer::Wall,
                     [
@@ -93,34 +119,60 @@ impl WallPlugin {
     }
 
     pub(crate) fn update_meshes(
-        mut triangulator: Local<Triangulator>,
-        mut meshes: ResMut<Assets<Mesh>>,
         mut changed_walls: Query<
             (
-                &Handle<Mesh>,
+                Entity,
                 Ref<SplineSegment>,
                 &SplineConnections,
+                &PointConnections,
                 &mut Apertures,
+                &Lowered,
                 &mut Collider,
+                &mut MeshTask,
             ),
-            Or<(Changed<SplineConnections>, Changed<Apertures>)>,
+            Or<(
+                Changed<SplineConnections>,
+                Changed<PointConnections>,
+                Changed<Apertures>,
+                Changed<Lowered>,
+            )>,
         >,
     ) {
-        for (mesh_handle, segment, connections, mut apertures, mut collider) in &mut changed_walls {
-            let mesh = meshes
-                .get_mut(mesh_handle)
-                .expect("wall handles should be valid");
-
-            trace!("regenerating wall mesh");
-            let mut dyn_mesh = DynamicMesh::take(mesh);
-            wall_mesh::generate(
-                &mut dyn_mesh,
-                *segment,
-                connections,
-                &apertures,
-                &mut triangulator,
-            );
-            dyn_mesh.apply(mesh);
+        for (
+            entity,
+            segment,
+            connections,
+            point_connections,
+            mut apertures,
+            lowered,
+            mut collider,
+            mut mesh_task,
+        ) in &mut changed_walls
+        {
+            trace!("regenerating mesh for wall `{entity}`");
+            let wall_segment = *segment;
+            let connections = connections.clone();
+            let point_connections = point_connections.clone();
+            let height = if lowered.0 {
+                wall_mesh::LOWERED_HEIGHT
+            } else {
+                wall_mesh::HEIGHT
+            };
+            let wall_apertures = apertures.clone();
+            mesh_task.spawn(move |dyn_mesh| {
+                let _span = info_span!("wall_mesh_regeneration").entered();
+                if let Err(error) = wall_mesh::generate(
+                    dyn_mesh,
+                    wall_segment,
+                    &connections,
+                    &point_connections,
+                    &wall_apertures,
+                    height,
+                    &mut Triangulator::default(),
+                ) {
+                    error!("unable to generate mesh for wall `{entity}`: {error}");
+                }
+            });
 
             if apertures.collision_outdated || segment.is_changed() || collider.is_added() {
                 trace!("regenerating wall collision");
@@ -134,10 +186,62 @@ impl WallPlugin {
         mut commands: Commands,
         mut request_events: EventReader<FromClient<CommandRequest<WallCommand>>>,
         mut confirm_events: EventWriter<ToClients<CommandConfirmation>>,
-        mut walls: Query<&mut SplineSegment, With<Wall>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        locks: ScenarioLocks,
+        permissions: BuildPermissions,
+        mut walls: Query<(&mut SplineSegment, &Parent), With<Wall>>,
+        lots: Query<(&Parent, &LotVertices, &LotFamily)>,
+        mut families: Query<&mut Budget>,
     ) {
         for FromClient { client_id, event } in request_events.read().copied() {
-            // TODO: validate if command can be applied.
+            if let Err(violation) = locks.check(ScenarioFeature::Building, client_id) {
+                violation_events.send(violation);
+                continue;
+            }
+
+            let city_points = event.command.city_points(&walls);
+            if !city_points
+                .iter()
+                .all(|&(_, point)| city::within_city(point))
+            {
+                error!("`{client_id:?}` sends wall points outside of city size");
+                violation_events.send(ClientViolation {
+                    client_id,
+                    reason: "building walls outside of the world".into(),
+                });
+                continue;
+            }
+
+            if !city_points
+                .into_iter()
+                .all(|(city_entity, point)| permissions.allowed(client_id, city_entity, point))
+            {
+                error!("`{client_id:?}` has no build rights to apply wall command");
+                violation_events.send(ClientViolation {
+                    client_id,
+                    reason: "building walls without build rights".into(),
+                });
+                continue;
+            }
+
+            // Like presets, walls are charged only on family lots.
+            if let Some((city_entity, point, price)) = event.command.price(&walls) {
+                let family = lots
+                    .iter()
+                    .find(|(parent, vertices, _)| {
+                        ***parent == city_entity && vertices.contains_point(point)
+                    })
+                    .and_then(|(.., lot_family)| families.get_mut(lot_family.0).ok());
+                if let Some(mut budget) = family {
+                    if !budget.spend(price) {
+                        error!("`{client_id:?}` can't afford wall for {price}");
+                        continue;
+                    }
+                }
+            }
+
+            recorder.record(client_id, &event);
             let mut confirmation = CommandConfirmation::new(event.id);
             match event.command {
                 WallCommand::Create {
@@ -155,7 +259,7 @@ impl WallPlugin {
                     kind,
                     point,
                 } => match walls.get_mut(entity) {
-                    Ok(mut segment) => {
+                    Ok((mut segment, _)) => {
                         info!("`{client_id:?}` moves `{kind:?}` for wall `{entity}`");
                         match kind {
                             PointKind::Start => segment.start = point,
@@ -178,8 +282,21 @@ impl WallPlugin {
     }
 }
 
+/// Price of each meter of a wall.
+const PRICE_PER_METER: f32 = 10.0;
+
+/// Returns the price for a wall with the specified segment.
+pub(crate) fn wall_price(segment: Segment) -> u32 {
+    (segment.displacement().length() * PRICE_PER_METER).ceil() as u32
+}
+
+/// Maximum ground height difference under a wall.
+///
+/// Walls don't follow the terrain and have no foundations.
+pub(crate) const MAX_GROUND_DIFF: f32 = 0.1;
+
 #[derive(Resource)]
-struct WallMaterial(Handle<StandardMaterial>);
+pub(crate) struct WallMaterial(pub(crate) Handle<StandardMaterial>);
 
 impl FromWorld for WallMaterial {
     fn from_world(world: &mut World) -> Self {
@@ -196,6 +313,7 @@ pub enum WallTool {
     #[default]
     Create,
     Move,
+    Room,
 }
 
 impl WallTool {
@@ -203,12 +321,13 @@ impl WallTool {
         match self {
             Self::Create => "✏",
             Self::Move => "↔",
+            Self::Room => "⬜",
         }
     }
 }
 
 #[derive(Bundle)]
-struct WallBundle {
+pub(crate) struct WallBundle {
     wall: Wall,
     segment: SplineSegment,
     parent_sync: ParentSync,
@@ -216,7 +335,7 @@ struct WallBundle {
 }
 
 impl WallBundle {
-    fn new(segment: Segment) -> Self {
+    pub(crate) fn new(segment: Segment) -> Self {
         Self {
             wall: Wall,
             segment: SplineSegment(segment),
@@ -233,7 +352,7 @@ pub(crate) struct Wall;
 /// Dynamically updated component with precalculated apertures for wall objects.
 ///
 /// Apertures are sorted by distance to the wall starting point.
-#[derive(Component, Default)]
+#[derive(Clone, Component, Default)]
 pub(crate) struct Apertures {
     apertures: Vec<Aperture>,
     collision_outdated: bool,
@@ -241,16 +360,23 @@ pub(crate) struct Apertures {
 
 impl Apertures {
     /// Returns iterator over all apertures.
-    fn iter(&self) -> impl Iterator<Item = &Aperture> {
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Aperture> {
         self.apertures.iter()
     }
 
     /// Inserts a new aperture in sorted order.
+    ///
+    /// Ties are broken by height and kind, so the order doesn't depend on the insertion order
+    /// and all clients skip the same overlapping apertures.
     pub(crate) fn insert(&mut self, aperture: Aperture) {
-        let index = self
-            .apertures
-            .binary_search_by(|other| other.distance.total_cmp(&aperture.distance))
-            .expect_err("apertures shouldn't have duplicates");
+        // Apertures at the same distance overlap and will be skipped during mesh generation.
+        let (Ok(index) | Err(index)) = self.apertures.binary_search_by(|other| {
+            other
+                .distance
+                .total_cmp(&aperture.distance)
+                .then(other.translation.y.total_cmp(&aperture.translation.y))
+                .then(other.hole.cmp(&aperture.hole))
+        });
 
         if !aperture.placing_object && !aperture.hole {
             self.collision_outdated = true;
@@ -273,6 +399,7 @@ impl Apertures {
     }
 }
 
+#[derive(Clone)]
 pub(crate) struct Aperture {
     /// The entity that cut this aperture.
     pub(crate) object_entity: Entity,
@@ -295,6 +422,94 @@ pub(crate) struct Aperture {
     pub(crate) placing_object: bool,
 }
 
+impl Aperture {
+    /// Returns bounding rectangle in wall space.
+    ///
+    /// X is the distance along the wall and Y is the height.
+    pub(crate) fn bounds(&self) -> Rect {
+        let mut half_width = 0.0;
+        let mut min_height = f32::MAX;
+        let mut max_height = f32::MIN;
+        for position in &self.cutout {
+            half_width = position.x.abs().max(half_width);
+            min_height = position.y.min(min_height);
+            max_height = position.y.max(max_height);
+        }
+
+        Rect::new(
+            self.distance - half_width,
+            self.translation.y + min_height,
+            self.distance + half_width,
+            self.translation.y + max_height,
+        )
+    }
+
+    /// Checks if the cutout can be triangulated as a part of a wall with the given length.
+    pub(crate) fn validate(&self, wall_length: f32) -> Result<(), ApertureError> {
+        if self.cutout.len() < 3 {
+            return Err(ApertureError::TooFewPoints);
+        }
+
+        if !self.translation.is_finite() || self.cutout.iter().any(|point| !point.is_finite()) {
+            return Err(ApertureError::NonFinite);
+        }
+
+        let edges: Vec<_> = self
+            .cutout
+            .iter()
+            .circular_tuple_windows()
+            .map(|(&start, &end)| Segment::new(start, end))
+            .collect();
+        for (index, edge) in edges.iter().enumerate() {
+            // Skip adjacent edges since they always share a point.
+            let last_index = if index == 0 {
+                edges.len() - 1
+            } else {
+                edges.len()
+            };
+            for other_edge in edges.get(index + 2..last_index).unwrap_or_default() {
+                if edge.intersects(*other_edge) {
+                    return Err(ApertureError::SelfIntersection);
+                }
+            }
+        }
+
+        // Clippings are inserted into the wall outline, which goes clockwise from the wall start.
+        let area: f32 = edges.iter().map(|edge| edge.start.perp_dot(edge.end)).sum();
+        if area == 0.0 || (!self.hole && area > 0.0) {
+            return Err(ApertureError::WrongWinding);
+        }
+
+        const TOLERANCE: f32 = 0.01;
+        let bounds = self.bounds();
+        if bounds.min.x < -TOLERANCE
+            || bounds.max.x > wall_length + TOLERANCE
+            || bounds.min.y < -TOLERANCE
+            || bounds.max.y > wall_mesh::HEIGHT + TOLERANCE
+        {
+            return Err(ApertureError::OutOfBounds);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+pub(crate) enum ApertureError {
+    #[strum(serialize = "cutout should have at least 3 points")]
+    TooFewPoints,
+    #[strum(serialize = "cutout has non-finite coordinates")]
+    NonFinite,
+    #[strum(serialize = "cutout intersects itself")]
+    SelfIntersection,
+    #[strum(serialize = "cutout has wrong winding")]
+    WrongWinding,
+    #[strum(serialize = "cutout is outside of the wall")]
+    OutOfBounds,
+    #[strum(serialize = "cutout overlaps another clipping")]
+    Overlap,
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy)]
 enum WallCommand {
     Create {
@@ -311,6 +526,100 @@ enum WallCommand {
     },
 }
 
+impl WallCommand {
+    /// Returns city entity, a point of the wall and the price for the command.
+    ///
+    /// Moving a point charges only for the added length.
+    fn price(
+        &self,
+        walls: &Query<(&mut SplineSegment, &Parent), With<Wall>>,
+    ) -> Option<(Entity, Vec2, u32)> {
+        match *self {
+            Self::Create {
+                city_entity,
+                segment,
+            } => Some((city_entity, segment.start, wall_price(segment))),
+            Self::MovePoint {
+                entity,
+                kind,
+                point,
+            } => {
+                let (segment, parent) = walls.get(entity).ok()?;
+                let mut new_segment = **segment;
+                match kind {
+                    PointKind::Start => new_segment.start = point,
+                    PointKind::End => new_segment.end = point,
+                }
+                let price = wall_price(new_segment).saturating_sub(wall_price(**segment));
+                Some((**parent, point, price))
+            }
+            Self::Delete { .. } => None,
+        }
+    }
+
+    /// Returns city entities and positions inside them affected by the command.
+    ///
+    /// Used to validate build rights.
+    fn city_points(
+        &self,
+        walls: &Query<(&mut SplineSegment, &Parent), With<Wall>>,
+    ) -> Vec<(Entity, Vec2)> {
+        match *self {
+            Self::Create {
+                city_entity,
+                segment,
+            } => segment
+                .points()
+                .into_iter()
+                .map(|point| (city_entity, point))
+                .collect(),
+            Self::MovePoint {
+                entity,
+                kind,
+                point,
+            } => walls
+                .get(entity)
+                .map(|(segment, parent)| {
+                    let old_point = match kind {
+                        PointKind::Start => segment.start,
+                        PointKind::End => segment.end,
+                    };
+                    vec![(**parent, old_point), (**parent, point)]
+                })
+                .unwrap_or_default(),
+            Self::Delete { entity } => walls
+                .get(entity)
+                .map(|(segment, parent)| {
+                    segment
+                        .points()
+                        .into_iter()
+                        .map(|point| (**parent, point))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Mutation for WallCommand {
+    const TARGET: &'static str = "wall";
+
+    fn kind(&self) -> MutationKind {
+        match self {
+            Self::Create { .. } => MutationKind::Spawn,
+            Self::MovePoint { .. } => MutationKind::Edit,
+            Self::Delete { .. } => MutationKind::Delete,
+        }
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        match *self {
+            Self::Create { .. } => None,
+            Self::MovePoint { entity, .. } | Self::Delete { entity } => Some(entity),
+        }
+    }
+}
+
 impl PendingCommand for WallCommand {
     fn apply(
         self: Box<Self>,
@@ -379,3 +688,142 @@ impl MapEntities for WallCommand {
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_cutouts() {
+        assert_eq!(door(door_cutout()).validate(WALL_LENGTH), Ok(()));
+
+        let mut window = door(window_cutout());
+        window.hole = true;
+        assert_eq!(window.validate(WALL_LENGTH), Ok(()));
+
+        // Holes could have any winding.
+        window.cutout.reverse();
+        assert_eq!(window.validate(WALL_LENGTH), Ok(()));
+    }
+
+    #[test]
+    fn malformed_cutouts() {
+        let cutout = vec![Vec2::new(-0.4, 0.0), Vec2::new(0.4, 0.0)];
+        assert_eq!(
+            door(cutout).validate(WALL_LENGTH),
+            Err(ApertureError::TooFewPoints)
+        );
+
+        let mut cutout = door_cutout();
+        cutout[1].y = f32::NAN;
+        assert_eq!(
+            door(cutout).validate(WALL_LENGTH),
+            Err(ApertureError::NonFinite)
+        );
+
+        let cutout = vec![
+            Vec2::new(-0.4, 0.0),
+            Vec2::new(0.4, 2.0),
+            Vec2::new(-0.4, 2.0),
+            Vec2::new(0.4, 0.0),
+        ];
+        assert_eq!(
+            door(cutout).validate(WALL_LENGTH),
+            Err(ApertureError::SelfIntersection)
+        );
+
+        let mut cutout = door_cutout();
+        cutout.reverse();
+        assert_eq!(
+            door(cutout).validate(WALL_LENGTH),
+            Err(ApertureError::WrongWinding)
+        );
+
+        let cutout = vec![Vec2::ZERO, Vec2::new(0.0, 1.0), Vec2::new(0.0, 2.0)];
+        assert_eq!(
+            door(cutout).validate(WALL_LENGTH),
+            Err(ApertureError::WrongWinding)
+        );
+    }
+
+    #[test]
+    fn out_of_bounds_cutouts() {
+        let mut aperture = door(door_cutout());
+        aperture.distance = 0.1;
+        assert_eq!(
+            aperture.validate(WALL_LENGTH),
+            Err(ApertureError::OutOfBounds)
+        );
+
+        let mut aperture = door(door_cutout());
+        aperture.distance = WALL_LENGTH;
+        assert_eq!(
+            aperture.validate(WALL_LENGTH),
+            Err(ApertureError::OutOfBounds)
+        );
+
+        let mut cutout = door_cutout();
+        cutout[1].y = 3.0;
+        cutout[2].y = 3.0;
+        assert_eq!(
+            door(cutout).validate(WALL_LENGTH),
+            Err(ApertureError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn insertion_order() {
+        let mut window = door(window_cutout());
+        window.hole = true;
+
+        let mut apertures = Apertures::default();
+        apertures.insert(door(door_cutout()));
+        apertures.insert(window.clone());
+        let mut reversed = Apertures::default();
+        reversed.insert(window);
+        reversed.insert(door(door_cutout()));
+
+        let holes: Vec<_> = apertures.iter().map(|aperture| aperture.hole).collect();
+        let reversed_holes: Vec<_> = reversed.iter().map(|aperture| aperture.hole).collect();
+        assert_eq!(holes, reversed_holes);
+    }
+
+    #[test]
+    fn same_distance() {
+        let mut apertures = Apertures::default();
+        apertures.insert(door(door_cutout()));
+        apertures.insert(door(door_cutout()));
+        assert_eq!(apertures.iter().count(), 2);
+    }
+
+    const WALL_LENGTH: f32 = 4.0;
+
+    pub(super) fn door(cutout: Vec<Vec2>) -> Aperture {
+        Aperture {
+            object_entity: Entity::PLACEHOLDER,
+            translation: Vec3::X * 2.0,
+            distance: 2.0,
+            cutout,
+            hole: false,
+            placing_object: false,
+        }
+    }
+
+    pub(super) fn door_cutout() -> Vec<Vec2> {
+        vec![
+            Vec2::new(-0.4, 0.0),
+            Vec2::new(-0.4, 2.0),
+            Vec2::new(0.4, 2.0),
+            Vec2::new(0.4, 0.0),
+        ]
+    }
+
+    fn window_cutout() -> Vec<Vec2> {
+        vec![
+            Vec2::new(-0.6, 0.9),
+            Vec2::new(0.6, 0.9),
+            Vec2::new(0.6, 2.3),
+            Vec2::new(-0.6, 2.3),
+        ]
+    }
+}
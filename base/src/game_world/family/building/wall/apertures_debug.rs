@@ -0,0 +1,54 @@
+use bevy::{
+    color::palettes::css::{AQUA, FUCHSIA, GRAY},
+    prelude::*,
+};
+
+use super::Apertures;
+use crate::{
+    common_conditions::in_any_state,
+    game_world::{spline::SplineSegment, WorldState},
+    settings::Settings,
+};
+
+pub(super) struct AperturesDebugPlugin;
+
+impl Plugin for AperturesDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            Self::draw_apertures
+                .run_if(in_any_state([WorldState::City, WorldState::Family]))
+                .run_if(|settings: Res<Settings>| settings.developer.apertures),
+        );
+    }
+}
+
+impl AperturesDebugPlugin {
+    /// Draws aperture cutouts the same way as they are placed in wall meshes.
+    ///
+    /// Holes are aqua, clippings are fuchsia and apertures from placing objects are gray.
+    fn draw_apertures(
+        mut gizmos: Gizmos,
+        walls: Query<(&GlobalTransform, &SplineSegment, &Apertures)>,
+    ) {
+        for (transform, segment, apertures) in &walls {
+            let angle = -segment.displacement().to_angle();
+            let quat = Quat::from_axis_angle(Vec3::Y, angle);
+            for aperture in apertures.iter() {
+                let color = if aperture.placing_object {
+                    GRAY
+                } else if aperture.hole {
+                    AQUA
+                } else {
+                    FUCHSIA
+                };
+
+                let points = aperture.cutout.iter().map(|&position| {
+                    transform.transform_point(quat * position.extend(0.0) + aperture.translation)
+                });
+                let first = points.clone().next();
+                gizmos.linestrip(points.chain(first), color);
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+
+use super::{Wall, WallPlugin};
+use crate::{
+    core::GameState,
+    game_world::{player_camera::PlayerCamera, spline::SplineSegment, WorldState},
+    settings::{Settings, WallView},
+};
+
+/// Lowers walls in family mode according to [`WallView`] from settings.
+///
+/// Only meshes are lowered, colliders always keep the full height.
+pub(super) struct CutawayPlugin;
+
+impl Plugin for CutawayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            Self::update_lowered
+                .before(WallPlugin::update_meshes)
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+impl CutawayPlugin {
+    fn update_lowered(
+        settings: Res<Settings>,
+        world_state: Res<State<WorldState>>,
+        cameras: Query<&GlobalTransform, With<PlayerCamera>>,
+        mut walls: Query<(&mut Lowered, &GlobalTransform, &SplineSegment), With<Wall>>,
+    ) {
+        let view = if **world_state == WorldState::Family {
+            settings.gameplay.wall_view
+        } else {
+            WallView::Up
+        };
+
+        // Walls between the camera and the point it looks at are lowered in cutaway view.
+        let cutaway = cameras.get_single().ok().and_then(|camera_transform| {
+            let origin = camera_transform.translation();
+            let forward = camera_transform.forward();
+            if forward.y >= 0.0 {
+                return None;
+            }
+            let focus = origin + forward * (-origin.y / forward.y);
+            let direction = (origin.xz() - focus.xz()).normalize_or_zero();
+            Some((focus.xz(), direction))
+        });
+
+        for (mut lowered, transform, segment) in &mut walls {
+            let value = match view {
+                WallView::Down => true,
+                WallView::Cutaway => cutaway.is_some_and(|(focus, direction)| {
+                    let middle = segment.start.lerp(segment.end, 0.5);
+                    let middle = transform.transform_point(Vec3::new(middle.x, 0.0, middle.y));
+                    (middle.xz() - focus).dot(direction) > 0.0
+                }),
+                WallView::Up => false,
+            };
+            if lowered.0 != value {
+                lowered.0 = value;
+            }
+        }
+    }
+}
+
+/// Indicates if the wall mesh should be generated with the lowered height.
+#[derive(Component, Default)]
+pub(crate) struct Lowered(pub(crate) bool);
@@ -0,0 +1,623 @@
+use anyhow::{ensure, Context, Result};
+use bevy::{
+    color::palettes::css::{RED, WHITE},
+    ecs::entity::MapEntities,
+    math::Vec3Swizzles,
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use itertools::Itertools;
+use leafwing_input_manager::common_conditions::action_just_pressed;
+use serde::{Deserialize, Serialize};
+
+use super::{placing_wall::SNAP_DELTA, Wall, WallBundle, WallTool};
+use crate::{
+    asset::info::scenario_info::ScenarioFeature,
+    game_world::{
+        city::{self, lot::permissions::BuildPermissions, ActiveCity},
+        commands_history::{
+            CommandConfirmation, CommandId, CommandRequest, CommandsHistory, ConfirmableCommand,
+            EntityRecorder, PendingCommand,
+        },
+        mutation_log::{Mutation, MutationKind, MutationRecorder},
+        player_camera::CameraCaster,
+        scenario::ScenarioLocks,
+        spline::SplineSegment,
+    },
+    math::segment::Segment,
+    network::validation::ClientViolation,
+    settings::Action,
+};
+
+/// Draws four connected walls of a rectangular room in a single drag.
+///
+/// Existing walls crossed by the room are split at intersections,
+/// so the whole room can be undone as a single [`RoomCommand`].
+pub(super) struct PlacingRoomPlugin;
+
+impl Plugin for PlacingRoomPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_mapped_client_event::<CommandRequest<RoomCommand>>(ChannelKind::Unordered)
+            .add_systems(
+                Update,
+                (
+                    Self::spawn
+                        .run_if(action_just_pressed(Action::Confirm))
+                        .run_if(not(any_with_component::<PlacingRoom>)),
+                    Self::update_end,
+                    Self::confirm.run_if(action_just_pressed(Action::Confirm)),
+                    Self::cancel.run_if(action_just_pressed(Action::Cancel)),
+                )
+                    .run_if(in_state(WallTool::Room)),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    Self::draw.run_if(in_state(WallTool::Room)),
+                    Self::apply_command
+                        .before(ServerSet::StoreHierarchy)
+                        .run_if(server_or_singleplayer),
+                ),
+            );
+    }
+}
+
+/// Minimum side of a room in meters.
+const MIN_SIZE: f32 = 1.0;
+
+/// Points closer than this distance are considered the same when splitting walls.
+const MERGE_DELTA: f32 = 0.05;
+
+impl PlacingRoomPlugin {
+    fn spawn(
+        camera_caster: CameraCaster,
+        mut commands: Commands,
+        permissions: BuildPermissions,
+        walls: Query<(&Parent, &SplineSegment), With<Wall>>,
+        cities: Query<Entity, With<ActiveCity>>,
+    ) {
+        let Some(point) = camera_caster.intersect_ground().map(|point| point.xz()) else {
+            return;
+        };
+
+        let city_entity = cities.single();
+        if !permissions.locally_allowed(city_entity, point) {
+            debug!("ignoring room spawning without build rights");
+            return;
+        }
+
+        let point = snap(point, city_entity, &walls);
+        info!("spawning new room");
+        commands.entity(city_entity).with_children(|parent| {
+            parent.spawn((
+                StateScoped(WallTool::Room),
+                PlacingRoom {
+                    start: point,
+                    end: point,
+                },
+            ));
+        });
+    }
+
+    fn update_end(
+        camera_caster: CameraCaster,
+        mut placing_rooms: Query<(&mut PlacingRoom, &Parent)>,
+        walls: Query<(&Parent, &SplineSegment), With<Wall>>,
+    ) {
+        let Ok((mut room, parent)) = placing_rooms.get_single_mut() else {
+            return;
+        };
+        let Some(point) = camera_caster.intersect_ground().map(|point| point.xz()) else {
+            return;
+        };
+
+        let point = snap(point, **parent, &walls);
+        if room.end != point {
+            trace!("updating room end to `{point:?}`");
+            room.end = point;
+        }
+    }
+
+    fn confirm(
+        mut commands: Commands,
+        mut history: CommandsHistory,
+        permissions: BuildPermissions,
+        placing_rooms: Query<(Entity, &Parent, &PlacingRoom)>,
+        walls: Query<(Entity, &Parent, &SplineSegment), With<Wall>>,
+    ) {
+        let Ok((entity, parent, room)) = placing_rooms.get_single() else {
+            return;
+        };
+        if !room.is_valid() {
+            debug!("ignoring confirmation of too small room");
+            return;
+        }
+
+        let city_walls: Vec<_> = walls
+            .iter()
+            .filter(|(_, wall_parent, _)| *wall_parent == parent)
+            .map(|(entity, _, &segment)| (entity, *segment))
+            .collect();
+        let plan = RoomPlan::new(room.corners(), &city_walls);
+        if !plan
+            .walls
+            .iter()
+            .flat_map(|segment| segment.points())
+            .all(|point| permissions.locally_allowed(**parent, point))
+        {
+            debug!("ignoring room confirmation without build rights");
+            return;
+        }
+
+        info!(
+            "confirming room with {} new walls and {} split walls",
+            plan.walls.len(),
+            plan.split.len()
+        );
+        history.push_pending(RoomCommand::Build {
+            city_entity: **parent,
+            walls: plan.walls,
+            split: plan.split,
+        });
+        commands.entity(entity).despawn();
+    }
+
+    fn cancel(mut commands: Commands, placing_rooms: Query<Entity, With<PlacingRoom>>) {
+        if let Ok(entity) = placing_rooms.get_single() {
+            debug!("cancelling room placing");
+            commands.entity(entity).despawn();
+        }
+    }
+
+    fn draw(
+        mut gizmos: Gizmos,
+        permissions: BuildPermissions,
+        placing_rooms: Query<(&Parent, &PlacingRoom)>,
+        cities: Query<&GlobalTransform>,
+    ) {
+        let Ok((parent, room)) = placing_rooms.get_single() else {
+            return;
+        };
+        let transform = cities.get(**parent).unwrap();
+
+        let allowed = room
+            .corners()
+            .into_iter()
+            .all(|point| permissions.locally_allowed(**parent, point));
+        let color = if room.is_valid() && allowed {
+            WHITE
+        } else {
+            RED
+        };
+
+        let corners = room
+            .corners()
+            .map(|point| transform.transform_point(point.extend(0.0).xzy()));
+        gizmos.linestrip(corners.into_iter().chain([corners[0]]), color);
+    }
+
+    fn apply_command(
+        mut commands: Commands,
+        mut request_events: EventReader<FromClient<CommandRequest<RoomCommand>>>,
+        mut confirm_events: EventWriter<ToClients<CommandConfirmation>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        locks: ScenarioLocks,
+        permissions: BuildPermissions,
+        walls: Query<(&Parent, &SplineSegment), With<Wall>>,
+    ) {
+        for FromClient { client_id, event } in request_events.read().cloned() {
+            if let Err(violation) = locks.check(ScenarioFeature::Building, client_id) {
+                violation_events.send(violation);
+                continue;
+            }
+
+            if let Err(error) = event.command.validate(&walls, |city_entity, point| {
+                permissions.allowed(client_id, city_entity, point)
+            }) {
+                error!("unable to apply room command from `{client_id:?}`: {error:#}");
+                continue;
+            }
+
+            recorder.record(client_id, &event);
+            let mut confirmation = CommandConfirmation::new(event.id);
+            let (city_entity, segments, entities) = match event.command {
+                RoomCommand::Build {
+                    city_entity,
+                    walls: segments,
+                    split,
+                } => {
+                    info!("`{client_id:?}` builds room");
+                    (city_entity, segments, split)
+                }
+                RoomCommand::Revert {
+                    city_entity,
+                    walls: wall_entities,
+                    split,
+                } => {
+                    info!("`{client_id:?}` reverts room");
+                    (city_entity, split, wall_entities)
+                }
+            };
+
+            for entity in entities {
+                commands.entity(entity).despawn();
+            }
+            commands.entity(city_entity).with_children(|parent| {
+                for segment in segments {
+                    let entity = parent.spawn(WallBundle::new(segment)).id();
+                    confirmation.entities.push(entity);
+                }
+            });
+
+            confirm_events.send(ToClients {
+                mode: SendMode::Direct(client_id),
+                event: confirmation,
+            });
+        }
+    }
+}
+
+/// Returns an existing wall vertex if it is within the [`SNAP_DELTA`] distance.
+fn snap(
+    point: Vec2,
+    city_entity: Entity,
+    walls: &Query<(&Parent, &SplineSegment), With<Wall>>,
+) -> Vec2 {
+    walls
+        .iter()
+        .filter(|(parent, _)| ***parent == city_entity)
+        .flat_map(|(_, segment)| segment.points())
+        .find(|vertex| vertex.distance(point) < SNAP_DELTA)
+        .unwrap_or(point)
+}
+
+/// Rectangular room that is currently being drawn in city coordinates.
+#[derive(Component)]
+pub struct PlacingRoom {
+    start: Vec2,
+    end: Vec2,
+}
+
+impl PlacingRoom {
+    /// Returns width and depth of the room in meters.
+    pub fn size(&self) -> Vec2 {
+        (self.end - self.start).abs()
+    }
+
+    fn corners(&self) -> [Vec2; 4] {
+        [
+            self.start,
+            Vec2::new(self.end.x, self.start.y),
+            self.end,
+            Vec2::new(self.start.x, self.end.y),
+        ]
+    }
+
+    fn is_valid(&self) -> bool {
+        let size = self.size();
+        size.x >= MIN_SIZE && size.y >= MIN_SIZE
+    }
+}
+
+/// Wall changes needed to merge a room with existing walls.
+#[derive(Default)]
+struct RoomPlan {
+    /// Walls to spawn, including parts of split walls.
+    walls: Vec<Segment>,
+
+    /// Existing walls that the room crosses and which are replaced by their parts.
+    split: Vec<Entity>,
+}
+
+impl RoomPlan {
+    fn new(corners: [Vec2; 4], walls: &[(Entity, Segment)]) -> Self {
+        let edges: Vec<_> = corners
+            .into_iter()
+            .circular_tuple_windows()
+            .map(|(start, end)| Segment::new(start, end))
+            .collect();
+
+        let mut plan = Self::default();
+        for &edge in &edges {
+            let mut points = Vec::new();
+            for (_, wall) in walls {
+                if edge.intersects(*wall) {
+                    points.extend(edge.line_intersection(*wall));
+                }
+                points.extend(
+                    wall.points()
+                        .into_iter()
+                        .filter(|&point| edge.contains(point)),
+                );
+            }
+
+            for part in split(edge, points) {
+                // Skip parts that go along existing walls.
+                let middle = part.start.lerp(part.end, 0.5);
+                if walls.iter().all(|(_, wall)| !wall.contains(middle)) {
+                    plan.walls.push(part);
+                }
+            }
+        }
+
+        for &(entity, wall) in walls {
+            let mut points = Vec::new();
+            for edge in &edges {
+                if edge.intersects(wall) {
+                    points.extend(edge.line_intersection(wall));
+                }
+                points.extend(
+                    edge.points()
+                        .into_iter()
+                        .filter(|&point| wall.contains(point)),
+                );
+            }
+
+            let parts = split(wall, points);
+            if parts.len() > 1 {
+                plan.split.push(entity);
+                plan.walls.extend(parts);
+            }
+        }
+
+        plan
+    }
+}
+
+/// Splits a segment at the given points.
+///
+/// Points near segment ends or each other are ignored.
+fn split(segment: Segment, points: Vec<Vec2>) -> Vec<Segment> {
+    let mut points: Vec<_> = points
+        .into_iter()
+        .filter(|point| {
+            point.distance(segment.start) >= MERGE_DELTA
+                && point.distance(segment.end) >= MERGE_DELTA
+        })
+        .collect();
+    points.sort_by(|a, b| {
+        segment
+            .start
+            .distance_squared(*a)
+            .total_cmp(&segment.start.distance_squared(*b))
+    });
+    points.dedup_by(|a, b| a.distance(*b) < MERGE_DELTA);
+
+    [segment.start]
+        .into_iter()
+        .chain(points)
+        .chain([segment.end])
+        .tuple_windows()
+        .map(|(start, end)| Segment::new(start, end))
+        .collect()
+}
+
+/// Builds a room as a single undoable step.
+///
+/// Entities are recorded in the same order for both variants:
+/// split walls first, then walls of the room.
+#[derive(Clone, Deserialize, Serialize)]
+enum RoomCommand {
+    /// Despawns split walls and spawns new ones.
+    ///
+    /// Spawned entities are confirmed in the same order as segments.
+    Build {
+        city_entity: Entity,
+        walls: Vec<Segment>,
+        split: Vec<Entity>,
+    },
+    /// Despawns walls of the room and restores split walls.
+    ///
+    /// Spawned entities are confirmed in the same order as segments.
+    Revert {
+        city_entity: Entity,
+        walls: Vec<Entity>,
+        split: Vec<Segment>,
+    },
+}
+
+impl RoomCommand {
+    /// Checks that all affected walls belong to the city and can be built.
+    fn validate(
+        &self,
+        walls: &Query<(&Parent, &SplineSegment), With<Wall>>,
+        allowed: impl Fn(Entity, Vec2) -> bool,
+    ) -> Result<()> {
+        let (city_entity, segments, entities) = match self {
+            Self::Build {
+                city_entity,
+                walls,
+                split,
+            } => (*city_entity, walls, split),
+            Self::Revert {
+                city_entity,
+                walls,
+                split,
+            } => (*city_entity, split, walls),
+        };
+
+        let mut points: Vec<_> = segments
+            .iter()
+            .flat_map(|segment| segment.points())
+            .collect();
+        for &entity in entities {
+            let (parent, segment) = walls
+                .get(entity)
+                .with_context(|| format!("wall `{entity}` doesn't exist"))?;
+            ensure!(
+                **parent == city_entity,
+                "wall `{entity}` belongs to another city"
+            );
+            points.extend(segment.points());
+        }
+
+        for point in points {
+            ensure!(
+                city::within_city(point),
+                "point {point} is outside of the city"
+            );
+            ensure!(allowed(city_entity, point), "no build rights at {point}");
+        }
+
+        Ok(())
+    }
+}
+
+impl Mutation for RoomCommand {
+    const TARGET: &'static str = "room";
+
+    fn kind(&self) -> MutationKind {
+        match self {
+            Self::Build { .. } => MutationKind::Spawn,
+            Self::Revert { .. } => MutationKind::Delete,
+        }
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        None
+    }
+}
+
+impl PendingCommand for RoomCommand {
+    fn apply(
+        self: Box<Self>,
+        id: CommandId,
+        mut recorder: EntityRecorder,
+        world: &mut World,
+    ) -> Box<dyn ConfirmableCommand> {
+        let segments = |entities: &[Entity]| -> Vec<Segment> {
+            entities
+                .iter()
+                .map(|&entity| **world.get::<SplineSegment>(entity).unwrap())
+                .collect()
+        };
+
+        let reverse_command = match &*self {
+            Self::Build {
+                city_entity,
+                walls,
+                split,
+            } => {
+                for &entity in split {
+                    recorder.record(entity);
+                }
+                Self::Revert {
+                    city_entity: *city_entity,
+                    // Correct entities will be set after the server confirmation.
+                    walls: vec![Entity::PLACEHOLDER; walls.len()],
+                    split: segments(split),
+                }
+            }
+            Self::Revert {
+                city_entity,
+                walls,
+                split,
+            } => {
+                recorder.skip(split.len());
+                for &entity in walls {
+                    recorder.record(entity);
+                }
+                Self::Build {
+                    city_entity: *city_entity,
+                    walls: segments(walls),
+                    // Correct entities will be set after the server confirmation.
+                    split: vec![Entity::PLACEHOLDER; split.len()],
+                }
+            }
+        };
+
+        world.send_event(CommandRequest { id, command: *self });
+
+        Box::new(reverse_command)
+    }
+}
+
+impl ConfirmableCommand for RoomCommand {
+    fn confirm(
+        mut self: Box<Self>,
+        mut recorder: EntityRecorder,
+        confirmation: CommandConfirmation,
+    ) -> Box<dyn PendingCommand> {
+        let entities = match &mut *self {
+            Self::Build { split, .. } => split,
+            Self::Revert { walls, split, .. } => {
+                recorder.skip(split.len());
+                walls
+            }
+        };
+        assert_eq!(
+            entities.len(),
+            confirmation.entities.len(),
+            "confirmation for room should contain all spawned walls"
+        );
+        entities.copy_from_slice(&confirmation.entities);
+        for &entity in entities.iter() {
+            recorder.record(entity);
+        }
+
+        self
+    }
+}
+
+impl MapEntities for RoomCommand {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        match self {
+            Self::Build {
+                city_entity, split, ..
+            } => {
+                *city_entity = entity_mapper.map_entity(*city_entity);
+                for entity in split {
+                    *entity = entity_mapper.map_entity(*entity);
+                }
+            }
+            Self::Revert {
+                city_entity, walls, ..
+            } => {
+                *city_entity = entity_mapper.map_entity(*city_entity);
+                for entity in walls {
+                    *entity = entity_mapper.map_entity(*entity);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_city() {
+        let plan = RoomPlan::new(ROOM, &[]);
+        assert_eq!(plan.walls.len(), 4);
+        assert!(plan.split.is_empty());
+    }
+
+    #[test]
+    fn crossing_wall() {
+        let wall = Segment::new(Vec2::new(2.0, -2.0), Vec2::new(2.0, 6.0));
+        let plan = RoomPlan::new(ROOM, &[(Entity::PLACEHOLDER, wall)]);
+        assert_eq!(plan.split, [Entity::PLACEHOLDER]);
+        assert_eq!(
+            plan.walls.len(),
+            6 + 3,
+            "two edges and the wall should be split at intersections"
+        );
+    }
+
+    #[test]
+    fn shared_edge() {
+        let wall = Segment::new(Vec2::new(0.0, 0.0), Vec2::new(4.0, 0.0));
+        let plan = RoomPlan::new(ROOM, &[(Entity::PLACEHOLDER, wall)]);
+        assert!(plan.split.is_empty());
+        assert_eq!(plan.walls.len(), 3, "edge along the wall should be skipped");
+    }
+
+    const ROOM: [Vec2; 4] = [
+        Vec2::new(0.0, 0.0),
+        Vec2::new(4.0, 0.0),
+        Vec2::new(4.0, 4.0),
+        Vec2::new(0.0, 4.0),
+    ];
+}
@@ -1,24 +1,27 @@
+use std::f32::consts::FRAC_PI_4;
+
 use avian3d::prelude::*;
-use bevy::{
-    color::palettes::css::{RED, WHITE},
-    math::Vec3Swizzles,
-    prelude::*,
-    render::view::NoFrustumCulling,
-};
-use leafwing_input_manager::common_conditions::action_just_pressed;
+use bevy::{math::Vec3Swizzles, prelude::*, render::view::NoFrustumCulling};
+use leafwing_input_manager::{common_conditions::action_just_pressed, prelude::ActionState};
 
-use super::{Wall, WallCommand, WallMaterial, WallTool};
+use super::{Wall, WallCommand, WallMaterial, WallTool, MAX_GROUND_DIFF};
 use crate::{
     game_world::{
-        city::ActiveCity,
+        city::{lot::permissions::BuildPermissions, ActiveCity, GroundSampler},
         commands_history::{CommandsHistory, PendingDespawn},
-        family::building::{wall::Apertures, BuildingMode},
+        family::{
+            building::{
+                wall::{self, Apertures},
+                BuildingMode,
+            },
+            Budget, SelectedFamily,
+        },
         hover::{HoverPlugin, Hovered},
         player_camera::CameraCaster,
-        spline::{dynamic_mesh::DynamicMesh, PointKind, SplineSegment},
+        spline::{dynamic_mesh::DynamicMesh, PointKind, SplinePoint, SplineSegment},
         Layer,
     },
-    ghost::Ghost,
+    ghost::{Ghost, GhostMaterial},
     math::segment::Segment,
     settings::Action,
 };
@@ -40,7 +43,7 @@ impl Plugin for PlacingWallPlugin {
                         .run_if(not(any_with_component::<PlacingWall>)),
                     (
                         Self::update_end,
-                        Self::update_material,
+                        Self::update_validity,
                         Self::confirm.run_if(action_just_pressed(Action::Confirm)),
                         Self::delete.run_if(action_just_pressed(Action::Delete)),
                         Self::cancel.run_if(action_just_pressed(Action::Cancel)),
@@ -51,19 +54,32 @@ impl Plugin for PlacingWallPlugin {
     }
 }
 
-const SNAP_DELTA: f32 = 0.5;
+pub(super) const SNAP_DELTA: f32 = 0.5;
+
+/// Step for wall lengths along the axes while snapping directions.
+const HALF_TILE: f32 = 0.5;
 
 impl PlacingWallPlugin {
     fn pick(
         mut commands: Commands,
         wall_material: Res<WallMaterial>,
         mut meshes: ResMut<Assets<Mesh>>,
+        permissions: BuildPermissions,
         walls: Query<(Entity, &Parent, &SplineSegment, &Hovered), With<Wall>>,
     ) {
         let Ok((entity, parent, &segment, hovered)) = walls.get_single() else {
             return;
         };
 
+        if !segment
+            .points()
+            .into_iter()
+            .all(|point| permissions.locally_allowed(**parent, point))
+        {
+            debug!("ignoring picking of `{entity}` without build rights");
+            return;
+        }
+
         const PICK_DELTA: f32 = 0.4;
         let point = hovered.xz();
         let kind = if segment.start.distance(point) < PICK_DELTA {
@@ -93,7 +109,9 @@ impl PlacingWallPlugin {
         mut commands: Commands,
         wall_material: Res<WallMaterial>,
         mut meshes: ResMut<Assets<Mesh>>,
+        permissions: BuildPermissions,
         walls: Query<(&Parent, &SplineSegment), With<Wall>>,
+        points: Query<(&Parent, &Transform), With<SplinePoint>>,
         cities: Query<Entity, With<ActiveCity>>,
     ) {
         let Some(point) = camera_caster.intersect_ground().map(|point| point.xz()) else {
@@ -101,12 +119,22 @@ impl PlacingWallPlugin {
         };
 
         let city_entity = cities.single();
+        if !permissions.locally_allowed(city_entity, point) {
+            debug!("ignoring wall spawning without build rights");
+            return;
+        }
 
         // Use an existing point if it is within the `SNAP_DELTA` distance.
         let point = walls
             .iter()
             .filter(|(parent, _)| ***parent == city_entity)
             .flat_map(|(_, segment)| segment.points())
+            .chain(
+                points
+                    .iter()
+                    .filter(|(parent, _)| ***parent == city_entity)
+                    .map(|(_, transform)| transform.translation.xz()),
+            )
             .find(|vertex| vertex.distance(point) < SNAP_DELTA)
             .unwrap_or(point);
 
@@ -121,39 +149,68 @@ impl PlacingWallPlugin {
         });
     }
 
-    fn update_material(
-        mut materials: ResMut<Assets<StandardMaterial>>,
+    /// Checks build rights, collisions, ground slope and price.
+    fn update_validity(
+        permissions: BuildPermissions,
+        ground_sampler: GroundSampler,
         mut placing_walls: Query<
-            (&mut Handle<StandardMaterial>, &CollidingEntities),
-            (Changed<CollidingEntities>, With<PlacingWall>),
+            (
+                &mut GhostMaterial,
+                &CollidingEntities,
+                &Parent,
+                &SplineSegment,
+                &PlacingWall,
+            ),
+            Or<(Changed<CollidingEntities>, Changed<SplineSegment>)>,
         >,
+        walls: Query<&SplineSegment, (With<Wall>, Without<PlacingWall>)>,
+        cities: Query<&GlobalTransform>,
+        families: Query<&Budget, With<SelectedFamily>>,
     ) {
-        let Ok((mut material_handle, colliding_entities)) = placing_walls.get_single_mut() else {
+        let Ok((mut ghost_material, colliding_entities, parent, &segment, &placing_wall)) =
+            placing_walls.get_single_mut()
+        else {
             return;
         };
 
-        let mut material = materials
-            .get(&*material_handle)
-            .cloned()
-            .expect("material should be preloaded");
-
-        let color = if colliding_entities.is_empty() {
-            WHITE.into()
-        } else {
-            RED.into()
+        let permitted = segment
+            .points()
+            .into_iter()
+            .all(|point| permissions.locally_allowed(**parent, point));
+
+        let city_transform = cities
+            .get(**parent)
+            .expect("walls should have a city parent");
+        let flat = ground_sampler
+            .segment_height_diff(city_transform, *segment)
+            .is_some_and(|diff| diff <= MAX_GROUND_DIFF);
+
+        let price = match placing_wall {
+            PlacingWall::Spawning => wall::wall_price(*segment),
+            PlacingWall::MovingPoint { entity, .. } => {
+                let old_price = walls
+                    .get(entity)
+                    .map(|old_segment| wall::wall_price(**old_segment))
+                    .unwrap_or_default();
+                wall::wall_price(*segment).saturating_sub(old_price)
+            }
         };
-        debug!("changing base color to `{color:?}`");
+        // Walls are charged on family lots, so only check when a family is building.
+        let affordable = families
+            .get_single()
+            .map_or(true, |budget| **budget >= price);
 
-        material.alpha_mode = AlphaMode::Add;
-        material.base_color = color;
-
-        *material_handle = materials.add(material);
+        ghost_material.set_if_neq(GhostMaterial {
+            allowed: colliding_entities.is_empty() && permitted && flat && affordable,
+        });
     }
 
     fn update_end(
         camera_caster: CameraCaster,
+        action_state: Res<ActionState<Action>>,
         mut placing_walls: Query<(&mut SplineSegment, &Parent, &PlacingWall)>,
         walls: Query<(&Parent, &SplineSegment), (With<Wall>, Without<PlacingWall>)>,
+        points: Query<(&Parent, &Transform), With<SplinePoint>>,
     ) {
         let Ok((mut segment, placing_parent, &placing_wall)) = placing_walls.get_single_mut()
         else {
@@ -169,10 +226,27 @@ impl PlacingWallPlugin {
             .iter()
             .filter(|(parent, _)| *parent == placing_parent)
             .flat_map(|(_, segment)| segment.points())
-            .find(|vertex| vertex.distance(point) < SNAP_DELTA)
-            .unwrap_or(point);
+            .chain(
+                points
+                    .iter()
+                    .filter(|(parent, _)| *parent == placing_parent)
+                    .map(|(_, transform)| transform.translation.xz()),
+            )
+            .find(|vertex| vertex.distance(point) < SNAP_DELTA);
 
         let point_kind = placing_wall.point_kind();
+        // Snap to 45° directions for diagonal walls, unless fine adjustment is requested.
+        let vertex = vertex.unwrap_or_else(|| {
+            if action_state.pressed(&Action::FineAdjustment) {
+                point
+            } else {
+                let origin = match point_kind {
+                    PointKind::Start => segment.end,
+                    PointKind::End => segment.start,
+                };
+                snap_direction(origin, point)
+            }
+        });
 
         trace!("updating `{point_kind:?}` to `{vertex:?}`");
         match point_kind {
@@ -184,12 +258,22 @@ impl PlacingWallPlugin {
     fn confirm(
         mut commands: Commands,
         mut history: CommandsHistory,
+        permissions: BuildPermissions,
         mut placing_walls: Query<(Entity, &Parent, &PlacingWall, &SplineSegment)>,
     ) {
         let Ok((entity, parent, &placing_wall, &segment)) = placing_walls.get_single_mut() else {
             return;
         };
 
+        if !segment
+            .points()
+            .into_iter()
+            .all(|point| permissions.locally_allowed(**parent, point))
+        {
+            debug!("ignoring confirmation without build rights");
+            return;
+        }
+
         info!("configrming {placing_wall:?}");
         let command_id = match placing_wall {
             PlacingWall::Spawning => history.push_pending(WallCommand::Create {
@@ -249,6 +333,25 @@ impl PlacingWallPlugin {
     }
 }
 
+/// Snaps the direction from `origin` to `point` to a multiple of 45°.
+///
+/// The length is rounded to [`HALF_TILE`] along the axes,
+/// so diagonal walls also end on the half-tile grid.
+fn snap_direction(origin: Vec2, point: Vec2) -> Vec2 {
+    let disp = point - origin;
+    if disp == Vec2::ZERO {
+        return point;
+    }
+
+    let angle = (disp.to_angle() / FRAC_PI_4).round() * FRAC_PI_4;
+    let dir = Vec2::from_angle(angle);
+    let axis_dir = (dir / dir.abs().max_element()).round();
+    let axis_len = disp.dot(dir) * dir.abs().max_element();
+    let axis_len = (axis_len / HALF_TILE).round() * HALF_TILE;
+
+    origin + axis_dir * axis_len
+}
+
 #[derive(Bundle)]
 struct PlacingWallBundle {
     name: Name,
@@ -258,6 +361,7 @@ struct PlacingWallBundle {
     apertures: Apertures,
     collider: Collider,
     collision_layers: CollisionLayers,
+    ghost_material: GhostMaterial,
     no_culling: NoFrustumCulling,
     pbr_bundle: PbrBundle,
 }
@@ -287,8 +391,10 @@ impl PlacingWallBundle {
                     Layer::PlacingObject,
                     Layer::Road,
                     Layer::PlacingRoad,
+                    Layer::Water,
                 ],
             ),
+            ghost_material: Default::default(),
             no_culling: NoFrustumCulling,
             pbr_bundle: PbrBundle {
                 material,
@@ -314,3 +420,21 @@ impl PlacingWall {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn axis_direction() {
+        let point = snap_direction(Vec2::ZERO, Vec2::new(2.2, 0.3));
+        assert_eq!(point, Vec2::new(2.0, 0.0));
+    }
+
+    #[test]
+    fn diagonal_direction() {
+        let origin = Vec2::new(1.0, 1.0);
+        let point = snap_direction(origin, origin + Vec2::new(-1.4, 1.6));
+        assert_eq!(point, origin + Vec2::new(-1.5, 1.5));
+    }
+}
@@ -4,27 +4,36 @@ use avian3d::prelude::*;
 use bevy::prelude::*;
 use itertools::MinMaxResult;
 
-use super::{Aperture, Apertures};
+use super::{Aperture, ApertureError, Apertures};
 use crate::{
-    game_world::spline::{dynamic_mesh::DynamicMesh, PointKind, SplineConnections, SplineSegment},
-    math::{segment::Segment, triangulator::Triangulator},
+    game_world::spline::{
+        dynamic_mesh::DynamicMesh, PointConnections, PointKind, SplineConnections, SplineSegment,
+    },
+    math::{
+        segment::{OffsetError, Segment},
+        triangulator::Triangulator,
+    },
 };
 
 const WIDTH: f32 = 0.15;
-const HEIGHT: f32 = 2.8;
+pub(crate) const HEIGHT: f32 = 2.8;
+/// Height of walls lowered by [`WallView`](crate::settings::WallView).
+pub(crate) const LOWERED_HEIGHT: f32 = 0.3;
 pub(crate) const HALF_WIDTH: f32 = WIDTH / 2.0;
 
-pub(super) fn generate(
+pub(crate) fn generate(
     mesh: &mut DynamicMesh,
     segment: SplineSegment,
     connections: &SplineConnections,
+    point_connections: &PointConnections,
     apertures: &Apertures,
+    height: f32,
     triangulator: &mut Triangulator,
-) {
+) -> Result<(), OffsetError> {
     mesh.clear();
 
     if segment.start == segment.end {
-        return;
+        return Ok(());
     }
 
     let disp = segment.displacement();
@@ -34,22 +43,38 @@ pub(super) fn generate(
 
     let start_connections = connections.minmax_angles(disp, PointKind::Start);
     let (start_left, start_right) =
-        segment.offset_points(width_disp, HALF_WIDTH, start_connections);
+        segment.offset_points(width_disp, HALF_WIDTH, start_connections)?;
 
     let end_connections = connections.minmax_angles(-disp, PointKind::End);
     let (end_right, end_left) =
         segment
             .inverse()
-            .offset_points(-width_disp, HALF_WIDTH, end_connections);
+            .offset_points(-width_disp, HALF_WIDTH, end_connections)?;
+    let (start_left, end_left) = segment.uncross(start_left, end_left);
+    let (start_right, end_right) = segment.uncross(start_right, end_right);
+
+    let apertures: Vec<_> = valid_apertures(*segment, apertures)
+        // Windows don't fit into lowered walls.
+        .filter(|aperture| !aperture.hole || aperture.bounds().max.y < height)
+        .collect();
+
+    // Clippings up to the top, like doors in lowered walls, leave a gap.
+    let gaps: Vec<_> = apertures
+        .iter()
+        .filter(|aperture| !aperture.hole)
+        .map(|aperture| aperture.bounds())
+        .filter(|bounds| bounds.max.y >= height)
+        .map(|bounds| (bounds.min.x, bounds.max.x))
+        .collect();
 
     generate_top(
         mesh,
         *segment,
-        start_left,
-        start_right,
-        end_left,
-        end_right,
+        [start_left, start_right, end_right, end_left],
+        width_disp,
+        &gaps,
         rotation_mat,
+        height,
     );
 
     let inverse_winding = angle.abs() < FRAC_PI_2;
@@ -59,86 +84,115 @@ pub(super) fn generate(
     generate_side(
         mesh,
         *segment,
-        apertures,
+        &apertures,
         triangulator,
         start_right,
         end_right,
         -width_disp,
         rotation_mat,
         quat,
+        height,
     );
 
     triangulator.set_inverse_winding(!inverse_winding);
     generate_side(
         mesh,
         *segment,
-        apertures,
+        &apertures,
         triangulator,
         start_left,
         end_left,
         width_disp,
         rotation_mat,
         quat,
+        height,
     );
 
+    // Ends inside pillars are hidden, so caps are not needed.
     match start_connections {
         MinMaxResult::OneElement(_) => (),
-        MinMaxResult::NoElements => generate_front(mesh, start_left, start_right, disp),
-        MinMaxResult::MinMax(_, _) => generate_start_connection(mesh, *segment),
+        MinMaxResult::NoElements => {
+            if !point_connections.contains(&PointKind::Start) {
+                generate_front(mesh, start_left, start_right, disp, height)
+            }
+        }
+        MinMaxResult::MinMax(_, _) => generate_start_connection(mesh, *segment, height),
     }
 
     match end_connections {
         MinMaxResult::OneElement(_) => (),
-        MinMaxResult::NoElements => generate_back(mesh, end_left, end_right, disp),
-        MinMaxResult::MinMax(_, _) => generate_end_connection(mesh, *segment, rotation_mat),
+        MinMaxResult::NoElements => {
+            if !point_connections.contains(&PointKind::End) {
+                generate_back(mesh, end_left, end_right, disp, height)
+            }
+        }
+        MinMaxResult::MinMax(_, _) => generate_end_connection(mesh, *segment, rotation_mat, height),
     }
+
+    Ok(())
 }
 
+/// Generates the top of the wall.
+///
+/// Corners are always the first 4 vertices in order: start left, start right, end right and end left.
+/// Gaps are specified as distances along the wall.
 fn generate_top(
     mesh: &mut DynamicMesh,
     segment: Segment,
-    start_left: Vec2,
-    start_right: Vec2,
-    end_left: Vec2,
-    end_right: Vec2,
+    corners: [Vec2; 4],
+    width_disp: Vec2,
+    gaps: &[(f32, f32)],
     rotation_mat: Mat2,
+    height: f32,
 ) {
-    mesh.positions.push([start_left.x, HEIGHT, start_left.y]);
-    mesh.positions.push([start_right.x, HEIGHT, start_right.y]);
-    mesh.positions.push([end_right.x, HEIGHT, end_right.y]);
-    mesh.positions.push([end_left.x, HEIGHT, end_left.y]);
-
-    mesh.uvs
-        .push((rotation_mat * (start_left - segment.start)).into());
-    mesh.uvs
-        .push((rotation_mat * (start_right - segment.start)).into());
-    mesh.uvs
-        .push((rotation_mat * (end_right - segment.start)).into());
-    mesh.uvs
-        .push((rotation_mat * (end_left - segment.start)).into());
+    let dir = segment.displacement().normalize();
+    let mut points = corners.to_vec();
+    for &(min, max) in gaps {
+        for distance in [min, max] {
+            let center = segment.start + dir * distance;
+            points.push(center + width_disp);
+            points.push(center - width_disp);
+        }
+    }
 
-    mesh.normals.extend_from_slice(&[[0.0, 1.0, 0.0]; 4]);
+    for point in points {
+        mesh.positions.push([point.x, height, point.y]);
+        mesh.uvs
+            .push((rotation_mat * (point - segment.start)).into());
+        mesh.normals.push([0.0, 1.0, 0.0]);
+    }
 
-    mesh.indices.push(0);
-    mesh.indices.push(3);
-    mesh.indices.push(1);
-    mesh.indices.push(1);
-    mesh.indices.push(3);
-    mesh.indices.push(2);
+    let mut push_quad = |start_left, start_right, end_left, end_right| {
+        mesh.indices.push(start_left);
+        mesh.indices.push(end_left);
+        mesh.indices.push(start_right);
+        mesh.indices.push(start_right);
+        mesh.indices.push(end_left);
+        mesh.indices.push(end_right);
+    };
+
+    let (mut left, mut right) = (0, 1);
+    for index in 0..gaps.len() as u32 {
+        let gap_start = 4 + index * 4;
+        push_quad(left, right, gap_start, gap_start + 1);
+        (left, right) = (gap_start + 2, gap_start + 3);
+    }
+    push_quad(left, right, 3, 2);
 }
 
 fn generate_side(
     mesh: &mut DynamicMesh,
     segment: Segment,
-    apertures: &Apertures,
+    apertures: &[&Aperture],
     triangulator: &mut Triangulator,
     start_side: Vec2,
     end_side: Vec2,
     width_disp: Vec2,
     rotation_mat: Mat2,
     quat: Quat,
+    height: f32,
 ) {
-    let vertices_start = mesh.vertices_count();
+    let mut vertices_start = mesh.vertices_count();
 
     mesh.positions.push([start_side.x, 0.0, start_side.y]);
     let start_uv = rotation_mat * (start_side - segment.start);
@@ -146,7 +200,11 @@ fn generate_side(
     let normal = [width_disp.x, 0.0, width_disp.y];
     mesh.normals.push(normal);
 
+    // Clippings up to the top split the side into separate parts.
+    // Only the first part is closed by the start top vertex.
+    let mut start_top = true;
     for aperture in apertures.iter().filter(|aperture| !aperture.hole) {
+        let cutout_start = mesh.positions.len();
         generate_apertures(
             mesh,
             segment,
@@ -155,19 +213,55 @@ fn generate_side(
             width_disp,
             rotation_mat,
             quat,
+            height,
         );
+
+        let reaches_top = |position: &Vec2| aperture.translation.y + position.y >= height;
+        let Some(first_top) = aperture.cutout.iter().position(reaches_top) else {
+            continue;
+        };
+        let last_top = aperture
+            .cutout
+            .iter()
+            .rposition(reaches_top)
+            .expect("cutout should have at least one top point");
+
+        let next_positions = mesh.positions.split_off(cutout_start + last_top);
+        let next_uvs = mesh.uvs.split_off(cutout_start + last_top);
+        mesh.positions.truncate(cutout_start + first_top + 1);
+        mesh.uvs.truncate(cutout_start + first_top + 1);
+        mesh.normals.truncate(cutout_start + first_top + 1);
+        if start_top {
+            mesh.positions.push([start_side.x, height, start_side.y]);
+            mesh.uvs.push([start_uv.x, start_uv.y + height]);
+            mesh.normals.push(normal);
+            start_top = false;
+        }
+        for &index in triangulator.triangulate(&mesh.positions[vertices_start as usize..]) {
+            mesh.indices.push(vertices_start + index);
+        }
+
+        vertices_start = mesh.vertices_count();
+        mesh.normals
+            .extend(std::iter::repeat(normal).take(next_positions.len()));
+        mesh.positions.extend(next_positions);
+        mesh.uvs.extend(next_uvs);
     }
 
     mesh.positions.push([end_side.x, 0.0, end_side.y]);
-    mesh.positions.push([end_side.x, HEIGHT, end_side.y]);
-    mesh.positions.push([start_side.x, HEIGHT, start_side.y]);
+    mesh.positions.push([end_side.x, height, end_side.y]);
 
     let end_uv = rotation_mat * (end_side - segment.start);
     mesh.uvs.push(end_uv.into());
-    mesh.uvs.push([end_uv.x, end_uv.y + HEIGHT]);
-    mesh.uvs.push([start_uv.x, start_uv.y + HEIGHT]);
+    mesh.uvs.push([end_uv.x, end_uv.y + height]);
 
-    mesh.normals.extend_from_slice(&[normal; 3]);
+    mesh.normals.extend_from_slice(&[normal; 2]);
+
+    if start_top {
+        mesh.positions.push([start_side.x, height, start_side.y]);
+        mesh.uvs.push([start_uv.x, start_uv.y + height]);
+        mesh.normals.push(normal);
+    }
 
     let mut last_index = mesh.vertices_count() - vertices_start;
     for aperture in apertures.iter().filter(|aperture| aperture.hole) {
@@ -179,6 +273,7 @@ fn generate_side(
             width_disp,
             rotation_mat,
             quat,
+            height,
         );
 
         triangulator.add_hole(last_index);
@@ -198,8 +293,11 @@ fn generate_apertures(
     width_disp: Vec2,
     rotation_mat: Mat2,
     quat: Quat,
+    height: f32,
 ) {
     for &position in &aperture.cutout {
+        // Clippings can't be higher than the wall.
+        let position = Vec2::new(position.x, position.y.min(height - aperture.translation.y));
         let translated = quat * position.extend(0.0)
             + aperture.translation
             + Vec3::new(width_disp.x, 0.0, width_disp.y);
@@ -213,17 +311,23 @@ fn generate_apertures(
     }
 }
 
-fn generate_front(mesh: &mut DynamicMesh, start_left: Vec2, start_right: Vec2, disp: Vec2) {
+fn generate_front(
+    mesh: &mut DynamicMesh,
+    start_left: Vec2,
+    start_right: Vec2,
+    disp: Vec2,
+    height: f32,
+) {
     let vertices_start = mesh.vertices_count();
 
     mesh.positions.push([start_left.x, 0.0, start_left.y]);
-    mesh.positions.push([start_left.x, HEIGHT, start_left.y]);
-    mesh.positions.push([start_right.x, HEIGHT, start_right.y]);
+    mesh.positions.push([start_left.x, height, start_left.y]);
+    mesh.positions.push([start_right.x, height, start_right.y]);
     mesh.positions.push([start_right.x, 0.0, start_right.y]);
 
     mesh.uvs.push([0.0, 0.0]);
-    mesh.uvs.push([0.0, HEIGHT]);
-    mesh.uvs.push([WIDTH, HEIGHT]);
+    mesh.uvs.push([0.0, height]);
+    mesh.uvs.push([WIDTH, height]);
     mesh.uvs.push([WIDTH, 0.0]);
 
     mesh.normals
@@ -237,18 +341,18 @@ fn generate_front(mesh: &mut DynamicMesh, start_left: Vec2, start_right: Vec2, d
     mesh.indices.push(vertices_start + 3);
 }
 
-fn generate_back(mesh: &mut DynamicMesh, end_left: Vec2, end_right: Vec2, disp: Vec2) {
+fn generate_back(mesh: &mut DynamicMesh, end_left: Vec2, end_right: Vec2, disp: Vec2, height: f32) {
     let vertices_start = mesh.vertices_count();
 
     // Back
     mesh.positions.push([end_left.x, 0.0, end_left.y]);
-    mesh.positions.push([end_left.x, HEIGHT, end_left.y]);
-    mesh.positions.push([end_right.x, HEIGHT, end_right.y]);
+    mesh.positions.push([end_left.x, height, end_left.y]);
+    mesh.positions.push([end_right.x, height, end_right.y]);
     mesh.positions.push([end_right.x, 0.0, end_right.y]);
 
     mesh.uvs.push([0.0, 0.0]);
-    mesh.uvs.push([0.0, HEIGHT]);
-    mesh.uvs.push([WIDTH, HEIGHT]);
+    mesh.uvs.push([0.0, height]);
+    mesh.uvs.push([WIDTH, height]);
     mesh.uvs.push([WIDTH, 0.0]);
 
     mesh.normals.extend_from_slice(&[[disp.x, 0.0, disp.y]; 4]);
@@ -262,12 +366,12 @@ fn generate_back(mesh: &mut DynamicMesh, end_left: Vec2, end_right: Vec2, disp:
 }
 
 /// Inside triangle to fill the gap between 3+ walls.
-fn generate_start_connection(mesh: &mut DynamicMesh, segment: Segment) {
+fn generate_start_connection(mesh: &mut DynamicMesh, segment: Segment, height: f32) {
     let vertices_start = mesh.vertices_count();
 
     // Inside triangle to fill the gap between 3+ walls.
     mesh.positions
-        .push([segment.start.x, HEIGHT, segment.start.y]);
+        .push([segment.start.x, height, segment.start.y]);
     mesh.uvs.push([0.0, 0.0]);
     mesh.normals.push([0.0, 1.0, 0.0]);
 
@@ -277,10 +381,15 @@ fn generate_start_connection(mesh: &mut DynamicMesh, segment: Segment) {
 }
 
 /// Inside triangle to fill the gap between 3+ walls.
-fn generate_end_connection(mesh: &mut DynamicMesh, segment: Segment, rotation_mat: Mat2) {
+fn generate_end_connection(
+    mesh: &mut DynamicMesh,
+    segment: Segment,
+    rotation_mat: Mat2,
+    height: f32,
+) {
     let vertices_start = mesh.vertices_count();
 
-    mesh.positions.push([segment.end.x, HEIGHT, segment.end.y]);
+    mesh.positions.push([segment.end.x, height, segment.end.y]);
     mesh.uvs
         .push((rotation_mat * (segment.end - segment.start)).into());
     mesh.normals.push([0.0, 1.0, 0.0]);
@@ -290,6 +399,42 @@ fn generate_end_connection(mesh: &mut DynamicMesh, segment: Segment, rotation_ma
     mesh.indices.push(2);
 }
 
+/// Returns apertures that can be cut from the wall.
+///
+/// Invalid apertures are skipped to keep the wall geometry intact.
+fn valid_apertures(segment: Segment, apertures: &Apertures) -> impl Iterator<Item = &Aperture> {
+    let wall_length = segment.start.distance(segment.end);
+    let mut clipping_end = f32::MIN;
+    apertures.iter().filter(move |aperture| {
+        let result = aperture.validate(wall_length).and_then(|()| {
+            if aperture.hole {
+                return Ok(());
+            }
+
+            // Apertures are sorted, so it's enough to check only the previous clipping.
+            let bounds = aperture.bounds();
+            if bounds.min.x < clipping_end {
+                return Err(ApertureError::Overlap);
+            }
+            clipping_end = bounds.max.x;
+
+            Ok(())
+        });
+
+        if let Err(error) = result {
+            // Apertures from placing objects are expected to be invalid sometimes.
+            if aperture.placing_object {
+                trace!("ignoring aperture of `{}`: {error}", aperture.object_entity);
+            } else {
+                warn!("ignoring aperture of `{}`: {error}", aperture.object_entity);
+            }
+            return false;
+        }
+
+        true
+    })
+}
+
 /// Generates a simplified collider consists of cuboids.
 ///
 /// Clippings split the collider into separate cuboids.
@@ -303,8 +448,7 @@ pub(super) fn generate_collider(segment: SplineSegment, apertures: &Apertures) -
     let mut indices = Vec::new();
     let mut start = segment.start;
     let dir = segment.displacement().normalize();
-    for aperture in apertures
-        .iter()
+    for aperture in valid_apertures(*segment, apertures)
         .filter(|aperture| !aperture.hole && !aperture.placing_object)
     {
         let first = aperture.cutout.first().expect("apertures can't be empty");
@@ -363,3 +507,182 @@ fn generate_cuboid(vertices: &mut Vec<Vec3>, indices: &mut Vec<[u32; 3]>, start:
     indices.push([last_index + 2, last_index + 6, last_index + 3]);
     indices.push([last_index + 6, last_index + 7, last_index + 3]);
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::game_world::{
+        family::building::wall::tests::{door, door_cutout},
+        spline::tests::random_graph,
+    };
+
+    #[test]
+    fn malformed_apertures_ignored() {
+        let segment = SplineSegment(Segment::new(Vec2::ZERO, Vec2::X * 4.0));
+        let expected = generate_with(segment, Apertures::default());
+
+        let mut apertures = Apertures::default();
+        apertures.insert(door(vec![
+            Vec2::new(-0.4, 0.0),
+            Vec2::new(0.4, 2.0),
+            Vec2::new(-0.4, 2.0),
+            Vec2::new(0.4, 0.0),
+        ]));
+        let mesh = generate_with(segment, apertures);
+
+        assert_eq!(mesh.positions, expected.positions);
+        assert_eq!(mesh.indices, expected.indices);
+    }
+
+    #[test]
+    fn overlapping_apertures_ignored() {
+        let segment = SplineSegment(Segment::new(Vec2::ZERO, Vec2::X * 4.0));
+        let mut apertures = Apertures::default();
+        apertures.insert(door(door_cutout()));
+        let expected = generate_with(segment, apertures);
+
+        let mut apertures = Apertures::default();
+        apertures.insert(door(door_cutout()));
+        apertures.insert(door(door_cutout()));
+        let mesh = generate_with(segment, apertures);
+
+        assert_eq!(mesh.positions, expected.positions);
+        assert_eq!(mesh.indices, expected.indices);
+    }
+
+    #[test]
+    fn lowered_door() {
+        let segment = SplineSegment(Segment::new(Vec2::ZERO, Vec2::X * 4.0));
+        let mut apertures = Apertures::default();
+        apertures.insert(door(door_cutout()));
+        let mut mesh = DynamicMesh::default();
+        generate(
+            &mut mesh,
+            segment,
+            &Default::default(),
+            &Default::default(),
+            &apertures,
+            LOWERED_HEIGHT,
+            &mut Triangulator::default(),
+        )
+        .expect("segment should be valid");
+
+        assert!(mesh
+            .indices
+            .iter()
+            .all(|&index| index < mesh.vertices_count()));
+        let contains = |[a, b, c]: [Vec2; 3], point: Vec2| {
+            let signs = [(a, b), (b, c), (c, a)]
+                .map(|(start, end)| (end - start).perp_dot(point - start).signum());
+            (b - a).perp_dot(c - a) != 0.0 && signs.iter().all(|&sign| sign == signs[0])
+        };
+        for triangle in mesh.indices.chunks_exact(3) {
+            let vertices =
+                [0, 1, 2].map(|index| Vec3::from(mesh.positions[triangle[index] as usize]));
+            assert!(
+                !contains(vertices.map(|vertex| vertex.xy()), Vec2::new(2.0, 0.1)),
+                "sides shouldn't cover the door"
+            );
+            assert!(
+                !contains(vertices.map(|vertex| vertex.xz()), Vec2::new(2.0, 0.0)),
+                "top shouldn't cover the door"
+            );
+        }
+    }
+
+    #[test]
+    fn diagonal_aperture() {
+        let segment = SplineSegment(Segment::new(Vec2::ZERO, Vec2::splat(3.0)));
+        let mut door = door(door_cutout());
+        door.translation = Vec3::new(1.5, 0.0, 1.5);
+        door.distance = door.translation.xz().length();
+        let mut apertures = Apertures::default();
+        apertures.insert(door);
+        let mesh = generate_with(segment, apertures);
+
+        let dir = segment.displacement().normalize();
+        for &[x, _, z] in &mesh.positions {
+            let distance = dir.perp_dot(Vec2::new(x, z)).abs();
+            assert!(
+                distance <= HALF_WIDTH + 1e-4,
+                "vertex ({x}, {z}) should be within the wall width, got {distance}"
+            );
+        }
+    }
+
+    #[test]
+    fn random_graphs() {
+        const EPSILON: f32 = 1e-4;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut mesh = DynamicMesh::default();
+        let mut triangulator = Triangulator::default();
+        for _ in 0..1000 {
+            for (segment, connections) in random_graph(&mut rng) {
+                generate(
+                    &mut mesh,
+                    segment,
+                    &connections,
+                    &Default::default(),
+                    &Default::default(),
+                    HEIGHT,
+                    &mut triangulator,
+                )
+                .expect("segment should be valid");
+
+                assert_eq!(mesh.uvs.len(), mesh.positions.len());
+                assert_eq!(mesh.normals.len(), mesh.positions.len());
+                assert_eq!(mesh.indices.len() % 3, 0);
+                assert!(mesh
+                    .indices
+                    .iter()
+                    .all(|&index| index < mesh.vertices_count()));
+                for (position, normal) in mesh.positions.iter().zip(&mesh.normals) {
+                    assert!(
+                        position.iter().chain(normal).all(|value| value.is_finite()),
+                        "vertex {position:?} with normal {normal:?} should be finite"
+                    );
+                }
+
+                if segment.start == segment.end {
+                    assert!(mesh.positions.is_empty());
+                    continue;
+                }
+
+                // Top vertices should stay on their sides of the segment.
+                let disp = segment.displacement();
+                let side = |index: usize| {
+                    let [x, _, z] = mesh.positions[index];
+                    disp.perp_dot(Vec2::new(x, z) - segment.start)
+                };
+                for (left, right) in [(0, 1), (3, 2)] {
+                    assert!(
+                        side(left) > -EPSILON,
+                        "left vertex should be on the left side"
+                    );
+                    assert!(
+                        side(right) < EPSILON,
+                        "right vertex should be on the right side"
+                    );
+                }
+            }
+        }
+    }
+
+    fn generate_with(segment: SplineSegment, apertures: Apertures) -> DynamicMesh {
+        let mut mesh = DynamicMesh::default();
+        generate(
+            &mut mesh,
+            segment,
+            &Default::default(),
+            &Default::default(),
+            &apertures,
+            HEIGHT,
+            &mut Triangulator::default(),
+        )
+        .expect("segment should be valid");
+        mesh
+    }
+}
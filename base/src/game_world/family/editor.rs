@@ -4,8 +4,13 @@ use bevy::prelude::*;
 
 use crate::{
     asset::collection::Collection,
+    core::transition::{Transition, TransitionRequest},
     game_world::{
-        actor::{human::Human, FirstName, LastName, SelectedActor, Sex},
+        actor::{
+            human::Human,
+            outfit::{CurrentOutfit, Outfits},
+            FirstName, LastName, SelectedActor, Sex,
+        },
         family::{FamilyMembers, SelectedFamilyCreated},
         player_camera::{EnvironmentMap, PlayerCameraBundle},
         WorldState,
@@ -54,7 +59,7 @@ impl EditorPlugin {
     fn play(
         mut commands: Commands,
         mut spawn_select_events: EventReader<SelectedFamilyCreated>,
-        mut world_state: ResMut<NextState<WorldState>>,
+        mut transition_events: EventWriter<TransitionRequest>,
         families: Query<&FamilyMembers>,
     ) {
         for members in families.iter_many(spawn_select_events.read().map(|event| event.0)) {
@@ -63,7 +68,9 @@ impl EditorPlugin {
                 .first()
                 .expect("family should always have at least one member");
             commands.entity(actor_entity).insert(SelectedActor);
-            world_state.set(WorldState::Family);
+            transition_events.send(TransitionRequest::new(Transition::World(
+                WorldState::Family,
+            )));
         }
     }
 
@@ -101,6 +108,8 @@ pub struct EditableActorBundle {
     first_name: FirstName,
     last_name: LastName,
     sex: Sex,
+    outfits: Outfits,
+    current_outfit: CurrentOutfit,
     editable_actor: EditableActor,
     spatial_bundle: SpatialBundle,
 }
@@ -112,6 +121,8 @@ impl Default for EditableActorBundle {
             first_name: Default::default(),
             last_name: Default::default(),
             sex: Default::default(),
+            outfits: Default::default(),
+            current_outfit: Default::default(),
             editable_actor: EditableActor,
             spatial_bundle: SpatialBundle {
                 transform: Transform::from_rotation(Quat::from_rotation_y(PI)), // Rotate towards camera.
@@ -0,0 +1,316 @@
+use std::{collections::VecDeque, fs};
+
+use anyhow::{Context, Result};
+use bevy::{
+    prelude::*, render::view::screenshot::ScreenshotManager, utils::EntityHashMap,
+    window::PrimaryWindow,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use super::Family;
+use crate::{
+    core::GameState,
+    game_paths::GamePaths,
+    game_world::{
+        actor::{job::Promotion, skills::Handiness, Actor},
+        clock::GameClock,
+        WorldName,
+    },
+    message::error_message,
+    settings::Settings,
+};
+
+/// Records notable family events into [`FamilyMemories`].
+///
+/// Other plugins send [`MemoryRecord`] on the server to add a memory.
+pub(super) struct MemoriesPlugin;
+
+impl Plugin for MemoriesPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<FamilyMemories>()
+            .replicate::<FamilyMemories>()
+            .add_event::<MemoryRecord>()
+            .add_event::<SnapshotCapture>()
+            .add_systems(
+                Update,
+                (
+                    Self::init,
+                    Self::record_skills,
+                    Self::record_promotions,
+                    Self::record,
+                    Self::capture_snapshots
+                        .pipe(error_message)
+                        .run_if(on_event::<SnapshotCapture>()),
+                )
+                    .chain()
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+impl MemoriesPlugin {
+    fn init(
+        mut commands: Commands,
+        families: Query<Entity, (With<Family>, Without<FamilyMemories>)>,
+    ) {
+        for entity in &families {
+            debug!("initializing memories for `{entity}`");
+            commands.entity(entity).insert(FamilyMemories::default());
+        }
+    }
+
+    /// Records reached skill levels.
+    ///
+    /// Skills are marked as changed even when they are already at the maximum level,
+    /// so levels are compared with the previously seen ones.
+    fn record_skills(
+        mut record_events: EventWriter<MemoryRecord>,
+        mut levels: Local<EntityHashMap<Entity, u8>>,
+        mut removed_skills: RemovedComponents<Handiness>,
+        actors: Query<(Entity, &Actor, &Name, Ref<Handiness>), Changed<Handiness>>,
+    ) {
+        for entity in removed_skills.read() {
+            levels.remove(&entity);
+        }
+
+        for (entity, actor, name, handiness) in &actors {
+            let previous = levels.insert(entity, **handiness);
+            // Skip initialization and loading.
+            if handiness.is_added() || previous.map_or(true, |level| level >= **handiness) {
+                continue;
+            }
+
+            record_events.send(MemoryRecord {
+                family_entity: actor.family_entity,
+                kind: MemoryKind::Skill,
+                description: format!("{name} reached handiness level {}", **handiness),
+            });
+        }
+    }
+
+    fn record_promotions(
+        mut promotion_events: EventReader<Promotion>,
+        mut record_events: EventWriter<MemoryRecord>,
+        actors: Query<(&Actor, &Name)>,
+    ) {
+        for promotion in promotion_events.read() {
+            let Ok((actor, name)) = actors.get(promotion.actor_entity) else {
+                continue;
+            };
+
+            record_events.send(MemoryRecord {
+                family_entity: actor.family_entity,
+                kind: MemoryKind::Promotion,
+                description: format!("{name} got promoted to level {}", promotion.level),
+            });
+        }
+    }
+
+    fn record(
+        mut record_events: EventReader<MemoryRecord>,
+        mut capture_events: EventWriter<SnapshotCapture>,
+        settings: Res<Settings>,
+        clocks: Query<&GameClock>,
+        mut families: Query<&mut FamilyMemories>,
+    ) {
+        let Ok(clock) = clocks.get_single() else {
+            return;
+        };
+
+        // Memories recorded in the same frame share the same picture
+        // since only one screenshot per window can be requested at once.
+        let mut frame_snapshot = None;
+        for event in record_events.read() {
+            let Ok(mut memories) = families.get_mut(event.family_entity) else {
+                error!("`{}` is not a family with memories", event.family_entity);
+                continue;
+            };
+
+            info!("recording memory for `{}`", event.family_entity);
+            let snapshot = settings.gameplay.memory_snapshots.then(|| {
+                frame_snapshot
+                    .get_or_insert_with(|| {
+                        let file_name = format!("{:016x}.png", rand::random::<u64>());
+                        capture_events.send(SnapshotCapture(file_name.clone()));
+                        file_name
+                    })
+                    .clone()
+            });
+            memories.push(
+                Memory {
+                    day: clock.day(),
+                    hour: clock.hour(),
+                    kind: event.kind,
+                    description: event.description.clone(),
+                    snapshot,
+                },
+                settings.gameplay.memories_capacity,
+            );
+        }
+    }
+
+    fn capture_snapshots(
+        mut capture_events: EventReader<SnapshotCapture>,
+        mut screenshot_manager: ResMut<ScreenshotManager>,
+        game_paths: Res<GamePaths>,
+        world_name: Res<WorldName>,
+        windows: Query<Entity, With<PrimaryWindow>>,
+    ) -> Result<()> {
+        // Snapshots are missing for memories recorded without a window.
+        let Ok(window_entity) = windows.get_single() else {
+            capture_events.clear();
+            return Ok(());
+        };
+
+        let snapshots_dir = game_paths.snapshots_dir(&world_name.0);
+        fs::create_dir_all(&snapshots_dir)
+            .with_context(|| format!("unable to create {snapshots_dir:?}"))?;
+
+        for event in capture_events.read() {
+            let path = snapshots_dir.join(&event.0);
+            debug!("capturing memory snapshot to {path:?}");
+            screenshot_manager
+                .save_screenshot_to_disk(window_entity, path)
+                .context("unable to capture memory snapshot")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Notable events of a family, oldest first.
+#[derive(Clone, Component, Default, Deref, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct FamilyMemories(VecDeque<Memory>);
+
+impl FamilyMemories {
+    /// Adds a memory, forgetting the oldest ones to fit into the capacity.
+    fn push(&mut self, memory: Memory, capacity: usize) {
+        self.0.push_back(memory);
+        while self.0.len() > capacity {
+            self.0.pop_front();
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Reflect, Serialize)]
+pub struct Memory {
+    pub day: u32,
+    pub hour: u32,
+    pub kind: MemoryKind,
+    pub description: String,
+
+    /// File name of the picture inside [`GamePaths::snapshots_dir`].
+    pub snapshot: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Display, PartialEq, Reflect, Serialize)]
+pub enum MemoryKind {
+    Home,
+    Skill,
+    Burglary,
+    Birth,
+    Promotion,
+    Party,
+}
+
+impl MemoryKind {
+    pub fn glyph(self) -> &'static str {
+        match self {
+            MemoryKind::Home => "🏠",
+            MemoryKind::Skill => "🔧",
+            MemoryKind::Burglary => "🦹",
+            MemoryKind::Birth => "👶",
+            MemoryKind::Promotion => "💼",
+            MemoryKind::Party => "🎉",
+        }
+    }
+}
+
+/// Adds a memory to the family.
+///
+/// Should be sent only on server.
+#[derive(Event)]
+pub(crate) struct MemoryRecord {
+    pub(crate) family_entity: Entity,
+    pub(crate) kind: MemoryKind,
+    pub(crate) description: String,
+}
+
+/// Captures a snapshot with the specified file name.
+#[derive(Event)]
+struct SnapshotCapture(String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_world::TestWorld;
+
+    #[test]
+    fn capacity() {
+        let mut memories = FamilyMemories::default();
+        for day in 0..3 {
+            let memory = Memory {
+                day,
+                hour: 0,
+                kind: MemoryKind::Home,
+                description: String::new(),
+                snapshot: None,
+            };
+            memories.push(memory, 2);
+        }
+
+        let days: Vec<_> = memories.iter().map(|memory| memory.day).collect();
+        assert_eq!(days, [1, 2], "oldest memories should be forgotten");
+    }
+
+    #[test]
+    fn max_skill() {
+        let mut test_world = TestWorld::builder().build();
+        let city_entity = test_world.spawn_city("City");
+        let family_entity = test_world.spawn_family(city_entity, "Family", 1);
+        let mut actors = test_world
+            .world_mut()
+            .query_filtered::<Entity, With<Actor>>();
+        let actor_entity = actors.single(test_world.world());
+
+        let initialized =
+            test_world.tick_until(10, |world| world.get::<Handiness>(actor_entity).is_some());
+        assert!(initialized);
+        // Let memories see the initial level.
+        test_world.tick();
+        let memories_count = |test_world: &TestWorld| {
+            test_world
+                .world()
+                .get::<FamilyMemories>(family_entity)
+                .unwrap()
+                .len()
+        };
+
+        for _ in 0..Handiness::MAX {
+            let mut handiness = test_world
+                .world_mut()
+                .get_mut::<Handiness>(actor_entity)
+                .unwrap();
+            handiness.improve();
+            test_world.tick();
+        }
+        let count = memories_count(&test_world);
+        assert_eq!(count, Handiness::MAX as usize);
+
+        let mut handiness = test_world
+            .world_mut()
+            .get_mut::<Handiness>(actor_entity)
+            .unwrap();
+        handiness.improve();
+        test_world.tick();
+        assert_eq!(
+            memories_count(&test_world),
+            count,
+            "improving at the max level shouldn't be recorded"
+        );
+    }
+}
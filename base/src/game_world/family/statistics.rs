@@ -0,0 +1,141 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{Budget, Family, FamilyMembers};
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{needs::Need, skills::Handiness},
+        clock::GameClock,
+    },
+};
+
+/// Periodically samples family state for graphs.
+pub(super) struct StatisticsPlugin;
+
+impl Plugin for StatisticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<FamilyStatistics>()
+            .replicate::<FamilyStatistics>()
+            .add_systems(
+                Update,
+                (Self::init, Self::sample)
+                    .chain()
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+impl StatisticsPlugin {
+    fn init(
+        mut commands: Commands,
+        families: Query<Entity, (With<Family>, Without<FamilyStatistics>)>,
+    ) {
+        for entity in &families {
+            debug!("initializing statistics for `{entity}`");
+            commands.entity(entity).insert(FamilyStatistics::default());
+        }
+    }
+
+    /// Records a sample for each family once per game hour.
+    fn sample(
+        clocks: Query<&GameClock>,
+        mut families: Query<(Entity, &mut FamilyStatistics, &Budget, &FamilyMembers)>,
+        actors: Query<(&Children, Option<&Handiness>)>,
+        needs: Query<&Need>,
+    ) {
+        let Ok(clock) = clocks.get_single() else {
+            return;
+        };
+
+        let hour = clock.day() * 24 + clock.hour();
+        for (entity, mut statistics, budget, members) in &mut families {
+            if statistics.last_hour == Some(hour) {
+                continue;
+            }
+
+            let mut needs_sum = 0.0;
+            let mut needs_count = 0;
+            let mut handiness_sum = 0.0;
+            for (children, handiness) in actors.iter_many(members.iter()) {
+                for need in needs.iter_many(children) {
+                    needs_sum += need.0;
+                    needs_count += 1;
+                }
+                handiness_sum += handiness.map(|handiness| **handiness).unwrap_or_default() as f32;
+            }
+
+            trace!("sampling statistics for `{entity}`");
+            statistics.last_hour = Some(hour);
+            statistics.budget.push(**budget as f32);
+            statistics.needs.push(average(needs_sum, needs_count));
+            statistics
+                .handiness
+                .push(average(handiness_sum, members.len()));
+        }
+    }
+}
+
+pub(crate) fn average(sum: f32, count: usize) -> f32 {
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f32
+    }
+}
+
+/// History of family state sampled every game hour.
+#[derive(Clone, Component, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct FamilyStatistics {
+    pub budget: Samples,
+
+    /// Average of all member needs.
+    pub needs: Samples,
+
+    /// Average handiness of members.
+    pub handiness: Samples,
+
+    /// Game hour of the last sample.
+    last_hour: Option<u32>,
+}
+
+/// Ring buffer with the last [`Samples::CAPACITY`] values.
+#[derive(Clone, Default, Deref, Deserialize, PartialEq, Reflect, Serialize)]
+pub struct Samples(VecDeque<f32>);
+
+impl Samples {
+    /// Two game days of hourly samples.
+    pub const CAPACITY: usize = 48;
+
+    pub(crate) fn push(&mut self, value: f32) {
+        if self.0.len() == Self::CAPACITY {
+            self.0.pop_front();
+        }
+        self.0.push_back(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_overflow() {
+        let mut samples = Samples::default();
+        for value in 0..Samples::CAPACITY + 2 {
+            samples.push(value as f32);
+        }
+
+        assert_eq!(samples.len(), Samples::CAPACITY);
+        assert_eq!(samples.front().copied(), Some(2.0));
+        assert_eq!(
+            samples.back().copied(),
+            Some((Samples::CAPACITY + 1) as f32)
+        );
+    }
+}
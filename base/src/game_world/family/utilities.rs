@@ -0,0 +1,165 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::{entity::EntityHashSet, system::SystemParam},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::Family;
+use crate::{
+    core::GameState,
+    game_world::{
+        city::lot::{LotFamily, LotObjects},
+        clock,
+    },
+};
+
+/// Accrues utility bills for objects on family lots.
+///
+/// If a bill is still unpaid when the next one arrives, powered objects on family lots are shut off.
+pub(super) struct UtilitiesPlugin;
+
+impl Plugin for UtilitiesPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Utilities>()
+            .register_type::<UtilityBills>()
+            .register_type::<UtilitiesShutOff>()
+            .replicate::<UtilityBills>()
+            .replicate::<UtilitiesShutOff>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    Self::init,
+                    Self::accrue.run_if(clock::on_game_timer(BILLING_PERIOD)),
+                    Self::update_shut_off,
+                )
+                    .chain()
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Game time between bills.
+const BILLING_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Bill per object for each utility.
+const POWER_COST: u32 = 5;
+const WATER_COST: u32 = 3;
+
+impl UtilitiesPlugin {
+    fn init(
+        mut commands: Commands,
+        families: Query<Entity, (With<Family>, Without<UtilityBills>)>,
+    ) {
+        for entity in &families {
+            debug!("initializing utility bills for `{entity}`");
+            commands.entity(entity).insert(UtilityBills::default());
+        }
+    }
+
+    fn accrue(mut families: Query<&mut UtilityBills>, lot_utilities: LotUtilities) {
+        for mut bills in &mut families {
+            if bills.due > 0 {
+                bills.overdue = true;
+            }
+        }
+
+        for (family_entity, entity, amount) in lot_utilities.collect() {
+            let Ok(mut bills) = families.get_mut(family_entity) else {
+                error!("lot owner `{family_entity}` is not a family");
+                continue;
+            };
+            trace!("adding bill {amount} for `{entity}` to `{family_entity}`");
+            bills.due += amount;
+        }
+    }
+
+    fn update_shut_off(
+        mut commands: Commands,
+        families: Query<&UtilityBills>,
+        lot_utilities: LotUtilities,
+    ) {
+        let overdue_objects: EntityHashSet = lot_utilities
+            .collect()
+            .into_iter()
+            .filter(|&(family_entity, ..)| {
+                families.get(family_entity).is_ok_and(|bills| bills.overdue)
+            })
+            .map(|(_, entity, ..)| entity)
+            .collect();
+
+        for (entity, _, shut_off) in &lot_utilities.objects {
+            let overdue = overdue_objects.contains(&entity);
+            if overdue && !shut_off {
+                debug!("shutting off utilities for `{entity}`");
+                commands.entity(entity).insert(UtilitiesShutOff);
+            } else if !overdue && shut_off {
+                debug!("restoring utilities for `{entity}`");
+                commands.entity(entity).remove::<UtilitiesShutOff>();
+            }
+        }
+    }
+}
+
+/// Objects with [`Utilities`] on family lots.
+///
+/// Shared by billing and shutting off to decide what belongs to a lot in the same way.
+#[derive(SystemParam)]
+struct LotUtilities<'w, 's> {
+    lots: Query<'w, 's, (&'static LotObjects, &'static LotFamily)>,
+    objects: Query<'w, 's, (Entity, &'static Utilities, Has<UtilitiesShutOff>)>,
+}
+
+impl LotUtilities<'_, '_> {
+    /// Returns family entity, object entity and its bill for each object on family lots.
+    fn collect(&self) -> Vec<(Entity, Entity, u32)> {
+        let mut objects = Vec::new();
+        for (lot_objects, lot_family) in &self.lots {
+            for (entity, utilities, _) in self.objects.iter_many(lot_objects.iter()) {
+                objects.push((lot_family.0, entity, utilities.cost()));
+            }
+        }
+        objects
+    }
+}
+
+/// Utilities required by an object.
+///
+/// Usually defined in object metadata.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+pub(crate) struct Utilities {
+    power: bool,
+    water: bool,
+}
+
+impl Utilities {
+    fn cost(&self) -> u32 {
+        let mut cost = 0;
+        if self.power {
+            cost += POWER_COST;
+        }
+        if self.water {
+            cost += WATER_COST;
+        }
+        cost
+    }
+}
+
+/// Unpaid utility bills of a family.
+#[derive(Clone, Component, Copy, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct UtilityBills {
+    pub due: u32,
+
+    /// Bills weren't paid before the next billing period.
+    pub overdue: bool,
+}
+
+/// Marks an object with [`Utilities`] that can't be used until the bills are paid.
+#[derive(Component, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct UtilitiesShutOff;
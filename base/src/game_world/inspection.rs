@@ -0,0 +1,84 @@
+use avian3d::prelude::*;
+use bevy::{prelude::*, utils::get_short_name};
+use bevy_replicon::prelude::*;
+
+use super::{
+    actor::task::{ReflectTask, TaskState},
+    object::{Object, Placeholder},
+};
+
+/// Debug information about an entity for developer tools.
+pub struct EntityInspection {
+    pub entity: Entity,
+
+    /// Path to the metadata if the entity is an object.
+    pub info_path: Option<String>,
+
+    /// Short names of all components.
+    pub components: Vec<String>,
+    pub replicated: bool,
+
+    /// Collider shape and AABB size.
+    pub collider: Option<String>,
+
+    /// Name of the active task if the entity is an actor.
+    pub task: Option<String>,
+}
+
+impl EntityInspection {
+    pub fn new(world: &World, entity: Entity) -> Self {
+        let info_path = world
+            .get::<Object>(entity)
+            .map(|object| object.0.to_string())
+            .or_else(|| {
+                world
+                    .get::<Placeholder>(entity)
+                    .map(|placeholder| placeholder.to_string())
+            });
+
+        let mut components: Vec<_> = world
+            .inspect_entity(entity)
+            .into_iter()
+            .map(|info| get_short_name(info.name()))
+            .collect();
+        components.sort_unstable();
+
+        let collider = world.get::<Collider>(entity).map(|collider| {
+            let shape_type = collider.shape().shape_type();
+            match world.get::<ColliderAabb>(entity) {
+                Some(aabb) => format!("{shape_type:?} {}", aabb.size()),
+                None => format!("{shape_type:?}"),
+            }
+        });
+
+        Self {
+            entity,
+            info_path,
+            components,
+            replicated: world.get::<Replicated>(entity).is_some(),
+            collider,
+            task: active_task(world, entity),
+        }
+    }
+}
+
+/// Returns name of the active task of an actor.
+pub fn active_task(world: &World, entity: Entity) -> Option<String> {
+    let registry = world.resource::<AppTypeRegistry>().read();
+    world
+        .get::<Children>(entity)?
+        .iter()
+        .filter_map(|&child_entity| world.get_entity(child_entity))
+        .filter(|child| child.get::<TaskState>() == Some(&TaskState::Active))
+        .find_map(|child| {
+            child.archetype().components().find_map(|component_id| {
+                let type_id = world.components().get_info(component_id)?.type_id()?;
+                let registration = registry.get(type_id)?;
+                let reflect_task = registration.data::<ReflectTask>()?;
+                let reflect = registration.data::<ReflectComponent>()?.reflect(child)?;
+                reflect_task
+                    .get(reflect)
+                    .map(|task| task.name().to_string())
+            })
+        })
+}
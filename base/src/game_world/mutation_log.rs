@@ -0,0 +1,266 @@
+use std::{
+    any,
+    collections::VecDeque,
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_replicon::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::UnsavedChanges;
+use crate::core::GameState;
+
+/// Keeps the history of world changes requested by clients.
+///
+/// Populated only on server.
+pub(super) struct MutationLogPlugin;
+
+impl Plugin for MutationLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MutationLog>()
+            .add_systems(OnExit(GameState::InGame), Self::cleanup);
+    }
+}
+
+impl MutationLogPlugin {
+    fn cleanup(mut log: ResMut<MutationLog>) {
+        log.entries.clear();
+    }
+}
+
+/// Records accepted mutations and marks the world as changed.
+///
+/// Handlers should record requests only after validation,
+/// so rejected requests never appear in the log.
+#[derive(SystemParam)]
+pub(super) struct MutationRecorder<'w> {
+    log: ResMut<'w, MutationLog>,
+    unsaved: ResMut<'w, UnsavedChanges>,
+    time: Res<'w, Time<Real>>,
+}
+
+impl MutationRecorder<'_> {
+    pub(super) fn record<E: Mutation + Serialize>(&mut self, client_id: ClientId, event: &E) {
+        let payload = bincode::serialize(event)
+            .inspect_err(|e| error!("unable to serialize mutation for the log: {e}"))
+            .ok();
+        self.push(client_id, event, payload);
+    }
+
+    /// Like [`Self::record`], but for events that can't be serialized with serde.
+    ///
+    /// Such entries can't be replayed.
+    pub(super) fn record_without_payload<E: Mutation>(&mut self, client_id: ClientId, event: &E) {
+        self.push(client_id, event, None);
+    }
+
+    fn push<E: Mutation>(&mut self, client_id: ClientId, event: &E, payload: Option<Vec<u8>>) {
+        let entry = MutationEntry {
+            time: self.time.elapsed(),
+            client_id,
+            kind: event.kind(),
+            target: E::TARGET,
+            entity: event.entity(),
+            event_type: any::type_name::<E>(),
+            payload,
+        };
+        debug!("recording {entry}");
+        self.log.push(entry);
+        self.unsaved.0 = true;
+    }
+}
+
+/// Append-only list of world changes in the order they were applied.
+///
+/// Keeps only the last [`Self::MAX_ENTRIES`] entries.
+/// Undo and redo are sent as regular requests, so they also appear here.
+#[derive(Default, Resource)]
+pub struct MutationLog {
+    entries: VecDeque<MutationEntry>,
+}
+
+impl MutationLog {
+    pub const MAX_ENTRIES: usize = 1000;
+
+    /// Returns entries from oldest to newest.
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &MutationEntry> {
+        self.entries.iter()
+    }
+
+    fn push(&mut self, entry: MutationEntry) {
+        if self.entries.len() == Self::MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MutationEntry {
+    /// Real time since the app startup when the request was received.
+    pub time: Duration,
+    pub client_id: ClientId,
+    pub kind: MutationKind,
+    /// Name of the changed entity type.
+    pub target: &'static str,
+    /// Changed entity if it existed before the mutation.
+    pub entity: Option<Entity>,
+    /// Type name of the recorded event.
+    event_type: &'static str,
+    /// Serialized event for replaying.
+    payload: Option<Vec<u8>>,
+}
+
+impl MutationEntry {
+    /// Deserializes the recorded event.
+    ///
+    /// Returns [`None`] if the entry was recorded for a different event type
+    /// or without a payload.
+    /// Sending the returned event again as [`FromClient`] replays the mutation,
+    /// and entities inside it refer to the server world.
+    pub fn event<E: Mutation + DeserializeOwned>(&self) -> Option<E> {
+        if self.event_type != any::type_name::<E>() {
+            return None;
+        }
+
+        let payload = self.payload.as_ref()?;
+        bincode::deserialize(payload)
+            .inspect_err(|e| error!("unable to deserialize `{}`: {e}", self.event_type))
+            .ok()
+    }
+}
+
+impl Display for MutationEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let seconds = self.time.as_secs();
+        write!(
+            f,
+            "{:02}:{:02}:{:02} client {}: {} {}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60,
+            self.client_id.get(),
+            self.kind,
+            self.target,
+        )?;
+        if let Some(entity) = self.entity {
+            write!(f, " `{entity}`")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, strum::Display, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum MutationKind {
+    Spawn,
+    Move,
+    Edit,
+    Delete,
+}
+
+/// Client event or command that changes the world.
+pub trait Mutation {
+    const TARGET: &'static str;
+
+    fn kind(&self) -> MutationKind;
+
+    /// Returns the affected entity if it already exists.
+    fn entity(&self) -> Option<Entity>;
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[test]
+    fn display() {
+        let entry = MutationEntry {
+            time: Duration::from_secs(3725),
+            client_id: ClientId::new(5),
+            kind: MutationKind::Move,
+            target: "object",
+            entity: Some(Entity::from_raw(2)),
+            event_type: any::type_name::<TestMutation>(),
+            payload: None,
+        };
+
+        assert_eq!(entry.to_string(), "01:02:05 client 5: move object `2v1`");
+    }
+
+    #[test]
+    fn payload() {
+        let mut app = App::new();
+        app.init_resource::<MutationLog>()
+            .init_resource::<UnsavedChanges>()
+            .init_resource::<Time<Real>>();
+
+        let event = TestMutation(42);
+        app.world_mut()
+            .run_system_once(move |mut recorder: MutationRecorder| {
+                recorder.record(ClientId::new(1), &event)
+            });
+
+        assert!(app.world().resource::<UnsavedChanges>().0);
+        let log = app.world().resource::<MutationLog>();
+        let entry = log.entries().next().expect("mutation should be recorded");
+        assert_eq!(entry.event::<TestMutation>(), Some(event));
+        assert_eq!(entry.event::<OtherMutation>(), None);
+    }
+
+    #[test]
+    fn bounded() {
+        let mut log = MutationLog::default();
+        for index in 0..MutationLog::MAX_ENTRIES + 1 {
+            log.push(MutationEntry {
+                time: Duration::from_secs(index as u64),
+                client_id: ClientId::SERVER,
+                kind: MutationKind::Spawn,
+                target: "test",
+                entity: None,
+                event_type: any::type_name::<TestMutation>(),
+                payload: None,
+            });
+        }
+
+        assert_eq!(log.entries().count(), MutationLog::MAX_ENTRIES);
+        let first = log.entries().next().unwrap();
+        assert_eq!(first.time, Duration::from_secs(1));
+    }
+
+    #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+    struct TestMutation(u32);
+
+    impl Mutation for TestMutation {
+        const TARGET: &'static str = "test";
+
+        fn kind(&self) -> MutationKind {
+            MutationKind::Edit
+        }
+
+        fn entity(&self) -> Option<Entity> {
+            None
+        }
+    }
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct OtherMutation;
+
+    impl Mutation for OtherMutation {
+        const TARGET: &'static str = "other";
+
+        fn kind(&self) -> MutationKind {
+            MutationKind::Edit
+        }
+
+        fn entity(&self) -> Option<Entity> {
+            None
+        }
+    }
+}
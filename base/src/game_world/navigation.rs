@@ -1,16 +1,25 @@
 pub(super) mod following;
 pub(super) mod path_debug;
 
+use std::mem;
+
 use bevy::{
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
     ecs::component::{ComponentHooks, StorageType},
+    math::Vec3Swizzles,
     prelude::*,
+    utils::{EntityHashMap, Instant},
 };
 use bevy_replicon::prelude::*;
+use itertools::Itertools;
 use path_debug::PathDebugPlugin;
 use serde::{Deserialize, Serialize};
 use vleue_navigator::prelude::*;
 
-use crate::game_world::city::CityNavMesh;
+use crate::game_world::{
+    city::{CityNavMesh, GroundSampler},
+    simulation::InterpolatedTransform,
+};
 use following::FollowingPlugin;
 
 pub(super) struct NavigationPlugin;
@@ -23,22 +32,80 @@ impl Plugin for NavigationPlugin {
             .replicate::<NavSettings>()
             .replicate::<NavDestination>()
             .replicate::<NavPath>()
+            .register_diagnostic(Diagnostic::new(NAVMESH_BUILD_TIME).with_suffix("ms"))
+            .add_systems(Update, Self::measure_builds)
             .add_systems(
                 PreUpdate,
-                (Self::update_paths, Self::generate_paths)
-                    .chain()
+                (
+                    Self::init_interpolation,
+                    (Self::update_paths, Self::generate_paths).chain(),
+                )
                     .after(ClientSet::Receive)
                     .run_if(server_or_singleplayer),
             )
-            .add_systems(Update, Self::navigate.run_if(server_or_singleplayer));
+            .add_systems(
+                FixedUpdate,
+                (Self::navigate, Self::follow_surface)
+                    .chain()
+                    .run_if(server_or_singleplayer),
+            );
     }
 }
 
+/// Time between the start and the end of the last navmesh build.
+///
+/// Navmeshes are built asynchronously, so it's measured by status changes.
+pub const NAVMESH_BUILD_TIME: DiagnosticPath =
+    DiagnosticPath::const_new("navigation/navmesh_build");
+
 impl NavigationPlugin {
-    /// Updates path on navmesh changes.
+    fn measure_builds(
+        mut diagnostics: Diagnostics,
+        mut build_starts: Local<EntityHashMap<Entity, Instant>>,
+        navmeshes: Query<(Entity, &NavMeshStatus), Changed<NavMeshStatus>>,
+    ) {
+        for (entity, status) in &navmeshes {
+            if matches!(status, NavMeshStatus::Building) {
+                build_starts.insert(entity, Instant::now());
+            } else if let Some(start) = build_starts.remove(&entity) {
+                if matches!(status, NavMeshStatus::Built) {
+                    diagnostics.add_measurement(&NAVMESH_BUILD_TIME, || {
+                        start.elapsed().as_secs_f64() * 1000.0
+                    });
+                }
+            }
+        }
+    }
+
+    /// Smooths movement of agents since it's simulated at a fixed rate.
+    fn init_interpolation(
+        mut commands: Commands,
+        agents: Query<(Entity, &Transform), (With<NavSettings>, Without<InterpolatedTransform>)>,
+    ) {
+        for (entity, &transform) in &agents {
+            debug!("initializing transform interpolation for `{entity}`");
+            commands
+                .entity(entity)
+                .insert(InterpolatedTransform::new(transform));
+        }
+    }
+
+    /// Re-plans paths that became blocked after a navmesh rebuild.
+    ///
+    /// Paths that are still walkable are kept and only receive the new version.
     fn update_paths(
         mut navmeshes: ResMut<Assets<NavMesh>>,
-        city_navmeshes: Query<(&Handle<NavMesh>, &Parent, &NavMeshStatus), Changed<NavMeshStatus>>,
+        ground_sampler: GroundSampler,
+        cities: Query<&GlobalTransform>,
+        mut city_navmeshes: Query<
+            (
+                &Handle<NavMesh>,
+                &Parent,
+                &NavMeshStatus,
+                &mut NavMeshVersion,
+            ),
+            Changed<NavMeshStatus>,
+        >,
         children: Query<&Children>,
         mut agents: Query<(
             Entity,
@@ -46,9 +113,10 @@ impl NavigationPlugin {
             &mut NavDestination,
             &mut NavPath,
             &mut NavPathIndex,
+            &mut NavPathVersion,
         )>,
     ) {
-        for (navmesh_handle, parent, status) in &city_navmeshes {
+        for (navmesh_handle, parent, status, mut version) in &mut city_navmeshes {
             if !matches!(status, NavMeshStatus::Built) {
                 continue;
             }
@@ -57,21 +125,44 @@ impl NavigationPlugin {
                 continue;
             };
 
+            version.0 += 1;
+            debug!("updating navmesh to version {}", version.0);
+
+            let _span = info_span!("navmesh_replan_paths").entered();
+            let city_transform = cities.get(**parent).unwrap();
             let children = children.get(**parent).unwrap();
             let mut iter = agents.iter_many_mut(children);
-            while let Some((entity, transform, mut dest, mut path, mut path_index)) =
-                iter.fetch_next()
+            while let Some((
+                entity,
+                transform,
+                mut dest,
+                mut path,
+                mut path_index,
+                mut path_version,
+            )) = iter.fetch_next()
             {
                 let Some(endpoint) = **dest else {
                     continue;
                 };
+                if path_version.0 == version.0 {
+                    continue;
+                }
 
+                path_version.0 = version.0;
+                let remaining = path.get(**path_index + 1..).unwrap_or_default();
+                if !path_blocked(navmesh, transform.translation, remaining) {
+                    trace!("keeping path for `{entity}`");
+                    continue;
+                }
+
+                path.0.clear();
+                path_index.0 = 0;
                 if let Some(transformed) = navmesh.transformed_path(transform.translation, endpoint)
                 {
-                    debug!("recalculating path for `{entity}`");
+                    debug!("re-planning blocked path for `{entity}`");
                     path.0.push(transform.translation);
                     path.0.extend(transformed.path);
-                    path_index.0 = 0;
+                    lift_path(&mut path.0, &ground_sampler, city_transform);
                 } else {
                     debug!("cancelling destination for `{entity}`");
                     **dest = None;
@@ -82,8 +173,9 @@ impl NavigationPlugin {
 
     fn generate_paths(
         mut navmeshes: ResMut<Assets<NavMesh>>,
-        cities: Query<&CityNavMesh>,
-        city_navmeshes: Query<&Handle<NavMesh>>,
+        ground_sampler: GroundSampler,
+        cities: Query<(&CityNavMesh, &GlobalTransform)>,
+        city_navmeshes: Query<(&Handle<NavMesh>, &NavMeshVersion)>,
         mut agents: Query<
             (
                 Entity,
@@ -92,11 +184,14 @@ impl NavigationPlugin {
                 &mut NavDestination,
                 &mut NavPath,
                 &mut NavPathIndex,
+                &mut NavPathVersion,
             ),
             Changed<NavDestination>,
         >,
     ) {
-        for (entity, parent, transform, mut dest, mut path, mut path_index) in &mut agents {
+        for (entity, parent, transform, mut dest, mut path, mut path_index, mut path_version) in
+            &mut agents
+        {
             path.0.clear();
             path_index.0 = 0;
 
@@ -104,10 +199,10 @@ impl NavigationPlugin {
                 continue;
             };
 
-            let navmesh_entity = cities
+            let (navmesh_entity, city_transform) = cities
                 .get(**parent)
                 .expect("all agents should have city as parents");
-            let navmesh_handle = city_navmeshes
+            let (navmesh_handle, version) = city_navmeshes
                 .get(**navmesh_entity)
                 .expect("city navmesh should always be valid");
 
@@ -115,10 +210,12 @@ impl NavigationPlugin {
                 continue;
             };
 
+            path_version.0 = version.0;
             if let Some(transformed) = navmesh.transformed_path(transform.translation, endpoint) {
                 debug!("calculating path for `{entity}`");
                 path.0.push(transform.translation);
                 path.0.extend(transformed.path);
+                lift_path(&mut path.0, &ground_sampler, city_transform);
             } else {
                 debug!("refusing destination for `{entity}`");
                 **dest = None;
@@ -131,22 +228,44 @@ impl NavigationPlugin {
         mut agents: Query<(
             Entity,
             &NavSettings,
+            Option<&mut RootMotionDelta>,
             &NavPath,
             &mut NavPathIndex,
             &mut NavDestination,
             &mut Transform,
         )>,
     ) {
-        for (entity, &nav_settings, path, mut path_index, mut dest, mut transform) in &mut agents {
+        for (
+            entity,
+            &nav_settings,
+            mut root_motion,
+            path,
+            mut path_index,
+            mut dest,
+            mut transform,
+        ) in &mut agents
+        {
             if dest.is_none() || path.is_empty() {
                 continue;
             }
 
+            let movement_step = match root_motion
+                .as_deref_mut()
+                .and_then(|root_motion| root_motion.0.as_mut())
+            {
+                Some(distance) => {
+                    trace!("applying root motion {distance} for `{entity}`");
+                    mem::take(distance)
+                }
+                None => nav_settings.speed * time.delta_seconds(),
+            };
+
             let target_index = **path_index + 1;
             if let Some(passed_points) = move_agent(
                 &mut transform,
-                nav_settings,
+                nav_settings.offset,
                 &path[target_index..],
+                movement_step,
                 time.delta_seconds(),
             ) {
                 if passed_points != 0 {
@@ -163,12 +282,116 @@ impl NavigationPlugin {
             }
         }
     }
+
+    /// Keeps moving agents on the ground or elevated roads.
+    ///
+    /// Agents move only horizontally and get their height from the surface
+    /// to stay on it between path points.
+    fn follow_surface(
+        ground_sampler: GroundSampler,
+        cities: Query<&GlobalTransform>,
+        mut agents: Query<(&Parent, &NavDestination, &mut Transform)>,
+    ) {
+        for (parent, dest, mut transform) in &mut agents {
+            if dest.is_none() {
+                continue;
+            }
+
+            let Ok(city_transform) = cities.get(**parent) else {
+                continue;
+            };
+            let point = city_transform.transform_point(transform.translation);
+            if let Some(height) = ground_sampler.surface_height(point) {
+                let y = height - city_transform.translation().y;
+                if transform.translation.y != y {
+                    transform.translation.y = y;
+                }
+            }
+        }
+    }
 }
 
 /// Marks an entity with [`Collider`] as a navigation mesh affector.
 #[derive(Component)]
 pub struct Obstacle;
 
+/// Places path points on the walkable surface, including elevated roads.
+///
+/// The navmesh is a flat projection of the city, so paths from it have no heights.
+/// Each segment is sampled and points are added where the height changes,
+/// like on road slopes and bridge ramps. Samples are taken from the previous height
+/// to keep paths under bridges on the ground.
+fn lift_path(
+    path: &mut Vec<Vec3>,
+    ground_sampler: &GroundSampler,
+    city_transform: &GlobalTransform,
+) {
+    const SAMPLE_STEP: f32 = 1.0;
+
+    let Some(&start) = path.first() else {
+        return;
+    };
+
+    let mut lifted = vec![start];
+    let mut height = start.y;
+    let mut last_sampled = false;
+    for (&from, &to) in path.iter().tuple_windows() {
+        let samples = (from.xz().distance(to.xz()) / SAMPLE_STEP).ceil().max(1.0) as usize;
+        for index in 1..=samples {
+            let mut point = from.lerp(to, index as f32 / samples as f32).with_y(height);
+            let global_point = city_transform.transform_point(point);
+            if let Some(surface_height) = ground_sampler.surface_height(global_point) {
+                height = surface_height - city_transform.translation().y;
+                point.y = height;
+            }
+
+            // Remove previous sample if it's on a flat part since it's on the same line.
+            if last_sampled {
+                let [.., before, previous] = lifted[..] else {
+                    unreachable!("sample should have a point before it");
+                };
+                if before.y == previous.y && previous.y == point.y {
+                    lifted.pop();
+                }
+            }
+
+            lifted.push(point);
+            last_sampled = index != samples;
+        }
+    }
+
+    *path = lifted;
+}
+
+/// Returns `true` if the path from the start point leaves the navmesh.
+///
+/// Points near the endpoint are ignored because destinations are often objects
+/// that are reached with an offset.
+fn path_blocked(navmesh: &NavMesh, start: Vec3, points: &[Vec3]) -> bool {
+    const SAMPLE_STEP: f32 = 0.25;
+    const ENDPOINT_TOLERANCE: f32 = 1.0;
+
+    let Some(&endpoint) = points.last() else {
+        return false;
+    };
+
+    let mut from = start;
+    for &to in points {
+        let samples = (from.distance(to) / SAMPLE_STEP).ceil().max(1.0) as usize;
+        for index in 1..=samples {
+            let point = from.lerp(to, index as f32 / samples as f32);
+            if point.distance(endpoint) > ENDPOINT_TOLERANCE
+                && !navmesh.transformed_is_in_mesh(point)
+            {
+                return true;
+            }
+        }
+        from = to;
+    }
+
+    false
+}
+
 /// Moves the agent along a path.
 ///
 /// The path should contain only the remaining points to reach.
@@ -176,28 +399,30 @@ pub struct Obstacle;
 /// Skips points that actor have projected past to prevent jitter
 /// when multiple points are near each other.
 ///
+/// Moves only horizontally, height is controlled by [`NavigationPlugin::follow_surface`].
+///
 /// Returns the number of points passed.
 /// If the path is completed, returns [`None`].
 fn move_agent(
     transform: &mut Transform,
-    nav_settings: NavSettings,
+    offset: Option<f32>,
     path: &[Vec3],
+    movement_step: f32,
     delta: f32,
 ) -> Option<usize> {
-    let movement_step = nav_settings.speed * delta;
     let (passed_points, &target_point) = path.iter().enumerate().find(|&(index, &point)| {
         const EPSILON: f32 = 0.1;
         let tolerance = if index == path.len() - 1 {
             // Apply the desired offset for the last point.
-            nav_settings.offset.unwrap_or(EPSILON)
+            offset.unwrap_or(EPSILON)
         } else {
             EPSILON
         };
 
-        transform.translation.distance(point) - movement_step > tolerance
+        transform.translation.xz().distance(point.xz()) - movement_step > tolerance
     })?;
 
-    let disp = target_point - transform.translation;
+    let disp = (target_point - transform.translation).with_y(0.0);
     let target_rotation = transform.looking_to(disp, Vec3::Y).rotation;
     const ROTATION_SPEED: f32 = 10.0;
     transform.translation += disp.normalize() * movement_step;
@@ -244,6 +469,14 @@ impl NavSettings {
     }
 }
 
+/// Distance covered by the root bone of the currently playing animation.
+///
+/// Accumulated from animation frames and consumed by navigation instead of [`NavSettings`] speed
+/// to remove foot sliding.
+/// [`None`] if the current animation doesn't have root motion.
+#[derive(Component, Default)]
+pub(super) struct RootMotionDelta(pub(super) Option<f32>);
+
 /// Defines navigation destination point.
 ///
 /// Changing this component to [`Some`] will trigger [`NavPath`] calculation.
@@ -271,6 +504,12 @@ impl Component for NavDestination {
                     .entity(targeted_entity)
                     .insert(NavPath::default());
             }
+            if world.get::<NavPathVersion>(targeted_entity).is_none() {
+                world
+                    .commands()
+                    .entity(targeted_entity)
+                    .insert(NavPathVersion::default());
+            }
         });
     }
 }
@@ -288,3 +527,13 @@ pub(super) struct NavPath(Vec<Vec3>);
 /// Resets to 0 each time [`NavPath`] changes.
 #[derive(Component, Default, Serialize, Deserialize, Deref, DerefMut)]
 struct NavPathIndex(usize);
+
+/// Navmesh version of the calculated [`NavPath`].
+///
+/// Compared with [`NavMeshVersion`] to check paths only once after a rebuild.
+#[derive(Component, Default)]
+struct NavPathVersion(u32);
+
+/// Incremented each time the city navmesh finishes rebuilding.
+#[derive(Component, Default)]
+pub(super) struct NavMeshVersion(u32);
@@ -1,11 +1,20 @@
+pub(crate) mod claim;
+pub(crate) mod counter;
 pub(crate) mod door;
+pub(crate) mod fire;
+pub(crate) mod garden_plot;
+pub(crate) mod lamp;
+pub(crate) mod occupancy;
+pub(crate) mod pillar;
 pub mod placing_object;
 pub(crate) mod wall_mount;
+pub(crate) mod wear;
 
 use avian3d::prelude::*;
 use bevy::{
-    asset::AssetPath,
+    asset::{AssetPath, LoadState},
     ecs::{entity::MapEntities, reflect::ReflectCommandExt},
+    math::Vec3Swizzles,
     prelude::*,
 };
 use bevy_mod_outline::OutlineBundle;
@@ -13,38 +22,83 @@ use bevy_replicon::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    city::{City, HALF_CITY_SIZE},
+    city::{
+        lot::{permissions::BuildPermissions, LotFamily, LotVertices},
+        City, HALF_CITY_SIZE,
+    },
     commands_history::{
-        CommandConfirmation, CommandId, CommandRequest, ConfirmableCommand, EntityRecorder,
-        PendingCommand,
+        CommandConfirmation, CommandId, CommandRequest, CommandsHistory, ConfirmableCommand,
+        EntityRecorder, PendingCommand,
     },
+    family::Budget,
     hover::{highlighting::OutlineHighlightingExt, Hoverable},
 };
-use crate::{asset::info::object_info::ObjectInfo, core::GameState, game_world::Layer};
+use crate::{
+    asset::info::object_info::{ObjectInfo, ObjectTag},
+    core::GameState,
+    game_world::{
+        mutation_log::{Mutation, MutationKind, MutationRecorder},
+        Layer,
+    },
+    network::validation::{AppValidationExt, ClientViolation},
+};
+use claim::ClaimPlugin;
+use counter::CounterPlugin;
 use door::DoorPlugin;
-use placing_object::PlacingObjectPlugin;
+use fire::FirePlugin;
+use garden_plot::GardenPlotPlugin;
+use lamp::LampPlugin;
+use occupancy::OccupancyPlugin;
+use pillar::PillarPlugin;
+use placing_object::{
+    ground_fit::PendingGroundCheck, surface_snap::SurfaceSlots, PlacingObjectPlugin,
+};
 use wall_mount::WallMountPlugin;
+use wear::WearPlugin;
 
 pub(super) struct ObjectPlugin;
 
 impl Plugin for ObjectPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((DoorPlugin, PlacingObjectPlugin, WallMountPlugin))
-            .register_type::<Object>()
-            .replicate_group::<(Object, Transform)>()
-            .add_mapped_client_event::<CommandRequest<ObjectCommand>>(ChannelKind::Unordered)
-            .add_systems(
-                PreUpdate,
-                Self::init
-                    .after(ClientSet::Receive)
-                    .run_if(in_state(GameState::InGame)),
+        app.add_plugins((
+            ClaimPlugin,
+            CounterPlugin,
+            DoorPlugin,
+            FirePlugin,
+            GardenPlotPlugin,
+            LampPlugin,
+            OccupancyPlugin,
+            PillarPlugin,
+            PlacingObjectPlugin,
+            WallMountPlugin,
+            WearPlugin,
+        ))
+        .register_type::<Object>()
+        .init_resource::<PlaceholderAssets>()
+        .add_event::<ObjectReplace>()
+        .replicate_group::<(Object, Transform)>()
+        .add_mapped_client_event::<CommandRequest<ObjectCommand>>(ChannelKind::Unordered)
+        .limit_client_event::<CommandRequest<ObjectCommand>>(30)
+        .add_systems(
+            PreUpdate,
+            Self::init
+                .after(ClientSet::Receive)
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            Update,
+            (
+                Self::replace_failed_scenes,
+                Self::request_replace.run_if(on_event::<ObjectReplace>()),
             )
-            .add_systems(
-                PostUpdate,
-                Self::apply_command
-                    .before(ServerSet::StoreHierarchy)
-                    .run_if(server_or_singleplayer),
-            );
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            PostUpdate,
+            Self::apply_command
+                .before(ServerSet::StoreHierarchy)
+                .run_if(server_or_singleplayer),
+        );
     }
 }
 
@@ -53,13 +107,21 @@ impl ObjectPlugin {
         mut commands: Commands,
         asset_server: Res<AssetServer>,
         objects_info: Res<Assets<ObjectInfo>>,
-        spawned_objects: Query<(Entity, &Object), Without<Handle<Scene>>>,
+        placeholder_assets: Res<PlaceholderAssets>,
+        spawned_objects: Query<(Entity, &Object), (Without<Handle<Scene>>, Without<Placeholder>)>,
     ) {
         for (entity, object) in &spawned_objects {
-            let info_handle = asset_server
+            let Some(info) = asset_server
                 .get_handle(&object.0)
-                .expect("info should be preloaded");
-            let info = objects_info.get(&info_handle).unwrap();
+                .and_then(|info_handle| objects_info.get(&info_handle))
+            else {
+                warn!("spawning placeholder for missing object '{}'", object.0);
+                commands.entity(entity).insert(PlaceholderBundle::new(
+                    &placeholder_assets,
+                    object.0.clone(),
+                ));
+                continue;
+            };
 
             debug!("initializing object '{}' for `{entity}`", object.0);
 
@@ -68,6 +130,7 @@ impl ObjectPlugin {
             entity.insert((
                 scene_handle,
                 Name::new(info.general.name.clone()),
+                ObjectTags(info.tags.clone()),
                 Hoverable,
                 RigidBody::Kinematic,
                 OutlineBundle::highlighting(),
@@ -88,50 +151,264 @@ impl ObjectPlugin {
         }
     }
 
+    /// Replaces scenes that failed to load with a placeholder.
+    fn replace_failed_scenes(
+        mut commands: Commands,
+        asset_server: Res<AssetServer>,
+        placeholder_assets: Res<PlaceholderAssets>,
+        objects: Query<(Entity, &Object, &Handle<Scene>), Without<Placeholder>>,
+    ) {
+        for (entity, object, scene_handle) in &objects {
+            if let Some(LoadState::Failed(error)) = asset_server.get_load_state(scene_handle) {
+                warn!("spawning placeholder for `{entity}` with missing scene: {error}");
+                commands
+                    .entity(entity)
+                    .remove::<Handle<Scene>>()
+                    .insert(PlaceholderBundle::new(
+                        &placeholder_assets,
+                        object.0.clone(),
+                    ));
+            }
+        }
+    }
+
+    fn request_replace(
+        mut history: CommandsHistory,
+        mut replace_events: EventReader<ObjectReplace>,
+        asset_server: Res<AssetServer>,
+    ) {
+        for event in replace_events.read() {
+            let info_path = asset_server
+                .get_path(event.info_id)
+                .expect("info should always come from file");
+            info!("replacing `{}` with {info_path:?}", event.entity);
+            history.push_pending(ObjectCommand::Replace {
+                entity: event.entity,
+                info_path: info_path.into_owned(),
+            });
+        }
+    }
+
     fn apply_command(
         mut commands: Commands,
         mut request_events: EventReader<FromClient<CommandRequest<ObjectCommand>>>,
         mut confirm_events: EventWriter<ToClients<CommandConfirmation>>,
-        mut objects: Query<&mut Transform, Without<City>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut recorder: MutationRecorder,
+        asset_server: Res<AssetServer>,
+        objects_info: Res<Assets<ObjectInfo>>,
+        permissions: BuildPermissions,
+        mut objects: Query<(&mut Transform, &Parent), (With<Object>, Without<City>)>,
+        object_paths: Query<&Object>,
+        placeholders: Query<(), With<Placeholder>>,
+        cities: Query<(), With<City>>,
+        surfaces: Query<(&Parent, &SurfaceSlots)>,
+        lots: Query<(&Parent, &LotVertices, &LotFamily)>,
+        mut families: Query<&mut Budget>,
     ) {
         for FromClient { client_id, event } in request_events.read().cloned() {
-            // TODO: validate if command can be applied.
+            if let Some(parent_entity) = event.command.parent_entity() {
+                let valid_parent = cities.contains(parent_entity)
+                    || surfaces
+                        .get(parent_entity)
+                        .is_ok_and(|(city_entity, _)| cities.contains(**city_entity));
+                if !valid_parent || event.command.entity() == Some(parent_entity) {
+                    error!("`{client_id:?}` sends invalid parent `{parent_entity}`");
+                    violation_events.send(ClientViolation {
+                        client_id,
+                        reason: "placing objects on an invalid parent".into(),
+                    });
+                    continue;
+                }
+            }
+
+            // Checked before permissions since missing objects have no city points.
+            if let Some(entity) = event.command.entity() {
+                if !objects.contains(entity) {
+                    error!("`{client_id:?}` references `{entity}` which is not an object");
+                    continue;
+                }
+            }
+
+            let city_points = event.command.city_points(&objects);
+            if !city_points
+                .iter()
+                .all(|&(city_entity, point)| permissions.allowed(client_id, city_entity, point))
+            {
+                error!("`{client_id:?}` has no build rights to apply object command");
+                violation_events.send(ClientViolation {
+                    client_id,
+                    reason: "modifying objects without build rights".into(),
+                });
+                continue;
+            }
+
+            if let Some(translation) = event.command.translation() {
+                if !translation.is_finite() || translation.abs().max_element() > HALF_CITY_SIZE {
+                    error!("`{client_id:?}` sends translation {translation} outside of city size");
+                    violation_events.send(ClientViolation {
+                        client_id,
+                        reason: "placing objects outside of the world".into(),
+                    });
+                    continue;
+                }
+
+                // Height above the ground is validated after placement, when the collider is ready.
+                if let Some((_, slots)) = event
+                    .command
+                    .parent_entity()
+                    .and_then(|parent_entity| surfaces.get(parent_entity).ok())
+                {
+                    if !slots.supports(translation) {
+                        error!("`{client_id:?}` sends translation {translation} outside of surface slots");
+                        violation_events.send(ClientViolation {
+                            client_id,
+                            reason: "placing objects outside of surfaces".into(),
+                        });
+                        continue;
+                    }
+                }
+            }
+
+            // Objects on family lots are paid from the family budget.
+            let family_entity = city_points.last().and_then(|&(city_entity, point)| {
+                lots.iter()
+                    .find(|(parent, vertices, _)| {
+                        ***parent == city_entity && vertices.contains_point(point)
+                    })
+                    .map(|(.., lot_family)| **lot_family)
+            });
+
             let mut confirmation = CommandConfirmation::new(event.id);
-            match event.command {
+            match event.command.clone() {
                 ObjectCommand::Buy {
                     info_path,
-                    city_entity,
+                    parent_entity,
                     translation,
                     rotation,
                 } => {
-                    if translation.y.abs() > HALF_CITY_SIZE {
-                        error!("received translation {translation} with 'y' outside of city size");
+                    let Some(info) = asset_server
+                        .get_handle(&info_path)
+                        .and_then(|handle| objects_info.get(&handle))
+                    else {
+                        error!("`{client_id:?}` tries to buy unknown object {info_path:?}");
+                        continue;
+                    };
+                    if !info.category.is_sold() {
+                        error!("`{client_id:?}` tries to buy not sold object {info_path:?}");
+                        violation_events.send(ClientViolation {
+                            client_id,
+                            reason: "buying objects that are not sold".into(),
+                        });
                         continue;
                     }
+                    if let Some(mut budget) =
+                        family_entity.and_then(|entity| families.get_mut(entity).ok())
+                    {
+                        if !budget.spend(info.price) {
+                            error!("`{client_id:?}` can't afford {info_path:?}");
+                            continue;
+                        }
+                    }
 
                     info!("`{client_id:?}` buys object {info_path:?}");
-                    commands.entity(city_entity).with_children(|parent| {
+                    recorder.record(client_id, &event);
+                    commands.entity(parent_entity).with_children(|parent| {
                         let transform =
                             Transform::from_translation(translation).with_rotation(rotation);
-                        let entity = parent.spawn(ObjectBundle::new(info_path, transform)).id();
+                        let entity = parent
+                            .spawn((
+                                ObjectBundle::new(info_path, transform),
+                                PendingGroundCheck::default(),
+                            ))
+                            .id();
                         confirmation.entity = Some(entity);
                     });
                 }
                 ObjectCommand::Move {
                     entity,
+                    parent_entity,
                     translation,
                     rotation,
                 } => match objects.get_mut(entity) {
-                    Ok(mut transform) => {
+                    Ok((mut transform, parent)) => {
                         info!("`{client_id:?}` moves object `{entity}`");
+                        recorder.record(client_id, &event);
+                        commands.entity(entity).insert(PendingGroundCheck {
+                            previous: Some(*transform),
+                        });
                         transform.translation = translation;
                         transform.rotation = rotation;
+                        if **parent != parent_entity {
+                            debug!("changing parent of `{entity}` to `{parent_entity}`");
+                            commands.entity(entity).set_parent(parent_entity);
+                        }
                     }
                     Err(e) => error!("unable to move object `{entity}`: {e}"),
                 },
-                ObjectCommand::Sell { entity } => {
-                    info!("`{client_id:?}` sells object `{entity}`");
+                ObjectCommand::Sell { entity } => match object_paths.get(entity) {
+                    Ok(object) => {
+                        info!("`{client_id:?}` sells object `{entity}`");
+                        recorder.record(client_id, &event);
+                        let price = asset_server
+                            .get_handle(&object.0)
+                            .and_then(|handle| objects_info.get(&handle))
+                            .map(|info| info.price)
+                            .unwrap_or_default();
+                        if let Some(mut budget) =
+                            family_entity.and_then(|entity| families.get_mut(entity).ok())
+                        {
+                            budget.earn(price);
+                        }
+                        commands.entity(entity).despawn_recursive();
+                    }
+                    Err(e) => error!("unable to sell object `{entity}`: {e}"),
+                },
+                ObjectCommand::Replace { entity, info_path } => {
+                    if !placeholders.contains(entity) {
+                        error!("`{client_id:?}` tries to replace regular object `{entity}`");
+                        violation_events.send(ClientViolation {
+                            client_id,
+                            reason: "replacing objects that are not placeholders".into(),
+                        });
+                        continue;
+                    }
+                    let Some(info) = asset_server
+                        .get_handle(&info_path)
+                        .and_then(|handle| objects_info.get(&handle))
+                    else {
+                        error!(
+                            "`{client_id:?}` tries to replace with unknown object {info_path:?}"
+                        );
+                        continue;
+                    };
+                    if !info.category.is_sold() {
+                        error!("`{client_id:?}` tries to buy not sold object {info_path:?}");
+                        violation_events.send(ClientViolation {
+                            client_id,
+                            reason: "buying objects that are not sold".into(),
+                        });
+                        continue;
+                    }
+                    if let Some(mut budget) =
+                        family_entity.and_then(|entity| families.get_mut(entity).ok())
+                    {
+                        if !budget.spend(info.price) {
+                            error!("`{client_id:?}` can't afford {info_path:?}");
+                            continue;
+                        }
+                    }
+
+                    let (&transform, parent) = objects
+                        .get(entity)
+                        .expect("object existence should be checked earlier");
+                    info!("`{client_id:?}` replaces object `{entity}` with {info_path:?}");
+                    recorder.record(client_id, &event);
                     commands.entity(entity).despawn_recursive();
+                    commands.entity(**parent).with_children(|parent| {
+                        let entity = parent.spawn(ObjectBundle::new(info_path, transform)).id();
+                        confirmation.entity = Some(entity);
+                    });
                 }
             }
 
@@ -144,7 +421,7 @@ impl ObjectPlugin {
 }
 
 #[derive(Bundle)]
-struct ObjectBundle {
+pub(crate) struct ObjectBundle {
     object: Object,
     transform: Transform,
     parent_sync: ParentSync,
@@ -152,7 +429,7 @@ struct ObjectBundle {
 }
 
 impl ObjectBundle {
-    fn new(info_path: AssetPath<'static>, transform: Transform) -> Self {
+    pub(crate) fn new(info_path: AssetPath<'static>, transform: Transform) -> Self {
         Self {
             object: Object(info_path),
             transform,
@@ -165,24 +442,211 @@ impl ObjectBundle {
 /// Contains path to the object info.
 #[derive(Clone, Component, Debug, Default, Reflect, Serialize, Deserialize)]
 #[reflect(Component)]
-pub(crate) struct Object(AssetPath<'static>);
+pub(crate) struct Object(pub(super) AssetPath<'static>);
+
+/// Marks an object whose info or scene can't be loaded.
+///
+/// Such objects are displayed as a box and keep their [`Object`] to stay in the save.
+/// Contains the original info path.
+#[derive(Component, Deref)]
+pub struct Placeholder(AssetPath<'static>);
+
+#[derive(Bundle)]
+struct PlaceholderBundle {
+    placeholder: Placeholder,
+    name: Name,
+    hoverable: Hoverable,
+    outline: OutlineBundle,
+    rigid_body: RigidBody,
+    collider: Collider,
+    collision_layers: CollisionLayers,
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+    global_transform: GlobalTransform,
+    visibility: VisibilityBundle,
+}
+
+impl PlaceholderBundle {
+    fn new(placeholder_assets: &PlaceholderAssets, info_path: AssetPath<'static>) -> Self {
+        let half_size = PLACEHOLDER_SIZE / 2.0;
+        Self {
+            placeholder: Placeholder(info_path),
+            name: Name::new("Missing object"),
+            hoverable: Hoverable,
+            outline: OutlineBundle::highlighting(),
+            rigid_body: RigidBody::Kinematic,
+            collider: Collider::compound(vec![(
+                Vec3::Y * half_size,
+                Quat::IDENTITY,
+                Collider::cuboid(PLACEHOLDER_SIZE, PLACEHOLDER_SIZE, PLACEHOLDER_SIZE),
+            )]),
+            collision_layers: CollisionLayers::new(
+                Layer::Object,
+                [Layer::PlacingObject, Layer::Wall, Layer::PlacingWall],
+            ),
+            mesh: placeholder_assets.mesh.clone(),
+            material: placeholder_assets.material.clone(),
+            global_transform: Default::default(),
+            visibility: Default::default(),
+        }
+    }
+}
+
+/// Size of the [`Placeholder`] box side in meters.
+const PLACEHOLDER_SIZE: f32 = 0.5;
+
+#[derive(Resource)]
+struct PlaceholderAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for PlaceholderAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = Mesh::from(Cuboid::from_length(PLACEHOLDER_SIZE))
+            .translated_by(Vec3::Y * PLACEHOLDER_SIZE / 2.0);
+        let mesh = world.resource_mut::<Assets<Mesh>>().add(mesh);
+        let material = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(Color::srgb(1.0, 0.0, 1.0));
+
+        Self { mesh, material }
+    }
+}
+
+/// Requests replacing an object with a different one at the same position.
+#[derive(Event)]
+pub struct ObjectReplace {
+    pub entity: Entity,
+    pub info_id: AssetId<ObjectInfo>,
+}
+
+/// Tags from the object info.
+///
+/// Inserted on initialization for systems that search objects by their capabilities.
+#[derive(Component, Deref)]
+pub struct ObjectTags(Vec<ObjectTag>);
 
 #[derive(Clone, Deserialize, Serialize)]
 enum ObjectCommand {
+    /// Spawns a new object.
+    ///
+    /// Parent is a city or an object with surface slots.
+    /// Translation and rotation are relative to the parent.
     Buy {
         info_path: AssetPath<'static>,
-        city_entity: Entity,
+        parent_entity: Entity,
         translation: Vec3,
         rotation: Quat,
     },
     Move {
         entity: Entity,
+        parent_entity: Entity,
         translation: Vec3,
         rotation: Quat,
     },
     Sell {
         entity: Entity,
     },
+    /// Spawns a new object instead of the existing one.
+    Replace {
+        entity: Entity,
+        info_path: AssetPath<'static>,
+    },
+}
+
+impl ObjectCommand {
+    /// Returns the requested parent for the object.
+    fn parent_entity(&self) -> Option<Entity> {
+        match *self {
+            Self::Buy { parent_entity, .. } | Self::Move { parent_entity, .. } => {
+                Some(parent_entity)
+            }
+            Self::Sell { .. } | Self::Replace { .. } => None,
+        }
+    }
+
+    /// Returns the requested translation relative to the new parent.
+    fn translation(&self) -> Option<Vec3> {
+        match *self {
+            Self::Buy { translation, .. } | Self::Move { translation, .. } => Some(translation),
+            Self::Sell { .. } | Self::Replace { .. } => None,
+        }
+    }
+
+    /// Returns city entities and positions inside them affected by the command.
+    ///
+    /// Used to validate build rights. The last point is the resulting object position.
+    fn city_points(
+        &self,
+        objects: &Query<(&mut Transform, &Parent), (With<Object>, Without<City>)>,
+    ) -> Vec<(Entity, Vec2)> {
+        let mut points = Vec::new();
+        match *self {
+            Self::Buy {
+                parent_entity,
+                translation,
+                ..
+            } => points.push(city_point(objects, parent_entity, translation)),
+            Self::Move {
+                entity,
+                parent_entity,
+                translation,
+                ..
+            } => {
+                if let Ok((transform, parent)) = objects.get(entity) {
+                    points.push(city_point(objects, **parent, transform.translation));
+                }
+                points.push(city_point(objects, parent_entity, translation));
+            }
+            Self::Sell { entity } | Self::Replace { entity, .. } => {
+                if let Ok((transform, parent)) = objects.get(entity) {
+                    points.push(city_point(objects, **parent, transform.translation));
+                }
+            }
+        }
+
+        points
+    }
+}
+
+/// Converts a translation relative to the parent into a city entity and a position inside it.
+///
+/// Parent is a city or an object with surface slots.
+fn city_point(
+    objects: &Query<(&mut Transform, &Parent), (With<Object>, Without<City>)>,
+    parent_entity: Entity,
+    translation: Vec3,
+) -> (Entity, Vec2) {
+    match objects.get(parent_entity) {
+        Ok((parent_transform, city_entity)) => (
+            **city_entity,
+            parent_transform.transform_point(translation).xz(),
+        ),
+        Err(_) => (parent_entity, translation.xz()),
+    }
+}
+
+impl Mutation for ObjectCommand {
+    const TARGET: &'static str = "object";
+
+    fn kind(&self) -> MutationKind {
+        match self {
+            Self::Buy { .. } => MutationKind::Spawn,
+            Self::Move { .. } => MutationKind::Move,
+            Self::Sell { .. } => MutationKind::Delete,
+            Self::Replace { .. } => MutationKind::Edit,
+        }
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        match *self {
+            Self::Buy { .. } => None,
+            Self::Move { entity, .. } | Self::Sell { entity } | Self::Replace { entity, .. } => {
+                Some(entity)
+            }
+        }
+    }
 }
 
 impl PendingCommand for ObjectCommand {
@@ -198,9 +662,12 @@ impl PendingCommand for ObjectCommand {
                 entity: Entity::PLACEHOLDER,
             },
             Self::Move { entity, .. } => {
-                let transform = world.get::<Transform>(entity).unwrap();
+                let entity = world.entity(entity);
+                let parent = entity.get::<Parent>().unwrap();
+                let transform = entity.get::<Transform>().unwrap();
                 Self::Move {
-                    entity,
+                    entity: entity.id(),
+                    parent_entity: **parent,
                     translation: transform.translation,
                     rotation: transform.rotation,
                 }
@@ -213,11 +680,19 @@ impl PendingCommand for ObjectCommand {
                 let transform = entity.get::<Transform>().unwrap();
                 Self::Buy {
                     info_path,
-                    city_entity: **parent,
+                    parent_entity: **parent,
                     translation: transform.translation,
                     rotation: transform.rotation,
                 }
             }
+            Self::Replace { entity, .. } => {
+                recorder.record(entity);
+                // Placeholders can't be restored, so undoing sells the replacement.
+                Self::Sell {
+                    // Correct entity will be set after the server confirmation.
+                    entity: Entity::PLACEHOLDER,
+                }
+            }
         };
 
         world.send_event(CommandRequest { id, command: *self });
@@ -232,11 +707,14 @@ impl ConfirmableCommand for ObjectCommand {
         mut recorder: EntityRecorder,
         confirmation: CommandConfirmation,
     ) -> Box<dyn PendingCommand> {
-        if let Self::Sell { entity } = &mut *self {
-            *entity = confirmation
-                .entity
-                .expect("confirmation for object buying should contain an entity");
-            recorder.record(*entity);
+        match &mut *self {
+            Self::Sell { entity } => {
+                *entity = confirmation
+                    .entity
+                    .expect("confirmation for object buying or replacing should contain an entity");
+                recorder.record(*entity);
+            }
+            Self::Buy { .. } | Self::Move { .. } | Self::Replace { .. } => (),
         }
 
         self
@@ -246,9 +724,20 @@ impl ConfirmableCommand for ObjectCommand {
 impl MapEntities for ObjectCommand {
     fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
         match self {
-            Self::Buy { .. } => (),
-            Self::Move { entity, .. } => *entity = entity_mapper.map_entity(*entity),
-            Self::Sell { entity } => *entity = entity_mapper.map_entity(*entity),
+            Self::Buy { parent_entity, .. } => {
+                *parent_entity = entity_mapper.map_entity(*parent_entity)
+            }
+            Self::Move {
+                entity,
+                parent_entity,
+                ..
+            } => {
+                *entity = entity_mapper.map_entity(*entity);
+                *parent_entity = entity_mapper.map_entity(*parent_entity);
+            }
+            Self::Sell { entity } | Self::Replace { entity, .. } => {
+                *entity = entity_mapper.map_entity(*entity)
+            }
         };
     }
 }
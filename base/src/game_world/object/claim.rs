@@ -0,0 +1,91 @@
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{core::GameState, game_world::actor::Actor};
+
+/// Personal objects that actors can claim, like beds or computers.
+pub(super) struct ClaimPlugin;
+
+impl Plugin for ClaimPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Claimable>()
+            .register_type::<ClaimedBy>()
+            .replicate_mapped::<ClaimedBy>()
+            .add_systems(
+                Update,
+                Self::release
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+impl ClaimPlugin {
+    /// Removes claims of actors that no longer exist.
+    fn release(
+        mut commands: Commands,
+        mut removed_actors: RemovedComponents<Actor>,
+        objects: Query<(Entity, &ClaimedBy)>,
+    ) {
+        for actor_entity in removed_actors.read() {
+            for (object_entity, _) in objects
+                .iter()
+                .filter(|(_, claimed_by)| claimed_by.0 == actor_entity)
+            {
+                debug!("releasing claim of `{actor_entity}` on `{object_entity}`");
+                commands.entity(object_entity).remove::<ClaimedBy>();
+            }
+        }
+    }
+}
+
+/// Marks an object that can be claimed by an actor.
+///
+/// Listed in object metadata.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component, Default)]
+pub(crate) struct Claimable;
+
+/// Actor that claimed the object.
+///
+/// Other actors prefer unclaimed objects while picking a slot, see [`ClaimPreference`].
+#[derive(Clone, Component, Copy, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+pub(crate) struct ClaimedBy(pub(crate) Entity);
+
+impl ClaimedBy {
+    /// Returns how much the actor wants to use an object with the given claim.
+    pub(crate) fn preference(claimed_by: Option<&Self>, actor_entity: Entity) -> ClaimPreference {
+        match claimed_by {
+            Some(claimed_by) if claimed_by.0 == actor_entity => ClaimPreference::Own,
+            Some(_) => ClaimPreference::Other,
+            None => ClaimPreference::Free,
+        }
+    }
+}
+
+impl FromWorld for ClaimedBy {
+    fn from_world(_world: &mut World) -> Self {
+        Self(Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for ClaimedBy {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
+
+/// Ordered from the least to the most preferred.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub(crate) enum ClaimPreference {
+    /// Claimed by someone else.
+    Other,
+    Free,
+    /// Claimed by the actor.
+    Own,
+}
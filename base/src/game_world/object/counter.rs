@@ -0,0 +1,241 @@
+use std::path::Path;
+
+use bevy::{asset::AssetPath, prelude::*};
+
+use super::{placing_object::PlacingObject, Object};
+use crate::{
+    asset::{
+        self,
+        info::{object_info::ObjectInfo, MapPaths, ReflectMapPaths},
+    },
+    core::GameState,
+};
+
+/// Merges adjacent counters into continuous runs.
+///
+/// Each counter picks a scene variant based on its neighbors, similar to wall connections.
+pub(super) struct CounterPlugin;
+
+impl Plugin for CounterPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Counter>().add_systems(
+            Update,
+            Self::update_variants.run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+impl CounterPlugin {
+    /// Recalculates variants for all counters when any of them is changed or removed.
+    fn update_variants(
+        mut commands: Commands,
+        mut removed_counters: RemovedComponents<Counter>,
+        asset_server: Res<AssetServer>,
+        objects_info: Res<Assets<ObjectInfo>>,
+        changed_counters: Query<
+            (),
+            (
+                With<Counter>,
+                Without<PlacingObject>,
+                Or<(Changed<Transform>, Changed<Visibility>, Changed<Parent>)>,
+            ),
+        >,
+        counters: Query<
+            (
+                Entity,
+                &Object,
+                &Counter,
+                &Parent,
+                &Transform,
+                &Visibility,
+                Option<&CounterVariant>,
+            ),
+            Without<PlacingObject>,
+        >,
+    ) {
+        if removed_counters.read().count() == 0 && changed_counters.is_empty() {
+            return;
+        }
+
+        for (entity, object, counter, parent, transform, visibility, current_variant) in &counters {
+            if visibility == Visibility::Hidden {
+                continue;
+            }
+
+            let neighbors = counters
+                .iter()
+                .filter(
+                    |&(other_entity, _, _, other_parent, _, other_visibility, _)| {
+                        other_entity != entity
+                            && other_parent == parent
+                            && other_visibility != Visibility::Hidden
+                    },
+                )
+                .map(|(_, _, other_counter, _, other_transform, ..)| {
+                    (*other_transform, other_counter.half_size)
+                });
+
+            let variant = CounterVariant::new(*transform, counter.half_size, neighbors);
+            if current_variant.copied().unwrap_or_default() == variant {
+                continue;
+            }
+
+            let scene = match counter.scene(variant) {
+                Some(scene) => scene.clone(),
+                None => {
+                    let Some(info) = asset_server
+                        .get_handle(&object.0)
+                        .and_then(|info_handle| objects_info.get(&info_handle))
+                    else {
+                        error!("unable to get info for counter `{entity}`");
+                        continue;
+                    };
+                    info.scene.clone()
+                }
+            };
+
+            debug!("changing counter variant for `{entity}` to `{variant:?}`");
+            let scene_handle: Handle<Scene> = asset_server.load(scene);
+            commands.entity(entity).insert((variant, scene_handle));
+        }
+    }
+}
+
+/// Modular counter that changes its scene depending on adjacent counters.
+///
+/// The front of the counter is its local Z axis.
+/// Without neighbors the scene from the object info is used.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, MapPaths)]
+pub(crate) struct Counter {
+    /// Half of the counter size along X and Z axes.
+    half_size: Vec2,
+    left_end: AssetPath<'static>,
+    right_end: AssetPath<'static>,
+    middle: AssetPath<'static>,
+    left_corner: AssetPath<'static>,
+    right_corner: AssetPath<'static>,
+}
+
+impl Counter {
+    fn scene(&self, variant: CounterVariant) -> Option<&AssetPath<'static>> {
+        match variant {
+            CounterVariant::Single => None,
+            CounterVariant::LeftEnd => Some(&self.left_end),
+            CounterVariant::RightEnd => Some(&self.right_end),
+            CounterVariant::Middle => Some(&self.middle),
+            CounterVariant::LeftCorner => Some(&self.left_corner),
+            CounterVariant::RightCorner => Some(&self.right_corner),
+        }
+    }
+}
+
+impl MapPaths for Counter {
+    fn map_paths(&mut self, dir: &Path) {
+        asset::change_parent_dir(&mut self.left_end, dir);
+        asset::change_parent_dir(&mut self.right_end, dir);
+        asset::change_parent_dir(&mut self.middle, dir);
+        asset::change_parent_dir(&mut self.left_corner, dir);
+        asset::change_parent_dir(&mut self.right_corner, dir);
+    }
+}
+
+/// Currently displayed counter scene.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+enum CounterVariant {
+    /// No neighbors.
+    #[default]
+    Single,
+    /// Beginning of a run, the neighbor is on the right.
+    LeftEnd,
+    /// End of a run, the neighbor is on the left.
+    RightEnd,
+    /// Neighbors on both sides.
+    Middle,
+    /// Perpendicular run in front and the neighbor is on the right.
+    LeftCorner,
+    /// Perpendicular run in front and the neighbor is on the left.
+    RightCorner,
+}
+
+impl CounterVariant {
+    /// Maximum gap between counters to consider them connected.
+    const TOLERANCE: f32 = 0.01;
+
+    /// Picks variant based on which sides of the counter have neighbors.
+    fn new(
+        transform: Transform,
+        half_size: Vec2,
+        neighbors: impl Iterator<Item = (Transform, Vec2)>,
+    ) -> Self {
+        let inverse_rotation = transform.rotation.inverse();
+        let mut left = false;
+        let mut right = false;
+        let mut front = false;
+        for (other_transform, other_half_size) in neighbors {
+            let disp =
+                (inverse_rotation * (other_transform.translation - transform.translation)).xz();
+            let other_x = inverse_rotation * other_transform.rotation * Vec3::X;
+            let perpendicular = other_x.z.abs() > other_x.x.abs();
+            let other_half_size = if perpendicular {
+                other_half_size.yx()
+            } else {
+                other_half_size
+            };
+
+            let limit = half_size + other_half_size + Vec2::splat(Self::TOLERANCE);
+            if disp.x.abs() > limit.x || disp.y.abs() > limit.y {
+                continue;
+            }
+
+            // The touching side is the one with the larger relative distance.
+            if disp.x.abs() / limit.x > disp.y.abs() / limit.y {
+                if disp.x.is_sign_negative() {
+                    left = true;
+                } else {
+                    right = true;
+                }
+            } else if perpendicular && disp.y.is_sign_positive() {
+                front = true;
+            }
+        }
+
+        match (left, right, front) {
+            (false, false, _) => Self::Single,
+            (false, true, false) => Self::LeftEnd,
+            (true, false, false) => Self::RightEnd,
+            (true, true, _) => Self::Middle,
+            (false, true, true) => Self::LeftCorner,
+            (true, false, true) => Self::RightCorner,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn l_shaped_run() {
+        const HALF_SIZE: Vec2 = Vec2::new(0.4, 0.3);
+        let corner = Transform::from_xyz(0.4, 0.0, 0.3);
+        let side = Transform::from_xyz(1.2, 0.0, 0.3);
+        let front =
+            Transform::from_xyz(0.3, 0.0, 1.0).with_rotation(Quat::from_rotation_y(FRAC_PI_2));
+
+        let variant = |transform, neighbors: &[Transform]| {
+            CounterVariant::new(
+                transform,
+                HALF_SIZE,
+                neighbors.iter().map(|&neighbor| (neighbor, HALF_SIZE)),
+            )
+        };
+
+        assert_eq!(variant(corner, &[side, front]), CounterVariant::LeftCorner);
+        assert_eq!(variant(side, &[corner, front]), CounterVariant::RightEnd);
+        assert_eq!(variant(front, &[corner, side]), CounterVariant::LeftEnd);
+        assert_eq!(variant(corner, &[]), CounterVariant::Single);
+    }
+}
@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::skills::Cooking,
+        clock,
+        rng::{RngStream, WorldRng},
+    },
+};
+
+/// Spreads fire between flammable objects.
+///
+/// Fires start from [`Stove`]s used by unskilled cooks.
+///
+/// Objects that burn for too long become [`FireDamaged`] and need to be repaired.
+pub(super) struct FirePlugin;
+
+impl Plugin for FirePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Flammable>()
+            .register_type::<Stove>()
+            .register_type::<Burning>()
+            .register_type::<FireDamaged>()
+            .replicate::<Burning>()
+            .replicate::<FireDamaged>()
+            .add_systems(
+                FixedUpdate,
+                Self::burn
+                    .run_if(clock::on_game_timer(BURN_INTERVAL))
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                Update,
+                (Self::spawn_flames, Self::flicker).run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::despawn_flames.run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// How often fires are updated in game time.
+///
+/// Updating less often than every tick avoids replicating [`Burning`] each tick.
+const BURN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Game minutes for a fire to burn out an object.
+const BURN_TIME: f32 = 60.0;
+
+/// Burning progress after which the fire starts spreading.
+const SPREAD_PROGRESS: f32 = 0.3;
+
+/// Maximum distance to spread fire.
+const SPREAD_RADIUS: f32 = 1.5;
+
+/// Chance per game minute to ignite a nearby object.
+const SPREAD_CHANCE: f32 = 0.05;
+
+/// Base intensity of the flickering fire light.
+const FLAMES_INTENSITY: f32 = 100_000.0;
+
+/// Burning progress after which an extinguished object is damaged.
+pub(crate) const DAMAGE_PROGRESS: f32 = 0.2;
+
+impl FirePlugin {
+    fn burn(
+        mut commands: Commands,
+        mut world_rng: ResMut<WorldRng>,
+        mut burning: Query<(Entity, &Parent, &Transform, &mut Burning)>,
+        objects: Query<
+            (Entity, &Parent, &Transform),
+            (With<Flammable>, Without<Burning>, Without<FireDamaged>),
+        >,
+    ) {
+        let rng = world_rng.stream(RngStream::Events);
+        let delta = BURN_INTERVAL.as_secs_f32() / 60.0;
+        for (entity, parent, transform, mut burning) in &mut burning {
+            burning.progress += delta / BURN_TIME;
+            if burning.progress >= 1.0 {
+                info!("`{entity}` burned out");
+                commands
+                    .entity(entity)
+                    .remove::<Burning>()
+                    .insert(FireDamaged);
+                continue;
+            }
+
+            if burning.progress < SPREAD_PROGRESS {
+                continue;
+            }
+
+            for (object_entity, object_parent, object_transform) in &objects {
+                if object_parent == parent
+                    && object_transform.translation.distance(transform.translation) <= SPREAD_RADIUS
+                    && rng.gen::<f32>() < SPREAD_CHANCE * delta
+                {
+                    info!("fire spreads from `{entity}` to `{object_entity}`");
+                    commands.entity(object_entity).insert(Burning::default());
+                }
+            }
+        }
+    }
+
+    fn spawn_flames(mut commands: Commands, objects: Query<Entity, Added<Burning>>) {
+        for entity in &objects {
+            debug!("spawning flames for `{entity}`");
+            commands.entity(entity).with_children(|parent| {
+                parent.spawn((
+                    Flames,
+                    PointLightBundle {
+                        point_light: PointLight {
+                            color: Color::srgb(1.0, 0.5, 0.1),
+                            intensity: FLAMES_INTENSITY,
+                            range: 5.0,
+                            ..Default::default()
+                        },
+                        transform: Transform::from_translation(Vec3::Y * 0.5),
+                        ..Default::default()
+                    },
+                ));
+            });
+        }
+    }
+
+    fn flicker(time: Res<Time>, mut flames: Query<&mut PointLight, With<Flames>>) {
+        let elapsed = time.elapsed_seconds();
+        for mut light in &mut flames {
+            let flicker = (elapsed * 11.0).sin() * (elapsed * 7.0).cos();
+            light.intensity = FLAMES_INTENSITY * (1.0 + 0.3 * flicker);
+        }
+    }
+
+    fn despawn_flames(
+        mut commands: Commands,
+        mut removed_burning: RemovedComponents<Burning>,
+        objects: Query<&Children>,
+        flames: Query<(), With<Flames>>,
+    ) {
+        for children in objects.iter_many(removed_burning.read()) {
+            for &entity in children {
+                if flames.get(entity).is_ok() {
+                    debug!("despawning flames `{entity}`");
+                    commands.entity(entity).despawn();
+                }
+            }
+        }
+    }
+}
+
+/// Object that can catch fire from a nearby fire.
+///
+/// Usually defined in object metadata.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+pub(crate) struct Flammable;
+
+/// Object used for cooking that can catch fire.
+///
+/// Usually defined in object metadata.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+pub(crate) struct Stove {
+    /// Chance to catch fire when cooked on by an actor without [`Cooking`] skill.
+    fire_chance: f32,
+}
+
+impl Stove {
+    /// Returns the chance to catch fire, experienced cooks rarely start fires.
+    pub(crate) fn fire_chance(&self, cooking: Cooking) -> f32 {
+        let skill = *cooking as f32 / Cooking::MAX as f32;
+        self.fire_chance * (1.0 - 0.9 * skill)
+    }
+}
+
+/// Fire on an object.
+#[derive(Clone, Component, Copy, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub(crate) struct Burning {
+    /// Burn out progress from 0 to 1.
+    pub(crate) progress: f32,
+}
+
+/// Marks an object damaged by fire that needs to be repaired.
+#[derive(Component, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub(crate) struct FireDamaged;
+
+/// Light spawned for [`Burning`] objects.
+#[derive(Component)]
+struct Flames;
@@ -0,0 +1,221 @@
+use std::{path::Path, time::Duration};
+
+use bevy::{asset::AssetPath, prelude::*};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asset::{
+        self,
+        info::{MapPaths, ReflectMapPaths},
+    },
+    core::GameState,
+    game_world::{clock, collectable::Collectable},
+};
+
+pub(super) struct GardenPlotPlugin;
+
+impl Plugin for GardenPlotPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GardenPlot>()
+            .register_type::<Plant>()
+            .register_type::<PlantCare>()
+            .replicate::<Plant>()
+            .replicate::<PlantCare>()
+            .add_systems(
+                FixedUpdate,
+                Self::grow
+                    .run_if(clock::on_game_timer(GROW_INTERVAL))
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                Update,
+                Self::update_scenes.run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// How often plants are updated in game time.
+///
+/// Updating less often than every tick avoids replicating [`PlantCare`] each tick.
+const GROW_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Game minutes after which a watered plant becomes dry.
+const DRYING_TIME: f32 = 240.0;
+
+/// Game minutes after which a plot becomes fully covered with weeds.
+const WEEDS_TIME: f32 = 480.0;
+
+impl GardenPlotPlugin {
+    /// Swaps the plant scene when the growth stage changes.
+    fn update_scenes(
+        mut commands: Commands,
+        asset_server: Res<AssetServer>,
+        plots: Query<&GardenPlot>,
+        plants: Query<(Entity, &Parent, &Plant), Changed<Plant>>,
+    ) {
+        for (entity, parent, plant) in &plants {
+            let plot = plots
+                .get(**parent)
+                .expect("plants should be children of garden plots");
+            let (Some(crop), Some(stage)) = (plot.crops.get(plant.crop), plot.stage(plant)) else {
+                error!("`{entity}` has invalid crop or stage");
+                continue;
+            };
+
+            debug!(
+                "changing scene for `{entity}` to stage {} of '{}'",
+                plant.stage, crop.name
+            );
+            let scene_handle: Handle<Scene> = asset_server.load(stage.scene.clone());
+            commands.entity(entity).insert((
+                scene_handle,
+                SpatialBundle::from_transform(
+                    Transform::from_translation(plot.plant_offset)
+                        .with_scale(Vec3::splat(stage.scale)),
+                ),
+            ));
+        }
+    }
+
+    fn grow(
+        plots: Query<&GardenPlot>,
+        mut plants: Query<(Entity, &Parent, &mut Plant, &mut PlantCare)>,
+    ) {
+        let delta = GROW_INTERVAL.as_secs_f32() / 60.0;
+        for (entity, parent, mut plant, mut care) in &mut plants {
+            let plot = plots
+                .get(**parent)
+                .expect("plants should be children of garden plots");
+            let (Some(crop), Some(stage)) = (plot.crops.get(plant.crop), plot.stage(&plant)) else {
+                error!("`{entity}` has invalid crop or stage");
+                continue;
+            };
+
+            care.hydration = (care.hydration - delta / DRYING_TIME).max(0.0);
+            care.weeds = (care.weeds + delta / WEEDS_TIME).min(1.0);
+
+            if plant.stage + 1 >= crop.stages.len() || care.hydration == 0.0 {
+                continue;
+            }
+
+            // Weeds slow down the growth.
+            care.growth += delta * (1.0 - care.weeds * 0.5);
+            if care.growth >= stage.duration {
+                care.growth = 0.0;
+                plant.stage += 1;
+                debug!("advancing '{}' to stage {}", crop.name, plant.stage);
+            }
+        }
+    }
+}
+
+/// Object on which crops can be planted.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component, MapPaths)]
+pub(crate) struct GardenPlot {
+    /// Position of the plant relative to the plot.
+    pub(crate) plant_offset: Vec3,
+    pub(crate) crops: Vec<Crop>,
+}
+
+impl GardenPlot {
+    fn stage(&self, plant: &Plant) -> Option<&GrowthStage> {
+        self.crops
+            .get(plant.crop)
+            .and_then(|crop| crop.stages.get(plant.stage))
+    }
+
+    pub(crate) fn is_grown(&self, plant: &Plant) -> bool {
+        self.crops
+            .get(plant.crop)
+            .is_some_and(|crop| plant.stage + 1 >= crop.stages.len())
+    }
+}
+
+impl MapPaths for GardenPlot {
+    fn map_paths(&mut self, dir: &Path) {
+        for stage in self.crops.iter_mut().flat_map(|crop| &mut crop.stages) {
+            asset::change_parent_dir(&mut stage.scene, dir);
+        }
+    }
+}
+
+#[derive(Reflect)]
+pub(crate) struct Crop {
+    pub(crate) name: String,
+    stages: Vec<GrowthStage>,
+
+    /// Collectable obtained after harvesting the last stage.
+    pub(crate) harvest: Collectable,
+}
+
+#[derive(Reflect)]
+struct GrowthStage {
+    scene: AssetPath<'static>,
+    scale: f32,
+
+    /// Game minutes of growth required to advance to the next stage.
+    duration: f32,
+}
+
+#[derive(Bundle)]
+pub(crate) struct PlantBundle {
+    plant: Plant,
+    care: PlantCare,
+    parent_sync: ParentSync,
+    replication: Replicated,
+}
+
+impl PlantBundle {
+    pub(crate) fn new(crop: usize) -> Self {
+        Self {
+            plant: Plant { crop, stage: 0 },
+            care: Default::default(),
+            parent_sync: Default::default(),
+            replication: Replicated,
+        }
+    }
+}
+
+/// Crop planted on the parent [`GardenPlot`].
+#[derive(Clone, Component, Copy, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub(crate) struct Plant {
+    /// Index of the crop inside [`GardenPlot::crops`].
+    crop: usize,
+
+    /// Current growth stage index.
+    stage: usize,
+}
+
+impl Plant {
+    pub(crate) fn crop(&self) -> usize {
+        self.crop
+    }
+}
+
+/// State that requires regular actor attention.
+#[derive(Clone, Component, Copy, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub(crate) struct PlantCare {
+    /// Game minutes spent on the current stage.
+    pub(crate) growth: f32,
+
+    /// Water level from 0 to 1.
+    pub(crate) hydration: f32,
+
+    /// Weeds coverage from 0 to 1.
+    pub(crate) weeds: f32,
+}
+
+impl Default for PlantCare {
+    fn default() -> Self {
+        Self {
+            growth: 0.0,
+            hydration: 1.0,
+            weeds: 0.0,
+        }
+    }
+}
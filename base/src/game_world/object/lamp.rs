@@ -0,0 +1,281 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::GameState,
+    game_world::{
+        city::lot::LotVertices, clock::GameClock, family::utilities::UtilitiesShutOff,
+        object::wear::Broken, player_camera::PlayerCamera,
+    },
+};
+
+/// Spawns lights for lamp objects and keeps their count within a per-lot budget.
+pub(super) struct LampPlugin;
+
+impl Plugin for LampPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<LightSource>()
+            .register_type::<LampState>()
+            .replicate::<LampState>()
+            .add_systems(
+                Update,
+                (
+                    (Self::init_states, Self::switch_automatically)
+                        .chain()
+                        .run_if(server_or_singleplayer),
+                    (Self::spawn_lights, Self::update_lights).chain(),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Maximum number of enabled lights rendered per lot.
+///
+/// Lights outside of lots share a separate budget.
+const LOT_LIGHTS_BUDGET: usize = 8;
+
+/// Distance within which lights merge into a single light when over budget.
+const CLUSTER_RADIUS: f32 = 2.0;
+
+impl LampPlugin {
+    fn init_states(
+        mut commands: Commands,
+        lamps: Query<Entity, (With<LightSource>, Without<LampState>)>,
+    ) {
+        for entity in &lamps {
+            debug!("initializing lamp state for `{entity}`");
+            commands.entity(entity).insert(LampState::default());
+        }
+    }
+
+    fn switch_automatically(clocks: Query<&GameClock>, mut lamps: Query<(Entity, &mut LampState)>) {
+        let Ok(clock) = clocks.get_single() else {
+            return;
+        };
+
+        let night = clock.is_night();
+        for (entity, mut state) in &mut lamps {
+            if state.automatic && state.enabled != night {
+                debug!("switching `{entity}` to {night}");
+                state.enabled = night;
+            }
+        }
+    }
+
+    fn spawn_lights(
+        mut commands: Commands,
+        lamps: Query<(Entity, &LightSource), Added<LightSource>>,
+    ) {
+        for (entity, source) in &lamps {
+            debug!("spawning {:?} light for `{entity}`", source.kind);
+            commands.entity(entity).with_children(|parent| {
+                let transform =
+                    Transform::from_translation(source.offset).looking_to(Vec3::NEG_Y, Vec3::Z);
+                match source.kind {
+                    LightKind::Point => parent.spawn((
+                        LampLight,
+                        PointLightBundle {
+                            point_light: PointLight {
+                                color: source.color,
+                                intensity: source.intensity,
+                                range: source.range,
+                                shadows_enabled: true,
+                                ..Default::default()
+                            },
+                            transform,
+                            visibility: Visibility::Hidden,
+                            ..Default::default()
+                        },
+                    )),
+                    LightKind::Spot => parent.spawn((
+                        LampLight,
+                        SpotLightBundle {
+                            spot_light: SpotLight {
+                                color: source.color,
+                                intensity: source.intensity,
+                                range: source.range,
+                                shadows_enabled: true,
+                                ..Default::default()
+                            },
+                            transform,
+                            visibility: Visibility::Hidden,
+                            ..Default::default()
+                        },
+                    )),
+                };
+            });
+        }
+    }
+
+    /// Shows enabled lights closest to the camera within [`LOT_LIGHTS_BUDGET`].
+    ///
+    /// Over-budget lights near a visible light are merged into it by increasing its intensity,
+    /// the rest are culled.
+    fn update_lights(
+        cameras: Query<&GlobalTransform, With<PlayerCamera>>,
+        lots: Query<(&Parent, &LotVertices)>,
+        lamps: Query<(
+            &Parent,
+            &Transform,
+            &LightSource,
+            Option<&LampState>,
+            Has<UtilitiesShutOff>,
+            Has<Broken>,
+        )>,
+        mut lights: Query<
+            (
+                Entity,
+                &Parent,
+                &GlobalTransform,
+                &mut Visibility,
+                Option<&mut PointLight>,
+                Option<&mut SpotLight>,
+            ),
+            With<LampLight>,
+        >,
+    ) {
+        let camera_translation = cameras
+            .get_single()
+            .map(|transform| transform.translation())
+            .unwrap_or_default();
+
+        // Enabled lights grouped by lot index, `None` for lights outside of lots.
+        let mut groups: Vec<(Option<usize>, Vec<(Entity, Vec3)>)> = Vec::new();
+        for (entity, parent, transform, ..) in &lights {
+            let Ok((city_parent, lamp_transform, _, state, shut_off, broken)) = lamps.get(**parent)
+            else {
+                continue;
+            };
+            if shut_off || broken || !state.is_some_and(|state| state.enabled) {
+                continue;
+            }
+
+            let lot = lots.iter().position(|(lot_parent, vertices)| {
+                lot_parent == city_parent
+                    && vertices.contains_point(lamp_transform.translation.xz())
+            });
+            let translation = transform.translation();
+            match groups.iter_mut().find(|(group_lot, _)| *group_lot == lot) {
+                Some((_, group)) => group.push((entity, translation)),
+                None => groups.push((lot, vec![(entity, translation)])),
+            }
+        }
+
+        // Visible lights with the number of lights merged into them.
+        let mut visible: Vec<(Entity, Vec3, u32)> = Vec::new();
+        for (_, mut group) in groups {
+            group.sort_by(|(_, a), (_, b)| {
+                let a = a.distance_squared(camera_translation);
+                let b = b.distance_squared(camera_translation);
+                a.total_cmp(&b)
+            });
+
+            let group_start = visible.len();
+            for (entity, translation) in group {
+                if visible.len() - group_start < LOT_LIGHTS_BUDGET {
+                    visible.push((entity, translation, 1));
+                } else if let Some((.., merged)) =
+                    visible[group_start..]
+                        .iter_mut()
+                        .find(|(_, visible_translation, _)| {
+                            visible_translation.distance(translation) <= CLUSTER_RADIUS
+                        })
+                {
+                    *merged += 1;
+                } else {
+                    trace!("culling light `{entity}`");
+                }
+            }
+        }
+
+        for (entity, parent, _, mut visibility, point_light, spot_light) in &mut lights {
+            let merged = visible
+                .iter()
+                .find(|&&(visible_entity, ..)| visible_entity == entity)
+                .map(|&(.., merged)| merged);
+
+            let Some(merged) = merged else {
+                visibility.set_if_neq(Visibility::Hidden);
+                continue;
+            };
+            visibility.set_if_neq(Visibility::Inherited);
+
+            let Ok((_, _, source, ..)) = lamps.get(**parent) else {
+                continue;
+            };
+            let intensity = source.intensity * merged as f32;
+            if let Some(mut point_light) = point_light {
+                if point_light.intensity != intensity {
+                    point_light.intensity = intensity;
+                }
+            }
+            if let Some(mut spot_light) = spot_light {
+                if spot_light.intensity != intensity {
+                    spot_light.intensity = intensity;
+                }
+            }
+        }
+    }
+}
+
+/// Light emitted by an object.
+///
+/// Usually defined in object metadata.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub(crate) struct LightSource {
+    kind: LightKind,
+
+    /// Light position relative to the object.
+    offset: Vec3,
+    color: Color,
+
+    /// Luminous power in lumens.
+    intensity: f32,
+    range: f32,
+}
+
+impl Default for LightSource {
+    fn default() -> Self {
+        Self {
+            kind: Default::default(),
+            offset: Vec3::Y,
+            color: Color::WHITE,
+            intensity: 800.0,
+            range: 10.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Reflect)]
+enum LightKind {
+    #[default]
+    Point,
+    /// Directed downwards.
+    Spot,
+}
+
+/// Current state of an object with [`LightSource`].
+#[derive(Clone, Component, Copy, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub(crate) struct LampState {
+    pub(crate) enabled: bool,
+
+    /// Follow the time of day.
+    pub(crate) automatic: bool,
+}
+
+impl Default for LampState {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            automatic: true,
+        }
+    }
+}
+
+/// Marks a light spawned for [`LightSource`].
+#[derive(Component)]
+struct LampLight;
@@ -0,0 +1,185 @@
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities, system::SystemParam},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    claim::{Claimable, ClaimedBy},
+    Object,
+};
+use crate::game_world::burglary::Stolen;
+
+pub(super) struct OccupancyPlugin;
+
+impl Plugin for OccupancyPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<UseSlots>()
+            .register_type::<UseSlot>()
+            .register_type::<SlotKind>()
+            .register_type::<SlotReservation>()
+            .replicate_mapped::<SlotReservation>();
+    }
+}
+
+/// Places on an object where actors sit or sleep, one actor per place.
+#[derive(Component, Reflect, Clone, Default, Deref)]
+#[reflect(Component)]
+pub(crate) struct UseSlots(Vec<UseSlot>);
+
+#[derive(Reflect, Clone, Default)]
+pub(crate) struct UseSlot {
+    /// Slot name for debugging.
+    pub(crate) name: String,
+
+    pub(crate) kind: SlotKind,
+
+    /// Position of the actor relative to the object origin.
+    pub(crate) translation: Vec3,
+
+    /// Rotation of the actor around Y axis relative to the object in radians.
+    pub(crate) yaw: f32,
+}
+
+impl UseSlot {
+    /// Returns the actor transform relative to the actor parent.
+    ///
+    /// Objects could be placed on surfaces, so global transforms are used.
+    pub(crate) fn transform(
+        &self,
+        object_transform: &GlobalTransform,
+        parent_transform: &GlobalTransform,
+    ) -> Transform {
+        let slot_transform = Transform::from_translation(self.translation)
+            .with_rotation(Quat::from_rotation_y(self.yaw));
+        object_transform
+            .mul_transform(slot_transform)
+            .reparented_to(parent_transform)
+    }
+}
+
+#[derive(Reflect, Clone, Copy, Default, PartialEq, Debug)]
+pub(crate) enum SlotKind {
+    #[default]
+    Seat,
+    Bed,
+    /// Place to stand, like in front of a TV.
+    Spot,
+    /// Place at a table for meals.
+    Dining,
+}
+
+/// Slot of an object reserved by a task.
+///
+/// Stored on the task entity, so the slot is released when the task is despawned.
+#[derive(Clone, Component, Copy, Debug, Deserialize, PartialEq, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+pub(crate) struct SlotReservation {
+    pub(crate) object_entity: Entity,
+    pub(crate) index: usize,
+}
+
+impl FromWorld for SlotReservation {
+    fn from_world(_world: &mut World) -> Self {
+        Self {
+            object_entity: Entity::PLACEHOLDER,
+            index: 0,
+        }
+    }
+}
+
+impl MapEntities for SlotReservation {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.object_entity = entity_mapper.map_entity(self.object_entity);
+    }
+}
+
+/// Maximum distance to similar objects that could be used instead of the requested one.
+const SIMILAR_DISTANCE: f32 = 15.0;
+
+#[derive(SystemParam)]
+pub(crate) struct SlotOccupancy<'w, 's> {
+    objects: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static UseSlots,
+            &'static Object,
+            &'static GlobalTransform,
+            Option<&'static ClaimedBy>,
+            Has<Claimable>,
+        ),
+        Without<Stolen>,
+    >,
+    reservations: Query<'w, 's, &'static SlotReservation>,
+}
+
+impl SlotOccupancy<'_, '_> {
+    /// Returns indices of slots with the given kind that aren't reserved by any task.
+    pub(crate) fn free_slots(
+        &self,
+        object_entity: Entity,
+        kind: SlotKind,
+    ) -> impl Iterator<Item = usize> + '_ {
+        self.objects
+            .get(object_entity)
+            .into_iter()
+            .flat_map(|(_, slots, ..)| slots.iter().enumerate())
+            .filter(move |(_, slot)| slot.kind == kind)
+            .map(|(index, _)| index)
+            .filter(move |&index| {
+                !self.reservations.iter().any(|reservation| {
+                    reservation.object_entity == object_entity && reservation.index == index
+                })
+            })
+    }
+    /// Picks an object with a free slot for the actor.
+    ///
+    /// Seats, beds and claimable objects are personal, so nearby objects of the same kind
+    /// are considered too, scored by [`ClaimPreference`](super::claim::ClaimPreference) and then by distance.
+    /// Other slots are shared, like in front of a TV, so only the requested object is used.
+    /// Slots from `reserved` are treated as taken.
+    pub(crate) fn pick(
+        &self,
+        actor_entity: Entity,
+        object_entity: Entity,
+        kind: SlotKind,
+        reserved: &[(Entity, usize)],
+    ) -> Option<SlotReservation> {
+        let (_, _, requested_object, requested_transform, _, claimable) =
+            self.objects.get(object_entity).ok()?;
+        let personal = claimable || matches!(kind, SlotKind::Seat | SlotKind::Bed);
+        let requested_translation = requested_transform.translation();
+
+        self.objects
+            .iter()
+            .filter(|&(entity, _, object, transform, ..)| {
+                entity == object_entity
+                    || (personal
+                        && object.0 == requested_object.0
+                        && transform.translation().distance(requested_translation)
+                            <= SIMILAR_DISTANCE)
+            })
+            .filter_map(|(entity, _, _, transform, claimed_by, _)| {
+                let index = self
+                    .free_slots(entity, kind)
+                    .find(|&index| !reserved.contains(&(entity, index)))?;
+                let preference = ClaimedBy::preference(claimed_by, actor_entity);
+                let distance = transform.translation().distance(requested_translation);
+                Some((entity, index, preference, distance))
+            })
+            .max_by(
+                |(.., preference_a, distance_a), (.., preference_b, distance_b)| {
+                    preference_a
+                        .cmp(preference_b)
+                        .then(distance_b.total_cmp(distance_a))
+                },
+            )
+            .map(|(object_entity, index, ..)| SlotReservation {
+                object_entity,
+                index,
+            })
+    }
+}
@@ -0,0 +1,74 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use super::placing_object::PlacingObject;
+use crate::{
+    core::GameState,
+    game_world::{
+        family::building::wall::{wall_mesh::HEIGHT, WallMaterial},
+        navigation::Obstacle,
+        spline::SplinePoint,
+        Layer,
+    },
+};
+
+/// Generates pillars and connects them to walls.
+pub(super) struct PillarPlugin;
+
+impl Plugin for PillarPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Pillar>()
+            .add_systems(Update, Self::init.run_if(in_state(GameState::InGame)));
+    }
+}
+
+impl PillarPlugin {
+    fn init(
+        mut commands: Commands,
+        mut meshes: ResMut<Assets<Mesh>>,
+        wall_material: Res<WallMaterial>,
+        mut objects: Query<
+            (Entity, &Pillar, &mut CollisionLayers, Has<PlacingObject>),
+            Without<SplinePoint>,
+        >,
+    ) {
+        for (entity, pillar, mut collision_layers, placing_object) in &mut objects {
+            debug!("initializing pillar for `{entity}`");
+
+            // Walls are allowed to end inside pillars.
+            collision_layers
+                .filters
+                .remove([Layer::Wall, Layer::PlacingWall]);
+
+            let mesh = Mesh::from(Cylinder::new(pillar.radius, HEIGHT))
+                .translated_by(Vec3::Y * HEIGHT / 2.0);
+            let collider = Collider::compound(vec![(
+                Vec3::Y * HEIGHT / 2.0,
+                Quat::IDENTITY,
+                Collider::cylinder(pillar.radius, HEIGHT),
+            )]);
+
+            let mut entity = commands.entity(entity);
+            entity.insert((
+                SplinePoint {
+                    radius: pillar.radius,
+                },
+                collider,
+                meshes.add(mesh),
+                wall_material.0.clone(),
+            ));
+            if !placing_object {
+                entity.insert(Obstacle);
+            }
+        }
+    }
+}
+
+/// Column that walls can be connected to.
+///
+/// Generates a cylinder mesh with the wall height.
+#[derive(Component, Reflect, Clone, Copy, Default)]
+#[reflect(Component)]
+pub(crate) struct Pillar {
+    radius: f32,
+}
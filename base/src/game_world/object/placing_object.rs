@@ -1,4 +1,9 @@
+pub(crate) mod ceiling_snap;
+mod collision_slide;
+pub(crate) mod ground_fit;
+pub mod placement_rules;
 pub(crate) mod side_snap;
+pub(crate) mod surface_snap;
 pub(crate) mod wall_snap;
 
 use std::{
@@ -8,80 +13,115 @@ use std::{
 
 use avian3d::prelude::*;
 use bevy::{
-    color::palettes::css::{RED, WHITE},
-    ecs::reflect::ReflectCommandExt,
+    ecs::{query::QueryFilter, reflect::ReflectCommandExt},
+    math::Vec3Swizzles,
     prelude::*,
-    scene,
 };
-use leafwing_input_manager::common_conditions::action_just_pressed;
+use leafwing_input_manager::{
+    common_conditions::{action_just_pressed, action_pressed},
+    prelude::ActionState,
+};
 
 use crate::{
     asset::info::object_info::ObjectInfo,
     game_world::{
-        city::CityMode,
+        city::{lot::permissions::BuildPermissions, CityMode},
         commands_history::{CommandsHistory, PendingDespawn},
         family::building::BuildingMode,
         hover::{HoverPlugin, Hovered},
-        object::{Object, ObjectCommand},
+        object::{Object, ObjectCommand, ObjectTags, Placeholder},
         player_camera::{CameraCaster, PlayerCamera},
         Layer,
     },
-    ghost::Ghost,
+    ghost::{Ghost, GhostMaterial},
     settings::Action,
 };
+use ceiling_snap::CeilingSnapPlugin;
+use collision_slide::CollisionSlidePlugin;
+use ground_fit::GroundFitPlugin;
+use placement_rules::{PlacementError, PlacementErrors, PlacementRules, PlacementRulesPlugin};
 use side_snap::SideSnapPlugin;
+use surface_snap::{SurfaceSlots, SurfaceSnapPlugin};
 use wall_snap::WallSnapPlugin;
 
 pub(super) struct PlacingObjectPlugin;
 
 impl Plugin for PlacingObjectPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(WallSnapPlugin)
-            .add_plugins(SideSnapPlugin)
-            .observe(HoverPlugin::enable_on_remove::<PlacingObject>)
-            .observe(HoverPlugin::disable_on_add::<PlacingObject>)
-            .observe(Self::ensure_single)
-            .add_systems(
-                PreUpdate,
-                Self::init
-                    .run_if(in_state(CityMode::Objects).or_else(in_state(BuildingMode::Objects))),
-            )
-            .add_systems(
-                Update,
+        app.add_plugins((
+            CeilingSnapPlugin,
+            CollisionSlidePlugin,
+            GroundFitPlugin,
+            PlacementRulesPlugin,
+            WallSnapPlugin,
+            SideSnapPlugin,
+            SurfaceSnapPlugin,
+        ))
+        .observe(HoverPlugin::enable_on_remove::<PlacingObject>)
+        .observe(HoverPlugin::disable_on_add::<PlacingObject>)
+        .observe(Self::ensure_single)
+        .add_systems(
+            PreUpdate,
+            Self::init.run_if(in_state(CityMode::Objects).or_else(in_state(BuildingMode::Objects))),
+        )
+        .add_systems(
+            Update,
+            (
+                (
+                    Self::pick
+                        .run_if(action_just_pressed(Action::Confirm))
+                        .run_if(not(any_with_component::<PlacingObject>)),
+                    Self::sell.run_if(action_just_pressed(Action::Delete)),
+                    Self::cancel.run_if(action_just_pressed(Action::Cancel)),
+                ),
                 (
-                    (
-                        Self::pick
-                            .run_if(action_just_pressed(Action::Confirm))
-                            .run_if(not(any_with_component::<PlacingObject>)),
-                        Self::sell.run_if(action_just_pressed(Action::Delete)),
-                        Self::cancel.run_if(action_just_pressed(Action::Cancel)),
-                    ),
-                    (
-                        Self::rotate.run_if(action_just_pressed(Action::RotateObject)),
-                        Self::apply_position,
-                        Self::confirm.run_if(action_just_pressed(Action::Confirm)),
-                    )
-                        .chain(),
+                    Self::rotate.run_if(action_just_pressed(Action::RotateObject)),
+                    Self::offset_vertically.run_if(action_pressed(Action::VerticalOffset)),
+                    Self::apply_position,
+                    Self::check_permissions,
+                    Self::update_validity,
+                    Self::confirm.run_if(action_just_pressed(Action::Confirm)),
                 )
-                    .run_if(in_state(CityMode::Objects).or_else(in_state(BuildingMode::Objects))),
+                    .chain(),
             )
-            .add_systems(
-                SpawnScene,
-                Self::update_materials
-                    .after(scene::scene_spawner_system)
-                    .run_if(in_state(CityMode::Objects).or_else(in_state(BuildingMode::Objects))),
-            );
+                .run_if(in_state(CityMode::Objects).or_else(in_state(BuildingMode::Objects))),
+        );
     }
 }
 
+/// Rotation step with [`Action::FineAdjustment`] modifier.
+const FINE_ROTATION_STEP: f32 = PI / 12.0;
+
+/// Vertical offset per [`Action::ZoomCamera`] unit.
+const VERTICAL_OFFSET_STEP: f32 = 0.05;
+
+/// Upper bound for [`PlacingObjectState::vertical_offset`].
+///
+/// Further limited by wall and ceiling snapping, the only ones that apply the offset.
+const MAX_VERTICAL_OFFSET: f32 = 2.5;
+
 impl PlacingObjectPlugin {
     fn pick(
         mut commands: Commands,
-        objects: Query<(Entity, &Parent), (With<Object>, With<Hovered>)>,
+        permissions: BuildPermissions,
+        objects: Query<
+            (Entity, &Parent, &Transform),
+            (With<Object>, With<Hovered>, Without<Placeholder>),
+        >,
+        surfaces: Query<&Parent, With<SurfaceSlots>>,
+        surface_transforms: Query<&Transform, With<SurfaceSlots>>,
     ) {
-        if let Ok((object_entity, parent)) = objects.get_single() {
+        if let Ok((object_entity, parent, &transform)) = objects.get_single() {
+            // Objects on surfaces are children of other objects.
+            let city_entity = surfaces.get(**parent).map_or(**parent, |parent| **parent);
+            let translation = city_transform(transform, parent, &surface_transforms).translation;
+            if !permissions.locally_allowed(city_entity, translation.xz()) {
+                debug!("ignoring picking of `{object_entity}` without build rights");
+                return;
+            }
+
             info!("picking object `{object_entity}`");
-            commands.entity(**parent).with_children(|parent| {
+            commands.entity(city_entity).with_children(|parent| {
                 parent.spawn(PlacingObject::Moving(object_entity));
             });
         }
@@ -95,7 +135,8 @@ impl PlacingObjectPlugin {
         asset_server: Res<AssetServer>,
         cameras: Query<&Transform, With<PlayerCamera>>,
         placing_objects: Query<(Entity, &PlacingObject), Without<PlacingObjectState>>,
-        objects: Query<(&Object, &Transform)>,
+        objects: Query<(&Object, &Transform, &Parent)>,
+        surfaces: Query<&Transform, With<SurfaceSlots>>,
     ) {
         let Some((placing_entity, &placing_object)) = placing_objects.iter().last() else {
             return;
@@ -116,9 +157,10 @@ impl PlacingObjectPlugin {
                 (info, Vec3::ZERO, rotation)
             }
             PlacingObject::Moving(object_entity) => {
-                let (object, &transform) = objects
+                let (object, &transform, parent) = objects
                     .get(object_entity)
                     .expect("moving object should referece a valid object");
+                let transform = city_transform(transform, parent, &surfaces);
 
                 let info_handle = asset_server
                     .get_handle(&object.0)
@@ -142,6 +184,8 @@ impl PlacingObjectPlugin {
             StateScoped(CityMode::Objects),
             scene_handle,
             PlacingObjectState::new(cursor_offset),
+            PlacementErrors::default(),
+            GhostMaterial::default(),
             ObjectRotationLimit::default(),
             SpatialBundle::from_transform(Transform::from_rotation(rotation)),
             RigidBody::Kinematic,
@@ -152,6 +196,7 @@ impl PlacingObjectPlugin {
                     Layer::PlacingObject,
                     Layer::Wall,
                     Layer::PlacingWall,
+                    Layer::Water,
                 ],
             ),
         ));
@@ -168,10 +213,17 @@ impl PlacingObjectPlugin {
         }
     }
 
-    fn rotate(mut placing_objects: Query<(&mut Transform, &ObjectRotationLimit)>) {
+    fn rotate(
+        action_state: Res<ActionState<Action>>,
+        mut placing_objects: Query<(&mut Transform, &ObjectRotationLimit)>,
+    ) {
         if let Ok((mut transform, rotation_limit)) = placing_objects.get_single_mut() {
-            transform.rotation *=
-                Quat::from_axis_angle(Vec3::Y, rotation_limit.unwrap_or(FRAC_PI_4));
+            let step = if action_state.pressed(&Action::FineAdjustment) {
+                FINE_ROTATION_STEP
+            } else {
+                FRAC_PI_4
+            };
+            transform.rotation *= Quat::from_axis_angle(Vec3::Y, rotation_limit.unwrap_or(step));
 
             debug!(
                 "rotating placing object to '{}'",
@@ -180,6 +232,29 @@ impl PlacingObjectPlugin {
         }
     }
 
+    fn offset_vertically(
+        action_state: Res<ActionState<Action>>,
+        mut placing_objects: Query<&mut PlacingObjectState>,
+    ) {
+        let Ok(mut state) = placing_objects.get_single_mut() else {
+            return;
+        };
+
+        let mut step = action_state.value(&Action::ZoomCamera) * VERTICAL_OFFSET_STEP;
+        if action_state.pressed(&Action::FineAdjustment) {
+            step /= 4.0;
+        }
+        if step == 0.0 {
+            return;
+        }
+
+        let vertical_offset = (state.vertical_offset + step).clamp(0.0, MAX_VERTICAL_OFFSET);
+        if state.vertical_offset != vertical_offset {
+            debug!("changing vertical offset to {vertical_offset}");
+            state.vertical_offset = vertical_offset;
+        }
+    }
+
     fn apply_position(
         camera_caster: CameraCaster,
         mut placing_objects: Query<(&mut Transform, &PlacingObjectState)>,
@@ -191,41 +266,70 @@ impl PlacingObjectPlugin {
         }
     }
 
-    fn update_materials(
-        mut materials: ResMut<Assets<StandardMaterial>>,
-        placing_objects: Query<
-            (Entity, &PlacingObjectState, &CollidingEntities),
+    fn check_permissions(
+        permissions: BuildPermissions,
+        mut placing_objects: Query<(&Parent, &Transform, &mut PlacingObjectState)>,
+    ) {
+        if let Ok((parent, transform, mut state)) = placing_objects.get_single_mut() {
+            let no_permission = !permissions.locally_allowed(**parent, transform.translation.xz());
+            if state.no_permission != no_permission {
+                debug!("changing build rights restriction to `{no_permission}`");
+                state.no_permission = no_permission;
+            }
+        }
+    }
+
+    fn update_validity(
+        mut placing_objects: Query<
+            (
+                &mut PlacementErrors,
+                &mut GhostMaterial,
+                &PlacingObjectState,
+                &CollidingEntities,
+                Option<&PlacementRules>,
+            ),
             Or<(Changed<CollidingEntities>, Changed<PlacingObjectState>)>,
         >,
-        children: Query<&Children>,
-        mut material_handles: Query<&mut Handle<StandardMaterial>>,
+        tags: Query<&ObjectTags>,
     ) {
-        if let Ok((placing_entity, state, colliding_entities)) = placing_objects.get_single() {
-            let color = if state.allowed_place && colliding_entities.is_empty() {
-                WHITE.into()
-            } else {
-                RED.into()
-            };
-            debug!("changing base color to `{color:?}`");
-
-            let mut iter =
-                material_handles.iter_many_mut(children.iter_descendants(placing_entity));
-            while let Some(mut material_handle) = iter.fetch_next() {
-                let material = materials
-                    .get(&*material_handle)
-                    .expect("material handle should be valid");
-
-                // If color matches, assume that we don't need any update.
-                if material.base_color == color {
-                    return;
-                }
+        let Ok((mut errors, mut ghost_material, state, colliding_entities, rules)) =
+            placing_objects.get_single_mut()
+        else {
+            return;
+        };
 
-                let mut material = material.clone();
-                material.base_color = color;
-                material.alpha_mode = AlphaMode::Add;
-                *material_handle = materials.add(material);
+        let mut new_errors = PlacementErrors::default();
+        if colliding_entities
+            .iter()
+            .any(|&entity| Some(entity) != state.surface)
+        {
+            new_errors.push(PlacementError::Collision);
+        }
+        if state.overlaps_aperture {
+            new_errors.push(PlacementError::Aperture);
+        }
+        if state.uneven_ground {
+            new_errors.push(PlacementError::UnevenGround);
+        }
+        if state.outside_ceiling {
+            new_errors.push(PlacementError::NoCeiling);
+        }
+        if state.no_permission {
+            new_errors.push(PlacementError::NoPermission);
+        }
+        let surface_tags = state.surface.and_then(|entity| tags.get(entity).ok());
+        for rule in rules.iter().flat_map(|rules| rules.iter()) {
+            if let Err(error) = rule.check(state, surface_tags) {
+                new_errors.push(error);
             }
         }
+
+        if errors.set_if_neq(new_errors) {
+            debug!("updating placement errors to `{:?}`", **errors);
+        }
+        ghost_material.set_if_neq(GhostMaterial {
+            allowed: errors.is_empty(),
+        });
     }
 
     fn confirm(
@@ -238,16 +342,30 @@ impl PlacingObjectPlugin {
             &Transform,
             &PlacingObject,
             &PlacingObjectState,
-            &CollidingEntities,
+            &PlacementErrors,
         )>,
+        surfaces: Query<&Transform, (With<SurfaceSlots>, Without<PlacingObject>)>,
     ) {
-        if let Ok((entity, parent, translation, &placing_object, state, colliding_entities)) =
+        if let Ok((entity, parent, &transform, &placing_object, state, errors)) =
             placing_objects.get_single()
         {
-            if !state.allowed_place || !colliding_entities.is_empty() {
+            if !errors.is_empty() {
                 return;
             }
 
+            // Attach to the surface object to move together with it.
+            let (parent_entity, transform) = match state
+                .surface
+                .and_then(|entity| surfaces.get(entity).ok().map(|surface| (entity, surface)))
+            {
+                Some((surface_entity, surface_transform)) => {
+                    let matrix =
+                        surface_transform.compute_matrix().inverse() * transform.compute_matrix();
+                    (surface_entity, Transform::from_matrix(matrix))
+                }
+                None => (**parent, transform),
+            };
+
             let command_id = match placing_object {
                 PlacingObject::Spawning(id) => {
                     let info_path = asset_server
@@ -255,22 +373,23 @@ impl PlacingObjectPlugin {
                         .expect("info should always come from file");
                     history.push_pending(ObjectCommand::Buy {
                         info_path: info_path.into_owned(),
-                        city_entity: **parent,
-                        translation: translation.translation,
-                        rotation: translation.rotation,
+                        parent_entity,
+                        translation: transform.translation,
+                        rotation: transform.rotation,
                     })
                 }
                 PlacingObject::Moving(entity) => history.push_pending(ObjectCommand::Move {
                     entity,
-                    translation: translation.translation,
-                    rotation: translation.rotation,
+                    parent_entity,
+                    translation: transform.translation,
+                    rotation: transform.rotation,
                 }),
             };
 
             commands
                 .entity(entity)
                 .insert(PendingDespawn { command_id })
-                .remove::<(PlacingObject, PlacingObjectState)>();
+                .remove::<(PlacingObject, PlacingObjectState, PlacementErrors)>();
 
             info!("confirming `{placing_object:?}`");
         }
@@ -280,7 +399,8 @@ impl PlacingObjectPlugin {
         mut commands: Commands,
         mut history: CommandsHistory,
         mut placing_objects: Query<(Entity, &PlacingObject, &mut Transform)>,
-        objects: Query<&Transform, Without<PlacingObject>>,
+        objects: Query<(&Transform, &Parent), Without<PlacingObject>>,
+        surfaces: Query<&Transform, (With<SurfaceSlots>, Without<PlacingObject>)>,
     ) {
         if let Ok((placing_entity, &placing_object, mut transform)) =
             placing_objects.get_single_mut()
@@ -288,13 +408,15 @@ impl PlacingObjectPlugin {
             info!("selling `{placing_object:?}`");
             if let PlacingObject::Moving(entity) = placing_object {
                 // Set original position until the deletion is confirmed.
-                *transform = *objects.get(entity).expect("moving object should exist");
+                let (&object_transform, parent) =
+                    objects.get(entity).expect("moving object should exist");
+                *transform = city_transform(object_transform, parent, &surfaces);
 
                 let command_id = history.push_pending(ObjectCommand::Sell { entity });
                 commands
                     .entity(placing_entity)
                     .insert(PendingDespawn { command_id })
-                    .remove::<(PlacingObject, PlacingObjectState)>();
+                    .remove::<(PlacingObject, PlacingObjectState, PlacementErrors)>();
             } else {
                 commands.entity(placing_entity).despawn_recursive();
             }
@@ -323,6 +445,20 @@ impl PlacingObjectPlugin {
     }
 }
 
+/// Converts transform of an object into city space.
+///
+/// Objects placed on [`SurfaceSlots`] are children of the surface object.
+fn city_transform<F: QueryFilter>(
+    transform: Transform,
+    parent: &Parent,
+    surfaces: &Query<&Transform, F>,
+) -> Transform {
+    match surfaces.get(**parent) {
+        Ok(surface_transform) => surface_transform.mul_transform(transform),
+        Err(_) => transform,
+    }
+}
+
 /// Marks an entity as an object that should be moved with cursor to preview spawn position.
 #[derive(Debug, Clone, Copy, Component)]
 pub enum PlacingObject {
@@ -333,25 +469,73 @@ pub enum PlacingObject {
 #[derive(Component, Default, Deref, DerefMut)]
 pub struct ObjectRotationLimit(Option<f32>);
 
-/// Controls if an object can be placed.
+/// Placing context used to check [`PlacementRules`].
 ///
-/// Stored as a separate component to avoid triggering change detection to update the object material.
+/// Stored as a separate component to avoid triggering change detection on every transform change.
 #[derive(Component)]
 struct PlacingObjectState {
     /// An offset between cursor position on first creation and object origin.
     cursor_offset: Vec3,
 
-    /// Additional object condition for placing.
+    /// Whether the object is currently attached to a wall.
+    ///
+    /// Controlled by [`WallSnapPlugin`].
+    snapped_to_wall: bool,
+
+    /// Whether the object attached to a wall overlaps a door or window.
+    ///
+    /// Controlled by [`WallSnapPlugin`].
+    overlaps_aperture: bool,
+
+    /// Whether the ground under the object is too uneven.
+    ///
+    /// Controlled by [`GroundFitPlugin`].
+    uneven_ground: bool,
+
+    /// Whether the ceiling-mounted object is not fully covered by a ceiling.
+    ///
+    /// Controlled by [`CeilingSnapPlugin`].
+    outside_ceiling: bool,
+
+    /// Whether the object is inside a room.
+    ///
+    /// Controlled by [`PlacementRulesPlugin`].
+    indoors: bool,
+
+    /// Whether the object is in or close to water.
+    ///
+    /// Controlled by [`PlacementRulesPlugin`].
+    near_water: bool,
+
+    /// Whether the object is located on a lot without build rights.
+    ///
+    /// Controlled by [`PlacingObjectPlugin::check_permissions`].
+    no_permission: bool,
+
+    /// Height above the ground adjusted with [`Action::VerticalOffset`].
+    ///
+    /// Applied only to objects attached to walls (as height) or ceilings (as drop).
+    vertical_offset: f32,
+
+    /// Object on which surface the placing object is located.
     ///
-    /// For example, a door can be placed only on a wall. Controlled by other plugins.
-    allowed_place: bool,
+    /// Controlled by [`SurfaceSnapPlugin`].
+    surface: Option<Entity>,
 }
 
 impl PlacingObjectState {
     fn new(cursor_offset: Vec3) -> Self {
         Self {
             cursor_offset,
-            allowed_place: true,
+            snapped_to_wall: false,
+            overlaps_aperture: false,
+            uneven_ground: false,
+            outside_ceiling: false,
+            indoors: false,
+            near_water: false,
+            no_permission: false,
+            vertical_offset: 0.0,
+            surface: None,
         }
     }
 }
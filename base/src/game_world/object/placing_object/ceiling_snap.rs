@@ -0,0 +1,86 @@
+use avian3d::prelude::*;
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ground_fit::{self, GroundFitPlugin},
+    PlacingObject, PlacingObjectPlugin, PlacingObjectState,
+};
+use crate::game_world::{
+    city::CityMode,
+    family::building::{ceiling::Ceiling, wall::wall_mesh::HEIGHT, BuildingMode},
+};
+
+pub(super) struct CeilingSnapPlugin;
+
+impl Plugin for CeilingSnapPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<CeilingSnap>()
+            .replicate::<CeilingSnap>()
+            .add_systems(
+                Update,
+                Self::snap
+                    .after(GroundFitPlugin::fit_placing)
+                    .before(PlacingObjectPlugin::update_validity)
+                    .run_if(in_state(CityMode::Objects).or_else(in_state(BuildingMode::Objects))),
+            );
+    }
+}
+
+impl CeilingSnapPlugin {
+    /// Hangs the object from the ceiling plane and checks if it's fully covered by a ceiling.
+    ///
+    /// Uses [`PlacingObjectState::vertical_offset`] as the distance below the ceiling.
+    fn snap(
+        ceilings: Query<(&Parent, &Ceiling)>,
+        mut placing_objects: Query<
+            (
+                &mut Transform,
+                &mut PlacingObjectState,
+                &Parent,
+                &Collider,
+                &CeilingSnap,
+            ),
+            With<PlacingObject>,
+        >,
+    ) {
+        let Ok((mut transform, mut state, parent, collider, snap)) =
+            placing_objects.get_single_mut()
+        else {
+            return;
+        };
+
+        let vertical_offset = state.vertical_offset.clamp(0.0, snap.max_drop);
+        if state.vertical_offset != vertical_offset {
+            debug!("clamping ceiling drop to {vertical_offset}");
+            state.vertical_offset = vertical_offset;
+        }
+
+        let aabb = collider.aabb(Vec3::ZERO, Quat::IDENTITY);
+        transform.translation.y = HEIGHT - aabb.max.y - state.vertical_offset;
+
+        // Objects that stick out of the room would cross wall tops.
+        let covered = ceilings
+            .iter()
+            .filter(|(ceiling_parent, _)| *ceiling_parent == parent)
+            .any(|(_, ceiling)| {
+                ground_fit::footprint(collider)
+                    .all(|point| ceiling.contains_point(transform.transform_point(point).xz()))
+            });
+        if state.outside_ceiling == covered {
+            debug!("changing ceiling coverage to `{covered}`");
+            state.outside_ceiling = !covered;
+        }
+    }
+}
+
+/// Attaches an object to the ceiling, like a lamp or a fan.
+///
+/// Replicated with objects to keep the mount type in sync with the server.
+#[derive(Component, Reflect, Clone, Copy, Deserialize, Serialize)]
+#[reflect(Component)]
+pub(crate) struct CeilingSnap {
+    /// Maximum distance the object can be lowered from the ceiling.
+    max_drop: f32,
+}
@@ -0,0 +1,130 @@
+use avian3d::{collision::contact_query, prelude::*};
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use super::{
+    side_snap::SideSnapPlugin, surface_snap::SurfaceSnapPlugin, wall_snap::WallSnapPlugin,
+    PlacingObject, PlacingObjectPlugin, PlacingObjectState,
+};
+use crate::{
+    game_world::{city::CityMode, family::building::BuildingMode, Layer},
+    settings::Action,
+};
+
+/// Pushes the placing object out of blocking geometry.
+///
+/// Instead of reporting a collision right away, the object slides along
+/// the obstacle to the nearest free position if it's close enough.
+pub(super) struct CollisionSlidePlugin;
+
+impl Plugin for CollisionSlidePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            Self::slide
+                .after(WallSnapPlugin::snap)
+                .after(SideSnapPlugin::snap)
+                .after(SurfaceSnapPlugin::snap)
+                .before(PlacingObjectPlugin::update_validity)
+                .run_if(in_state(CityMode::Objects).or_else(in_state(BuildingMode::Objects))),
+        );
+    }
+}
+
+/// Maximum distance the object can be pushed away from the cursor.
+const MAX_SLIDE: f32 = 0.5;
+
+/// Number of push steps to resolve contacts with multiple obstacles, like corners.
+const MAX_ITERATIONS: usize = 4;
+
+/// Penetration depth that is not considered as a collision.
+///
+/// Allows objects to touch each other, like when snapped by sides.
+const PENETRATION_TOLERANCE: f32 = 0.001;
+
+impl CollisionSlidePlugin {
+    fn slide(
+        spatial_query: SpatialQuery,
+        action_state: Res<ActionState<Action>>,
+        mut placing_objects: Query<(
+            Entity,
+            &mut Transform,
+            &Parent,
+            &Collider,
+            &PlacingObject,
+            &PlacingObjectState,
+        )>,
+        obstacles: Query<(&Collider, &Position, &Rotation)>,
+        cities: Query<&GlobalTransform>,
+    ) {
+        let Ok((entity, mut transform, parent, collider, &placing_object, state)) =
+            placing_objects.get_single_mut()
+        else {
+            return;
+        };
+
+        // Snapped objects are positioned intentionally.
+        if state.snapped_to_wall
+            || state.surface.is_some()
+            || action_state.pressed(&Action::FineAdjustment)
+        {
+            return;
+        }
+
+        let mut excluded = vec![entity];
+        if let PlacingObject::Moving(object_entity) = placing_object {
+            excluded.push(object_entity);
+        }
+        let filter = SpatialQueryFilter::from_mask([Layer::Object, Layer::Wall])
+            .with_excluded_entities(excluded);
+
+        let city_transform = cities.get(**parent).unwrap();
+        let global_transform = city_transform.mul_transform(*transform);
+        let (_, rotation, origin) = global_transform.to_scale_rotation_translation();
+
+        let mut translation = origin;
+        for _ in 0..MAX_ITERATIONS {
+            let push = spatial_query
+                .shape_intersections(collider, translation, rotation, filter.clone())
+                .into_iter()
+                .filter_map(|obstacle_entity| obstacles.get(obstacle_entity).ok())
+                .filter_map(|(obstacle_collider, &position, &obstacle_rotation)| {
+                    contact_query::contact(
+                        collider,
+                        translation,
+                        rotation,
+                        obstacle_collider,
+                        position,
+                        obstacle_rotation,
+                        0.0,
+                    )
+                    .ok()
+                    .flatten()
+                })
+                .filter(|contact| contact.penetration > PENETRATION_TOLERANCE)
+                .map(|contact| {
+                    // Slide only horizontally, the height is controlled by other plugins.
+                    let normal = contact.global_normal1(&Rotation(rotation));
+                    -normal.xz().normalize_or_zero() * contact.penetration
+                })
+                .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()));
+
+            let Some(push) = push else {
+                let offset = translation - origin;
+                if offset != Vec3::ZERO {
+                    trace!("sliding placing object by {offset}");
+                    transform.translation +=
+                        city_transform.affine().inverse().transform_vector3(offset);
+                }
+                return;
+            };
+
+            translation += Vec3::new(push.x, 0.0, push.y);
+            if translation.xz().distance(origin.xz()) > MAX_SLIDE {
+                break;
+            }
+        }
+
+        trace!("unable to find a free position nearby");
+    }
+}
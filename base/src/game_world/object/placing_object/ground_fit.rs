@@ -0,0 +1,307 @@
+use avian3d::prelude::*;
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_replicon::prelude::*;
+
+use super::{
+    ceiling_snap::CeilingSnap, surface_snap::SurfaceSlots, wall_snap::WallHeight, PlacingObject,
+    PlacingObjectPlugin, PlacingObjectState,
+};
+use crate::{
+    asset::info::object_info::ObjectInfo,
+    core::GameState,
+    game_world::{
+        city::{CityMode, GroundSampler},
+        family::building::BuildingMode,
+        object::Object,
+    },
+};
+
+pub(super) struct GroundFitPlugin;
+
+impl Plugin for GroundFitPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GroundFit>()
+            .add_systems(
+                Update,
+                Self::fit_placing
+                    .after(PlacingObjectPlugin::apply_position)
+                    .before(PlacingObjectPlugin::update_validity)
+                    .run_if(in_state(CityMode::Objects).or_else(in_state(BuildingMode::Objects))),
+            )
+            .add_systems(
+                Update,
+                Self::check_placed
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::update_foundations
+                    .after(TransformSystem::TransformPropagate)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Maximum ground height difference under an object that doesn't require fitting.
+const MAX_HEIGHT_DIFF: f32 = 0.05;
+
+impl GroundFitPlugin {
+    /// Lifts placing object to the highest ground point under it and checks for slope.
+    ///
+    /// Applies [`PlacingObjectState::vertical_offset`] above the ground only to objects
+    /// attached to walls, others always rest on the ground.
+    pub(super) fn fit_placing(
+        ground_sampler: GroundSampler,
+        mut placing_objects: Query<
+            (
+                &mut Transform,
+                &mut PlacingObjectState,
+                &Parent,
+                &Collider,
+                Option<&GroundFit>,
+                Has<WallHeight>,
+            ),
+            (With<PlacingObject>, Without<CeilingSnap>),
+        >,
+        cities: Query<&GlobalTransform>,
+    ) {
+        let Ok((mut transform, mut state, parent, collider, ground_fit, wall_height)) =
+            placing_objects.get_single_mut()
+        else {
+            return;
+        };
+
+        let Ok(city_transform) = cities.get(**parent) else {
+            return;
+        };
+        let global_transform = city_transform.mul_transform(*transform);
+        let Some((min, max)) = ground_sampler.height_range(
+            footprint(collider).map(|point| global_transform.transform_point(point).xz()),
+        ) else {
+            return;
+        };
+
+        let uneven = match ground_fit.copied().unwrap_or_default() {
+            GroundFit::Reject => max - min > MAX_HEIGHT_DIFF,
+            GroundFit::Foundation => false,
+        };
+        if state.uneven_ground != uneven {
+            debug!("changing uneven ground to `{uneven}`");
+            state.uneven_ground = uneven;
+        }
+
+        let vertical_offset = if state.snapped_to_wall && wall_height {
+            state.vertical_offset
+        } else {
+            0.0
+        };
+        transform.translation.y = max - city_transform.translation().y + vertical_offset;
+    }
+
+    /// Validates height and ground under objects placed by clients.
+    ///
+    /// Rejected purchases are removed and rejected movements are reverted.
+    /// Only objects mounted on walls or ceilings can be raised above the ground.
+    /// Objects on surfaces are validated on request since the surface defines their height.
+    fn check_placed(
+        mut commands: Commands,
+        ground_sampler: GroundSampler,
+        asset_server: Res<AssetServer>,
+        objects_info: Res<Assets<ObjectInfo>>,
+        mut objects: Query<(
+            Entity,
+            &mut Transform,
+            &Parent,
+            &Object,
+            &Collider,
+            Option<&GroundFit>,
+            Has<CeilingSnap>,
+            &PendingGroundCheck,
+        )>,
+        parents: Query<(&GlobalTransform, Has<SurfaceSlots>)>,
+    ) {
+        for (entity, mut transform, parent, object, collider, ground_fit, ceiling_snap, check) in
+            &mut objects
+        {
+            commands.entity(entity).remove::<PendingGroundCheck>();
+
+            let Ok((parent_transform, surface)) = parents.get(**parent) else {
+                continue;
+            };
+            if surface {
+                continue;
+            }
+
+            let global_transform = parent_transform.mul_transform(*transform);
+            let Some((min, max)) = ground_sampler.height_range(
+                footprint(collider).map(|point| global_transform.transform_point(point).xz()),
+            ) else {
+                continue;
+            };
+
+            let height = global_transform.translation().y - max;
+            let reason = if height > MAX_HEIGHT_DIFF {
+                let wall_height = asset_server
+                    .get_handle(&object.0)
+                    .and_then(|handle| objects_info.get(&handle))
+                    .and_then(|info| {
+                        info.place_components
+                            .iter()
+                            .find_map(|component| WallHeight::from_reflect(&**component))
+                    });
+                let mounted = ceiling_snap
+                    || wall_height.is_some_and(|range| range.contains(height, MAX_HEIGHT_DIFF));
+                if mounted {
+                    continue;
+                }
+                "floating above the ground"
+            } else {
+                let uneven = match ground_fit.copied().unwrap_or_default() {
+                    GroundFit::Reject => max - min > MAX_HEIGHT_DIFF,
+                    GroundFit::Foundation => false,
+                };
+                if !uneven {
+                    continue;
+                }
+                "on uneven ground"
+            };
+
+            match check.previous {
+                Some(previous) => {
+                    error!("reverting movement of `{entity}` {reason}");
+                    *transform = previous;
+                }
+                None => {
+                    error!("removing `{entity}` bought {reason}");
+                    commands.entity(entity).despawn_recursive();
+                }
+            }
+        }
+    }
+
+    fn update_foundations(
+        mut commands: Commands,
+        mut meshes: ResMut<Assets<Mesh>>,
+        foundation_material: Local<FoundationMaterial>,
+        ground_sampler: GroundSampler,
+        objects: Query<
+            (
+                Entity,
+                &GroundFit,
+                &GlobalTransform,
+                &Collider,
+                Option<&Children>,
+            ),
+            Or<(Changed<GlobalTransform>, Changed<Collider>)>,
+        >,
+        foundations: Query<(Entity, &Foundation)>,
+    ) {
+        for (entity, &ground_fit, transform, collider, children) in &objects {
+            if ground_fit != GroundFit::Foundation {
+                continue;
+            }
+
+            let Some((min, max)) = ground_sampler.height_range(
+                footprint(collider).map(|point| transform.transform_point(point).xz()),
+            ) else {
+                continue;
+            };
+
+            let depth = if max - min > MAX_HEIGHT_DIFF {
+                // Bury the foundation a little to hide gaps.
+                const BURY_DEPTH: f32 = 0.1;
+                transform.translation().y - min + BURY_DEPTH
+            } else {
+                0.0
+            };
+
+            let current = foundations.iter_many(children.into_iter().flatten()).next();
+            if let Some((foundation_entity, foundation)) = current {
+                if foundation.depth == depth {
+                    continue;
+                }
+                commands.entity(foundation_entity).despawn_recursive();
+            }
+
+            if depth == 0.0 {
+                continue;
+            }
+
+            debug!("generating foundation with depth {depth} for `{entity}`");
+            let aabb = collider.aabb(Vec3::ZERO, Quat::IDENTITY);
+            let size = aabb.max - aabb.min;
+            let center = (aabb.min + aabb.max) / 2.0;
+            commands.entity(entity).with_children(|parent| {
+                parent.spawn((
+                    Name::new("Foundation"),
+                    Foundation { depth },
+                    PbrBundle {
+                        mesh: meshes.add(Cuboid::new(size.x, depth, size.z)),
+                        material: foundation_material.0.clone(),
+                        transform: Transform::from_xyz(center.x, -depth / 2.0, center.z),
+                        ..Default::default()
+                    },
+                ));
+            });
+        }
+    }
+}
+
+/// Returns bottom corners and center of the collider in local coordinates.
+pub(super) fn footprint(collider: &Collider) -> impl Iterator<Item = Vec3> {
+    let aabb = collider.aabb(Vec3::ZERO, Quat::IDENTITY);
+    let center = (aabb.min + aabb.max) / 2.0;
+    [
+        Vec3::new(aabb.min.x, 0.0, aabb.min.z),
+        Vec3::new(aabb.max.x, 0.0, aabb.min.z),
+        Vec3::new(aabb.max.x, 0.0, aabb.max.z),
+        Vec3::new(aabb.min.x, 0.0, aabb.max.z),
+        Vec3::new(center.x, 0.0, center.z),
+    ]
+    .into_iter()
+}
+
+/// Defines how an object handles uneven ground.
+///
+/// Objects without this component behave as [`GroundFit::Reject`].
+#[derive(Clone, Component, Copy, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub(crate) enum GroundFit {
+    /// Disallow placing on uneven ground.
+    #[default]
+    Reject,
+
+    /// Generate a foundation skirt under the object to fill the gap.
+    Foundation,
+}
+
+/// Requests ground validation for an object placed by a client.
+///
+/// Inserted on server, the check waits for the collider that is constructed from the scene.
+#[derive(Component, Default)]
+pub(crate) struct PendingGroundCheck {
+    /// Transform before the movement or [`None`] if the object was bought.
+    pub(crate) previous: Option<Transform>,
+}
+
+/// Foundation skirt under an object with [`GroundFit::Foundation`].
+#[derive(Component)]
+struct Foundation {
+    depth: f32,
+}
+
+struct FoundationMaterial(Handle<StandardMaterial>);
+
+impl FromWorld for FoundationMaterial {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let material_handle = materials.add(StandardMaterial {
+            base_color: Color::srgb(0.55, 0.53, 0.5),
+            perceptual_roughness: 0.9,
+            ..Default::default()
+        });
+
+        Self(material_handle)
+    }
+}
@@ -0,0 +1,210 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use strum::Display;
+
+use super::{
+    city_transform, surface_snap::SurfaceSlots, PlacingObject, PlacingObjectPlugin,
+    PlacingObjectState,
+};
+use crate::{
+    asset::info::object_info::ObjectTag,
+    game_world::{
+        city::{water::WaterVertices, ActiveCity, CityMode},
+        family::building::{room::Rooms, BuildingMode},
+        object::ObjectTags,
+    },
+};
+
+pub(super) struct PlacementRulesPlugin;
+
+impl Plugin for PlacementRulesPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PlacementRules>()
+            .register_type::<PlacementRule>()
+            .add_systems(
+                Update,
+                Self::check_surroundings
+                    .after(PlacingObjectPlugin::apply_position)
+                    .before(PlacingObjectPlugin::update_validity)
+                    .run_if(in_state(CityMode::Objects).or_else(in_state(BuildingMode::Objects))),
+            );
+    }
+}
+
+/// Maximum distance to water for [`PlacementRule::NearWater`].
+const NEAR_WATER_DISTANCE: f32 = 3.0;
+
+impl PlacementRulesPlugin {
+    /// Updates rooms and water around the placing object.
+    fn check_surroundings(
+        rooms: Res<Rooms>,
+        mut placing_objects: Query<
+            (&Parent, &Transform, &mut PlacingObjectState),
+            With<PlacingObject>,
+        >,
+        surfaces: Query<&Transform, (With<SurfaceSlots>, Without<PlacingObject>)>,
+        cities: Query<Entity, With<ActiveCity>>,
+        water: Query<(&Parent, &WaterVertices)>,
+    ) {
+        let Ok((parent, &transform, mut state)) = placing_objects.get_single_mut() else {
+            return;
+        };
+        let Ok(city_entity) = cities.get_single() else {
+            return;
+        };
+
+        let point = city_transform(transform, parent, &surfaces)
+            .translation
+            .xz();
+        let indoors = rooms.room_at(city_entity, point).is_some();
+        let near_water = water
+            .iter()
+            .filter(|(parent, _)| ***parent == city_entity)
+            .any(|(_, vertices)| {
+                vertices.contains_point(point)
+                    || vertices
+                        .closest_boundary_point(point)
+                        .is_some_and(|shore| shore.distance(point) <= NEAR_WATER_DISTANCE)
+            });
+
+        if state.indoors != indoors {
+            debug!("changing indoors to `{indoors}`");
+            state.indoors = indoors;
+        }
+        if state.near_water != near_water {
+            debug!("changing near water to `{near_water}`");
+            state.near_water = near_water;
+        }
+    }
+}
+
+/// Additional requirements for placing an object.
+///
+/// Listed in object metadata and checked on every placing object state change.
+#[derive(Component, Default, Deref, Reflect)]
+#[reflect(Component, Default)]
+pub(crate) struct PlacementRules(Vec<PlacementRule>);
+
+#[derive(Clone, Copy, Debug, Reflect)]
+pub(crate) enum PlacementRule {
+    /// Object should be snapped to a wall.
+    ///
+    /// Requires [`WallSnap`](super::wall_snap::WallSnap) to be present.
+    NeedsWall,
+    /// Object should be placed on a surface of an object with [`ObjectTag::Surface`].
+    ///
+    /// Requires [`SurfaceItem`](super::surface_snap::SurfaceItem) to be present.
+    NeedsSurface,
+    /// Object should be placed inside a room, which always has a floor.
+    NeedsFloor,
+    /// Object can't be placed inside a room.
+    OutdoorOnly,
+    /// Object should be placed in or close to water.
+    NearWater,
+}
+
+impl PlacementRule {
+    /// Checks the rule for the current state.
+    ///
+    /// `surface_tags` are tags of the object under the placing object, if any.
+    pub(super) fn check(
+        self,
+        state: &PlacingObjectState,
+        surface_tags: Option<&ObjectTags>,
+    ) -> Result<(), PlacementError> {
+        match self {
+            PlacementRule::NeedsWall => {
+                if state.snapped_to_wall {
+                    Ok(())
+                } else {
+                    Err(PlacementError::NoWall)
+                }
+            }
+            PlacementRule::NeedsSurface => {
+                if surface_tags.is_some_and(|tags| tags.contains(&ObjectTag::Surface)) {
+                    Ok(())
+                } else {
+                    Err(PlacementError::NoSurface)
+                }
+            }
+            PlacementRule::NeedsFloor => {
+                if state.indoors {
+                    Ok(())
+                } else {
+                    Err(PlacementError::NoFloor)
+                }
+            }
+            PlacementRule::OutdoorOnly => {
+                if state.indoors {
+                    Err(PlacementError::Indoors)
+                } else {
+                    Ok(())
+                }
+            }
+            PlacementRule::NearWater => {
+                if state.near_water {
+                    Ok(())
+                } else {
+                    Err(PlacementError::NoWater)
+                }
+            }
+        }
+    }
+}
+
+/// Reasons why the placing object can't be placed at its current position.
+///
+/// Empty if placing is allowed.
+#[derive(Component, Default, Deref, DerefMut, PartialEq)]
+pub struct PlacementErrors(Vec<PlacementError>);
+
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+pub enum PlacementError {
+    #[strum(serialize = "Collides with other objects")]
+    Collision,
+    #[strum(serialize = "Should be placed on a wall")]
+    NoWall,
+    #[strum(serialize = "Should be placed on a surface")]
+    NoSurface,
+    #[strum(serialize = "Should be placed on a floor")]
+    NoFloor,
+    #[strum(serialize = "Should be placed outdoors")]
+    Indoors,
+    #[strum(serialize = "Should be placed near water")]
+    NoWater,
+    #[strum(serialize = "Overlaps a door or window")]
+    Aperture,
+    #[strum(serialize = "Should be placed under a ceiling")]
+    NoCeiling,
+    #[strum(serialize = "Ground is too uneven")]
+    UnevenGround,
+    #[strum(serialize = "No build rights on this lot")]
+    NoPermission,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn needs_surface() {
+        let mut state = PlacingObjectState::new(Vec3::ZERO);
+        assert_eq!(
+            PlacementRule::NeedsSurface.check(&state, None),
+            Err(PlacementError::NoSurface)
+        );
+
+        state.surface = Some(Entity::PLACEHOLDER);
+        let tags = ObjectTags(vec![ObjectTag::Seating]);
+        assert_eq!(
+            PlacementRule::NeedsSurface.check(&state, Some(&tags)),
+            Err(PlacementError::NoSurface),
+            "objects without the capability shouldn't count as surfaces"
+        );
+
+        let tags = ObjectTags(vec![ObjectTag::Surface]);
+        assert_eq!(
+            PlacementRule::NeedsSurface.check(&state, Some(&tags)),
+            Ok(())
+        );
+    }
+}
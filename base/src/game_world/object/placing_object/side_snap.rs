@@ -71,7 +71,7 @@ impl SideSnapPlugin {
         }
     }
 
-    fn snap(
+    pub(super) fn snap(
         objects: Query<
             (&SideSnap, &Transform, &SideSnapNodes, &Visibility),
             Without<PlacingObject>,
@@ -0,0 +1,139 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+
+use super::{ground_fit::GroundFitPlugin, PlacingObject, PlacingObjectPlugin, PlacingObjectState};
+use crate::game_world::{
+    city::CityMode, family::building::BuildingMode, object::Object, player_camera::CameraCaster,
+};
+
+pub(super) struct SurfaceSnapPlugin;
+
+impl Plugin for SurfaceSnapPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SurfaceSlots>()
+            .register_type::<SurfaceSlot>()
+            .register_type::<SurfaceItem>()
+            .add_systems(
+                Update,
+                Self::snap
+                    .after(GroundFitPlugin::fit_placing)
+                    .before(PlacingObjectPlugin::update_validity)
+                    .run_if(in_state(CityMode::Objects).or_else(in_state(BuildingMode::Objects))),
+            );
+    }
+}
+
+impl SurfaceSnapPlugin {
+    /// Places the object on the slot under cursor.
+    pub(super) fn snap(
+        camera_caster: CameraCaster,
+        mut placing_objects: Query<
+            (
+                &mut Transform,
+                &mut PlacingObjectState,
+                &PlacingObject,
+                &Parent,
+            ),
+            With<SurfaceItem>,
+        >,
+        surfaces: Query<(Entity, &Parent, &GlobalTransform, &SurfaceSlots), With<Object>>,
+        cities: Query<&GlobalTransform>,
+    ) {
+        let Ok((mut transform, mut state, &placing_object, placing_parent)) =
+            placing_objects.get_single_mut()
+        else {
+            return;
+        };
+        let Some(ray) = camera_caster.ray() else {
+            return;
+        };
+
+        let hit = surfaces
+            .iter()
+            .filter(|&(entity, parent, ..)| {
+                parent == placing_parent
+                    && !matches!(placing_object, PlacingObject::Moving(moving) if moving == entity)
+            })
+            .flat_map(|(entity, _, &global_transform, slots)| {
+                slots
+                    .iter()
+                    .filter_map(move |slot| slot.intersect(ray, global_transform))
+                    .map(move |(distance, point)| (entity, distance, point))
+            })
+            .min_by(|(_, a, _), (_, b, _)| a.total_cmp(b));
+
+        let surface = hit.map(|(entity, ..)| entity);
+        if state.surface != surface {
+            debug!("changing surface to `{surface:?}`");
+            state.surface = surface;
+        }
+
+        if let Some((_, _, point)) = hit {
+            trace!("snapping to surface");
+            let city_transform = cities.get(**placing_parent).unwrap();
+            let local_point = city_transform.affine().inverse().transform_point3(point);
+            transform.translation = local_point;
+
+            // Surface is flat, ignore the ground.
+            if state.uneven_ground {
+                state.uneven_ground = false;
+            }
+        }
+    }
+}
+
+/// Flat areas on top of an object where [`SurfaceItem`]s can be placed.
+///
+/// Placed items become children of the object and move together with it.
+#[derive(Component, Reflect, Clone, Default, Deref)]
+#[reflect(Component)]
+pub(crate) struct SurfaceSlots(Vec<SurfaceSlot>);
+
+#[derive(Reflect, Clone, Default)]
+pub(crate) struct SurfaceSlot {
+    /// Slot name for debugging.
+    name: String,
+
+    /// Center of the slot relative to the object origin.
+    translation: Vec3,
+
+    /// Half of the slot size along X and Z axes.
+    half_size: Vec2,
+}
+
+impl SurfaceSlots {
+    /// Returns `true` if the translation relative to the object lies on one of its slots.
+    ///
+    /// Used to validate objects placed on surfaces by clients.
+    pub(crate) fn supports(&self, translation: Vec3) -> bool {
+        // Compensates floating-point errors after converting transforms to the surface space.
+        const HEIGHT_TOLERANCE: f32 = 0.01;
+        self.iter().any(|slot| {
+            let offset = (translation.xz() - slot.translation.xz()).abs();
+            offset.cmple(slot.half_size).all()
+                && (translation.y - slot.translation.y).abs() <= HEIGHT_TOLERANCE
+        })
+    }
+}
+
+impl SurfaceSlot {
+    /// Returns distance along the ray and intersection point in global coordinates.
+    fn intersect(&self, ray: Ray3d, global_transform: GlobalTransform) -> Option<(f32, Vec3)> {
+        let origin = global_transform.transform_point(self.translation);
+        let distance = ray.intersect_plane(origin, InfinitePlane3d::new(global_transform.up()))?;
+        let point = ray.get_point(distance);
+
+        let local_point = global_transform.affine().inverse().transform_point3(point);
+        let offset = (local_point - self.translation).xz().abs();
+        if offset.cmple(self.half_size).all() {
+            trace!("intersecting slot '{}'", self.name);
+            Some((distance, point))
+        } else {
+            None
+        }
+    }
+}
+
+/// Marks an object that can be placed on [`SurfaceSlots`].
+#[derive(Component, Reflect, Clone, Copy, Default)]
+#[reflect(Component)]
+pub(crate) struct SurfaceItem;
@@ -1,12 +1,15 @@
 use std::f32::consts::PI;
 
+use avian3d::prelude::*;
 use bevy::prelude::*;
 
-use super::{ObjectRotationLimit, PlacingObjectPlugin, PlacingObjectState};
+use super::{
+    ground_fit::GroundFitPlugin, ObjectRotationLimit, PlacingObjectPlugin, PlacingObjectState,
+};
 use crate::game_world::{
     city::CityMode,
     family::building::{
-        wall::{wall_mesh::HALF_WIDTH, Wall},
+        wall::{wall_mesh::HALF_WIDTH, Apertures, Wall},
         BuildingMode,
     },
     spline::SplineSegment,
@@ -16,33 +19,29 @@ pub(super) struct WallSnapPlugin;
 
 impl Plugin for WallSnapPlugin {
     fn build(&self, app: &mut App) {
-        app.register_type::<WallSnap>().add_systems(
-            Update,
-            (
-                Self::init_placing,
-                Self::snap
-                    .after(PlacingObjectPlugin::apply_position)
-                    .before(PlacingObjectPlugin::confirm),
-            )
-                .chain()
-                .run_if(in_state(CityMode::Objects).or_else(in_state(BuildingMode::Objects))),
-        );
+        app.register_type::<WallSnap>()
+            .register_type::<WallHeight>()
+            .add_systems(
+                Update,
+                (
+                    Self::snap
+                        .after(PlacingObjectPlugin::apply_position)
+                        .before(GroundFitPlugin::fit_placing),
+                    (Self::clamp_height, Self::check_apertures)
+                        .chain()
+                        .after(GroundFitPlugin::fit_placing),
+                )
+                    .before(PlacingObjectPlugin::update_validity)
+                    .run_if(in_state(CityMode::Objects).or_else(in_state(BuildingMode::Objects))),
+            );
     }
 }
 
-impl WallSnapPlugin {
-    fn init_placing(
-        mut placing_objects: Query<(&mut PlacingObjectState, &WallSnap), Added<WallSnap>>,
-    ) {
-        if let Ok((mut placing_object, snap)) = placing_objects.get_single_mut() {
-            if snap.required() {
-                debug!("disabling placing until snapped");
-                placing_object.allowed_place = false;
-            }
-        }
-    }
+/// Maximum distance from a wall at which objects snap to it.
+const SNAP_DELTA: f32 = 1.0;
 
-    fn snap(
+impl WallSnapPlugin {
+    pub(super) fn snap(
         walls: Query<&SplineSegment, With<Wall>>,
         mut placing_objects: Query<(
             &mut Transform,
@@ -57,12 +56,16 @@ impl WallSnapPlugin {
             return;
         };
 
-        const SNAP_DELTA: f32 = 1.0;
         let object_point = transform.translation.xz();
         if let Some((wall, wall_point)) = walls
             .iter()
             .map(|wall| (wall, wall.closest_point(object_point)))
-            .find(|(_, point)| point.distance(object_point) <= SNAP_DELTA)
+            .filter(|(_, point)| point.distance(object_point) <= SNAP_DELTA)
+            .min_by(|(_, a), (_, b)| {
+                // Pick the closest wall since several of them are in range near diagonal corners.
+                a.distance_squared(object_point)
+                    .total_cmp(&b.distance_squared(object_point))
+            })
         {
             trace!("snapping to wall");
             const GAP: f32 = 0.03; // A small gap between the object and wall to avoid collision.
@@ -70,7 +73,7 @@ impl WallSnapPlugin {
             let sign = disp.perp_dot(object_point - wall_point).signum();
             let offset = match snap {
                 WallSnap::Inside => Vec2::ZERO,
-                WallSnap::Outside { .. } => sign * disp.perp().normalize() * (HALF_WIDTH + GAP),
+                WallSnap::Outside => sign * disp.perp().normalize() * (HALF_WIDTH + GAP),
             };
             let snap_point = wall_point + offset;
             let angle = disp.angle_between(Vec2::X * sign);
@@ -81,42 +84,112 @@ impl WallSnapPlugin {
                 debug!("applying rotation {angle}");
                 transform.rotation = Quat::from_rotation_y(angle);
                 **rotation_limit = Some(PI);
-                if snap.required() {
-                    debug!("allowing placing");
-                    state.allowed_place = true;
-                }
+                state.snapped_to_wall = true;
             }
         } else if rotation_limit.is_some() {
+            debug!("detaching from wall");
             **rotation_limit = None;
-            if snap.required() {
-                debug!("disallowing placing");
-                state.allowed_place = false;
-            }
+            state.snapped_to_wall = false;
+        }
+    }
+
+    /// Keeps [`PlacingObjectState::vertical_offset`] of wall-snapped objects within [`WallHeight`].
+    fn clamp_height(
+        mut placing_objects: Query<(&mut Transform, &mut PlacingObjectState, &WallHeight)>,
+    ) {
+        let Ok((mut transform, mut state, height)) = placing_objects.get_single_mut() else {
+            return;
+        };
+
+        if !state.snapped_to_wall {
+            return;
+        }
+
+        let vertical_offset = state.vertical_offset.clamp(height.min, height.max);
+        if state.vertical_offset != vertical_offset {
+            debug!("clamping vertical offset to {vertical_offset}");
+            transform.translation.y += vertical_offset - state.vertical_offset;
+            state.vertical_offset = vertical_offset;
+        }
+    }
+
+    /// Checks if an object attached to a wall overlaps doors or windows on it.
+    ///
+    /// Other wall-mounted objects are already detected by collisions.
+    fn check_apertures(
+        walls: Query<(&SplineSegment, &Apertures), With<Wall>>,
+        mut placing_objects: Query<(&Transform, &mut PlacingObjectState, &Collider, &WallSnap)>,
+    ) {
+        let Ok((transform, mut state, collider, &snap)) = placing_objects.get_single_mut() else {
+            return;
+        };
+
+        let overlaps = state.snapped_to_wall
+            && snap == WallSnap::Outside
+            && walls
+                .iter()
+                .map(|(wall, apertures)| {
+                    (
+                        wall,
+                        apertures,
+                        wall.closest_point(transform.translation.xz()),
+                    )
+                })
+                .filter(|(.., point)| point.distance(transform.translation.xz()) <= SNAP_DELTA)
+                .min_by(|(.., a), (.., b)| {
+                    a.distance_squared(transform.translation.xz())
+                        .total_cmp(&b.distance_squared(transform.translation.xz()))
+                })
+                .is_some_and(|(wall, apertures, point)| {
+                    // Object is rotated to face away from the wall, so its X axis goes along it.
+                    let aabb = collider.aabb(Vec3::ZERO, Quat::IDENTITY);
+                    let half_width = aabb.min.x.abs().max(aabb.max.x.abs());
+                    let distance = point.distance(wall.start);
+                    let bounds = Rect::new(
+                        distance - half_width,
+                        transform.translation.y + aabb.min.y,
+                        distance + half_width,
+                        transform.translation.y + aabb.max.y,
+                    );
+
+                    apertures
+                        .iter()
+                        .filter(|aperture| !aperture.placing_object)
+                        .any(|aperture| !aperture.bounds().intersect(bounds).is_empty())
+                });
+
+        if state.overlaps_aperture != overlaps {
+            debug!("changing aperture overlap to `{overlaps}`");
+            state.overlaps_aperture = overlaps;
         }
     }
 }
 
 /// Enables attaching objects to walls.
-#[derive(Component, Reflect, Clone, Copy)]
+///
+/// To require attachment, use [`PlacementRule::NeedsWall`](super::placement_rules::PlacementRule::NeedsWall).
+#[derive(Component, Reflect, Clone, Copy, PartialEq)]
 #[reflect(Component)]
 pub(crate) enum WallSnap {
     /// Place inside a wall, like a door or a window.
-    ///
-    /// Object will be required to placed inside.
     Inside,
 
     /// Attach to a wall, like painting.
-    Outside {
-        /// Requires an object to be placed on a wall.
-        required: bool,
-    },
+    Outside,
 }
 
-impl WallSnap {
-    fn required(self) -> bool {
-        match self {
-            WallSnap::Inside => true,
-            WallSnap::Outside { required } => required,
-        }
+/// Height range for [`PlacingObjectState::vertical_offset`] while attached to a wall.
+///
+/// Allows raising or lowering objects like paintings and shelves.
+#[derive(Component, Reflect, Clone, Copy)]
+#[reflect(Component)]
+pub(crate) struct WallHeight {
+    min: f32,
+    max: f32,
+}
+
+impl WallHeight {
+    pub(super) fn contains(self, height: f32, tolerance: f32) -> bool {
+        (self.min - tolerance..=self.max + tolerance).contains(&height)
     }
 }
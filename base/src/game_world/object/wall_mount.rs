@@ -1,14 +1,22 @@
 use avian3d::prelude::*;
 use bevy::{
-    ecs::component::{ComponentHooks, StorageType},
+    ecs::{
+        component::{ComponentHooks, StorageType},
+        entity::MapEntities,
+        reflect::ReflectMapEntities,
+    },
     prelude::*,
 };
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use super::placing_object::PlacingObject;
 use crate::{
     core::GameState,
     game_world::{
-        family::building::wall::{Aperture, Apertures, WallPlugin},
+        family::building::wall::{
+            placing_wall::PlacingWall, Aperture, Apertures, Wall, WallPlugin,
+        },
         spline::SplineSegment,
         Layer,
     },
@@ -19,10 +27,23 @@ pub(super) struct WallMountPlugin;
 impl Plugin for WallMountPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<WallMount>()
-            .add_systems(Update, Self::init.run_if(in_state(GameState::InGame)))
+            .register_type::<WallAttachment>()
+            .replicate_mapped::<WallAttachment>()
+            .add_systems(
+                Update,
+                (
+                    Self::init,
+                    Self::init_attachment.run_if(server_or_singleplayer),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
             .add_systems(
                 PostUpdate,
-                Self::update_apertures
+                (
+                    Self::attach.run_if(server_or_singleplayer),
+                    Self::update_apertures,
+                )
+                    .chain()
                     .before(WallPlugin::update_meshes)
                     .run_if(in_state(GameState::InGame)),
             );
@@ -42,7 +63,63 @@ impl WallMountPlugin {
         }
     }
 
+    fn init_attachment(
+        mut commands: Commands,
+        objects: Query<
+            Entity,
+            (
+                With<WallMount>,
+                Without<WallAttachment>,
+                Without<PlacingObject>,
+            ),
+        >,
+    ) {
+        for entity in &objects {
+            debug!("initializing wall attachment for `{entity}`");
+            commands.entity(entity).insert(WallAttachment::default());
+        }
+    }
+
+    /// Picks walls for placed objects on server.
+    ///
+    /// Re-attaches all objects when walls are changed or removed, for example after a split.
+    /// Clients rely on the replicated result to reconstruct the same apertures.
+    fn attach(
+        mut removed_walls: RemovedComponents<Wall>,
+        walls: Query<(Entity, &Parent, Ref<SplineSegment>), (With<Wall>, Without<PlacingWall>)>,
+        mut objects: Query<
+            (Entity, &Parent, Ref<Transform>, &mut WallAttachment),
+            Without<PlacingObject>,
+        >,
+    ) {
+        let walls_changed = removed_walls.read().count() != 0
+            || walls.iter().any(|(.., segment)| segment.is_changed());
+        for (object_entity, object_parent, transform, mut attachment) in &mut objects {
+            if !walls_changed && !transform.is_changed() && !attachment.is_added() {
+                continue;
+            }
+
+            let wall_entity = closest_wall(
+                walls
+                    .iter()
+                    .map(|(entity, parent, segment)| (entity, parent, segment.into_inner())),
+                object_parent,
+                transform.translation.xz(),
+            );
+            if attachment.0 != wall_entity {
+                debug!("attaching `{object_entity}` to `{wall_entity:?}`");
+                attachment.0 = wall_entity;
+            } else if walls_changed && wall_entity.is_some() {
+                // The wall could be moved, so the aperture needs an update.
+                attachment.set_changed();
+            }
+        }
+    }
+
     /// Updates [`Apertures`] based on spawned objects.
+    ///
+    /// Placing objects are attached to the closest wall locally,
+    /// while placed objects follow their [`WallAttachment`].
     fn update_apertures(
         mut walls: Query<(Entity, &Parent, &SplineSegment, &mut Apertures)>,
         mut objects: Query<
@@ -53,9 +130,14 @@ impl WallMountPlugin {
                 &Transform,
                 &WallMount,
                 &mut ObjectWall,
+                Option<&WallAttachment>,
                 Has<PlacingObject>,
             ),
-            Or<(Changed<Transform>, Changed<Visibility>)>,
+            Or<(
+                Changed<Transform>,
+                Changed<Visibility>,
+                Changed<WallAttachment>,
+            )>,
         >,
     ) {
         for (
@@ -65,86 +147,97 @@ impl WallMountPlugin {
             transform,
             wall_mount,
             mut object_wall,
+            attachment,
             placing_object,
         ) in &mut objects
         {
-            if visibility == Visibility::Hidden {
-                if let Some(wall_entity) = object_wall.0.take() {
-                    trace!(
-                        "removing hidden `{object_entity}` from the apreture of `{wall_entity}`"
-                    );
-                    let (.., mut apertures) = walls.get_mut(wall_entity).unwrap();
+            let translation = transform.translation;
+            let target_entity = if visibility == Visibility::Hidden {
+                None
+            } else if placing_object {
+                closest_wall(
+                    walls
+                        .iter()
+                        .map(|(entity, parent, segment, _)| (entity, parent, segment)),
+                    object_parent,
+                    translation.xz(),
+                )
+            } else {
+                attachment.and_then(|attachment| attachment.0)
+            };
+
+            if let Some(current_entity) = object_wall
+                .0
+                .filter(|&entity| Some(entity) != target_entity)
+            {
+                trace!("removing `{object_entity}` from the apreture of `{current_entity}`");
+                if let Ok((.., mut apertures)) = walls.get_mut(current_entity) {
                     apertures.remove(object_entity);
                 }
-                continue;
+                object_wall.0 = None;
             }
 
-            let translation = transform.translation;
-            if let Some((wall_entity, _, sement, mut apertures)) = walls
-                .iter_mut()
-                .filter(|&(_, parent, ..)| parent == object_parent)
-                .find(|(.., segment, _)| segment.contains(translation.xz()))
-            {
-                let distance = translation.xz().distance(sement.start);
-                if let Some(current_entity) = object_wall.0 {
-                    if current_entity == wall_entity {
-                        trace!("updating apreture of `{wall_entity}` for `{object_entity}`");
-                        // Remove to update distance.
-                        let mut aperture = apertures.remove(object_entity);
-
-                        aperture.distance = distance;
-                        aperture.translation = translation;
-
-                        apertures.insert(aperture);
-                    } else {
-                        trace!("adding `{object_entity}` to the apreture of `{wall_entity}`");
-                        apertures.insert(Aperture {
-                            object_entity,
-                            translation,
-                            distance,
-                            cutout: wall_mount.cutout.clone(),
-                            hole: wall_mount.hole,
-                            placing_object,
-                        });
-
-                        object_wall.0 = Some(wall_entity);
-
-                        trace!(
-                            "removing `{object_entity}` from the apreture of `{current_entity}`"
-                        );
-                        let (.., mut current_apertures) = walls.get_mut(current_entity).unwrap();
-                        current_apertures.remove(object_entity);
-                    }
-                } else {
-                    trace!("adding `{object_entity}` to the apreture of `{wall_entity}`");
-                    apertures.insert(Aperture {
-                        object_entity,
-                        translation,
-                        distance,
-                        cutout: wall_mount.cutout.clone(),
-                        hole: wall_mount.hole,
-                        placing_object,
-                    });
-
-                    object_wall.0 = Some(wall_entity);
-                }
-            } else if let Some(wall_entity) = object_wall.0.take() {
-                trace!("removing `{object_entity}` from the apreture of `{wall_entity}`");
-                let (.., mut apertures) = walls.get_mut(wall_entity).unwrap();
-                apertures.remove(object_entity);
+            let Some(wall_entity) = target_entity else {
+                continue;
+            };
+            let Ok((_, _, segment, mut apertures)) = walls.get_mut(wall_entity) else {
+                continue;
+            };
+
+            let distance = translation.xz().distance(segment.start);
+            if object_wall.0.is_some() {
+                trace!("updating apreture of `{wall_entity}` for `{object_entity}`");
+                // Remove to update distance.
+                let mut aperture = apertures.remove(object_entity);
+
+                aperture.distance = distance;
+                aperture.translation = translation;
+
+                apertures.insert(aperture);
+            } else {
+                trace!("adding `{object_entity}` to the apreture of `{wall_entity}`");
+                apertures.insert(Aperture {
+                    object_entity,
+                    translation,
+                    distance,
+                    cutout: wall_mount.cutout.clone(),
+                    hole: wall_mount.hole,
+                    placing_object,
+                });
+
+                object_wall.0 = Some(wall_entity);
             }
         }
     }
 }
 
+/// Returns the closest wall from the same city that contains the point.
+///
+/// Objects near corners may lie on several walls.
+fn closest_wall<'a>(
+    walls: impl Iterator<Item = (Entity, &'a Parent, &'a SplineSegment)>,
+    object_parent: &Parent,
+    point: Vec2,
+) -> Option<Entity> {
+    walls
+        .filter(|&(_, parent, segment)| parent == object_parent && segment.contains(point))
+        .min_by(|(_, _, a), (_, _, b)| {
+            a.closest_point(point)
+                .distance_squared(point)
+                .total_cmp(&b.closest_point(point).distance_squared(point))
+        })
+        .map(|(entity, ..)| entity)
+}
+
 /// A component that marks that entity can be placed only on walls or inside them.
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub(crate) struct WallMount {
     /// Points for an aperture in the wall.
     ///
-    /// Should be set clockwise if the object creates a hole (such as a window),
-    /// or counterclockwise if it creates a clipping (such as a door).
+    /// Could have any winding if the object creates a hole (such as a window),
+    /// but should be set clockwise if it creates a clipping (such as a door).
+    /// Invalid cutouts are ignored during the wall mesh generation.
     cutout: Vec<Vec2>,
 
     /// Should be set to `true` if the object creates a hole (such as a window).
@@ -168,3 +261,18 @@ impl Component for ObjectWall {
         });
     }
 }
+
+/// Wall to which a placed object cuts its aperture.
+///
+/// Decided on server and replicated, so all clients generate identical wall meshes.
+#[derive(Component, Clone, Copy, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+pub(crate) struct WallAttachment(Option<Entity>);
+
+impl MapEntities for WallAttachment {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        if let Some(entity) = &mut self.0 {
+            *entity = entity_mapper.map_entity(*entity);
+        }
+    }
+}
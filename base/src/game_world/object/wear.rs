@@ -0,0 +1,175 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{core::GameState, game_world::aging::WorldAging};
+
+/// Degrades objects with use until they break.
+///
+/// Broken objects don't provide tasks until repaired.
+pub(super) struct WearPlugin;
+
+impl Plugin for WearPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Durability>()
+            .register_type::<Condition>()
+            .register_type::<Broken>()
+            .replicate::<Condition>()
+            .replicate::<Broken>()
+            .add_event::<ObjectUsed>()
+            .add_systems(
+                Update,
+                (
+                    (Self::init, Self::wear)
+                        .chain()
+                        .run_if(server_or_singleplayer),
+                    (Self::spawn_sparks, Self::flicker),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::despawn_sparks.run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Base intensity of the sparks light.
+const SPARKS_INTENSITY: f32 = 20_000.0;
+
+impl WearPlugin {
+    fn init(
+        mut commands: Commands,
+        objects: Query<Entity, (With<Durability>, Without<Condition>)>,
+    ) {
+        for entity in &objects {
+            debug!("initializing condition for `{entity}`");
+            commands.entity(entity).insert(Condition::default());
+        }
+    }
+
+    fn wear(
+        mut commands: Commands,
+        mut used_events: EventReader<ObjectUsed>,
+        mut objects: Query<(&Durability, &mut Condition), Without<Broken>>,
+        aging: Query<(), With<WorldAging>>,
+    ) {
+        let multiplier = if aging.is_empty() {
+            1.0
+        } else {
+            WorldAging::WEAR_MULTIPLIER
+        };
+        for event in used_events.read() {
+            let Ok((durability, mut condition)) = objects.get_mut(event.0) else {
+                continue;
+            };
+
+            // Objects with zero uses from metadata break on the first use.
+            **condition -= multiplier / durability.uses.max(1) as f32;
+            if **condition <= 0.0 {
+                info!("`{}` broke", event.0);
+                **condition = 0.0;
+                commands.entity(event.0).insert(Broken);
+            }
+        }
+    }
+
+    fn spawn_sparks(mut commands: Commands, objects: Query<Entity, Added<Broken>>) {
+        for entity in &objects {
+            debug!("spawning sparks for `{entity}`");
+            commands.entity(entity).with_children(|parent| {
+                parent.spawn((
+                    Sparks,
+                    PointLightBundle {
+                        point_light: PointLight {
+                            color: Color::srgb(0.6, 0.8, 1.0),
+                            intensity: SPARKS_INTENSITY,
+                            range: 2.0,
+                            ..Default::default()
+                        },
+                        transform: Transform::from_translation(Vec3::Y * 0.5),
+                        ..Default::default()
+                    },
+                ));
+            });
+        }
+    }
+
+    /// Makes sparks blink at irregular intervals.
+    fn flicker(time: Res<Time>, mut sparks: Query<&mut Visibility, With<Sparks>>) {
+        let elapsed = time.elapsed_seconds();
+        let visible = (elapsed * 13.0).sin() * (elapsed * 3.0).sin() > 0.6;
+        for mut visibility in &mut sparks {
+            let new_visibility = if visible {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+            visibility.set_if_neq(new_visibility);
+        }
+    }
+
+    fn despawn_sparks(
+        mut commands: Commands,
+        mut removed_broken: RemovedComponents<Broken>,
+        objects: Query<&Children>,
+        sparks: Query<(), With<Sparks>>,
+    ) {
+        for children in objects.iter_many(removed_broken.read()) {
+            for &entity in children {
+                if sparks.get(entity).is_ok() {
+                    debug!("despawning sparks `{entity}`");
+                    commands.entity(entity).despawn();
+                }
+            }
+        }
+    }
+}
+
+/// Object that wears out with use.
+///
+/// Usually defined in object metadata.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub(crate) struct Durability {
+    /// Number of uses before the object breaks.
+    uses: u32,
+
+    /// Minimum [`Handiness`](crate::game_world::actor::skills::Handiness) required to repair.
+    pub(crate) repair_level: u8,
+}
+
+impl Default for Durability {
+    fn default() -> Self {
+        Self {
+            uses: 50,
+            repair_level: 0,
+        }
+    }
+}
+
+/// Remaining durability of an object from 0 to 1.
+#[derive(Clone, Component, Copy, Deref, DerefMut, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub(crate) struct Condition(f32);
+
+impl Default for Condition {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Marks an object that needs to be repaired before use.
+#[derive(Component, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub(crate) struct Broken;
+
+/// Emitted on the server when an actor uses an object.
+///
+/// Wears the object if it has [`Durability`].
+#[derive(Event)]
+pub(crate) struct ObjectUsed(pub(crate) Entity);
+
+/// Light spawned for [`Broken`] objects.
+#[derive(Component)]
+struct Sparks;
@@ -1,4 +1,5 @@
 mod exp_smoothed;
+pub mod watching;
 
 use std::f32::consts::{FRAC_PI_2, PI};
 
@@ -21,15 +22,26 @@ use self::exp_smoothed::ExpSmoothed;
 use crate::{
     asset::collection::{AssetCollection, Collection},
     common_conditions::in_any_state,
-    game_world::WorldState,
+    game_world::{
+        actor::{task::travel::InTransit, SelectedActor},
+        WorldState,
+    },
     settings::{Action, Settings},
 };
+use watching::WatchingPlugin;
 
 pub(super) struct PlayerCameraPlugin;
 
 impl Plugin for PlayerCameraPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Collection<EnvironmentMap>>()
+            .add_plugins(WatchingPlugin)
+            .add_systems(
+                Update,
+                Self::follow_arrived
+                    .before(Self::update_origin)
+                    .run_if(in_state(WorldState::Family)),
+            )
             .add_systems(
                 Update,
                 (
@@ -48,6 +60,7 @@ impl Plugin for PlayerCameraPlugin {
                         WorldState::FamilyEditor,
                         WorldState::City,
                         WorldState::Family,
+                        WorldState::Spectator,
                     ])),
             );
     }
@@ -65,7 +78,24 @@ impl PlayerCameraPlugin {
         let motion = motion_events.read().map(|event| &event.delta).sum::<Vec2>();
         if action_state.pressed(&Action::RotateCamera) {
             const SENSETIVITY: f32 = 0.01;
-            orbit_rotation.dest -= SENSETIVITY * motion;
+            let controls = &settings.controls;
+            let invert = Vec2::new(
+                if controls.invert_rotation_x {
+                    -1.0
+                } else {
+                    1.0
+                },
+                if controls.invert_rotation_y {
+                    -1.0
+                } else {
+                    1.0
+                },
+            );
+            orbit_rotation.dest -= SENSETIVITY
+                * controls.rotation_sensitivity
+                * fine_factor(&action_state)
+                * invert
+                * motion;
 
             let max_y = if settings.developer.free_camera_rotation {
                 PI
@@ -85,17 +115,39 @@ impl PlayerCameraPlugin {
     ) {
         let (mut orbit_origin, transform, spring_arm) = cameras.single_mut();
         let direction = movement_direction(&action_state, transform.rotation);
-        orbit_origin.dest += direction * time.delta_seconds() * spring_arm.dest;
+        orbit_origin.dest +=
+            direction * fine_factor(&action_state) * time.delta_seconds() * spring_arm.dest;
         orbit_origin.smooth(time.delta_seconds());
     }
 
+    /// Moves camera origin to the selected actor after travelling.
+    fn follow_arrived(
+        mut removed_transits: RemovedComponents<InTransit>,
+        actors: Query<&Transform, With<SelectedActor>>,
+        mut cameras: Query<&mut OrbitOrigin, With<PlayerCamera>>,
+    ) {
+        for actor_transform in actors.iter_many(removed_transits.read()) {
+            if let Ok(mut orbit_origin) = cameras.get_single_mut() {
+                debug!("moving camera to the arrived actor");
+                orbit_origin.dest = actor_transform.translation;
+            }
+        }
+    }
+
     fn update_spring_arm(
         time: Res<Time>,
+        settings: Res<Settings>,
         action_state: Res<ActionState<Action>>,
         mut cameras: Query<&mut SpringArm, With<PlayerCamera>>,
     ) {
         let mut spring_arm = cameras.single_mut();
-        spring_arm.dest = (spring_arm.dest - action_state.value(&Action::ZoomCamera)).max(0.0);
+        // Zoom is used to offset placing objects.
+        if !action_state.pressed(&Action::VerticalOffset) {
+            let zoom = action_state.value(&Action::ZoomCamera)
+                * settings.controls.zoom_sensitivity
+                * fine_factor(&action_state);
+            spring_arm.dest = (spring_arm.dest - zoom).max(0.0);
+        }
         spring_arm.smooth(time.delta_seconds());
     }
 
@@ -112,6 +164,15 @@ impl PlayerCameraPlugin {
     }
 }
 
+/// Returns speed multiplier based on [`Action::FineAdjustment`] modifier.
+fn fine_factor(action_state: &ActionState<Action>) -> f32 {
+    if action_state.pressed(&Action::FineAdjustment) {
+        0.25
+    } else {
+        1.0
+    }
+}
+
 fn movement_direction(action_state: &ActionState<Action>, rotation: Quat) -> Vec3 {
     let mut direction = Vec3::ZERO;
     if action_state.pressed(&Action::CameraLeft) {
@@ -177,6 +238,11 @@ impl PlayerCameraBundle {
             },
         }
     }
+
+    pub(crate) fn with_order(mut self, order: isize) -> Self {
+        self.camera_3d_bundle.camera.order = order;
+        self
+    }
 }
 
 #[derive(Clone, Copy, Debug, EnumIter, IntoPrimitive)]
@@ -228,7 +294,7 @@ impl Default for SpringArm {
 }
 
 #[derive(Component, Default)]
-pub(super) struct PlayerCamera;
+pub struct PlayerCamera;
 
 /// A helper to cast rays from [`PlayerCamera`].
 #[derive(SystemParam)]
@@ -0,0 +1,188 @@
+use bevy::{ecs::entity::MapEntities, prelude::*};
+use bevy_replicon::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+use serde::{Deserialize, Serialize};
+
+use super::{movement_direction, OrbitOrigin, PlayerCamera, PlayerCameraPlugin};
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{Actor, SelectedActor},
+        family::FamilyOwner,
+    },
+    settings::Action,
+};
+
+/// Following actors selected by other players.
+///
+/// Players share their selected actor only with [`WatchConsent`].
+pub(super) struct WatchingPlugin;
+
+impl Plugin for WatchingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WatchConsent>()
+            .init_resource::<WatchedActor>()
+            .replicate::<SharedSelection>()
+            .add_mapped_client_event::<SelectionShare>(ChannelKind::Ordered)
+            .add_systems(
+                PreUpdate,
+                (Self::apply_shares, Self::release)
+                    .after(ServerSet::Receive)
+                    .run_if(server_running),
+            )
+            .add_systems(
+                Update,
+                (
+                    Self::share_selection.run_if(client_connected.or_else(server_running)),
+                    Self::follow.before(PlayerCameraPlugin::update_origin),
+                ),
+            )
+            .add_systems(OnExit(GameState::InGame), Self::cleanup);
+    }
+}
+
+impl WatchingPlugin {
+    fn share_selection(
+        mut share_events: EventWriter<SelectionShare>,
+        mut removed_selections: RemovedComponents<SelectedActor>,
+        consent: Res<WatchConsent>,
+        actors: Query<(Entity, Ref<SelectedActor>)>,
+    ) {
+        let selected = actors.get_single().ok();
+        let deselected = removed_selections.read().count() > 0;
+        if !consent.is_changed()
+            && !deselected
+            && !selected
+                .as_ref()
+                .is_some_and(|(_, selection)| selection.is_added())
+        {
+            return;
+        }
+
+        let actor_entity = selected.map(|(entity, _)| entity).filter(|_| consent.0);
+        debug!("sharing selection `{actor_entity:?}`");
+        share_events.send(SelectionShare(actor_entity));
+    }
+
+    fn apply_shares(
+        mut commands: Commands,
+        mut share_events: EventReader<FromClient<SelectionShare>>,
+        shared_actors: Query<(Entity, &SharedSelection)>,
+        actors: Query<&Actor>,
+        families: Query<&FamilyOwner>,
+    ) {
+        for FromClient { client_id, event } in share_events.read().copied() {
+            for (entity, _) in shared_actors
+                .iter()
+                .filter(|(_, shared)| ***shared == client_id)
+            {
+                commands.entity(entity).remove::<SharedSelection>();
+            }
+
+            let Some(actor_entity) = event.0 else {
+                debug!("`{client_id:?}` stops sharing selection");
+                continue;
+            };
+
+            let Ok(actor) = actors.get(actor_entity) else {
+                error!("`{client_id:?}` tries to share invalid actor `{actor_entity}`");
+                continue;
+            };
+            if !families
+                .get(actor.family_entity)
+                .is_ok_and(|&owner| *owner == client_id)
+            {
+                error!("`{client_id:?}` tries to share not controlled actor `{actor_entity}`");
+                continue;
+            }
+
+            info!("`{client_id:?}` shares selection of `{actor_entity}`");
+            commands
+                .entity(actor_entity)
+                .insert(SharedSelection(client_id));
+        }
+    }
+
+    fn release(
+        mut commands: Commands,
+        mut server_events: EventReader<ServerEvent>,
+        shared_actors: Query<(Entity, &SharedSelection)>,
+    ) {
+        for event in server_events.read() {
+            if let ServerEvent::ClientDisconnected { client_id, .. } = *event {
+                for (entity, _) in shared_actors
+                    .iter()
+                    .filter(|(_, shared)| ***shared == client_id)
+                {
+                    debug!("removing shared selection of `{client_id:?}` from `{entity}`");
+                    commands.entity(entity).remove::<SharedSelection>();
+                }
+            }
+        }
+    }
+
+    /// Moves camera origin to the watched actor.
+    ///
+    /// Stops on manual camera movement or if the actor is no longer shared.
+    fn follow(
+        action_state: Res<ActionState<Action>>,
+        mut watched_actor: ResMut<WatchedActor>,
+        actors: Query<(&Parent, &Transform), With<SharedSelection>>,
+        mut cameras: Query<(&Parent, &Transform, &mut OrbitOrigin), With<PlayerCamera>>,
+    ) {
+        let Some(actor_entity) = watched_actor.0 else {
+            return;
+        };
+        let Ok((camera_parent, camera_transform, mut orbit_origin)) = cameras.get_single_mut()
+        else {
+            return;
+        };
+
+        if movement_direction(&action_state, camera_transform.rotation) != Vec3::ZERO {
+            info!("stopping watching `{actor_entity}` due to camera movement");
+            watched_actor.0 = None;
+            return;
+        }
+
+        match actors.get(actor_entity) {
+            // Camera origin is in city space, so the actor should be in the same city.
+            Ok((parent, transform)) if **parent == **camera_parent => {
+                orbit_origin.dest = transform.translation;
+            }
+            _ => {
+                info!("stopping watching `{actor_entity}`");
+                watched_actor.0 = None;
+            }
+        }
+    }
+
+    fn cleanup(mut watched_actor: ResMut<WatchedActor>) {
+        watched_actor.0 = None;
+    }
+}
+
+/// Allows other players to follow the locally selected actor.
+#[derive(Default, Resource)]
+pub struct WatchConsent(pub bool);
+
+/// Actor with [`SharedSelection`] followed by the camera.
+///
+/// Resets to [`None`] when the camera is moved manually.
+#[derive(Default, Resource)]
+pub struct WatchedActor(pub Option<Entity>);
+
+/// Actor selected by a player who allowed watching.
+#[derive(Clone, Component, Copy, Deref, Deserialize, Serialize)]
+pub struct SharedSelection(ClientId);
+
+/// Shares the selected actor or stops sharing with [`None`].
+#[derive(Clone, Copy, Deserialize, Event, Serialize)]
+struct SelectionShare(Option<Entity>);
+
+impl MapEntities for SelectionShare {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        if let Some(entity) = &mut self.0 {
+            *entity = entity_mapper.map_entity(*entity);
+        }
+    }
+}
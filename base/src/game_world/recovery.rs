@@ -0,0 +1,486 @@
+use std::{any::TypeId, collections::BTreeMap};
+
+use bevy::{
+    prelude::*,
+    reflect::{serde::TypedReflectDeserializer, ReflectRef, TypeRegistry},
+    scene::{
+        ron::{self, value::RawValue},
+        DynamicEntity,
+    },
+    utils::HashSet,
+};
+use bevy_replicon::prelude::*;
+use serde::{de::DeserializeSeed, Deserialize};
+
+use super::object::Object;
+use crate::core::{
+    transition::{Transition, TransitionRequest},
+    GameState,
+};
+
+/// Handles worlds that were loaded with problematic entities.
+///
+/// Instead of failing the whole load, [`super::GameWorldPlugin`] inserts [`LoadRecovery`]
+/// and resolves it according to [`RecoveryPolicy`].
+pub(super) struct RecoveryPlugin;
+
+impl Plugin for RecoveryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RecoveryResolve>()
+            .init_resource::<RecoveryPolicy>()
+            .add_systems(
+                SpawnScene,
+                (
+                    Self::apply_policy.run_if(resource_added::<LoadRecovery>),
+                    Self::resolve
+                        .run_if(on_event::<RecoveryResolve>())
+                        .run_if(resource_exists::<LoadRecovery>),
+                )
+                    .chain()
+                    .before(bevy::scene::scene_spawner_system),
+            );
+    }
+}
+
+impl RecoveryPlugin {
+    fn apply_policy(mut resolve_events: EventWriter<RecoveryResolve>, policy: Res<RecoveryPolicy>) {
+        if *policy == RecoveryPolicy::Drop {
+            debug!("resolving recovery without asking");
+            resolve_events.send(RecoveryResolve::Drop);
+        }
+    }
+
+    fn resolve(
+        mut commands: Commands,
+        mut resolve_events: EventReader<RecoveryResolve>,
+        mut scene_spawner: ResMut<SceneSpawner>,
+        mut scenes: ResMut<Assets<DynamicScene>>,
+        mut transition_events: EventWriter<TransitionRequest>,
+        mut recovery: ResMut<LoadRecovery>,
+    ) {
+        let Some(&event) = resolve_events.read().last() else {
+            return;
+        };
+
+        match event {
+            RecoveryResolve::Drop => {
+                info!(
+                    "dropping {} problematic entities and continuing the load",
+                    recovery.issues.len()
+                );
+                let scene = recovery
+                    .scene
+                    .take()
+                    .expect("scene should be taken only once");
+                scene_spawner.spawn_dynamic(scenes.add(scene));
+                transition_events.send(TransitionRequest::new(Transition::Game(GameState::InGame)));
+            }
+            RecoveryResolve::Abort => info!("aborting world load"),
+        }
+
+        commands.remove_resource::<LoadRecovery>();
+    }
+}
+
+/// Parses a saved world entity by entity and collects everything that can't be loaded.
+///
+/// Unlike [`bevy::scene::serde::SceneDeserializer`], a single invalid component doesn't fail
+/// the whole world. Entities with invalid components are dropped together with all entities
+/// that are synchronized as their children.
+///
+/// `metadata_exists` should return `true` if the metadata with the given path is loaded.
+pub(super) fn deserialize_lenient(
+    bytes: &[u8],
+    registry: &TypeRegistry,
+    metadata_exists: impl Fn(&str) -> bool,
+) -> Result<(DynamicScene, Vec<LoadIssue>), ron::de::SpannedError> {
+    let raw_scene: RawScene = ron::de::from_bytes(bytes)?;
+    let parent_sync_path = registry
+        .get(TypeId::of::<ParentSync>())
+        .map(|registration| registration.type_info().type_path());
+    let object_path = registry
+        .get(TypeId::of::<Object>())
+        .map(|registration| registration.type_info().type_path());
+
+    let mut issues = Vec::new();
+    let mut entities = Vec::new();
+    let mut parents = BTreeMap::new();
+    for (bits, raw_entity) in raw_scene.entities {
+        let Ok(entity) = Entity::try_from_bits(bits) else {
+            issues.push(LoadIssue::new(
+                Entity::PLACEHOLDER,
+                format!("invalid entity id {bits}"),
+            ));
+            continue;
+        };
+
+        let mut components = Vec::new();
+        let mut descriptions = Vec::new();
+        for (type_path, raw_component) in raw_entity.components {
+            let raw_ron = raw_component.get_ron();
+            match deserialize_component(&type_path, raw_ron, registry) {
+                Ok(component) => {
+                    if Some(type_path.as_str()) == parent_sync_path {
+                        if let Some(parent) = synced_parent(&*component) {
+                            parents.insert(entity, parent);
+                        }
+                    }
+                    components.push(component);
+                }
+                Err(description) => descriptions.push(description),
+            }
+
+            // Objects with missing metadata are loaded as placeholders.
+            if Some(type_path.as_str()) == object_path {
+                continue;
+            }
+            for path in quoted_metadata_paths(raw_ron) {
+                if !metadata_exists(path) {
+                    descriptions.push(format!("references missing metadata '{path}'"));
+                }
+            }
+        }
+
+        if descriptions.is_empty() {
+            entities.push(DynamicEntity { entity, components });
+        } else {
+            issues.push(LoadIssue::new(entity, descriptions.join(", ")));
+        }
+    }
+
+    // Drop entities whose parents were dropped, repeating until nothing changes to cover nested hierarchies.
+    let mut dropped: HashSet<_> = issues.iter().map(|issue| issue.entity).collect();
+    loop {
+        let len = entities.len();
+        entities.retain(|dynamic_entity| {
+            let Some(parent) = parents.get(&dynamic_entity.entity) else {
+                return true;
+            };
+            if !dropped.contains(parent) {
+                return true;
+            }
+
+            dropped.insert(dynamic_entity.entity);
+            issues.push(LoadIssue::new(
+                dynamic_entity.entity,
+                format!("parent `{parent}` was dropped"),
+            ));
+            false
+        });
+
+        if entities.len() == len {
+            break;
+        }
+    }
+
+    audit_references(&mut entities, &mut issues);
+
+    let scene = DynamicScene {
+        resources: Vec::new(),
+        entities,
+    };
+
+    Ok((scene, issues))
+}
+
+/// Drops entities with references to entities that are not in the scene.
+///
+/// Otherwise scene spawning silently maps such references to dead entities
+/// and families, lots, tasks or claims break later. Repeats until nothing changes
+/// to catch entities that reference dropped ones.
+fn audit_references(entities: &mut Vec<DynamicEntity>, issues: &mut Vec<LoadIssue>) {
+    loop {
+        let saved: HashSet<_> = entities
+            .iter()
+            .map(|dynamic_entity| dynamic_entity.entity)
+            .collect();
+
+        let len = entities.len();
+        entities.retain(|dynamic_entity| {
+            for component in &dynamic_entity.components {
+                let mut references = Vec::new();
+                collect_entities(component.as_reflect(), &mut references);
+                if let Some(missing) = references
+                    .into_iter()
+                    .find(|&entity| entity != Entity::PLACEHOLDER && !saved.contains(&entity))
+                {
+                    // Deserialized components are dynamic, so take the name from the represented type.
+                    let type_path = component
+                        .get_represented_type_info()
+                        .map(|info| info.type_path_table().short_path())
+                        .unwrap_or_else(|| component.reflect_short_type_path());
+                    issues.push(LoadIssue::new(
+                        dynamic_entity.entity,
+                        format!("`{type_path}` references missing entity `{missing}`"),
+                    ));
+                    return false;
+                }
+            }
+
+            true
+        });
+
+        if entities.len() == len {
+            break;
+        }
+        debug!(
+            "dropped {} entities with dangling references",
+            len - entities.len()
+        );
+    }
+}
+
+/// Recursively collects all [`Entity`] values from a reflected value.
+fn collect_entities(value: &dyn Reflect, entities: &mut Vec<Entity>) {
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => {
+            for field in value.iter_fields() {
+                collect_entities(field, entities);
+            }
+        }
+        ReflectRef::TupleStruct(value) => {
+            for field in value.iter_fields() {
+                collect_entities(field, entities);
+            }
+        }
+        ReflectRef::Tuple(value) => {
+            for field in value.iter_fields() {
+                collect_entities(field, entities);
+            }
+        }
+        ReflectRef::List(value) => {
+            for item in value.iter() {
+                collect_entities(item, entities);
+            }
+        }
+        ReflectRef::Array(value) => {
+            for item in value.iter() {
+                collect_entities(item, entities);
+            }
+        }
+        ReflectRef::Map(value) => {
+            for (key, item) in value.iter() {
+                collect_entities(key, entities);
+                collect_entities(item, entities);
+            }
+        }
+        ReflectRef::Enum(value) => {
+            for field in value.iter_fields() {
+                collect_entities(field.value(), entities);
+            }
+        }
+        ReflectRef::Value(value) => {
+            if let Some(&entity) = value.downcast_ref::<Entity>() {
+                entities.push(entity);
+            }
+        }
+    }
+}
+
+fn deserialize_component(
+    type_path: &str,
+    raw_ron: &str,
+    registry: &TypeRegistry,
+) -> Result<Box<dyn Reflect>, String> {
+    let registration = registry
+        .get_with_type_path(type_path)
+        .ok_or_else(|| format!("unknown component `{type_path}`"))?;
+
+    let mut deserializer = ron::Deserializer::from_str(raw_ron)
+        .map_err(|error| format!("unable to parse `{type_path}`: {error}"))?;
+    TypedReflectDeserializer::new(registration, registry)
+        .deserialize(&mut deserializer)
+        .map_err(|error| format!("unable to deserialize `{type_path}`: {error}"))
+}
+
+/// Extracts parent from a reflected [`ParentSync`].
+fn synced_parent(component: &dyn Reflect) -> Option<Entity> {
+    let ReflectRef::TupleStruct(parent_sync) = component.reflect_ref() else {
+        return None;
+    };
+    let ReflectRef::Enum(parent) = parent_sync.field(0)?.reflect_ref() else {
+        return None;
+    };
+
+    parent.field_at(0)?.downcast_ref().copied()
+}
+
+/// Returns all string literals that look like paths to object metadata.
+fn quoted_metadata_paths(raw_ron: &str) -> impl Iterator<Item = &str> {
+    raw_ron
+        .split('"')
+        .skip(1)
+        .step_by(2)
+        .filter(|value| value.ends_with(".ron"))
+}
+
+/// Scene structure from [`DynamicScene::serialize`] with components kept unparsed.
+#[derive(Deserialize)]
+struct RawScene {
+    entities: BTreeMap<u64, RawEntity>,
+}
+
+#[derive(Deserialize)]
+struct RawEntity {
+    components: BTreeMap<String, Box<RawValue>>,
+}
+
+/// World that was partially loaded and waits for the player's decision.
+///
+/// Inserted instead of entering [`GameState::InGame`] when [`deserialize_lenient`] reports issues.
+#[derive(Resource)]
+pub struct LoadRecovery {
+    /// Valid part of the world.
+    ///
+    /// Taken when the player decides to continue.
+    scene: Option<DynamicScene>,
+    pub issues: Vec<LoadIssue>,
+}
+
+impl LoadRecovery {
+    pub(super) fn new(scene: DynamicScene, issues: Vec<LoadIssue>) -> Self {
+        Self {
+            scene: Some(scene),
+            issues,
+        }
+    }
+}
+
+/// Entity that can't be loaded.
+pub struct LoadIssue {
+    /// Entity ID from the saved world.
+    pub entity: Entity,
+    pub description: String,
+}
+
+impl LoadIssue {
+    fn new(entity: Entity, description: String) -> Self {
+        Self {
+            entity,
+            description,
+        }
+    }
+}
+
+/// Decides how [`LoadRecovery`] is resolved.
+///
+/// Headless apps have nobody to ask, so problematic entities are dropped by default.
+#[derive(Clone, Copy, Default, PartialEq, Resource)]
+pub enum RecoveryPolicy {
+    /// Wait for [`RecoveryResolve`], usually sent from a dialog.
+    Ask,
+    /// Send [`RecoveryResolve::Drop`] immediately.
+    #[default]
+    Drop,
+}
+
+/// Player's decision for [`LoadRecovery`].
+#[derive(Clone, Copy, Event)]
+pub enum RecoveryResolve {
+    /// Spawn the world without problematic entities.
+    Drop,
+    /// Return to the menu without loading the world.
+    Abort,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dangling_references() {
+        let actor = Entity::from_raw(1);
+        let object = Entity::from_raw(2);
+        let task = Entity::from_raw(3);
+        let mut entities = vec![
+            DynamicEntity {
+                entity: actor,
+                components: Vec::new(),
+            },
+            DynamicEntity {
+                entity: object,
+                components: vec![Box::new(Reference(Entity::from_raw(4)))],
+            },
+            DynamicEntity {
+                entity: task,
+                components: vec![Box::new(Reference(object)), Box::new(Reference(actor))],
+            },
+        ];
+
+        let mut issues = Vec::new();
+        audit_references(&mut entities, &mut issues);
+        let dropped: Vec<_> = issues.iter().map(|issue| issue.entity).collect();
+        assert_eq!(dropped, [object, task]);
+        assert_eq!(entities.len(), 1);
+    }
+
+    #[test]
+    fn nested_entities() {
+        let mut entities = Vec::new();
+        let value = (Some(Entity::from_raw(1)), vec![Entity::from_raw(2)]);
+        collect_entities(&value, &mut entities);
+        assert_eq!(entities, [Entity::from_raw(1), Entity::from_raw(2)]);
+    }
+
+    #[test]
+    fn metadata_paths() {
+        let paths: Vec<_> = quoted_metadata_paths(
+            r#"("base/objects/chairs/chair.ron", "not a path", "base/roads/dirt.ron")"#,
+        )
+        .collect();
+        assert_eq!(
+            paths,
+            ["base/objects/chairs/chair.ron", "base/roads/dirt.ron"]
+        );
+    }
+
+    #[test]
+    fn lenient_deserialization() {
+        let mut registry = TypeRegistry::default();
+        registry.register::<Entity>();
+        registry.register::<Option<Entity>>();
+        registry.register::<ParentSync>();
+        registry.register::<Metadata>();
+
+        let valid = Entity::from_raw(1);
+        let unknown = Entity::from_raw(2);
+        let child = Entity::from_raw(3);
+        let missing = Entity::from_raw(4);
+        let bytes = format!(
+            r#"(
+    resources: {{}},
+    entities: {{
+        {}: (components: {{ "{metadata}": ("base/objects/chair.ron") }}),
+        {}: (components: {{ "unknown::Component": () }}),
+        {}: (components: {{ "{parent_sync}": (Some({})) }}),
+        {}: (components: {{ "{metadata}": ("base/objects/missing.ron") }}),
+    }},
+)"#,
+            valid.to_bits(),
+            unknown.to_bits(),
+            child.to_bits(),
+            unknown.to_bits(),
+            missing.to_bits(),
+            metadata = Metadata::type_path(),
+            parent_sync = ParentSync::type_path(),
+        );
+
+        let (scene, issues) = deserialize_lenient(bytes.as_bytes(), &registry, |path| {
+            path == "base/objects/chair.ron"
+        })
+        .unwrap();
+
+        let loaded: Vec<_> = scene.entities.iter().map(|entity| entity.entity).collect();
+        assert_eq!(loaded, [valid]);
+
+        let mut dropped: Vec<_> = issues.iter().map(|issue| issue.entity).collect();
+        dropped.sort();
+        assert_eq!(dropped, [unknown, child, missing]);
+    }
+
+    #[derive(Reflect)]
+    struct Reference(Entity);
+
+    #[derive(Reflect)]
+    struct Metadata(String);
+}
@@ -0,0 +1,122 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_replicon::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::core::GameState;
+
+/// Provides reproducible randomness for the server simulation.
+///
+/// All streams are derived from the [`WorldSeed`] stored in the save.
+pub(super) struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<WorldSeed>()
+            .replicate::<WorldSeed>()
+            .init_resource::<WorldRng>()
+            // Should run before all simulation systems.
+            .add_systems(
+                PreUpdate,
+                (Self::init, Self::reseed)
+                    .chain()
+                    .after(ClientSet::Receive)
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+impl RngPlugin {
+    /// Spawns a random seed if the world doesn't have one.
+    ///
+    /// Seed is a replicated entity to be stored in saves.
+    fn init(mut commands: Commands, seeds: Query<(), With<WorldSeed>>) {
+        if seeds.is_empty() {
+            debug!("spawning world seed");
+            commands.spawn((
+                Name::new("World seed"),
+                StateScoped(GameState::InGame),
+                WorldSeed(rand::random()),
+                Replicated,
+            ));
+        }
+    }
+
+    fn reseed(mut rng: ResMut<WorldRng>, seeds: Query<&WorldSeed, Changed<WorldSeed>>) {
+        if let Ok(&seed) = seeds.get_single() {
+            info!("using world seed {}", seed.0);
+            *rng = WorldRng::new(seed);
+        }
+    }
+}
+
+/// Seed from which all [`WorldRng`] streams are derived.
+#[derive(Clone, Component, Copy, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct WorldSeed(pub u64);
+
+/// Independent random generators for each [`RngStream`].
+///
+/// Separate streams keep one system from shifting the sequence of another,
+/// so the results stay the same when systems are added or reordered.
+#[derive(Resource)]
+pub struct WorldRng {
+    seed: WorldSeed,
+    streams: HashMap<RngStream, StdRng>,
+}
+
+impl WorldRng {
+    fn new(seed: WorldSeed) -> Self {
+        Self {
+            seed,
+            streams: Default::default(),
+        }
+    }
+
+    /// Returns generator for the stream, creating it on first access.
+    pub fn stream(&mut self, stream: RngStream) -> &mut StdRng {
+        self.streams.entry(stream).or_insert_with(|| {
+            // `seed_from_u64` scrambles the input, so adjacent seeds are independent.
+            StdRng::seed_from_u64(self.seed.0.wrapping_add(stream as u64))
+        })
+    }
+}
+
+impl Default for WorldRng {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RngStream {
+    /// Decisions made by actors.
+    Ai,
+    /// Random world events, such as fires.
+    Events,
+    /// Collectables production.
+    Collectables,
+    /// Overgrowth on unattended lots.
+    Aging,
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn streams_reproducible() {
+        let mut first = WorldRng::new(WorldSeed(42));
+        let mut second = WorldRng::new(WorldSeed(42));
+
+        // Drawing from one stream shouldn't affect another.
+        first.stream(RngStream::Events).gen::<u64>();
+
+        let value = first.stream(RngStream::Collectables).gen::<u64>();
+        assert_eq!(value, second.stream(RngStream::Collectables).gen::<u64>());
+        assert_ne!(value, second.stream(RngStream::Ai).gen::<u64>());
+    }
+}
@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+
+use super::WorldSaved;
+
+/// Notifies registered [`SaveHook`]s about each [`WorldSaved`].
+///
+/// Does nothing if no hooks are registered via [`AppSaveHookExt::add_save_hook`].
+pub(super) struct SaveHooksPlugin;
+
+impl Plugin for SaveHooksPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SaveHooks>().add_systems(
+            Last,
+            Self::notify_hooks
+                .run_if(on_event::<WorldSaved>())
+                .run_if(|hooks: Res<SaveHooks>| !hooks.is_empty()),
+        );
+    }
+}
+
+impl SaveHooksPlugin {
+    fn notify_hooks(mut saved_events: EventReader<WorldSaved>, mut hooks: ResMut<SaveHooks>) {
+        for event in saved_events.read() {
+            debug!(
+                "notifying {} save hooks about {:?}",
+                hooks.len(),
+                event.path
+            );
+            for hook in hooks.iter_mut() {
+                hook.world_saved(event);
+            }
+        }
+    }
+}
+
+/// External integration that reacts to saves, like a cloud backup or a chat webhook.
+///
+/// Called on the main thread, so long operations like uploads should be spawned
+/// on [`IoTaskPool`](bevy::tasks::IoTaskPool).
+pub trait SaveHook: Send + Sync + 'static {
+    /// Called after the world was successfully written to disk.
+    fn world_saved(&mut self, event: &WorldSaved);
+}
+
+pub trait AppSaveHookExt {
+    /// Registers a hook that will be notified about saves.
+    fn add_save_hook(&mut self, hook: impl SaveHook) -> &mut Self;
+}
+
+impl AppSaveHookExt for App {
+    fn add_save_hook(&mut self, hook: impl SaveHook) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(SaveHooks::default)
+            .push(Box::new(hook));
+        self
+    }
+}
+
+#[derive(Resource, Default, Deref, DerefMut)]
+struct SaveHooks(Vec<Box<dyn SaveHook>>);
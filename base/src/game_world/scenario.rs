@@ -0,0 +1,315 @@
+use std::time::Duration;
+
+use bevy::{
+    asset::AssetPath, ecs::system::SystemParam, prelude::*, time::common_conditions::on_timer,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    city::{generation, statistics::CityStatistics, ActiveCity, CityBundle, CityMode},
+    clock::GameClock,
+    WorldState,
+};
+use crate::{
+    asset::info::scenario_info::{ScenarioCondition, ScenarioFeature, ScenarioInfo},
+    core::{
+        transition::{Transition, TransitionRequest},
+        GameState,
+    },
+    network::validation::ClientViolation,
+};
+
+/// Starts scenarios and checks their conditions.
+pub(super) struct ScenarioPlugin;
+
+impl Plugin for ScenarioPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Scenario>()
+            .replicate::<Scenario>()
+            .add_systems(
+                OnEnter(GameState::InGame),
+                Self::start.run_if(resource_exists::<ScenarioStart>),
+            )
+            .add_systems(
+                PreUpdate,
+                Self::init
+                    .after(ClientSet::Receive)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                Update,
+                Self::evaluate
+                    .run_if(on_timer(Duration::from_secs(1)))
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+impl ScenarioPlugin {
+    /// Generates the starting city and opens it.
+    fn start(
+        mut commands: Commands,
+        mut transition_events: EventWriter<TransitionRequest>,
+        asset_server: Res<AssetServer>,
+        scenarios_info: Res<Assets<ScenarioInfo>>,
+        start: Res<ScenarioStart>,
+    ) {
+        commands.remove_resource::<ScenarioStart>();
+        let Some(info) = asset_server
+            .get_handle(&start.0)
+            .and_then(|handle| scenarios_info.get(&handle))
+        else {
+            error!("scenario {:?} is not loaded", start.0);
+            return;
+        };
+
+        info!("starting scenario {:?}", start.0);
+        commands.spawn((
+            Name::new("Scenario"),
+            Scenario {
+                info_path: start.0.clone(),
+                budget: info.budget,
+                locked: info.locked.clone(),
+                outcome: None,
+            },
+            Replicated,
+        ));
+
+        // Spawned directly because generation requests can be locked by the scenario.
+        commands
+            .spawn((CityBundle::new(info.city_name.clone()), ActiveCity))
+            .with_children(|parent| generation::spawn_layout(parent, info.generation));
+        transition_events.send(TransitionRequest::new(Transition::World(WorldState::City)));
+    }
+
+    fn init(
+        mut commands: Commands,
+        scenarios: Query<Entity, (With<Scenario>, Without<StateScoped<GameState>>)>,
+    ) {
+        for entity in &scenarios {
+            commands
+                .entity(entity)
+                .insert(StateScoped(GameState::InGame));
+        }
+    }
+
+    fn evaluate(
+        asset_server: Res<AssetServer>,
+        scenarios_info: Res<Assets<ScenarioInfo>>,
+        clocks: Query<&GameClock>,
+        cities: Query<&CityStatistics>,
+        mut scenarios: Query<&mut Scenario>,
+    ) {
+        let Ok(mut scenario) = scenarios.get_single_mut() else {
+            return;
+        };
+        if scenario.outcome.is_some() {
+            return;
+        }
+        let Some(info) = asset_server
+            .get_handle(&scenario.info_path)
+            .and_then(|handle| scenarios_info.get(&handle))
+        else {
+            return;
+        };
+
+        let mut progress = ScenarioProgress {
+            day: clocks.get_single().map(GameClock::day).unwrap_or_default(),
+            ..Default::default()
+        };
+        for statistics in &cities {
+            progress.population += statistics.population;
+            progress.wealth += statistics.wealth;
+            progress.residential_lots += statistics.residential_lots;
+            progress.community_lots += statistics.community_lots;
+        }
+
+        if let Some(outcome) = progress.outcome(&info.goals, &info.failures) {
+            info!(
+                "ending scenario {:?} with `{outcome:?}`",
+                scenario.info_path
+            );
+            scenario.outcome = Some(outcome);
+        }
+    }
+}
+
+/// Starts the scenario from the path after entering [`GameState::InGame`].
+///
+/// Should be inserted together with [`WorldName`](super::WorldName).
+#[derive(Resource)]
+pub struct ScenarioStart(pub AssetPath<'static>);
+
+/// Active scenario of the world.
+///
+/// Values from [`ScenarioInfo`] that affect the gameplay are copied
+/// to keep saves consistent after metadata changes.
+#[derive(Component, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct Scenario {
+    info_path: AssetPath<'static>,
+    budget: u32,
+    locked: Vec<ScenarioFeature>,
+    outcome: Option<ScenarioOutcome>,
+}
+
+impl Scenario {
+    pub fn info_path(&self) -> &AssetPath<'static> {
+        &self.info_path
+    }
+
+    /// Returns the budget for newly created families.
+    pub fn budget(&self) -> u32 {
+        self.budget
+    }
+
+    pub fn outcome(&self) -> Option<ScenarioOutcome> {
+        self.outcome
+    }
+
+    pub fn is_locked(&self, feature: ScenarioFeature) -> bool {
+        self.locked.contains(&feature)
+    }
+
+    pub fn is_city_mode_locked(&self, mode: CityMode) -> bool {
+        self.locked
+            .iter()
+            .any(|feature| feature.city_mode() == Some(mode))
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_locked(locked: Vec<ScenarioFeature>) -> Self {
+        Self {
+            info_path: Default::default(),
+            budget: 0,
+            locked,
+            outcome: None,
+        }
+    }
+}
+
+/// Checks features locked by the active [`Scenario`].
+///
+/// Used by the server to reject requests that the UI doesn't allow.
+#[derive(SystemParam)]
+pub(crate) struct ScenarioLocks<'w, 's> {
+    scenarios: Query<'w, 's, &'static Scenario>,
+}
+
+impl ScenarioLocks<'_, '_> {
+    /// Returns a violation if the client tries to use a locked feature.
+    pub(crate) fn check(
+        &self,
+        feature: ScenarioFeature,
+        client_id: ClientId,
+    ) -> Result<(), ClientViolation> {
+        let locked = self
+            .scenarios
+            .get_single()
+            .is_ok_and(|scenario| scenario.is_locked(feature));
+        if !locked {
+            return Ok(());
+        }
+
+        error!("`{client_id:?}` tries to use `{feature:?}` while it's locked by the scenario");
+        Err(ClientViolation {
+            client_id,
+            reason: format!("using `{feature:?}` locked by the scenario"),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Reflect, Serialize)]
+pub enum ScenarioOutcome {
+    Won,
+    Lost,
+}
+
+/// Aggregated world values to check [`ScenarioCondition`]s.
+#[derive(Clone, Copy, Default)]
+struct ScenarioProgress {
+    population: u32,
+    wealth: u32,
+    residential_lots: u32,
+    community_lots: u32,
+    day: u32,
+}
+
+impl ScenarioProgress {
+    fn is_met(&self, condition: ScenarioCondition) -> bool {
+        match condition {
+            ScenarioCondition::Population(value) => self.population >= value,
+            ScenarioCondition::Wealth(value) => self.wealth >= value,
+            ScenarioCondition::ResidentialLots(value) => self.residential_lots >= value,
+            ScenarioCondition::CommunityLots(value) => self.community_lots >= value,
+            ScenarioCondition::Day(value) => self.day >= value,
+            ScenarioCondition::Bankrupt => self.population > 0 && self.wealth == 0,
+        }
+    }
+
+    /// Returns the outcome if the scenario should end.
+    ///
+    /// Failures are checked first, scenarios without goals can only be lost.
+    fn outcome(
+        &self,
+        goals: &[ScenarioCondition],
+        failures: &[ScenarioCondition],
+    ) -> Option<ScenarioOutcome> {
+        if failures.iter().any(|&condition| self.is_met(condition)) {
+            Some(ScenarioOutcome::Lost)
+        } else if !goals.is_empty() && goals.iter().all(|&condition| self.is_met(condition)) {
+            Some(ScenarioOutcome::Won)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outcome() {
+        let goals = [
+            ScenarioCondition::Population(4),
+            ScenarioCondition::Wealth(1000),
+        ];
+        let failures = [ScenarioCondition::Day(10), ScenarioCondition::Bankrupt];
+
+        let mut progress = ScenarioProgress {
+            population: 4,
+            wealth: 500,
+            day: 2,
+            ..Default::default()
+        };
+        assert_eq!(progress.outcome(&goals, &failures), None);
+
+        progress.wealth = 1000;
+        assert_eq!(
+            progress.outcome(&goals, &failures),
+            Some(ScenarioOutcome::Won)
+        );
+
+        progress.day = 10;
+        assert_eq!(
+            progress.outcome(&goals, &failures),
+            Some(ScenarioOutcome::Lost),
+            "failures should take priority"
+        );
+
+        progress.day = 2;
+        progress.wealth = 0;
+        assert_eq!(
+            progress.outcome(&goals, &failures),
+            Some(ScenarioOutcome::Lost)
+        );
+        assert_eq!(
+            progress.outcome(&[], &[]),
+            None,
+            "scenario without goals can't be won"
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use std::{any::TypeId, f32::consts::TAU};
+
+use bevy::{
+    asset::AssetPath, prelude::*, render::mesh::VertexAttributeValues, ui::IsDefaultUiCamera,
+};
+use bevy_atmosphere::prelude::*;
+
+use super::{
+    object::Object,
+    player_camera::{EnvironmentMap, PlayerCameraBundle},
+};
+use crate::{
+    asset::{collection::Collection, info::object_info::ObjectInfo},
+    core::GameState,
+};
+
+/// Lightweight world preview for menu backgrounds.
+///
+/// Loads only visual part of objects from a scene without replication, physics and simulation.
+pub(super) struct ShowcasePlugin;
+
+impl Plugin for ShowcasePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                Self::init,
+                Self::spawn_objects,
+                Self::init_objects,
+                Self::orbit,
+            )
+                .run_if(in_state(GameState::Menu)),
+        );
+    }
+}
+
+/// Ground plane side size.
+const GROUND_SIZE: f32 = 40.0;
+
+impl ShowcasePlugin {
+    fn init(
+        mut commands: Commands,
+        mut meshes: ResMut<Assets<Mesh>>,
+        asset_server: Res<AssetServer>,
+        environment_map: Res<Collection<EnvironmentMap>>,
+        showcases: Query<Entity, Added<Showcase>>,
+    ) {
+        for entity in &showcases {
+            debug!("initializing showcase `{entity}`");
+
+            let mut ground_mesh = Plane3d::default()
+                .mesh()
+                .size(GROUND_SIZE, GROUND_SIZE)
+                .build();
+            if let Some(VertexAttributeValues::Float32x2(uvs)) =
+                ground_mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0)
+            {
+                // Adjust UVs to tile the texture properly.
+                for value in uvs.iter_mut().flatten() {
+                    *value *= GROUND_SIZE;
+                }
+            }
+
+            commands.entity(entity).with_children(|parent| {
+                parent.spawn(PbrBundle {
+                    mesh: meshes.add(ground_mesh),
+                    material: asset_server.load("base/ground/spring_grass/spring_glass.ron"),
+                    ..Default::default()
+                });
+                parent.spawn(DirectionalLightBundle {
+                    directional_light: DirectionalLight {
+                        shadows_enabled: true,
+                        ..Default::default()
+                    },
+                    transform: Transform::from_xyz(4.0, 7.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+                    ..Default::default()
+                });
+                parent.spawn((
+                    ShowcaseCamera::default(),
+                    IsDefaultUiCamera,
+                    AtmosphereCamera::default(),
+                    // Render after UI camera to draw on top of its clear color.
+                    PlayerCameraBundle::new(&environment_map).with_order(1),
+                ));
+            });
+        }
+    }
+
+    fn spawn_objects(
+        mut commands: Commands,
+        scenes: Res<Assets<DynamicScene>>,
+        showcases: Query<
+            (Entity, &Handle<DynamicScene>),
+            (With<Showcase>, Without<ShowcaseLoaded>),
+        >,
+    ) {
+        for (entity, scene_handle) in &showcases {
+            let Some(scene) = scenes.get(scene_handle) else {
+                continue;
+            };
+
+            debug!("spawning objects for showcase `{entity}`");
+            commands
+                .entity(entity)
+                .insert(ShowcaseLoaded)
+                .with_children(|parent| {
+                    for scene_entity in &scene.entities {
+                        let mut object = None;
+                        let mut transform = Transform::default();
+                        for component in &scene_entity.components {
+                            let Some(type_info) = component.get_represented_type_info() else {
+                                continue;
+                            };
+
+                            if type_info.type_id() == TypeId::of::<Object>() {
+                                object = Object::from_reflect(&**component);
+                            } else if type_info.type_id() == TypeId::of::<Transform>() {
+                                transform = Transform::from_reflect(&**component)
+                                    .expect("transform should be reflectable");
+                            }
+                        }
+
+                        if let Some(object) = object {
+                            parent.spawn((
+                                ShowcaseObject(object.0),
+                                SpatialBundle::from_transform(transform),
+                            ));
+                        }
+                    }
+                });
+        }
+    }
+
+    fn init_objects(
+        mut commands: Commands,
+        asset_server: Res<AssetServer>,
+        objects_info: Res<Assets<ObjectInfo>>,
+        objects: Query<(Entity, &ShowcaseObject), Without<Handle<Scene>>>,
+    ) {
+        for (entity, object) in &objects {
+            // Info could be not loaded yet because the menu is shown right after startup.
+            let Some(info) = asset_server
+                .get_handle(&object.0)
+                .and_then(|info_handle| objects_info.get(&info_handle))
+            else {
+                continue;
+            };
+
+            debug!("initializing showcase object '{}' for `{entity}`", object.0);
+            let scene_handle: Handle<Scene> = asset_server.load(info.scene.clone());
+            commands.entity(entity).insert(scene_handle);
+        }
+    }
+
+    fn orbit(time: Res<Time>, mut cameras: Query<(&mut Transform, &mut ShowcaseCamera)>) {
+        for (mut transform, mut camera) in &mut cameras {
+            const SPEED: f32 = 0.05;
+            camera.angle = (camera.angle + SPEED * time.delta_seconds()) % TAU;
+
+            let (sin, cos) = camera.angle.sin_cos();
+            transform.translation =
+                Vec3::new(sin, 0.0, cos) * camera.distance + Vec3::Y * camera.height;
+            transform.look_at(Vec3::ZERO, Vec3::Y);
+        }
+    }
+}
+
+#[derive(Bundle)]
+pub struct ShowcaseBundle {
+    showcase: Showcase,
+    scene_handle: Handle<DynamicScene>,
+    spatial_bundle: SpatialBundle,
+}
+
+impl ShowcaseBundle {
+    /// Creates a showcase from a scene in the same format as saved worlds.
+    pub fn new(scene_handle: Handle<DynamicScene>) -> Self {
+        Self {
+            showcase: Showcase,
+            scene_handle,
+            spatial_bundle: Default::default(),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Showcase;
+
+/// Marks showcase with spawned objects.
+#[derive(Component)]
+struct ShowcaseLoaded;
+
+/// Contains path to the object info.
+#[derive(Component)]
+struct ShowcaseObject(AssetPath<'static>);
+
+/// Slowly rotates around the showcase origin.
+#[derive(Component)]
+struct ShowcaseCamera {
+    angle: f32,
+    distance: f32,
+    height: f32,
+}
+
+impl Default for ShowcaseCamera {
+    fn default() -> Self {
+        Self {
+            angle: 0.0,
+            distance: 15.0,
+            height: 6.0,
+        }
+    }
+}
@@ -0,0 +1,191 @@
+mod network_transform;
+
+use bevy::{prelude::*, transform::TransformSystem};
+use bevy_replicon::prelude::*;
+
+use crate::network::ServerTickRate;
+use network_transform::NetworkTransformPlugin;
+
+/// Runs gameplay simulation at a fixed rate and smooths visuals between steps.
+///
+/// Time-dependent gameplay systems are added to [`FixedUpdate`],
+/// where [`Time`] advances by a constant delta independent of the frame rate.
+///
+/// On clients smooths replicated transforms between server ticks.
+pub(super) struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(NetworkTransformPlugin)
+            .insert_resource(Time::<Fixed>::from_hz(TICK_RATE.into()))
+            .add_systems(FixedFirst, Self::restore_transforms)
+            .add_systems(FixedLast, Self::store_transforms)
+            .add_systems(
+                PreUpdate,
+                (Self::init_smoothing, Self::receive_transforms)
+                    .chain()
+                    .after(ClientSet::Receive)
+                    .run_if(client_connected),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    Self::interpolate_transforms,
+                    Self::smooth_transforms.run_if(client_connected),
+                )
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// Simulation steps per second.
+///
+/// Matches the server tick rate to replicate the result of each step.
+pub const TICK_RATE: u16 = 30;
+
+/// Longest interval in seconds between transform updates that is used for smoothing.
+const MAX_SMOOTHING_INTERVAL: f32 = 0.5;
+
+impl SimulationPlugin {
+    /// Returns transforms to the simulated state before a step.
+    fn restore_transforms(mut entities: Query<(&mut Transform, &InterpolatedTransform)>) {
+        for (mut transform, interpolated) in &mut entities {
+            // Changes outside of the simulation, like teleportation, take priority.
+            if *transform == interpolated.rendered {
+                *transform = interpolated.current;
+            }
+        }
+    }
+
+    fn store_transforms(mut entities: Query<(&Transform, &mut InterpolatedTransform)>) {
+        for (&transform, mut interpolated) in &mut entities {
+            interpolated.previous = interpolated.current;
+            interpolated.current = transform;
+        }
+    }
+
+    /// Blends between the last two simulation steps based on the time left until the next one.
+    fn interpolate_transforms(
+        time: Res<Time<Fixed>>,
+        mut entities: Query<(&mut Transform, &mut InterpolatedTransform)>,
+    ) {
+        let overstep = time.overstep_fraction();
+        for (mut transform, mut interpolated) in &mut entities {
+            interpolated.rendered = lerp(interpolated.previous, interpolated.current, overstep);
+            transform.set_if_neq(interpolated.rendered);
+        }
+    }
+
+    fn init_smoothing(
+        mut commands: Commands,
+        tick_rate: Res<ServerTickRate>,
+        entities: Query<(Entity, &Transform), (With<Replicated>, Without<SmoothedTransform>)>,
+    ) {
+        let interval = 1.0 / **tick_rate as f32;
+        for (entity, &transform) in &entities {
+            commands
+                .entity(entity)
+                .insert(SmoothedTransform::new(transform, interval));
+        }
+    }
+
+    /// Starts smoothing towards a newly received transform from the currently displayed one.
+    ///
+    /// Updates may arrive less often than server ticks, so each one is spread over the measured
+    /// interval since the previous update.
+    fn receive_transforms(
+        tick_rate: Res<ServerTickRate>,
+        mut entities: Query<(&mut Transform, &mut SmoothedTransform), Changed<Transform>>,
+    ) {
+        let min_interval = 1.0 / **tick_rate as f32;
+        for (mut transform, mut smoothed) in &mut entities {
+            // Skip changes made by smoothing itself.
+            if *transform != smoothed.rendered {
+                // A long pause means the entity was idle, keep the previous interval.
+                if smoothed.since_update <= MAX_SMOOTHING_INTERVAL {
+                    smoothed.interval = smoothed.since_update.max(min_interval);
+                }
+                smoothed.since_update = 0.0;
+                smoothed.start = smoothed.rendered;
+                smoothed.target = *transform;
+                smoothed.elapsed = 0.0;
+                transform.set_if_neq(smoothed.start);
+            }
+        }
+    }
+
+    /// Moves transforms towards their targets, reaching them by the expected next update.
+    fn smooth_transforms(
+        time: Res<Time>,
+        mut entities: Query<(&mut Transform, &mut SmoothedTransform)>,
+    ) {
+        for (mut transform, mut smoothed) in &mut entities {
+            smoothed.since_update += time.delta_seconds();
+            if smoothed.rendered == smoothed.target {
+                continue;
+            }
+
+            smoothed.elapsed += time.delta_seconds();
+            let fraction = (smoothed.elapsed / smoothed.interval).min(1.0);
+            smoothed.rendered = lerp(smoothed.start, smoothed.target, fraction);
+            transform.set_if_neq(smoothed.rendered);
+        }
+    }
+}
+
+fn lerp(from: Transform, to: Transform, s: f32) -> Transform {
+    Transform {
+        translation: from.translation.lerp(to.translation, s),
+        rotation: from.rotation.slerp(to.rotation, s),
+        scale: from.scale.lerp(to.scale, s),
+    }
+}
+
+/// Smooths [`Transform`] changes made in [`FixedUpdate`].
+///
+/// Outside of the simulation schedule the transform contains an interpolated value.
+#[derive(Component)]
+pub(crate) struct InterpolatedTransform {
+    previous: Transform,
+    current: Transform,
+    rendered: Transform,
+}
+
+impl InterpolatedTransform {
+    pub(crate) fn new(transform: Transform) -> Self {
+        Self {
+            previous: transform,
+            current: transform,
+            rendered: transform,
+        }
+    }
+}
+
+/// Smooths replicated [`Transform`] changes on clients.
+///
+/// Each update is spread over the interval between the last two received updates,
+/// which is at least the server tick duration from [`ServerTickRate`].
+#[derive(Component)]
+struct SmoothedTransform {
+    start: Transform,
+    target: Transform,
+    rendered: Transform,
+    elapsed: f32,
+    /// Seconds since the last received update.
+    since_update: f32,
+    /// Seconds over which the current update is smoothed.
+    interval: f32,
+}
+
+impl SmoothedTransform {
+    fn new(transform: Transform, interval: f32) -> Self {
+        Self {
+            start: transform,
+            target: transform,
+            rendered: transform,
+            elapsed: 0.0,
+            since_update: 0.0,
+            interval,
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use std::{f32::consts::SQRT_2, time::Duration};
+
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::SimulationPlugin;
+use crate::{
+    game_world::actor::Actor,
+    settings::{ServerSettings, Settings},
+};
+
+/// Replicates actor transforms with reduced precision and rate.
+///
+/// Instead of sending [`Transform`] on every change, the server updates [`NetworkTransform`]
+/// at most [`ServerSettings::transform_rate`] times per second and only if the actor moved
+/// or turned more than the configured thresholds.
+pub(super) struct NetworkTransformPlugin;
+
+impl Plugin for NetworkTransformPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SendTimer>()
+            .replicate::<NetworkTransform>()
+            .add_systems(
+                PreUpdate,
+                Self::apply
+                    .after(ClientSet::Receive)
+                    .before(SimulationPlugin::init_smoothing)
+                    .run_if(client_connected),
+            )
+            .add_systems(
+                PostUpdate,
+                (Self::init, Self::update)
+                    .before(ServerSet::Send)
+                    .run_if(server_running),
+            );
+    }
+}
+
+/// Scale to convert meters into sent units.
+const POSITION_SCALE: f32 = 100.0;
+
+impl NetworkTransformPlugin {
+    fn init(
+        mut commands: Commands,
+        actors: Query<(Entity, &Transform), (With<Actor>, Without<NetworkTransform>)>,
+    ) {
+        for (entity, transform) in &actors {
+            commands
+                .entity(entity)
+                .insert(NetworkTransform::new(transform));
+        }
+    }
+
+    fn update(
+        time: Res<Time<Real>>,
+        settings: Res<Settings>,
+        mut timer: ResMut<SendTimer>,
+        mut actors: Query<(&Transform, &mut NetworkTransform)>,
+    ) {
+        let server_settings = if settings.server.validate().is_ok() {
+            settings.server.clone()
+        } else {
+            ServerSettings::default()
+        };
+
+        let duration = Duration::from_secs_f32(1.0 / server_settings.transform_rate as f32);
+        if timer.duration() != duration {
+            timer.set_duration(duration);
+        }
+        if !timer.tick(time.delta()).just_finished() {
+            return;
+        }
+
+        let rotation_threshold = server_settings.rotation_threshold.to_radians();
+        for (transform, mut network_transform) in &mut actors {
+            let distance = transform
+                .translation
+                .distance(network_transform.translation());
+            let angle = transform
+                .rotation
+                .angle_between(network_transform.rotation());
+            if distance > server_settings.position_threshold || angle > rotation_threshold {
+                network_transform.set_if_neq(NetworkTransform::new(transform));
+            }
+        }
+    }
+
+    /// Writes received values into [`Transform`], which is smoothed afterward.
+    fn apply(
+        mut commands: Commands,
+        mut actors: Query<
+            (Entity, &NetworkTransform, Option<&mut Transform>),
+            Changed<NetworkTransform>,
+        >,
+    ) {
+        for (entity, network_transform, transform) in &mut actors {
+            let translation = network_transform.translation();
+            let rotation = network_transform.rotation();
+            if let Some(mut transform) = transform {
+                transform.translation = translation;
+                transform.rotation = rotation;
+            } else {
+                commands
+                    .entity(entity)
+                    .insert(Transform::from_translation(translation).with_rotation(rotation));
+            }
+        }
+    }
+}
+
+/// Quantized actor position and rotation sent to clients.
+///
+/// Position is stored in centimeters and rotation is compressed into 32 bits.
+/// Scale is not replicated.
+#[derive(Clone, Component, Copy, Deserialize, PartialEq, Serialize)]
+struct NetworkTransform {
+    translation: IVec3,
+    rotation: u32,
+}
+
+impl NetworkTransform {
+    fn new(transform: &Transform) -> Self {
+        Self {
+            translation: (transform.translation * POSITION_SCALE).round().as_ivec3(),
+            rotation: compress_rotation(transform.rotation),
+        }
+    }
+
+    fn translation(&self) -> Vec3 {
+        self.translation.as_vec3() / POSITION_SCALE
+    }
+
+    fn rotation(&self) -> Quat {
+        decompress_rotation(self.rotation)
+    }
+}
+
+/// Number of bits for each of the three smallest quaternion components.
+const ROTATION_BITS: u32 = 10;
+
+const ROTATION_MASK: u32 = (1 << ROTATION_BITS) - 1;
+
+/// Encodes rotation using the "smallest three" technique.
+///
+/// The largest component is dropped and restored from the others
+/// since the quaternion is normalized. The remaining components are in `-1/√2..=1/√2` range
+/// and stored with [`ROTATION_BITS`] precision, while the 2 highest bits store
+/// the index of the dropped component.
+fn compress_rotation(rotation: Quat) -> u32 {
+    let components = rotation.normalize().to_array();
+    let largest = (0..components.len())
+        .max_by(|&a, &b| components[a].abs().total_cmp(&components[b].abs()))
+        .unwrap();
+    // `q` and `-q` represent the same rotation, so the dropped component is always positive.
+    let sign = if components[largest] < 0.0 { -1.0 } else { 1.0 };
+
+    let mut packed = largest as u32;
+    for (index, &value) in components.iter().enumerate() {
+        if index != largest {
+            let normalized = ((value * sign * SQRT_2 + 1.0) / 2.0).clamp(0.0, 1.0);
+            let quantized = (normalized * ROTATION_MASK as f32).round() as u32;
+            packed = (packed << ROTATION_BITS) | quantized;
+        }
+    }
+
+    packed
+}
+
+fn decompress_rotation(packed: u32) -> Quat {
+    let largest = (packed >> (3 * ROTATION_BITS)) as usize;
+    let mut components = [0.0; 4];
+    let mut shift = 3 * ROTATION_BITS;
+    for (index, component) in components.iter_mut().enumerate() {
+        if index != largest {
+            shift -= ROTATION_BITS;
+            let quantized = (packed >> shift) & ROTATION_MASK;
+            let normalized = quantized as f32 / ROTATION_MASK as f32;
+            *component = (normalized * 2.0 - 1.0) / SQRT_2;
+        }
+    }
+
+    let sum: f32 = components.iter().map(|value| value * value).sum();
+    components[largest] = (1.0 - sum).max(0.0).sqrt();
+
+    Quat::from_array(components).normalize()
+}
+
+/// Limits how often [`NetworkTransform`] is updated.
+#[derive(Resource, Deref, DerefMut)]
+struct SendTimer(Timer);
+
+impl Default for SendTimer {
+    fn default() -> Self {
+        let rate = ServerSettings::default().transform_rate;
+        Self(Timer::from_seconds(1.0 / rate as f32, TimerMode::Repeating))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotation_compression() {
+        let rotations = [
+            Quat::IDENTITY,
+            Quat::from_rotation_y(1.0),
+            Quat::from_rotation_y(-2.5),
+            Quat::from_rotation_x(0.3) * Quat::from_rotation_z(-1.2),
+            Quat::from_euler(EulerRot::YXZ, 3.0, -0.7, 1.9),
+        ];
+        for rotation in rotations {
+            let decompressed = decompress_rotation(compress_rotation(rotation));
+            let angle = rotation.angle_between(decompressed);
+            assert!(
+                angle < 0.5_f32.to_radians(),
+                "{rotation} should be preserved, but got {decompressed}"
+            );
+        }
+    }
+
+    #[test]
+    fn position_quantization() {
+        let transform = Transform::from_xyz(1.234, -0.005, 250.0);
+        let network_transform = NetworkTransform::new(&transform);
+        assert!(network_transform
+            .translation()
+            .abs_diff_eq(transform.translation, 0.005));
+    }
+}
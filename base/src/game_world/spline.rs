@@ -1,3 +1,4 @@
+mod connections_debug;
 pub(crate) mod dynamic_mesh;
 
 use std::{f32::consts::PI, mem};
@@ -14,17 +15,25 @@ use itertools::{Itertools, MinMaxResult};
 use serde::{Deserialize, Serialize};
 
 use crate::{core::GameState, math::segment::Segment};
+use connections_debug::ConnectionsDebugPlugin;
+use dynamic_mesh::MeshTask;
 
 pub(super) struct SplinePlugin;
 
 impl Plugin for SplinePlugin {
     fn build(&self, app: &mut App) {
-        app.register_type::<SplineSegment>()
+        app.add_plugins(ConnectionsDebugPlugin)
+            .register_type::<SplineSegment>()
             .replicate::<SplineSegment>()
             .observe(Self::cleanup_connections)
             .add_systems(
                 PostUpdate,
-                Self::update_connections.run_if(in_state(GameState::InGame)),
+                (
+                    Self::update_connections,
+                    Self::update_point_connections,
+                    Self::apply_meshes,
+                )
+                    .run_if(in_state(GameState::InGame)),
             );
     }
 }
@@ -71,15 +80,7 @@ impl SplinePlugin {
                         continue;
                     }
 
-                    let kind = if segment.start == other_segment.start {
-                        (PointKind::Start, PointKind::Start)
-                    } else if segment.start == other_segment.end {
-                        (PointKind::Start, PointKind::End)
-                    } else if segment.end == other_segment.end {
-                        (PointKind::End, PointKind::End)
-                    } else if segment.end == other_segment.start {
-                        (PointKind::End, PointKind::Start)
-                    } else {
+                    let Some(kind) = connection_kind(*segment, *other_segment) else {
                         continue;
                     };
 
@@ -105,6 +106,65 @@ impl SplinePlugin {
         }
     }
 
+    /// Updates [`PointConnections`] between segments and [`SplinePoint`]s.
+    pub(super) fn update_point_connections(
+        mut removed_points: RemovedComponents<SplinePoint>,
+        mut segments: Query<(&Parent, &SplineSegment, &mut PointConnections)>,
+        changed_segments: Query<(), Changed<SplineSegment>>,
+        changed_points: Query<
+            (),
+            (
+                With<SplinePoint>,
+                Or<(Changed<Transform>, Changed<Visibility>)>,
+            ),
+        >,
+        points: Query<(&Parent, &Visibility, &Transform, &SplinePoint)>,
+    ) {
+        if removed_points.read().count() == 0
+            && changed_segments.is_empty()
+            && changed_points.is_empty()
+        {
+            return;
+        }
+
+        for (parent, &segment, mut connections) in &mut segments {
+            let mut new_connections = PointConnections::default();
+            for (point_parent, visibility, transform, point) in &points {
+                if point_parent != parent || visibility == Visibility::Hidden {
+                    continue;
+                }
+
+                let center = transform.translation.xz();
+                for (kind, segment_point) in [
+                    (PointKind::Start, segment.start),
+                    (PointKind::End, segment.end),
+                ] {
+                    if segment_point.distance(center) <= point.radius
+                        && !new_connections.contains(&kind)
+                    {
+                        new_connections.0.push(kind);
+                    }
+                }
+            }
+
+            if connections.set_if_neq(new_connections) {
+                trace!("updating point connections to `{:?}`", connections.0);
+            }
+        }
+    }
+
+    /// Applies meshes from finished [`MeshTask`]s.
+    fn apply_meshes(
+        mut meshes: ResMut<Assets<Mesh>>,
+        mut segments: Query<(Entity, &Handle<Mesh>, &mut MeshTask)>,
+    ) {
+        for (entity, mesh_handle, mut mesh_task) in &mut segments {
+            if mesh_task.apply_finished(&mut meshes, mesh_handle) {
+                trace!("applying generated mesh for `{entity}`");
+            }
+        }
+    }
+
     fn cleanup_connections(
         trigger: Trigger<OnRemove, SplineSegment>,
         mut entities_buffer: Local<Vec<Entity>>,
@@ -126,16 +186,36 @@ impl SplinePlugin {
     }
 }
 
+/// Returns connected points of two segments.
+fn connection_kind(segment: Segment, other_segment: Segment) -> Option<(PointKind, PointKind)> {
+    if segment.start == other_segment.start {
+        Some((PointKind::Start, PointKind::Start))
+    } else if segment.start == other_segment.end {
+        Some((PointKind::Start, PointKind::End))
+    } else if segment.end == other_segment.end {
+        Some((PointKind::End, PointKind::End))
+    } else if segment.end == other_segment.start {
+        Some((PointKind::End, PointKind::Start))
+    } else {
+        None
+    }
+}
+
 #[derive(Clone, Deref, DerefMut, Copy, Default, Deserialize, Reflect, Serialize)]
 #[reflect(Component)]
 pub(crate) struct SplineSegment(pub(super) Segment);
 
 impl SplineSegment {
+    /// Inserts [`MeshTask`] only once to avoid cancelling a running generation on segment updates.
+    fn on_add(mut world: DeferredWorld, entity: Entity, _component_id: ComponentId) {
+        world.commands().entity(entity).insert(MeshTask::default());
+    }
+
     fn on_insert(mut world: DeferredWorld, entity: Entity, _component_id: ComponentId) {
         world
             .commands()
             .entity(entity)
-            .insert(SplineConnections::default());
+            .insert((SplineConnections::default(), PointConnections::default()));
     }
 }
 
@@ -143,13 +223,13 @@ impl Component for SplineSegment {
     const STORAGE_TYPE: StorageType = StorageType::Table;
 
     fn register_component_hooks(hooks: &mut ComponentHooks) {
-        hooks.on_insert(Self::on_insert);
+        hooks.on_add(Self::on_add).on_insert(Self::on_insert);
     }
 }
 
 /// Dynamically updated component with precalculated connected entities for each segment point.
-#[derive(Component, Default, Deref)]
-pub(crate) struct SplineConnections(Vec<SplineConnection>);
+#[derive(Clone, Component, Default, Deref)]
+pub(crate) struct SplineConnections(pub(super) Vec<SplineConnection>);
 
 impl SplineConnections {
     /// Returns the segments with the maximum and minimum angle relative
@@ -183,14 +263,87 @@ impl SplineConnections {
     }
 }
 
+#[derive(Clone)]
 pub(crate) struct SplineConnection {
-    entity: Entity,
-    segment: Segment,
-    kind: (PointKind, PointKind),
+    pub(super) entity: Entity,
+    pub(super) segment: Segment,
+    pub(super) kind: (PointKind, PointKind),
 }
 
+/// Entity that acts as a point in the connection graph, like a pillar.
+///
+/// Segment points within the radius are connected to it.
+#[derive(Component, Clone, Copy)]
+pub(crate) struct SplinePoint {
+    pub(crate) radius: f32,
+}
+
+/// Dynamically updated component with segment points connected to [`SplinePoint`]s.
+#[derive(Clone, Component, Default, Deref, PartialEq)]
+pub(crate) struct PointConnections(Vec<PointKind>);
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
 pub enum PointKind {
     Start,
     End,
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    /// Generates random segments between points of a small grid with connections between them.
+    ///
+    /// Points are sometimes slightly moved to make nearly parallel segments common.
+    /// Zero-length segments are included, but excluded from connections like in [`SplinePlugin::update_connections`].
+    pub(crate) fn random_graph(rng: &mut impl Rng) -> Vec<(SplineSegment, SplineConnections)> {
+        const GRID_SIZE: i32 = 3;
+        let points: Vec<_> = (0..rng.gen_range(2..8))
+            .map(|_| {
+                let point = Vec2::new(
+                    rng.gen_range(-GRID_SIZE..=GRID_SIZE) as f32,
+                    rng.gen_range(-GRID_SIZE..=GRID_SIZE) as f32,
+                );
+                if rng.gen_bool(0.2) {
+                    point + Vec2::new(rng.gen_range(-1e-3..1e-3), rng.gen_range(-1e-3..1e-3))
+                } else {
+                    point
+                }
+            })
+            .collect();
+
+        let segments: Vec<_> = (0..rng.gen_range(1..10))
+            .map(|_| {
+                let start = points[rng.gen_range(0..points.len())];
+                let end = points[rng.gen_range(0..points.len())];
+                Segment::new(start, end)
+            })
+            .collect();
+
+        segments
+            .iter()
+            .enumerate()
+            .map(|(index, &segment)| {
+                let mut connections = SplineConnections::default();
+                if segment.start != segment.end {
+                    for (other_index, &other_segment) in segments.iter().enumerate() {
+                        if other_index == index || other_segment.start == other_segment.end {
+                            continue;
+                        }
+                        if let Some(kind) = connection_kind(segment, other_segment) {
+                            connections.0.push(SplineConnection {
+                                entity: Entity::from_raw(other_index as u32),
+                                segment: other_segment,
+                                kind,
+                            });
+                        }
+                    }
+                }
+
+                (SplineSegment(segment), connections)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,88 @@
+use bevy::{
+    color::palettes::css::{GREEN, ORANGE, RED, WHITE, YELLOW},
+    prelude::*,
+};
+use itertools::MinMaxResult;
+
+use super::{PointConnections, PointKind, SplineConnections, SplineSegment};
+use crate::{common_conditions::in_any_state, game_world::WorldState, settings::Settings};
+
+pub(super) struct ConnectionsDebugPlugin;
+
+impl Plugin for ConnectionsDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            Self::draw_connections
+                .run_if(in_any_state([WorldState::City, WorldState::Family]))
+                .run_if(|settings: Res<Settings>| settings.developer.spline_connections),
+        );
+    }
+}
+
+/// Radius of circles drawn at segment endpoints.
+const POINT_RADIUS: f32 = 0.1;
+
+/// Length of lines that point to the segments used for the endpoint offset.
+const ANGLE_LINE_LENGTH: f32 = 0.5;
+
+/// Lift above the ground to keep lines visible.
+const DEBUG_OFFSET: f32 = 0.05;
+
+impl ConnectionsDebugPlugin {
+    /// Draws endpoints colored by their connections and directions to the neighbors with minimal and maximal angles.
+    ///
+    /// Unconnected endpoints are red, connected to other segments are green
+    /// and connected to a point are white.
+    /// Connections with minimal and maximal angle are yellow and orange.
+    fn draw_connections(
+        mut gizmos: Gizmos,
+        segments: Query<(
+            &GlobalTransform,
+            &SplineSegment,
+            &SplineConnections,
+            &PointConnections,
+        )>,
+    ) {
+        for (transform, segment, connections, point_connections) in &segments {
+            if segment.start == segment.end {
+                continue;
+            }
+
+            let disp = segment.displacement();
+            for (kind, point, point_disp) in [
+                (PointKind::Start, segment.start, disp),
+                (PointKind::End, segment.end, -disp),
+            ] {
+                let connected = connections
+                    .iter()
+                    .any(|connection| connection.kind.0 == kind);
+                let color = if point_connections.contains(&kind) {
+                    WHITE
+                } else if connected {
+                    GREEN
+                } else {
+                    RED
+                };
+
+                let center = transform.transform_point(to_3d(point));
+                gizmos.circle(center, Dir3::Y, POINT_RADIUS, color);
+
+                let (min, max) = match connections.minmax_angles(point_disp, kind) {
+                    MinMaxResult::NoElements => continue,
+                    MinMaxResult::OneElement(segment) => (segment, segment),
+                    MinMaxResult::MinMax(min, max) => (min, max),
+                };
+                for (other, color) in [(min, YELLOW), (max, ORANGE)] {
+                    let direction = other.displacement().normalize_or_zero() * ANGLE_LINE_LENGTH;
+                    let end = transform.transform_point(to_3d(point + direction));
+                    gizmos.arrow(center, end, color);
+                }
+            }
+        }
+    }
+}
+
+fn to_3d(point: Vec2) -> Vec3 {
+    Vec3::new(point.x, DEBUG_OFFSET, point.y)
+}
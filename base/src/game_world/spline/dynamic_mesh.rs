@@ -1,6 +1,7 @@
 use bevy::{
     prelude::*,
     render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues},
+    tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task},
 };
 
 #[derive(Default)]
@@ -70,3 +71,52 @@ impl DynamicMesh {
             .with_inserted_indices(Indices::U32(Vec::new()))
     }
 }
+
+/// Mesh regeneration that runs in [`AsyncComputeTaskPool`].
+///
+/// The current mesh stays displayed until the task finishes.
+/// Attributes of the replaced mesh are kept to reuse allocations for the next task.
+#[derive(Component, Default)]
+pub(crate) struct MeshTask {
+    task: Option<Task<DynamicMesh>>,
+    buffer: Option<DynamicMesh>,
+}
+
+impl MeshTask {
+    /// Starts a new generation, replacing the running one.
+    pub(crate) fn spawn(&mut self, generate: impl FnOnce(&mut DynamicMesh) + Send + 'static) {
+        let mut dyn_mesh = self.buffer.take().unwrap_or_default();
+        // Dropping the previous task cancels it.
+        self.task = Some(AsyncComputeTaskPool::get().spawn(async move {
+            dyn_mesh.clear();
+            generate(&mut dyn_mesh);
+            dyn_mesh
+        }));
+    }
+
+    /// Applies the generated mesh to the asset if the task is finished.
+    ///
+    /// Returns `true` if the mesh was applied.
+    pub(crate) fn apply_finished(
+        &mut self,
+        meshes: &mut Assets<Mesh>,
+        mesh_handle: &Handle<Mesh>,
+    ) -> bool {
+        let Some(task) = &mut self.task else {
+            return false;
+        };
+        let Some(dyn_mesh) = block_on(future::poll_once(task)) else {
+            return false;
+        };
+        self.task = None;
+
+        // Access the asset only after the task is finished to avoid marking it as modified.
+        let mesh = meshes
+            .get_mut(mesh_handle)
+            .expect("spline handles should be valid");
+        self.buffer = Some(DynamicMesh::take(mesh));
+        dyn_mesh.apply(mesh);
+
+        true
+    }
+}
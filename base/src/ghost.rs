@@ -1,9 +1,87 @@
+use std::iter;
+
 use avian3d::prelude::*;
 use bevy::{
+    color::palettes::css::{RED, WHITE},
     ecs::component::{ComponentHooks, StorageType},
     prelude::*,
+    scene::{self, SceneInstanceReady},
 };
 
+pub(super) struct GhostPlugin;
+
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            SpawnScene,
+            Self::update_materials.after(scene::scene_spawner_system),
+        );
+    }
+}
+
+impl GhostPlugin {
+    /// Overrides materials on [`GhostMaterial`] change or when its scene is spawned.
+    fn update_materials(
+        mut ready_events: EventReader<SceneInstanceReady>,
+        mut materials: ResMut<Assets<StandardMaterial>>,
+        ghosts: Query<(Entity, Ref<GhostMaterial>)>,
+        children: Query<&Children>,
+        mut material_handles: Query<&mut Handle<StandardMaterial>>,
+    ) {
+        let ready_entities: Vec<_> = ready_events.read().map(|event| event.parent).collect();
+        for (entity, ghost_material) in &ghosts {
+            if !ghost_material.is_changed() && !ready_entities.contains(&entity) {
+                continue;
+            }
+
+            let color = ghost_material.color();
+            debug!("changing base color to `{color:?}` for `{entity}`");
+
+            let mut iter = material_handles
+                .iter_many_mut(iter::once(entity).chain(children.iter_descendants(entity)));
+            while let Some(mut material_handle) = iter.fetch_next() {
+                let material = materials
+                    .get(&*material_handle)
+                    .expect("material handle should be valid");
+
+                // If color matches, assume that the material is already overridden.
+                if material.base_color == color && material.alpha_mode == AlphaMode::Add {
+                    continue;
+                }
+
+                let mut material = material.clone();
+                material.base_color = color;
+                material.alpha_mode = AlphaMode::Add;
+                *material_handle = materials.add(material);
+            }
+        }
+    }
+}
+
+/// Displays entity with its descendants as translucent preview colored by placement validity.
+///
+/// Shared by all placement tools, each tool is responsible for updating the validity.
+#[derive(Component, Clone, Copy, PartialEq)]
+pub(super) struct GhostMaterial {
+    pub(super) allowed: bool,
+}
+
+impl GhostMaterial {
+    fn color(self) -> Color {
+        if self.allowed {
+            WHITE.into()
+        } else {
+            RED.into()
+        }
+    }
+}
+
+impl Default for GhostMaterial {
+    fn default() -> Self {
+        Self { allowed: true }
+    }
+}
+
 /// Entity that displayed instead of the original.
 #[derive(Clone, Copy)]
 pub(super) struct Ghost {
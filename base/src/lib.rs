@@ -1,7 +1,10 @@
+pub mod achievements;
 pub mod asset;
+pub mod bug_report;
 mod combined_scene_collider;
 pub mod common_conditions;
 mod component_commands;
+pub mod console;
 pub mod core;
 pub mod game_paths;
 pub mod game_world;
@@ -9,19 +12,35 @@ mod ghost;
 pub mod input_events;
 mod math;
 pub mod message;
+pub mod mod_package;
 pub mod network;
+pub mod presence;
+pub mod profiling;
+pub mod save_encryption;
 pub mod settings;
+#[cfg(any(test, feature = "test_world"))]
+pub mod test_world;
+pub mod world_archive;
+mod zip;
 
 use bevy::{app::PluginGroupBuilder, prelude::*};
 
+use achievements::AchievementsPlugin;
 use asset::AssetPlugin;
+use bug_report::BugReportPlugin;
 use combined_scene_collider::SceneColliderConstructorPlugin;
+use console::ConsolePlugin;
 use core::CorePlugin;
 use game_paths::GamePathsPlugin;
 use game_world::GameWorldPlugin;
+use ghost::GhostPlugin;
 use math::MathPlugin;
 use message::ErrorReportPlugin;
+use network::NetworkPlugin;
+use presence::PresencePlugin;
+use profiling::ProfilingPlugin;
 use settings::SettingsPlugin;
+use world_archive::WorldArchivePlugin;
 
 pub struct CorePlugins;
 
@@ -31,10 +50,18 @@ impl PluginGroup for CorePlugins {
             .add(AssetPlugin)
             .add(MathPlugin)
             .add(CorePlugin)
+            .add(ConsolePlugin)
             .add(SceneColliderConstructorPlugin)
             .add(GameWorldPlugin)
+            .add(GhostPlugin)
             .add(ErrorReportPlugin)
+            .add(ProfilingPlugin)
             .add(GamePathsPlugin)
+            .add(BugReportPlugin)
             .add(SettingsPlugin)
+            .add(NetworkPlugin)
+            .add(AchievementsPlugin)
+            .add(WorldArchivePlugin)
+            .add(PresencePlugin)
     }
 }
@@ -2,15 +2,19 @@ use bevy::prelude::*;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
+use super::segment::Segment;
+
 #[derive(Clone, Default, Deref, DerefMut, Deserialize, Reflect, Serialize)]
 pub(crate) struct Polygon(pub(crate) Vec<Vec2>);
 
 impl Polygon {
     /// A port of W. Randolph Franklin's [PNPOLY](https://wrf.ecse.rpi.edu//Research/Short_Notes/pnpoly.html) algorithm.
+    ///
+    /// Works for both closed and open polygons.
     #[must_use]
     pub(crate) fn contains_point(&self, point: Vec2) -> bool {
         let mut inside = false;
-        for (a, b) in self.iter().tuple_windows() {
+        for (a, b) in self.iter().circular_tuple_windows() {
             if ((a.y > point.y) != (b.y > point.y))
                 && (point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x)
             {
@@ -20,6 +24,81 @@ impl Polygon {
 
         inside
     }
+
+    /// Returns the closest point on the polygon boundary.
+    #[must_use]
+    pub(crate) fn closest_boundary_point(&self, point: Vec2) -> Option<Vec2> {
+        self.iter()
+            .tuple_windows()
+            .map(|(&start, &end)| Segment::new(start, end))
+            .filter(|segment| segment.start != segment.end)
+            .map(|segment| segment.closest_point(point))
+            .min_by(|a, b| {
+                a.distance_squared(point)
+                    .total_cmp(&b.distance_squared(point))
+            })
+    }
+
+    /// Returns segments between consecutive vertices.
+    pub(crate) fn edges(&self) -> impl Iterator<Item = Segment> + '_ {
+        self.iter()
+            .tuple_windows()
+            .map(|(&start, &end)| Segment::new(start, end))
+    }
+
+    /// Returns `true` if non-adjacent edges don't cross each other.
+    #[must_use]
+    pub(crate) fn is_simple(&self) -> bool {
+        let edges: Vec<_> = self
+            .edges()
+            .filter(|segment| segment.start != segment.end)
+            .collect();
+        let last_index = edges.len().saturating_sub(1);
+        for (index, &edge) in edges.iter().enumerate() {
+            for (other_index, &other_edge) in edges.iter().enumerate().skip(index + 2) {
+                // The last edge is adjacent to the first one in a closed polygon.
+                if index == 0 && other_index == last_index {
+                    continue;
+                }
+                if edge.intersects(other_edge) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Returns the unsigned area using the shoelace formula.
+    ///
+    /// Works for both closed and open polygons.
+    #[must_use]
+    pub(crate) fn area(&self) -> f32 {
+        let doubled: f32 = self
+            .iter()
+            .circular_tuple_windows()
+            .map(|(a, b)| a.perp_dot(*b))
+            .sum();
+
+        doubled.abs() / 2.0
+    }
+
+    /// Returns `true` if edges of polygons cross or one of them contains a vertex of another.
+    #[must_use]
+    pub(crate) fn overlaps(&self, other: &Self) -> bool {
+        self.edges()
+            .any(|edge| other.edges().any(|other_edge| edge.intersects(other_edge)))
+            || self.iter().any(|&vertex| other.contains_point(vertex))
+            || other.iter().any(|&vertex| self.contains_point(vertex))
+    }
+
+    /// Returns `true` if a segment crosses an edge or lies inside.
+    #[must_use]
+    pub(crate) fn overlaps_segment(&self, segment: Segment) -> bool {
+        self.edges().any(|edge| edge.intersects(segment))
+            || self.contains_point(segment.start)
+            || self.contains_point(segment.end)
+    }
 }
 
 impl From<Vec<Vec2>> for Polygon {
@@ -53,4 +132,88 @@ mod tests {
         ]);
         assert!(!polygon.contains_point(Vec2::new(3.2, 4.9)));
     }
+
+    #[test]
+    fn open_polygon_contains_point() {
+        // The implicit closing edge is the only one to the right of the point.
+        let polygon = Polygon(vec![
+            Vec2::new(2.0, 2.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(2.0, 1.0),
+        ]);
+        assert!(polygon.contains_point(Vec2::new(1.5, 1.5)));
+    }
+
+    #[test]
+    fn closest_boundary_point() {
+        let polygon = Polygon(vec![
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(1.0, 1.0),
+        ]);
+        assert_eq!(
+            polygon.closest_boundary_point(Vec2::new(1.2, 1.5)),
+            Some(Vec2::new(1.0, 1.5))
+        );
+        assert_eq!(
+            polygon.closest_boundary_point(Vec2::new(3.0, 3.0)),
+            Some(Vec2::new(2.0, 2.0))
+        );
+    }
+
+    #[test]
+    fn simple() {
+        let square = Polygon(vec![
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(1.0, 1.0),
+        ]);
+        assert!(square.is_simple());
+
+        let bowtie = Polygon(vec![
+            Vec2::new(1.0, 1.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(1.0, 1.0),
+        ]);
+        assert!(!bowtie.is_simple());
+    }
+
+    #[test]
+    fn area() {
+        let rectangle = Polygon(vec![
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 3.0),
+            Vec2::new(2.0, 3.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(1.0, 1.0),
+        ]);
+        assert_eq!(rectangle.area(), 2.0);
+
+        let line = Polygon(vec![Vec2::ZERO, Vec2::X, Vec2::ZERO]);
+        assert_eq!(line.area(), 0.0);
+    }
+
+    #[test]
+    fn overlaps() {
+        let polygon = Polygon(vec![
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(1.0, 1.0),
+        ]);
+        let shifted = Polygon(polygon.iter().map(|&vertex| vertex + 0.5).collect());
+        let distant = Polygon(polygon.iter().map(|&vertex| vertex + 5.0).collect());
+        assert!(polygon.overlaps(&shifted));
+        assert!(!polygon.overlaps(&distant));
+        assert!(polygon.overlaps_segment(Segment::new(Vec2::ZERO, Vec2::splat(1.5))));
+        assert!(!polygon.overlaps_segment(Segment::new(Vec2::ZERO, Vec2::new(0.0, 3.0))));
+    }
 }
@@ -3,6 +3,10 @@ use std::ops::{Add, Sub};
 use bevy::prelude::*;
 use itertools::MinMaxResult;
 use serde::{Deserialize, Serialize};
+use strum::Display;
+
+/// Maximum distance from a segment point to its offset point relative to the half-width.
+const MAX_MITER: f32 = 4.0;
 
 #[derive(Clone, Copy, Default, Deserialize, Reflect, Serialize)]
 pub(crate) struct Segment {
@@ -70,22 +74,21 @@ impl Segment {
     }
 
     /// Returns the intersection point of lines constructed from segments.
+    ///
+    /// Returns [`None`] for parallel, nearly parallel or zero-length segments.
     pub(crate) fn line_intersection(&self, other: Self) -> Option<Vec2> {
-        let slope1 = self.slope();
-        let slope2 = other.slope();
+        let disp = self.displacement();
+        let other_disp = other.displacement();
 
-        if slope1 == slope2 {
-            return None; // Parallel lines, no intersection point
+        // Sine of the angle between segments scaled by their lengths.
+        let denominator = disp.perp_dot(other_disp);
+        const PARALLEL_TOLERANCE: f32 = 1e-4;
+        if denominator.abs() <= PARALLEL_TOLERANCE * disp.length() * other_disp.length() {
+            return None;
         }
 
-        let start1 = self.start;
-        let start2 = other.start;
-
-        let x =
-            ((slope1 * start1.x) - (slope2 * start2.x) + start2.y - start1.y) / (slope1 - slope2);
-        let y = slope1 * (x - start1.x) + start1.y;
-
-        Some(Vec2 { x, y })
+        let t = (other.start - self.start).perp_dot(other_disp) / denominator;
+        Some(self.start + disp * t)
     }
 
     /// Returns `true` if two segments intersect.
@@ -106,40 +109,63 @@ impl Segment {
     ///
     /// `width_disp` is the width displacement vector of the segment.
     /// `half_width` is the half-width of the points for other segments.
+    ///
+    /// Collinear and nearly parallel connections use the regular offset
+    /// and miters of sharp angles are clamped to [`MAX_MITER`] of the width.
     pub(crate) fn offset_points(
         self,
         width_disp: Vec2,
         half_width: f32,
         connections: MinMaxResult<Segment>,
-    ) -> (Vec2, Vec2) {
-        match connections {
-            MinMaxResult::NoElements => (self.start + width_disp, self.start - width_disp),
-            MinMaxResult::OneElement(other_segment) => {
-                let other_width = other_segment.displacement().perp().normalize() * half_width;
-                let left = (self + width_disp)
-                    .line_intersection(other_segment - other_width)
-                    .unwrap_or_else(|| self.start + width_disp);
-                let right = (self - width_disp)
-                    .line_intersection(other_segment.inverse() + other_width)
-                    .unwrap_or_else(|| self.start + width_disp);
-
-                (left, right)
-            }
-            MinMaxResult::MinMax(min_segment, max_segment) => {
-                let max_width = max_segment.displacement().perp().normalize() * half_width;
-                let left = (self + width_disp)
-                    .line_intersection(max_segment - max_width)
-                    .unwrap_or_else(|| self.start + width_disp);
-                let min_width = min_segment.displacement().perp().normalize() * half_width;
-                let right = (self - width_disp)
-                    .line_intersection(min_segment.inverse() + min_width)
-                    .unwrap_or_else(|| self.start + width_disp);
-
-                (left, right)
+    ) -> Result<(Vec2, Vec2), OffsetError> {
+        if !self.start.is_finite() || !self.end.is_finite() || !width_disp.is_finite() {
+            return Err(OffsetError::NonFinite);
+        }
+        if self.start == self.end {
+            return Err(OffsetError::ZeroLength);
+        }
+
+        let (min_segment, max_segment) = match connections {
+            MinMaxResult::NoElements => {
+                return Ok((self.start + width_disp, self.start - width_disp))
             }
+            MinMaxResult::OneElement(other_segment) => (other_segment, other_segment),
+            MinMaxResult::MinMax(min_segment, max_segment) => (min_segment, max_segment),
+        };
+
+        let max_miter = width_disp.length().max(half_width) * MAX_MITER;
+        let max_width = max_segment.displacement().perp().normalize_or_zero() * half_width;
+        let left = (self + width_disp)
+            .line_intersection(max_segment - max_width)
+            .map(|point| self.clamp_miter(point, max_miter))
+            .unwrap_or(self.start + width_disp);
+
+        let min_width = min_segment.displacement().perp().normalize_or_zero() * half_width;
+        let right = (self - width_disp)
+            .line_intersection(min_segment.inverse() + min_width)
+            .map(|point| self.clamp_miter(point, max_miter))
+            .unwrap_or(self.start - width_disp);
+
+        Ok((left, right))
+    }
+
+    /// Returns offset points for both ends of the segment, moved to their middle if they cross each other.
+    ///
+    /// Happens on short segments with sharp connections on both ends.
+    pub(crate) fn uncross(&self, start_point: Vec2, end_point: Vec2) -> (Vec2, Vec2) {
+        if (end_point - start_point).dot(self.displacement()) < 0.0 {
+            let middle = (start_point + end_point) / 2.0;
+            (middle, middle)
+        } else {
+            (start_point, end_point)
         }
     }
 
+    /// Moves the offset point closer to the `start` if the angle between segments is too sharp.
+    fn clamp_miter(&self, point: Vec2, max_length: f32) -> Vec2 {
+        self.start + (point - self.start).clamp_length_max(max_length)
+    }
+
     /// Returns distance from start to end.
     fn len(&self) -> f32 {
         self.start.distance(self.end)
@@ -149,11 +175,6 @@ impl Segment {
     pub(crate) fn points(&self) -> [Vec2; 2] {
         [self.start, self.end]
     }
-
-    /// Calculates the slope (Δy/Δx).
-    fn slope(&self) -> f32 {
-        (self.end.y - self.start.y) / (self.end.x - self.start.x)
-    }
 }
 
 impl Add<Vec2> for Segment {
@@ -177,3 +198,165 @@ impl Sub<Vec2> for Segment {
         }
     }
 }
+
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+pub(crate) enum OffsetError {
+    #[strum(serialize = "segment has zero length")]
+    ZeroLength,
+    #[strum(serialize = "segment has non-finite coordinates")]
+    NonFinite,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_4, TAU};
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn vertical_intersection() {
+        let vertical = Segment::new(Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0));
+        let horizontal = Segment::new(Vec2::new(-1.0, 0.0), Vec2::new(2.0, 0.0));
+        assert_eq!(
+            vertical.line_intersection(horizontal),
+            Some(Vec2::new(1.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn parallel_intersection() {
+        let segment = Segment::new(Vec2::ZERO, Vec2::X);
+        assert_eq!(segment.line_intersection(segment + Vec2::Y), None);
+        assert_eq!(segment.line_intersection(segment.inverse()), None);
+        assert_eq!(segment.line_intersection(Segment::splat(Vec2::Y)), None);
+    }
+
+    #[test]
+    fn degenerate_offset() {
+        let width_disp = Vec2::Y;
+        assert_eq!(
+            Segment::splat(Vec2::ONE).offset_points(width_disp, 1.0, MinMaxResult::NoElements),
+            Err(OffsetError::ZeroLength)
+        );
+        assert_eq!(
+            Segment::new(Vec2::ZERO, Vec2::NAN).offset_points(
+                width_disp,
+                1.0,
+                MinMaxResult::NoElements
+            ),
+            Err(OffsetError::NonFinite)
+        );
+    }
+
+    #[test]
+    fn collinear_offset() {
+        let segment = Segment::new(Vec2::ZERO, Vec2::X);
+        let width_disp = Vec2::Y * 0.5;
+        let other = Segment::new(Vec2::ZERO, Vec2::NEG_X);
+        let (left, right) = segment
+            .offset_points(width_disp, 0.5, MinMaxResult::OneElement(other))
+            .unwrap();
+        assert_eq!(left, width_disp);
+        assert_eq!(right, -width_disp);
+    }
+
+    #[test]
+    fn diagonal_corner() {
+        const HALF_WIDTH: f32 = 0.5;
+        let horizontal = Segment::new(Vec2::ZERO, Vec2::X * 2.0);
+        let diagonal = Segment::new(Vec2::ZERO, Vec2::ONE);
+
+        let width_disp = horizontal.displacement().perp().normalize() * HALF_WIDTH;
+        let (left, _) = horizontal
+            .offset_points(width_disp, HALF_WIDTH, MinMaxResult::OneElement(diagonal))
+            .unwrap();
+
+        let width_disp = diagonal.displacement().perp().normalize() * HALF_WIDTH;
+        let (_, right) = diagonal
+            .offset_points(width_disp, HALF_WIDTH, MinMaxResult::OneElement(horizontal))
+            .unwrap();
+
+        assert!(
+            left.distance(right) < 1e-4,
+            "inner corner points {left} and {right} should match"
+        );
+    }
+
+    #[test]
+    fn crossed_offsets() {
+        let segment = Segment::new(Vec2::ZERO, Vec2::X);
+        let start_point = Vec2::new(0.8, 0.5);
+        let end_point = Vec2::new(0.2, 0.5);
+        assert_eq!(
+            segment.uncross(start_point, end_point),
+            (Vec2::new(0.5, 0.5), Vec2::new(0.5, 0.5))
+        );
+        assert_eq!(
+            segment.uncross(end_point, start_point),
+            (end_point, start_point)
+        );
+    }
+
+    #[test]
+    fn random_offsets() {
+        const HALF_WIDTH: f32 = 0.5;
+        const EPSILON: f32 = 1e-3;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..10_000 {
+            let start = Vec2::new(rng.gen_range(-10.0..10.0), rng.gen_range(-10.0..10.0));
+            let segment = Segment::new(start, start + random_disp(&mut rng));
+            if segment.start == segment.end {
+                continue;
+            }
+
+            let connections: Vec<_> = (0..rng.gen_range(0..4))
+                .map(|_| Segment::new(start, start + random_disp(&mut rng)))
+                .collect();
+            let connections = match connections.as_slice() {
+                [] => MinMaxResult::NoElements,
+                &[other] => MinMaxResult::OneElement(other),
+                &[min, .., max] => MinMaxResult::MinMax(min, max),
+            };
+
+            let disp = segment.displacement();
+            let width_disp = disp.perp().normalize() * HALF_WIDTH;
+            let (left, right) = segment
+                .offset_points(width_disp, HALF_WIDTH, connections)
+                .expect("segment should be valid");
+
+            for point in [left, right] {
+                assert!(point.is_finite(), "{point} should be finite");
+                assert!(
+                    point.distance(start) <= HALF_WIDTH * MAX_MITER + EPSILON,
+                    "{point} should be within the miter limit"
+                );
+            }
+            assert!(
+                disp.perp_dot(left - start) > 0.0,
+                "{left} should be on the left side"
+            );
+            assert!(
+                disp.perp_dot(right - start) < 0.0,
+                "{right} should be on the right side"
+            );
+        }
+    }
+
+    /// Returns displacement with angles snapped to make collinear and nearly parallel segments common.
+    fn random_disp(rng: &mut impl Rng) -> Vec2 {
+        let angle = match rng.gen_range(0..3) {
+            0 => rng.gen_range(0..8) as f32 * FRAC_PI_4,
+            1 => rng.gen_range(0..8) as f32 * FRAC_PI_4 + rng.gen_range(-1e-3..1e-3),
+            _ => rng.gen_range(0.0..TAU),
+        };
+        let length = if rng.gen_bool(0.1) {
+            rng.gen_range(0.0..1e-3)
+        } else {
+            rng.gen_range(0.1..10.0)
+        };
+        Vec2::from_angle(angle) * length
+    }
+}
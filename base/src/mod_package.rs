@@ -0,0 +1,349 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    fs::{self, File},
+    io::BufWriter,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{bail, ensure, Context, Result};
+use bevy::{prelude::*, scene::ron};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use super::zip::{self, ZipWriter};
+
+pub const MOD_EXTENSION: &str = "harmonia-mod";
+
+/// Stored at the archive root, all mod files are placed under the directory with the mod name.
+const MANIFEST_FILE: &str = "manifest.ron";
+
+/// Extensions of files that can be referenced from metadata.
+const ASSET_EXTENSIONS: &[&str] = &["ron", "gltf", "glb", "bin", "png", "jpg", "ktx2", "ogg"];
+
+/// Directory inside the mod with rendered object previews for mod listings.
+pub const PREVIEWS_DIR: &str = "previews";
+
+/// Checks that the mod is loaded by the game to render its object previews and removes outdated ones.
+///
+/// Returns the mod name and the directory for rendered previews.
+pub fn prepare_previews(dir: &Path) -> Result<(String, PathBuf)> {
+    let name = mod_name(dir)?;
+    let assets_dir = assets_dir();
+    let same_dir = dir
+        .canonicalize()
+        .ok()
+        .zip(assets_dir.join(name).canonicalize().ok())
+        .is_some_and(|(dir, installed)| dir == installed);
+    ensure!(
+        same_dir,
+        "mod should be placed in {assets_dir:?} to render object previews"
+    );
+
+    let previews_dir = dir.join(PREVIEWS_DIR);
+    if previews_dir.exists() {
+        fs::remove_dir_all(&previews_dir)
+            .with_context(|| format!("unable to remove {previews_dir:?}"))?;
+    }
+
+    Ok((name.to_string(), previews_dir))
+}
+
+/// Validates the mod directory and packs it into an archive ready for distribution.
+///
+/// The directory name is used as the mod name, the same as for asset packs in the game assets.
+/// Object previews should be rendered into [`PREVIEWS_DIR`] beforehand, see [`prepare_previews`].
+///
+/// Returns path to the created archive.
+pub fn package(dir: &Path, output_dir: &Path) -> Result<PathBuf> {
+    let name = mod_name(dir)?;
+
+    info!("packaging mod '{name}' from {dir:?}");
+    let mut files = BTreeMap::new();
+    for entry in WalkDir::new(dir).sort_by_file_name() {
+        let entry = entry.with_context(|| format!("unable to read {dir:?}"))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative_path = entry
+            .path()
+            .strip_prefix(dir)
+            .expect("entries should start with the mod directory");
+        let path = normalize_path(relative_path)?;
+        if path.split('/').any(|part| part.starts_with('.')) {
+            debug!("skipping hidden '{path}'");
+            continue;
+        }
+
+        if let Some(other) = files
+            .keys()
+            .find(|other: &&String| other.eq_ignore_ascii_case(&path))
+        {
+            bail!("'{path}' and '{other}' differ only in case");
+        }
+
+        let data =
+            fs::read(entry.path()).with_context(|| format!("unable to read {:?}", entry.path()))?;
+        files.insert(path, data);
+    }
+    ensure!(!files.is_empty(), "{dir:?} doesn't contain any files");
+
+    let errors: Vec<_> = files
+        .iter()
+        .filter(|(path, _)| path.ends_with(".ron"))
+        .flat_map(|(path, data)| validate_metadata(path, data, &files))
+        .collect();
+    ensure!(
+        errors.is_empty(),
+        "mod '{name}' contains invalid metadata:\n{}",
+        errors.join("\n")
+    );
+
+    let manifest = ModManifest {
+        name: name.to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        files: files
+            .iter()
+            .map(|(path, data)| (path.clone(), ManifestFile::new(data)))
+            .collect(),
+    };
+    let manifest = ron::ser::to_string_pretty(&manifest, Default::default())
+        .expect("manifest should be serializable");
+
+    fs::create_dir_all(output_dir).with_context(|| format!("unable to create {output_dir:?}"))?;
+    let mut path = output_dir.join(name);
+    path.set_extension(MOD_EXTENSION);
+
+    let file = File::create(&path).with_context(|| format!("unable to create {path:?}"))?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    zip.add(MANIFEST_FILE, manifest.as_bytes())?;
+    for (file_path, data) in &files {
+        zip.add(&format!("{name}/{file_path}"), data)
+            .with_context(|| format!("unable to add '{file_path}'"))?;
+    }
+    zip.finish()
+        .with_context(|| format!("unable to write {path:?}"))?;
+
+    Ok(path)
+}
+
+/// Verifies the archive against its manifest and extracts it into the game assets.
+///
+/// Fails if any file is missing, modified or not listed in the manifest,
+/// or if an asset pack with the same name is already installed.
+///
+/// Returns the installed mod name.
+pub fn install(archive: &Path) -> Result<String> {
+    info!("installing mod from {archive:?}");
+    let bytes = fs::read(archive).with_context(|| format!("unable to read {archive:?}"))?;
+    let mut entries: BTreeMap<_, _> = zip::read(&bytes)
+        .with_context(|| format!("unable to unpack {archive:?}"))?
+        .into_iter()
+        .collect();
+
+    let manifest = entries
+        .remove(MANIFEST_FILE)
+        .with_context(|| format!("{archive:?} is missing {MANIFEST_FILE}"))?;
+    let manifest: ModManifest = ron::de::from_bytes(&manifest)
+        .with_context(|| format!("unable to parse {MANIFEST_FILE} from {archive:?}"))?;
+
+    // The name is used as a directory name, don't allow it to point outside of the assets.
+    ensure!(
+        Path::new(&manifest.name).file_name() == Some(manifest.name.as_ref()),
+        "'{}' is not a valid mod name",
+        manifest.name
+    );
+    if manifest.version != env!("CARGO_PKG_VERSION") {
+        warn!(
+            "mod '{}' was packaged for version {}",
+            manifest.name, manifest.version
+        );
+    }
+
+    let mut files = Vec::with_capacity(manifest.files.len());
+    for (path, expected) in &manifest.files {
+        let normalized = normalize_path(Path::new(path))?;
+        ensure!(normalized == *path, "'{path}' is not a normalized path");
+
+        let archive_path = format!("{}/{path}", manifest.name);
+        let data = entries
+            .remove(&archive_path)
+            .with_context(|| format!("'{path}' is missing from the archive"))?;
+        ensure!(
+            ManifestFile::new(&data) == *expected,
+            "'{path}' doesn't match the manifest"
+        );
+        files.push((path, data));
+    }
+    if let Some(unexpected) = entries.keys().next() {
+        bail!("'{unexpected}' is not listed in the manifest");
+    }
+
+    let mod_dir = assets_dir().join(&manifest.name);
+    ensure!(
+        !mod_dir.exists(),
+        "asset pack '{}' is already installed",
+        manifest.name
+    );
+
+    for (path, data) in files {
+        let file_path = mod_dir.join(path);
+        let parent = file_path
+            .parent()
+            .expect("mod files should have a parent directory");
+        fs::create_dir_all(parent).with_context(|| format!("unable to create {parent:?}"))?;
+        fs::write(&file_path, data).with_context(|| format!("unable to write {file_path:?}"))?;
+    }
+
+    Ok(manifest.name)
+}
+
+fn assets_dir() -> PathBuf {
+    Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap_or_default()).join("assets")
+}
+
+fn mod_name(dir: &Path) -> Result<&str> {
+    dir.file_name()
+        .and_then(|name| name.to_str())
+        .with_context(|| format!("{dir:?} is not a valid mod directory"))
+}
+
+/// Converts a relative path into a string with `/` separators.
+///
+/// Fails for paths that could point outside of the mod.
+fn normalize_path(path: &Path) -> Result<String> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => {
+                let part = part
+                    .to_str()
+                    .with_context(|| format!("{path:?} is not a valid UTF-8 path"))?;
+                parts.push(part);
+            }
+            Component::CurDir => (),
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                bail!("{path:?} should be relative to the mod directory")
+            }
+        }
+    }
+    ensure!(!parts.is_empty(), "path should not be empty");
+
+    Ok(parts.join("/"))
+}
+
+/// Checks the metadata syntax and existence of all referenced files.
+///
+/// Returns found errors.
+fn validate_metadata(path: &str, data: &[u8], files: &BTreeMap<String, Vec<u8>>) -> Vec<String> {
+    let Ok(content) = std::str::from_utf8(data) else {
+        return vec![format!("'{path}' is not valid UTF-8")];
+    };
+    if let Err(error) = ron::from_str::<ron::Value>(content) {
+        return vec![format!("'{path}' is not valid RON: {error}")];
+    }
+
+    let dir = path
+        .rsplit_once('/')
+        .map(|(dir, _)| dir)
+        .unwrap_or_default();
+    let mut errors = Vec::new();
+    for reference in content.split('"').skip(1).step_by(2) {
+        let (file, _label) = reference.split_once('#').unwrap_or((reference, ""));
+        let is_asset = file.rsplit_once('.').is_some_and(|(_, extension)| {
+            ASSET_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+        });
+        if !is_asset {
+            continue;
+        }
+
+        let referenced = if dir.is_empty() {
+            file.to_string()
+        } else {
+            format!("{dir}/{file}")
+        };
+        match normalize_path(Path::new(&referenced)) {
+            Ok(referenced) if files.contains_key(&referenced) => (),
+            Ok(referenced) => {
+                errors.push(format!("'{path}' references missing '{referenced}'"));
+            }
+            Err(error) => errors.push(format!("'{path}' has invalid reference: {error}")),
+        }
+    }
+
+    errors
+}
+
+/// Metadata stored inside a mod archive.
+#[derive(Deserialize, Serialize)]
+struct ModManifest {
+    name: String,
+
+    /// Game version used for packaging.
+    version: String,
+
+    /// Paths relative to the mod directory.
+    files: BTreeMap<String, ManifestFile>,
+}
+
+#[derive(Deserialize, PartialEq, Serialize)]
+struct ManifestFile {
+    size: u64,
+
+    /// BLAKE3 hash in hex.
+    hash: String,
+}
+
+impl ManifestFile {
+    fn new(data: &[u8]) -> Self {
+        Self {
+            size: data.len() as u64,
+            hash: blake3::hash(data).to_hex().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_normalization() {
+        assert_eq!(
+            normalize_path(Path::new("objects/./chair/chair.gltf")).unwrap(),
+            "objects/chair/chair.gltf"
+        );
+        assert!(normalize_path(Path::new("../base/chair.gltf")).is_err());
+        assert!(normalize_path(Path::new("/chair.gltf")).is_err());
+    }
+
+    #[test]
+    fn metadata_references() {
+        let files = BTreeMap::from([
+            ("chair/chair.gltf".to_string(), Vec::new()),
+            ("chair/chair.object.ron".to_string(), Vec::new()),
+        ]);
+
+        let valid = br#"(scene: "chair.gltf#Scene0", name: "Chair")"#;
+        assert!(validate_metadata("chair/chair.object.ron", valid, &files).is_empty());
+
+        let missing = br#"(scene: "table.gltf#Scene0")"#;
+        assert_eq!(
+            validate_metadata("chair/chair.object.ron", missing, &files).len(),
+            1
+        );
+
+        let outside = br#"(scene: "../../base/chair.gltf#Scene0")"#;
+        assert_eq!(
+            validate_metadata("chair/chair.object.ron", outside, &files).len(),
+            1
+        );
+
+        let invalid = b"(scene: ";
+        assert_eq!(
+            validate_metadata("chair/chair.object.ron", invalid, &files).len(),
+            1
+        );
+    }
+}
@@ -1,29 +1,265 @@
+pub mod chat;
+pub mod moderation;
+pub mod server_console;
+pub mod shutdown;
+pub mod validation;
+
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
     time::SystemTime,
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
+use argon2::Argon2;
 use bevy::prelude::*;
-use bevy_replicon_renet::renet::transport::{
-    ClientAuthentication, NetcodeClientTransport, NetcodeServerTransport, ServerAuthentication,
-    ServerConfig,
+use bevy_replicon::prelude::*;
+use bevy_replicon_renet::{
+    renet::{
+        transport::{
+            ClientAuthentication, ConnectToken, NetcodeClientTransport, NetcodeServerTransport,
+            ServerAuthentication, ServerConfig, NETCODE_KEY_BYTES,
+        },
+        ConnectionConfig,
+    },
+    RenetChannelsExt,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game_world::simulation,
+    message::error_message,
+    settings::{ServerSettings, Settings, SettingsApply},
 };
+use chat::ChatPlugin;
+use moderation::{ModerationPlugin, PlayerIdentity};
+use server_console::ServerConsolePlugin;
+use shutdown::ShutdownPlugin;
+use validation::ValidationPlugin;
 
 pub const DEFAULT_PORT: u16 = 4761;
+pub const DEFAULT_MAX_CLIENTS: usize = 4;
 const PROTOCOL_ID: u64 = 7;
 
-pub fn create_server(port: u16) -> Result<NetcodeServerTransport> {
+/// Lifetime of connect tokens generated by clients for password-protected servers.
+const TOKEN_EXPIRE_SECONDS: u64 = 300;
+
+/// Same as the timeout netcode uses for unsecure connections.
+const TOKEN_TIMEOUT_SECONDS: i32 = 15;
+
+/// Salt for deriving a netcode key from a server password.
+///
+/// Constant because clients and the server derive the key independently.
+const PASSWORD_SALT: &[u8] = b"project_harmonia-server-password";
+
+/// Drives replication ticks based on [`ServerSettings`] and shares the rate with clients.
+///
+/// Requires [`TickPolicy::Manual`] in [`ServerPlugin`].
+pub(super) struct NetworkPlugin;
+
+impl Plugin for NetworkPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            ChatPlugin,
+            ModerationPlugin,
+            ServerConsolePlugin,
+            ShutdownPlugin,
+            ValidationPlugin,
+        ))
+        .init_resource::<ServerTickRate>()
+        .init_resource::<TickTimer>()
+        .add_server_event::<ServerAdvertise>(ChannelKind::Ordered)
+        .add_systems(Startup, Self::apply_tick_rate.pipe(error_message))
+        .add_systems(
+            PreUpdate,
+            (
+                Self::advertise
+                    .after(ServerSet::Receive)
+                    .run_if(server_running.and_then(resource_exists::<ServerInfo>)),
+                Self::receive_advertise
+                    .after(ClientSet::Receive)
+                    .run_if(client_connected),
+            ),
+        )
+        .add_systems(
+            PostUpdate,
+            (
+                Self::apply_tick_rate
+                    .pipe(error_message)
+                    .run_if(on_event::<SettingsApply>()),
+                Self::increment_tick.run_if(server_running),
+            )
+                .chain()
+                .before(ServerSet::Send),
+        );
+    }
+}
+
+impl NetworkPlugin {
+    /// Updates tick timer from settings, falling back to defaults on invalid values.
+    fn apply_tick_rate(
+        mut timer: ResMut<TickTimer>,
+        mut advertise_events: EventWriter<ToClients<ServerAdvertise>>,
+        server: Res<RepliconServer>,
+        server_info: Option<Res<ServerInfo>>,
+        settings: Res<Settings>,
+    ) -> Result<()> {
+        let result = settings.server.validate();
+        let new_rate = if result.is_ok() {
+            settings.server.tick_rate
+        } else {
+            ServerSettings::default().tick_rate
+        };
+
+        if timer.rate != new_rate {
+            info!("setting server tick rate to {new_rate}");
+            *timer = TickTimer::new(new_rate);
+            if let Some(server_info) = server_info.filter(|_| server.is_running()) {
+                advertise_events.send(ToClients {
+                    mode: SendMode::Broadcast,
+                    event: ServerAdvertise {
+                        name: server_info.name.clone(),
+                        tick_rate: new_rate,
+                    },
+                });
+            }
+        }
+
+        result.context("unable to apply server settings, using default tick rate")
+    }
+
+    fn advertise(
+        mut server_events: EventReader<ServerEvent>,
+        mut advertise_events: EventWriter<ToClients<ServerAdvertise>>,
+        timer: Res<TickTimer>,
+        server_info: Res<ServerInfo>,
+    ) {
+        for event in server_events.read() {
+            if let ServerEvent::ClientConnected { client_id } = *event {
+                debug!("advertising tick rate {} to `{client_id:?}`", timer.rate);
+                advertise_events.send(ToClients {
+                    mode: SendMode::Direct(client_id),
+                    event: ServerAdvertise {
+                        name: server_info.name.clone(),
+                        tick_rate: timer.rate,
+                    },
+                });
+            }
+        }
+    }
+
+    fn receive_advertise(
+        mut advertise_events: EventReader<ServerAdvertise>,
+        mut tick_rate: ResMut<ServerTickRate>,
+    ) {
+        if let Some(event) = advertise_events.read().last() {
+            info!(
+                "connected to `{}` with tick rate {}",
+                event.name, event.tick_rate
+            );
+            tick_rate.0 = event.tick_rate;
+        }
+    }
+
+    fn increment_tick(
+        time: Res<Time<Real>>,
+        mut timer: ResMut<TickTimer>,
+        mut server_tick: ResMut<ServerTick>,
+    ) {
+        timer.timer.tick(time.delta());
+        if timer.timer.just_finished() {
+            server_tick.increment();
+        }
+    }
+}
+
+/// Replication rate of the server the client is connected to.
+///
+/// Received from the server after connection and used to smooth replicated movement.
+#[derive(Resource, Clone, Copy, Deref)]
+pub struct ServerTickRate(u16);
+
+impl Default for ServerTickRate {
+    fn default() -> Self {
+        Self(simulation::TICK_RATE)
+    }
+}
+
+/// Sent by the server to inform clients about its name and [`ServerTickRate`].
+#[derive(Event, Deserialize, Serialize)]
+struct ServerAdvertise {
+    name: String,
+    tick_rate: u16,
+}
+
+/// Information about the hosted server.
+///
+/// Should be inserted together with the server resources.
+#[derive(Resource)]
+pub struct ServerInfo {
+    pub name: String,
+}
+
+/// Triggers replication on the server with [`Self::rate`] frequency.
+#[derive(Resource)]
+struct TickTimer {
+    timer: Timer,
+    rate: u16,
+}
+
+impl TickTimer {
+    fn new(rate: u16) -> Self {
+        Self {
+            timer: Timer::from_seconds(1.0 / rate as f32, TimerMode::Repeating),
+            rate,
+        }
+    }
+}
+
+impl Default for TickTimer {
+    fn default() -> Self {
+        Self::new(simulation::TICK_RATE)
+    }
+}
+
+/// Creates a connection config for a server with the bandwidth limit from settings.
+pub fn server_connection_config(
+    network_channels: &RepliconChannels,
+    settings: &ServerSettings,
+) -> Result<ConnectionConfig> {
+    settings.validate().context("invalid server settings")?;
+
+    Ok(ConnectionConfig {
+        available_bytes_per_tick: settings.bytes_per_tick,
+        server_channels_config: network_channels.get_server_configs(),
+        client_channels_config: network_channels.get_client_configs(),
+    })
+}
+
+/// Creates a server transport.
+///
+/// If the password is not empty, clients need to derive the same key from it to pass the handshake.
+pub fn create_server(
+    port: u16,
+    max_clients: usize,
+    password: &str,
+) -> Result<NetcodeServerTransport> {
     info!("creating server transport");
 
     let current_time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
     let public_addr = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port);
     let socket = UdpSocket::bind(public_addr)?;
+    let authentication = if password.is_empty() {
+        ServerAuthentication::Unsecure
+    } else {
+        ServerAuthentication::Secure {
+            private_key: password_key(password)?,
+        }
+    };
     let server_config = ServerConfig {
         current_time,
-        max_clients: 1,
+        max_clients,
         protocol_id: PROTOCOL_ID,
-        authentication: ServerAuthentication::Unsecure,
+        authentication,
         public_addresses: vec![public_addr],
     };
     let transport = NetcodeServerTransport::new(server_config, socket)?;
@@ -31,20 +267,71 @@ pub fn create_server(port: u16) -> Result<NetcodeServerTransport> {
     Ok(transport)
 }
 
-pub fn create_client(ip: IpAddr, port: u16) -> Result<NetcodeClientTransport> {
+/// Creates a client transport.
+///
+/// The password is never sent, it's used to sign the connect token instead.
+/// The identity is passed in user data to let the server ban players.
+pub fn create_client(
+    ip: IpAddr,
+    port: u16,
+    password: &str,
+    identity: PlayerIdentity,
+) -> Result<NetcodeClientTransport> {
     info!("creating client transport");
 
     let current_time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
     let client_id = current_time.as_millis() as u64;
     let server_addr = SocketAddr::new(ip, port);
     let socket = UdpSocket::bind((ip, 0))?;
-    let authentication = ClientAuthentication::Unsecure {
-        client_id,
-        protocol_id: PROTOCOL_ID,
-        server_addr,
-        user_data: None,
+    let user_data = identity.to_data();
+    let authentication = if password.is_empty() {
+        ClientAuthentication::Unsecure {
+            client_id,
+            protocol_id: PROTOCOL_ID,
+            server_addr,
+            user_data: Some(user_data),
+        }
+    } else {
+        let connect_token = ConnectToken::generate(
+            current_time,
+            PROTOCOL_ID,
+            TOKEN_EXPIRE_SECONDS,
+            client_id,
+            TOKEN_TIMEOUT_SECONDS,
+            vec![server_addr],
+            Some(&user_data),
+            &password_key(password)?,
+        )
+        .context("unable to generate connect token")?;
+        ClientAuthentication::Secure { connect_token }
     };
     let transport = NetcodeClientTransport::new(current_time, authentication, socket)?;
 
     Ok(transport)
 }
+
+/// Derives netcode private key from the server password.
+///
+/// A wrong password results in a token that the server can't decrypt during the handshake.
+fn password_key(password: &str) -> Result<[u8; NETCODE_KEY_BYTES]> {
+    let mut key = [0; NETCODE_KEY_BYTES];
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), PASSWORD_SALT, &mut key)
+        .map_err(|e| anyhow!("{e}"))
+        .context("unable to derive key from password")?;
+
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn password_keys() -> Result<()> {
+        assert_eq!(password_key("password")?, password_key("password")?);
+        assert_ne!(password_key("password")?, password_key("пароль")?);
+
+        Ok(())
+    }
+}
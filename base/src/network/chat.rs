@@ -0,0 +1,90 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::validation::{AppValidationExt, ValidationSet};
+use crate::core::GameState;
+
+/// Text messages between players.
+///
+/// Clients send messages to the server which broadcasts them to everyone.
+pub(super) struct ChatPlugin;
+
+impl Plugin for ChatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChatHistory>()
+            .add_client_event::<ChatSend>(ChannelKind::Ordered)
+            .limit_client_event::<ChatSend>(5)
+            .add_server_event::<ChatMessage>(ChannelKind::Ordered)
+            .add_systems(
+                PreUpdate,
+                (
+                    Self::broadcast
+                        .after(ValidationSet)
+                        .run_if(server_or_singleplayer),
+                    Self::receive.after(ClientSet::Receive),
+                ),
+            )
+            .add_systems(OnExit(GameState::InGame), Self::cleanup);
+    }
+}
+
+/// Maximum number of characters in a message.
+pub const MAX_MESSAGE_LEN: usize = 256;
+
+/// Number of messages to keep in [`ChatHistory`].
+const HISTORY_LEN: usize = 50;
+
+impl ChatPlugin {
+    fn broadcast(
+        mut send_events: EventReader<FromClient<ChatSend>>,
+        mut message_events: EventWriter<ToClients<ChatMessage>>,
+    ) {
+        for FromClient { client_id, event } in send_events.read() {
+            let text = event.0.trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            let text: String = text.chars().take(MAX_MESSAGE_LEN).collect();
+            debug!("broadcasting chat message from `{client_id:?}`");
+            message_events.send(ToClients {
+                mode: SendMode::Broadcast,
+                event: ChatMessage {
+                    sender: *client_id,
+                    text,
+                },
+            });
+        }
+    }
+
+    fn receive(mut message_events: EventReader<ChatMessage>, mut history: ResMut<ChatHistory>) {
+        for message in message_events.read() {
+            if history.len() == HISTORY_LEN {
+                history.0.pop_front();
+            }
+            history.0.push_back(message.clone());
+        }
+    }
+
+    fn cleanup(mut history: ResMut<ChatHistory>) {
+        history.0.clear();
+    }
+}
+
+/// Sends a message to all players.
+#[derive(Deserialize, Event, Serialize)]
+pub struct ChatSend(pub String);
+
+/// Message received from a player.
+#[derive(Clone, Deserialize, Event, Serialize)]
+pub struct ChatMessage {
+    pub sender: ClientId,
+    pub text: String,
+}
+
+/// Last received messages for the current session.
+#[derive(Default, Deref, Resource)]
+pub struct ChatHistory(VecDeque<ChatMessage>);
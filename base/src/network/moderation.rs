@@ -0,0 +1,356 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::{self, Display, Formatter},
+    fs,
+    net::IpAddr,
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use bevy::{prelude::*, scene::ron};
+use bevy_replicon::prelude::*;
+use bevy_replicon_renet::renet::{
+    transport::{NetcodeServerTransport, NETCODE_USER_DATA_BYTES},
+    ClientId as RenetClientId, RenetServer,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{game_paths::GamePaths, message::error_message};
+
+/// Kicks and bans clients on the server.
+///
+/// Like achievements, the ban list is stored next to the settings and shared between worlds.
+/// Clients are banned by their [`PlayerIdentity`] or IP since client IDs change on each connection.
+pub(super) struct ModerationPlugin;
+
+impl Plugin for ModerationPlugin {
+    fn build(&self, app: &mut App) {
+        let game_paths = app.world().resource::<GamePaths>();
+        let identity = PlayerIdentity::read_or_create(&game_paths.identity).unwrap_or_else(|e| {
+            error!("unable to persist player identity: {e:#}");
+            PlayerIdentity::random()
+        });
+
+        app.insert_resource(BanList::read(&game_paths.bans).unwrap_or_default())
+            .insert_resource(ClientIdentities::new(identity))
+            .insert_resource(identity)
+            .add_event::<ClientKick>()
+            .add_event::<ClientBan>()
+            .add_event::<ClientUnban>()
+            .add_systems(
+                PreUpdate,
+                (Self::track_identities, Self::enforce)
+                    .chain()
+                    .after(ServerSet::Receive)
+                    .run_if(server_running.and_then(resource_exists::<NetcodeServerTransport>)),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    (Self::kick, Self::ban)
+                        .run_if(server_running.and_then(resource_exists::<NetcodeServerTransport>)),
+                    Self::unban,
+                    Self::write
+                        .pipe(error_message)
+                        .run_if(resource_changed::<BanList>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+impl ModerationPlugin {
+    fn track_identities(
+        mut server_events: EventReader<ServerEvent>,
+        mut identities: ResMut<ClientIdentities>,
+        transport: Res<NetcodeServerTransport>,
+    ) {
+        for event in server_events.read() {
+            match *event {
+                ServerEvent::ClientConnected { client_id } => {
+                    let renet_id = RenetClientId::from_raw(client_id.get());
+                    if let Some(data) = transport.user_data(renet_id) {
+                        let identity = PlayerIdentity::from_data(&data);
+                        debug!("`{client_id:?}` connected as `{identity}`");
+                        identities.clients.insert(client_id, identity);
+                    }
+                }
+                ServerEvent::ClientDisconnected { client_id, .. } => {
+                    identities.clients.remove(&client_id);
+                }
+            }
+        }
+    }
+
+    /// Disconnects banned clients right after connection.
+    fn enforce(
+        mut server_events: EventReader<ServerEvent>,
+        mut server: ResMut<RenetServer>,
+        transport: Res<NetcodeServerTransport>,
+        ban_list: Res<BanList>,
+    ) {
+        for event in server_events.read() {
+            if let ServerEvent::ClientConnected { client_id } = *event {
+                let renet_id = RenetClientId::from_raw(client_id.get());
+                let identity = transport
+                    .user_data(renet_id)
+                    .map(|data| PlayerIdentity::from_data(&data));
+                let ip = transport.client_addr(renet_id).map(|addr| addr.ip());
+                if ban_list.contains(identity, ip) {
+                    info!("disconnecting banned `{client_id:?}`");
+                    server.disconnect(renet_id);
+                }
+            }
+        }
+    }
+
+    fn kick(mut kick_events: EventReader<ClientKick>, mut server: ResMut<RenetServer>) {
+        for event in kick_events.read() {
+            info!("kicking `{:?}`", event.0);
+            server.disconnect(RenetClientId::from_raw(event.0.get()));
+        }
+    }
+
+    fn ban(
+        mut ban_events: EventReader<ClientBan>,
+        mut server: ResMut<RenetServer>,
+        mut ban_list: ResMut<BanList>,
+        transport: Res<NetcodeServerTransport>,
+    ) {
+        for event in ban_events.read() {
+            let renet_id = RenetClientId::from_raw(event.client_id.get());
+            let ban = match event.kind {
+                BanKind::Player => {
+                    let Some(data) = transport.user_data(renet_id) else {
+                        error!("unable to get identity of `{:?}`", event.client_id);
+                        continue;
+                    };
+                    Ban::Player(PlayerIdentity::from_data(&data))
+                }
+                BanKind::Ip => {
+                    let Some(addr) = transport.client_addr(renet_id) else {
+                        error!("unable to get address of `{:?}`", event.client_id);
+                        continue;
+                    };
+                    Ban::Ip(addr.ip())
+                }
+            };
+
+            info!("banning `{:?}` by `{ban:?}`", event.client_id);
+            ban_list.0.insert(ban);
+            server.disconnect(renet_id);
+        }
+    }
+
+    fn unban(mut unban_events: EventReader<ClientUnban>, mut ban_list: ResMut<BanList>) {
+        for event in unban_events.read() {
+            info!("removing `{:?}` from ban list", event.0);
+            ban_list.0.remove(&event.0);
+        }
+    }
+
+    fn write(ban_list: Res<BanList>, game_paths: Res<GamePaths>) -> Result<()> {
+        // Avoid writing the list that was just read.
+        if ban_list.is_added() {
+            return Ok(());
+        }
+
+        ban_list.write(&game_paths.bans)
+    }
+}
+
+/// Clients that are not allowed to connect to the server.
+#[derive(Default, Deref, Deserialize, Resource, Serialize)]
+#[serde(default)]
+pub struct BanList(BTreeSet<Ban>);
+
+impl BanList {
+    fn contains(&self, identity: Option<PlayerIdentity>, ip: Option<IpAddr>) -> bool {
+        identity.is_some_and(|identity| self.0.contains(&Ban::Player(identity)))
+            || ip.is_some_and(|ip| self.0.contains(&Ban::Ip(ip)))
+    }
+
+    /// Will be initialed with defaults if the file does not exist.
+    fn read(file_name: &Path) -> Result<Self> {
+        info!("reading ban list from {file_name:?}");
+
+        match fs::read_to_string(file_name) {
+            Ok(content) => ron::from_str(&content)
+                .with_context(|| format!("unable to read ban list from {file_name:?}")),
+            Err(_) => Ok(Default::default()),
+        }
+    }
+
+    fn write(&self, file_name: &Path) -> Result<()> {
+        info!("writing ban list to {file_name:?}");
+
+        let content = ron::ser::to_string_pretty(&self, Default::default())
+            .context("unable to serialize ban list")?;
+
+        let parent_folder = file_name
+            .parent()
+            .expect("ban list filename should have a parent dir");
+
+        fs::create_dir_all(parent_folder)
+            .with_context(|| format!("unable to create {parent_folder:?}"))?;
+
+        fs::write(file_name, content)
+            .with_context(|| format!("unable to write ban list to {file_name:?}"))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Ban {
+    Player(PlayerIdentity),
+    Ip(IpAddr),
+}
+
+/// Identities of connected clients on the server.
+///
+/// Used to store per-player data in the world since client IDs change on each connection.
+#[derive(Resource)]
+pub(crate) struct ClientIdentities {
+    local: PlayerIdentity,
+    clients: HashMap<ClientId, PlayerIdentity>,
+}
+
+impl ClientIdentities {
+    fn new(local: PlayerIdentity) -> Self {
+        Self {
+            local,
+            clients: Default::default(),
+        }
+    }
+
+    /// Returns identity of the client.
+    ///
+    /// [`ClientId::SERVER`] corresponds to the local identity.
+    pub(crate) fn get(&self, client_id: ClientId) -> Option<PlayerIdentity> {
+        if client_id == ClientId::SERVER {
+            Some(self.local)
+        } else {
+            self.clients.get(&client_id).copied()
+        }
+    }
+
+    /// Returns a connected client with the identity.
+    pub(crate) fn client_id(&self, identity: PlayerIdentity) -> Option<ClientId> {
+        if identity == self.local {
+            Some(ClientId::SERVER)
+        } else {
+            self.clients
+                .iter()
+                .find(|(_, &client_identity)| client_identity == identity)
+                .map(|(&client_id, _)| client_id)
+        }
+    }
+}
+
+/// Random ID generated once per installation.
+///
+/// Sent to servers in the connection user data to identify the player across reconnects.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Reflect,
+    Resource,
+    Serialize,
+)]
+pub struct PlayerIdentity(u64);
+
+impl PlayerIdentity {
+    fn random() -> Self {
+        Self(rand::random())
+    }
+
+    /// Reads the identity from the file or creates a new one if it doesn't exist.
+    fn read_or_create(file_name: &Path) -> Result<Self> {
+        if let Ok(content) = fs::read_to_string(file_name) {
+            let id = u64::from_str_radix(content.trim(), 16)
+                .with_context(|| format!("unable to parse player identity from {file_name:?}"))?;
+            return Ok(Self(id));
+        }
+
+        let identity = Self::random();
+        info!("writing new player identity to {file_name:?}");
+        if let Some(parent_folder) = file_name.parent() {
+            fs::create_dir_all(parent_folder)
+                .with_context(|| format!("unable to create {parent_folder:?}"))?;
+        }
+        fs::write(file_name, identity.to_string())
+            .with_context(|| format!("unable to write player identity to {file_name:?}"))?;
+
+        Ok(identity)
+    }
+
+    /// Encodes the identity into netcode user data.
+    pub(super) fn to_data(self) -> [u8; NETCODE_USER_DATA_BYTES] {
+        let mut data = [0; NETCODE_USER_DATA_BYTES];
+        data[..8].copy_from_slice(&self.0.to_le_bytes());
+        data
+    }
+
+    fn from_data(data: &[u8; NETCODE_USER_DATA_BYTES]) -> Self {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&data[..8]);
+        Self(u64::from_le_bytes(bytes))
+    }
+}
+
+impl Display for PlayerIdentity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// Disconnects a client from the server.
+#[derive(Event)]
+pub struct ClientKick(pub ClientId);
+
+/// Disconnects a client and adds it to the [`BanList`].
+#[derive(Event)]
+pub struct ClientBan {
+    pub client_id: ClientId,
+    pub kind: BanKind,
+}
+
+#[derive(Clone, Copy)]
+pub enum BanKind {
+    Player,
+    Ip,
+}
+
+/// Removes an entry from the [`BanList`].
+#[derive(Event)]
+pub struct ClientUnban(pub Ban);
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    #[test]
+    fn ban_matching() {
+        let banned_ip = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 2));
+        let ban_list = BanList([Ban::Player(PlayerIdentity(1)), Ban::Ip(banned_ip)].into());
+
+        assert!(ban_list.contains(Some(PlayerIdentity(1)), None));
+        assert!(ban_list.contains(Some(PlayerIdentity(2)), Some(banned_ip)));
+        assert!(!ban_list.contains(Some(PlayerIdentity(2)), Some(Ipv4Addr::LOCALHOST.into())));
+        assert!(!ban_list.contains(None, None));
+    }
+
+    #[test]
+    fn identity_encoding() {
+        let identity = PlayerIdentity(u64::MAX - 1);
+        assert_eq!(PlayerIdentity::from_data(&identity.to_data()), identity);
+    }
+}
@@ -0,0 +1,627 @@
+use std::{
+    collections::HashMap,
+    io::{self, ErrorKind, Read, Write},
+    net::{IpAddr, TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver, TryRecvError},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, ensure, Context, Result};
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use bevy_replicon_renet::renet::{transport::NetcodeServerTransport, ClientId as RenetClientId};
+
+use super::{
+    chat::{ChatMessage, MAX_MESSAGE_LEN},
+    moderation::ClientKick,
+    shutdown::ServerShutdown,
+};
+use crate::{
+    console::{AppConsoleExt, ConsoleInput, ConsoleOutput, ConsoleSource},
+    game_world::{clock::GameClock, GameSave, WorldName},
+};
+
+/// Server administration from the terminal or over TCP.
+///
+/// Reads commands from [`StdinConsole`] and [`Rcon`] if they are inserted
+/// and registers server commands for all consoles.
+pub(super) struct ServerConsolePlugin;
+
+impl Plugin for ServerConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_console_command("list", "", "list connected players", Self::list)
+            .add_console_command("kick", "<client id>", "disconnect a player", Self::kick)
+            .add_console_command("save", "", "save the world", Self::save)
+            .add_console_command(
+                "say",
+                "<message>",
+                "send a message to all players",
+                Self::say,
+            )
+            .add_console_command(
+                "set-time",
+                "<hour>[:<minute>]",
+                "advance the clock to the time of day",
+                Self::set_time,
+            )
+            .add_console_command("stop", "", "save the world and stop the server", Self::stop)
+            .add_systems(
+                PreUpdate,
+                (
+                    Self::read_stdin.run_if(resource_exists::<StdinConsole>),
+                    Self::read_rcon.run_if(resource_exists::<Rcon>),
+                ),
+            )
+            .add_systems(
+                Last,
+                (
+                    Self::write_output.run_if(on_event::<ConsoleOutput>()),
+                    Self::flush_rcon.run_if(resource_exists::<Rcon>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+impl ServerConsolePlugin {
+    fn read_stdin(
+        mut commands: Commands,
+        mut input_events: EventWriter<ConsoleInput>,
+        console: Res<StdinConsole>,
+    ) {
+        let receiver = console.0.lock().expect("stdin receiver should be lockable");
+        loop {
+            match receiver.try_recv() {
+                Ok(line) => {
+                    input_events.send(ConsoleInput {
+                        source: ConsoleSource::Stdin,
+                        line,
+                    });
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    info!("stdin closed, disabling console");
+                    commands.remove_resource::<StdinConsole>();
+                    break;
+                }
+            }
+        }
+    }
+
+    fn read_rcon(mut input_events: EventWriter<ConsoleInput>, mut rcon: ResMut<Rcon>) {
+        rcon.accept();
+        for (index, line) in rcon.receive() {
+            input_events.send(ConsoleInput {
+                source: ConsoleSource::Rcon(index),
+                line,
+            });
+        }
+    }
+
+    fn write_output(mut output_events: EventReader<ConsoleOutput>, mut rcon: Option<ResMut<Rcon>>) {
+        for event in output_events.read() {
+            match event.source {
+                ConsoleSource::Stdin => println!("{}", event.text),
+                ConsoleSource::Rcon(index) => {
+                    if let Some(rcon) = &mut rcon {
+                        rcon.send(index, &event.text);
+                    }
+                }
+                ConsoleSource::Game => (),
+            }
+        }
+    }
+
+    fn flush_rcon(mut rcon: ResMut<Rcon>) {
+        rcon.flush();
+    }
+
+    fn list(world: &mut World, _args: &[&str]) -> Result<String> {
+        ensure!(
+            world.resource::<RepliconServer>().is_running(),
+            "server is not running"
+        );
+
+        let transport = world.get_resource::<NetcodeServerTransport>();
+        let players: Vec<_> = world
+            .resource::<ConnectedClients>()
+            .iter()
+            .map(|client| {
+                let client_id = client.id().get();
+                let addr = transport
+                    .and_then(|transport| transport.client_addr(RenetClientId::from_raw(client_id)))
+                    .map(|addr| addr.ip().to_string())
+                    .unwrap_or_else(|| "unknown address".to_string());
+                format!("{client_id} ({addr})")
+            })
+            .collect();
+
+        if players.is_empty() {
+            Ok("no players connected".to_string())
+        } else {
+            Ok(players.join("\n"))
+        }
+    }
+
+    fn kick(world: &mut World, args: &[&str]) -> Result<String> {
+        let &[id] = args else {
+            bail!("expected a client ID");
+        };
+        let id: u64 = id
+            .parse()
+            .with_context(|| format!("'{id}' is not a valid client ID"))?;
+        let client_id = ClientId::new(id);
+        ensure!(
+            world
+                .resource::<ConnectedClients>()
+                .iter()
+                .any(|client| client.id() == client_id),
+            "client {id} is not connected"
+        );
+
+        world.send_event(ClientKick(client_id));
+
+        Ok(format!("kicking {id}"))
+    }
+
+    fn save(world: &mut World, _args: &[&str]) -> Result<String> {
+        ensure!(world.contains_resource::<WorldName>(), "no world is loaded");
+        world.send_event(GameSave);
+
+        Ok("saving world".to_string())
+    }
+
+    fn say(world: &mut World, args: &[&str]) -> Result<String> {
+        ensure!(!args.is_empty(), "expected a message");
+        ensure!(
+            world.resource::<RepliconServer>().is_running(),
+            "server is not running"
+        );
+
+        let text: String = args.join(" ").chars().take(MAX_MESSAGE_LEN).collect();
+        world.send_event(ToClients {
+            mode: SendMode::Broadcast,
+            event: ChatMessage {
+                sender: ClientId::SERVER,
+                text,
+            },
+        });
+
+        Ok("message sent".to_string())
+    }
+
+    fn set_time(world: &mut World, args: &[&str]) -> Result<String> {
+        let &[time] = args else {
+            bail!("expected time in 'hour:minute' format");
+        };
+        let (hour, minute) = parse_time(time)?;
+        ensure!(
+            !world.resource::<RepliconClient>().is_connected(),
+            "only the server can change the time"
+        );
+
+        let mut clock = world
+            .query::<&mut GameClock>()
+            .get_single_mut(world)
+            .context("no world is loaded")?;
+        clock.advance_to(hour, minute);
+
+        Ok(format!("time set to {hour:02}:{minute:02}"))
+    }
+
+    fn stop(world: &mut World, _args: &[&str]) -> Result<String> {
+        ensure!(
+            world.resource::<RepliconServer>().is_running(),
+            "server is not running"
+        );
+        world.send_event(ServerShutdown { exit: true });
+
+        Ok("stopping server".to_string())
+    }
+}
+
+/// Parses time of day in `hour` or `hour:minute` format.
+fn parse_time(time: &str) -> Result<(u32, u32)> {
+    let (hour, minute) = time.split_once(':').unwrap_or((time, "0"));
+    let hour = hour
+        .parse()
+        .with_context(|| format!("'{hour}' is not a valid hour"))?;
+    let minute = minute
+        .parse()
+        .with_context(|| format!("'{minute}' is not a valid minute"))?;
+    ensure!(hour < 24, "hour should be less than 24");
+    ensure!(minute < 60, "minute should be less than 60");
+
+    Ok((hour, minute))
+}
+
+/// Reads console commands from the standard input.
+///
+/// Lines are read on a separate thread since reading from stdin blocks.
+#[derive(Resource)]
+pub struct StdinConsole(Mutex<Receiver<String>>);
+
+impl StdinConsole {
+    pub fn spawn() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in io::stdin().lines() {
+                match line {
+                    Ok(line) => {
+                        if sender.send(line).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        error!("unable to read stdin: {e}");
+                        break;
+                    }
+                }
+            }
+        });
+
+        Self(Mutex::new(receiver))
+    }
+}
+
+/// Maximum length of a line received over [`Rcon`].
+const MAX_RCON_LINE: usize = 1024;
+
+/// Maximum number of bytes waiting to be sent to a single [`Rcon`] connection.
+const MAX_RCON_OUTPUT: usize = 64 * 1024;
+
+/// Maximum number of simultaneous [`Rcon`] connections.
+const MAX_RCON_CONNECTIONS: usize = 4;
+
+/// Time a connection has to send the password.
+const RCON_AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of wrong passwords after which the address is locked out.
+const MAX_RCON_AUTH_FAILURES: u32 = 3;
+
+/// Time during which connections from a locked out address are rejected.
+const RCON_LOCKOUT: Duration = Duration::from_secs(60);
+
+/// Remote console over TCP.
+///
+/// Uses a line-based protocol: the first line should contain the password,
+/// each next line is a command and each command is answered with its output.
+/// Sockets are non-blocking and polled every frame, output is buffered
+/// until the socket accepts it.
+#[derive(Resource)]
+pub struct Rcon {
+    listener: TcpListener,
+    password: String,
+    connections: Vec<RconConnection>,
+    /// Number of wrong passwords and the time of the last one for each address.
+    failures: HashMap<IpAddr, (u32, Instant)>,
+    next_index: u64,
+}
+
+impl Rcon {
+    pub fn bind(ip: IpAddr, port: u16, password: String) -> Result<Self> {
+        ensure!(!password.is_empty(), "remote console requires a password");
+
+        info!("listening for remote console on {ip}:{port}");
+        let listener = TcpListener::bind((ip, port))
+            .with_context(|| format!("unable to listen on {ip}:{port}"))?;
+        listener.set_nonblocking(true)?;
+
+        Ok(Self {
+            listener,
+            password,
+            connections: Default::default(),
+            failures: Default::default(),
+            next_index: 0,
+        })
+    }
+
+    fn accept(&mut self) {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, addr)) => {
+                    if self.locked_out(addr.ip()) {
+                        info!("rejecting remote console connection from locked out {addr}");
+                        continue;
+                    }
+                    if self.connections.len() >= MAX_RCON_CONNECTIONS {
+                        warn!(
+                            "rejecting remote console connection from {addr}: too many connections"
+                        );
+                        continue;
+                    }
+                    if let Err(e) = stream.set_nonblocking(true) {
+                        error!("unable to configure remote console connection: {e}");
+                        continue;
+                    }
+                    info!("remote console connection {} from {addr}", self.next_index);
+                    self.connections.push(RconConnection {
+                        index: self.next_index,
+                        ip: addr.ip(),
+                        stream,
+                        connected_at: Instant::now(),
+                        buffer: Default::default(),
+                        output: Default::default(),
+                        authorized: false,
+                    });
+                    self.next_index += 1;
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    error!("unable to accept remote console connection: {e}");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Returns received commands from authorized connections and drops closed ones.
+    fn receive(&mut self) -> Vec<(u64, String)> {
+        let mut lines = Vec::new();
+        self.connections.retain_mut(|connection| {
+            let open = connection.read();
+            while let Some(line) = connection.next_line() {
+                let Some(line) = line else {
+                    warn!(
+                        "remote console connection {} sent too long line",
+                        connection.index
+                    );
+                    return false;
+                };
+                if connection.authorized {
+                    lines.push((connection.index, line));
+                } else if constant_time_eq(line.as_bytes(), self.password.as_bytes()) {
+                    info!("remote console connection {} authorized", connection.index);
+                    self.failures.remove(&connection.ip);
+                    connection.authorized = true;
+                    connection.send("authorized");
+                } else {
+                    info!(
+                        "remote console connection {} provided wrong password",
+                        connection.index
+                    );
+                    let (count, last) = self
+                        .failures
+                        .entry(connection.ip)
+                        .or_insert((0, Instant::now()));
+                    *count += 1;
+                    *last = Instant::now();
+                    connection.send("wrong password");
+                    connection.flush();
+                    return false;
+                }
+            }
+            if connection.buffer.len() > MAX_RCON_LINE {
+                warn!(
+                    "remote console connection {} sent too long line",
+                    connection.index
+                );
+                return false;
+            }
+            if !connection.authorized && connection.connected_at.elapsed() > RCON_AUTH_TIMEOUT {
+                info!(
+                    "remote console connection {} did not authorize in time",
+                    connection.index
+                );
+                return false;
+            }
+            if !open {
+                info!("remote console connection {} closed", connection.index);
+            }
+            open
+        });
+
+        lines
+    }
+
+    /// Returns `true` if the address sent too many wrong passwords recently.
+    fn locked_out(&mut self, ip: IpAddr) -> bool {
+        self.failures
+            .retain(|_, &mut (_, last)| last.elapsed() < RCON_LOCKOUT);
+        self.failures
+            .get(&ip)
+            .is_some_and(|&(count, _)| count >= MAX_RCON_AUTH_FAILURES)
+    }
+
+    /// Writes buffered output and drops connections that can't keep up.
+    fn flush(&mut self) {
+        self.connections.retain_mut(|connection| {
+            if !connection.flush() {
+                return false;
+            }
+            if connection.output.len() > MAX_RCON_OUTPUT {
+                warn!(
+                    "remote console connection {} does not read its output",
+                    connection.index
+                );
+                return false;
+            }
+            true
+        });
+    }
+
+    fn send(&mut self, index: u64, text: &str) {
+        if let Some(connection) = self
+            .connections
+            .iter_mut()
+            .find(|connection| connection.index == index)
+        {
+            connection.send(text);
+        }
+    }
+}
+
+struct RconConnection {
+    index: u64,
+    ip: IpAddr,
+    stream: TcpStream,
+    connected_at: Instant,
+    buffer: Vec<u8>,
+    output: Vec<u8>,
+    authorized: bool,
+}
+
+impl RconConnection {
+    /// Reads available bytes into the buffer.
+    ///
+    /// Stops once the buffer is larger than [`MAX_RCON_LINE`] to avoid unbounded growth.
+    /// Returns `false` if the connection was closed.
+    fn read(&mut self) -> bool {
+        let mut bytes = [0; 512];
+        while self.buffer.len() <= MAX_RCON_LINE {
+            match self.stream.read(&mut bytes) {
+                Ok(0) => return false,
+                Ok(len) => self.buffer.extend_from_slice(&bytes[..len]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return true,
+                Err(e) => {
+                    debug!("remote console connection {} failed: {e}", self.index);
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Takes the next complete line from the buffer.
+    ///
+    /// Returns `Some(None)` if the line is longer than [`MAX_RCON_LINE`].
+    fn next_line(&mut self) -> Option<Option<String>> {
+        let end = self.buffer.iter().position(|&byte| byte == b'\n')?;
+        let line: Vec<_> = self.buffer.drain(..=end).collect();
+        if line.len() > MAX_RCON_LINE {
+            return Some(None);
+        }
+        Some(Some(String::from_utf8_lossy(&line).trim().to_string()))
+    }
+
+    fn send(&mut self, text: &str) {
+        self.output.extend_from_slice(text.as_bytes());
+        self.output.push(b'\n');
+    }
+
+    /// Writes as much buffered output as the socket accepts.
+    ///
+    /// Returns `false` if the connection failed.
+    fn flush(&mut self) -> bool {
+        while !self.output.is_empty() {
+            match self.stream.write(&self.output) {
+                Ok(0) => return false,
+                Ok(len) => {
+                    self.output.drain(..len);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    error!(
+                        "unable to write to remote console connection {}: {e}",
+                        self.index
+                    );
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+/// Compares bytes in time that doesn't depend on the position of the first mismatch.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    #[test]
+    fn time_parsing() {
+        assert_eq!(parse_time("7").unwrap(), (7, 0));
+        assert_eq!(parse_time("18:45").unwrap(), (18, 45));
+        assert!(parse_time("24").is_err());
+        assert!(parse_time("12:60").is_err());
+        assert!(parse_time("noon").is_err());
+    }
+
+    #[test]
+    fn password_comparison() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret "));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+
+    #[test]
+    fn authorization() {
+        let mut rcon = Rcon::bind(Ipv4Addr::LOCALHOST.into(), 0, "secret".into()).unwrap();
+        let addr = rcon.listener.local_addr().unwrap();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(b"secret\nlist\n").unwrap();
+        let lines = poll_lines(&mut rcon);
+        assert_eq!(lines, [(0, "list".to_string())]);
+
+        for index in 1..=MAX_RCON_AUTH_FAILURES as u64 {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(b"wrong\n").unwrap();
+            poll_until(&mut rcon, |rcon| {
+                rcon.next_index == index + 1 && rcon.connections.len() == 1
+            });
+        }
+
+        let ip = addr.ip();
+        assert!(rcon.locked_out(ip));
+        let _stream = TcpStream::connect(addr).unwrap();
+        for _ in 0..10 {
+            rcon.accept();
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(
+            rcon.next_index,
+            MAX_RCON_AUTH_FAILURES as u64 + 1,
+            "locked out address shouldn't be accepted"
+        );
+    }
+
+    #[test]
+    fn long_line() {
+        let mut rcon = Rcon::bind(Ipv4Addr::LOCALHOST.into(), 0, "secret".into()).unwrap();
+        let addr = rcon.listener.local_addr().unwrap();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(b"secret\n").unwrap();
+        stream.write_all(&[b'a'; MAX_RCON_LINE + 1]).unwrap();
+        poll_until(&mut rcon, |rcon| rcon.next_index == 1);
+        poll_until(&mut rcon, |rcon| rcon.connections.is_empty());
+    }
+
+    fn poll_lines(rcon: &mut Rcon) -> Vec<(u64, String)> {
+        for _ in 0..100 {
+            rcon.accept();
+            let lines = rcon.receive();
+            if !lines.is_empty() {
+                return lines;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("remote console should receive lines");
+    }
+
+    fn poll_until(rcon: &mut Rcon, condition: impl Fn(&Rcon) -> bool) {
+        for _ in 0..100 {
+            rcon.accept();
+            rcon.receive();
+            if condition(rcon) {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("condition should be met");
+    }
+}
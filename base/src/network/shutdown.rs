@@ -0,0 +1,211 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashSet};
+use bevy_replicon::prelude::*;
+use bevy_replicon_renet::renet::{transport::NetcodeServerTransport, RenetServer};
+use serde::{Deserialize, Serialize};
+
+use super::ServerInfo;
+use crate::{
+    core::{
+        transition::{Transition, TransitionRequest},
+        GameState,
+    },
+    game_world::{GameSave, WorldName, WorldSaved},
+    message::Message,
+};
+
+/// Stops the hosted server without losing progress.
+///
+/// On [`ServerShutdown`] clients are notified, the world is saved and the server waits
+/// until all clients acknowledge the notice or [`SHUTDOWN_TIMEOUT`] passes
+/// before disconnecting everyone.
+pub(super) struct ShutdownPlugin;
+
+impl Plugin for ShutdownPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ServerShutdown>()
+            .add_client_event::<ShutdownAck>(ChannelKind::Ordered)
+            .add_server_event::<ShutdownNotice>(ChannelKind::Ordered)
+            .add_systems(
+                PreUpdate,
+                (
+                    Self::receive_acks
+                        .after(ServerSet::Receive)
+                        .run_if(resource_exists::<ShuttingDown>),
+                    Self::acknowledge
+                        .after(ClientSet::Receive)
+                        .run_if(client_connected),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    Self::start.run_if(on_event::<ServerShutdown>()),
+                    Self::finish.run_if(resource_exists::<ShuttingDown>),
+                )
+                    .chain()
+                    .before(ServerSet::Send)
+                    .run_if(server_running),
+            )
+            .add_systems(Last, Self::mark_saved.run_if(on_event::<WorldSaved>()))
+            .add_systems(
+                OnExit(GameState::InGame),
+                Self::cleanup.run_if(resource_exists::<ShuttingDown>),
+            );
+    }
+}
+
+/// Maximum time to wait for the save and client acknowledgments.
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl ShutdownPlugin {
+    fn start(
+        mut commands: Commands,
+        mut shutdown_events: EventReader<ServerShutdown>,
+        mut save_events: EventWriter<GameSave>,
+        mut notice_events: EventWriter<ToClients<ShutdownNotice>>,
+        connected_clients: Res<ConnectedClients>,
+        world_name: Option<Res<WorldName>>,
+        shutting_down: Option<Res<ShuttingDown>>,
+    ) {
+        let Some(&event) = shutdown_events.read().last() else {
+            return;
+        };
+        if shutting_down.is_some() {
+            debug!("ignoring shutdown request because shutdown is in progress");
+            return;
+        }
+
+        info!("shutting down server");
+        notice_events.send(ToClients {
+            mode: SendMode::Broadcast,
+            event: ShutdownNotice,
+        });
+
+        let saved = world_name.is_none();
+        if !saved {
+            save_events.send_default();
+        }
+
+        commands.insert_resource(ShuttingDown {
+            exit: event.exit,
+            pending: connected_clients.iter().map(|client| client.id()).collect(),
+            saved,
+            disconnected: false,
+            timer: Timer::new(SHUTDOWN_TIMEOUT, TimerMode::Once),
+        });
+    }
+
+    fn receive_acks(
+        mut ack_events: EventReader<FromClient<ShutdownAck>>,
+        mut server_events: EventReader<ServerEvent>,
+        mut shutting_down: ResMut<ShuttingDown>,
+    ) {
+        for FromClient { client_id, .. } in ack_events.read() {
+            debug!("`{client_id:?}` acknowledged shutdown");
+            shutting_down.pending.remove(client_id);
+        }
+
+        for event in server_events.read() {
+            if let ServerEvent::ClientDisconnected { client_id, .. } = event {
+                shutting_down.pending.remove(client_id);
+            }
+        }
+    }
+
+    fn mark_saved(mut shutting_down: Option<ResMut<ShuttingDown>>) {
+        if let Some(shutting_down) = &mut shutting_down {
+            debug!("world saved before shutdown");
+            shutting_down.saved = true;
+        }
+    }
+
+    /// Disconnects everyone once the world is saved and all clients are notified.
+    ///
+    /// Runs before sending to let disconnect packets leave in the same frame.
+    fn finish(
+        mut server: ResMut<RenetServer>,
+        mut exit_events: EventWriter<AppExit>,
+        mut transition_events: EventWriter<TransitionRequest>,
+        mut shutting_down: ResMut<ShuttingDown>,
+        time: Res<Time<Real>>,
+    ) {
+        if shutting_down.disconnected {
+            return;
+        }
+
+        shutting_down.timer.tick(time.delta());
+        let ready = shutting_down.saved && shutting_down.pending.is_empty();
+        if !ready && !shutting_down.timer.finished() {
+            return;
+        }
+
+        if !shutting_down.saved {
+            warn!("world wasn't saved in {SHUTDOWN_TIMEOUT:?}, shutting down anyway");
+        } else if !shutting_down.pending.is_empty() {
+            warn!(
+                "{} clients didn't acknowledge shutdown in {SHUTDOWN_TIMEOUT:?}",
+                shutting_down.pending.len()
+            );
+        }
+
+        info!("disconnecting all clients");
+        server.disconnect_all();
+        shutting_down.disconnected = true;
+
+        if shutting_down.exit {
+            info!("exiting after shutdown");
+            exit_events.send_default();
+        } else {
+            // The world is already saved, so skip the guard.
+            transition_events.send(TransitionRequest::forced(Transition::Game(GameState::Menu)));
+        }
+    }
+
+    fn cleanup(mut commands: Commands) {
+        commands.remove_resource::<RenetServer>();
+        commands.remove_resource::<NetcodeServerTransport>();
+        commands.remove_resource::<ServerInfo>();
+        commands.remove_resource::<ShuttingDown>();
+    }
+
+    fn acknowledge(
+        mut notice_events: EventReader<ShutdownNotice>,
+        mut ack_events: EventWriter<ShutdownAck>,
+        mut message_events: EventWriter<Message>,
+    ) {
+        if notice_events.read().count() > 0 {
+            info!("server is shutting down");
+            ack_events.send(ShutdownAck);
+            message_events.send(Message("The server is shutting down.".to_string()));
+        }
+    }
+}
+
+/// Requests a graceful shutdown of the hosted server.
+///
+/// Ignored if the server is not running.
+#[derive(Clone, Copy, Default, Event)]
+pub struct ServerShutdown {
+    /// Exit the app after the shutdown instead of returning to the main menu.
+    pub exit: bool,
+}
+
+/// Sent by the server to inform clients that it's about to stop.
+#[derive(Deserialize, Event, Serialize)]
+struct ShutdownNotice;
+
+/// Confirms that a client received [`ShutdownNotice`].
+#[derive(Deserialize, Event, Serialize)]
+struct ShutdownAck;
+
+/// Shutdown in progress.
+#[derive(Resource)]
+pub struct ShuttingDown {
+    exit: bool,
+    pending: HashSet<ClientId>,
+    saved: bool,
+    disconnected: bool,
+    timer: Timer,
+}
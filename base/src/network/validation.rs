@@ -0,0 +1,349 @@
+use std::{any, marker::PhantomData, time::Duration};
+
+use bevy::{
+    ecs::event::ManualEventReader,
+    prelude::*,
+    time::common_conditions::on_timer,
+    utils::{get_short_name, HashMap},
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::moderation::ClientKick;
+use crate::message::Message;
+
+/// Protects the server from misbehaving clients.
+///
+/// Client events registered via [`AppValidationExt::limit_client_event`] are filtered by rate.
+/// Systems that check ownership or ranges report failed checks with [`ClientViolation`].
+/// Clients that collect too many violations are disconnected with [`DisconnectReason`].
+pub(super) struct ValidationPlugin;
+
+impl Plugin for ValidationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Strikes>()
+            .add_event::<ClientViolation>()
+            .add_server_event::<DisconnectReason>(ChannelKind::Ordered)
+            .configure_sets(
+                PreUpdate,
+                ValidationSet
+                    .after(ServerSet::Receive)
+                    .run_if(server_running),
+            )
+            .add_systems(
+                PreUpdate,
+                Self::receive_reason
+                    .after(ClientSet::Receive)
+                    .run_if(client_connected),
+            )
+            .add_systems(
+                PostUpdate,
+                (Self::strike, Self::kick_offenders)
+                    .chain()
+                    .run_if(server_running),
+            )
+            .add_systems(
+                FixedUpdate,
+                Self::forgive
+                    .run_if(on_timer(STRIKE_WINDOW))
+                    .run_if(server_running),
+            );
+    }
+}
+
+/// Number of violations after which the client is disconnected.
+const MAX_STRIKES: u32 = 5;
+
+/// Violations are forgotten after this period.
+const STRIKE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Delay between sending [`DisconnectReason`] and disconnecting to let the message arrive.
+const KICK_DELAY: Duration = Duration::from_millis(500);
+
+impl ValidationPlugin {
+    fn strike(
+        mut violation_events: EventReader<ClientViolation>,
+        mut reason_events: EventWriter<ToClients<DisconnectReason>>,
+        mut strikes: ResMut<Strikes>,
+    ) {
+        for violation in violation_events.read() {
+            // Host can't cheat on its own server.
+            if violation.client_id == ClientId::SERVER {
+                continue;
+            }
+
+            let count = strikes.counts.entry(violation.client_id).or_default();
+            *count += 1;
+            warn!(
+                "`{:?}` violation {count}/{MAX_STRIKES}: {}",
+                violation.client_id, violation.reason
+            );
+
+            if *count == MAX_STRIKES {
+                info!(
+                    "disconnecting `{:?}` for repeated violations",
+                    violation.client_id
+                );
+                reason_events.send(ToClients {
+                    mode: SendMode::Direct(violation.client_id),
+                    event: DisconnectReason(violation.reason.clone()),
+                });
+                strikes
+                    .pending_kicks
+                    .push((violation.client_id, Timer::new(KICK_DELAY, TimerMode::Once)));
+            }
+        }
+    }
+
+    fn kick_offenders(
+        mut kick_events: EventWriter<ClientKick>,
+        mut strikes: ResMut<Strikes>,
+        time: Res<Time<Real>>,
+    ) {
+        strikes.pending_kicks.retain_mut(|(client_id, timer)| {
+            if timer.tick(time.delta()).finished() {
+                kick_events.send(ClientKick(*client_id));
+                return false;
+            }
+            true
+        });
+    }
+
+    fn forgive(mut strikes: ResMut<Strikes>) {
+        if !strikes.counts.is_empty() {
+            debug!("forgiving violations of {} clients", strikes.counts.len());
+            strikes.counts.clear();
+        }
+    }
+
+    fn receive_reason(
+        mut reason_events: EventReader<DisconnectReason>,
+        mut message_events: EventWriter<Message>,
+    ) {
+        for reason in reason_events.read() {
+            info!("server disconnects us: {}", reason.0);
+            message_events.send(Message(format!("Disconnected by server: {}", reason.0)));
+        }
+    }
+
+    /// Drops events that exceed the rate limit of their client.
+    ///
+    /// Only events received since the last run are counted.
+    fn limit_rate<E: Event>(
+        mut reader: Local<ManualEventReader<FromClient<E>>>,
+        mut events: ResMut<Events<FromClient<E>>>,
+        mut violation_events: EventWriter<ClientViolation>,
+        mut limit: ResMut<RateLimit<E>>,
+        time: Res<Time<Real>>,
+    ) {
+        if limit.timer.tick(time.delta()).just_finished() {
+            limit.counts.clear();
+        }
+
+        let mut received = 0;
+        let mut rejected = Vec::new();
+        for (index, event) in reader.read(&events).enumerate() {
+            received += 1;
+            if event.client_id == ClientId::SERVER {
+                continue;
+            }
+
+            let count = limit.counts.entry(event.client_id).or_default();
+            *count += 1;
+            if *count > limit.max_per_second {
+                // Report only once per window to avoid instant disconnects on a single burst.
+                if *count == limit.max_per_second + 1 {
+                    violation_events.send(ClientViolation {
+                        client_id: event.client_id,
+                        reason: format!(
+                            "exceeded {} `{}` per second",
+                            limit.max_per_second,
+                            get_short_name(any::type_name::<E>())
+                        ),
+                    });
+                }
+                rejected.push(index);
+            }
+        }
+
+        if rejected.is_empty() {
+            return;
+        }
+
+        // Events can't be removed selectively, so the new ones are sent again without rejected.
+        // Events from previous updates are dropped since they were already read.
+        let mut drained: Vec<_> = events.drain().collect();
+        let new_events = drained.split_off(drained.len() - received);
+        for (index, event) in new_events.into_iter().enumerate() {
+            if !rejected.contains(&index) {
+                events.send(event);
+            }
+        }
+
+        // Skip sent events to avoid counting them twice.
+        reader.clear(&events);
+    }
+}
+
+pub trait AppValidationExt {
+    /// Limits how many events of this type each client can send per second.
+    ///
+    /// Excess events are dropped and reported as [`ClientViolation`].
+    /// Systems that read these events on server should run after [`ValidationSet`].
+    fn limit_client_event<E: Event>(&mut self, max_per_second: u32) -> &mut Self;
+}
+
+impl AppValidationExt for App {
+    fn limit_client_event<E: Event>(&mut self, max_per_second: u32) -> &mut Self {
+        self.insert_resource(RateLimit::<E>::new(max_per_second))
+            .add_systems(
+                PreUpdate,
+                ValidationPlugin::limit_rate::<E>.in_set(ValidationSet),
+            )
+    }
+}
+
+/// Filters received client events.
+///
+/// Runs in [`PreUpdate`] after [`ServerSet::Receive`].
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct ValidationSet;
+
+/// Failed server-side check of a client request, like building on a lot without rights.
+#[derive(Event)]
+pub(crate) struct ClientViolation {
+    pub(crate) client_id: ClientId,
+    pub(crate) reason: String,
+}
+
+/// Explains to a client why it's about to be disconnected.
+#[derive(Deserialize, Event, Serialize)]
+pub struct DisconnectReason(pub String);
+
+#[derive(Default, Resource)]
+struct Strikes {
+    counts: HashMap<ClientId, u32>,
+    pending_kicks: Vec<(ClientId, Timer)>,
+}
+
+#[derive(Resource)]
+struct RateLimit<E> {
+    max_per_second: u32,
+    counts: HashMap<ClientId, u32>,
+    timer: Timer,
+    marker: PhantomData<E>,
+}
+
+impl<E> RateLimit<E> {
+    fn new(max_per_second: u32) -> Self {
+        Self {
+            max_per_second,
+            counts: Default::default(),
+            timer: Timer::new(Duration::from_secs(1), TimerMode::Repeating),
+            marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limit() {
+        const MAX_PER_SECOND: u32 = 3;
+        const SENT: usize = 5;
+
+        let mut app = App::new();
+        app.init_resource::<Time<Real>>()
+            .init_resource::<Processed>()
+            .add_event::<ClientViolation>()
+            .add_event::<FromClient<DummyEvent>>()
+            .insert_resource(RateLimit::<DummyEvent>::new(MAX_PER_SECOND))
+            .add_systems(
+                Update,
+                (ValidationPlugin::limit_rate::<DummyEvent>, process).chain(),
+            );
+
+        let client_id = ClientId::new(1);
+        for _ in 0..SENT {
+            app.world_mut().send_event(FromClient {
+                client_id,
+                event: DummyEvent,
+            });
+        }
+        app.world_mut().send_event(FromClient {
+            client_id: ClientId::SERVER,
+            event: DummyEvent,
+        });
+
+        for _ in 0..5 {
+            app.update();
+        }
+
+        let processed = app.world().resource::<Processed>();
+        assert_eq!(processed.client, MAX_PER_SECOND as usize);
+        assert_eq!(processed.server, 1, "server events shouldn't be limited");
+    }
+
+    #[test]
+    fn processed_once() {
+        const SENT: usize = 5;
+
+        let mut app = App::new();
+        app.init_resource::<Time<Real>>()
+            .init_resource::<Processed>()
+            .add_event::<ClientViolation>()
+            .add_event::<FromClient<DummyEvent>>()
+            .insert_resource(RateLimit::<DummyEvent>::new(SENT as u32))
+            .add_systems(
+                Update,
+                (ValidationPlugin::limit_rate::<DummyEvent>, process).chain(),
+            );
+
+        let client_id = ClientId::new(1);
+        for _ in 0..5 {
+            for _ in 0..SENT {
+                app.world_mut().send_event(FromClient {
+                    client_id,
+                    event: DummyEvent,
+                });
+            }
+            app.update();
+
+            let violations = app.world().resource::<Events<ClientViolation>>();
+            assert!(
+                violations.is_empty(),
+                "events within the limit shouldn't be reported"
+            );
+
+            // Start a new window.
+            app.world_mut()
+                .resource_mut::<RateLimit<DummyEvent>>()
+                .counts
+                .clear();
+        }
+
+        let processed = app.world().resource::<Processed>();
+        assert_eq!(processed.client, SENT * 5);
+    }
+
+    fn process(mut events: EventReader<FromClient<DummyEvent>>, mut processed: ResMut<Processed>) {
+        for event in events.read() {
+            if event.client_id == ClientId::SERVER {
+                processed.server += 1;
+            } else {
+                processed.client += 1;
+            }
+        }
+    }
+
+    #[derive(Event)]
+    struct DummyEvent;
+
+    #[derive(Default, Resource)]
+    struct Processed {
+        client: usize,
+        server: usize,
+    }
+}
@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+use strum::Display;
+
+use crate::{
+    core::GameState,
+    game_world::{
+        city::ActiveCity,
+        family::{FamilyMembers, SelectedFamily},
+        WorldState,
+    },
+};
+
+/// Tracks what the player is doing and reports it to registered [`PresenceBackend`]s.
+///
+/// Does nothing if no backends are registered via [`AppPresenceExt::add_presence_backend`].
+pub(super) struct PresencePlugin;
+
+impl Plugin for PresencePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PresenceBackends>()
+            .init_resource::<CurrentActivity>()
+            .add_systems(
+                PostUpdate,
+                (Self::update_activity, Self::notify_backends)
+                    .chain()
+                    .run_if(|backends: Res<PresenceBackends>| !backends.is_empty()),
+            );
+    }
+}
+
+impl PresencePlugin {
+    fn update_activity(
+        mut current_activity: ResMut<CurrentActivity>,
+        game_state: Res<State<GameState>>,
+        world_state: Option<Res<State<WorldState>>>,
+        cities: Query<&Name, With<ActiveCity>>,
+        families: Query<(&Name, &FamilyMembers), With<SelectedFamily>>,
+    ) {
+        let city_name = || cities.get_single().map(ToString::to_string).ok();
+        let activity = match (game_state.get(), world_state.as_deref().map(State::get)) {
+            (GameState::InGame, Some(WorldState::FamilyEditor)) => Activity::EditingFamily,
+            (GameState::InGame, Some(WorldState::City)) => match city_name() {
+                Some(city) => Activity::Building { city },
+                None => return,
+            },
+            (GameState::InGame, Some(WorldState::Spectator)) => match city_name() {
+                Some(city) => Activity::Spectating { city },
+                None => return,
+            },
+            (GameState::InGame, Some(WorldState::Family)) => {
+                let Ok((name, members)) = families.get_single() else {
+                    return;
+                };
+                Activity::Playing {
+                    family: name.to_string(),
+                    members: members.len(),
+                }
+            }
+            _ => Activity::Menu,
+        };
+
+        if current_activity.0 != activity {
+            debug!("changing activity to '{activity}'");
+            current_activity.0 = activity;
+        }
+    }
+
+    fn notify_backends(
+        current_activity: Res<CurrentActivity>,
+        mut backends: ResMut<PresenceBackends>,
+    ) {
+        if current_activity.is_changed() {
+            for backend in backends.iter_mut() {
+                backend.set_activity(&current_activity);
+            }
+        }
+    }
+}
+
+/// Platform-specific integration that displays the current activity, like Discord or Steam.
+pub trait PresenceBackend: Send + Sync + 'static {
+    /// Called on startup and on each activity change.
+    fn set_activity(&mut self, activity: &Activity);
+}
+
+pub trait AppPresenceExt {
+    /// Registers a backend that will be notified about activity changes.
+    fn add_presence_backend(&mut self, backend: impl PresenceBackend) -> &mut Self;
+}
+
+impl AppPresenceExt for App {
+    fn add_presence_backend(&mut self, backend: impl PresenceBackend) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(PresenceBackends::default)
+            .push(Box::new(backend));
+        self
+    }
+}
+
+#[derive(Resource, Default, Deref, DerefMut)]
+struct PresenceBackends(Vec<Box<dyn PresenceBackend>>);
+
+/// What the player is currently doing.
+#[derive(Resource, Default, Deref)]
+pub struct CurrentActivity(Activity);
+
+#[derive(Clone, Debug, Default, Display, PartialEq)]
+pub enum Activity {
+    #[default]
+    #[strum(serialize = "In menu")]
+    Menu,
+    #[strum(serialize = "Creating a family")]
+    EditingFamily,
+    #[strum(to_string = "Building in {city}")]
+    Building { city: String },
+    #[strum(to_string = "Playing {family} ({members} members)")]
+    Playing { family: String, members: usize },
+    #[strum(to_string = "Watching {city}")]
+    Spectating { city: String },
+}
@@ -0,0 +1,87 @@
+use bevy::{
+    app::MainScheduleOrder,
+    diagnostic::{
+        Diagnostic, DiagnosticPath, Diagnostics, EntityCountDiagnosticsPlugin,
+        FrameTimeDiagnosticsPlugin, RegisterDiagnostic,
+    },
+    ecs::schedule::{InternedScheduleLabel, ScheduleLabel},
+    prelude::*,
+    utils::Instant,
+};
+use strum::{EnumIter, IntoEnumIterator};
+
+/// Collects diagnostics for the performance overlay.
+///
+/// In addition to built-in diagnostics measures time spent in each main schedule.
+pub(super) struct ProfilingPlugin;
+
+impl Plugin for ProfilingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((FrameTimeDiagnosticsPlugin, EntityCountDiagnosticsPlugin))
+            .init_resource::<LastMark>();
+
+        for mark in ScheduleMark::iter() {
+            app.register_diagnostic(Diagnostic::new(mark.diagnostic_path()).with_suffix("ms"))
+                .add_systems(mark, measure(mark));
+
+            let mut order = app.world_mut().resource_mut::<MainScheduleOrder>();
+            order.insert_after(mark.schedule(), mark);
+        }
+    }
+}
+
+/// Returns a system that records the time since the previous mark.
+fn measure(mark: ScheduleMark) -> impl FnMut(Diagnostics, Res<Time<Real>>, ResMut<LastMark>) {
+    move |mut diagnostics, time, mut last_mark| {
+        let now = Instant::now();
+        let previous = if mark == ScheduleMark::First {
+            // Time updates at the beginning of the frame.
+            time.last_update()
+        } else {
+            last_mark.0
+        };
+
+        if let Some(previous) = previous {
+            diagnostics.add_measurement(&mark.diagnostic_path(), || {
+                (now - previous).as_secs_f64() * 1000.0
+            });
+        }
+        last_mark.0 = Some(now);
+    }
+}
+
+/// Schedule that runs right after the corresponding main schedule to measure its time.
+#[derive(ScheduleLabel, Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter)]
+pub enum ScheduleMark {
+    First,
+    PreUpdate,
+    Update,
+    PostUpdate,
+    Last,
+}
+
+impl ScheduleMark {
+    pub fn diagnostic_path(self) -> DiagnosticPath {
+        match self {
+            ScheduleMark::First => DiagnosticPath::const_new("schedule/first"),
+            ScheduleMark::PreUpdate => DiagnosticPath::const_new("schedule/pre_update"),
+            ScheduleMark::Update => DiagnosticPath::const_new("schedule/update"),
+            ScheduleMark::PostUpdate => DiagnosticPath::const_new("schedule/post_update"),
+            ScheduleMark::Last => DiagnosticPath::const_new("schedule/last"),
+        }
+    }
+
+    fn schedule(self) -> InternedScheduleLabel {
+        match self {
+            ScheduleMark::First => First.intern(),
+            ScheduleMark::PreUpdate => PreUpdate.intern(),
+            ScheduleMark::Update => Update.intern(),
+            ScheduleMark::PostUpdate => PostUpdate.intern(),
+            ScheduleMark::Last => Last.intern(),
+        }
+    }
+}
+
+/// Time of the last executed [`ScheduleMark`].
+#[derive(Default, Resource)]
+struct LastMark(Option<Instant>);
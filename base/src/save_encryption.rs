@@ -0,0 +1,127 @@
+//! At-rest encryption of world saves.
+//!
+//! Allows server operators to keep player worlds on shared hosts.
+//! The key is derived from a host passphrase, so it's never stored on disk.
+
+use std::{fs::File, io::Read, path::Path};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use argon2::Argon2;
+use bevy::prelude::*;
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    XChaCha20Poly1305, XNonce,
+};
+
+/// Prefix of encrypted saves to distinguish them from plain scenes.
+const MAGIC: &[u8] = b"HARMONIA-ENCRYPTED-1\n";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+/// Passphrase for encrypting the current world on save and decrypting it on load.
+///
+/// Saves are written unencrypted if the resource is missing.
+#[derive(Resource)]
+pub struct SavePassphrase(pub String);
+
+/// Returns `true` if the save was written by [`encrypt`].
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Returns `true` if the save file at the path is encrypted.
+///
+/// Reads only the header to let UI ask for the passphrase before loading.
+pub fn is_file_encrypted(path: &Path) -> Result<bool> {
+    let file = File::open(path).with_context(|| format!("unable to open {path:?}"))?;
+    let mut header = Vec::with_capacity(MAGIC.len());
+    file.take(MAGIC.len() as u64)
+        .read_to_end(&mut header)
+        .with_context(|| format!("unable to read {path:?}"))?;
+
+    Ok(is_encrypted(&header))
+}
+
+/// Encrypts the save with a key derived from the passphrase.
+///
+/// A new salt and nonce are generated on each call.
+pub fn encrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let salt: [u8; SALT_LEN] = rand::random();
+    let nonce: [u8; NONCE_LEN] = rand::random();
+
+    let cipher = cipher(passphrase, &salt)?;
+    let ciphertext = cipher
+        .encrypt(XNonce::from_slice(&nonce), data)
+        .map_err(|_| anyhow!("unable to encrypt world"))?;
+
+    let mut encrypted = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    encrypted.extend_from_slice(MAGIC);
+    encrypted.extend_from_slice(&salt);
+    encrypted.extend_from_slice(&nonce);
+    encrypted.extend_from_slice(&ciphertext);
+
+    Ok(encrypted)
+}
+
+/// Decrypts a save written by [`encrypt`].
+///
+/// Fails with a dedicated message on a wrong passphrase or a corrupted file.
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    ensure!(is_encrypted(data), "world is not encrypted");
+    let data = &data[MAGIC.len()..];
+    ensure!(
+        data.len() >= SALT_LEN + NONCE_LEN,
+        "encrypted world is truncated"
+    );
+
+    let (salt, data) = data.split_at(SALT_LEN);
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let cipher = cipher(passphrase, salt)?;
+    let Ok(decrypted) = cipher.decrypt(XNonce::from_slice(nonce), ciphertext) else {
+        bail!("wrong passphrase or the world is corrupted");
+    };
+
+    Ok(decrypted)
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<XChaCha20Poly1305> {
+    let mut key = [0; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("{e}"))
+        .context("unable to derive key from passphrase")?;
+
+    Ok(XChaCha20Poly1305::new(&key.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let data = b"(resources: {}, entities: {})";
+        let encrypted = encrypt(data, "secret")?;
+        assert!(is_encrypted(&encrypted));
+        assert!(!is_encrypted(data));
+        assert_eq!(decrypt(&encrypted, "secret")?, data);
+
+        Ok(())
+    }
+
+    #[test]
+    fn wrong_passphrase() -> Result<()> {
+        let encrypted = encrypt(b"world", "secret")?;
+        assert!(decrypt(&encrypted, "guess").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn truncated() -> Result<()> {
+        let encrypted = encrypt(b"world", "secret")?;
+        assert!(decrypt(&encrypted[..MAGIC.len() + 4], "secret").is_err());
+
+        Ok(())
+    }
+}
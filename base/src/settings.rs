@@ -1,17 +1,17 @@
-use std::{fs, path::Path};
+use std::{fs, ops::RangeInclusive, path::Path};
 
-use anyhow::{Context, Result};
+use anyhow::{ensure, Context, Result};
 use avian3d::prelude::*;
 use bevy::{
     color::palettes::css::DARK_RED, pbr::wireframe::WireframeConfig, prelude::*, scene::ron,
-    utils::HashMap, window::WindowMode,
+    utils::HashMap,
 };
 use leafwing_input_manager::{prelude::*, user_input::InputKind};
 use serde::{Deserialize, Serialize};
-use strum::Display;
+use strum::{Display, EnumIter};
 use vleue_navigator::prelude::*;
 
-use super::{game_paths::GamePaths, message::error_message};
+use super::{game_paths::GamePaths, game_world::simulation, message::error_message};
 
 pub(super) struct SettingsPlugin;
 
@@ -41,18 +41,12 @@ impl SettingsPlugin {
         mut config_store: ResMut<GizmoConfigStore>,
         mut wireframe_config: ResMut<WireframeConfig>,
         mut input_map: ResMut<InputMap<Action>>,
+        mut gamepad_settings: ResMut<GamepadSettings>,
         settings: Res<Settings>,
-        mut windows: Query<&mut Window>,
     ) {
         info!("applying settings");
 
-        let mut window = windows.single_mut();
-        if settings.video.fullscreen {
-            window.mode = WindowMode::Fullscreen;
-        } else {
-            window.mode = WindowMode::Windowed;
-        }
-
+        commands.insert_resource(GlobalVolume::new(settings.audio.master_volume));
         wireframe_config.global = settings.developer.wireframe;
         config_store.config_mut::<PhysicsGizmos>().0.enabled = settings.developer.colliders;
         if settings.developer.nav_mesh {
@@ -61,6 +55,11 @@ impl SettingsPlugin {
             commands.remove_resource::<NavMeshesDebug>();
         }
 
+        let deadzone = settings.controls.gamepad_deadzone;
+        let axis_settings = &mut gamepad_settings.default_axis_settings;
+        axis_settings.set_deadzone_upperbound(deadzone);
+        axis_settings.set_deadzone_lowerbound(-deadzone);
+
         input_map.clear();
         for (&action, inputs) in &settings.controls.mappings {
             input_map.insert_one_to_many(action, inputs.iter().cloned());
@@ -76,9 +75,13 @@ pub struct SettingsApply;
 #[serde(default)]
 pub struct Settings {
     pub video: VideoSettings,
-    #[reflect(ignore)]
+    pub audio: AudioSettings,
     pub controls: ControlsSettings,
+    pub gameplay: GameplaySettings,
     pub developer: DeveloperSettings,
+    pub server: ServerSettings,
+    /// Game version for which the changelog was shown.
+    pub last_version: String,
 }
 
 impl Settings {
@@ -88,16 +91,27 @@ impl Settings {
         info!("reading settings from {file_name:?}");
 
         match fs::read_to_string(file_name) {
-            Ok(content) => ron::from_str::<Settings>(&content)
-                .with_context(|| format!("unable to read settings from {file_name:?}")),
+            Ok(content) => {
+                let mut settings = ron::from_str::<Settings>(&content)
+                    .with_context(|| format!("unable to read settings from {file_name:?}"))?;
+                settings.clamp();
+                Ok(settings)
+            }
             Err(_) => Ok(Settings::default()),
         }
     }
 
+    /// Brings manually edited values back into the ranges allowed by the menu.
+    fn clamp(&mut self) {
+        self.audio.clamp();
+        self.controls.clamp();
+        self.gameplay.clamp();
+    }
+
     /// Saves settings on disk under.
     ///
     /// Automatically creates all parent folders.
-    fn write(&self, file_name: &Path) -> Result<()> {
+    pub fn write(&self, file_name: &Path) -> Result<()> {
         info!("writing settings to {file_name:?}");
 
         let content = ron::ser::to_string_pretty(&self, Default::default())
@@ -120,12 +134,125 @@ impl Settings {
 pub struct VideoSettings {
     /// TODO: Replace with combobox for all window modes.
     pub fullscreen: bool,
+
+    /// Index of the monitor used in fullscreen mode.
+    pub monitor: usize,
+
+    pub window: WindowState,
+}
+
+impl VideoSettings {
+    /// Allowed range for [`Self::monitor`].
+    ///
+    /// Out of bounds indices fall back to the current monitor.
+    pub const MONITOR_RANGE: RangeInclusive<usize> = 0..=7;
+}
+
+/// Window state from the last session.
+///
+/// Not exposed in the menu, updated on exit.
+#[derive(Clone, Default, Deserialize, PartialEq, Reflect, Serialize)]
+#[serde(default)]
+pub struct WindowState {
+    /// Logical size in windowed mode.
+    pub size: Option<Vec2>,
+
+    /// Position in windowed mode.
+    pub position: Option<IVec2>,
+
+    /// Index of the monitor on which the window was located.
+    pub monitor: Option<usize>,
+
+    pub maximized: bool,
+}
+
+#[derive(Clone, Deserialize, PartialEq, Reflect, Serialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    /// Multiplier for all sounds.
+    pub master_volume: f32,
+
+    /// Multiplier for ambient sounds of the city.
+    pub ambience_volume: f32,
+}
+
+impl AudioSettings {
+    pub const VOLUME_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+
+    fn clamp(&mut self) {
+        let default = Self::default();
+        self.master_volume = clamp_or(
+            self.master_volume,
+            Self::VOLUME_RANGE,
+            default.master_volume,
+        );
+        self.ambience_volume = clamp_or(
+            self.ambience_volume,
+            Self::VOLUME_RANGE,
+            default.ambience_volume,
+        );
+    }
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            ambience_volume: 0.7,
+        }
+    }
 }
 
-#[derive(Clone, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Deserialize, PartialEq, Reflect, Serialize)]
 #[serde(default)]
 pub struct ControlsSettings {
+    #[reflect(ignore)]
     pub mappings: HashMap<Action, Vec<InputKind>>,
+
+    /// Multiplier for camera rotation speed.
+    pub rotation_sensitivity: f32,
+
+    /// Multiplier for camera zoom speed.
+    pub zoom_sensitivity: f32,
+
+    pub invert_rotation_x: bool,
+    pub invert_rotation_y: bool,
+
+    /// Gamepad axis values below this threshold are ignored.
+    pub gamepad_deadzone: f32,
+}
+
+impl ControlsSettings {
+    pub const SENSITIVITY_RANGE: RangeInclusive<f32> = 0.1..=5.0;
+    pub const DEADZONE_RANGE: RangeInclusive<f32> = 0.0..=0.9;
+
+    fn clamp(&mut self) {
+        let default = Self::default();
+        self.rotation_sensitivity = clamp_or(
+            self.rotation_sensitivity,
+            Self::SENSITIVITY_RANGE,
+            default.rotation_sensitivity,
+        );
+        self.zoom_sensitivity = clamp_or(
+            self.zoom_sensitivity,
+            Self::SENSITIVITY_RANGE,
+            default.zoom_sensitivity,
+        );
+        self.gamepad_deadzone = clamp_or(
+            self.gamepad_deadzone,
+            Self::DEADZONE_RANGE,
+            default.gamepad_deadzone,
+        );
+    }
+}
+
+/// Clamps `value` into `range` or returns `default` if it's not a number.
+fn clamp_or(value: f32, range: RangeInclusive<f32>, default: f32) -> f32 {
+    if value.is_nan() {
+        default
+    } else {
+        value.clamp(*range.start(), *range.end())
+    }
 }
 
 impl Default for ControlsSettings {
@@ -150,13 +277,102 @@ impl Default for ControlsSettings {
             (Action::RotateCamera, vec![MouseButton::Middle.into()]),
             (Action::ZoomCamera, vec![SingleAxis::mouse_wheel_y().into()]),
             (Action::RotateObject, vec![MouseButton::Right.into()]),
+            (Action::FineAdjustment, vec![KeyCode::ShiftLeft.into()]),
+            (Action::VerticalOffset, vec![KeyCode::ControlLeft.into()]),
             (Action::Confirm, vec![MouseButton::Left.into()]),
             (Action::Delete, vec![KeyCode::Delete.into()]),
             (Action::Cancel, vec![KeyCode::Escape.into()]),
+            (Action::ToggleProfiling, vec![KeyCode::F3.into()]),
+            (Action::ToggleGrid, vec![KeyCode::KeyG.into()]),
+            (Action::ToggleActorDebug, vec![KeyCode::F4.into()]),
+            (Action::ToggleConsole, vec![KeyCode::Backquote.into()]),
         ]
         .into();
 
-        Self { mappings }
+        Self {
+            mappings,
+            rotation_sensitivity: 1.0,
+            zoom_sensitivity: 1.0,
+            invert_rotation_x: false,
+            invert_rotation_y: false,
+            gamepad_deadzone: 0.1,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, PartialEq, Reflect, Serialize)]
+#[serde(default)]
+pub struct GameplaySettings {
+    /// Maximum number of memories kept per family.
+    ///
+    /// Applied by the host when new memories are recorded.
+    pub memories_capacity: usize,
+
+    /// Capture a picture for each new family memory.
+    pub memory_snapshots: bool,
+
+    /// How walls are displayed in family mode.
+    pub wall_view: WallView,
+
+    /// Language code of names for generated actors, like `en`.
+    ///
+    /// Not exposed in the menu, the system language is used if not set.
+    pub names_locale: Option<String>,
+}
+
+impl GameplaySettings {
+    pub const MEMORIES_CAPACITY_RANGE: RangeInclusive<usize> = 10..=200;
+
+    fn clamp(&mut self) {
+        self.memories_capacity = self.memories_capacity.clamp(
+            *Self::MEMORIES_CAPACITY_RANGE.start(),
+            *Self::MEMORIES_CAPACITY_RANGE.end(),
+        );
+    }
+}
+
+impl Default for GameplaySettings {
+    fn default() -> Self {
+        Self {
+            memories_capacity: 50,
+            memory_snapshots: true,
+            wall_view: Default::default(),
+            names_locale: None,
+        }
+    }
+}
+
+/// How walls are displayed in family mode.
+#[derive(
+    Clone,
+    Component,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Display,
+    EnumIter,
+    PartialEq,
+    Reflect,
+    Serialize,
+)]
+pub enum WallView {
+    /// All walls are lowered.
+    Down,
+    /// Walls between the camera and the viewed point are lowered.
+    #[default]
+    Cutaway,
+    /// All walls are displayed with the full height.
+    Up,
+}
+
+impl WallView {
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Self::Down => "▁",
+            Self::Cutaway => "▄",
+            Self::Up => "█",
+        }
     }
 }
 
@@ -168,6 +384,98 @@ pub struct DeveloperSettings {
     pub colliders: bool,
     pub paths: bool,
     pub nav_mesh: bool,
+    pub spline_connections: bool,
+    pub apertures: bool,
+    pub inspector: bool,
+}
+
+/// Network settings used when hosting a world.
+///
+/// Not exposed in the menu, can be changed only in the settings file.
+#[derive(Clone, Deserialize, PartialEq, Reflect, Serialize)]
+#[serde(default)]
+pub struct ServerSettings {
+    /// How many times per second the world state is sent to clients.
+    ///
+    /// Higher values reduce latency and make movement on clients smoother,
+    /// but increase bandwidth and CPU usage on the server.
+    /// Values above [`simulation::TICK_RATE`] only resend the same simulation results.
+    pub tick_rate: u16,
+
+    /// Maximum number of bytes sent to each client per frame.
+    ///
+    /// Higher values allow to synchronize large worlds faster after connection,
+    /// but may cause packet loss on slow connections.
+    /// Lower values spread the initial synchronization over more frames.
+    pub bytes_per_tick: u64,
+
+    /// Minimum distance in meters an actor should move before its position is sent to clients.
+    ///
+    /// Positions are sent with centimeter precision.
+    /// Higher values reduce bandwidth for slowly moving actors,
+    /// but make their movement less accurate.
+    pub position_threshold: f32,
+
+    /// Minimum angle in degrees an actor should turn before its rotation is sent to clients.
+    pub rotation_threshold: f32,
+
+    /// Maximum number of transform updates per second for each actor.
+    ///
+    /// Clients smooth movement between updates, so values below [`Self::tick_rate`]
+    /// save bandwidth at the cost of less precise paths.
+    pub transform_rate: u16,
+}
+
+impl ServerSettings {
+    pub const TICK_RATE_RANGE: RangeInclusive<u16> = 1..=60;
+
+    /// Minimum value for [`Self::bytes_per_tick`] to fit at least a single packet.
+    pub const MIN_BYTES_PER_TICK: u64 = 1200;
+
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            Self::TICK_RATE_RANGE.contains(&self.tick_rate),
+            "tick rate should be in range {:?}, but it's {}",
+            Self::TICK_RATE_RANGE,
+            self.tick_rate
+        );
+        ensure!(
+            self.bytes_per_tick >= Self::MIN_BYTES_PER_TICK,
+            "bytes per tick should be at least {}, but it's {}",
+            Self::MIN_BYTES_PER_TICK,
+            self.bytes_per_tick
+        );
+        ensure!(
+            self.position_threshold >= 0.0,
+            "position threshold can't be negative, but it's {}",
+            self.position_threshold
+        );
+        ensure!(
+            self.rotation_threshold >= 0.0,
+            "rotation threshold can't be negative, but it's {}",
+            self.rotation_threshold
+        );
+        ensure!(
+            Self::TICK_RATE_RANGE.contains(&self.transform_rate),
+            "transform rate should be in range {:?}, but it's {}",
+            Self::TICK_RATE_RANGE,
+            self.transform_rate
+        );
+
+        Ok(())
+    }
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            tick_rate: simulation::TICK_RATE,
+            bytes_per_tick: 60_000,
+            position_threshold: 0.01,
+            rotation_threshold: 1.0,
+            transform_rate: 15,
+        }
+    }
 }
 
 #[derive(
@@ -200,7 +508,108 @@ pub enum Action {
     ZoomCamera,
     #[strum(serialize = "Rotate Object")]
     RotateObject,
+    /// Modifier for slower camera and smaller rotation steps.
+    #[strum(serialize = "Fine Adjustment")]
+    FineAdjustment,
+    /// Modifier to move placing objects up and down with [`Action::ZoomCamera`].
+    #[strum(serialize = "Vertical Offset")]
+    VerticalOffset,
     Confirm,
     Delete,
     Cancel,
+    #[strum(serialize = "Toggle Profiling")]
+    ToggleProfiling,
+    #[strum(serialize = "Toggle Grid")]
+    ToggleGrid,
+    /// Toggles debug overlay for the selected actor.
+    #[strum(serialize = "Toggle Actor Debug")]
+    ToggleActorDebug,
+    #[strum(serialize = "Toggle Console")]
+    ToggleConsole,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_validation() {
+        assert!(ServerSettings::default().validate().is_ok());
+
+        let settings = ServerSettings {
+            tick_rate: 0,
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = ServerSettings {
+            bytes_per_tick: ServerSettings::MIN_BYTES_PER_TICK - 1,
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = ServerSettings {
+            position_threshold: -1.0,
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = ServerSettings {
+            transform_rate: 0,
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn audio_clamping() {
+        let mut audio = AudioSettings {
+            master_volume: 3.0,
+            ambience_volume: f32::NAN,
+        };
+        audio.clamp();
+
+        assert_eq!(audio.master_volume, *AudioSettings::VOLUME_RANGE.end());
+        assert_eq!(
+            audio.ambience_volume,
+            AudioSettings::default().ambience_volume
+        );
+    }
+
+    #[test]
+    fn controls_clamping() {
+        let mut controls = ControlsSettings {
+            rotation_sensitivity: 100.0,
+            zoom_sensitivity: f32::NAN,
+            gamepad_deadzone: -1.0,
+            ..Default::default()
+        };
+        controls.clamp();
+
+        assert_eq!(
+            controls.rotation_sensitivity,
+            *ControlsSettings::SENSITIVITY_RANGE.end()
+        );
+        assert_eq!(
+            controls.zoom_sensitivity,
+            ControlsSettings::default().zoom_sensitivity
+        );
+        assert_eq!(
+            controls.gamepad_deadzone,
+            *ControlsSettings::DEADZONE_RANGE.start()
+        );
+    }
+
+    #[test]
+    fn gameplay_clamping() {
+        let mut gameplay = GameplaySettings {
+            memories_capacity: 0,
+            ..Default::default()
+        };
+        gameplay.clamp();
+        assert_eq!(
+            gameplay.memories_capacity,
+            *GameplaySettings::MEMORIES_CAPACITY_RANGE.start()
+        );
+    }
 }
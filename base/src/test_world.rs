@@ -0,0 +1,318 @@
+//! Utilities for integration tests of gameplay systems and mod content.
+
+use std::{
+    env, fs, process,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use avian3d::{prelude::*, sync::SyncConfig};
+use bevy::{
+    app::PluginsState,
+    log::LogPlugin,
+    pbr::wireframe::WireframePlugin,
+    prelude::*,
+    render::{settings::WgpuSettings, RenderPlugin},
+    time::TimeUpdateStrategy,
+    window::ExitCondition,
+};
+use bevy_replicon::prelude::*;
+use leafwing_input_manager::prelude::*;
+use vleue_navigator::prelude::*;
+
+use crate::{
+    core::{
+        transition::{Transition, TransitionRequest},
+        GameState,
+    },
+    game_paths::GamePaths,
+    game_world::{
+        actor::{human::HumanBundle, ActorBundle},
+        city::{lot::LotBundle, CityBundle},
+        family::{building::wall::WallBundle, Family, FamilyCreate, FamilyScene},
+        navigation::Obstacle,
+        object::ObjectBundle,
+    },
+    math::{polygon::Polygon, segment::Segment},
+    settings::Action,
+    CorePlugins,
+};
+
+/// Configures and creates [`TestWorld`].
+pub struct TestWorldBuilder {
+    tick_duration: Duration,
+    app: App,
+}
+
+impl TestWorldBuilder {
+    /// Time that passes on each [`TestWorld::tick`].
+    ///
+    /// Defaults to 60 ticks per second.
+    pub fn tick_duration(mut self, duration: Duration) -> Self {
+        self.tick_duration = duration;
+        self
+    }
+
+    /// Adds plugins with additional content, for example from a mod.
+    pub fn add_plugins<M>(mut self, plugins: impl Plugins<M>) -> Self {
+        self.app.add_plugins(plugins);
+        self
+    }
+
+    pub fn build(mut self) -> TestWorld {
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(self.tick_duration));
+
+        // Usually called by the runner.
+        while self.app.plugins_state() == PluginsState::Adding {
+            bevy::tasks::tick_global_task_pools_on_main_thread();
+        }
+        self.app.finish();
+        self.app.cleanup();
+
+        let mut test_world = TestWorld { app: self.app };
+        test_world
+            .world_mut()
+            .send_event(TransitionRequest::new(Transition::Game(GameState::InGame)));
+        let entered = test_world.tick_until(10, |world| {
+            **world.resource::<State<GameState>>() == GameState::InGame
+        });
+        assert!(entered, "game should enter the world");
+
+        test_world
+    }
+}
+
+impl Default for TestWorldBuilder {
+    fn default() -> Self {
+        let mut app = App::new();
+        app.insert_resource(temporary_game_paths())
+            .insert_resource(SyncConfig {
+                position_to_transform: false,
+                ..Default::default()
+            })
+            .add_plugins((
+                DefaultPlugins
+                    .set(RenderPlugin {
+                        render_creation: WgpuSettings {
+                            backends: None,
+                            ..Default::default()
+                        }
+                        .into(),
+                        ..Default::default()
+                    })
+                    .set(WindowPlugin {
+                        primary_window: None,
+                        exit_condition: ExitCondition::DontExit,
+                        close_when_requested: false,
+                    })
+                    .disable::<LogPlugin>(),
+                RepliconPlugins,
+                WireframePlugin,
+                InputManagerPlugin::<Action>::default(),
+                VleueNavigatorPlugin,
+                NavmeshUpdaterPlugin::<Collider, Obstacle>::default(),
+                PhysicsPlugins::default()
+                    .build()
+                    .disable::<CcdPlugin>()
+                    .disable::<SleepingPlugin>(),
+                // Required to apply developer settings.
+                PhysicsDebugPlugin::default(),
+                CorePlugins,
+            ));
+
+        Self {
+            tick_duration: Duration::from_secs_f64(1.0 / 60.0),
+            app,
+        }
+    }
+}
+
+/// Headless singleplayer game with [`CorePlugins`] and disabled rendering.
+///
+/// Starts already in [`GameState::InGame`].
+/// Game files are stored in a temporary directory which is removed on drop.
+pub struct TestWorld {
+    app: App,
+}
+
+impl TestWorld {
+    pub fn builder() -> TestWorldBuilder {
+        TestWorldBuilder::default()
+    }
+
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    /// Runs all schedules once.
+    pub fn tick(&mut self) {
+        self.app.update();
+    }
+
+    /// Runs all schedules the specified number of times.
+    pub fn tick_n(&mut self, count: usize) {
+        for _ in 0..count {
+            self.tick();
+        }
+    }
+
+    /// Ticks until `condition` returns `true` or `max_ticks` is reached.
+    ///
+    /// Returns `true` if the condition was satisfied.
+    pub fn tick_until(&mut self, max_ticks: usize, condition: impl Fn(&mut World) -> bool) -> bool {
+        for _ in 0..max_ticks {
+            self.tick();
+            if condition(self.world_mut()) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn spawn_city(&mut self, name: impl Into<String>) -> Entity {
+        self.world_mut().spawn(CityBundle::new(name.into())).id()
+    }
+
+    /// Spawns a lot with the given vertices inside a city.
+    ///
+    /// The polygon will be closed automatically.
+    pub fn spawn_lot(
+        &mut self,
+        city_entity: Entity,
+        vertices: impl IntoIterator<Item = Vec2>,
+    ) -> Entity {
+        let mut polygon = Polygon(vertices.into_iter().collect());
+        if let (Some(&first), Some(&last)) = (polygon.first(), polygon.last()) {
+            if first != last {
+                polygon.push(first);
+            }
+        }
+
+        self.spawn_child(city_entity, LotBundle::new(polygon))
+    }
+
+    /// Spawns a wall inside a city.
+    pub fn spawn_wall(&mut self, city_entity: Entity, start: Vec2, end: Vec2) -> Entity {
+        self.spawn_child(city_entity, WallBundle::new(Segment::new(start, end)))
+    }
+
+    /// Spawns an object inside a city.
+    ///
+    /// `info_path` is a path to the object metadata, such as `base/objects/.../name.object.ron`.
+    pub fn spawn_object(
+        &mut self,
+        city_entity: Entity,
+        info_path: impl Into<String>,
+        transform: Transform,
+    ) -> Entity {
+        let info_path = info_path.into();
+        self.spawn_child(city_entity, ObjectBundle::new(info_path.into(), transform))
+    }
+
+    /// Creates a family with default humans and returns its entity.
+    ///
+    /// Goes through the same event as family creation from the editor.
+    pub fn spawn_family(
+        &mut self,
+        city_entity: Entity,
+        name: impl Into<String>,
+        actors_count: usize,
+    ) -> Entity {
+        let name = name.into();
+        let mut scene = FamilyScene::new(name.clone());
+        for _ in 0..actors_count {
+            let actor: Box<dyn ActorBundle> = Box::new(HumanBundle::default());
+            scene.actors.push(actor);
+        }
+
+        self.world_mut().send_event(FromClient {
+            client_id: ClientId::SERVER,
+            event: FamilyCreate {
+                city_entity,
+                scene,
+                select: false,
+            },
+        });
+        self.tick();
+
+        let mut families = self
+            .world_mut()
+            .query_filtered::<(Entity, &Name), With<Family>>();
+        families
+            .iter(self.world())
+            .find(|(_, family_name)| family_name.as_str() == name)
+            .map(|(entity, _)| entity)
+            .expect("family should be created after a tick")
+    }
+
+    fn spawn_child(&mut self, parent_entity: Entity, bundle: impl Bundle) -> Entity {
+        let entity = self.world_mut().spawn(bundle).id();
+        self.world_mut().entity_mut(parent_entity).add_child(entity);
+        entity
+    }
+}
+
+impl Drop for TestWorld {
+    fn drop(&mut self) {
+        let game_paths = self.world().resource::<GamePaths>();
+        if let Some(dir) = game_paths.worlds.parent() {
+            if let Err(e) = fs::remove_dir_all(dir) {
+                warn!("unable to remove {dir:?}: {e}");
+            }
+        }
+    }
+}
+
+/// Creates paths in a unique temporary directory to run multiple worlds in parallel.
+pub(crate) fn temporary_game_paths() -> GamePaths {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let index = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let dir = env::temp_dir().join(format!("project_harmonia-{}-{index}", process::id()));
+
+    let worlds = dir.join("worlds");
+    fs::create_dir_all(&worlds).unwrap_or_else(|e| panic!("{worlds:?} should be writable: {e}"));
+
+    GamePaths {
+        settings: dir.join("settings.ron"),
+        achievements: dir.join("achievements.ron"),
+        worlds,
+        log: dir.join("game.log"),
+        previous_log: dir.join("game.previous.log"),
+        diagnostics: dir.join("diagnostics"),
+        archives: dir.join("archives"),
+        bans: dir.join("bans.ron"),
+        identity: dir.join("identity"),
+        snapshots: dir.join("snapshots"),
+        crash_marker: dir.join("crash_marker"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_world::family::FamilyMembers;
+
+    #[test]
+    fn family_creation() {
+        let mut test_world = TestWorld::builder().build();
+        let city_entity = test_world.spawn_city("City");
+        let family_entity = test_world.spawn_family(city_entity, "Family", 2);
+        test_world.tick();
+
+        let members = test_world
+            .world()
+            .get::<FamilyMembers>(family_entity)
+            .expect("members should be assigned after a tick");
+        assert_eq!(members.len(), 2);
+    }
+}
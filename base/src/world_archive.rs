@@ -0,0 +1,283 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    env,
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{ensure, Context, Result};
+use bevy::{
+    prelude::*, render::view::screenshot::ScreenshotManager, scene::ron, window::PrimaryWindow,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    game_paths::GamePaths,
+    game_world::{GameSave, WorldName},
+    message::error_message,
+    save_encryption,
+    zip::{self, ZipWriter},
+};
+
+/// Captures world thumbnails on save to include them into exported archives.
+pub(super) struct WorldArchivePlugin;
+
+impl Plugin for WorldArchivePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            Self::capture_thumbnail
+                .pipe(error_message)
+                .run_if(on_event::<GameSave>()),
+        );
+    }
+}
+
+impl WorldArchivePlugin {
+    fn capture_thumbnail(
+        mut screenshot_manager: ResMut<ScreenshotManager>,
+        game_paths: Res<GamePaths>,
+        world_name: Res<WorldName>,
+        windows: Query<Entity, With<PrimaryWindow>>,
+    ) -> Result<()> {
+        let Ok(window_entity) = windows.get_single() else {
+            return Ok(());
+        };
+
+        let path = game_paths.world_thumbnail_path(&world_name.0);
+        debug!("capturing world thumbnail to {path:?}");
+        screenshot_manager
+            .save_screenshot_to_disk(window_entity, path)
+            .context("unable to capture world thumbnail")?;
+
+        Ok(())
+    }
+}
+
+pub const ARCHIVE_EXTENSION: &str = "harmonia";
+
+const MANIFEST_FILE: &str = "manifest.ron";
+const WORLD_FILE: &str = "world.scn";
+const THUMBNAIL_FILE: &str = "thumbnail.png";
+
+/// Packs the world save, its thumbnail and the list of used asset packs into a single archive.
+///
+/// Returns path to the created archive.
+pub fn export(game_paths: &GamePaths, world_name: &str) -> Result<PathBuf> {
+    fs::create_dir_all(&game_paths.archives)
+        .with_context(|| format!("unable to create {:?}", game_paths.archives))?;
+
+    let mut path = game_paths.archives.join(world_name);
+    path.set_extension(ARCHIVE_EXTENSION);
+    info!("exporting world '{world_name}' to {path:?}");
+
+    let world_path = game_paths.world_path(world_name);
+    let world = fs::read(&world_path).with_context(|| format!("unable to read {world_path:?}"))?;
+    // Archives are meant for sharing, so they can't contain a key-protected world.
+    ensure!(
+        !save_encryption::is_encrypted(&world),
+        "{world_path:?} is encrypted and can't be exported"
+    );
+    let world = String::from_utf8(world).with_context(|| format!("{world_path:?} is not valid"))?;
+    let manifest = Manifest {
+        world_name: world_name.to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        asset_packs: used_asset_packs(&world),
+    };
+    let manifest = ron::ser::to_string_pretty(&manifest, Default::default())
+        .expect("manifest should be serializable");
+
+    let file = File::create(&path).with_context(|| format!("unable to create {path:?}"))?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    zip.add(MANIFEST_FILE, manifest.as_bytes())?;
+    zip.add(WORLD_FILE, world.as_bytes())?;
+
+    // Thumbnail is missing for worlds saved without a window.
+    let thumbnail_path = game_paths.world_thumbnail_path(world_name);
+    if let Ok(thumbnail) = fs::read(&thumbnail_path) {
+        zip.add(THUMBNAIL_FILE, &thumbnail)
+            .with_context(|| format!("unable to add {thumbnail_path:?}"))?;
+    }
+
+    zip.finish()
+        .with_context(|| format!("unable to write {path:?}"))?;
+
+    Ok(path)
+}
+
+/// World from an opened archive.
+pub struct WorldArchive {
+    pub world_name: String,
+
+    /// Asset packs used by the world that are not installed.
+    pub missing_packs: Vec<String>,
+
+    world: Vec<u8>,
+    thumbnail: Option<Vec<u8>>,
+}
+
+impl WorldArchive {
+    /// Reads the archive and checks which of the used asset packs are missing.
+    pub fn open(path: &Path) -> Result<Self> {
+        info!("opening world archive {path:?}");
+        let bytes = fs::read(path).with_context(|| format!("unable to read {path:?}"))?;
+
+        let mut manifest = None;
+        let mut world = None;
+        let mut thumbnail = None;
+        for (name, data) in
+            zip::read(&bytes).with_context(|| format!("unable to unpack {path:?}"))?
+        {
+            match name.as_str() {
+                MANIFEST_FILE => manifest = Some(data),
+                WORLD_FILE => world = Some(data),
+                THUMBNAIL_FILE => thumbnail = Some(data),
+                _ => warn!("ignoring unknown file '{name}' in {path:?}"),
+            }
+        }
+
+        let manifest = manifest.with_context(|| format!("{path:?} is missing {MANIFEST_FILE}"))?;
+        let manifest: Manifest = ron::de::from_bytes(&manifest)
+            .with_context(|| format!("unable to parse {MANIFEST_FILE} from {path:?}"))?;
+        let world = world.with_context(|| format!("{path:?} is missing {WORLD_FILE}"))?;
+
+        // The name is used as a file name, don't allow it to point outside of the worlds directory.
+        ensure!(
+            Path::new(&manifest.world_name).file_name() == Some(manifest.world_name.as_ref()),
+            "'{}' is not a valid world name",
+            manifest.world_name
+        );
+
+        if manifest.version != env!("CARGO_PKG_VERSION") {
+            warn!(
+                "world archive {path:?} was exported from version {}",
+                manifest.version
+            );
+        }
+
+        let installed_packs = installed_asset_packs()?;
+        let missing_packs = manifest
+            .asset_packs
+            .into_iter()
+            .filter(|pack| !installed_packs.contains(pack))
+            .collect();
+
+        Ok(Self {
+            world_name: manifest.world_name,
+            missing_packs,
+            world,
+            thumbnail,
+        })
+    }
+
+    /// Writes the world into [`GamePaths::worlds`].
+    ///
+    /// Fails if any asset packs are missing or a world with the same name already exists.
+    pub fn import(&self, game_paths: &GamePaths) -> Result<()> {
+        ensure!(
+            self.missing_packs.is_empty(),
+            "world '{}' requires missing asset packs: {}",
+            self.world_name,
+            self.missing_packs.join(", ")
+        );
+
+        let world_path = game_paths.world_path(&self.world_name);
+        ensure!(
+            !world_path.exists(),
+            "world '{}' already exists",
+            self.world_name
+        );
+
+        info!("importing world to {world_path:?}");
+        fs::create_dir_all(&game_paths.worlds)
+            .with_context(|| format!("unable to create {:?}", game_paths.worlds))?;
+        fs::write(&world_path, &self.world)
+            .with_context(|| format!("unable to write {world_path:?}"))?;
+
+        if let Some(thumbnail) = &self.thumbnail {
+            let thumbnail_path = game_paths.world_thumbnail_path(&self.world_name);
+            fs::write(&thumbnail_path, thumbnail)
+                .with_context(|| format!("unable to write {thumbnail_path:?}"))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Metadata stored inside an archive.
+#[derive(Deserialize, Serialize)]
+struct Manifest {
+    world_name: String,
+
+    /// Game version used for export.
+    version: String,
+
+    /// Top-level asset directories referenced by the world.
+    asset_packs: BTreeSet<String>,
+}
+
+/// Collects asset packs from paths to metadata files referenced in the world save.
+///
+/// All asset paths are serialized as strings, so there is no need to deserialize the scene.
+fn used_asset_packs(world: &str) -> BTreeSet<String> {
+    world
+        .split('"')
+        .skip(1)
+        .step_by(2)
+        .filter(|value| value.ends_with(".ron"))
+        .filter_map(|path| path.split_once('/'))
+        .map(|(pack, _)| pack.to_string())
+        .collect()
+}
+
+fn installed_asset_packs() -> Result<HashSet<String>> {
+    let assets_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap_or_default()).join("assets");
+    let entries = assets_dir
+        .read_dir()
+        .with_context(|| format!("unable to read {assets_dir:?}"))?;
+
+    let packs = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+
+    Ok(packs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_world;
+
+    #[test]
+    fn asset_packs() {
+        const WORLD: &str = r#"
+            "project_harmonia_base::game_world::object::Object": ("base/objects/chair/chair.object.ron"),
+            "project_harmonia_base::game_world::city::road::Road": ("extra/roads/dirt/dirt.road.ron"),
+            "bevy_core::name::Name": "Some city",
+        "#;
+
+        let packs = used_asset_packs(WORLD);
+        assert_eq!(packs, BTreeSet::from(["base".into(), "extra".into()]));
+    }
+
+    #[test]
+    fn import_without_worlds_dir() -> Result<()> {
+        let game_paths = test_world::temporary_game_paths();
+        fs::remove_dir_all(&game_paths.worlds)?;
+
+        let archive = WorldArchive {
+            world_name: "Imported".into(),
+            missing_packs: Vec::new(),
+            world: b"()".to_vec(),
+            thumbnail: None,
+        };
+        archive.import(&game_paths)?;
+
+        assert!(game_paths.world_path("Imported").exists());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+//! Minimal ZIP archive reader and writer without compression.
+
+use std::io::{self, Write};
+
+/// Writes files into a ZIP archive using the "stored" method.
+///
+/// Diagnostic bundles and world archives are small, so compression is not worth an extra dependency.
+pub(super) struct ZipWriter<W: Write> {
+    writer: W,
+    offset: u32,
+    entries: Vec<Entry>,
+}
+
+impl<W: Write> ZipWriter<W> {
+    pub(super) fn new(writer: W) -> Self {
+        Self {
+            writer,
+            offset: 0,
+            entries: Default::default(),
+        }
+    }
+
+    /// Adds a file to the archive.
+    ///
+    /// Returns an error if the archive exceeds the format limits since ZIP64 is not supported.
+    pub(super) fn add(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        if u16::try_from(name.len()).is_err() {
+            return Err(invalid_input("file name is too long"));
+        }
+        if self.entries.len() >= u16::MAX.into() {
+            return Err(invalid_input("too many files"));
+        }
+        let size = u32::try_from(data.len()).map_err(|_| invalid_input("file is too large"))?;
+
+        let entry = Entry {
+            name: name.to_string(),
+            crc: crc32(data),
+            size,
+            offset: self.offset,
+        };
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&LOCAL_HEADER_SIGNATURE.to_le_bytes());
+        header.extend_from_slice(&VERSION.to_le_bytes());
+        entry.write_common(&mut header);
+        header.extend_from_slice(&0u16.to_le_bytes()); // Extra field length.
+        header.extend_from_slice(entry.name.as_bytes());
+
+        self.write(&header)?;
+        self.write(data)?;
+        self.entries.push(entry);
+
+        Ok(())
+    }
+
+    /// Writes the central directory.
+    pub(super) fn finish(mut self) -> io::Result<W> {
+        let directory_offset = self.offset;
+        for entry in &self.entries {
+            let mut header = Vec::new();
+            header.extend_from_slice(&CENTRAL_HEADER_SIGNATURE.to_le_bytes());
+            header.extend_from_slice(&VERSION.to_le_bytes()); // Made by.
+            header.extend_from_slice(&VERSION.to_le_bytes()); // Needed to extract.
+            entry.write_common(&mut header);
+            header.extend_from_slice(&0u16.to_le_bytes()); // Extra field length.
+            header.extend_from_slice(&0u16.to_le_bytes()); // Comment length.
+            header.extend_from_slice(&0u16.to_le_bytes()); // Disk number.
+            header.extend_from_slice(&0u16.to_le_bytes()); // Internal attributes.
+            header.extend_from_slice(&0u32.to_le_bytes()); // External attributes.
+            header.extend_from_slice(&entry.offset.to_le_bytes());
+            header.extend_from_slice(entry.name.as_bytes());
+
+            self.write(&header)?;
+        }
+
+        let entries_count = self.entries.len() as u16;
+        let mut end = Vec::new();
+        end.extend_from_slice(&END_SIGNATURE.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes()); // Disk number.
+        end.extend_from_slice(&0u16.to_le_bytes()); // Disk with central directory.
+        end.extend_from_slice(&entries_count.to_le_bytes());
+        end.extend_from_slice(&entries_count.to_le_bytes());
+        end.extend_from_slice(&(self.offset - directory_offset).to_le_bytes());
+        end.extend_from_slice(&directory_offset.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes()); // Comment length.
+        self.writer.write_all(&end)?;
+
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        self.offset = u32::try_from(data.len())
+            .ok()
+            .and_then(|len| self.offset.checked_add(len))
+            .ok_or_else(|| invalid_input("archive is too large"))?;
+        self.writer.write_all(data)
+    }
+}
+
+/// Reads all files from an archive created by [`ZipWriter`].
+///
+/// Only the "stored" method without archive comment is supported.
+pub(super) fn read(archive: &[u8]) -> io::Result<Vec<(String, Vec<u8>)>> {
+    let end = archive
+        .len()
+        .checked_sub(END_SIZE)
+        .filter(|&end| read_u32(archive, end).is_ok_and(|signature| signature == END_SIGNATURE))
+        .ok_or_else(|| invalid_data("missing end of central directory"))?;
+
+    let entries_count = read_u16(archive, end + 10)?;
+    let mut offset = read_u32(archive, end + 16)? as usize;
+    let mut files = Vec::with_capacity(entries_count.into());
+    for _ in 0..entries_count {
+        if read_u32(archive, offset)? != CENTRAL_HEADER_SIGNATURE {
+            return Err(invalid_data("invalid central directory header"));
+        }
+        if read_u16(archive, offset + 10)? != 0 {
+            return Err(invalid_data("compressed entries are not supported"));
+        }
+
+        let crc = read_u32(archive, offset + 16)?;
+        let size = read_u32(archive, offset + 24)? as usize;
+        let name_len = read_u16(archive, offset + 28)? as usize;
+        let extra_len = read_u16(archive, offset + 30)? as usize;
+        let comment_len = read_u16(archive, offset + 32)? as usize;
+        let local_offset = read_u32(archive, offset + 42)? as usize;
+        let name_start = offset + CENTRAL_HEADER_SIZE;
+        let name = slice(archive, name_start, name_len)?;
+        let name = String::from_utf8(name.to_vec()).map_err(|_| invalid_data("invalid name"))?;
+
+        if read_u32(archive, local_offset)? != LOCAL_HEADER_SIGNATURE {
+            return Err(invalid_data("invalid local header"));
+        }
+        let data_start = local_offset
+            + LOCAL_HEADER_SIZE
+            + read_u16(archive, local_offset + 26)? as usize
+            + read_u16(archive, local_offset + 28)? as usize;
+        let data = slice(archive, data_start, size)?;
+        if crc32(data) != crc {
+            return Err(invalid_data("checksum mismatch"));
+        }
+
+        files.push((name, data.to_vec()));
+        offset = name_start + name_len + extra_len + comment_len;
+    }
+
+    Ok(files)
+}
+
+fn slice(data: &[u8], start: usize, len: usize) -> io::Result<&[u8]> {
+    data.get(start..start + len)
+        .ok_or_else(|| invalid_data("unexpected end of archive"))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> io::Result<u16> {
+    let bytes = slice(data, offset, 2)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> io::Result<u32> {
+    let bytes = slice(data, offset, 4)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn invalid_input(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const END_SIGNATURE: u32 = 0x0605_4b50;
+
+const LOCAL_HEADER_SIZE: usize = 30;
+const CENTRAL_HEADER_SIZE: usize = 46;
+const END_SIZE: usize = 22;
+
+/// Version 2.0, the minimum for files inside directories.
+const VERSION: u16 = 20;
+
+/// Indicates that names are encoded in UTF-8.
+const UTF8_FLAG: u16 = 1 << 11;
+
+/// 1980-01-01 in MS-DOS format.
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+struct Entry {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+impl Entry {
+    /// Writes fields that are the same for local and central headers.
+    fn write_common(&self, header: &mut Vec<u8>) {
+        header.extend_from_slice(&UTF8_FLAG.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // Stored without compression.
+        header.extend_from_slice(&0u16.to_le_bytes()); // Modification time.
+        header.extend_from_slice(&DOS_DATE.to_le_bytes());
+        header.extend_from_slice(&self.crc.to_le_bytes());
+        header.extend_from_slice(&self.size.to_le_bytes()); // Compressed size.
+        header.extend_from_slice(&self.size.to_le_bytes()); // Uncompressed size.
+        header.extend_from_slice(&(self.name.len() as u16).to_le_bytes());
+    }
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn archive() -> io::Result<()> {
+        let mut zip = ZipWriter::new(Vec::new());
+        zip.add("a.txt", b"a")?;
+        zip.add("b.txt", b"bb")?;
+        let archive = zip.finish()?;
+
+        assert_eq!(archive[..4], LOCAL_HEADER_SIGNATURE.to_le_bytes());
+        let end = &archive[archive.len() - END_SIZE..];
+        assert_eq!(end[..4], END_SIGNATURE.to_le_bytes());
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn too_many_files() -> io::Result<()> {
+        let mut zip = ZipWriter::new(io::sink());
+        for index in 0..u16::MAX {
+            zip.add(&index.to_string(), &[])?;
+        }
+        assert_eq!(
+            zip.add("overflow", &[]).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn roundtrip() -> io::Result<()> {
+        let mut zip = ZipWriter::new(Vec::new());
+        zip.add("a.txt", b"a")?;
+        zip.add("dir/b.txt", b"bb")?;
+        let archive = zip.finish()?;
+
+        let files = read(&archive)?;
+        assert_eq!(
+            files,
+            [
+                ("a.txt".to_string(), b"a".to_vec()),
+                ("dir/b.txt".to_string(), b"bb".to_vec())
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+
+use project_harmonia_base::achievements::AchievementUnlocked;
+use project_harmonia_widgets::{label::LabelBundle, theme::Theme};
+
+/// Briefly shows unlocked achievements.
+pub(super) struct AchievementToastPlugin;
+
+impl Plugin for AchievementToastPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (Self::show, Self::hide));
+    }
+}
+
+/// Seconds to display a toast.
+const TOAST_DURATION: f32 = 5.0;
+
+impl AchievementToastPlugin {
+    fn show(
+        mut commands: Commands,
+        mut unlock_events: EventReader<AchievementUnlocked>,
+        theme: Res<Theme>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        for event in unlock_events.read() {
+            debug!("showing toast for `{:?}`", event.0);
+            commands.entity(roots.single()).with_children(|parent| {
+                parent
+                    .spawn((
+                        Toast(Timer::from_seconds(TOAST_DURATION, TimerMode::Once)),
+                        NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                align_self: AlignSelf::Center,
+                                top: Val::Px(0.0),
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                margin: theme.padding.global,
+                                padding: theme.padding.normal,
+                                ..Default::default()
+                            },
+                            background_color: theme.panel_color.into(),
+                            ..Default::default()
+                        },
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(LabelBundle::normal(
+                            &theme,
+                            format!("🏆 Achievement unlocked: {}", event.0.name()),
+                        ));
+                        parent.spawn(LabelBundle::normal(&theme, event.0.description()));
+                    });
+            });
+        }
+    }
+
+    fn hide(mut commands: Commands, time: Res<Time>, mut toasts: Query<(Entity, &mut Toast)>) {
+        for (entity, mut toast) in &mut toasts {
+            if toast.tick(time.delta()).just_finished() {
+                debug!("hiding toast `{entity}`");
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+#[derive(Component, Deref, DerefMut)]
+struct Toast(Timer);
@@ -0,0 +1,164 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_simple_text_input::TextInputSubmitEvent;
+use leafwing_input_manager::common_conditions::action_just_pressed;
+
+use project_harmonia_base::{
+    console::{ConsoleInput, ConsoleOutput, ConsoleSource},
+    settings::Action,
+};
+use project_harmonia_widgets::{label::LabelBundle, text_edit::TextEditBundle, theme::Theme};
+
+/// In-game console that executes the same commands as the server console.
+pub(super) struct ConsoleNodePlugin;
+
+impl Plugin for ConsoleNodePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConsoleHistory>().add_systems(
+            Update,
+            (
+                Self::toggle.run_if(action_just_pressed(Action::ToggleConsole)),
+                Self::submit,
+                Self::receive_output.run_if(on_event::<ConsoleOutput>()),
+                Self::update_log,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Maximum number of lines kept in [`ConsoleHistory`].
+const MAX_HISTORY_LINES: usize = 50;
+
+impl ConsoleNodePlugin {
+    fn toggle(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        nodes: Query<Entity, With<ConsoleNode>>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        if let Ok(entity) = nodes.get_single() {
+            debug!("hiding console");
+            commands.entity(entity).despawn_recursive();
+            return;
+        }
+
+        debug!("showing console");
+        commands.entity(roots.single()).with_children(|parent| {
+            parent
+                .spawn((
+                    ConsoleNode,
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            top: Val::Px(0.0),
+                            left: Val::Px(0.0),
+                            width: Val::Percent(50.0),
+                            flex_direction: FlexDirection::Column,
+                            row_gap: theme.gap.normal,
+                            margin: theme.padding.global,
+                            padding: theme.padding.normal,
+                            ..Default::default()
+                        },
+                        background_color: theme.panel_color.into(),
+                        z_index: ZIndex::Global(1),
+                        ..Default::default()
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        ConsoleLog,
+                        NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                    ));
+                    parent.spawn((ConsoleEdit, TextEditBundle::empty(&theme)));
+                });
+        });
+    }
+
+    fn submit(
+        mut input_events: EventWriter<ConsoleInput>,
+        mut submit_events: EventReader<TextInputSubmitEvent>,
+        mut history: ResMut<ConsoleHistory>,
+        edits: Query<(), With<ConsoleEdit>>,
+    ) {
+        for event in submit_events.read() {
+            if edits.get(event.entity).is_err() || event.value.trim().is_empty() {
+                continue;
+            }
+
+            history.push(format!("> {}", event.value));
+            input_events.send(ConsoleInput {
+                source: ConsoleSource::Game,
+                line: event.value.clone(),
+            });
+        }
+    }
+
+    fn receive_output(
+        mut output_events: EventReader<ConsoleOutput>,
+        mut history: ResMut<ConsoleHistory>,
+    ) {
+        for event in output_events
+            .read()
+            .filter(|event| event.source == ConsoleSource::Game)
+        {
+            for line in event.text.lines() {
+                history.push(line.to_string());
+            }
+        }
+    }
+
+    fn update_log(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        history: Res<ConsoleHistory>,
+        logs: Query<(Entity, Ref<ConsoleLog>)>,
+    ) {
+        let Ok((log_entity, log)) = logs.get_single() else {
+            return;
+        };
+        if !history.is_changed() && !log.is_added() {
+            return;
+        }
+
+        commands
+            .entity(log_entity)
+            .despawn_descendants()
+            .with_children(|parent| {
+                for line in &history.0 {
+                    parent.spawn(LabelBundle::normal(&theme, line.clone()));
+                }
+            });
+    }
+}
+
+/// Last entered commands and their output.
+///
+/// Kept between console toggles.
+#[derive(Default, Resource)]
+struct ConsoleHistory(VecDeque<String>);
+
+impl ConsoleHistory {
+    fn push(&mut self, line: String) {
+        if self.0.len() == MAX_HISTORY_LINES {
+            self.0.pop_front();
+        }
+        self.0.push_back(line);
+    }
+}
+
+#[derive(Component)]
+struct ConsoleNode;
+
+#[derive(Component)]
+struct ConsoleLog;
+
+#[derive(Component)]
+struct ConsoleEdit;
@@ -1,14 +1,31 @@
+mod actor_debug_label;
 mod city_hud;
+mod cursor_coords;
 mod family_hud;
+#[cfg(feature = "developer")]
+mod inspector_panel;
 mod objects_node;
+mod placement_tooltip;
+mod replace_menu;
+mod room_dimensions;
+mod spectator_hud;
+mod statistics_graph;
 pub(super) mod task_menu;
 mod tools_node;
 
 use bevy::prelude::*;
 
+use actor_debug_label::ActorDebugLabelPlugin;
 use city_hud::CityHudPlugin;
+use cursor_coords::CursorCoordsPlugin;
 use family_hud::FamilyHudPlugin;
+#[cfg(feature = "developer")]
+use inspector_panel::InspectorPanelPlugin;
 use objects_node::ObjectsNodePlugin;
+use placement_tooltip::PlacementTooltipPlugin;
+use replace_menu::ReplaceMenuPlugin;
+use room_dimensions::RoomDimensionsPlugin;
+use spectator_hud::SpectatorHudPlugin;
 use task_menu::TaskMenuPlugin;
 use tools_node::ToolsNodePlugin;
 
@@ -17,11 +34,20 @@ pub(super) struct HudPlugin;
 impl Plugin for HudPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((
+            ActorDebugLabelPlugin,
             CityHudPlugin,
+            CursorCoordsPlugin,
             ObjectsNodePlugin,
+            PlacementTooltipPlugin,
+            ReplaceMenuPlugin,
+            RoomDimensionsPlugin,
             FamilyHudPlugin,
+            SpectatorHudPlugin,
             TaskMenuPlugin,
             ToolsNodePlugin,
         ));
+
+        #[cfg(feature = "developer")]
+        app.add_plugins(InspectorPanelPlugin);
     }
 }
@@ -0,0 +1,130 @@
+use std::fmt::Write;
+
+use bevy::{prelude::*, transform::TransformSystem};
+
+use project_harmonia_base::{
+    common_conditions::in_any_state,
+    game_world::{
+        actor::{
+            actor_debug::ActorDebug,
+            needs::{Need, NeedGlyph},
+        },
+        inspection,
+        player_camera::PlayerCamera,
+        WorldState,
+    },
+};
+use project_harmonia_widgets::{label::LabelBundle, theme::Theme};
+
+/// Shows needs and the current task above actors with [`ActorDebug`].
+pub(super) struct ActorDebugLabelPlugin;
+
+impl Plugin for ActorDebugLabelPlugin {
+    fn build(&self, app: &mut App) {
+        app.observe(Self::spawn).observe(Self::despawn).add_systems(
+            PostUpdate,
+            (
+                Self::update_text,
+                Self::follow_actors.after(TransformSystem::TransformPropagate),
+            )
+                .run_if(in_any_state([WorldState::City, WorldState::Family])),
+        );
+    }
+}
+
+/// Height above the actor origin.
+const LABEL_OFFSET: f32 = 2.4;
+
+impl ActorDebugLabelPlugin {
+    fn spawn(
+        trigger: Trigger<OnAdd, ActorDebug>,
+        mut commands: Commands,
+        theme: Res<Theme>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        debug!("creating debug label for `{}`", trigger.entity());
+        commands.entity(roots.single()).with_children(|parent| {
+            parent
+                .spawn((DebugLabel(trigger.entity()), LabelBundle::small(&theme, "")))
+                .insert((
+                    Style {
+                        position_type: PositionType::Absolute,
+                        padding: theme.padding.normal,
+                        ..Default::default()
+                    },
+                    BackgroundColor(theme.panel_color),
+                    ZIndex::Global(-1),
+                ));
+        });
+    }
+
+    /// Updates label content via commands since reading the active task requires access to the whole world.
+    fn update_text(
+        mut commands: Commands,
+        world: &World,
+        theme: Res<Theme>,
+        labels: Query<(Entity, &DebugLabel, &Text)>,
+        needs: Query<(&Need, &NeedGlyph)>,
+    ) {
+        for (label_entity, label, text) in &labels {
+            let mut content = world
+                .get::<Name>(label.0)
+                .map(ToString::to_string)
+                .unwrap_or_else(|| label.0.to_string());
+            let task = inspection::active_task(world, label.0);
+            write!(content, "\nTask: {}", task.as_deref().unwrap_or("none")).unwrap();
+            if let Some(children) = world.get::<Children>(label.0) {
+                for (need, glyph) in needs.iter_many(children) {
+                    write!(content, "\n{} {:.0}", glyph.0, need.0).unwrap();
+                }
+            }
+
+            if text.sections[0].value != content {
+                commands
+                    .entity(label_entity)
+                    .insert(Text::from_section(content, theme.label.small.clone()));
+            }
+        }
+    }
+
+    fn follow_actors(
+        cameras: Query<(&GlobalTransform, &Camera), With<PlayerCamera>>,
+        actors: Query<&GlobalTransform>,
+        mut labels: Query<(&mut Style, &Node, &DebugLabel)>,
+    ) {
+        let Ok((camera_transform, camera)) = cameras.get_single() else {
+            return;
+        };
+
+        for (mut style, node, label) in &mut labels {
+            let position = actors.get(label.0).ok().and_then(|actor_transform| {
+                let point = actor_transform.translation() + Vec3::Y * LABEL_OFFSET;
+                camera.world_to_viewport(camera_transform, point)
+            });
+
+            if let Some(position) = position {
+                let position = position - node.size() / 2.0;
+                style.display = Display::Flex;
+                style.left = Val::Px(position.x);
+                style.top = Val::Px(position.y);
+            } else {
+                style.display = Display::None;
+            }
+        }
+    }
+
+    fn despawn(
+        trigger: Trigger<OnRemove, ActorDebug>,
+        mut commands: Commands,
+        labels: Query<(Entity, &DebugLabel)>,
+    ) {
+        if let Some((entity, _)) = labels.iter().find(|(_, label)| label.0 == trigger.entity()) {
+            debug!("removing debug label `{entity}` for `{}`", trigger.entity());
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Label above an actor for the stored entity.
+#[derive(Component)]
+struct DebugLabel(Entity);
@@ -1,34 +1,49 @@
+mod generation_node;
 mod lots_node;
 mod roads_node;
+mod statistics_node;
 
 use bevy::prelude::*;
 use project_harmonia_base::{
     asset::info::{
         object_info::{ObjectCategory, ObjectInfo},
         road_info::RoadInfo,
+        service_info::ServiceInfo,
+    },
+    game_world::{
+        city::{road::placing_road::RoadDecoration, CityMode},
+        scenario::Scenario,
+        WorldState,
     },
-    game_world::{city::CityMode, WorldState},
 };
 use project_harmonia_widgets::{
     button::{ExclusiveButton, TabContent, TextButtonBundle, Toggled},
+    label::LabelBundle,
     theme::Theme,
 };
 use strum::IntoEnumIterator;
 
 use crate::hud::{objects_node, tools_node};
+use generation_node::GenerationNodePlugin;
 use lots_node::LotsNodePlugin;
 use roads_node::RoadsNodePlugin;
+use statistics_node::StatisticsNodePlugin;
 
 pub(super) struct CityHudPlugin;
 
 impl Plugin for CityHudPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((LotsNodePlugin, RoadsNodePlugin))
-            .add_systems(OnEnter(WorldState::City), Self::setup)
-            .add_systems(
-                Update,
-                Self::set_city_mode.run_if(in_state(WorldState::City)),
-            );
+        app.add_plugins((
+            GenerationNodePlugin,
+            LotsNodePlugin,
+            RoadsNodePlugin,
+            StatisticsNodePlugin,
+        ))
+        .add_systems(OnEnter(WorldState::City), Self::setup)
+        .add_systems(
+            Update,
+            Self::set_city_mode.run_if(in_state(WorldState::City)),
+        );
     }
 }
 
@@ -40,6 +55,9 @@ impl CityHudPlugin {
         asset_server: Res<AssetServer>,
         objects_info: Res<Assets<ObjectInfo>>,
         roads_info: Res<Assets<RoadInfo>>,
+        services_info: Res<Assets<ServiceInfo>>,
+        road_decoration: Res<RoadDecoration>,
+        scenarios: Query<&Scenario>,
     ) {
         debug!("showing city HUD");
         commands
@@ -71,6 +89,14 @@ impl CityHudPlugin {
                     .id();
 
                 for mode in CityMode::iter() {
+                    if scenarios
+                        .get_single()
+                        .is_ok_and(|scenario| scenario.is_city_mode_locked(mode))
+                    {
+                        debug!("skipping `{mode:?}` locked by scenario");
+                        continue;
+                    }
+
                     let content_entity = parent
                         .spawn(NodeBundle {
                             style: Style {
@@ -92,14 +118,25 @@ impl CityHudPlugin {
                                     ObjectCategory::CITY_CATEGORIES,
                                 );
                             }
-                            CityMode::Lots => lots_node::setup(parent, &theme),
+                            CityMode::Lots => {
+                                lots_node::setup(parent, &mut tab_commands, &theme, &services_info)
+                            }
                             CityMode::Roads => roads_node::setup(
                                 parent,
                                 &mut tab_commands,
                                 &asset_server,
                                 &theme,
                                 &roads_info,
+                                &road_decoration,
                             ),
+                            CityMode::Water => {
+                                parent.spawn(LabelBundle::normal(
+                                    &theme,
+                                    "Draw ponds and rivers on the ground",
+                                ));
+                            }
+                            CityMode::Generation => generation_node::setup(parent, &theme),
+                            CityMode::Statistics => statistics_node::setup(parent, &theme),
                         })
                         .id();
 
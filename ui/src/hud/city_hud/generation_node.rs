@@ -0,0 +1,149 @@
+use anyhow::{ensure, Context, Result};
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use bevy_simple_text_input::TextInputValue;
+use strum::{EnumIter, IntoEnumIterator};
+
+use project_harmonia_base::{
+    game_world::city::{
+        generation::{CityGenerate, GenerationParams},
+        ActiveCity, CityMode,
+    },
+    message::error_message,
+};
+use project_harmonia_widgets::{
+    button::{ExclusiveButton, TextButtonBundle, Toggled},
+    click::Click,
+    label::LabelBundle,
+    text_edit::TextEditBundle,
+    theme::Theme,
+};
+
+pub(super) struct GenerationNodePlugin;
+
+impl Plugin for GenerationNodePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            Self::generate
+                .pipe(error_message)
+                .run_if(in_state(CityMode::Generation)),
+        );
+    }
+}
+
+impl GenerationNodePlugin {
+    fn generate(
+        mut click_events: EventReader<Click>,
+        mut generate_events: EventWriter<CityGenerate>,
+        client: Res<RepliconClient>,
+        buttons: Query<(), With<GenerateButton>>,
+        presets: Query<(&Toggled, &DensityPreset)>,
+        seed_edits: Query<&TextInputValue, With<SeedEdit>>,
+        cities: Query<Entity, With<ActiveCity>>,
+    ) -> Result<()> {
+        for _ in buttons.iter_many(click_events.read().map(|event| event.0)) {
+            ensure!(!client.is_connected(), "only the host can generate a city");
+
+            let seed_text = seed_edits.single();
+            let seed = seed_text
+                .0
+                .parse()
+                .with_context(|| format!("'{}' is not a valid seed", seed_text.0))?;
+
+            let preset = presets
+                .iter()
+                .find_map(|(toggled, &preset)| toggled.0.then_some(preset))
+                .expect("one preset should always be selected");
+
+            info!("requesting city generation with seed {seed} and `{preset:?}` density");
+            generate_events.send(CityGenerate {
+                city_entity: cities.single(),
+                params: preset.params(seed),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+pub(super) fn setup(parent: &mut ChildBuilder, theme: &Theme) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                row_gap: theme.gap.normal,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent.spawn(LabelBundle::normal(
+                theme,
+                "Generate a town on an empty city",
+            ));
+            parent.spawn((
+                SeedEdit,
+                TextEditBundle::new(theme, rand::random::<u32>().to_string()),
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: theme.gap.normal,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    for preset in DensityPreset::iter() {
+                        parent.spawn((
+                            preset,
+                            ExclusiveButton,
+                            Toggled(preset == Default::default()),
+                            TextButtonBundle::normal(theme, format!("{preset:?}")),
+                        ));
+                    }
+                });
+            parent.spawn((GenerateButton, TextButtonBundle::normal(theme, "Generate")));
+        });
+}
+
+#[derive(Component)]
+struct SeedEdit;
+
+#[derive(Component)]
+struct GenerateButton;
+
+#[derive(Clone, Component, Copy, Debug, Default, EnumIter, PartialEq)]
+enum DensityPreset {
+    Sparse,
+    #[default]
+    Normal,
+    Dense,
+}
+
+impl DensityPreset {
+    fn params(self, seed: u64) -> GenerationParams {
+        let params = GenerationParams {
+            seed,
+            ..Default::default()
+        };
+        match self {
+            DensityPreset::Sparse => GenerationParams {
+                lot_density: 0.4,
+                street_density: 0.3,
+                foliage_density: 3.0,
+                ..params
+            },
+            DensityPreset::Normal => params,
+            DensityPreset::Dense => GenerationParams {
+                blocks: 4,
+                lot_density: 0.9,
+                street_density: 0.8,
+                foliage_density: 10.0,
+                parks: 2,
+                ..params
+            },
+        }
+    }
+}
@@ -1,9 +1,20 @@
 use bevy::prelude::*;
 use strum::IntoEnumIterator;
 
-use project_harmonia_base::game_world::{city::lot::LotTool, WorldState};
+use project_harmonia_base::{
+    asset::info::service_info::ServiceInfo,
+    game_world::{
+        city::lot::{
+            bulldozing_lot::{BulldozingLot, LotBulldoze},
+            community_lot::SelectedService,
+            LotTool,
+        },
+        WorldState,
+    },
+};
 use project_harmonia_widgets::{
-    button::{ExclusiveButton, TextButtonBundle, Toggled},
+    button::{ExclusiveButton, TabContent, TextButtonBundle, Toggled},
+    dialog::{ConfirmationDialogBundle, DialogResponse, DialogResult},
     theme::Theme,
 };
 
@@ -13,7 +24,13 @@ impl Plugin for LotsNodePlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            Self::set_lot_tool.run_if(in_state(WorldState::City)),
+            (
+                Self::set_lot_tool,
+                Self::select_service,
+                Self::show_bulldoze_dialog,
+                Self::handle_bulldoze_results,
+            )
+                .run_if(in_state(WorldState::City)),
         );
     }
 }
@@ -30,10 +47,80 @@ impl LotsNodePlugin {
             }
         }
     }
+
+    fn select_service(
+        mut commands: Commands,
+        buttons: Query<(&Toggled, &ServiceButton), Changed<Toggled>>,
+    ) {
+        for (toggled, service_button) in &buttons {
+            if toggled.0 {
+                debug!("selecting service `{:?}`", service_button.0);
+                commands.insert_resource(SelectedService(service_button.0));
+            }
+        }
+    }
+
+    fn show_bulldoze_dialog(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        bulldozing_lots: Query<(Entity, &BulldozingLot), Added<BulldozingLot>>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        let Ok((entity, bulldozing_lot)) = bulldozing_lots.get_single() else {
+            return;
+        };
+
+        info!("showing bulldoze dialog");
+        let text = format!(
+            "Bulldoze the lot? This will remove {} objects and {} walls.",
+            bulldozing_lot.objects, bulldozing_lot.walls
+        );
+        commands.entity(roots.single()).with_children(|parent| {
+            parent.spawn((
+                BulldozeDialog(entity),
+                ConfirmationDialogBundle::new(&theme, text)
+                    .with_accept("Bulldoze")
+                    .with_cancel("Cancel"),
+            ));
+        });
+    }
+
+    fn handle_bulldoze_results(
+        mut commands: Commands,
+        mut result_events: EventReader<DialogResult>,
+        mut bulldoze_events: EventWriter<LotBulldoze>,
+        dialogs: Query<&BulldozeDialog>,
+        bulldozing_lots: Query<&BulldozingLot>,
+    ) {
+        for event in result_events.read() {
+            let Ok(&BulldozeDialog(entity)) = dialogs.get(event.dialog_entity) else {
+                continue;
+            };
+
+            // Could be already despawned if the tool was switched.
+            let Ok(bulldozing_lot) = bulldozing_lots.get(entity) else {
+                continue;
+            };
+
+            if event.response == DialogResponse::Accept {
+                bulldoze_events.send(LotBulldoze(bulldozing_lot.lot_entity));
+            }
+            commands.entity(entity).despawn();
+        }
+    }
 }
 
-pub(super) fn setup(parent: &mut ChildBuilder, theme: &Theme) {
-    parent
+/// Dialog for the entity with [`BulldozingLot`].
+#[derive(Component)]
+struct BulldozeDialog(Entity);
+
+pub(super) fn setup(
+    parent: &mut ChildBuilder,
+    tab_commands: &mut Commands,
+    theme: &Theme,
+    services_info: &Assets<ServiceInfo>,
+) {
+    let tabs_entity = parent
         .spawn(NodeBundle {
             style: Style {
                 flex_direction: FlexDirection::Column,
@@ -41,14 +128,44 @@ pub(super) fn setup(parent: &mut ChildBuilder, theme: &Theme) {
             },
             ..Default::default()
         })
-        .with_children(|parent| {
-            for tool in LotTool::iter() {
-                parent.spawn((
-                    tool,
-                    ExclusiveButton,
-                    Toggled(tool == Default::default()),
-                    TextButtonBundle::symbol(theme, tool.glyph()),
-                ));
-            }
-        });
+        .id();
+
+    for tool in LotTool::iter() {
+        let mut button_entity = tab_commands.spawn((
+            tool,
+            ExclusiveButton,
+            Toggled(tool == Default::default()),
+            TextButtonBundle::symbol(theme, tool.glyph()),
+        ));
+        button_entity.set_parent(tabs_entity);
+
+        if tool == LotTool::Service {
+            let content_entity = parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: theme.gap.normal,
+                        padding: theme.padding.normal,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    for (id, info) in services_info.iter() {
+                        parent.spawn((
+                            ServiceButton(id),
+                            Toggled(false),
+                            ExclusiveButton,
+                            TextButtonBundle::normal(theme, info.general.name.clone()),
+                        ));
+                    }
+                })
+                .id();
+
+            button_entity.insert(TabContent(content_entity));
+        }
+    }
 }
+
+#[derive(Component)]
+struct ServiceButton(AssetId<ServiceInfo>);
@@ -4,7 +4,10 @@ use strum::IntoEnumIterator;
 use project_harmonia_base::{
     asset::info::road_info::RoadInfo,
     game_world::city::{
-        road::{placing_road::SpawnRoadId, RoadTool},
+        road::{
+            placing_road::{RoadDecoration, SpawnRoadId},
+            RoadTool,
+        },
         CityMode,
     },
 };
@@ -21,7 +24,12 @@ impl Plugin for RoadsNodePlugin {
         app.add_systems(OnEnter(CityMode::Roads), Self::sync_road_tool)
             .add_systems(
                 Update,
-                (Self::select, Self::show_popup, Self::set_road_tool)
+                (
+                    Self::select,
+                    Self::toggle_decoration,
+                    Self::show_popup,
+                    Self::set_road_tool,
+                )
                     .run_if(in_state(CityMode::Roads)),
             );
     }
@@ -37,6 +45,16 @@ impl RoadsNodePlugin {
         }
     }
 
+    fn toggle_decoration(
+        mut decoration: ResMut<RoadDecoration>,
+        buttons: Query<&Toggled, (Changed<Toggled>, With<DecorationButton>)>,
+    ) {
+        if let Ok(toggled) = buttons.get_single() {
+            info!("setting road decoration to `{}`", toggled.0);
+            decoration.0 = toggled.0;
+        }
+    }
+
     fn show_popup(
         mut commands: Commands,
         theme: Res<Theme>,
@@ -116,6 +134,7 @@ pub(super) fn setup(
     asset_server: &AssetServer,
     theme: &Theme,
     roads_info: &Assets<RoadInfo>,
+    decoration: &RoadDecoration,
 ) {
     let tabs_entity = parent
         .spawn(NodeBundle {
@@ -150,6 +169,11 @@ pub(super) fn setup(
                     ..Default::default()
                 })
                 .with_children(|parent| {
+                    parent.spawn((
+                        DecorationButton,
+                        Toggled(decoration.0),
+                        TextButtonBundle::symbol(theme, "🌳"),
+                    ));
                     for (id, info) in roads_info.iter() {
                         parent.spawn((
                             RoadButton(id),
@@ -168,3 +192,7 @@ pub(super) fn setup(
 
 #[derive(Component)]
 struct RoadButton(AssetId<RoadInfo>);
+
+/// Toggles [`RoadDecoration`] for new roads.
+#[derive(Component)]
+struct DecorationButton;
@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+use project_harmonia_base::game_world::city::{statistics::CityStatistics, ActiveCity, CityMode};
+use project_harmonia_widgets::{label::LabelBundle, theme::Theme};
+
+use crate::hud::statistics_graph;
+
+pub(super) struct StatisticsNodePlugin;
+
+impl Plugin for StatisticsNodePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (Self::update_labels, Self::update_graphs).run_if(in_state(CityMode::Statistics)),
+        );
+    }
+}
+
+impl StatisticsNodePlugin {
+    fn update_labels(
+        cities: Query<Ref<CityStatistics>, With<ActiveCity>>,
+        mut labels: Query<(&mut Text, Ref<StatisticsLabel>)>,
+    ) {
+        let Ok(statistics) = cities.get_single() else {
+            return;
+        };
+
+        for (mut text, label) in &mut labels {
+            if !statistics.is_changed() && !label.is_added() {
+                continue;
+            }
+
+            let value = match *label {
+                StatisticsLabel::Population => statistics.population.to_string(),
+                StatisticsLabel::Employment => {
+                    format!("{}/{}", statistics.employed, statistics.working_age)
+                }
+                StatisticsLabel::Wealth => format!("${}", statistics.wealth),
+                StatisticsLabel::ResidentialLots => statistics.residential_lots.to_string(),
+                StatisticsLabel::CommunityLots => statistics.community_lots.to_string(),
+                StatisticsLabel::VacantLots => statistics.vacant_lots.to_string(),
+                StatisticsLabel::AverageNeeds => format!("{:.0}%", statistics.needs),
+            };
+            text.sections[0].value = format!("{}: {value}", *label);
+        }
+    }
+
+    fn update_graphs(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        cities: Query<Ref<CityStatistics>, With<ActiveCity>>,
+        graphs: Query<(Entity, Ref<TrendGraph>, Ref<Node>)>,
+    ) {
+        let Ok(statistics) = cities.get_single() else {
+            return;
+        };
+
+        for (entity, graph, node) in &graphs {
+            if !statistics.is_changed() && !graph.is_added() && !node.is_changed() {
+                continue;
+            }
+
+            trace!("updating {:?} graph", *graph);
+            let samples = match *graph {
+                TrendGraph::Population => &statistics.population_trend,
+                TrendGraph::Wealth => &statistics.wealth_trend,
+                TrendGraph::Needs => &statistics.needs_trend,
+            };
+
+            statistics_graph::update(&mut commands, entity, node.size(), &theme, samples);
+        }
+    }
+}
+
+pub(super) fn setup(parent: &mut ChildBuilder, theme: &Theme) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(600.0),
+                column_gap: theme.gap.normal,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: theme.gap.normal,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    for label in StatisticsLabel::iter() {
+                        parent.spawn((label, LabelBundle::normal(theme, label.to_string())));
+                    }
+                });
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        flex_grow: 1.0,
+                        row_gap: theme.gap.normal,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    for graph in TrendGraph::iter() {
+                        parent.spawn(LabelBundle::normal(theme, graph.to_string()));
+                        parent.spawn((
+                            graph,
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Percent(100.0),
+                                    height: Val::Px(40.0),
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            },
+                        ));
+                    }
+                });
+        });
+}
+
+/// Label that displays the corresponding current value from [`CityStatistics`].
+#[derive(Clone, Component, Copy, Display, EnumIter)]
+enum StatisticsLabel {
+    Population,
+    Employment,
+    Wealth,
+    #[strum(serialize = "Residential lots")]
+    ResidentialLots,
+    #[strum(serialize = "Community lots")]
+    CommunityLots,
+    #[strum(serialize = "Vacant lots")]
+    VacantLots,
+    #[strum(serialize = "Average needs")]
+    AverageNeeds,
+}
+
+/// Node that displays the corresponding [`CityStatistics`] trend.
+#[derive(Clone, Component, Copy, Debug, Display, EnumIter)]
+enum TrendGraph {
+    Population,
+    Wealth,
+    Needs,
+}
@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+
+use project_harmonia_base::game_world::city::grid::CursorCoords;
+use project_harmonia_widgets::{label::LabelBundle, theme::Theme};
+
+/// Displays cursor position on the ground while the grid is visible.
+pub(super) struct CursorCoordsPlugin;
+
+impl Plugin for CursorCoordsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            Self::update.run_if(resource_exists_and_changed::<CursorCoords>),
+        );
+    }
+}
+
+impl CursorCoordsPlugin {
+    fn update(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        cursor_coords: Res<CursorCoords>,
+        mut readouts: Query<(Entity, &mut Text), With<CoordsReadout>>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        let Some(coords) = **cursor_coords else {
+            if let Ok((entity, _)) = readouts.get_single() {
+                debug!("hiding cursor coordinates");
+                commands.entity(entity).despawn_recursive();
+            }
+            return;
+        };
+
+        let text = format!("X: {:.2} Z: {:.2}", coords.x, coords.y);
+        if let Ok((_, mut readout_text)) = readouts.get_single_mut() {
+            readout_text.sections[0].value = text;
+        } else {
+            debug!("showing cursor coordinates");
+            commands.entity(roots.single()).with_children(|parent| {
+                parent
+                    .spawn((CoordsReadout, LabelBundle::normal(&theme, text)))
+                    .insert((
+                        Style {
+                            position_type: PositionType::Absolute,
+                            left: Val::Px(0.0),
+                            bottom: Val::Px(0.0),
+                            margin: theme.padding.global,
+                            padding: theme.padding.normal,
+                            ..Default::default()
+                        },
+                        BackgroundColor(theme.panel_color),
+                    ));
+            });
+        }
+    }
+}
+
+#[derive(Component)]
+struct CoordsReadout;
@@ -1,17 +1,30 @@
+mod actor_progress;
 mod building_hud;
 mod info_node;
 mod members_node;
 mod portrait_node;
+mod switcher_node;
 mod tasks_node;
+mod wall_view_node;
 
 use bevy::prelude::*;
+use bevy_replicon::prelude::*;
 use project_harmonia_base::{
-    asset::info::object_info::ObjectInfo,
+    asset::info::{
+        object_info::ObjectInfo, preset_info::PresetInfo, scenario_info::ScenarioFeature,
+    },
     game_world::{
         actor::SelectedActor,
-        family::{Budget, FamilyMembers, FamilyMode, FamilyPlugin, SelectedFamily},
+        aging::{Maintenance, WorldAging},
+        family::{
+            Budget, Family, FamilyMembers, FamilyMode, FamilyOwner, FamilyPlugin, FamilySwitch,
+            SelectedFamily,
+        },
+        player_camera::watching::WatchConsent,
+        scenario::Scenario,
         WorldState,
     },
+    settings::Settings,
 };
 use project_harmonia_widgets::{
     button::{ExclusiveButton, TabContent, TextButtonBundle, Toggled},
@@ -19,22 +32,28 @@ use project_harmonia_widgets::{
 };
 use strum::IntoEnumIterator;
 
+use actor_progress::ActorProgressPlugin;
 use building_hud::BuildingHudPlugin;
 use info_node::InfoNodePlugin;
 use members_node::MembersNodePlugin;
 use portrait_node::PortraitNodePlugin;
+use switcher_node::SwitcherNodePlugin;
 use tasks_node::TasksNodePlugin;
+use wall_view_node::WallViewNodePlugin;
 
 pub(super) struct FamilyHudPlugin;
 
 impl Plugin for FamilyHudPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((
+            ActorProgressPlugin,
             TasksNodePlugin,
             InfoNodePlugin,
             PortraitNodePlugin,
             MembersNodePlugin,
             BuildingHudPlugin,
+            SwitcherNodePlugin,
+            WallViewNodePlugin,
         ))
         .add_systems(
             OnEnter(WorldState::Family),
@@ -43,6 +62,13 @@ impl Plugin for FamilyHudPlugin {
         .add_systems(
             Update,
             Self::set_family_mode.run_if(in_state(WorldState::Family)),
+        )
+        .add_systems(
+            PostUpdate,
+            (Self::cleanup, Self::setup)
+                .chain()
+                .run_if(on_event::<FamilySwitch>())
+                .run_if(in_state(WorldState::Family)),
         );
     }
 }
@@ -52,13 +78,26 @@ impl FamilyHudPlugin {
         mut commands: Commands,
         mut tab_commands: Commands,
         theme: Res<Theme>,
+        settings: Res<Settings>,
+        asset_server: Res<AssetServer>,
         objects_info: Res<Assets<ObjectInfo>>,
-        families: Query<(&Budget, &FamilyMembers), With<SelectedFamily>>,
+        presets_info: Res<Assets<PresetInfo>>,
+        client: Res<RepliconClient>,
+        server: Res<RepliconServer>,
+        consent: Res<WatchConsent>,
+        families: Query<(&Budget, &FamilyMembers, Has<Maintenance>), With<SelectedFamily>>,
+        other_families: Query<
+            (Entity, &Name, &FamilyOwner),
+            (With<Family>, Without<SelectedFamily>),
+        >,
         actors: Query<Entity, With<SelectedActor>>,
+        scenarios: Query<&Scenario>,
+        aging: Query<(), With<WorldAging>>,
     ) {
         debug!("showing family hud");
         commands
             .spawn((
+                FamilyHud,
                 StateScoped(WorldState::Family),
                 NodeBundle {
                     style: Style {
@@ -81,9 +120,21 @@ impl FamilyHudPlugin {
                         background_color: theme.panel_color.into(),
                         ..Default::default()
                     })
+                    .with_children(|parent| {
+                        wall_view_node::setup(parent, &theme, settings.gameplay.wall_view)
+                    })
                     .id();
 
                 for mode in FamilyMode::iter() {
+                    if mode == FamilyMode::Building
+                        && scenarios
+                            .get_single()
+                            .is_ok_and(|scenario| scenario.is_locked(ScenarioFeature::Building))
+                    {
+                        debug!("skipping building locked by scenario");
+                        continue;
+                    }
+
                     let content_entity = parent
                         .spawn(NodeBundle {
                             style: Style {
@@ -96,16 +147,28 @@ impl FamilyHudPlugin {
                             FamilyMode::Life => {
                                 tasks_node::setup(parent, &theme);
 
-                                let (&budget, members) = families.single();
-                                portrait_node::setup(parent, &theme, budget);
+                                let (&budget, members, hired) = families.single();
+                                let multiplayer = client.is_connected() || server.is_running();
+                                let maintenance = (!aging.is_empty()).then_some(hired);
+                                portrait_node::setup(
+                                    parent,
+                                    &theme,
+                                    budget,
+                                    &consent,
+                                    multiplayer,
+                                    maintenance,
+                                );
                                 members_node::setup(parent, &theme, members, actors.single());
                                 info_node::setup(parent, &mut tab_commands, &theme);
+                                switcher_node::setup(parent, &theme, &client, &other_families);
                             }
                             FamilyMode::Building => building_hud::setup(
                                 parent,
                                 &mut tab_commands,
                                 &theme,
+                                &asset_server,
                                 &objects_info,
+                                &presets_info,
                             ),
                         })
                         .id();
@@ -123,6 +186,17 @@ impl FamilyHudPlugin {
             });
     }
 
+    /// Removes HUD of the previous family on switch.
+    fn cleanup(
+        mut commands: Commands,
+        mut family_mode: ResMut<NextState<FamilyMode>>,
+        huds: Query<Entity, With<FamilyHud>>,
+    ) {
+        debug!("removing family hud");
+        commands.entity(huds.single()).despawn_recursive();
+        family_mode.set(Default::default());
+    }
+
     fn set_family_mode(
         mut family_mode: ResMut<NextState<FamilyMode>>,
         buttons: Query<(Ref<Toggled>, &FamilyMode), Changed<Toggled>>,
@@ -135,3 +209,6 @@ impl FamilyHudPlugin {
         }
     }
 }
+
+#[derive(Component)]
+struct FamilyHud;
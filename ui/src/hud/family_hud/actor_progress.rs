@@ -0,0 +1,117 @@
+use bevy::{prelude::*, transform::TransformSystem};
+
+use project_harmonia_base::game_world::{
+    actor::task::TaskProgress, player_camera::PlayerCamera, WorldState,
+};
+use project_harmonia_widgets::{
+    progress_bar::{ProgressBar, ProgressBarBundle},
+    theme::Theme,
+};
+
+/// Shows progress of long-running tasks above actors.
+pub(super) struct ActorProgressPlugin;
+
+impl Plugin for ActorProgressPlugin {
+    fn build(&self, app: &mut App) {
+        app.observe(Self::cleanup).add_systems(
+            PostUpdate,
+            (
+                Self::update_bars,
+                Self::follow_actors.after(TransformSystem::TransformPropagate),
+            )
+                .run_if(in_state(WorldState::Family)),
+        );
+    }
+}
+
+/// Height above the actor origin.
+const BAR_OFFSET: f32 = 2.1;
+
+const BAR_SIZE: Vec2 = Vec2::new(60.0, 6.0);
+
+impl ActorProgressPlugin {
+    fn update_bars(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        tasks: Query<(Entity, &TaskProgress), Changed<TaskProgress>>,
+        mut bars: Query<(&mut ProgressBar, &ActorBar)>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        for (task_entity, progress) in &tasks {
+            if let Some((mut progress_bar, _)) = bars
+                .iter_mut()
+                .find(|(_, actor_bar)| actor_bar.0 == task_entity)
+            {
+                trace!("updating actor bar for `{task_entity}`");
+                progress_bar.0 = progress.value() * 100.0;
+            } else {
+                debug!("creating actor bar for `{task_entity}`");
+                commands.entity(roots.single()).with_children(|parent| {
+                    parent
+                        .spawn((
+                            ActorBar(task_entity),
+                            ProgressBarBundle::new(&theme, progress.value() * 100.0),
+                        ))
+                        .insert((
+                            Style {
+                                position_type: PositionType::Absolute,
+                                width: Val::Px(BAR_SIZE.x),
+                                height: Val::Px(BAR_SIZE.y),
+                                ..Default::default()
+                            },
+                            ZIndex::Global(-1),
+                        ));
+                });
+            }
+        }
+    }
+
+    fn follow_actors(
+        cameras: Query<(&GlobalTransform, &Camera), With<PlayerCamera>>,
+        tasks: Query<&Parent>,
+        actors: Query<&GlobalTransform>,
+        mut bars: Query<(&mut Style, &ActorBar)>,
+    ) {
+        let Ok((camera_transform, camera)) = cameras.get_single() else {
+            return;
+        };
+
+        for (mut style, actor_bar) in &mut bars {
+            let position = tasks
+                .get(actor_bar.0)
+                .and_then(|parent| actors.get(**parent))
+                .ok()
+                .and_then(|actor_transform| {
+                    let point = actor_transform.translation() + Vec3::Y * BAR_OFFSET;
+                    camera.world_to_viewport(camera_transform, point)
+                });
+
+            if let Some(position) = position {
+                let position = position - BAR_SIZE / 2.0;
+                style.display = Display::Flex;
+                style.left = Val::Px(position.x);
+                style.top = Val::Px(position.y);
+            } else {
+                style.display = Display::None;
+            }
+        }
+    }
+
+    fn cleanup(
+        trigger: Trigger<OnRemove, TaskProgress>,
+        mut commands: Commands,
+        bars: Query<(Entity, &ActorBar)>,
+    ) {
+        if let Some((entity, _)) = bars
+            .iter()
+            .find(|(_, actor_bar)| actor_bar.0 == trigger.entity())
+        {
+            debug!("removing actor bar `{entity}` for `{}`", trigger.entity());
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Progress bar above an actor for the stored task entity.
+#[derive(Component)]
+struct ActorBar(Entity);
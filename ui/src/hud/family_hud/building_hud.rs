@@ -1,8 +1,14 @@
+mod decorations_node;
+mod permissions_node;
+mod presets_node;
 mod walls_node;
 
 use bevy::prelude::*;
 use project_harmonia_base::{
-    asset::info::object_info::{ObjectCategory, ObjectInfo},
+    asset::info::{
+        object_info::{ObjectCategory, ObjectInfo},
+        preset_info::PresetInfo,
+    },
     game_world::family::{building::BuildingMode, FamilyMode},
 };
 use project_harmonia_widgets::{
@@ -12,18 +18,26 @@ use project_harmonia_widgets::{
 use strum::IntoEnumIterator;
 
 use crate::hud::{objects_node, tools_node};
+use decorations_node::DecorationsNodePlugin;
+use permissions_node::PermissionsNodePlugin;
+use presets_node::PresetsNodePlugin;
 use walls_node::WallsNodePlugin;
 
 pub(super) struct BuildingHudPlugin;
 
 impl Plugin for BuildingHudPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(WallsNodePlugin)
-            .add_systems(OnEnter(FamilyMode::Building), Self::sync_building_mode)
-            .add_systems(
-                Update,
-                Self::set_building_mode.run_if(in_state(FamilyMode::Building)),
-            );
+        app.add_plugins((
+            DecorationsNodePlugin,
+            PermissionsNodePlugin,
+            PresetsNodePlugin,
+            WallsNodePlugin,
+        ))
+        .add_systems(OnEnter(FamilyMode::Building), Self::sync_building_mode)
+        .add_systems(
+            Update,
+            Self::set_building_mode.run_if(in_state(FamilyMode::Building)),
+        );
     }
 }
 
@@ -60,9 +74,12 @@ pub(super) fn setup(
     parent: &mut ChildBuilder,
     tab_commands: &mut Commands,
     theme: &Theme,
+    asset_server: &AssetServer,
     objects_info: &Assets<ObjectInfo>,
+    presets_info: &Assets<PresetInfo>,
 ) {
     tools_node::setup(parent, theme);
+    permissions_node::setup(parent, theme);
 
     let tabs_entity = parent
         .spawn(NodeBundle {
@@ -100,6 +117,10 @@ pub(super) fn setup(
                     );
                 }
                 BuildingMode::Walls => walls_node::setup(parent, theme),
+                BuildingMode::Decorations => decorations_node::setup(parent, theme, objects_info),
+                BuildingMode::Presets => {
+                    presets_node::setup(parent, theme, asset_server, objects_info, presets_info)
+                }
             })
             .id();
 
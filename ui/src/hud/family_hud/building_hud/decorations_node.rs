@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+use project_harmonia_base::{
+    asset::info::object_info::ObjectInfo,
+    game_world::family::building::{
+        decoration::{EdgeDecoration, SelectedDecoration},
+        BuildingMode,
+    },
+};
+use project_harmonia_widgets::{
+    button::{ExclusiveButton, TextButtonBundle, Toggled},
+    theme::Theme,
+};
+
+pub(super) struct DecorationsNodePlugin;
+
+impl Plugin for DecorationsNodePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(BuildingMode::Decorations), Self::sync_decoration)
+            .add_systems(
+                Update,
+                Self::select_decoration.run_if(in_state(BuildingMode::Decorations)),
+            );
+    }
+}
+
+impl DecorationsNodePlugin {
+    fn select_decoration(
+        mut commands: Commands,
+        buttons: Query<(Ref<Toggled>, &DecorationButton), Changed<Toggled>>,
+    ) {
+        for (toggled, button) in &buttons {
+            if toggled.0 && !toggled.is_added() {
+                info!("selecting decoration `{:?}`", button.0);
+                commands.insert_resource(SelectedDecoration(button.0));
+            }
+        }
+    }
+
+    /// Selects the last selected decoration.
+    ///
+    /// Needed because on switching tab the selection resets, but selected button doesn't.
+    fn sync_decoration(mut commands: Commands, buttons: Query<(&Toggled, &DecorationButton)>) {
+        for (toggled, button) in &buttons {
+            if toggled.0 {
+                debug!("syncing decoration to `{:?}`", button.0);
+                commands.insert_resource(SelectedDecoration(button.0));
+            }
+        }
+    }
+}
+
+pub(super) fn setup(parent: &mut ChildBuilder, theme: &Theme, objects_info: &Assets<ObjectInfo>) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                row_gap: theme.gap.normal,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            for (id, info) in objects_info
+                .iter()
+                .filter(|(_, info)| EdgeDecoration::from_info(info).is_some())
+            {
+                parent.spawn((
+                    DecorationButton(id),
+                    ExclusiveButton,
+                    Toggled(false),
+                    TextButtonBundle::normal(theme, info.general.name.clone()),
+                ));
+            }
+        });
+}
+
+/// Selects the decoration to place when toggled.
+#[derive(Component)]
+struct DecorationButton(AssetId<ObjectInfo>);
@@ -0,0 +1,159 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use project_harmonia_base::game_world::{
+    city::lot::{
+        permissions::{LotBuilders, LotPermissionChange},
+        LotFamily,
+    },
+    family::{FamilyMode, FamilyOwner, SelectedFamily},
+};
+use project_harmonia_widgets::{
+    button::{TextButtonBundle, Toggled},
+    label::LabelBundle,
+    theme::Theme,
+};
+
+pub(super) struct PermissionsNodePlugin;
+
+impl Plugin for PermissionsNodePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (Self::update_players, Self::change_permission).run_if(in_state(FamilyMode::Building)),
+        );
+    }
+}
+
+impl PermissionsNodePlugin {
+    /// Lists players that control other families with their build rights on the family lot.
+    fn update_players(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        client: Res<RepliconClient>,
+        lists: Query<(Entity, Ref<PlayersList>)>,
+        families: Query<Entity, With<SelectedFamily>>,
+        lots: Query<(Entity, &LotFamily, Ref<LotBuilders>)>,
+        owners: Query<Ref<FamilyOwner>>,
+    ) {
+        let Ok((list_entity, list)) = lists.get_single() else {
+            return;
+        };
+        if !list.is_added()
+            && !lots.iter().any(|(.., builders)| builders.is_changed())
+            && !owners.iter().any(|owner| owner.is_changed())
+        {
+            return;
+        }
+
+        let family_entity = families.single();
+        let lot = lots
+            .iter()
+            .find(|(_, lot_family, _)| ***lot_family == family_entity);
+
+        let mut players = Vec::new();
+        for owner in owners.iter().filter(|owner| !owner.is_local(&client)) {
+            if !players.contains(&**owner) {
+                players.push(**owner);
+            }
+        }
+
+        debug!("updating build rights list");
+        commands
+            .entity(list_entity)
+            .despawn_descendants()
+            .with_children(|parent| {
+                let Some((lot_entity, _, builders)) = lot else {
+                    parent.spawn(LabelBundle::normal(&theme, "The family has no lot"));
+                    return;
+                };
+                if players.is_empty() {
+                    parent.spawn(LabelBundle::normal(&theme, "No other players"));
+                }
+                for client_id in players {
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                align_items: AlignItems::Center,
+                                column_gap: theme.gap.normal,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn(LabelBundle::normal(
+                                &theme,
+                                format!("Player {}", client_id.get()),
+                            ));
+                            parent.spawn((
+                                PermissionButton {
+                                    lot_entity,
+                                    client_id,
+                                },
+                                Toggled(builders.contains(&client_id)),
+                                TextButtonBundle::normal(&theme, "Can build"),
+                            ));
+                        });
+                }
+            });
+    }
+
+    fn change_permission(
+        mut change_events: EventWriter<LotPermissionChange>,
+        buttons: Query<(Ref<Toggled>, &PermissionButton), Changed<Toggled>>,
+    ) {
+        for (toggled, button) in &buttons {
+            if !toggled.is_added() {
+                info!(
+                    "changing build rights of `{:?}` to `{}`",
+                    button.client_id, toggled.0
+                );
+                change_events.send(LotPermissionChange {
+                    lot_entity: button.lot_entity,
+                    client_id: button.client_id,
+                    allowed: toggled.0,
+                });
+            }
+        }
+    }
+}
+
+pub(super) fn setup(parent: &mut ChildBuilder, theme: &Theme) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(0.0),
+                left: Val::Px(0.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: theme.gap.normal,
+                padding: theme.padding.normal,
+                ..Default::default()
+            },
+            background_color: theme.panel_color.into(),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent.spawn(LabelBundle::normal(theme, "Build rights"));
+            parent.spawn((
+                PlayersList,
+                NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: theme.gap.normal,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            ));
+        });
+}
+
+/// Node with players who can receive build rights.
+#[derive(Component)]
+struct PlayersList;
+
+#[derive(Component)]
+struct PermissionButton {
+    lot_entity: Entity,
+    client_id: ClientId,
+}
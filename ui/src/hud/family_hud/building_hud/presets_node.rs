@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use project_harmonia_base::{
+    asset::info::{object_info::ObjectInfo, preset_info::PresetInfo},
+    game_world::family::building::{preset::SelectedPreset, BuildingMode},
+};
+use project_harmonia_widgets::{
+    button::{ExclusiveButton, TextButtonBundle, Toggled},
+    theme::Theme,
+};
+
+pub(super) struct PresetsNodePlugin;
+
+impl Plugin for PresetsNodePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(BuildingMode::Presets), Self::sync_preset)
+            .add_systems(
+                Update,
+                Self::select_preset.run_if(in_state(BuildingMode::Presets)),
+            );
+    }
+}
+
+impl PresetsNodePlugin {
+    fn select_preset(
+        mut commands: Commands,
+        buttons: Query<(Ref<Toggled>, &PresetButton), Changed<Toggled>>,
+    ) {
+        for (toggled, button) in &buttons {
+            if toggled.0 && !toggled.is_added() {
+                info!("selecting preset `{:?}`", button.0);
+                commands.insert_resource(SelectedPreset(button.0));
+            }
+        }
+    }
+
+    /// Selects the last selected preset.
+    ///
+    /// Needed because on switching tab the selection resets, but selected button doesn't.
+    fn sync_preset(mut commands: Commands, buttons: Query<(&Toggled, &PresetButton)>) {
+        for (toggled, button) in &buttons {
+            if toggled.0 {
+                debug!("syncing preset to `{:?}`", button.0);
+                commands.insert_resource(SelectedPreset(button.0));
+            }
+        }
+    }
+}
+
+pub(super) fn setup(
+    parent: &mut ChildBuilder,
+    theme: &Theme,
+    asset_server: &AssetServer,
+    objects_info: &Assets<ObjectInfo>,
+    presets_info: &Assets<PresetInfo>,
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                row_gap: theme.gap.normal,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            for (id, info) in presets_info.iter() {
+                let cost = match info.cost(asset_server, objects_info) {
+                    Ok(cost) => cost,
+                    Err(e) => {
+                        error!("ignoring preset '{}': {e:#}", info.general.name);
+                        continue;
+                    }
+                };
+                parent.spawn((
+                    PresetButton(id),
+                    ExclusiveButton,
+                    Toggled(false),
+                    TextButtonBundle::normal(theme, format!("{} ({cost})", info.general.name)),
+                ));
+            }
+        });
+}
+
+/// Selects the preset to place when toggled.
+#[derive(Component)]
+struct PresetButton(AssetId<PresetInfo>);
@@ -1,10 +1,24 @@
-use bevy::prelude::*;
-use project_harmonia_base::game_world::{
-    actor::{
-        needs::{Need, NeedGlyph},
-        SelectedActor,
+use std::{fs, path::PathBuf};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        texture::{CompressedImageFormats, ImageSampler, ImageType},
+    },
+    tasks::{block_on, futures_lite::future, IoTaskPool, Task},
+    utils::HashMap,
+};
+use project_harmonia_base::{
+    game_paths::GamePaths,
+    game_world::{
+        actor::{
+            needs::{Need, NeedGlyph},
+            SelectedActor,
+        },
+        family::{memories::FamilyMemories, statistics::FamilyStatistics, SelectedFamily},
+        WorldName, WorldState,
     },
-    WorldState,
 };
 use project_harmonia_widgets::{
     button::{ExclusiveButton, TabContent, TextButtonBundle, Toggled},
@@ -12,16 +26,25 @@ use project_harmonia_widgets::{
     progress_bar::{ProgressBar, ProgressBarBundle},
     theme::Theme,
 };
-use strum::{EnumIter, IntoEnumIterator};
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+use crate::hud::statistics_graph;
 
 pub(super) struct InfoNodePlugin;
 
 impl Plugin for InfoNodePlugin {
     fn build(&self, app: &mut App) {
-        app.observe(Self::cleanup_need_bars).add_systems(
-            Update,
-            Self::update_need_bars.run_if(in_state(WorldState::Family)),
-        );
+        app.init_resource::<AlbumSnapshots>()
+            .observe(Self::cleanup_need_bars)
+            .add_systems(
+                Update,
+                (
+                    Self::update_need_bars,
+                    Self::update_graphs,
+                    (Self::update_album, Self::show_snapshots).chain(),
+                )
+                    .run_if(in_state(WorldState::Family)),
+            );
     }
 }
 
@@ -64,6 +87,154 @@ impl InfoNodePlugin {
         }
     }
 
+    fn update_graphs(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        families: Query<Ref<FamilyStatistics>, With<SelectedFamily>>,
+        graphs: Query<(Entity, Ref<StatisticsGraph>, Ref<Node>)>,
+    ) {
+        let Ok(statistics) = families.get_single() else {
+            return;
+        };
+
+        for (entity, graph, node) in &graphs {
+            if !statistics.is_changed() && !graph.is_added() && !node.is_changed() {
+                continue;
+            }
+
+            trace!("updating {:?} graph", *graph);
+            let samples = match *graph {
+                StatisticsGraph::Budget => &statistics.budget,
+                StatisticsGraph::Needs => &statistics.needs,
+                StatisticsGraph::Handiness => &statistics.handiness,
+            };
+
+            statistics_graph::update(&mut commands, entity, node.size(), &theme, samples);
+        }
+    }
+
+    /// Lists family memories from the newest.
+    ///
+    /// Also updates on opening the tab to show snapshots that were saved after the memory was recorded.
+    /// Snapshots are decoded in [`IoTaskPool`] and displayed by [`Self::show_snapshots`].
+    fn update_album(
+        mut commands: Commands,
+        mut snapshots: ResMut<AlbumSnapshots>,
+        theme: Res<Theme>,
+        game_paths: Res<GamePaths>,
+        world_name: Option<Res<WorldName>>,
+        families: Query<Ref<FamilyMemories>, With<SelectedFamily>>,
+        tabs: Query<(Ref<Toggled>, &TabContent, &InfoTab)>,
+    ) {
+        let Ok(memories) = families.get_single() else {
+            return;
+        };
+        let Some((toggled, tab_content, _)) = tabs.iter().find(|(.., &tab)| tab == InfoTab::Album)
+        else {
+            return;
+        };
+        if !memories.is_changed() && !(toggled.is_changed() && toggled.0) {
+            return;
+        }
+
+        trace!("updating album");
+        // Forget snapshots of removed memories.
+        snapshots.retain(|file_name, _| {
+            memories
+                .iter()
+                .any(|memory| memory.snapshot.as_ref() == Some(file_name))
+        });
+
+        commands.entity(tab_content.0).despawn_descendants();
+        commands.entity(tab_content.0).with_children(|parent| {
+            for memory in memories.iter().rev() {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            column_gap: theme.gap.normal,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        if let (Some(file_name), Some(world_name)) = (&memory.snapshot, &world_name)
+                        {
+                            // Hidden until loaded.
+                            let mut entity = parent.spawn((
+                                AlbumSnapshot(file_name.clone()),
+                                ImageBundle {
+                                    style: Style {
+                                        display: Display::None,
+                                        width: Val::Px(SNAPSHOT_WIDTH),
+                                        ..Default::default()
+                                    },
+                                    ..Default::default()
+                                },
+                            ));
+                            if !snapshots.contains_key(file_name) {
+                                let path = game_paths.snapshots_dir(&world_name.0).join(file_name);
+                                let task = IoTaskPool::get().spawn(async { load_snapshot(path) });
+                                entity.insert(SnapshotTask(task));
+                            }
+                        }
+                        parent.spawn(LabelBundle::normal(
+                            &theme,
+                            format!(
+                                "{} Day {}, {:02}:00\n{}",
+                                memory.kind.glyph(),
+                                memory.day + 1,
+                                memory.hour,
+                                memory.description
+                            ),
+                        ));
+                    });
+            }
+        });
+    }
+
+    /// Displays snapshots that finished loading.
+    ///
+    /// Snapshots are stored only on the host and may be missing.
+    fn show_snapshots(
+        mut commands: Commands,
+        mut snapshots: ResMut<AlbumSnapshots>,
+        mut images: ResMut<Assets<Image>>,
+        mut tasks: Query<(Entity, &AlbumSnapshot, &mut SnapshotTask)>,
+        mut nodes: Query<(Entity, Ref<AlbumSnapshot>, &mut Style, &mut UiImage)>,
+    ) {
+        let mut loaded = Vec::new();
+        for (entity, snapshot, mut task) in &mut tasks {
+            let Some(image) = block_on(future::poll_once(&mut task.0)) else {
+                continue;
+            };
+
+            commands.entity(entity).remove::<SnapshotTask>();
+            match image {
+                Some(image) => {
+                    let aspect_ratio = image.width() as f32 / image.height() as f32;
+                    snapshots.insert(snapshot.0.clone(), (images.add(image), aspect_ratio));
+                    loaded.push(entity);
+                }
+                None => {
+                    debug!("snapshot '{}' is missing", snapshot.0);
+                    commands.entity(entity).despawn_recursive();
+                }
+            }
+        }
+
+        // Also covers new nodes for snapshots that were loaded earlier.
+        for (entity, snapshot, mut style, mut ui_image) in &mut nodes {
+            if !snapshot.is_added() && !loaded.contains(&entity) {
+                continue;
+            }
+            if let Some((handle, aspect_ratio)) = snapshots.get(&snapshot.0) {
+                style.display = Display::Flex;
+                style.aspect_ratio = Some(*aspect_ratio);
+                ui_image.texture = handle.clone();
+            }
+        }
+    }
+
     fn cleanup_need_bars(
         trigger: Trigger<OnRemove, Need>,
         mut commands: Commands,
@@ -128,6 +299,50 @@ pub(super) fn setup(parent: &mut ChildBuilder, tab_commands: &mut Commands, them
                         })
                         .id(),
                     InfoTab::Skills => parent.spawn(NodeBundle::default()).id(),
+                    InfoTab::Album => parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                width: Val::Px(400.0),
+                                max_height: Val::Px(400.0),
+                                overflow: Overflow::clip_y(),
+                                row_gap: theme.gap.normal,
+                                padding: theme.padding.normal,
+                                ..Default::default()
+                            },
+                            background_color: theme.panel_color.into(),
+                            ..Default::default()
+                        })
+                        .id(),
+                    InfoTab::Statistics => parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                width: Val::Px(400.0),
+                                row_gap: theme.gap.normal,
+                                padding: theme.padding.normal,
+                                ..Default::default()
+                            },
+                            background_color: theme.panel_color.into(),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            for graph in StatisticsGraph::iter() {
+                                parent.spawn(LabelBundle::normal(theme, graph.to_string()));
+                                parent.spawn((
+                                    graph,
+                                    NodeBundle {
+                                        style: Style {
+                                            width: Val::Percent(100.0),
+                                            height: Val::Px(60.0),
+                                            ..Default::default()
+                                        },
+                                        ..Default::default()
+                                    },
+                                ));
+                            }
+                        })
+                        .id(),
                 };
 
                 tab_commands
@@ -143,13 +358,52 @@ pub(super) fn setup(parent: &mut ChildBuilder, tab_commands: &mut Commands, them
         });
 }
 
+const SNAPSHOT_WIDTH: f32 = 120.0;
+
+/// Reads and decodes a memory snapshot.
+fn load_snapshot(path: PathBuf) -> Option<Image> {
+    let bytes = fs::read(&path).ok()?;
+    Image::from_buffer(
+        &bytes,
+        ImageType::Extension("png"),
+        CompressedImageFormats::NONE,
+        true,
+        ImageSampler::Default,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+    .map_err(|e| error!("unable to decode {path:?}: {e}"))
+    .ok()
+}
+
+/// Loaded snapshots with their aspect ratios by file names.
+#[derive(Default, Resource, Deref, DerefMut)]
+struct AlbumSnapshots(HashMap<String, (Handle<Image>, f32)>);
+
+/// Image node of a memory snapshot with the file name.
+#[derive(Component)]
+struct AlbumSnapshot(String);
+
+/// Snapshot loading for [`AlbumSnapshot`].
+#[derive(Component)]
+struct SnapshotTask(Task<Option<Image>>);
+
 #[derive(Component)]
 struct BarNeed(Entity);
 
+/// Node that displays the corresponding [`FamilyStatistics`] samples.
+#[derive(Clone, Component, Copy, Debug, Display, EnumIter)]
+enum StatisticsGraph {
+    Budget,
+    Needs,
+    Handiness,
+}
+
 #[derive(Component, EnumIter, Clone, Copy, PartialEq)]
 enum InfoTab {
     Needs,
     Skills,
+    Statistics,
+    Album,
 }
 
 impl InfoTab {
@@ -157,6 +411,8 @@ impl InfoTab {
         match self {
             InfoTab::Needs => "📈",
             InfoTab::Skills => "💡",
+            InfoTab::Statistics => "📊",
+            InfoTab::Album => "📷",
         }
     }
 }
@@ -1,9 +1,15 @@
 use bevy::prelude::*;
 use project_harmonia_base::game_world::{
-    family::{Budget, SelectedFamily},
+    aging::{Maintenance, MaintenanceHire, MAINTENANCE_COST},
+    family::{utilities::UtilityBills, Budget, SelectedFamily},
+    player_camera::watching::WatchConsent,
     WorldState,
 };
-use project_harmonia_widgets::{label::LabelBundle, theme::Theme};
+use project_harmonia_widgets::{
+    button::{TextButtonBundle, Toggled},
+    label::LabelBundle,
+    theme::Theme,
+};
 
 pub(super) struct PortraitNodePlugin;
 
@@ -11,7 +17,14 @@ impl Plugin for PortraitNodePlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            Self::update_budget.run_if(in_state(WorldState::Family)),
+            (
+                Self::update_budget,
+                Self::update_bills,
+                Self::update_consent,
+                Self::hire_maintenance,
+                Self::cancel_maintenance,
+            )
+                .run_if(in_state(WorldState::Family)),
         );
     }
 }
@@ -26,16 +39,108 @@ impl PortraitNodePlugin {
             labels.single_mut().sections[0].value = budget.to_string();
         }
     }
+
+    fn update_bills(
+        families: Query<Ref<UtilityBills>, With<SelectedFamily>>,
+        mut labels: Query<(&mut Text, &mut Style, Ref<BillsLabel>)>,
+    ) {
+        let Ok(bills) = families.get_single() else {
+            return;
+        };
+        let Ok((mut text, mut style, label)) = labels.get_single_mut() else {
+            return;
+        };
+        if !bills.is_changed() && !label.is_added() {
+            return;
+        }
+
+        debug!("changing bills to {}", bills.due);
+        if bills.due == 0 {
+            style.display = Display::None;
+        } else {
+            style.display = Display::Flex;
+            text.sections[0].value = if bills.overdue {
+                format!("Utilities shut off, bills due: {}", bills.due)
+            } else {
+                format!("Bills due: {}", bills.due)
+            };
+        }
+    }
+
+    fn update_consent(
+        mut consent: ResMut<WatchConsent>,
+        buttons: Query<Ref<Toggled>, (Changed<Toggled>, With<WatchConsentButton>)>,
+    ) {
+        if let Ok(toggled) = buttons.get_single() {
+            if !toggled.is_added() {
+                info!("changing watch consent to `{}`", toggled.0);
+                consent.0 = toggled.0;
+            }
+        }
+    }
+
+    fn hire_maintenance(
+        mut hire_events: EventWriter<MaintenanceHire>,
+        buttons: Query<Ref<Toggled>, (Changed<Toggled>, With<MaintenanceButton>)>,
+        families: Query<(Entity, Has<Maintenance>), With<SelectedFamily>>,
+    ) {
+        let Ok(toggled) = buttons.get_single() else {
+            return;
+        };
+        let Ok((family_entity, hired)) = families.get_single() else {
+            return;
+        };
+        if toggled.is_added() || toggled.0 == hired {
+            return;
+        }
+
+        info!(
+            "changing maintenance for `{family_entity}` to `{}`",
+            toggled.0
+        );
+        hire_events.send(MaintenanceHire {
+            family_entity,
+            hire: toggled.0,
+        });
+    }
+
+    /// Releases the button when the server cancels maintenance due to insufficient budget.
+    fn cancel_maintenance(
+        mut removed_maintenance: RemovedComponents<Maintenance>,
+        families: Query<(), With<SelectedFamily>>,
+        mut buttons: Query<&mut Toggled, With<MaintenanceButton>>,
+    ) {
+        if families
+            .iter_many(removed_maintenance.read())
+            .next()
+            .is_some()
+        {
+            if let Ok(mut toggled) = buttons.get_single_mut() {
+                if toggled.0 {
+                    debug!("releasing maintenance button");
+                    toggled.0 = false;
+                }
+            }
+        }
+    }
 }
 
-pub(super) fn setup(parent: &mut ChildBuilder, theme: &Theme, budget: Budget) {
+pub(super) fn setup(
+    parent: &mut ChildBuilder,
+    theme: &Theme,
+    budget: Budget,
+    consent: &WatchConsent,
+    multiplayer: bool,
+    maintenance: Option<bool>,
+) {
     parent
         .spawn(NodeBundle {
             style: Style {
                 width: Val::Px(180.0),
-                height: Val::Px(30.0),
+                min_height: Val::Px(30.0),
+                flex_direction: FlexDirection::Column,
                 align_self: AlignSelf::FlexEnd,
-                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
                 ..Default::default()
             },
             background_color: theme.panel_color.into(),
@@ -43,8 +148,38 @@ pub(super) fn setup(parent: &mut ChildBuilder, theme: &Theme, budget: Budget) {
         })
         .with_children(|parent| {
             parent.spawn((BudgetLabel, LabelBundle::normal(theme, budget.to_string())));
+            // Visibility will be updated based on bills.
+            parent.spawn((BillsLabel, LabelBundle::normal(theme, String::new())));
+            // Selection sharing makes sense only with other players.
+            let display = if multiplayer {
+                Display::Flex
+            } else {
+                Display::None
+            };
+            parent.spawn((
+                WatchConsentButton,
+                Toggled(consent.0),
+                TextButtonBundle::normal(theme, "Allow watching").with_display(display),
+            ));
+            // Maintenance is available only in aging worlds.
+            if let Some(hired) = maintenance {
+                parent.spawn((
+                    MaintenanceButton,
+                    Toggled(hired),
+                    TextButtonBundle::normal(theme, format!("Maintenance ({MAINTENANCE_COST})")),
+                ));
+            }
         });
 }
 
 #[derive(Component)]
 struct BudgetLabel;
+
+#[derive(Component)]
+struct BillsLabel;
+
+#[derive(Component)]
+struct WatchConsentButton;
+
+#[derive(Component)]
+struct MaintenanceButton;
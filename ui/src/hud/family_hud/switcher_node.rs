@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use project_harmonia_base::game_world::{
+    family::{Family, FamilyOwner, FamilySwitch, SelectedFamily},
+    WorldState,
+};
+use project_harmonia_widgets::{button::TextButtonBundle, click::Click, theme::Theme};
+
+pub(super) struct SwitcherNodePlugin;
+
+impl Plugin for SwitcherNodePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            Self::switch_family.run_if(in_state(WorldState::Family)),
+        );
+    }
+}
+
+impl SwitcherNodePlugin {
+    fn switch_family(
+        mut switch_events: EventWriter<FamilySwitch>,
+        mut click_events: EventReader<Click>,
+        buttons: Query<&SwitchFamily>,
+    ) {
+        if let Some(button) = buttons
+            .iter_many(click_events.read().map(|event| event.0))
+            .last()
+        {
+            switch_events.send(FamilySwitch(button.0));
+        }
+    }
+}
+
+/// Creates buttons for other families controlled by the player.
+///
+/// Not spawned if the player controls only one family.
+pub(super) fn setup(
+    parent: &mut ChildBuilder,
+    theme: &Theme,
+    client: &RepliconClient,
+    families: &Query<(Entity, &Name, &FamilyOwner), (With<Family>, Without<SelectedFamily>)>,
+) {
+    let mut owned = families
+        .iter()
+        .filter(|(.., owner)| owner.is_local(client))
+        .peekable();
+    if owned.peek().is_none() {
+        return;
+    }
+
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(0.0),
+                left: Val::Px(0.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: theme.gap.normal,
+                padding: theme.padding.normal,
+                ..Default::default()
+            },
+            background_color: theme.panel_color.into(),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            for (family_entity, name, _) in owned {
+                parent.spawn((
+                    SwitchFamily(family_entity),
+                    TextButtonBundle::normal(theme, name.as_str()),
+                ));
+            }
+        });
+}
+
+#[derive(Component)]
+struct SwitchFamily(Entity);
@@ -2,13 +2,20 @@ use bevy::prelude::*;
 
 use project_harmonia_base::game_world::{
     actor::{
-        task::{TaskCancel, TaskState},
+        task::{TaskCancel, TaskMove, TaskProgress, TaskState},
         SelectedActor,
     },
     family::FamilyMode,
     WorldState,
 };
-use project_harmonia_widgets::{button::ImageButtonBundle, click::Click, theme::Theme};
+use project_harmonia_widgets::{
+    button::ImageButtonBundle,
+    click::Click,
+    drag_drop::{DragDrop, Draggable, DropTarget},
+    label::LabelBundle,
+    progress_bar::{ProgressBar, ProgressBarBundle},
+    theme::Theme,
+};
 
 pub(super) struct TasksNodePlugin;
 
@@ -18,7 +25,8 @@ impl Plugin for TasksNodePlugin {
             Update,
             (
                 Self::update_nodes,
-                Self::cancel.run_if(in_state(FamilyMode::Life)),
+                Self::update_progress.after(Self::update_nodes),
+                (Self::cancel, Self::reorder).run_if(in_state(FamilyMode::Life)),
             )
                 .run_if(in_state(WorldState::Family)),
         );
@@ -34,6 +42,7 @@ impl TasksNodePlugin {
         queued_task_nodes: Query<Entity, With<QueuedTasksNode>>,
         active_task_nodes: Query<Entity, With<ActiveTasksNode>>,
         buttons: Query<(Entity, &ButtonTask)>,
+        paused_labels: Query<(Entity, &Parent), With<PausedLabel>>,
     ) {
         let (children, selected_actor) = actors.single();
 
@@ -58,6 +67,8 @@ impl TasksNodePlugin {
                         .with_children(|parent| {
                             parent.spawn((
                                 ButtonTask(task_entity),
+                                Draggable,
+                                DropTarget,
                                 ImageButtonBundle::placeholder(&theme),
                             ));
                         });
@@ -68,8 +79,15 @@ impl TasksNodePlugin {
                         .find(|(_, button_task)| button_task.0 == task_entity)
                     {
                         debug!("turning queued button for `{task_entity}` into active");
+                        if let Some((label_entity, _)) = paused_labels
+                            .iter()
+                            .find(|(_, parent)| ***parent == button_entity)
+                        {
+                            commands.entity(label_entity).despawn_recursive();
+                        }
                         commands
                             .entity(button_entity)
+                            .remove::<(Draggable, DropTarget)>()
                             .set_parent(active_task_nodes.single());
                     } else {
                         debug!("creating active task button for `{task_entity}`");
@@ -83,6 +101,31 @@ impl TasksNodePlugin {
                             });
                     }
                 }
+                TaskState::Paused => {
+                    let button_entity = if let Some((button_entity, _)) = buttons
+                        .iter()
+                        .find(|(_, button_task)| button_task.0 == task_entity)
+                    {
+                        debug!("moving button for paused `{task_entity}` to queued");
+                        commands
+                            .entity(button_entity)
+                            .set_parent(queued_task_nodes.single());
+                        button_entity
+                    } else {
+                        debug!("creating paused task button for `{task_entity}`");
+                        commands
+                            .spawn((
+                                ButtonTask(task_entity),
+                                ImageButtonBundle::placeholder(&theme),
+                            ))
+                            .set_parent(queued_task_nodes.single())
+                            .id()
+                    };
+
+                    commands.entity(button_entity).with_children(|parent| {
+                        parent.spawn((PausedLabel, LabelBundle::symbol(&theme, "⏸")));
+                    });
+                }
                 TaskState::Cancelled => {
                     debug!("marking button for task `{task_entity}` as cancelled")
                 }
@@ -90,6 +133,66 @@ impl TasksNodePlugin {
         }
     }
 
+    fn update_progress(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        tasks: Query<(Entity, &TaskProgress), Changed<TaskProgress>>,
+        buttons: Query<(Entity, &ButtonTask)>,
+        mut bars: Query<(&mut ProgressBar, &BarTask)>,
+        mut labels: Query<(&mut Text, &EtaTask)>,
+    ) {
+        for (task_entity, &progress) in &tasks {
+            if let Some((mut progress_bar, _)) = bars
+                .iter_mut()
+                .find(|(_, bar_task)| bar_task.0 == task_entity)
+            {
+                trace!("updating progress for `{task_entity}`");
+                progress_bar.0 = progress.value() * 100.0;
+                if let Some((mut text, _)) = labels
+                    .iter_mut()
+                    .find(|(_, eta_task)| eta_task.0 == task_entity)
+                {
+                    text.sections[0].value = eta_text(progress);
+                }
+            } else if let Some((button_entity, _)) = buttons
+                .iter()
+                .find(|(_, button_task)| button_task.0 == task_entity)
+            {
+                debug!("creating progress bar for `{task_entity}`");
+                commands.entity(button_entity).with_children(|parent| {
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                bottom: Val::Px(0.0),
+                                width: Val::Percent(100.0),
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn((
+                                EtaTask(task_entity),
+                                LabelBundle::small(&theme, eta_text(progress)),
+                            ));
+                            parent
+                                .spawn((
+                                    BarTask(task_entity),
+                                    ProgressBarBundle::new(&theme, progress.value() * 100.0),
+                                ))
+                                .insert(Style {
+                                    width: Val::Percent(100.0),
+                                    height: Val::Px(4.0),
+                                    ..Default::default()
+                                });
+                        });
+                });
+            }
+        }
+    }
+
     fn cancel(
         mut cancel_events: EventWriter<TaskCancel>,
         mut click_events: EventReader<Click>,
@@ -100,6 +203,42 @@ impl TasksNodePlugin {
         }
     }
 
+    fn reorder(
+        mut commands: Commands,
+        mut move_events: EventWriter<TaskMove>,
+        mut drop_events: EventReader<DragDrop>,
+        buttons: Query<(&Parent, &ButtonTask)>,
+        queued_task_nodes: Query<&Children, With<QueuedTasksNode>>,
+    ) {
+        for event in drop_events.read() {
+            let Ok([(parent, button_task), (target_parent, target_task)]) =
+                buttons.get_many([event.source, event.target])
+            else {
+                continue;
+            };
+            let Ok(children) = queued_task_nodes.get(**parent) else {
+                continue;
+            };
+            if parent != target_parent {
+                continue;
+            }
+
+            let index = children
+                .iter()
+                .position(|&entity| entity == event.target)
+                .expect("target button should be a child of the queued tasks node");
+
+            // Reorder the buttons in the same way as the server reorders tasks.
+            commands
+                .entity(**parent)
+                .insert_children(index, &[event.source]);
+            move_events.send(TaskMove {
+                task_entity: button_task.0,
+                target_entity: target_task.0,
+            });
+        }
+    }
+
     fn cleanup(
         trigger: Trigger<OnRemove, TaskState>,
         mut commands: Commands,
@@ -115,6 +254,13 @@ impl TasksNodePlugin {
     }
 }
 
+fn eta_text(progress: TaskProgress) -> String {
+    progress
+        .eta()
+        .map(|secs| format!("{secs}s"))
+        .unwrap_or_default()
+}
+
 pub(super) fn setup(parent: &mut ChildBuilder, theme: &Theme) {
     parent
         .spawn(NodeBundle {
@@ -184,5 +330,17 @@ struct ActiveTasksNode;
 #[derive(Component)]
 struct QueuedTasksNode;
 
+/// Progress bar for a task button.
+#[derive(Component)]
+struct BarTask(Entity);
+
+/// Remaining time label for a task button.
+#[derive(Component)]
+struct EtaTask(Entity);
+
+/// Marks a paused task button.
+#[derive(Component)]
+struct PausedLabel;
+
 #[derive(Component, Debug)]
 struct ButtonTask(Entity);
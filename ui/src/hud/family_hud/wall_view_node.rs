@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use project_harmonia_base::{
+    game_world::WorldState,
+    settings::{Settings, SettingsApply, WallView},
+};
+use project_harmonia_widgets::{
+    button::{ExclusiveButton, TextButtonBundle, Toggled},
+    theme::Theme,
+};
+use strum::IntoEnumIterator;
+
+pub(super) struct WallViewNodePlugin;
+
+impl Plugin for WallViewNodePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, Self::select.run_if(in_state(WorldState::Family)));
+    }
+}
+
+impl WallViewNodePlugin {
+    fn select(
+        mut apply_events: EventWriter<SettingsApply>,
+        mut settings: ResMut<Settings>,
+        buttons: Query<(Ref<Toggled>, &WallView), Changed<Toggled>>,
+    ) {
+        for (toggled, &view) in &buttons {
+            if toggled.0 && !toggled.is_added() {
+                info!("changing wall view to `{view}`");
+                settings.gameplay.wall_view = view;
+                apply_events.send_default();
+            }
+        }
+    }
+}
+
+/// Creates buttons to switch between wall views.
+pub(super) fn setup(parent: &mut ChildBuilder, theme: &Theme, current_view: WallView) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                margin: UiRect::right(theme.gap.normal),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            for view in WallView::iter() {
+                parent.spawn((
+                    view,
+                    ExclusiveButton,
+                    Toggled(view == current_view),
+                    TextButtonBundle::symbol(theme, view.glyph()),
+                ));
+            }
+        });
+}
@@ -0,0 +1,174 @@
+use std::fmt::Write;
+
+use bevy::prelude::*;
+
+use project_harmonia_base::{
+    core::GameState,
+    game_world::{hover::Hovered, inspection::EntityInspection},
+    settings::Settings,
+};
+use project_harmonia_widgets::{
+    button::TextButtonBundle, click::Click, label::LabelBundle, theme::Theme,
+};
+
+/// Displays debug information about the last hovered entity.
+pub(super) struct InspectorPanelPlugin;
+
+impl Plugin for InspectorPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (Self::update, Self::copy).run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+impl InspectorPanelPlugin {
+    /// Keeps the last hovered entity to let the player interact with the panel.
+    fn update(
+        mut commands: Commands,
+        world: &World,
+        theme: Res<Theme>,
+        settings: Res<Settings>,
+        hovered: Query<Entity, With<Hovered>>,
+        panels: Query<(Entity, &InspectorPanel)>,
+        texts: Query<(Entity, &Text), With<InspectorText>>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        let panel = panels.get_single().ok();
+        let inspected_entity = hovered.get_single().ok().or_else(|| {
+            panel
+                .map(|(_, panel)| panel.entity)
+                .filter(|&entity| world.get_entity(entity).is_some())
+        });
+
+        let Some(inspected_entity) = inspected_entity.filter(|_| settings.developer.inspector)
+        else {
+            if let Some((panel_entity, _)) = panel {
+                debug!("hiding inspector panel");
+                commands.entity(panel_entity).despawn_recursive();
+            }
+            return;
+        };
+
+        let inspection = EntityInspection::new(world, inspected_entity);
+        let content = format_inspection(&inspection);
+        let inspector_panel = InspectorPanel {
+            entity: inspected_entity,
+            info_path: inspection.info_path,
+        };
+
+        if let Some((panel_entity, _)) = panel {
+            commands.entity(panel_entity).insert(inspector_panel);
+            let (text_entity, text) = texts.single();
+            if text.sections[0].value != content {
+                commands
+                    .entity(text_entity)
+                    .insert(Text::from_section(content, theme.label.small.clone()));
+            }
+            return;
+        }
+
+        debug!("showing inspector panel");
+        commands.entity(roots.single()).with_children(|parent| {
+            parent
+                .spawn((
+                    inspector_panel,
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            top: Val::Px(0.0),
+                            left: Val::Px(0.0),
+                            width: Val::Px(350.0),
+                            flex_direction: FlexDirection::Column,
+                            row_gap: theme.gap.normal,
+                            margin: theme.padding.global,
+                            padding: theme.padding.normal,
+                            ..Default::default()
+                        },
+                        background_color: theme.panel_color.into(),
+                        ..Default::default()
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn((InspectorText, LabelBundle::small(&theme, content)));
+                    for button in [CopyButton::Entity, CopyButton::Path] {
+                        parent.spawn((button, TextButtonBundle::normal(&theme, button.label())));
+                    }
+                });
+        });
+    }
+
+    fn copy(
+        mut click_events: EventReader<Click>,
+        buttons: Query<&CopyButton>,
+        panels: Query<&InspectorPanel>,
+    ) {
+        for &button in buttons.iter_many(click_events.read().map(|event| event.0)) {
+            let panel = panels.single();
+            let content = match button {
+                CopyButton::Entity => panel.entity.to_bits().to_string(),
+                CopyButton::Path => match &panel.info_path {
+                    Some(path) => path.clone(),
+                    None => {
+                        info!("`{}` has no metadata path to copy", panel.entity);
+                        continue;
+                    }
+                },
+            };
+
+            match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(&content)) {
+                Ok(()) => info!("copied '{content}' to clipboard"),
+                Err(error) => error!("unable to copy to clipboard: {error}"),
+            }
+        }
+    }
+}
+
+fn format_inspection(inspection: &EntityInspection) -> String {
+    let mut content = format!(
+        "Entity: {} ({})",
+        inspection.entity,
+        inspection.entity.to_bits()
+    );
+    if let Some(info_path) = &inspection.info_path {
+        write!(content, "\nMetadata: {info_path}").unwrap();
+    }
+    write!(content, "\nReplicated: {}", inspection.replicated).unwrap();
+    if let Some(collider) = &inspection.collider {
+        write!(content, "\nCollider: {collider}").unwrap();
+    }
+    if let Some(task) = &inspection.task {
+        write!(content, "\nTask: {task}").unwrap();
+    }
+    write!(content, "\nComponents:").unwrap();
+    for component in &inspection.components {
+        write!(content, "\n  {component}").unwrap();
+    }
+
+    content
+}
+
+#[derive(Component)]
+struct InspectorPanel {
+    entity: Entity,
+    info_path: Option<String>,
+}
+
+#[derive(Component)]
+struct InspectorText;
+
+#[derive(Clone, Component, Copy)]
+enum CopyButton {
+    Entity,
+    Path,
+}
+
+impl CopyButton {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Entity => "Copy entity ID",
+            Self::Path => "Copy metadata path",
+        }
+    }
+}
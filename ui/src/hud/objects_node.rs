@@ -2,9 +2,10 @@ use bevy::prelude::*;
 
 use crate::preview::Preview;
 use project_harmonia_base::{
-    asset::info::object_info::{ObjectCategory, ObjectInfo},
+    asset::info::object_info::{ObjectCategory, ObjectInfo, ObjectTag},
     game_world::{
         city::{ActiveCity, CityMode},
+        clock::{GameClock, Season},
         family::FamilyMode,
         object::placing_object::PlacingObject,
     },
@@ -21,7 +22,12 @@ impl Plugin for ObjectsNodePlugin {
     fn build(&self, app: &mut App) {
         app.observe(Self::untoggle).add_systems(
             Update,
-            (Self::start_placing, Self::show_popup, Self::reload_buttons)
+            (
+                Self::start_placing,
+                Self::show_popup,
+                Self::reload_buttons,
+                Self::filter_tags,
+            )
                 .run_if(in_state(CityMode::Objects).or_else(in_state(FamilyMode::Building))),
         );
     }
@@ -139,6 +145,52 @@ impl ObjectsNodePlugin {
         }
     }
 
+    /// Shows only objects with all selected capabilities and from the current season.
+    fn filter_tags(
+        mut current_season: Local<Option<Season>>,
+        objects_info: Res<Assets<ObjectInfo>>,
+        clocks: Query<&GameClock>,
+        filters: Query<(Ref<Toggled>, &TagFilter)>,
+        season_filters: Query<Ref<Toggled>, With<SeasonFilter>>,
+        mut buttons: Query<(&mut Style, &Preview), With<ObjectButton>>,
+    ) {
+        let season = clocks.get_single().ok().map(GameClock::season);
+        let season_changed = *current_season != season;
+        *current_season = season;
+        if !season_changed
+            && !filters.iter().any(|(toggled, _)| toggled.is_changed())
+            && !season_filters.iter().any(|toggled| toggled.is_changed())
+        {
+            return;
+        }
+
+        let all_seasons = season_filters.iter().any(|toggled| toggled.0);
+        let selected: Vec<_> = filters
+            .iter()
+            .filter(|(toggled, _)| toggled.0)
+            .map(|(_, filter)| &filter.0)
+            .collect();
+        debug!("filtering objects by {selected:?} for season `{season:?}`");
+        for (mut style, &preview) in &mut buttons {
+            let Preview::Object(id) = preview else {
+                continue;
+            };
+            let Some(info) = objects_info.get(id) else {
+                continue;
+            };
+            let in_season = all_seasons
+                || info
+                    .season()
+                    .zip(season)
+                    .map_or(true, |(object_season, season)| object_season == season);
+            style.display = if in_season && selected.iter().all(|tag| info.tags.contains(tag)) {
+                Display::Flex
+            } else {
+                Display::None
+            };
+        }
+    }
+
     fn untoggle(
         trigger: Trigger<OnRemove, PlacingObject>,
         mut commands: Commands,
@@ -211,6 +263,30 @@ pub(super) fn setup(
             ))
             .set_parent(tabs_entity);
     }
+
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                row_gap: theme.gap.normal,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            for tag in ObjectTag::CAPABILITIES {
+                parent.spawn((
+                    TagFilter(tag.clone()),
+                    Toggled(false),
+                    TextButtonBundle::normal(theme, tag.to_string()),
+                ));
+            }
+            parent.spawn((
+                SeasonFilter,
+                Toggled(false),
+                TextButtonBundle::normal(theme, "All seasons"),
+            ));
+        });
 }
 
 #[derive(Component)]
@@ -237,3 +313,11 @@ impl ObjectButtonBundle {
 
 #[derive(Component)]
 struct ButtonPlacingObject(Entity);
+
+/// Hides objects without the capability when toggled.
+#[derive(Component)]
+struct TagFilter(ObjectTag);
+
+/// Shows seasonal objects outside of their season when toggled.
+#[derive(Component)]
+struct SeasonFilter;
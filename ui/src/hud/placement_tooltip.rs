@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+
+use project_harmonia_base::game_world::object::placing_object::{
+    placement_rules::PlacementErrors, PlacingObject,
+};
+use project_harmonia_widgets::{label::LabelBundle, theme::Theme};
+
+pub(super) struct PlacementTooltipPlugin;
+
+impl Plugin for PlacementTooltipPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            (
+                Self::show,
+                Self::hide.run_if(not(any_with_component::<PlacingObject>)),
+                Self::follow_cursor,
+            )
+                .run_if(
+                    any_with_component::<PlacementTooltip>
+                        .or_else(any_with_component::<PlacingObject>),
+                ),
+        );
+    }
+}
+
+/// Offset from the cursor to avoid covering the placing object.
+const CURSOR_OFFSET: Vec2 = Vec2::splat(16.0);
+
+impl PlacementTooltipPlugin {
+    fn show(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        placing_objects: Query<Ref<PlacementErrors>, With<PlacingObject>>,
+        tooltips: Query<Entity, With<PlacementTooltip>>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        let Ok(errors) = placing_objects.get_single() else {
+            return;
+        };
+        if !errors.is_changed() {
+            return;
+        }
+
+        if let Ok(entity) = tooltips.get_single() {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        if errors.is_empty() {
+            return;
+        }
+
+        debug!("showing placement tooltip");
+        commands.entity(roots.single()).with_children(|parent| {
+            parent
+                .spawn((
+                    PlacementTooltip,
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            flex_direction: FlexDirection::Column,
+                            padding: theme.padding.normal,
+                            ..Default::default()
+                        },
+                        background_color: theme.popup_color.into(),
+                        z_index: ZIndex::Global(1),
+                        ..Default::default()
+                    },
+                ))
+                .with_children(|parent| {
+                    for error in errors.iter() {
+                        parent.spawn(LabelBundle::normal(&theme, error.to_string()));
+                    }
+                });
+        });
+    }
+
+    fn hide(mut commands: Commands, tooltips: Query<Entity, With<PlacementTooltip>>) {
+        if let Ok(entity) = tooltips.get_single() {
+            debug!("hiding placement tooltip");
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    fn follow_cursor(
+        windows: Query<&Window>,
+        mut tooltips: Query<&mut Style, With<PlacementTooltip>>,
+    ) {
+        let Ok(mut style) = tooltips.get_single_mut() else {
+            return;
+        };
+        let Some(cursor_pos) = windows.single().cursor_position() else {
+            return;
+        };
+
+        let pos = cursor_pos + CURSOR_OFFSET;
+        style.left = Val::Px(pos.x);
+        style.top = Val::Px(pos.y);
+    }
+}
+
+/// Lists reasons why the placing object can't be placed.
+#[derive(Component)]
+struct PlacementTooltip;
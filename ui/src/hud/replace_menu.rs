@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+use leafwing_input_manager::common_conditions::action_just_pressed;
+
+use project_harmonia_base::{
+    asset::info::object_info::ObjectInfo,
+    game_world::{
+        city::CityMode,
+        family::building::BuildingMode,
+        hover::Hovered,
+        object::{placing_object::PlacingObject, ObjectReplace, Placeholder},
+    },
+    settings::Action,
+};
+use project_harmonia_widgets::{
+    button::TextButtonBundle, click::Click, label::LabelBundle, theme::Theme,
+};
+
+/// Offers objects to replace a clicked [`Placeholder`] with.
+pub(super) struct ReplaceMenuPlugin;
+
+impl Plugin for ReplaceMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                Self::open
+                    .run_if(action_just_pressed(Action::Confirm))
+                    .run_if(not(any_with_component::<PlacingObject>)),
+                Self::replace,
+                Self::close.run_if(action_just_pressed(Action::Cancel)),
+            )
+                .run_if(in_state(CityMode::Objects).or_else(in_state(BuildingMode::Objects))),
+        );
+    }
+}
+
+impl ReplaceMenuPlugin {
+    fn open(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        objects_info: Res<Assets<ObjectInfo>>,
+        placeholders: Query<(Entity, &Placeholder), With<Hovered>>,
+        menus: Query<Entity, With<ReplaceMenu>>,
+        windows: Query<&Window>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        let Ok((placeholder_entity, placeholder)) = placeholders.get_single() else {
+            return;
+        };
+
+        if let Ok(entity) = menus.get_single() {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        info!("showing replacements for `{placeholder_entity}`");
+        let mut objects: Vec<_> = objects_info
+            .iter()
+            .map(|(id, info)| (id, info.general.name.clone()))
+            .collect();
+        objects.sort_unstable_by(|(_, a), (_, b)| a.cmp(b));
+
+        let cursor_pos = windows.single().cursor_position().unwrap_or_default();
+        commands.entity(roots.single()).with_children(|parent| {
+            parent
+                .spawn((
+                    ReplaceMenu(placeholder_entity),
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            left: Val::Px(cursor_pos.x),
+                            top: Val::Px(cursor_pos.y),
+                            max_height: Val::Percent(60.0),
+                            flex_direction: FlexDirection::Column,
+                            flex_wrap: FlexWrap::Wrap,
+                            align_items: AlignItems::Center,
+                            padding: theme.padding.normal,
+                            row_gap: theme.gap.normal,
+                            column_gap: theme.gap.normal,
+                            ..Default::default()
+                        },
+                        background_color: theme.panel_color.into(),
+                        ..Default::default()
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn(LabelBundle::normal(
+                        &theme,
+                        format!("Replace missing '{}' with", **placeholder),
+                    ));
+                    for (id, name) in objects {
+                        parent.spawn((
+                            ReplacementButton(id),
+                            TextButtonBundle::normal(&theme, name),
+                        ));
+                    }
+                });
+        });
+    }
+
+    fn replace(
+        mut commands: Commands,
+        mut replace_events: EventWriter<ObjectReplace>,
+        mut click_events: EventReader<Click>,
+        buttons: Query<&ReplacementButton>,
+        menus: Query<(Entity, &ReplaceMenu)>,
+    ) {
+        for &ReplacementButton(info_id) in
+            buttons.iter_many(click_events.read().map(|event| event.0))
+        {
+            let (menu_entity, menu) = menus.single();
+            replace_events.send(ObjectReplace {
+                entity: menu.0,
+                info_id,
+            });
+
+            commands.entity(menu_entity).despawn_recursive();
+        }
+    }
+
+    fn close(mut commands: Commands, menus: Query<Entity, With<ReplaceMenu>>) {
+        if let Ok(entity) = menus.get_single() {
+            info!("closing replace menu");
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// List of objects to replace the stored placeholder entity.
+#[derive(Component)]
+struct ReplaceMenu(Entity);
+
+#[derive(Component)]
+struct ReplacementButton(AssetId<ObjectInfo>);
@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+
+use project_harmonia_base::game_world::family::building::wall::placing_room::PlacingRoom;
+use project_harmonia_widgets::{label::LabelBundle, theme::Theme};
+
+/// Displays size of the room that is currently being drawn near the cursor.
+pub(super) struct RoomDimensionsPlugin;
+
+impl Plugin for RoomDimensionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            (
+                Self::update,
+                Self::hide.run_if(not(any_with_component::<PlacingRoom>)),
+                Self::follow_cursor,
+            )
+                .run_if(
+                    any_with_component::<DimensionsLabel>
+                        .or_else(any_with_component::<PlacingRoom>),
+                ),
+        );
+    }
+}
+
+/// Offset from the cursor to avoid covering the room corner.
+const CURSOR_OFFSET: Vec2 = Vec2::splat(16.0);
+
+impl RoomDimensionsPlugin {
+    fn update(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        placing_rooms: Query<&PlacingRoom, Changed<PlacingRoom>>,
+        mut labels: Query<&mut Text, With<DimensionsLabel>>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        let Ok(room) = placing_rooms.get_single() else {
+            return;
+        };
+
+        let size = room.size();
+        let text = format!("{:.1} × {:.1} m", size.x, size.y);
+        if let Ok(mut label_text) = labels.get_single_mut() {
+            label_text.sections[0].value = text;
+        } else {
+            debug!("showing room dimensions");
+            commands.entity(roots.single()).with_children(|parent| {
+                parent
+                    .spawn((DimensionsLabel, LabelBundle::normal(&theme, text)))
+                    .insert((
+                        Style {
+                            position_type: PositionType::Absolute,
+                            padding: theme.padding.normal,
+                            ..Default::default()
+                        },
+                        BackgroundColor(theme.popup_color),
+                        ZIndex::Global(1),
+                    ));
+            });
+        }
+    }
+
+    fn hide(mut commands: Commands, labels: Query<Entity, With<DimensionsLabel>>) {
+        if let Ok(entity) = labels.get_single() {
+            debug!("hiding room dimensions");
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    fn follow_cursor(
+        windows: Query<&Window>,
+        mut labels: Query<&mut Style, With<DimensionsLabel>>,
+    ) {
+        let Ok(mut style) = labels.get_single_mut() else {
+            return;
+        };
+        let Some(cursor_pos) = windows.single().cursor_position() else {
+            return;
+        };
+
+        let pos = cursor_pos + CURSOR_OFFSET;
+        style.left = Val::Px(pos.x);
+        style.top = Val::Px(pos.y);
+    }
+}
+
+#[derive(Component)]
+struct DimensionsLabel;
@@ -0,0 +1,379 @@
+use std::mem;
+
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use bevy_simple_text_input::{TextInputSubmitEvent, TextInputValue};
+
+use project_harmonia_base::{
+    core::transition::{Transition, TransitionRequest},
+    game_world::{
+        actor::SelectedActor,
+        family::{Family, FamilyClaim, FamilyMembers, FamilyOwner},
+        player_camera::watching::{SharedSelection, WatchedActor},
+        WorldState,
+    },
+    message::Message,
+    network::chat::{ChatHistory, ChatSend, MAX_MESSAGE_LEN},
+};
+use project_harmonia_widgets::{
+    button::TextButtonBundle, click::Click, label::LabelBundle, text_edit::TextEditBundle,
+    theme::Theme,
+};
+
+/// Chat and family selection while observing a city.
+pub(super) struct SpectatorHudPlugin;
+
+impl Plugin for SpectatorHudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(WorldState::Spectator), Self::setup)
+            .add_systems(
+                Update,
+                (
+                    Self::update_chat_log,
+                    Self::send_message,
+                    Self::toggle_families,
+                    Self::handle_family_clicks,
+                    Self::toggle_players,
+                    Self::handle_watch_clicks,
+                )
+                    .run_if(in_state(WorldState::Spectator)),
+            );
+    }
+}
+
+impl SpectatorHudPlugin {
+    fn setup(mut commands: Commands, theme: Res<Theme>) {
+        debug!("showing spectator HUD");
+        commands
+            .spawn((
+                StateScoped(WorldState::Spectator),
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        justify_content: JustifyContent::SpaceBetween,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            ))
+            .with_children(|parent| {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_self: AlignSelf::FlexEnd,
+                            padding: theme.padding.normal,
+                            row_gap: theme.gap.normal,
+                            ..Default::default()
+                        },
+                        background_color: theme.panel_color.into(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            ChatLog,
+                            NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Column,
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            },
+                        ));
+                        parent
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    column_gap: theme.gap.normal,
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            })
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    ChatEdit,
+                                    TextEditBundle::empty(&theme).inactive(&theme),
+                                ));
+                                parent
+                                    .spawn((SendButton, TextButtonBundle::normal(&theme, "Send")));
+                            });
+                    });
+
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::FlexEnd,
+                            padding: theme.padding.normal,
+                            row_gap: theme.gap.normal,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent
+                            .spawn((FamiliesButton, TextButtonBundle::normal(&theme, "Families")));
+                        parent.spawn((PlayersButton, TextButtonBundle::normal(&theme, "Players")));
+                    });
+            });
+    }
+
+    fn update_chat_log(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        history: Res<ChatHistory>,
+        logs: Query<(Entity, Ref<ChatLog>)>,
+    ) {
+        let Ok((log_entity, log)) = logs.get_single() else {
+            return;
+        };
+        if !history.is_changed() && !log.is_added() {
+            return;
+        }
+
+        commands
+            .entity(log_entity)
+            .despawn_descendants()
+            .with_children(|parent| {
+                for message in history.iter() {
+                    let sender = if message.sender == ClientId::SERVER {
+                        "Host".to_string()
+                    } else {
+                        format!("Player {}", message.sender.get())
+                    };
+                    parent.spawn(LabelBundle::normal(
+                        &theme,
+                        format!("{sender}: {}", message.text),
+                    ));
+                }
+            });
+    }
+
+    fn send_message(
+        mut send_events: EventWriter<ChatSend>,
+        mut submit_events: EventReader<TextInputSubmitEvent>,
+        mut click_events: EventReader<Click>,
+        buttons: Query<(), With<SendButton>>,
+        mut chat_edits: Query<(Entity, &mut TextInputValue), With<ChatEdit>>,
+    ) {
+        let Ok((edit_entity, mut value)) = chat_edits.get_single_mut() else {
+            return;
+        };
+
+        let mut texts: Vec<_> = submit_events
+            .read()
+            .filter(|event| event.entity == edit_entity)
+            .map(|event| event.value.clone())
+            .collect();
+        if buttons
+            .iter_many(click_events.read().map(|event| event.0))
+            .next()
+            .is_some()
+        {
+            texts.push(mem::take(&mut value.0));
+        }
+
+        for text in texts {
+            if !text.trim().is_empty() {
+                send_events.send(ChatSend(text.chars().take(MAX_MESSAGE_LEN).collect()));
+            }
+        }
+    }
+
+    fn toggle_families(
+        mut commands: Commands,
+        mut click_events: EventReader<Click>,
+        theme: Res<Theme>,
+        buttons: Query<&Parent, With<FamiliesButton>>,
+        panels: Query<Entity, With<FamiliesPanel>>,
+        families: Query<(Entity, &Name), With<Family>>,
+    ) {
+        for parent in buttons.iter_many(click_events.read().map(|event| event.0)) {
+            if let Ok(panel_entity) = panels.get_single() {
+                debug!("closing families panel");
+                commands.entity(panel_entity).despawn_recursive();
+                continue;
+            }
+
+            debug!("opening families panel");
+            commands.entity(**parent).with_children(|parent| {
+                parent
+                    .spawn((
+                        FamiliesPanel,
+                        NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                padding: theme.padding.normal,
+                                row_gap: theme.gap.normal,
+                                ..Default::default()
+                            },
+                            background_color: theme.panel_color.into(),
+                            ..Default::default()
+                        },
+                    ))
+                    .with_children(|parent| {
+                        if families.is_empty() {
+                            parent.spawn(LabelBundle::normal(&theme, "No families"));
+                        }
+                        for (family_entity, name) in &families {
+                            parent
+                                .spawn(NodeBundle {
+                                    style: Style {
+                                        align_items: AlignItems::Center,
+                                        column_gap: theme.gap.normal,
+                                        ..Default::default()
+                                    },
+                                    ..Default::default()
+                                })
+                                .with_children(|parent| {
+                                    parent.spawn(LabelBundle::normal(&theme, name.as_str()));
+                                    parent.spawn((
+                                        PlayFamilyButton(family_entity),
+                                        TextButtonBundle::normal(&theme, "Play"),
+                                    ));
+                                });
+                        }
+                    });
+            });
+        }
+    }
+
+    fn toggle_players(
+        mut commands: Commands,
+        mut click_events: EventReader<Click>,
+        theme: Res<Theme>,
+        buttons: Query<&Parent, With<PlayersButton>>,
+        panels: Query<Entity, With<PlayersPanel>>,
+        actors: Query<(Entity, &Name, &SharedSelection)>,
+    ) {
+        for parent in buttons.iter_many(click_events.read().map(|event| event.0)) {
+            if let Ok(panel_entity) = panels.get_single() {
+                debug!("closing players panel");
+                commands.entity(panel_entity).despawn_recursive();
+                continue;
+            }
+
+            debug!("opening players panel");
+            commands.entity(**parent).with_children(|parent| {
+                parent
+                    .spawn((
+                        PlayersPanel,
+                        NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                padding: theme.padding.normal,
+                                row_gap: theme.gap.normal,
+                                ..Default::default()
+                            },
+                            background_color: theme.panel_color.into(),
+                            ..Default::default()
+                        },
+                    ))
+                    .with_children(|parent| {
+                        if actors.is_empty() {
+                            parent.spawn(LabelBundle::normal(&theme, "No players to watch"));
+                        }
+                        for (actor_entity, name, shared) in &actors {
+                            parent
+                                .spawn(NodeBundle {
+                                    style: Style {
+                                        align_items: AlignItems::Center,
+                                        column_gap: theme.gap.normal,
+                                        ..Default::default()
+                                    },
+                                    ..Default::default()
+                                })
+                                .with_children(|parent| {
+                                    parent.spawn(LabelBundle::normal(
+                                        &theme,
+                                        format!("Player {}: {name}", shared.get()),
+                                    ));
+                                    parent.spawn((
+                                        WatchButton(actor_entity),
+                                        TextButtonBundle::normal(&theme, "Watch"),
+                                    ));
+                                });
+                        }
+                    });
+            });
+        }
+    }
+
+    fn handle_watch_clicks(
+        mut click_events: EventReader<Click>,
+        mut watched_actor: ResMut<WatchedActor>,
+        buttons: Query<&WatchButton>,
+    ) {
+        if let Some(button) = buttons
+            .iter_many(click_events.read().map(|event| event.0))
+            .last()
+        {
+            info!("watching actor `{}`", button.0);
+            watched_actor.0 = Some(button.0);
+        }
+    }
+
+    fn handle_family_clicks(
+        mut commands: Commands,
+        mut claim_events: EventWriter<FamilyClaim>,
+        mut message_events: EventWriter<Message>,
+        mut click_events: EventReader<Click>,
+        mut transition_events: EventWriter<TransitionRequest>,
+        client: Res<RepliconClient>,
+        buttons: Query<&PlayFamilyButton>,
+        families: Query<(&FamilyMembers, Option<&FamilyOwner>)>,
+    ) {
+        for button in buttons.iter_many(click_events.read().map(|event| event.0)) {
+            let Ok((members, owner)) = families.get(button.0) else {
+                // Family could be removed while the panel is open.
+                continue;
+            };
+            if owner.is_some_and(|owner| !owner.is_local(&client)) {
+                message_events.send(Message(
+                    "This family is controlled by another player".to_string(),
+                ));
+                continue;
+            }
+            let Some(&actor_entity) = members.first() else {
+                // Members are updated after replication, the family could be empty for a moment.
+                message_events.send(Message("This family has no members".to_string()));
+                continue;
+            };
+
+            info!("starting playing for family `{}`", button.0);
+            claim_events.send(FamilyClaim(button.0));
+            commands.entity(actor_entity).insert(SelectedActor);
+            transition_events.send(TransitionRequest::new(Transition::World(
+                WorldState::Family,
+            )));
+        }
+    }
+}
+
+/// Node with received chat messages.
+#[derive(Component)]
+struct ChatLog;
+
+#[derive(Component)]
+struct ChatEdit;
+
+#[derive(Component)]
+struct SendButton;
+
+#[derive(Component)]
+struct FamiliesButton;
+
+#[derive(Component)]
+struct FamiliesPanel;
+
+#[derive(Component)]
+struct PlayFamilyButton(Entity);
+
+#[derive(Component)]
+struct PlayersButton;
+
+#[derive(Component)]
+struct PlayersPanel;
+
+#[derive(Component)]
+struct WatchButton(Entity);
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use project_harmonia_base::game_world::family::statistics::Samples;
+use project_harmonia_widgets::theme::Theme;
+
+/// Thickness of lines on statistics graphs.
+const GRAPH_LINE_WIDTH: f32 = 2.0;
+
+/// Replaces children of the graph node with line segments between samples.
+///
+/// Segments are positioned in pixels, so the graph should be updated when its node size changes.
+pub(super) fn update(
+    commands: &mut Commands,
+    graph_entity: Entity,
+    graph_size: Vec2,
+    theme: &Theme,
+    samples: &Samples,
+) {
+    // Normalize to the graph height, but keep flat lines at the bottom.
+    let max = samples.iter().copied().fold(0.0, f32::max);
+    let scale = if max > 0.0 { 1.0 / max } else { 0.0 };
+
+    let points: Vec<_> = samples
+        .iter()
+        .enumerate()
+        .map(|(index, &value)| {
+            let x = index as f32 / (Samples::CAPACITY - 1) as f32;
+            Vec2::new(x, value * scale) * graph_size
+        })
+        .collect();
+
+    commands
+        .entity(graph_entity)
+        .despawn_descendants()
+        .with_children(|parent| {
+            for (&start, &end) in points.iter().zip(points.iter().skip(1)) {
+                let delta = end - start;
+                let center = (start + end) / 2.0;
+                let length = delta.length();
+                parent.spawn(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(center.x - length / 2.0),
+                        bottom: Val::Px(center.y - GRAPH_LINE_WIDTH / 2.0),
+                        width: Val::Px(length),
+                        height: Val::Px(GRAPH_LINE_WIDTH),
+                        ..Default::default()
+                    },
+                    // UI space points down, so the rotation is inverted.
+                    transform: Transform::from_rotation(Quat::from_rotation_z(
+                        -delta.y.atan2(delta.x),
+                    )),
+                    background_color: theme.progress_bar.fill_color.into(),
+                    ..Default::default()
+                });
+            }
+        });
+}
@@ -1,26 +1,53 @@
+mod achievement_toast;
 mod camera_2d;
+mod console_node;
 mod error_dialog;
 mod hud;
 mod menu;
+mod preload_splash;
 mod preview;
+mod preview_export;
+mod profiling_overlay;
+mod recovery_dialog;
+mod scenario_results;
+mod season_cleanup;
+mod transition_spinner;
 
 use bevy::{app::PluginGroupBuilder, prelude::*};
 
+use achievement_toast::AchievementToastPlugin;
 use camera_2d::Camera2dPlugin;
+use console_node::ConsoleNodePlugin;
 use error_dialog::MessageBoxPlugin;
 use hud::HudPlugin;
 use menu::MenuPlugin;
+use preload_splash::PreloadSplashPlugin;
 use preview::PreviewPlugin;
+use profiling_overlay::ProfilingOverlayPlugin;
+use recovery_dialog::RecoveryDialogPlugin;
+use scenario_results::ScenarioResultsPlugin;
+use season_cleanup::SeasonCleanupPlugin;
+use transition_spinner::TransitionSpinnerPlugin;
+
+pub use preview_export::{PreviewExportPlugin, EXPORT_SIZE};
 
 pub struct UiPlugins;
 
 impl PluginGroup for UiPlugins {
     fn build(self) -> PluginGroupBuilder {
         PluginGroupBuilder::start::<Self>()
+            .add(AchievementToastPlugin)
             .add(Camera2dPlugin)
+            .add(ConsoleNodePlugin)
             .add(MenuPlugin)
             .add(MessageBoxPlugin)
+            .add(PreloadSplashPlugin)
             .add(HudPlugin)
             .add(PreviewPlugin)
+            .add(ProfilingOverlayPlugin)
+            .add(RecoveryDialogPlugin)
+            .add(ScenarioResultsPlugin)
+            .add(SeasonCleanupPlugin)
+            .add(TransitionSpinnerPlugin)
     }
 }
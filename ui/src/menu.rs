@@ -1,18 +1,24 @@
+mod achievements_menu;
 mod connection_dialog;
 mod editor_menu;
 mod ingame_menu;
 mod main_menu;
+mod players_menu;
+mod scenarios_menu;
 mod settings_menu;
 mod world_browser;
 mod world_menu;
 
 use bevy::prelude::*;
 
+use achievements_menu::AchievementsMenuPlugin;
 use connection_dialog::ConnectionDialogPlugin;
 use editor_menu::EditorMenuPlugin;
 use ingame_menu::InGameMenuPlugin;
 use main_menu::MainMenuPlugin;
+use players_menu::PlayersMenuPlugin;
 use project_harmonia_base::core::GameState;
+use scenarios_menu::ScenariosMenuPlugin;
 use settings_menu::SettingsMenuPlugin;
 use world_browser::WorldBrowserPlugin;
 use world_menu::WorldMenuPlugin;
@@ -24,10 +30,13 @@ impl Plugin for MenuPlugin {
         app.add_sub_state::<MenuState>()
             .enable_state_scoped_entities::<MenuState>()
             .add_plugins((
+                AchievementsMenuPlugin,
                 ConnectionDialogPlugin,
                 EditorMenuPlugin,
                 InGameMenuPlugin,
                 MainMenuPlugin,
+                PlayersMenuPlugin,
+                ScenariosMenuPlugin,
                 SettingsMenuPlugin,
                 WorldBrowserPlugin,
                 WorldMenuPlugin,
@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+use strum::IntoEnumIterator;
+
+use project_harmonia_base::achievements::{Achievement, Achievements};
+use project_harmonia_widgets::{
+    button::TextButtonBundle, click::Click, dialog::DialogBundle, label::LabelBundle, theme::Theme,
+};
+
+pub(super) struct AchievementsMenuPlugin;
+
+impl Plugin for AchievementsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AchievementsMenuOpen>()
+            .add_systems(
+                Update,
+                Self::close.run_if(any_with_component::<AchievementsMenu>),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::setup.run_if(on_event::<AchievementsMenuOpen>()),
+            );
+    }
+}
+
+impl AchievementsMenuPlugin {
+    fn setup(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        achievements: Res<Achievements>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        info!("opening achievements menu");
+        commands.entity(roots.single()).with_children(|parent| {
+            parent
+                .spawn((AchievementsMenu, DialogBundle::new(&theme)))
+                .with_children(|parent| {
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                padding: theme.padding.normal,
+                                row_gap: theme.gap.normal,
+                                ..Default::default()
+                            },
+                            background_color: theme.panel_color.into(),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent
+                                .spawn(NodeBundle {
+                                    style: Style {
+                                        display: Display::Grid,
+                                        column_gap: theme.gap.normal,
+                                        row_gap: theme.gap.normal,
+                                        grid_template_columns: vec![GridTrack::auto(); 3],
+                                        ..Default::default()
+                                    },
+                                    ..Default::default()
+                                })
+                                .with_children(|parent| {
+                                    for achievement in Achievement::iter() {
+                                        let glyph = if achievements.is_unlocked(achievement) {
+                                            "🏆"
+                                        } else {
+                                            "🔒"
+                                        };
+                                        parent.spawn(LabelBundle::symbol(&theme, glyph));
+                                        parent
+                                            .spawn(LabelBundle::normal(&theme, achievement.name()));
+                                        parent.spawn(LabelBundle::normal(
+                                            &theme,
+                                            achievement.description(),
+                                        ));
+                                    }
+                                });
+
+                            parent.spawn((CloseButton, TextButtonBundle::normal(&theme, "Close")));
+                        });
+                });
+        });
+    }
+
+    fn close(
+        mut commands: Commands,
+        mut click_events: EventReader<Click>,
+        buttons: Query<(), With<CloseButton>>,
+        menus: Query<Entity, With<AchievementsMenu>>,
+    ) {
+        for _ in buttons.iter_many(click_events.read().map(|event| event.0)) {
+            info!("closing achievements menu");
+            commands.entity(menus.single()).despawn_recursive();
+        }
+    }
+}
+
+#[derive(Default, Event)]
+pub(super) struct AchievementsMenuOpen;
+
+#[derive(Component)]
+struct AchievementsMenu;
+
+#[derive(Component)]
+struct CloseButton;
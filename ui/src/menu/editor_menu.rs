@@ -3,12 +3,18 @@ use std::mem;
 use anyhow::Result;
 use bevy::prelude::*;
 use bevy_simple_text_input::TextInputValue;
+use rand::seq::IteratorRandom;
 use strum::{Display, EnumIter, IntoEnumIterator};
 
 use crate::preview::{Preview, PreviewProcessed};
 use project_harmonia_base::{
+    core::transition::{Transition, TransitionRequest},
     game_world::{
-        actor::{FirstName, LastName, Sex},
+        actor::{
+            name_generator::NameGenerator,
+            outfit::{CurrentOutfit, OutfitCategory, OutfitColor, Outfits},
+            FirstName, LastName, Sex,
+        },
         city::City,
         family::{
             editor::{EditableActor, EditableActorBundle, EditableFamily, FamilyReset},
@@ -41,8 +47,10 @@ impl Plugin for EditorMenuPlugin {
                     Self::update_actor_previews,
                     (
                         Self::switch_actor,
+                        Self::randomize,
                         (
                             Self::set_sex,
+                            Self::set_outfit,
                             Self::update_first_name,
                             Self::update_last_name,
                         ),
@@ -157,8 +165,15 @@ impl EditorMenuPlugin {
 
     fn switch_actor(
         actor_buttons: Query<(&Toggled, &EditActor), Changed<Toggled>>,
-        mut actors: Query<(&mut Visibility, &Sex, &FirstName, &LastName), With<EditableActor>>,
+        mut actors: Query<
+            (&mut Visibility, &Sex, &Outfits, &FirstName, &LastName),
+            With<EditableActor>,
+        >,
         mut sex_buttons: Query<(&mut Toggled, &Sex), Without<EditActor>>,
+        mut outfit_buttons: Query<
+            (&mut Toggled, &OutfitButton),
+            (Without<EditActor>, Without<Sex>),
+        >,
         mut first_name_edits: Query<&mut TextInputValue, With<FirstNameEdit>>,
         mut last_name_edits: Query<
             &mut TextInputValue,
@@ -177,7 +192,7 @@ impl EditorMenuPlugin {
                 info!("switching actor to `{edit_actor:?}`");
 
                 // Update UI with parameters of the current actor.
-                let (mut visibility, &actor_sex, first_name, last_name) = actors
+                let (mut visibility, &actor_sex, outfits, first_name, last_name) = actors
                     .get_mut(edit_actor.0)
                     .expect("actor button should point to a valid actor");
                 *visibility = Visibility::Visible;
@@ -189,10 +204,67 @@ impl EditorMenuPlugin {
                     .find(|(_, &sex)| sex == actor_sex)
                     .expect("sex buttons should be spawned for each variant");
                 sex_toggled.0 = true;
+
+                for (mut outfit_toggled, outfit_button) in &mut outfit_buttons {
+                    if outfits.get(outfit_button.category) == outfit_button.color {
+                        outfit_toggled.0 = true;
+                    }
+                }
             }
         }
     }
 
+    /// Randomizes UI controls, which will update the current actor.
+    fn randomize(
+        mut click_events: EventReader<Click>,
+        name_generator: NameGenerator,
+        buttons: Query<(), With<RandomizeButton>>,
+        actors: Query<(&LastName, &Visibility), With<EditableActor>>,
+        mut sex_buttons: Query<(&mut Toggled, &Sex)>,
+        mut outfit_buttons: Query<(&mut Toggled, &OutfitButton), Without<Sex>>,
+        mut first_name_edits: Query<&mut TextInputValue, With<FirstNameEdit>>,
+        mut last_name_edits: Query<
+            &mut TextInputValue,
+            (With<LastNameEdit>, Without<FirstNameEdit>),
+        >,
+    ) {
+        for _ in buttons.iter_many(click_events.read().map(|event| event.0)) {
+            info!("randomizing actor");
+            let mut rng = rand::thread_rng();
+
+            let (mut sex_toggled, &sex) = sex_buttons
+                .iter_mut()
+                .choose(&mut rng)
+                .expect("sex buttons should be spawned for each variant");
+            sex_toggled.0 = true;
+
+            for category in OutfitCategory::iter() {
+                if let Some((mut outfit_toggled, _)) = outfit_buttons
+                    .iter_mut()
+                    .filter(|(_, button)| button.category == category)
+                    .choose(&mut rng)
+                {
+                    outfit_toggled.0 = true;
+                }
+            }
+
+            // Keep the last name of other family members.
+            let family_name = actors
+                .iter()
+                .find(|(last_name, &visibility)| {
+                    visibility == Visibility::Hidden && !last_name.is_empty()
+                })
+                .map(|(last_name, _)| last_name.0.clone());
+
+            let Some((first_name, last_name)) = name_generator.generate(&mut rng, sex, None) else {
+                warn!("no name tables are loaded");
+                continue;
+            };
+            first_name_edits.single_mut().0 = first_name.0;
+            last_name_edits.single_mut().0 = family_name.unwrap_or(last_name.0);
+        }
+    }
+
     fn set_sex(
         buttons: Query<(&Toggled, &Sex), (Changed<Toggled>, Without<EditableActor>)>,
         mut actors: Query<(&mut Sex, &Visibility), With<EditableActor>>,
@@ -211,6 +283,26 @@ impl EditorMenuPlugin {
         }
     }
 
+    fn set_outfit(
+        buttons: Query<(&Toggled, &OutfitButton), Changed<Toggled>>,
+        mut actors: Query<(&mut Outfits, &mut CurrentOutfit, &Visibility), With<EditableActor>>,
+    ) {
+        for (toggled, button) in &buttons {
+            if toggled.0 {
+                if let Some((mut outfits, mut current_outfit, _)) = actors
+                    .iter_mut()
+                    .filter(|(.., visibility)| !visibility.is_changed()) // Avoid changes on actor switching.
+                    .find(|(.., &visibility)| visibility == Visibility::Visible)
+                {
+                    info!("changing {} outfit to '{}'", button.category, button.color);
+                    outfits.set(button.category, button.color);
+                    // Show the edited outfit.
+                    current_outfit.0 = button.category;
+                }
+            }
+        }
+    }
+
     fn update_first_name(
         text_edits: Query<&TextInputValue, (Changed<TextInputValue>, With<FirstNameEdit>)>,
         mut actors: Query<(&mut FirstName, &Visibility), With<EditableActor>>,
@@ -246,7 +338,7 @@ impl EditorMenuPlugin {
     fn handle_family_menu_clicks(
         mut commands: Commands,
         mut click_events: EventReader<Click>,
-        mut world_state: ResMut<NextState<WorldState>>,
+        mut transition_events: EventWriter<TransitionRequest>,
         theme: Res<Theme>,
         buttons: Query<&FamilyMenuButton>,
         roots: Query<Entity, (With<Node>, Without<Parent>)>,
@@ -256,7 +348,10 @@ impl EditorMenuPlugin {
                 FamilyMenuButton::Confirm => {
                     setup_save_family_dialog(&mut commands, roots.single(), &theme);
                 }
-                FamilyMenuButton::Cancel => world_state.set(WorldState::World),
+                FamilyMenuButton::Cancel => {
+                    transition_events
+                        .send(TransitionRequest::new(Transition::World(WorldState::World)));
+                }
             }
         }
     }
@@ -381,6 +476,39 @@ fn setup_personality_node(parent: &mut ChildBuilder, theme: &Theme) {
                     ));
                 }
             });
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        display: Display::Grid,
+                        column_gap: theme.gap.normal,
+                        row_gap: theme.gap.normal,
+                        grid_template_columns: vec![GridTrack::auto(); 2],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    let outfits = Outfits::default();
+                    for category in OutfitCategory::iter() {
+                        parent.spawn(LabelBundle::normal(theme, category.to_string()));
+                        parent.spawn(NodeBundle::default()).with_children(|parent| {
+                            for color in OutfitColor::iter() {
+                                parent.spawn((
+                                    OutfitButton { category, color },
+                                    ExclusiveButton,
+                                    Toggled(outfits.get(category) == color),
+                                    TextButtonBundle::normal(theme, color.to_string()),
+                                ));
+                            }
+                        });
+                    }
+                });
+
+            parent.spawn((
+                RandomizeButton,
+                TextButtonBundle::normal(theme, "Randomize"),
+            ));
         });
 }
 
@@ -565,6 +693,12 @@ struct FirstNameEdit;
 #[derive(Component)]
 struct LastNameEdit;
 
+#[derive(Component)]
+struct OutfitButton {
+    category: OutfitCategory,
+    color: OutfitColor,
+}
+
 #[derive(Component, EnumIter, Clone, Copy, Display)]
 enum FamilyMenuButton {
     Confirm,
@@ -580,6 +714,9 @@ enum SaveDialogButton {
 #[derive(Component)]
 struct PlusButton;
 
+#[derive(Component)]
+struct RandomizeButton;
+
 #[derive(Component)]
 struct ActorsNode;
 
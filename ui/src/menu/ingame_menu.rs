@@ -1,25 +1,41 @@
 use bevy::{app::AppExit, prelude::*};
+use bevy_replicon::prelude::*;
 use leafwing_input_manager::common_conditions::action_just_pressed;
 use project_harmonia_base::{
+    bug_report::DiagnosticsExport,
     common_conditions::in_any_state,
-    core::GameState,
+    core::{
+        transition::{BlockReason, Transition, TransitionBlocked, TransitionRequest},
+        GameState,
+    },
     game_world::{
         city::{
-            lot::{creating_lot::CreatingLot, moving_lot::MovingLot},
+            lot::{
+                bulldozing_lot::BulldozingLot, creating_lot::CreatingLot, editing_lot::EditingLot,
+                moving_lot::MovingLot,
+            },
             road::placing_road::PlacingRoad,
+            water::creating_water::CreatingWater,
         },
         family::building::wall::placing_wall::PlacingWall,
         object::placing_object::PlacingObject,
-        GameSave, WorldState,
+        ExitRequest, GameSave, UnsavedChanges, WorldState,
     },
+    network::shutdown::ServerShutdown,
     settings::Action,
 };
 use project_harmonia_widgets::{
-    button::TextButtonBundle, click::Click, dialog::DialogBundle, label::LabelBundle, theme::Theme,
+    button::TextButtonBundle,
+    click::Click,
+    dialog::{
+        ConfirmationDialog, ConfirmationDialogBundle, DialogBundle, DialogResponse, DialogResult,
+    },
+    label::LabelBundle,
+    theme::Theme,
 };
 use strum::{Display, EnumIter, IntoEnumIterator};
 
-use super::settings_menu::SettingsMenuOpen;
+use super::{players_menu::PlayersMenuOpen, settings_menu::SettingsMenuOpen};
 use crate::hud::task_menu::TaskMenu;
 
 pub(super) struct InGameMenuPlugin;
@@ -36,14 +52,30 @@ impl Plugin for InGameMenuPlugin {
                     .run_if(not(any_with_component::<PlacingObject>))
                     .run_if(not(any_with_component::<MovingLot>))
                     .run_if(not(any_with_component::<CreatingLot>))
+                    .run_if(not(any_with_component::<EditingLot>))
+                    .run_if(not(any_with_component::<BulldozingLot>))
                     .run_if(not(any_with_component::<PlacingWall>))
                     .run_if(not(any_with_component::<PlacingRoad>))
-                    .run_if(in_any_state([WorldState::Family, WorldState::City])),
+                    .run_if(not(any_with_component::<CreatingWater>))
+                    .run_if(in_any_state([
+                        WorldState::Family,
+                        WorldState::City,
+                        WorldState::Spectator,
+                    ])),
+                Self::show_exit_request
+                    .run_if(on_event::<ExitRequest>())
+                    .run_if(not(any_with_component::<ExitDialog>)),
+                Self::show_blocked_transition
+                    .run_if(on_event::<TransitionBlocked>())
+                    .run_if(not(any_with_component::<ExitDialog>))
+                    .run_if(not(any_with_component::<PendingCommandsDialog>)),
+                Self::handle_exit_dialog_results.run_if(any_with_component::<ExitDialog>),
+                Self::handle_pending_dialog_results
+                    .run_if(any_with_component::<PendingCommandsDialog>),
                 (
                     Self::handle_menu_clicks,
-                    Self::handle_exit_dialog_clicks,
                     Self::close
-                        .run_if(not(any_with_component::<ExitDialog>))
+                        .run_if(not(any_with_component::<ConfirmationDialog>))
                         .run_if(action_just_pressed(Action::Cancel)),
                 )
                     .run_if(any_with_component::<IngameMenu>),
@@ -56,6 +88,7 @@ impl InGameMenuPlugin {
     fn open(
         mut commands: Commands,
         theme: Res<Theme>,
+        server: Res<RepliconServer>,
         roots: Query<Entity, (With<Node>, Without<Parent>)>,
     ) {
         info!("showing in-game menu");
@@ -80,6 +113,13 @@ impl InGameMenuPlugin {
                             parent.spawn(LabelBundle::normal(&theme, "Main menu"));
 
                             for button in IngameMenuButton::iter() {
+                                let server_only = matches!(
+                                    button,
+                                    IngameMenuButton::Players | IngameMenuButton::StopServer
+                                );
+                                if server_only && !server.is_running() {
+                                    continue;
+                                }
                                 parent.spawn((
                                     button,
                                     TextButtonBundle::normal(&theme, button.to_string()),
@@ -94,9 +134,13 @@ impl InGameMenuPlugin {
         mut commands: Commands,
         mut save_events: EventWriter<GameSave>,
         mut settings_events: EventWriter<SettingsMenuOpen>,
+        mut players_events: EventWriter<PlayersMenuOpen>,
+        mut diagnostics_events: EventWriter<DiagnosticsExport>,
+        mut shutdown_events: EventWriter<ServerShutdown>,
         mut click_events: EventReader<Click>,
         theme: Res<Theme>,
-        mut world_state: ResMut<NextState<WorldState>>,
+        unsaved: Res<UnsavedChanges>,
+        mut transition_events: EventWriter<TransitionRequest>,
         buttons: Query<&IngameMenuButton>,
         roots: Query<Entity, (With<Node>, Without<Parent>)>,
         ingame_menus: Query<Entity, With<IngameMenu>>,
@@ -115,49 +159,139 @@ impl InGameMenuPlugin {
                 IngameMenuButton::Settings => {
                     settings_events.send_default();
                 }
-                IngameMenuButton::World => world_state.set(WorldState::World),
+                IngameMenuButton::Players => {
+                    players_events.send_default();
+                }
+                IngameMenuButton::ExportDiagnostics => {
+                    diagnostics_events.send_default();
+                }
+                IngameMenuButton::World => {
+                    transition_events
+                        .send(TransitionRequest::new(Transition::World(WorldState::World)));
+                }
                 IngameMenuButton::MainMenu => {
-                    setup_exit_dialog(&mut commands, roots.single(), &theme, ExitDialog::MainMenu)
+                    transition_events
+                        .send(TransitionRequest::new(Transition::Game(GameState::Menu)));
                 }
-                IngameMenuButton::ExitGame => {
-                    setup_exit_dialog(&mut commands, roots.single(), &theme, ExitDialog::Game)
+                IngameMenuButton::StopServer => {
+                    shutdown_events.send_default();
+                    info!("closing in-game menu");
+                    commands.entity(ingame_menus.single()).despawn_recursive();
                 }
+                IngameMenuButton::ExitGame => setup_exit_dialog(
+                    &mut commands,
+                    roots.single(),
+                    &theme,
+                    ExitDialog::Game,
+                    unsaved.0,
+                ),
             }
         }
     }
 
-    fn handle_exit_dialog_clicks(
+    fn show_exit_request(
         mut commands: Commands,
+        mut request_events: EventReader<ExitRequest>,
+        theme: Res<Theme>,
+        unsaved: Res<UnsavedChanges>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        request_events.clear();
+        setup_exit_dialog(
+            &mut commands,
+            roots.single(),
+            &theme,
+            ExitDialog::Game,
+            unsaved.0,
+        );
+    }
+
+    fn show_blocked_transition(
+        mut commands: Commands,
+        mut blocked_events: EventReader<TransitionBlocked>,
+        theme: Res<Theme>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        let Some(event) = blocked_events.read().last() else {
+            return;
+        };
+
+        match event.reason {
+            BlockReason::UnsavedChanges => setup_exit_dialog(
+                &mut commands,
+                roots.single(),
+                &theme,
+                ExitDialog::MainMenu,
+                true,
+            ),
+            BlockReason::PendingCommands => {
+                info!("showing pending commands dialog");
+                commands.entity(roots.single()).with_children(|parent| {
+                    parent.spawn((
+                        PendingCommandsDialog(event.transition),
+                        ConfirmationDialogBundle::new(
+                            &theme,
+                            "Some changes are not yet confirmed by the server, exit anyway?",
+                        )
+                        .with_accept("Exit"),
+                    ));
+                });
+            }
+        }
+    }
+
+    fn handle_pending_dialog_results(
+        mut transition_events: EventWriter<TransitionRequest>,
+        mut result_events: EventReader<DialogResult>,
+        dialogs: Query<&PendingCommandsDialog>,
+    ) {
+        for event in result_events.read() {
+            let Ok(&PendingCommandsDialog(transition)) = dialogs.get(event.dialog_entity) else {
+                continue;
+            };
+
+            if event.response == DialogResponse::Accept {
+                transition_events.send(TransitionRequest::forced(transition));
+            } else {
+                info!("cancelling exit");
+            }
+        }
+    }
+
+    fn handle_exit_dialog_results(
         mut save_events: EventWriter<GameSave>,
         mut exit_events: EventWriter<AppExit>,
-        mut click_events: EventReader<Click>,
-        mut game_state: ResMut<NextState<GameState>>,
-        buttons: Query<&ExitDialogButton>,
-        exit_dialogs: Query<(Entity, &ExitDialog)>,
+        mut result_events: EventReader<DialogResult>,
+        mut transition_events: EventWriter<TransitionRequest>,
+        unsaved: Res<UnsavedChanges>,
+        exit_dialogs: Query<&ExitDialog>,
     ) {
-        for button in buttons.iter_many(click_events.read().map(|event| event.0)) {
-            let (dialog_entity, exit_dialog) = exit_dialogs.single();
-            match button {
-                ExitDialogButton::SaveAndExit => {
-                    save_events.send_default();
-                    match exit_dialog {
-                        ExitDialog::MainMenu => game_state.set(GameState::Menu),
-                        ExitDialog::Game => {
-                            info!("exiting game");
-                            exit_events.send_default();
-                        }
+        for event in result_events.read() {
+            let Ok(exit_dialog) = exit_dialogs.get(event.dialog_entity) else {
+                continue;
+            };
+
+            match event.response {
+                DialogResponse::Accept => {
+                    if unsaved.0 {
+                        save_events.send_default();
                     }
                 }
-                ExitDialogButton::Exit => match exit_dialog {
-                    ExitDialog::MainMenu => game_state.set(GameState::Menu),
-                    ExitDialog::Game => {
-                        info!("exiting game");
-                        exit_events.send_default();
-                    }
-                },
-                ExitDialogButton::Cancel => {
+                DialogResponse::Decline => (),
+                DialogResponse::Cancel => {
                     info!("cancelling exit");
-                    commands.entity(dialog_entity).despawn_recursive();
+                    continue;
+                }
+            }
+
+            match exit_dialog {
+                ExitDialog::MainMenu => {
+                    transition_events
+                        .send(TransitionRequest::forced(Transition::Game(GameState::Menu)));
+                }
+                ExitDialog::Game => {
+                    info!("exiting game");
+                    exit_events.send_default();
                 }
             }
         }
@@ -174,46 +308,18 @@ fn setup_exit_dialog(
     root_entity: Entity,
     theme: &Theme,
     exit_dialog: ExitDialog,
+    unsaved: bool,
 ) {
     info!("showing exit dialog");
+    let dialog_bundle = if unsaved {
+        ConfirmationDialogBundle::new(theme, "Save before exiting?")
+            .with_accept("Save & exit")
+            .with_decline("Exit")
+    } else {
+        ConfirmationDialogBundle::new(theme, exit_dialog.label()).with_accept("Exit")
+    };
     commands.entity(root_entity).with_children(|parent| {
-        parent
-            .spawn((exit_dialog, DialogBundle::new(theme)))
-            .with_children(|parent| {
-                parent
-                    .spawn(NodeBundle {
-                        style: Style {
-                            flex_direction: FlexDirection::Column,
-                            justify_content: JustifyContent::Center,
-                            align_items: AlignItems::Center,
-                            padding: theme.padding.normal,
-                            row_gap: theme.gap.normal,
-                            ..Default::default()
-                        },
-                        background_color: theme.panel_color.into(),
-                        ..Default::default()
-                    })
-                    .with_children(|parent| {
-                        parent.spawn(LabelBundle::normal(theme, exit_dialog.label()));
-
-                        parent
-                            .spawn(NodeBundle {
-                                style: Style {
-                                    column_gap: theme.gap.normal,
-                                    ..Default::default()
-                                },
-                                ..Default::default()
-                            })
-                            .with_children(|parent| {
-                                for button in ExitDialogButton::iter() {
-                                    parent.spawn((
-                                        button,
-                                        TextButtonBundle::normal(theme, button.to_string()),
-                                    ));
-                                }
-                            });
-                    });
-            });
+        parent.spawn((exit_dialog, dialog_bundle));
     });
 }
 
@@ -225,9 +331,14 @@ enum IngameMenuButton {
     Resume,
     Save,
     Settings,
+    Players,
+    #[strum(serialize = "Export diagnostics")]
+    ExportDiagnostics,
     World,
     #[strum(serialize = "Main menu")]
     MainMenu,
+    #[strum(serialize = "Stop server")]
+    StopServer,
     #[strum(serialize = "Exit game")]
     ExitGame,
 }
@@ -247,13 +358,9 @@ impl ExitDialog {
     }
 }
 
-#[derive(Clone, Component, Copy, Display, EnumIter, PartialEq)]
-enum ExitDialogButton {
-    #[strum(serialize = "Save & exit")]
-    SaveAndExit,
-    Exit,
-    Cancel,
-}
+/// Confirmation for a transition blocked by commands not yet confirmed by the server.
+#[derive(Component)]
+struct PendingCommandsDialog(Transition);
 
 #[derive(Clone, Component, Copy, Display, EnumIter, PartialEq)]
 enum SaveAsDialogButton {
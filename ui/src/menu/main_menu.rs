@@ -1,8 +1,17 @@
 use bevy::{app::AppExit, prelude::*};
 use strum::{Display, EnumIter, IntoEnumIterator};
 
-use super::{settings_menu::SettingsMenuOpen, MenuState};
-use project_harmonia_widgets::{button::TextButtonBundle, click::Click, theme::Theme};
+use super::{
+    achievements_menu::AchievementsMenuOpen, scenarios_menu::ScenariosMenuOpen,
+    settings_menu::SettingsMenuOpen, MenuState,
+};
+use project_harmonia_base::{
+    game_world::showcase::ShowcaseBundle,
+    settings::{Settings, SettingsApply},
+};
+use project_harmonia_widgets::{
+    button::TextButtonBundle, click::Click, label::LabelBundle, theme::Theme,
+};
 
 pub(super) struct MainMenuPlugin;
 
@@ -11,14 +20,25 @@ impl Plugin for MainMenuPlugin {
         app.add_systems(OnEnter(MenuState::MainMenu), Self::setup)
             .add_systems(
                 Update,
-                Self::handle_clicks.run_if(in_state(MenuState::MainMenu)),
+                (Self::handle_clicks, Self::handle_changelog_clicks)
+                    .run_if(in_state(MenuState::MainMenu)),
             );
     }
 }
 
 impl MainMenuPlugin {
-    fn setup(mut commands: Commands, theme: Res<Theme>) {
+    fn setup(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        asset_server: Res<AssetServer>,
+        settings: Res<Settings>,
+    ) {
         info!("entering main menu");
+        commands.spawn((
+            StateScoped(MenuState::MainMenu),
+            ShowcaseBundle::new(asset_server.load("base/showcase/showcase.scn.ron")),
+        ));
+
         commands
             .spawn((
                 StateScoped(MenuState::MainMenu),
@@ -40,11 +60,33 @@ impl MainMenuPlugin {
                 for button in MainMenuButton::iter() {
                     parent.spawn((button, TextButtonBundle::large(&theme, button.to_string())));
                 }
+
+                if settings.last_version != env!("CARGO_PKG_VERSION") {
+                    setup_changelog(parent, &theme);
+                }
             });
     }
 
+    fn handle_changelog_clicks(
+        mut commands: Commands,
+        mut apply_events: EventWriter<SettingsApply>,
+        mut click_events: EventReader<Click>,
+        mut settings: ResMut<Settings>,
+        buttons: Query<(), With<ChangelogCloseButton>>,
+        changelogs: Query<Entity, With<Changelog>>,
+    ) {
+        for _ in buttons.iter_many(click_events.read().map(|event| event.0)) {
+            info!("closing changelog");
+            settings.last_version = env!("CARGO_PKG_VERSION").to_string();
+            apply_events.send_default();
+            commands.entity(changelogs.single()).despawn_recursive();
+        }
+    }
+
     fn handle_clicks(
         mut settings_events: EventWriter<SettingsMenuOpen>,
+        mut achievements_events: EventWriter<AchievementsMenuOpen>,
+        mut scenarios_events: EventWriter<ScenariosMenuOpen>,
         mut exit_events: EventWriter<AppExit>,
         mut click_events: EventReader<Click>,
         mut menu_state: ResMut<NextState<MenuState>>,
@@ -53,9 +95,15 @@ impl MainMenuPlugin {
         for button in buttons.iter_many(click_events.read().map(|event| event.0)) {
             match button {
                 MainMenuButton::Play => menu_state.set(MenuState::WorldBrowser),
+                MainMenuButton::Scenarios => {
+                    scenarios_events.send_default();
+                }
                 MainMenuButton::Settings => {
                     settings_events.send_default();
                 }
+                MainMenuButton::Achievements => {
+                    achievements_events.send_default();
+                }
                 MainMenuButton::Exit => {
                     info!("exiting game");
                     exit_events.send_default();
@@ -65,9 +113,148 @@ impl MainMenuPlugin {
     }
 }
 
+fn setup_changelog(parent: &mut ChildBuilder, theme: &Theme) {
+    debug!("showing changelog");
+    parent
+        .spawn((
+            Changelog,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(0.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::FlexEnd,
+                    margin: theme.padding.global,
+                    padding: theme.padding.normal,
+                    row_gap: theme.gap.normal,
+                    ..Default::default()
+                },
+                background_color: theme.panel_color.into(),
+                ..Default::default()
+            },
+        ))
+        .with_children(|parent| {
+            let entries = parse_changelog(include_str!("../../../CHANGELOG.md"));
+            let mut items = 0;
+            for entry in &entries {
+                match entry {
+                    ChangelogEntry::Release(title) => {
+                        parent.spawn(LabelBundle::large(theme, title.clone()));
+                    }
+                    ChangelogEntry::Section(title) => {
+                        parent.spawn(LabelBundle::normal(theme, title.clone()));
+                    }
+                    ChangelogEntry::Item(text) => {
+                        items += 1;
+                        if items <= MAX_CHANGELOG_ITEMS {
+                            parent.spawn(LabelBundle::small(theme, format!("• {text}")));
+                        }
+                    }
+                }
+            }
+            if items > MAX_CHANGELOG_ITEMS {
+                let hidden = items - MAX_CHANGELOG_ITEMS;
+                parent.spawn(LabelBundle::small(theme, format!("…and {hidden} more")));
+            }
+            parent.spawn((
+                ChangelogCloseButton,
+                TextButtonBundle::normal(theme, "Close"),
+            ));
+        });
+}
+
+/// Maximum number of displayed changes to fit the panel on the screen.
+const MAX_CHANGELOG_ITEMS: usize = 12;
+
+/// Extracts the latest release from the changelog in the "Keep a Changelog" format.
+///
+/// Markdown markup is stripped since labels display plain text.
+fn parse_changelog(text: &str) -> Vec<ChangelogEntry> {
+    let mut entries = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(title) = line.strip_prefix("## ") {
+            if !entries.is_empty() {
+                break;
+            }
+            let title = title.trim_start_matches('[').replacen(']', "", 1);
+            entries.push(ChangelogEntry::Release(title));
+        } else if entries.is_empty() {
+            continue;
+        } else if let Some(title) = line.strip_prefix("### ") {
+            entries.push(ChangelogEntry::Section(title.to_string()));
+        } else if let Some(item) = line.strip_prefix("- ") {
+            entries.push(ChangelogEntry::Item(item.replace('`', "")));
+        } else if let Some(ChangelogEntry::Item(item)) = entries.last_mut() {
+            // Continuation of a wrapped list item.
+            if !line.is_empty() {
+                item.push(' ');
+                item.push_str(&line.replace('`', ""));
+            }
+        }
+    }
+
+    entries
+}
+
+#[derive(Debug, PartialEq)]
+enum ChangelogEntry {
+    Release(String),
+    Section(String),
+    Item(String),
+}
+
+#[derive(Component)]
+struct Changelog;
+
+#[derive(Component)]
+struct ChangelogCloseButton;
+
 #[derive(Clone, Component, Copy, Display, EnumIter)]
 enum MainMenuButton {
     Play,
+    Scenarios,
     Settings,
+    Achievements,
     Exit,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changelog() {
+        const TEXT: &str = "# Changelog
+
+Description.
+
+## [Unreleased]
+
+### Added
+
+- First `item`.
+- Second item
+  on two lines.
+
+### Fixed
+
+- Third item.
+
+## [0.1.0] - 2024-01-01
+
+- Old item.
+";
+
+        assert_eq!(
+            parse_changelog(TEXT),
+            [
+                ChangelogEntry::Release("Unreleased".to_string()),
+                ChangelogEntry::Section("Added".to_string()),
+                ChangelogEntry::Item("First item.".to_string()),
+                ChangelogEntry::Item("Second item on two lines.".to_string()),
+                ChangelogEntry::Section("Fixed".to_string()),
+                ChangelogEntry::Item("Third item.".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use bevy_replicon_renet::renet::{transport::NetcodeServerTransport, ClientId as RenetClientId};
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+use project_harmonia_base::{
+    game_world::mutation_log::MutationLog,
+    network::moderation::{Ban, BanKind, BanList, ClientBan, ClientKick, ClientUnban},
+};
+use project_harmonia_widgets::{
+    button::TextButtonBundle, click::Click, dialog::DialogBundle, label::LabelBundle, theme::Theme,
+};
+
+/// Player management for the hosting player.
+pub(super) struct PlayersMenuPlugin;
+
+impl Plugin for PlayersMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlayersMenuOpen>()
+            .add_systems(
+                Update,
+                (
+                    Self::update_players,
+                    Self::update_bans,
+                    Self::update_changes,
+                    Self::handle_player_clicks,
+                    Self::handle_ban_clicks,
+                    Self::handle_menu_clicks,
+                )
+                    .run_if(any_with_component::<PlayersMenu>),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::setup.run_if(on_event::<PlayersMenuOpen>()),
+            );
+    }
+}
+
+impl PlayersMenuPlugin {
+    fn setup(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        info!("opening players menu");
+        commands.entity(roots.single()).with_children(|parent| {
+            parent
+                .spawn((PlayersMenu, DialogBundle::new(&theme)))
+                .with_children(|parent| {
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                padding: theme.padding.normal,
+                                row_gap: theme.gap.normal,
+                                ..Default::default()
+                            },
+                            background_color: theme.panel_color.into(),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn(LabelBundle::normal(&theme, "Players"));
+                            parent.spawn((PlayerList, list_node(&theme)));
+
+                            parent.spawn(LabelBundle::normal(&theme, "Banned"));
+                            parent.spawn((BannedList, list_node(&theme)));
+
+                            parent.spawn(LabelBundle::normal(&theme, "Recent changes"));
+                            parent.spawn((ChangeList, list_node(&theme)));
+
+                            parent.spawn((
+                                PlayersMenuButton,
+                                TextButtonBundle::normal(&theme, "Close"),
+                            ));
+                        });
+                });
+        });
+    }
+
+    fn update_players(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        connected_clients: Res<ConnectedClients>,
+        transport: Option<Res<NetcodeServerTransport>>,
+        lists: Query<(Entity, Ref<PlayerList>)>,
+    ) {
+        let (list_entity, list) = lists.single();
+        if !connected_clients.is_changed() && !list.is_added() {
+            return;
+        }
+
+        debug!("updating player list");
+        commands
+            .entity(list_entity)
+            .despawn_descendants()
+            .with_children(|parent| {
+                for client_id in connected_clients.iter().map(|client| client.id()) {
+                    let addr = transport
+                        .as_ref()
+                        .and_then(|transport| {
+                            transport.client_addr(RenetClientId::from_raw(client_id.get()))
+                        })
+                        .map(|addr| addr.ip().to_string())
+                        .unwrap_or_else(|| "unknown address".to_string());
+
+                    parent.spawn(row_node(&theme)).with_children(|parent| {
+                        parent.spawn(LabelBundle::normal(
+                            &theme,
+                            format!("{} ({addr})", client_id.get()),
+                        ));
+                        for button in PlayerButton::iter() {
+                            parent.spawn((
+                                button,
+                                PlayerNode(client_id),
+                                TextButtonBundle::normal(&theme, button.to_string()),
+                            ));
+                        }
+                    });
+                }
+            });
+    }
+
+    fn update_bans(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        ban_list: Res<BanList>,
+        lists: Query<(Entity, Ref<BannedList>)>,
+    ) {
+        let (list_entity, list) = lists.single();
+        if !ban_list.is_changed() && !list.is_added() {
+            return;
+        }
+
+        debug!("updating ban list");
+        commands
+            .entity(list_entity)
+            .despawn_descendants()
+            .with_children(|parent| {
+                for &ban in ban_list.iter() {
+                    let label = match ban {
+                        Ban::Player(identity) => format!("Player {identity}"),
+                        Ban::Ip(ip) => format!("IP {ip}"),
+                    };
+
+                    parent.spawn(row_node(&theme)).with_children(|parent| {
+                        parent.spawn(LabelBundle::normal(&theme, label));
+                        parent.spawn((UnbanButton(ban), TextButtonBundle::normal(&theme, "Unban")));
+                    });
+                }
+            });
+    }
+
+    fn update_changes(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        mutation_log: Res<MutationLog>,
+        lists: Query<(Entity, Ref<ChangeList>)>,
+    ) {
+        let (list_entity, list) = lists.single();
+        if !mutation_log.is_changed() && !list.is_added() {
+            return;
+        }
+
+        debug!("updating change list");
+        commands
+            .entity(list_entity)
+            .despawn_descendants()
+            .with_children(|parent| {
+                for entry in mutation_log.entries().rev().take(MAX_CHANGES) {
+                    parent.spawn(LabelBundle::small(&theme, entry.to_string()));
+                }
+            });
+    }
+
+    fn handle_player_clicks(
+        mut kick_events: EventWriter<ClientKick>,
+        mut ban_events: EventWriter<ClientBan>,
+        mut click_events: EventReader<Click>,
+        buttons: Query<(&PlayerButton, &PlayerNode)>,
+    ) {
+        for (&button, player_node) in buttons.iter_many(click_events.read().map(|event| event.0)) {
+            let client_id = player_node.0;
+            match button {
+                PlayerButton::Kick => {
+                    kick_events.send(ClientKick(client_id));
+                }
+                PlayerButton::BanPlayer => {
+                    ban_events.send(ClientBan {
+                        client_id,
+                        kind: BanKind::Player,
+                    });
+                }
+                PlayerButton::BanIp => {
+                    ban_events.send(ClientBan {
+                        client_id,
+                        kind: BanKind::Ip,
+                    });
+                }
+            }
+        }
+    }
+
+    fn handle_ban_clicks(
+        mut unban_events: EventWriter<ClientUnban>,
+        mut click_events: EventReader<Click>,
+        buttons: Query<&UnbanButton>,
+    ) {
+        for button in buttons.iter_many(click_events.read().map(|event| event.0)) {
+            unban_events.send(ClientUnban(button.0));
+        }
+    }
+
+    fn handle_menu_clicks(
+        mut commands: Commands,
+        mut click_events: EventReader<Click>,
+        buttons: Query<(), With<PlayersMenuButton>>,
+        menus: Query<Entity, With<PlayersMenu>>,
+    ) {
+        for _ in buttons.iter_many(click_events.read().map(|event| event.0)) {
+            info!("closing players menu");
+            commands.entity(menus.single()).despawn_recursive();
+        }
+    }
+}
+
+/// Number of the latest mutation log entries to display.
+const MAX_CHANGES: usize = 10;
+
+fn list_node(theme: &Theme) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Column,
+            row_gap: theme.gap.normal,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn row_node(theme: &Theme) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            align_items: AlignItems::Center,
+            column_gap: theme.gap.normal,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Creates a players menu node.
+#[derive(Default, Event)]
+pub(super) struct PlayersMenuOpen;
+
+#[derive(Component)]
+struct PlayersMenu;
+
+/// Node with connected players.
+#[derive(Component)]
+struct PlayerList;
+
+/// Node with banned players.
+#[derive(Component)]
+struct BannedList;
+
+/// Node with the latest world changes from [`MutationLog`].
+#[derive(Component)]
+struct ChangeList;
+
+/// Associated client for a button.
+#[derive(Component)]
+struct PlayerNode(ClientId);
+
+#[derive(Clone, Component, Copy, Display, EnumIter)]
+enum PlayerButton {
+    Kick,
+    #[strum(serialize = "Ban player")]
+    BanPlayer,
+    #[strum(serialize = "Ban IP")]
+    BanIp,
+}
+
+#[derive(Component)]
+struct UnbanButton(Ban);
+
+#[derive(Component)]
+struct PlayersMenuButton;
@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+
+use project_harmonia_base::{
+    asset::info::scenario_info::ScenarioInfo,
+    core::{
+        transition::{Transition, TransitionRequest},
+        GameState,
+    },
+    game_world::{scenario::ScenarioStart, WorldName},
+};
+use project_harmonia_widgets::{
+    button::TextButtonBundle, click::Click, dialog::DialogBundle, label::LabelBundle, theme::Theme,
+};
+
+/// Lists available scenarios and starts the selected one in a new world.
+pub(super) struct ScenariosMenuPlugin;
+
+impl Plugin for ScenariosMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ScenariosMenuOpen>()
+            .add_systems(
+                Update,
+                (Self::handle_scenario_clicks, Self::close)
+                    .run_if(any_with_component::<ScenariosMenu>),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::setup.run_if(on_event::<ScenariosMenuOpen>()),
+            );
+    }
+}
+
+impl ScenariosMenuPlugin {
+    fn setup(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        scenarios_info: Res<Assets<ScenarioInfo>>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        info!("opening scenarios menu");
+        commands.entity(roots.single()).with_children(|parent| {
+            parent
+                .spawn((ScenariosMenu, DialogBundle::new(&theme)))
+                .with_children(|parent| {
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                padding: theme.padding.normal,
+                                row_gap: theme.gap.normal,
+                                ..Default::default()
+                            },
+                            background_color: theme.panel_color.into(),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent
+                                .spawn(NodeBundle {
+                                    style: Style {
+                                        display: Display::Grid,
+                                        column_gap: theme.gap.normal,
+                                        row_gap: theme.gap.normal,
+                                        align_items: AlignItems::Center,
+                                        grid_template_columns: vec![GridTrack::auto(); 3],
+                                        ..Default::default()
+                                    },
+                                    ..Default::default()
+                                })
+                                .with_children(|parent| {
+                                    let mut scenarios: Vec<_> = scenarios_info.iter().collect();
+                                    scenarios.sort_by_key(|(_, info)| &info.general.name);
+                                    for (id, info) in scenarios {
+                                        parent.spawn(LabelBundle::normal(
+                                            &theme,
+                                            info.general.name.clone(),
+                                        ));
+                                        parent.spawn(LabelBundle::small(
+                                            &theme,
+                                            info.description.clone(),
+                                        ));
+                                        parent.spawn((
+                                            ScenarioButton(id),
+                                            TextButtonBundle::normal(&theme, "Play"),
+                                        ));
+                                    }
+                                });
+
+                            parent.spawn((CloseButton, TextButtonBundle::normal(&theme, "Close")));
+                        });
+                });
+        });
+    }
+
+    fn handle_scenario_clicks(
+        mut commands: Commands,
+        mut click_events: EventReader<Click>,
+        mut transition_events: EventWriter<TransitionRequest>,
+        asset_server: Res<AssetServer>,
+        scenarios_info: Res<Assets<ScenarioInfo>>,
+        buttons: Query<&ScenarioButton>,
+        menus: Query<Entity, With<ScenariosMenu>>,
+    ) {
+        for button in buttons.iter_many(click_events.read().map(|event| event.0)) {
+            let info = scenarios_info
+                .get(button.0)
+                .expect("buttons should be created only for loaded scenarios");
+            let path = asset_server
+                .get_path(button.0)
+                .expect("scenarios should be loaded from files");
+
+            info!("selecting scenario {path:?}");
+            commands.insert_resource(WorldName(info.general.name.clone()));
+            commands.insert_resource(ScenarioStart(path.into_owned()));
+            commands.entity(menus.single()).despawn_recursive();
+            transition_events.send(TransitionRequest::new(Transition::Game(GameState::InGame)));
+        }
+    }
+
+    fn close(
+        mut commands: Commands,
+        mut click_events: EventReader<Click>,
+        buttons: Query<(), With<CloseButton>>,
+        menus: Query<Entity, With<ScenariosMenu>>,
+    ) {
+        for _ in buttons.iter_many(click_events.read().map(|event| event.0)) {
+            info!("closing scenarios menu");
+            commands.entity(menus.single()).despawn_recursive();
+        }
+    }
+}
+
+#[derive(Default, Event)]
+pub(super) struct ScenariosMenuOpen;
+
+#[derive(Component)]
+struct ScenariosMenu;
+
+#[derive(Component)]
+struct ScenarioButton(AssetId<ScenarioInfo>);
+
+#[derive(Component)]
+struct CloseButton;
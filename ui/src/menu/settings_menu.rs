@@ -1,10 +1,15 @@
+use std::ops::RangeInclusive;
+
 use bevy::{prelude::*, reflect::GetPath, ui::FocusPolicy};
 use leafwing_input_manager::user_input::InputKind;
 use strum::{Display, EnumIter, IntoEnumIterator};
 
 use project_harmonia_base::{
     input_events::InputEvents,
-    settings::{Action, Settings, SettingsApply},
+    settings::{
+        Action, AudioSettings, ControlsSettings, GameplaySettings, Settings, SettingsApply,
+        VideoSettings,
+    },
 };
 use project_harmonia_widgets::{
     button::{ButtonText, ExclusiveButton, TabContent, TextButtonBundle, Toggled},
@@ -27,6 +32,8 @@ impl Plugin for SettingsMenuPlugin {
                     Self::start_mapping,
                     Self::read_binding,
                     Self::handle_binding_dialog_clicks,
+                    Self::handle_number_clicks,
+                    Self::preview_checkboxes,
                     Self::handle_settings_menu_clicks,
                 )
                     .run_if(any_with_component::<SettingsMenu>),
@@ -51,6 +58,7 @@ impl SettingsMenuPlugin {
             parent
                 .spawn((
                     SettingsMenu,
+                    OriginalSettings(settings.clone()),
                     Interaction::None,
                     NodeBundle {
                         style: Style {
@@ -90,9 +98,13 @@ impl SettingsMenuPlugin {
                             })
                             .with_children(|parent| match tab {
                                 SettingsTab::Video => setup_video_tab(parent, &theme, &settings),
+                                SettingsTab::Audio => setup_audio_tab(parent, &theme, &settings),
                                 SettingsTab::Controls => {
                                     setup_controls_tab(parent, &theme, &settings)
                                 }
+                                SettingsTab::Gameplay => {
+                                    setup_gameplay_tab(parent, &theme, &settings)
+                                }
                                 SettingsTab::Developer => {
                                     setup_developer_tab(parent, &theme, &settings)
                                 }
@@ -302,18 +314,70 @@ impl SettingsMenuPlugin {
         }
     }
 
+    /// Changes the value immediately to preview it.
+    fn handle_number_clicks(
+        mut click_events: EventReader<Click>,
+        mut settings: ResMut<Settings>,
+        buttons: Query<(&NumberButton, &Parent)>,
+        fields: Query<(&NumberField, &SettingsField, &Children)>,
+        mut texts: Query<&mut Text, With<NumberText>>,
+    ) {
+        for (&number_button, parent) in buttons.iter_many(click_events.read().map(|event| event.0))
+        {
+            let (field, settings_field, children) = fields
+                .get(**parent)
+                .expect("number buttons should be children of a field");
+            let field_value = settings
+                .reflect_path_mut(settings_field.0)
+                .expect("number fields should point to existing settings");
+            let value = if let Some(value) = field_value.downcast_mut::<f32>() {
+                *value = field.apply_step(*value, number_button.0);
+                *value
+            } else {
+                let value = field_value
+                    .downcast_mut::<usize>()
+                    .expect("fields with number buttons should be stored as floats or integers");
+                let new_value = field.apply_step(*value as f32, number_button.0);
+                *value = new_value as usize;
+                new_value
+            };
+            debug!("changing `{}` to {value}", settings_field.0);
+
+            let mut text = texts
+                .iter_many_mut(children)
+                .fetch_next()
+                .expect("number field should have a text");
+            text.sections[0].value = format_number(value, field.precision);
+        }
+    }
+
+    fn preview_checkboxes(
+        mut settings: ResMut<Settings>,
+        checkboxes: Query<(&Checkbox, &SettingsField), Changed<Checkbox>>,
+    ) {
+        for (checkbox, field) in &checkboxes {
+            let field_value = settings
+                .path_mut::<bool>(field.0)
+                .expect("fields with checkboxes should be stored as bools");
+            if *field_value != checkbox.0 {
+                *field_value = checkbox.0;
+            }
+        }
+    }
+
     fn handle_settings_menu_clicks(
         mut commands: Commands,
         mut apply_events: EventWriter<SettingsApply>,
         mut click_events: EventReader<Click>,
         mut settings: ResMut<Settings>,
-        settings_menus: Query<Entity, With<SettingsMenu>>,
+        settings_menus: Query<(Entity, &OriginalSettings)>,
         settings_buttons: Query<&SettingsButton>,
         mapping_buttons: Query<&Mapping>,
         checkboxes: Query<(&Checkbox, &SettingsField)>,
     ) {
         for &settings_button in settings_buttons.iter_many(click_events.read().map(|event| event.0))
         {
+            let (menu_entity, original_settings) = settings_menus.single();
             if settings_button == SettingsButton::Ok {
                 for (checkbox, field) in &checkboxes {
                     let field_value = settings
@@ -333,10 +397,13 @@ impl SettingsMenuPlugin {
                     }
                 }
                 apply_events.send_default();
+            } else {
+                debug!("reverting previewed settings");
+                *settings = original_settings.0.clone();
             }
 
             info!("closing settings menu");
-            commands.entity(settings_menus.single()).despawn_recursive()
+            commands.entity(menu_entity).despawn_recursive()
         }
     }
 }
@@ -364,10 +431,110 @@ fn setup_video_tab(parent: &mut ChildBuilder, theme: &Theme, settings: &Settings
                 CheckboxBundle::new(theme, settings.video.fullscreen, "Fullscreen"),
                 setting_field!(settings.video.fullscreen),
             ));
+            spawn_number_field(
+                parent,
+                theme,
+                "Fullscreen monitor",
+                settings.video.monitor as f32,
+                NumberField {
+                    step: 1.0,
+                    range: *VideoSettings::MONITOR_RANGE.start() as f32
+                        ..=*VideoSettings::MONITOR_RANGE.end() as f32,
+                    precision: 0,
+                },
+                setting_field!(settings.video.monitor),
+            );
+        });
+}
+
+fn setup_audio_tab(parent: &mut ChildBuilder, theme: &Theme, settings: &Settings) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                row_gap: theme.gap.normal,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            spawn_number_field(
+                parent,
+                theme,
+                "Master volume",
+                settings.audio.master_volume,
+                NumberField::volume(),
+                setting_field!(settings.audio.master_volume),
+            );
+            spawn_number_field(
+                parent,
+                theme,
+                "Ambience volume",
+                settings.audio.ambience_volume,
+                NumberField::volume(),
+                setting_field!(settings.audio.ambience_volume),
+            );
         });
 }
 
 fn setup_controls_tab(parent: &mut ChildBuilder, theme: &Theme, settings: &Settings) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                row_gap: theme.gap.normal,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            let controls = &settings.controls;
+            spawn_number_field(
+                parent,
+                theme,
+                "Rotation sensitivity",
+                controls.rotation_sensitivity,
+                NumberField::sensitivity(),
+                setting_field!(settings.controls.rotation_sensitivity),
+            );
+            spawn_number_field(
+                parent,
+                theme,
+                "Zoom sensitivity",
+                controls.zoom_sensitivity,
+                NumberField::sensitivity(),
+                setting_field!(settings.controls.zoom_sensitivity),
+            );
+            parent.spawn((
+                CheckboxBundle::new(
+                    theme,
+                    controls.invert_rotation_x,
+                    "Invert horizontal rotation",
+                ),
+                setting_field!(settings.controls.invert_rotation_x),
+            ));
+            parent.spawn((
+                CheckboxBundle::new(
+                    theme,
+                    controls.invert_rotation_y,
+                    "Invert vertical rotation",
+                ),
+                setting_field!(settings.controls.invert_rotation_y),
+            ));
+            spawn_number_field(
+                parent,
+                theme,
+                "Gamepad deadzone",
+                controls.gamepad_deadzone,
+                NumberField {
+                    step: 0.05,
+                    range: ControlsSettings::DEADZONE_RANGE,
+                    precision: 2,
+                },
+                setting_field!(settings.controls.gamepad_deadzone),
+            );
+        });
+
     const INPUTS_PER_ACTION: usize = 3;
     parent
         .spawn(NodeBundle {
@@ -400,6 +567,78 @@ fn setup_controls_tab(parent: &mut ChildBuilder, theme: &Theme, settings: &Setti
         });
 }
 
+fn spawn_number_field(
+    parent: &mut ChildBuilder,
+    theme: &Theme,
+    label: &str,
+    value: f32,
+    number_field: NumberField,
+    settings_field: SettingsField,
+) {
+    let precision = number_field.precision;
+    parent
+        .spawn((
+            number_field,
+            settings_field,
+            NodeBundle {
+                style: Style {
+                    align_items: AlignItems::Center,
+                    column_gap: theme.gap.normal,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(LabelBundle::normal(theme, label));
+            parent.spawn((NumberButton(-1.0), TextButtonBundle::symbol(theme, "➖")));
+            parent.spawn((
+                NumberText,
+                LabelBundle::normal(theme, format_number(value, precision)),
+            ));
+            parent.spawn((NumberButton(1.0), TextButtonBundle::symbol(theme, "➕")));
+        });
+}
+
+fn format_number(value: f32, precision: usize) -> String {
+    format!("{value:.precision$}")
+}
+
+fn setup_gameplay_tab(parent: &mut ChildBuilder, theme: &Theme, settings: &Settings) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                row_gap: theme.gap.normal,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            spawn_number_field(
+                parent,
+                theme,
+                "Memories per family",
+                settings.gameplay.memories_capacity as f32,
+                NumberField {
+                    step: 10.0,
+                    range: *GameplaySettings::MEMORIES_CAPACITY_RANGE.start() as f32
+                        ..=*GameplaySettings::MEMORIES_CAPACITY_RANGE.end() as f32,
+                    precision: 0,
+                },
+                setting_field!(settings.gameplay.memories_capacity),
+            );
+            parent.spawn((
+                CheckboxBundle::new(
+                    theme,
+                    settings.gameplay.memory_snapshots,
+                    "Capture memory snapshots",
+                ),
+                setting_field!(settings.gameplay.memory_snapshots),
+            ));
+        });
+}
+
 fn setup_developer_tab(parent: &mut ChildBuilder, theme: &Theme, settings: &Settings) {
     parent
         .spawn(NodeBundle {
@@ -439,6 +678,23 @@ fn setup_developer_tab(parent: &mut ChildBuilder, theme: &Theme, settings: &Sett
                 ),
                 setting_field!(settings.developer.nav_mesh),
             ));
+            parent.spawn((
+                CheckboxBundle::new(
+                    theme,
+                    settings.developer.spline_connections,
+                    "Display spline connections",
+                ),
+                setting_field!(settings.developer.spline_connections),
+            ));
+            parent.spawn((
+                CheckboxBundle::new(theme, settings.developer.apertures, "Display apertures"),
+                setting_field!(settings.developer.apertures),
+            ));
+            #[cfg(feature = "developer")]
+            parent.spawn((
+                CheckboxBundle::new(theme, settings.developer.inspector, "Display inspector"),
+                setting_field!(settings.developer.inspector),
+            ));
         });
 }
 
@@ -449,11 +705,17 @@ pub(super) struct SettingsMenuOpen;
 #[derive(Component)]
 struct SettingsMenu;
 
+/// Settings before opening the menu to revert previewed changes.
+#[derive(Component)]
+struct OriginalSettings(Settings);
+
 #[derive(Default, Display, EnumIter, PartialEq)]
 enum SettingsTab {
     #[default]
     Video,
+    Audio,
     Controls,
+    Gameplay,
     Developer,
 }
 
@@ -491,3 +753,44 @@ struct BindingLabel;
 
 #[derive(Component)]
 struct SettingsField(&'static str);
+
+/// Field with buttons to step the value within the range.
+#[derive(Component)]
+struct NumberField {
+    step: f32,
+    range: RangeInclusive<f32>,
+
+    /// Number of displayed decimal places.
+    precision: usize,
+}
+
+impl NumberField {
+    fn sensitivity() -> Self {
+        Self {
+            step: 0.1,
+            range: ControlsSettings::SENSITIVITY_RANGE,
+            precision: 2,
+        }
+    }
+
+    fn volume() -> Self {
+        Self {
+            step: 0.1,
+            range: AudioSettings::VOLUME_RANGE,
+            precision: 1,
+        }
+    }
+
+    /// Returns `value` changed by the step in the specified direction within the range.
+    fn apply_step(&self, value: f32, direction: f32) -> f32 {
+        (value + self.step * direction).clamp(*self.range.start(), *self.range.end())
+    }
+}
+
+/// Button that changes [`NumberField`] by its step multiplied by the stored value.
+#[derive(Clone, Component, Copy)]
+struct NumberButton(f32);
+
+/// Marker for the label with the current value of [`NumberField`].
+#[derive(Component)]
+struct NumberText;
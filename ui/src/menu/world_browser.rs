@@ -1,4 +1,4 @@
-use std::{fs, mem, net::Ipv4Addr};
+use std::{fs, mem, net::Ipv4Addr, path::Path};
 
 use anyhow::{Context, Result};
 use bevy::prelude::*;
@@ -12,15 +12,28 @@ use strum::{Display, EnumIter, IntoEnumIterator};
 
 use super::MenuState;
 use project_harmonia_base::{
-    core::GameState,
+    core::{
+        transition::{Transition, TransitionRequest},
+        GameState,
+    },
     game_paths::GamePaths,
-    game_world::{GameLoad, WorldName},
-    message::error_message,
-    network::{self, DEFAULT_PORT},
+    game_world::{aging::EnableAging, GameLoad, WorldName},
+    message::{error_message, Message},
+    network::{self, moderation::PlayerIdentity, ServerInfo, DEFAULT_MAX_CLIENTS, DEFAULT_PORT},
+    save_encryption::{self, SavePassphrase},
+    settings::Settings,
+    world_archive::{self, WorldArchive, ARCHIVE_EXTENSION},
 };
 use project_harmonia_widgets::{
-    button::TextButtonBundle, click::Click, dialog::Dialog, dialog::DialogBundle,
-    label::LabelBundle, text_edit::TextEditBundle, theme::Theme,
+    button::{TextButtonBundle, Toggled},
+    click::Click,
+    dialog::{
+        ConfirmationDialog, ConfirmationDialogBundle, Dialog, DialogBundle, DialogResponse,
+        DialogResult,
+    },
+    label::LabelBundle,
+    text_edit::TextEditBundle,
+    theme::Theme,
 };
 
 pub(super) struct WorldBrowserPlugin;
@@ -31,12 +44,14 @@ impl Plugin for WorldBrowserPlugin {
             .add_systems(
                 Update,
                 (
-                    Self::handle_world_clicks,
+                    Self::handle_world_clicks.pipe(error_message),
                     Self::handle_host_dialog_clicks.pipe(error_message),
-                    Self::handle_remove_dialog_clicks.pipe(error_message),
+                    Self::handle_passphrase_dialog_clicks,
+                    Self::handle_remove_dialog_results.pipe(error_message),
                     Self::handle_world_browser_clicks,
                     Self::handle_create_dialog_clicks,
                     Self::handle_join_dialog_clicks.pipe(error_message),
+                    Self::handle_import_dialog_clicks.pipe(error_message),
                 )
                     .run_if(in_state(MenuState::WorldBrowser)),
             );
@@ -65,19 +80,22 @@ impl WorldBrowserPlugin {
             .with_children(|parent| {
                 parent.spawn(LabelBundle::large(&theme, "World browser"));
                 parent
-                    .spawn(NodeBundle {
-                        style: Style {
-                            width: Val::Percent(100.0),
-                            height: Val::Percent(100.0),
-                            flex_direction: FlexDirection::Column,
-                            align_items: AlignItems::Center,
-                            justify_content: JustifyContent::FlexStart,
-                            padding: theme.padding.normal,
-                            row_gap: theme.gap.normal,
+                    .spawn((
+                        WorldList,
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                justify_content: JustifyContent::FlexStart,
+                                padding: theme.padding.normal,
+                                row_gap: theme.gap.normal,
+                                ..Default::default()
+                            },
                             ..Default::default()
                         },
-                        ..Default::default()
-                    })
+                    ))
                     .with_children(|parent| {
                         let world_names = game_paths
                             .get_world_names()
@@ -112,12 +130,14 @@ impl WorldBrowserPlugin {
     fn handle_world_clicks(
         mut commands: Commands,
         mut load_events: EventWriter<GameLoad>,
+        mut message_events: EventWriter<Message>,
         mut click_events: EventReader<Click>,
         theme: Res<Theme>,
+        game_paths: Res<GamePaths>,
         buttons: Query<(&WorldButton, &WorldNode)>,
         labels: Query<&Text>,
         roots: Query<Entity, (With<Node>, Without<Parent>)>,
-    ) {
+    ) -> Result<()> {
         for (world_button, &world_node) in
             buttons.iter_many(click_events.read().map(|event| event.0))
         {
@@ -126,8 +146,20 @@ impl WorldBrowserPlugin {
                 .expect("world label should contain text");
             match world_button {
                 WorldButton::Play => {
-                    commands.insert_resource(WorldName(world_name.sections[0].value.clone()));
-                    load_events.send_default();
+                    let world_path = game_paths.world_path(&world_name.sections[0].value);
+                    if save_encryption::is_file_encrypted(&world_path)? {
+                        setup_passphrase_dialog(
+                            &mut commands,
+                            roots.single(),
+                            &theme,
+                            world_node,
+                            &world_name.sections[0].value,
+                        );
+                    } else {
+                        commands.remove_resource::<SavePassphrase>();
+                        commands.insert_resource(WorldName(world_name.sections[0].value.clone()));
+                        load_events.send_default();
+                    }
                 }
                 WorldButton::Host => setup_host_world_dialog(
                     &mut commands,
@@ -136,6 +168,10 @@ impl WorldBrowserPlugin {
                     world_node,
                     &world_name.sections[0].value,
                 ),
+                WorldButton::Export => {
+                    let path = world_archive::export(&game_paths, &world_name.sections[0].value)?;
+                    message_events.send(Message(format!("World exported to {path:?}")));
+                }
                 WorldButton::Remove => {
                     setup_remove_world_dialog(
                         &mut commands,
@@ -147,6 +183,8 @@ impl WorldBrowserPlugin {
                 }
             }
         }
+
+        Ok(())
     }
 
     fn handle_host_dialog_clicks(
@@ -154,26 +192,51 @@ impl WorldBrowserPlugin {
         mut load_events: EventWriter<GameLoad>,
         mut click_events: EventReader<Click>,
         network_channels: Res<RepliconChannels>,
+        settings: Res<Settings>,
         dialogs: Query<(Entity, &WorldNode), With<Dialog>>,
         buttons: Query<&HostDialogButton>,
-        text_edits: Query<&Text, With<PortEdit>>,
-        mut labels: Query<&mut Text, Without<PortEdit>>,
+        name_edits: Query<&TextInputValue, With<ServerNameEdit>>,
+        port_edits: Query<&TextInputValue, With<PortEdit>>,
+        password_edits: Query<&TextInputValue, With<PasswordEdit>>,
+        passphrase_edits: Query<&TextInputValue, With<PassphraseEdit>>,
+        max_players_edits: Query<&TextInputValue, With<MaxPlayersEdit>>,
+        mut labels: Query<&mut Text>,
     ) -> Result<()> {
         for &button in buttons.iter_many(click_events.read().map(|event| event.0)) {
             let (dialog_entity, world_node) = dialogs.single();
             match button {
                 HostDialogButton::Host => {
-                    let server = RenetServer::new(ConnectionConfig {
-                        server_channels_config: network_channels.get_server_configs(),
-                        client_channels_config: network_channels.get_client_configs(),
-                        ..Default::default()
-                    });
-                    let port = text_edits.single();
-                    let transport = network::create_server(port.sections[0].value.parse()?)
-                        .context("unable to create server")?;
+                    let port = port_edits.single();
+                    let port = port
+                        .0
+                        .parse()
+                        .with_context(|| format!("{:?} is not a valid port", port.0))?;
+                    let max_players = max_players_edits.single();
+                    let max_players = max_players.0.parse().with_context(|| {
+                        format!("{:?} is not a valid number of players", max_players.0)
+                    })?;
+
+                    let server = RenetServer::new(network::server_connection_config(
+                        &network_channels,
+                        &settings.server,
+                    )?);
+                    let transport =
+                        network::create_server(port, max_players, &password_edits.single().0)
+                            .with_context(|| format!("unable to create server on port {port}"))?;
 
                     commands.insert_resource(server);
                     commands.insert_resource(transport);
+                    commands.insert_resource(ServerInfo {
+                        name: name_edits.single().0.clone(),
+                    });
+
+                    // Empty passphrase keeps the world unencrypted.
+                    let passphrase = passphrase_edits.single();
+                    if passphrase.0.is_empty() {
+                        commands.remove_resource::<SavePassphrase>();
+                    } else {
+                        commands.insert_resource(SavePassphrase(passphrase.0.clone()));
+                    }
 
                     let mut world_name = labels
                         .get_mut(world_node.label_entity)
@@ -191,30 +254,71 @@ impl WorldBrowserPlugin {
         Ok(())
     }
 
-    fn handle_remove_dialog_clicks(
+    fn handle_passphrase_dialog_clicks(
         mut commands: Commands,
+        mut load_events: EventWriter<GameLoad>,
         mut click_events: EventReader<Click>,
-        game_paths: Res<GamePaths>,
         dialogs: Query<(Entity, &WorldNode), With<Dialog>>,
-        buttons: Query<&RemoveDialogButton>,
+        buttons: Query<&PassphraseDialogButton>,
+        mut passphrase_edits: Query<&mut TextInputValue, With<PassphraseEdit>>,
         labels: Query<&Text>,
-    ) -> Result<()> {
+    ) {
         for &button in buttons.iter_many(click_events.read().map(|event| event.0)) {
             let (dialog_entity, world_node) = dialogs.single();
-            let world_name = labels
-                .get(world_node.label_entity)
-                .expect("world label should contain text");
             match button {
-                RemoveDialogButton::Remove => {
-                    let world_path = game_paths.world_path(&world_name.sections[0].value);
-                    fs::remove_file(&world_path)
-                        .with_context(|| format!("unable to remove {world_path:?}"))?;
-                    commands.entity(world_node.node_entity).despawn_recursive();
+                PassphraseDialogButton::Load => {
+                    let world_name = labels
+                        .get(world_node.label_entity)
+                        .expect("world label should contain text");
+                    let mut passphrase = passphrase_edits.single_mut();
+                    commands.insert_resource(SavePassphrase(mem::take(&mut passphrase.0)));
+                    commands.insert_resource(WorldName(world_name.sections[0].value.clone()));
+                    load_events.send_default();
                 }
-                RemoveDialogButton::Cancel => info!("cancelling removal"),
+                PassphraseDialogButton::Cancel => info!("cancelling encrypted world loading"),
             }
             commands.entity(dialog_entity).despawn_recursive();
         }
+    }
+
+    fn handle_remove_dialog_results(
+        mut commands: Commands,
+        mut result_events: EventReader<DialogResult>,
+        game_paths: Res<GamePaths>,
+        dialogs: Query<&WorldNode, With<ConfirmationDialog>>,
+        labels: Query<&Text>,
+    ) -> Result<()> {
+        for event in result_events.read() {
+            let Ok(world_node) = dialogs.get(event.dialog_entity) else {
+                continue;
+            };
+
+            if event.response == DialogResponse::Accept {
+                let world_name = labels
+                    .get(world_node.label_entity)
+                    .expect("world label should contain text");
+                let world_path = game_paths.world_path(&world_name.sections[0].value);
+                fs::remove_file(&world_path)
+                    .with_context(|| format!("unable to remove {world_path:?}"))?;
+
+                // Thumbnail may not exist.
+                let thumbnail_path = game_paths.world_thumbnail_path(&world_name.sections[0].value);
+                if thumbnail_path.exists() {
+                    fs::remove_file(&thumbnail_path)
+                        .with_context(|| format!("unable to remove {thumbnail_path:?}"))?;
+                }
+
+                let snapshots_dir = game_paths.snapshots_dir(&world_name.sections[0].value);
+                if snapshots_dir.exists() {
+                    fs::remove_dir_all(&snapshots_dir)
+                        .with_context(|| format!("unable to remove {snapshots_dir:?}"))?;
+                }
+
+                commands.entity(world_node.node_entity).despawn_recursive();
+            } else {
+                info!("cancelling removal");
+            }
+        }
 
         Ok(())
     }
@@ -223,6 +327,7 @@ impl WorldBrowserPlugin {
         mut commands: Commands,
         mut click_events: EventReader<Click>,
         theme: Res<Theme>,
+        game_paths: Res<GamePaths>,
         buttons: Query<&WorldBrowserButton>,
         roots: Query<Entity, (With<Node>, Without<Parent>)>,
     ) {
@@ -234,6 +339,9 @@ impl WorldBrowserPlugin {
                 WorldBrowserButton::Join => {
                     setup_join_world_dialog(&mut commands, roots.single(), &theme)
                 }
+                WorldBrowserButton::Import => {
+                    setup_import_world_dialog(&mut commands, roots.single(), &theme, &game_paths)
+                }
             }
         }
     }
@@ -241,9 +349,10 @@ impl WorldBrowserPlugin {
     fn handle_create_dialog_clicks(
         mut commands: Commands,
         mut click_events: EventReader<Click>,
-        mut game_state: ResMut<NextState<GameState>>,
+        mut transition_events: EventWriter<TransitionRequest>,
         buttons: Query<&CreateDialogButton>,
         mut text_edits: Query<&mut TextInputValue, With<WorldNameEdit>>,
+        aging_buttons: Query<&Toggled, With<AgingButton>>,
         dialogs: Query<Entity, With<Dialog>>,
     ) {
         for &button in buttons.iter_many(click_events.read().map(|event| event.0)) {
@@ -251,7 +360,11 @@ impl WorldBrowserPlugin {
                 CreateDialogButton::Create => {
                     let mut world_name = text_edits.single_mut();
                     commands.insert_resource(WorldName(mem::take(&mut world_name.0)));
-                    game_state.set(GameState::InGame);
+                    if aging_buttons.single().0 {
+                        commands.insert_resource(EnableAging);
+                    }
+                    transition_events
+                        .send(TransitionRequest::new(Transition::Game(GameState::InGame)));
                 }
                 CreateDialogButton::Cancel => info!("cancelling creation"),
             }
@@ -263,9 +376,11 @@ impl WorldBrowserPlugin {
         mut commands: Commands,
         mut click_events: EventReader<Click>,
         network_channels: Res<RepliconChannels>,
+        identity: Res<PlayerIdentity>,
         buttons: Query<&JoinDialogButton>,
         port_edits: Query<&TextInputValue, With<PortEdit>>,
         ip_edits: Query<&TextInputValue, With<IpEdit>>,
+        password_edits: Query<&TextInputValue, With<PasswordEdit>>,
         dialogs: Query<Entity, With<Dialog>>,
     ) -> Result<()> {
         for &button in buttons.iter_many(click_events.read().map(|event| event.0)) {
@@ -278,8 +393,14 @@ impl WorldBrowserPlugin {
                     });
                     let ip = ip_edits.single();
                     let port = port_edits.single();
-                    let transport = network::create_client(ip.0.parse()?, port.0.parse()?)
-                        .context("unable to create connection")?;
+                    let password = password_edits.single();
+                    let transport = network::create_client(
+                        ip.0.parse()?,
+                        port.0.parse()?,
+                        &password.0,
+                        *identity,
+                    )
+                    .context("unable to create connection")?;
 
                     commands.insert_resource(client);
                     commands.insert_resource(transport);
@@ -293,6 +414,37 @@ impl WorldBrowserPlugin {
 
         Ok(())
     }
+
+    fn handle_import_dialog_clicks(
+        mut commands: Commands,
+        mut click_events: EventReader<Click>,
+        theme: Res<Theme>,
+        game_paths: Res<GamePaths>,
+        buttons: Query<&ImportDialogButton>,
+        path_edits: Query<&TextInputValue, With<ArchivePathEdit>>,
+        dialogs: Query<Entity, With<Dialog>>,
+        world_lists: Query<Entity, With<WorldList>>,
+    ) -> Result<()> {
+        for &button in buttons.iter_many(click_events.read().map(|event| event.0)) {
+            match button {
+                ImportDialogButton::Import => {
+                    let path = path_edits.single();
+                    let archive = WorldArchive::open(Path::new(&path.0))?;
+                    archive.import(&game_paths)?;
+
+                    commands
+                        .entity(world_lists.single())
+                        .with_children(|parent| {
+                            setup_world_node(parent, &theme, archive.world_name)
+                        });
+                }
+                ImportDialogButton::Cancel => info!("cancelling import"),
+            }
+            commands.entity(dialogs.single()).despawn_recursive();
+        }
+
+        Ok(())
+    }
 }
 
 fn setup_world_node(parent: &mut ChildBuilder, theme: &Theme, label: impl Into<String>) {
@@ -374,17 +526,45 @@ fn setup_host_world_dialog(
                         parent
                             .spawn(NodeBundle {
                                 style: Style {
+                                    display: Display::Grid,
                                     column_gap: theme.gap.normal,
-                                    justify_content: JustifyContent::Center,
+                                    row_gap: theme.gap.normal,
+                                    grid_template_columns: vec![GridTrack::auto(); 2],
                                     ..Default::default()
                                 },
                                 ..Default::default()
                             })
                             .with_children(|parent| {
+                                parent.spawn(LabelBundle::normal(theme, "Name:"));
+                                parent.spawn((
+                                    ServerNameEdit,
+                                    TextEditBundle::new(theme, world_name),
+                                ));
+
                                 parent.spawn(LabelBundle::normal(theme, "Port:"));
                                 parent.spawn((
                                     PortEdit,
-                                    TextEditBundle::new(theme, DEFAULT_PORT.to_string()),
+                                    TextEditBundle::new(theme, DEFAULT_PORT.to_string())
+                                        .inactive(theme),
+                                ));
+
+                                parent.spawn(LabelBundle::normal(theme, "Password:"));
+                                parent.spawn((
+                                    PasswordEdit,
+                                    TextEditBundle::empty(theme).inactive(theme).masked(),
+                                ));
+
+                                parent.spawn(LabelBundle::normal(theme, "Save passphrase:"));
+                                parent.spawn((
+                                    PassphraseEdit,
+                                    TextEditBundle::empty(theme).inactive(theme).masked(),
+                                ));
+
+                                parent.spawn(LabelBundle::normal(theme, "Max players:"));
+                                parent.spawn((
+                                    MaxPlayersEdit,
+                                    TextEditBundle::new(theme, DEFAULT_MAX_CLIENTS.to_string())
+                                        .inactive(theme),
                                 ));
                             });
 
@@ -409,15 +589,15 @@ fn setup_host_world_dialog(
     });
 }
 
-fn setup_remove_world_dialog(
+fn setup_passphrase_dialog(
     commands: &mut Commands,
     root_entity: Entity,
     theme: &Theme,
     world_node: WorldNode,
     world_name: &str,
 ) {
+    info!("showing passphrase dialog");
     commands.entity(root_entity).with_children(|parent| {
-        info!("showing remove dialog");
         parent
             .spawn((DialogBundle::new(theme), world_node))
             .with_children(|parent| {
@@ -437,9 +617,9 @@ fn setup_remove_world_dialog(
                     .with_children(|parent| {
                         parent.spawn(LabelBundle::normal(
                             theme,
-                            format!("Are you sure you want to remove world {world_name}?",),
+                            format!("World {world_name} is encrypted, enter passphrase"),
                         ));
-
+                        parent.spawn((PassphraseEdit, TextEditBundle::empty(theme).masked()));
                         parent
                             .spawn(NodeBundle {
                                 style: Style {
@@ -449,7 +629,7 @@ fn setup_remove_world_dialog(
                                 ..Default::default()
                             })
                             .with_children(|parent| {
-                                for button in RemoveDialogButton::iter() {
+                                for button in PassphraseDialogButton::iter() {
                                     parent.spawn((
                                         button,
                                         TextButtonBundle::normal(theme, button.to_string()),
@@ -461,6 +641,26 @@ fn setup_remove_world_dialog(
     });
 }
 
+fn setup_remove_world_dialog(
+    commands: &mut Commands,
+    root_entity: Entity,
+    theme: &Theme,
+    world_node: WorldNode,
+    world_name: &str,
+) {
+    info!("showing remove dialog");
+    commands.entity(root_entity).with_children(|parent| {
+        parent.spawn((
+            world_node,
+            ConfirmationDialogBundle::new(
+                theme,
+                format!("Are you sure you want to remove world {world_name}?"),
+            )
+            .with_accept("Remove"),
+        ));
+    });
+}
+
 fn setup_create_world_dialog(commands: &mut Commands, root_entity: Entity, theme: &Theme) {
     info!("showing create dialog");
     commands.entity(root_entity).with_children(|parent| {
@@ -483,6 +683,11 @@ fn setup_create_world_dialog(commands: &mut Commands, root_entity: Entity, theme
                     .with_children(|parent| {
                         parent.spawn(LabelBundle::normal(theme, "Create world"));
                         parent.spawn((WorldNameEdit, TextEditBundle::new(theme, "New world")));
+                        parent.spawn((
+                            AgingButton,
+                            Toggled(false),
+                            TextButtonBundle::normal(theme, "World aging"),
+                        ));
                         parent
                             .spawn(NodeBundle {
                                 style: Style {
@@ -550,6 +755,12 @@ fn setup_join_world_dialog(commands: &mut Commands, root_entity: Entity, theme:
                                     TextEditBundle::new(theme, DEFAULT_PORT.to_string())
                                         .inactive(theme),
                                 ));
+
+                                parent.spawn(LabelBundle::normal(theme, "Password:"));
+                                parent.spawn((
+                                    PasswordEdit,
+                                    TextEditBundle::empty(theme).inactive(theme).masked(),
+                                ));
                             });
 
                         parent
@@ -573,18 +784,70 @@ fn setup_join_world_dialog(commands: &mut Commands, root_entity: Entity, theme:
     });
 }
 
+fn setup_import_world_dialog(
+    commands: &mut Commands,
+    root_entity: Entity,
+    theme: &Theme,
+    game_paths: &GamePaths,
+) {
+    info!("showing import dialog");
+    let mut default_path = game_paths.archives.join("world");
+    default_path.set_extension(ARCHIVE_EXTENSION);
+    commands.entity(root_entity).with_children(|parent| {
+        parent
+            .spawn(DialogBundle::new(theme))
+            .with_children(|parent| {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            padding: theme.padding.normal,
+                            row_gap: theme.gap.normal,
+                            ..Default::default()
+                        },
+                        background_color: theme.panel_color.into(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(LabelBundle::normal(theme, "Import world"));
+                        parent.spawn((
+                            ArchivePathEdit,
+                            TextEditBundle::new(theme, default_path.to_string_lossy()),
+                        ));
+                        parent
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    column_gap: theme.gap.normal,
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            })
+                            .with_children(|parent| {
+                                for button in ImportDialogButton::iter() {
+                                    parent.spawn((
+                                        button,
+                                        TextButtonBundle::normal(theme, button.to_string()),
+                                    ));
+                                }
+                            });
+                    });
+            });
+    });
+}
+
 #[derive(Component, EnumIter, Clone, Copy, Display)]
 enum WorldButton {
     Play,
     Host,
+    Export,
     Remove,
 }
 
-#[derive(Component, EnumIter, Clone, Copy, Display, PartialEq)]
-enum RemoveDialogButton {
-    Remove,
-    Cancel,
-}
+/// Node with all worlds.
+#[derive(Component)]
+struct WorldList;
 
 /// Associated world node entities.
 #[derive(Clone, Component, Copy)]
@@ -597,6 +860,7 @@ struct WorldNode {
 enum WorldBrowserButton {
     Create,
     Join,
+    Import,
 }
 
 #[derive(Component, EnumIter, Clone, Copy, Display, PartialEq)]
@@ -608,20 +872,51 @@ enum CreateDialogButton {
 #[derive(Component)]
 struct WorldNameEdit;
 
+#[derive(Component)]
+struct AgingButton;
+
 #[derive(Component)]
 struct PortEdit;
 
 #[derive(Component)]
 struct IpEdit;
 
+#[derive(Component)]
+struct ServerNameEdit;
+
+#[derive(Component)]
+struct PasswordEdit;
+
+/// Passphrase for world save encryption.
+#[derive(Component)]
+struct PassphraseEdit;
+
+#[derive(Component)]
+struct MaxPlayersEdit;
+
+#[derive(Component)]
+struct ArchivePathEdit;
+
 #[derive(Component, EnumIter, Clone, Copy, Display, PartialEq)]
 enum HostDialogButton {
     Host,
     Cancel,
 }
 
+#[derive(Component, EnumIter, Clone, Copy, Display, PartialEq)]
+enum PassphraseDialogButton {
+    Load,
+    Cancel,
+}
+
 #[derive(Component, EnumIter, Clone, Copy, Display, PartialEq)]
 enum JoinDialogButton {
     Join,
     Cancel,
 }
+
+#[derive(Component, EnumIter, Clone, Copy, Display, PartialEq)]
+enum ImportDialogButton {
+    Import,
+    Cancel,
+}
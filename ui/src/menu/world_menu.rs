@@ -1,17 +1,22 @@
 use std::{fmt::Display, mem};
 
 use bevy::prelude::*;
+use bevy_replicon::prelude::*;
 use bevy_simple_text_input::TextInputValue;
 use strum::{Display, EnumIter, IntoEnumIterator};
 
 use project_harmonia_base::{
-    core::GameState,
+    core::{
+        transition::{Transition, TransitionRequest},
+        GameState,
+    },
     game_world::{
         actor::SelectedActor,
-        city::{ActiveCity, City, CityBundle},
-        family::{Family, FamilyDelete, FamilyMembers},
+        city::{lot::community_lot::ServiceCoverage, ActiveCity, City, CityBundle},
+        family::{Family, FamilyClaim, FamilyDelete, FamilyMembers, FamilyOwner},
         WorldName, WorldState,
     },
+    message::Message,
 };
 use project_harmonia_widgets::{
     button::{ExclusiveButton, TabContent, TextButtonBundle, Toggled},
@@ -43,7 +48,12 @@ impl Plugin for WorldMenuPlugin {
             )
             .add_systems(
                 PostUpdate,
-                (Self::create_family_nodes, Self::create_city_nodes)
+                (
+                    Self::create_family_nodes,
+                    Self::create_city_nodes,
+                    Self::show_coverage,
+                )
+                    .chain()
                     .run_if(in_state(WorldState::World)),
             );
     }
@@ -199,14 +209,47 @@ impl WorldMenuPlugin {
         }
     }
 
+    /// Lists how many residential lots each service covers under the city name.
+    fn show_coverage(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        coverage: ServiceCoverage,
+        infos: Query<(Entity, &Parent), Added<WorldEntityInfo>>,
+        nodes: Query<&WorldEntity>,
+        cities: Query<(), With<City>>,
+    ) {
+        for (info_entity, parent) in &infos {
+            let world_entity = nodes.get(**parent).expect("info should belong to a node");
+            if cities.get(world_entity.0).is_err() {
+                continue;
+            }
+
+            let (services, residential) = coverage.city_coverage(world_entity.0);
+            commands.entity(info_entity).with_children(|parent| {
+                if services.is_empty() {
+                    parent.spawn(LabelBundle::normal(&theme, "No services"));
+                }
+                for (name, covered) in services {
+                    parent.spawn(LabelBundle::normal(
+                        &theme,
+                        format!("{name}: {covered}/{residential} lots"),
+                    ));
+                }
+            });
+        }
+    }
+
     fn handle_family_clicks(
         mut commands: Commands,
         mut delete_events: EventWriter<FamilyDelete>,
+        mut claim_events: EventWriter<FamilyClaim>,
+        mut message_events: EventWriter<Message>,
         mut click_events: EventReader<Click>,
-        mut world_state: ResMut<NextState<WorldState>>,
+        mut transition_events: EventWriter<TransitionRequest>,
+        client: Res<RepliconClient>,
         buttons: Query<(&WorldEntityNode, &FamilyButton)>,
         nodes: Query<&WorldEntity>,
-        families: Query<&FamilyMembers>,
+        families: Query<(&FamilyMembers, Option<&FamilyOwner>)>,
     ) {
         for (entity_node, family_button) in
             buttons.iter_many(click_events.read().map(|event| event.0))
@@ -216,16 +259,25 @@ impl WorldMenuPlugin {
                 .expect("family button should reference world entity node");
             match family_button {
                 FamilyButton::Play => {
-                    let members = families
+                    let (members, owner) = families
                         .get(world_entity.0)
                         .expect("world entity node should reference a family");
+                    if owner.is_some_and(|owner| !owner.is_local(&client)) {
+                        message_events.send(Message(
+                            "This family is controlled by another player".to_string(),
+                        ));
+                        continue;
+                    }
                     let actor_entity = *members
                         .first()
                         .expect("family always have at least one member");
 
                     info!("starting playing for family `{:?}`", world_entity.0);
+                    claim_events.send(FamilyClaim(world_entity.0));
                     commands.entity(actor_entity).insert(SelectedActor);
-                    world_state.set(WorldState::Family);
+                    transition_events.send(TransitionRequest::new(Transition::World(
+                        WorldState::Family,
+                    )));
                 }
                 FamilyButton::Delete => {
                     info!("deleting family `{:?}`", world_entity.0);
@@ -238,7 +290,7 @@ impl WorldMenuPlugin {
     fn handle_city_clicks(
         mut commands: Commands,
         mut click_events: EventReader<Click>,
-        mut world_state: ResMut<NextState<WorldState>>,
+        mut transition_events: EventWriter<TransitionRequest>,
         buttons: Query<(&WorldEntityNode, &CityButton)>,
         nodes: Query<&WorldEntity>,
     ) {
@@ -253,7 +305,15 @@ impl WorldMenuPlugin {
                 CityButton::Edit => {
                     info!("starting editing city `{:?}`", world_entity.0);
                     commands.entity(world_entity.0).insert(ActiveCity);
-                    world_state.set(WorldState::City);
+                    transition_events
+                        .send(TransitionRequest::new(Transition::World(WorldState::City)));
+                }
+                CityButton::Spectate => {
+                    info!("starting spectating city `{:?}`", world_entity.0);
+                    commands.entity(world_entity.0).insert(ActiveCity);
+                    transition_events.send(TransitionRequest::new(Transition::World(
+                        WorldState::Spectator,
+                    )));
                 }
                 CityButton::Delete => {
                     info!("deleting city `{:?}`", world_entity.0);
@@ -265,18 +325,18 @@ impl WorldMenuPlugin {
 
     fn handle_main_menu_clicks(
         mut click_events: EventReader<Click>,
-        mut game_state: ResMut<NextState<GameState>>,
+        mut transition_events: EventWriter<TransitionRequest>,
         buttons: Query<(), With<MainMenuButton>>,
     ) {
         for _ in buttons.iter_many(click_events.read().map(|event| event.0)) {
-            game_state.set(GameState::Menu);
+            transition_events.send(TransitionRequest::new(Transition::Game(GameState::Menu)));
         }
     }
 
     fn handle_create_clicks(
         mut commands: Commands,
         mut click_events: EventReader<Click>,
-        mut world_state: ResMut<NextState<WorldState>>,
+        mut transition_events: EventWriter<TransitionRequest>,
         theme: Res<Theme>,
         buttons: Query<(), With<CreateEntityButton>>,
         tabs: Query<(&Toggled, &WorldTab)>,
@@ -289,7 +349,11 @@ impl WorldMenuPlugin {
                 .expect("one tab should always be active");
 
             match current_tab {
-                WorldTab::Families => world_state.set(WorldState::FamilyEditor),
+                WorldTab::Families => {
+                    transition_events.send(TransitionRequest::new(Transition::World(
+                        WorldState::FamilyEditor,
+                    )));
+                }
                 WorldTab::Cities => {
                     setup_create_city_dialog(&mut commands, roots.single(), &theme);
                 }
@@ -357,14 +421,19 @@ fn setup_entity_node<E>(
             let node_entity = parent.parent_entity();
 
             parent
-                .spawn(NodeBundle {
-                    style: Style {
-                        width: Val::Percent(100.0),
-                        height: Val::Percent(100.0),
+                .spawn((
+                    WorldEntityInfo,
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(100.0),
+                            flex_direction: FlexDirection::Column,
+                            row_gap: theme.gap.normal,
+                            ..Default::default()
+                        },
                         ..Default::default()
                     },
-                    ..Default::default()
-                })
+                ))
                 .with_children(|parent| {
                     parent.spawn(LabelBundle::large(theme, label));
                 });
@@ -447,6 +516,7 @@ enum FamilyButton {
 #[derive(Component, EnumIter, Clone, Copy, Display)]
 enum CityButton {
     Edit,
+    Spectate,
     Delete,
 }
 
@@ -454,6 +524,10 @@ enum CityButton {
 #[derive(Component)]
 struct WorldEntity(Entity);
 
+/// Column with the name and details of a family or city.
+#[derive(Component)]
+struct WorldEntityInfo;
+
 /// References family node for [`FamilyButton`] or city node for [`CityButton`].
 #[derive(Component)]
 struct WorldEntityNode(Entity);
@@ -0,0 +1,133 @@
+use bevy::{prelude::*, ui::FocusPolicy};
+
+use project_harmonia_base::asset::info::preload::{preload_finished, PreloadProgress};
+use project_harmonia_widgets::{
+    label::LabelBundle,
+    progress_bar::{ProgressBar, ProgressBarBundle},
+    theme::Theme,
+};
+
+/// Covers the menu with per-pack loading progress until all metadata is preloaded.
+pub(super) struct PreloadSplashPlugin;
+
+impl Plugin for PreloadSplashPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                Self::show
+                    .run_if(not(preload_finished))
+                    .run_if(not(any_with_component::<PreloadSplash>)),
+                Self::update
+                    .run_if(resource_changed::<PreloadProgress>)
+                    .run_if(any_with_component::<PreloadSplash>),
+                Self::hide
+                    .run_if(preload_finished)
+                    .run_if(any_with_component::<PreloadSplash>),
+            ),
+        );
+    }
+}
+
+impl PreloadSplashPlugin {
+    fn show(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        // Menu root is spawned after the first state transition.
+        // During transitions the previous root may still exist, any root works
+        // since the splash is shown again if its root gets despawned.
+        let Some(root_entity) = roots.iter().next() else {
+            return;
+        };
+
+        debug!("showing preload splash");
+        commands.entity(root_entity).with_children(|parent| {
+            parent
+                .spawn((
+                    PreloadSplash,
+                    Interaction::default(),
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            flex_direction: FlexDirection::Column,
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(100.0),
+                            align_items: AlignItems::Center,
+                            justify_content: JustifyContent::Center,
+                            row_gap: theme.gap.normal,
+                            ..Default::default()
+                        },
+                        // Opaque and blocking to prevent interaction with the menu during loading.
+                        focus_policy: FocusPolicy::Block,
+                        background_color: theme.background_color.into(),
+                        z_index: ZIndex::Global(1),
+                        ..Default::default()
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn(LabelBundle::large(&theme, "Loading assets"));
+                    parent.spawn((
+                        PackList,
+                        NodeBundle {
+                            style: Style {
+                                display: Display::Grid,
+                                grid_template_columns: vec![GridTrack::auto(); 2],
+                                column_gap: theme.gap.normal,
+                                row_gap: theme.gap.normal,
+                                align_items: AlignItems::Center,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                    ));
+                });
+        });
+    }
+
+    fn update(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        progress: Res<PreloadProgress>,
+        lists: Query<Entity, With<PackList>>,
+        mut bars: Query<(&PackBar, &mut ProgressBar)>,
+    ) {
+        for (pack, pack_progress) in progress.packs() {
+            let value = pack_progress.fraction() * 100.0;
+            if let Some((_, mut bar)) = bars.iter_mut().find(|(bar, _)| bar.0 == pack) {
+                bar.0 = value;
+            } else {
+                debug!("adding preload progress for '{pack}'");
+                commands.entity(lists.single()).with_children(|parent| {
+                    parent.spawn(LabelBundle::normal(&theme, pack));
+                    parent
+                        .spawn((
+                            PackBar(pack.to_string()),
+                            ProgressBarBundle::new(&theme, value),
+                        ))
+                        .insert(Style {
+                            width: Val::Px(200.0),
+                            height: Val::Px(8.0),
+                            ..Default::default()
+                        });
+                });
+            }
+        }
+    }
+
+    fn hide(mut commands: Commands, splashes: Query<Entity, With<PreloadSplash>>) {
+        debug!("hiding preload splash");
+        commands.entity(splashes.single()).despawn_recursive();
+    }
+}
+
+#[derive(Component)]
+struct PreloadSplash;
+
+#[derive(Component)]
+struct PackList;
+
+/// Progress bar of an asset pack with its name.
+#[derive(Component)]
+struct PackBar(String);
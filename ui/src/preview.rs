@@ -11,7 +11,7 @@ use bevy::{
     },
 };
 
-use project_harmonia_base::asset::info::object_info::ObjectInfo;
+use project_harmonia_base::asset::info::object_info::{ObjectInfo, PreviewInfo};
 
 pub(super) struct PreviewPlugin;
 
@@ -23,9 +23,11 @@ impl Plugin for PreviewPlugin {
             .add_systems(
                 Update,
                 (
+                    Self::preload_scenes,
                     Self::wait_for_request.run_if(in_state(PreviewState::Inactive)),
                     Self::wait_for_loading.run_if(in_state(PreviewState::LoadingAsset)),
                     Self::render_preview.run_if(in_state(PreviewState::Rendering)),
+                    Self::reset_objects.run_if(on_event::<AssetEvent<ObjectInfo>>()),
                 ),
             );
     }
@@ -33,14 +35,46 @@ impl Plugin for PreviewPlugin {
 
 impl PreviewPlugin {
     fn setup(mut commands: Commands) {
-        commands.spawn(PreviewCameraBundle::default());
-        commands.spawn((
-            PREVIEW_RENDER_LAYER,
-            DirectionalLightBundle {
-                transform: Transform::from_xyz(4.0, 7.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
-                ..Default::default()
-            },
-        ));
+        commands
+            .spawn(PreviewCameraBundle::default())
+            .with_children(|parent| {
+                // Lights are relative to the camera, which looks along -Z.
+                parent.spawn(PreviewLightBundle::new(
+                    Vec3::new(-1.0, 1.5, 1.0),
+                    light_consts::lux::AMBIENT_DAYLIGHT,
+                ));
+                parent.spawn(PreviewLightBundle::new(
+                    Vec3::new(1.0, 0.5, 1.0),
+                    light_consts::lux::AMBIENT_DAYLIGHT * 0.4,
+                ));
+                parent.spawn(PreviewLightBundle::new(
+                    Vec3::new(0.0, 1.0, -1.0),
+                    light_consts::lux::AMBIENT_DAYLIGHT * 0.6,
+                ));
+            });
+    }
+
+    /// Starts loading scenes for all requested object previews at once.
+    ///
+    /// Previews are rendered one by one, but their assets are loaded in parallel.
+    fn preload_scenes(
+        mut commands: Commands,
+        asset_server: Res<AssetServer>,
+        objects_info: Res<Assets<ObjectInfo>>,
+        previews: Query<(Entity, &Preview), (Without<PreviewProcessed>, Without<PreloadedScene>)>,
+    ) {
+        for (preview_entity, &preview) in &previews {
+            let Preview::Object(id) = preview else {
+                continue;
+            };
+            let Some(info) = objects_info.get(id) else {
+                continue;
+            };
+
+            commands
+                .entity(preview_entity)
+                .insert(PreloadedScene(asset_server.load(info.scene.clone())));
+        }
     }
 
     fn wait_for_request(
@@ -48,12 +82,28 @@ impl PreviewPlugin {
         mut preview_state: ResMut<NextState<PreviewState>>,
         asset_server: Res<AssetServer>,
         objects_info: Res<Assets<ObjectInfo>>,
-        previews: Query<(Entity, &Preview, Has<CalculatedClip>), Without<PreviewProcessed>>,
+        previews: Query<
+            (
+                Entity,
+                &Preview,
+                Option<&PreloadedScene>,
+                Has<CalculatedClip>,
+            ),
+            Without<PreviewProcessed>,
+        >,
         actors: Query<&Handle<Scene>>,
-        preview_cameras: Query<Entity, With<PreviewCamera>>,
+        mut preview_cameras: Query<(Entity, &mut Camera), With<PreviewCamera>>,
     ) {
-        if let Some((preview_entity, &preview, ..)) = previews.iter().find(|&(.., c)| !c) {
-            let (translation, scene_handle) = match preview {
+        // Prefer previews whose assets are already loaded to avoid waiting for others.
+        let preview = previews
+            .iter()
+            .filter(|&(.., clipped)| !clipped)
+            .find(|(_, _, scene, _)| {
+                scene.is_some_and(|scene| asset_server.is_loaded_with_dependencies(&scene.0))
+            })
+            .or_else(|| previews.iter().find(|&(.., clipped)| !clipped));
+        if let Some((preview_entity, &preview, preloaded_scene, _)) = preview {
+            let (translation, preview_info, scene_handle) = match preview {
                 Preview::Actor(entity) => {
                     debug!("generating preview for actor `{entity}`");
 
@@ -61,29 +111,39 @@ impl PreviewPlugin {
                         .get(entity)
                         .expect("actor for preview should have a scene handle");
 
-                    (Vec3::new(0.0, -1.67, -0.42), scene_handle.clone())
+                    (
+                        Vec3::new(0.0, -1.67, -0.42),
+                        PreviewInfo::default(),
+                        scene_handle.clone(),
+                    )
                 }
                 Preview::Object(id) => {
                     let info = objects_info.get(id).expect("info should be preloaded");
 
                     debug!("generating preview for object '{:?}'", info.scene);
 
-                    let scene_handle = asset_server.load(info.scene.clone());
+                    let scene_handle = preloaded_scene
+                        .map(|scene| scene.0.clone())
+                        .unwrap_or_else(|| asset_server.load(info.scene.clone()));
 
-                    (info.preview_translation, scene_handle)
+                    (info.preview_translation, info.preview, scene_handle)
                 }
             };
 
-            commands.entity(preview_entity).insert(PreviewProcessed);
+            let (camera_entity, mut camera) = preview_cameras.single_mut();
+            camera.clear_color = ClearColorConfig::Custom(preview_info.background);
+
             commands
-                .entity(preview_cameras.single())
-                .with_children(|parent| {
-                    parent.spawn(PreviewSceneBundle::new(
-                        translation,
-                        scene_handle,
-                        preview_entity,
-                    ));
-                });
+                .entity(preview_entity)
+                .insert(PreviewProcessed)
+                .remove::<PreloadedScene>();
+            commands.entity(camera_entity).with_children(|parent| {
+                parent.spawn(PreviewSceneBundle::new(
+                    scene_transform(translation, preview_info),
+                    scene_handle,
+                    preview_entity,
+                ));
+            });
 
             preview_state.set(PreviewState::LoadingAsset);
         }
@@ -150,6 +210,28 @@ impl PreviewPlugin {
         preview_state.set(PreviewState::Inactive);
     }
 
+    /// Requests new previews for objects whose metadata was reloaded.
+    fn reset_objects(
+        mut commands: Commands,
+        mut asset_events: EventReader<AssetEvent<ObjectInfo>>,
+        previews: Query<(Entity, &Preview)>,
+    ) {
+        for event in asset_events.read() {
+            let AssetEvent::Modified { id } = *event else {
+                continue;
+            };
+
+            for (entity, &preview) in &previews {
+                if preview == Preview::Object(id) {
+                    debug!("resetting preview for `{entity}`");
+                    commands
+                        .entity(entity)
+                        .remove::<(PreviewProcessed, PreloadedScene)>();
+                }
+            }
+        }
+    }
+
     fn despawn_scene(
         mut commands: Commands,
         mut preview_cameras: Query<&mut Camera, With<PreviewCamera>>,
@@ -179,6 +261,19 @@ impl PreviewPlugin {
 
 const PREVIEW_RENDER_LAYER: RenderLayers = RenderLayers::layer(1);
 
+/// Places the scene in front of the preview camera according to the framing settings.
+fn scene_transform(mut translation: Vec3, preview_info: PreviewInfo) -> Transform {
+    if let Some(distance) = preview_info.distance {
+        translation.z = -distance;
+    }
+
+    // Tilt the scene around the point the camera looks at.
+    let pivot = Vec3::Z * translation.z;
+    let tilt = Quat::from_rotation_x(preview_info.angle.to_radians());
+    Transform::from_translation(pivot + tilt * (translation - pivot))
+        .with_rotation(tilt * Quat::from_rotation_y(PI)) // Rotate towards camera.
+}
+
 #[derive(Bundle)]
 struct PreviewCameraBundle {
     name: Name,
@@ -209,6 +304,33 @@ impl Default for PreviewCameraBundle {
     }
 }
 
+/// Light that affects only preview scenes.
+///
+/// Makes previews independent from the world lighting.
+#[derive(Bundle)]
+struct PreviewLightBundle {
+    name: Name,
+    render_layer: RenderLayers,
+    light_bundle: DirectionalLightBundle,
+}
+
+impl PreviewLightBundle {
+    fn new(direction: Vec3, illuminance: f32) -> Self {
+        Self {
+            name: "Preview light".into(),
+            render_layer: PREVIEW_RENDER_LAYER,
+            light_bundle: DirectionalLightBundle {
+                directional_light: DirectionalLight {
+                    illuminance,
+                    ..Default::default()
+                },
+                transform: Transform::from_translation(direction).looking_at(Vec3::ZERO, Vec3::Y),
+                ..Default::default()
+            },
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, States)]
 enum PreviewState {
     #[default]
@@ -226,7 +348,7 @@ struct PreviewCamera;
 /// Generated image handle will be written to the image handle on this entity.
 /// Preview generation happens only if UI element entity is visible.
 /// Processed entities will be marked with [`PreviewProcessed`].
-#[derive(Clone, Component, Copy)]
+#[derive(Clone, Component, Copy, PartialEq)]
 pub(crate) enum Preview {
     Actor(Entity),
     Object(AssetId<ObjectInfo>),
@@ -236,6 +358,10 @@ pub(crate) enum Preview {
 #[derive(Component)]
 pub(super) struct PreviewProcessed;
 
+/// Scene of an object preview that is loaded before the preview is rendered.
+#[derive(Component)]
+struct PreloadedScene(Handle<Scene>);
+
 /// Scene that used for preview generation.
 #[derive(Bundle)]
 struct PreviewSceneBundle {
@@ -245,14 +371,13 @@ struct PreviewSceneBundle {
 }
 
 impl PreviewSceneBundle {
-    fn new(translation: Vec3, scene_handle: Handle<Scene>, preview_entity: Entity) -> Self {
+    fn new(transform: Transform, scene_handle: Handle<Scene>, preview_entity: Entity) -> Self {
         Self {
             name: "Preview scene".into(),
             preview_target: PreviewTarget(preview_entity),
             scene_bundle: SceneBundle {
                 scene: scene_handle,
-                transform: Transform::from_translation(translation)
-                    .with_rotation(Quat::from_rotation_y(PI)), // Rotate towards camera.
+                transform,
                 ..Default::default()
             },
         }
@@ -0,0 +1,237 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Mutex,
+    },
+};
+
+use anyhow::{anyhow, Context, Result};
+use bevy::{prelude::*, render::view::screenshot::ScreenshotManager, window::PrimaryWindow};
+
+use crate::preview::Preview;
+use project_harmonia_base::asset::info::{object_info::ObjectInfo, preload::preload_finished};
+
+/// Size of exported previews in pixels.
+///
+/// The primary window should have the same size since previews are captured from it.
+pub const EXPORT_SIZE: f32 = 256.0;
+
+/// Renders previews for all objects from an asset pack into PNG files and exits.
+///
+/// Not a part of [`UiPlugins`](crate::UiPlugins), intended to be added only for mod packaging.
+/// Exits with an error if the pack has no objects or any preview can't be saved.
+pub struct PreviewExportPlugin {
+    /// Name of the asset pack directory.
+    pub pack: String,
+
+    /// Directory for the rendered files.
+    pub output_dir: PathBuf,
+}
+
+impl Plugin for PreviewExportPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = mpsc::channel();
+        app.insert_resource(PreviewExport {
+            pack: self.pack.clone(),
+            output_dir: self.output_dir.clone(),
+            pending: None,
+            sender: Mutex::new(sender),
+            receiver: Mutex::new(receiver),
+        })
+        .add_systems(
+            Update,
+            (
+                Self::collect.run_if(run_once()),
+                Self::capture,
+                Self::finish,
+                Self::spawn_next,
+            )
+                .chain()
+                .run_if(preload_finished),
+        );
+    }
+}
+
+impl PreviewExportPlugin {
+    fn collect(
+        mut commands: Commands,
+        mut exit_events: EventWriter<AppExit>,
+        asset_server: Res<AssetServer>,
+        export: Res<PreviewExport>,
+        objects_info: Res<Assets<ObjectInfo>>,
+    ) {
+        let mut queue: Vec<_> = objects_info
+            .ids()
+            .filter_map(|id| {
+                let path = asset_server.get_path(id)?;
+                let relative = path.path().strip_prefix(&export.pack).ok()?;
+                // Strip both parts of the `.object.ron` extension.
+                let file_name = relative.with_extension("").with_extension("png");
+                Some((id, export.output_dir.join(file_name)))
+            })
+            .collect();
+
+        if queue.is_empty() {
+            error!("asset pack '{}' doesn't contain any objects", export.pack);
+            exit_events.send(AppExit::error());
+            return;
+        }
+
+        info!("exporting {} previews", queue.len());
+        queue.sort_by(|(_, a), (_, b)| b.cmp(a));
+        commands.insert_resource(ExportQueue(queue));
+    }
+
+    /// Displays the preview once it's rendered and requests a screenshot of it.
+    fn capture(
+        mut screenshot_manager: ResMut<ScreenshotManager>,
+        mut export: ResMut<PreviewExport>,
+        windows: Query<Entity, With<PrimaryWindow>>,
+        mut nodes: Query<(&Handle<Image>, &mut UiImage, &mut ExportNode)>,
+    ) {
+        let Ok((image_handle, mut ui_image, mut node)) = nodes.get_single_mut() else {
+            return;
+        };
+        if *image_handle == Handle::default() || node.requested {
+            return;
+        }
+
+        ui_image.texture = image_handle.clone();
+
+        let path = node.path.clone();
+        debug!("capturing preview into {path:?}");
+        // Wrap into a mutex since the callback should be `Sync`.
+        let sender = Mutex::new(
+            export
+                .sender
+                .lock()
+                .expect("sender shouldn't be poisoned")
+                .clone(),
+        );
+        screenshot_manager
+            .take_screenshot(windows.single(), move |image| {
+                let result = save(image, &path);
+                // Ignore the error since the app could already exit.
+                let _ = sender
+                    .lock()
+                    .expect("sender shouldn't be poisoned")
+                    .send(result);
+            })
+            .expect("screenshot shouldn't be requested twice in a frame");
+
+        node.requested = true;
+        export.pending = Some(node.path.clone());
+    }
+
+    fn finish(
+        mut commands: Commands,
+        mut exit_events: EventWriter<AppExit>,
+        mut export: ResMut<PreviewExport>,
+        nodes: Query<Entity, With<ExportNode>>,
+    ) {
+        if export.pending.is_none() {
+            return;
+        }
+
+        let result = export
+            .receiver
+            .lock()
+            .expect("receiver shouldn't be poisoned")
+            .try_recv();
+        let Ok(result) = result else {
+            return;
+        };
+
+        let path = export.pending.take().expect("preview should be pending");
+        if let Err(e) = result {
+            error!("unable to export {path:?}: {e:#}");
+            exit_events.send(AppExit::error());
+            return;
+        }
+
+        info!("exported {path:?}");
+        commands.entity(nodes.single()).despawn_recursive();
+    }
+
+    fn spawn_next(
+        mut commands: Commands,
+        mut exit_events: EventWriter<AppExit>,
+        queue: Option<ResMut<ExportQueue>>,
+        nodes: Query<(), With<ExportNode>>,
+    ) {
+        let Some(mut queue) = queue else {
+            return;
+        };
+        if !nodes.is_empty() {
+            return;
+        }
+
+        let Some((id, path)) = queue.0.pop() else {
+            info!("all previews exported");
+            exit_events.send(AppExit::Success);
+            return;
+        };
+
+        debug!("requesting preview for {path:?}");
+        commands.spawn((
+            ExportNode {
+                path,
+                requested: false,
+            },
+            Preview::Object(id),
+            // Written by the preview plugin.
+            Handle::<Image>::default(),
+            // Should be drawn above menus.
+            ZIndex::Global(i32::MAX),
+            ImageBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(EXPORT_SIZE),
+                    height: Val::Px(EXPORT_SIZE),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ));
+    }
+}
+
+fn save(image: Image, path: &Path) -> Result<()> {
+    let parent = path.parent().expect("previews should have a parent dir");
+    fs::create_dir_all(parent).with_context(|| format!("unable to create {parent:?}"))?;
+
+    let image = image
+        .try_into_dynamic()
+        .map_err(|e| anyhow!("unable to convert screenshot: {e:?}"))?;
+    image
+        .to_rgb8()
+        .save(path)
+        .with_context(|| format!("unable to write {path:?}"))
+}
+
+#[derive(Resource)]
+struct PreviewExport {
+    pack: String,
+    output_dir: PathBuf,
+
+    /// Path of the preview whose screenshot is being saved.
+    pending: Option<PathBuf>,
+
+    sender: Mutex<Sender<Result<()>>>,
+    receiver: Mutex<Receiver<Result<()>>>,
+}
+
+/// Objects left for export with their output paths.
+///
+/// Sorted in reverse order to pop them in alphabetical order.
+#[derive(Resource)]
+struct ExportQueue(Vec<(AssetId<ObjectInfo>, PathBuf)>);
+
+/// UI node that displays the preview for capturing.
+#[derive(Component)]
+struct ExportNode {
+    path: PathBuf,
+    requested: bool,
+}
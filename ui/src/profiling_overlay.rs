@@ -0,0 +1,189 @@
+use std::fmt::Write;
+
+use bevy::{
+    diagnostic::{DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+};
+use leafwing_input_manager::common_conditions::action_just_pressed;
+use strum::IntoEnumIterator;
+
+use project_harmonia_base::{
+    asset::lifetime::{
+        IMAGES_MEMORY, LOADED_IMAGES, LOADED_MESHES, LOADED_SCENES, RETAINED_ASSETS,
+    },
+    game_world::navigation::NAVMESH_BUILD_TIME,
+    profiling::ScheduleMark,
+    settings::Action,
+};
+use project_harmonia_widgets::{label::LabelBundle, theme::Theme};
+
+/// Displays frame time, schedule timings and asset counters.
+pub(super) struct ProfilingOverlayPlugin;
+
+impl Plugin for ProfilingOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                Self::toggle.run_if(action_just_pressed(Action::ToggleProfiling)),
+                (Self::update_text, Self::update_graph).run_if(any_with_component::<Overlay>),
+            ),
+        );
+    }
+}
+
+/// Number of frames displayed on the graph.
+const GRAPH_BARS: usize = 120;
+
+/// Frame time in milliseconds that corresponds to the full graph height.
+const GRAPH_MAX_FRAME_TIME: f64 = 1000.0 / 30.0;
+
+impl ProfilingOverlayPlugin {
+    fn toggle(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        overlays: Query<Entity, With<Overlay>>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        if let Ok(entity) = overlays.get_single() {
+            debug!("hiding profiling overlay");
+            commands.entity(entity).despawn_recursive();
+            return;
+        }
+
+        debug!("showing profiling overlay");
+        commands.entity(roots.single()).with_children(|parent| {
+            parent
+                .spawn((
+                    Overlay,
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            top: Val::Px(0.0),
+                            right: Val::Px(0.0),
+                            width: Val::Px(300.0),
+                            flex_direction: FlexDirection::Column,
+                            row_gap: theme.gap.normal,
+                            margin: theme.padding.global,
+                            padding: theme.padding.normal,
+                            ..Default::default()
+                        },
+                        background_color: theme.panel_color.into(),
+                        ..Default::default()
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn((OverlayText, LabelBundle::normal(&theme, "")));
+                    parent
+                        .spawn((
+                            Graph,
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Percent(100.0),
+                                    height: Val::Px(60.0),
+                                    align_items: AlignItems::End,
+                                    ..Default::default()
+                                },
+                                background_color: theme.progress_bar.background_color.into(),
+                                ..Default::default()
+                            },
+                        ))
+                        .with_children(|parent| {
+                            for _ in 0..GRAPH_BARS {
+                                parent.spawn(NodeBundle {
+                                    style: Style {
+                                        width: Val::Percent(100.0 / GRAPH_BARS as f32),
+                                        height: Val::Percent(0.0),
+                                        ..Default::default()
+                                    },
+                                    background_color: theme.progress_bar.fill_color.into(),
+                                    ..Default::default()
+                                });
+                            }
+                        });
+                });
+        });
+    }
+
+    fn update_text(
+        diagnostics: Res<DiagnosticsStore>,
+        mut texts: Query<&mut Text, With<OverlayText>>,
+    ) {
+        let smoothed = |path| {
+            diagnostics
+                .get(path)
+                .and_then(|diagnostic| diagnostic.smoothed())
+                .unwrap_or_default()
+        };
+
+        let mut content = format!(
+            "FPS: {:.0}\nFrame time: {:.2} ms\nEntities: {:.0}",
+            smoothed(&FrameTimeDiagnosticsPlugin::FPS),
+            smoothed(&FrameTimeDiagnosticsPlugin::FRAME_TIME),
+            smoothed(&EntityCountDiagnosticsPlugin::ENTITY_COUNT),
+        );
+        for mark in ScheduleMark::iter() {
+            write!(
+                content,
+                "\n{mark:?}: {:.2} ms",
+                smoothed(&mark.diagnostic_path())
+            )
+            .unwrap();
+        }
+        write!(
+            content,
+            "\nNavmesh build: {:.2} ms",
+            smoothed(&NAVMESH_BUILD_TIME)
+        )
+        .unwrap();
+        write!(
+            content,
+            "\nScenes: {:.0}\nImages: {:.0} ({:.0} MiB)\nMeshes: {:.0}\nRetained: {:.0}",
+            smoothed(&LOADED_SCENES),
+            smoothed(&LOADED_IMAGES),
+            smoothed(&IMAGES_MEMORY),
+            smoothed(&LOADED_MESHES),
+            smoothed(&RETAINED_ASSETS),
+        )
+        .unwrap();
+
+        let mut text = texts.single_mut();
+        text.sections[0].value = content;
+    }
+
+    fn update_graph(
+        diagnostics: Res<DiagnosticsStore>,
+        graphs: Query<&Children, With<Graph>>,
+        mut bars: Query<&mut Style>,
+    ) {
+        let Some(frame_time) = diagnostics.get(&FrameTimeDiagnosticsPlugin::FRAME_TIME) else {
+            return;
+        };
+
+        // Align the latest value to the right edge.
+        let skip = GRAPH_BARS.saturating_sub(frame_time.history_len());
+        let mut values = frame_time.values().rev().take(GRAPH_BARS).rev();
+        let mut iter = bars.iter_many_mut(graphs.single());
+        let mut index = 0;
+        while let Some(mut style) = iter.fetch_next() {
+            let value = if index < skip {
+                0.0
+            } else {
+                values.next().copied().unwrap_or_default()
+            };
+            let height = (value / GRAPH_MAX_FRAME_TIME).min(1.0) * 100.0;
+            style.height = Val::Percent(height as f32);
+            index += 1;
+        }
+    }
+}
+
+#[derive(Component)]
+struct Overlay;
+
+#[derive(Component)]
+struct OverlayText;
+
+/// Node with frame time bars as children.
+#[derive(Component)]
+struct Graph;
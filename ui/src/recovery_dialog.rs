@@ -0,0 +1,78 @@
+use std::fmt::Write;
+
+use bevy::prelude::*;
+
+use project_harmonia_base::game_world::recovery::{LoadRecovery, RecoveryPolicy, RecoveryResolve};
+use project_harmonia_widgets::{
+    dialog::{ConfirmationDialogBundle, DialogResponse, DialogResult},
+    theme::Theme,
+};
+
+/// Asks the player what to do with a partially loaded world.
+pub(super) struct RecoveryDialogPlugin;
+
+impl Plugin for RecoveryDialogPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RecoveryPolicy::Ask).add_systems(
+            Update,
+            (
+                Self::show.run_if(resource_added::<LoadRecovery>),
+                Self::handle_results,
+            ),
+        );
+    }
+}
+
+/// Maximum number of issues listed in the dialog.
+const MAX_LISTED: usize = 10;
+
+impl RecoveryDialogPlugin {
+    fn show(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        recovery: Res<LoadRecovery>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        info!("showing recovery dialog");
+        let mut text = "Some entities can't be loaded:\n".to_string();
+        for issue in recovery.issues.iter().take(MAX_LISTED) {
+            writeln!(text, "{}: {}", issue.entity, issue.description).unwrap();
+        }
+        if let Some(remaining) = recovery.issues.len().checked_sub(MAX_LISTED) {
+            if remaining > 0 {
+                writeln!(text, "...and {remaining} more").unwrap();
+            }
+        }
+        text.push_str("Drop them and continue loading?");
+
+        commands.entity(roots.single()).with_children(|parent| {
+            parent.spawn((
+                RecoveryDialog,
+                ConfirmationDialogBundle::new(&theme, text)
+                    .with_accept("Drop & continue")
+                    .with_cancel("Abort"),
+            ));
+        });
+    }
+
+    fn handle_results(
+        mut result_events: EventReader<DialogResult>,
+        mut resolve_events: EventWriter<RecoveryResolve>,
+        dialogs: Query<(), With<RecoveryDialog>>,
+    ) {
+        for event in result_events.read() {
+            if dialogs.get(event.dialog_entity).is_err() {
+                continue;
+            }
+
+            let resolve = match event.response {
+                DialogResponse::Accept => RecoveryResolve::Drop,
+                DialogResponse::Decline | DialogResponse::Cancel => RecoveryResolve::Abort,
+            };
+            resolve_events.send(resolve);
+        }
+    }
+}
+
+#[derive(Component)]
+struct RecoveryDialog;
@@ -0,0 +1,97 @@
+use std::fmt::Write;
+
+use bevy::prelude::*;
+
+use project_harmonia_base::{
+    asset::info::scenario_info::ScenarioInfo,
+    core::{
+        transition::{Transition, TransitionRequest},
+        GameState,
+    },
+    game_world::scenario::{Scenario, ScenarioOutcome},
+};
+use project_harmonia_widgets::{
+    dialog::{ConfirmationDialogBundle, DialogResponse, DialogResult},
+    theme::Theme,
+};
+
+/// Shows the results when the active scenario ends.
+pub(super) struct ScenarioResultsPlugin;
+
+impl Plugin for ScenarioResultsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (Self::show, Self::handle_results).run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+impl ScenarioResultsPlugin {
+    fn show(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        asset_server: Res<AssetServer>,
+        scenarios_info: Res<Assets<ScenarioInfo>>,
+        scenarios: Query<Ref<Scenario>>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        let Ok(scenario) = scenarios.get_single() else {
+            return;
+        };
+        // Don't show results again for loaded worlds.
+        if !scenario.is_changed() || scenario.is_added() {
+            return;
+        }
+        let Some(outcome) = scenario.outcome() else {
+            return;
+        };
+        let Some(info) = asset_server
+            .get_handle(scenario.info_path())
+            .and_then(|handle| scenarios_info.get(&handle))
+        else {
+            return;
+        };
+
+        info!("showing scenario results");
+        let mut text = match outcome {
+            ScenarioOutcome::Won => format!("Scenario \"{}\" completed!\n", info.general.name),
+            ScenarioOutcome::Lost => format!("Scenario \"{}\" failed.\n", info.general.name),
+        };
+        text.push_str("Goals:\n");
+        for goal in &info.goals {
+            writeln!(text, "- {}", goal.description()).unwrap();
+        }
+        text.push_str("Return to the main menu?");
+
+        commands.entity(roots.single()).with_children(|parent| {
+            parent.spawn((
+                ScenarioResultsDialog,
+                ConfirmationDialogBundle::new(&theme, text)
+                    .with_accept("Main menu")
+                    .with_cancel("Keep playing"),
+            ));
+        });
+    }
+
+    fn handle_results(
+        mut result_events: EventReader<DialogResult>,
+        mut transition_events: EventWriter<TransitionRequest>,
+        dialogs: Query<(), With<ScenarioResultsDialog>>,
+    ) {
+        for event in result_events.read() {
+            if dialogs.get(event.dialog_entity).is_err() {
+                continue;
+            }
+
+            if event.response == DialogResponse::Accept {
+                transition_events.send(TransitionRequest::new(Transition::Game(GameState::Menu)));
+            } else {
+                info!("continuing after the scenario end");
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+struct ScenarioResultsDialog;
@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+
+use project_harmonia_base::{
+    core::GameState,
+    game_world::{
+        clock::Season,
+        family::building::decoration::{CleanupOffer, DecorationCleanup},
+    },
+};
+use project_harmonia_widgets::{
+    dialog::{ConfirmationDialogBundle, DialogResponse, DialogResult},
+    theme::Theme,
+};
+
+/// Offers to remove seasonal decorations after their season ends.
+pub(super) struct SeasonCleanupPlugin;
+
+impl Plugin for SeasonCleanupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (Self::show, Self::handle_response).run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+impl SeasonCleanupPlugin {
+    fn show(
+        mut commands: Commands,
+        mut offer_events: EventReader<CleanupOffer>,
+        theme: Res<Theme>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        for event in offer_events.read() {
+            info!("showing `{}` cleanup dialog", event.season);
+            commands.entity(roots.single()).with_children(|parent| {
+                parent.spawn((
+                    SeasonCleanupDialog {
+                        family_entity: event.family_entity,
+                        season: event.season,
+                    },
+                    ConfirmationDialogBundle::new(
+                        &theme,
+                        format!("{} is over. Remove seasonal decorations?", event.season),
+                    )
+                    .with_accept("Remove")
+                    .with_cancel("Keep"),
+                ));
+            });
+        }
+    }
+
+    fn handle_response(
+        mut result_events: EventReader<DialogResult>,
+        mut cleanup_events: EventWriter<DecorationCleanup>,
+        dialogs: Query<&SeasonCleanupDialog>,
+    ) {
+        for event in result_events.read() {
+            let Ok(dialog) = dialogs.get(event.dialog_entity) else {
+                continue;
+            };
+
+            if event.response == DialogResponse::Accept {
+                cleanup_events.send(DecorationCleanup {
+                    family_entity: dialog.family_entity,
+                    season: dialog.season,
+                });
+            } else {
+                info!("keeping `{}` decorations", dialog.season);
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+struct SeasonCleanupDialog {
+    family_entity: Entity,
+    season: Season,
+}
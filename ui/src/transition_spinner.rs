@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+
+use project_harmonia_base::core::transition::ActiveTransition;
+use project_harmonia_widgets::{label::LabelBundle, theme::Theme};
+
+/// Shows a loading label while [`ActiveTransition`] waits for its steps.
+pub(super) struct TransitionSpinnerPlugin;
+
+impl Plugin for TransitionSpinnerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                Self::show
+                    .run_if(resource_exists::<ActiveTransition>)
+                    .run_if(not(any_with_component::<TransitionSpinner>)),
+                Self::animate,
+                Self::hide.run_if(any_with_component::<TransitionSpinner>),
+            ),
+        );
+    }
+}
+
+/// Seconds between adding a dot to the label.
+const DOT_INTERVAL: f32 = 0.3;
+
+const MAX_DOTS: usize = 3;
+
+impl TransitionSpinnerPlugin {
+    fn show(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        active_transition: Res<ActiveTransition>,
+        roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    ) {
+        if active_transition.pending_steps().is_empty() {
+            return;
+        }
+
+        debug!("showing spinner for `{:?}`", active_transition.transition());
+        commands.entity(roots.single()).with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..Default::default()
+                    },
+                    background_color: theme.modal_color.into(),
+                    z_index: ZIndex::Global(1),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        TransitionSpinner::default(),
+                        LabelBundle::large(&theme, "Loading"),
+                    ));
+                });
+        });
+    }
+
+    fn animate(time: Res<Time>, mut spinners: Query<(&mut Text, &mut TransitionSpinner)>) {
+        for (mut text, mut spinner) in &mut spinners {
+            if spinner.timer.tick(time.delta()).just_finished() {
+                spinner.dots = (spinner.dots + 1) % (MAX_DOTS + 1);
+                text.sections[0].value = format!("Loading{}", ".".repeat(spinner.dots));
+            }
+        }
+    }
+
+    fn hide(
+        mut commands: Commands,
+        active_transition: Option<Res<ActiveTransition>>,
+        spinners: Query<&Parent, With<TransitionSpinner>>,
+    ) {
+        if active_transition.is_some_and(|transition| !transition.pending_steps().is_empty()) {
+            return;
+        }
+
+        debug!("hiding transition spinner");
+        commands.entity(**spinners.single()).despawn_recursive();
+    }
+}
+
+#[derive(Component)]
+struct TransitionSpinner {
+    timer: Timer,
+    dots: usize,
+}
+
+impl Default for TransitionSpinner {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(DOT_INTERVAL, TimerMode::Repeating),
+            dots: 0,
+        }
+    }
+}
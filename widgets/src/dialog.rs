@@ -1,6 +1,158 @@
-use bevy::{prelude::*, ui::FocusPolicy};
+use bevy::{input::InputSystem, prelude::*, ui::FocusPolicy};
+use bevy_simple_text_input::TextInputInactive;
 
-use crate::theme::Theme;
+use crate::{button::TextButtonBundle, click::Click, label::LabelBundle, theme::Theme};
+
+pub(super) struct DialogPlugin;
+
+impl Plugin for DialogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DialogResult>()
+            .add_systems(PreUpdate, Self::read_keys.after(InputSystem))
+            .add_systems(Update, (Self::init, Self::read_clicks))
+            .add_systems(PostUpdate, Self::despawn_answered);
+    }
+}
+
+impl DialogPlugin {
+    /// Spawns dialog content and places the dialog above all previous ones.
+    ///
+    /// Also deactivates text inputs, so typing can't reach widgets under the dialog.
+    fn init(
+        mut commands: Commands,
+        mut last_layer: Local<u32>,
+        theme: Res<Theme>,
+        dialogs: Query<(Entity, &ConfirmationDialog), Added<ConfirmationDialog>>,
+        mut text_inputs: Query<&mut TextInputInactive>,
+    ) {
+        for (dialog_entity, dialog) in &dialogs {
+            debug!("initializing confirmation dialog `{dialog_entity}`");
+            *last_layer += 1;
+            for mut inactive in &mut text_inputs {
+                inactive.0 = true;
+            }
+
+            // Global index places the dialog above everything, even if it was spawned as a child,
+            // and makes its blocking background intercept all clicks under it.
+            commands
+                .entity(dialog_entity)
+                .insert((
+                    DialogLayer(*last_layer),
+                    ZIndex::Global(DIALOG_Z_INDEX + *last_layer as i32),
+                ))
+                .with_children(|parent| {
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                padding: theme.padding.normal,
+                                row_gap: theme.gap.normal,
+                                ..Default::default()
+                            },
+                            background_color: theme.panel_color.into(),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn(LabelBundle::normal(&theme, dialog.text.clone()));
+                            parent
+                                .spawn(NodeBundle {
+                                    style: Style {
+                                        column_gap: theme.gap.normal,
+                                        ..Default::default()
+                                    },
+                                    ..Default::default()
+                                })
+                                .with_children(|parent| {
+                                    for (response, text) in dialog.buttons() {
+                                        parent.spawn((
+                                            DialogButton {
+                                                dialog_entity,
+                                                response,
+                                            },
+                                            TextButtonBundle::normal(&theme, text),
+                                        ));
+                                    }
+                                });
+                        });
+                });
+        }
+    }
+
+    fn read_clicks(
+        mut commands: Commands,
+        mut result_events: EventWriter<DialogResult>,
+        mut click_events: EventReader<Click>,
+        buttons: Query<&DialogButton>,
+        dialogs: Query<(), (With<ConfirmationDialog>, Without<Answered>)>,
+    ) {
+        for button in buttons.iter_many(click_events.read().map(|event| event.0)) {
+            if dialogs.get(button.dialog_entity).is_ok() {
+                answer(
+                    &mut commands,
+                    &mut result_events,
+                    button.dialog_entity,
+                    button.response,
+                );
+            }
+        }
+    }
+
+    /// Handles shortcuts for the top dialog and consumes the keys.
+    ///
+    /// Runs before any input processing to avoid triggering actions under the dialog.
+    fn read_keys(
+        mut commands: Commands,
+        mut result_events: EventWriter<DialogResult>,
+        mut keys: ResMut<ButtonInput<KeyCode>>,
+        dialogs: Query<
+            (Entity, Option<&DialogLayer>),
+            (With<ConfirmationDialog>, Without<Answered>),
+        >,
+    ) {
+        // Dialogs spawned during the last frame are not initialized yet, but they are on top.
+        let Some((dialog_entity, _)) = dialogs
+            .iter()
+            .max_by_key(|(_, layer)| layer.map_or(u32::MAX, |layer| layer.0))
+        else {
+            return;
+        };
+
+        let response = if keys.just_pressed(KeyCode::Enter) {
+            keys.reset(KeyCode::Enter);
+            DialogResponse::Accept
+        } else if keys.just_pressed(KeyCode::Escape) {
+            keys.reset(KeyCode::Escape);
+            DialogResponse::Cancel
+        } else {
+            return;
+        };
+
+        answer(&mut commands, &mut result_events, dialog_entity, response);
+    }
+
+    fn despawn_answered(mut commands: Commands, dialogs: Query<Entity, With<Answered>>) {
+        for dialog_entity in &dialogs {
+            debug!("closing confirmation dialog `{dialog_entity}`");
+            commands.entity(dialog_entity).despawn_recursive();
+        }
+    }
+}
+
+fn answer(
+    commands: &mut Commands,
+    result_events: &mut EventWriter<DialogResult>,
+    dialog_entity: Entity,
+    response: DialogResponse,
+) {
+    info!("answering `{response:?}` to dialog `{dialog_entity}`");
+    commands.entity(dialog_entity).insert(Answered);
+    result_events.send(DialogResult {
+        dialog_entity,
+        response,
+    });
+}
 
 #[derive(Bundle)]
 pub struct DialogBundle {
@@ -33,3 +185,100 @@ impl DialogBundle {
 
 #[derive(Component)]
 pub struct Dialog;
+
+/// Modal dialog with a question and buttons to answer it.
+///
+/// Enter accepts the top dialog and Escape cancels it.
+/// Dialogs are stacked in spawn order and block interaction with everything under them.
+/// The answer is sent as [`DialogResult`] and the dialog despawns itself at the end of the frame,
+/// so components on the dialog entity can be used to identify it in the result handlers.
+#[derive(Bundle)]
+pub struct ConfirmationDialogBundle {
+    confirmation_dialog: ConfirmationDialog,
+    dialog_bundle: DialogBundle,
+}
+
+impl ConfirmationDialogBundle {
+    pub fn new(theme: &Theme, text: impl Into<String>) -> Self {
+        Self {
+            confirmation_dialog: ConfirmationDialog {
+                text: text.into(),
+                accept: "Ok".to_string(),
+                decline: None,
+                cancel: "Cancel".to_string(),
+            },
+            dialog_bundle: DialogBundle::new(theme),
+        }
+    }
+
+    pub fn with_accept(mut self, text: impl Into<String>) -> Self {
+        self.confirmation_dialog.accept = text.into();
+        self
+    }
+
+    /// Adds a button for [`DialogResponse::Decline`] between accept and cancel buttons.
+    pub fn with_decline(mut self, text: impl Into<String>) -> Self {
+        self.confirmation_dialog.decline = Some(text.into());
+        self
+    }
+
+    pub fn with_cancel(mut self, text: impl Into<String>) -> Self {
+        self.confirmation_dialog.cancel = text.into();
+        self
+    }
+}
+
+#[derive(Component)]
+pub struct ConfirmationDialog {
+    text: String,
+    accept: String,
+    decline: Option<String>,
+    cancel: String,
+}
+
+impl ConfirmationDialog {
+    fn buttons(&self) -> impl Iterator<Item = (DialogResponse, String)> + '_ {
+        [
+            Some((DialogResponse::Accept, self.accept.clone())),
+            self.decline
+                .clone()
+                .map(|text| (DialogResponse::Decline, text)),
+            Some((DialogResponse::Cancel, self.cancel.clone())),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+/// Base [`ZIndex::Global`] for confirmation dialogs, above tooltips and other overlays.
+const DIALOG_Z_INDEX: i32 = 10;
+
+/// Spawn order of an initialized confirmation dialog.
+///
+/// The dialog with the highest value is on top and receives keyboard shortcuts.
+#[derive(Component)]
+struct DialogLayer(u32);
+
+/// Marks a dialog that already received an answer and will be despawned.
+#[derive(Component)]
+struct Answered;
+
+#[derive(Component)]
+struct DialogButton {
+    dialog_entity: Entity,
+    response: DialogResponse,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DialogResponse {
+    Accept,
+    Decline,
+    Cancel,
+}
+
+/// Answer for [`ConfirmationDialog`].
+#[derive(Event)]
+pub struct DialogResult {
+    pub dialog_entity: Entity,
+    pub response: DialogResponse,
+}
@@ -0,0 +1,162 @@
+use bevy::{
+    prelude::*,
+    ui::{self, FocusPolicy},
+};
+
+use super::theme::Theme;
+
+pub(super) struct DragDropPlugin;
+
+impl Plugin for DragDropPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DragDrop>().add_systems(
+            PreUpdate,
+            (Self::start, Self::update_ghosts, Self::drop)
+                .chain()
+                .after(ui::ui_focus_system),
+        );
+    }
+}
+
+/// Minimal cursor distance in pixels to turn a press into a drag.
+///
+/// Allows to click on draggable buttons.
+const DRAG_THRESHOLD: f32 = 5.0;
+
+impl DragDropPlugin {
+    fn start(
+        mut commands: Commands,
+        windows: Query<&Window>,
+        sources: Query<(Entity, &Interaction), (Changed<Interaction>, With<Draggable>)>,
+    ) {
+        let Some(cursor_pos) = windows.single().cursor_position() else {
+            return;
+        };
+
+        for (entity, &interaction) in &sources {
+            if interaction == Interaction::Pressed {
+                debug!("pressing draggable `{entity}`");
+                commands.entity(entity).insert(Dragging {
+                    origin: cursor_pos,
+                    ghost_entity: None,
+                });
+            }
+        }
+    }
+
+    fn update_ghosts(
+        mut commands: Commands,
+        theme: Res<Theme>,
+        windows: Query<&Window>,
+        mut sources: Query<(Entity, &Node, &GlobalTransform, &mut Dragging)>,
+        mut ghosts: Query<&mut Style, With<DragGhost>>,
+    ) {
+        let Some(cursor_pos) = windows.single().cursor_position() else {
+            return;
+        };
+
+        for (source_entity, node, transform, mut dragging) in &mut sources {
+            // Ghost is a child of the source, so its position is relative to the source node.
+            let offset = cursor_pos - transform.translation().truncate();
+            let size = node.size();
+            match dragging.ghost_entity {
+                Some(ghost_entity) => {
+                    let mut style = ghosts
+                        .get_mut(ghost_entity)
+                        .expect("dragged entity should have a ghost");
+                    style.left = Val::Px(offset.x);
+                    style.top = Val::Px(offset.y);
+                }
+                None if cursor_pos.distance(dragging.origin) > DRAG_THRESHOLD => {
+                    debug!("starting dragging `{source_entity}`");
+                    let ghost_entity = commands
+                        .spawn((
+                            DragGhost,
+                            NodeBundle {
+                                style: Style {
+                                    position_type: PositionType::Absolute,
+                                    width: Val::Px(size.x),
+                                    height: Val::Px(size.y),
+                                    left: Val::Px(offset.x),
+                                    top: Val::Px(offset.y),
+                                    ..Default::default()
+                                },
+                                background_color: theme.ghost_color.into(),
+                                focus_policy: FocusPolicy::Pass,
+                                z_index: ZIndex::Global(i32::MAX),
+                                ..Default::default()
+                            },
+                        ))
+                        .set_parent(source_entity)
+                        .id();
+                    dragging.ghost_entity = Some(ghost_entity);
+                }
+                None => (),
+            }
+        }
+    }
+
+    fn drop(
+        mut commands: Commands,
+        mut drop_events: EventWriter<DragDrop>,
+        mouse_buttons: Res<ButtonInput<MouseButton>>,
+        sources: Query<(Entity, &Dragging)>,
+        targets: Query<(Entity, &Interaction), With<DropTarget>>,
+    ) {
+        if !mouse_buttons.just_released(MouseButton::Left) {
+            return;
+        }
+
+        for (source_entity, dragging) in &sources {
+            commands.entity(source_entity).remove::<Dragging>();
+            let Some(ghost_entity) = dragging.ghost_entity else {
+                continue;
+            };
+
+            commands.entity(ghost_entity).despawn_recursive();
+            if let Some((target_entity, _)) = targets.iter().find(|&(entity, &interaction)| {
+                entity != source_entity && interaction == Interaction::Hovered
+            }) {
+                debug!("dropping `{source_entity}` on `{target_entity}`");
+                drop_events.send(DragDrop {
+                    source: source_entity,
+                    target: target_entity,
+                });
+            } else {
+                debug!("cancelling dragging `{source_entity}`");
+            }
+        }
+    }
+}
+
+/// Happens when a [`Draggable`] node was dropped onto a [`DropTarget`].
+///
+/// What the drop means is up to the receiving side.
+#[derive(Event)]
+pub struct DragDrop {
+    pub source: Entity,
+    pub target: Entity,
+}
+
+/// Allows dragging the node with the left mouse button.
+///
+/// Requires [`Interaction`].
+#[derive(Component)]
+pub struct Draggable;
+
+/// Allows dropping [`Draggable`] nodes onto this node.
+///
+/// Requires [`Interaction`].
+#[derive(Component)]
+pub struct DropTarget;
+
+/// Stores dragging state while the draggable node is pressed.
+#[derive(Component)]
+struct Dragging {
+    origin: Vec2,
+    ghost_entity: Option<Entity>,
+}
+
+/// Preview node that follows the cursor while dragging.
+#[derive(Component)]
+struct DragGhost;
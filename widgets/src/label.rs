@@ -9,6 +9,13 @@ pub struct LabelBundle {
 }
 
 impl LabelBundle {
+    pub fn small(theme: &Theme, text: impl Into<String>) -> Self {
+        Self {
+            label: Label,
+            text_bundle: TextBundle::from_section(text, theme.label.small.clone()),
+        }
+    }
+
     pub fn normal(theme: &Theme, text: impl Into<String>) -> Self {
         Self {
             label: Label,
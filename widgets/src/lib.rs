@@ -2,6 +2,7 @@ pub mod button;
 pub mod checkbox;
 pub mod click;
 pub mod dialog;
+pub mod drag_drop;
 pub mod label;
 pub mod popup;
 pub mod progress_bar;
@@ -13,6 +14,8 @@ use bevy::prelude::*;
 use button::ButtonPlugin;
 use checkbox::CheckboxPlugin;
 use click::ClickPlugin;
+use dialog::DialogPlugin;
+use drag_drop::DragDropPlugin;
 use popup::PopupPlugin;
 use progress_bar::ProgressBarPlugin;
 use text_edit::TextEditPlugin;
@@ -26,6 +29,8 @@ impl Plugin for WidgetsPlugin {
             ButtonPlugin,
             CheckboxPlugin,
             ClickPlugin,
+            DialogPlugin,
+            DragDropPlugin,
             PopupPlugin,
             ProgressBarPlugin,
             TextEditPlugin,
@@ -1,6 +1,7 @@
 use bevy::{prelude::*, ui::UiSystem};
 use bevy_simple_text_input::{
-    TextInputBundle, TextInputCursorPos, TextInputInactive, TextInputTextStyle, TextInputValue,
+    TextInputBundle, TextInputCursorPos, TextInputInactive, TextInputSettings, TextInputTextStyle,
+    TextInputValue,
 };
 
 use super::theme::Theme;
@@ -70,4 +71,13 @@ impl TextEditBundle {
         self.node_bundle.border_color = theme.text_edit.inactive_border.into();
         self
     }
+
+    /// Hides the entered text, useful for passwords.
+    pub fn masked(mut self) -> Self {
+        self.text_input_bundle.settings = TextInputSettings {
+            mask_character: Some('*'),
+            ..self.text_input_bundle.settings
+        };
+        self
+    }
 }
@@ -28,6 +28,7 @@ pub struct Theme {
     pub modal_color: Color,
     pub panel_color: Color,
     pub popup_color: Color,
+    pub ghost_color: Color,
 }
 
 impl FromWorld for Theme {
@@ -165,6 +166,7 @@ impl FromWorld for Theme {
             modal_color: Color::srgba(0.0, 0.0, 0.0, 0.0), // TODO: Make gray when we will have multiple UI roots.
             panel_color: Color::srgb(0.8, 0.8, 0.8),
             popup_color: Color::srgb(0.75, 0.75, 0.75),
+            ghost_color: Color::srgba(0.35, 0.75, 0.35, 0.5),
         }
     }
 }